@@ -0,0 +1,291 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encoding metrics to be pushed to a Prometheus remote-write endpoint or a Pushgateway, for
+//! nodes and indexers that can't be scraped, e.g. behind NAT or in serverless environments.
+//! Sending the encoded bodies is left to the caller.
+
+use prometheus::proto::{LabelPair, MetricFamily, MetricType};
+use prometheus::{Encoder, TextEncoder};
+
+/// Headers to send a remote-write body with.
+pub const REMOTE_WRITE_HEADERS: [(&str, &str); 4] = [
+    ("Content-Type", "application/x-protobuf"),
+    ("Content-Encoding", "snappy"),
+    ("X-Prometheus-Remote-Write-Version", "0.1.0"),
+    ("User-Agent", "mysten-metrics"),
+];
+
+/// Headers to send a Pushgateway body with.
+pub const PUSHGATEWAY_HEADERS: [(&str, &str); 1] = [("Content-Type", "text/plain; version=0.0.4")];
+
+/// Which metrics and labels are exported, and the labels added to them, e.g. to tell nodes apart
+/// on a shared endpoint, or to keep the number of series down.
+#[derive(Clone, Debug, Default)]
+pub struct Relabel {
+    /// If not empty, only metrics whose names start with one of these are exported.
+    pub keep_metrics: Vec<String>,
+    /// Metrics whose names start with one of these are not exported.
+    pub drop_metrics: Vec<String>,
+    /// Labels removed from every metric.
+    pub drop_labels: Vec<String>,
+    /// Labels added to every metric, replacing any of the same name.
+    pub external_labels: Vec<(String, String)>,
+}
+
+impl Relabel {
+    pub fn apply(&self, families: Vec<MetricFamily>) -> Vec<MetricFamily> {
+        let matches =
+            |prefixes: &[String], name: &str| prefixes.iter().any(|p| name.starts_with(p));
+        families
+            .into_iter()
+            .filter(|mf| {
+                (self.keep_metrics.is_empty() || matches(&self.keep_metrics, mf.get_name()))
+                    && !matches(&self.drop_metrics, mf.get_name())
+            })
+            .map(|mut mf| {
+                for m in mf.mut_metric().iter_mut() {
+                    let mut labels: Vec<LabelPair> = m
+                        .take_label()
+                        .into_iter()
+                        .filter(|l| {
+                            !self.drop_labels.iter().any(|d| d == l.get_name())
+                                && !self.external_labels.iter().any(|(n, _)| n == l.get_name())
+                        })
+                        .collect();
+                    for (name, value) in &self.external_labels {
+                        let mut label = LabelPair::default();
+                        label.set_name(name.clone());
+                        label.set_value(value.clone());
+                        labels.push(label);
+                    }
+                    m.set_label(labels.into());
+                }
+                mf
+            })
+            .collect()
+    }
+}
+
+/// The body of a Pushgateway push of `families`, in the text exposition format.
+pub fn pushgateway_body(families: &[MetricFamily]) -> prometheus::Result<Vec<u8>> {
+    let mut buf = vec![];
+    TextEncoder.encode(families, &mut buf)?;
+    Ok(buf)
+}
+
+/// The body of a remote write of `families`, sampled at `timestamp_ms`: a snappy-encoded
+/// `WriteRequest` protobuf.
+pub fn remote_write_body(families: &[MetricFamily], timestamp_ms: i64) -> Vec<u8> {
+    let mut request = vec![];
+    for (labels, value) in series(families) {
+        let mut timeseries = vec![];
+        for (name, value) in &labels {
+            let mut label = vec![];
+            put_bytes(&mut label, 1, name.as_bytes());
+            put_bytes(&mut label, 2, value.as_bytes());
+            put_bytes(&mut timeseries, 1, &label);
+        }
+        let mut sample = vec![];
+        put_tag(&mut sample, 1, WIRE_FIXED64);
+        sample.extend_from_slice(&value.to_le_bytes());
+        put_tag(&mut sample, 2, WIRE_VARINT);
+        put_varint(&mut sample, timestamp_ms as u64);
+        put_bytes(&mut timeseries, 2, &sample);
+        put_bytes(&mut request, 1, &timeseries);
+    }
+    snappy_literals(&request)
+}
+
+/// The series in `families`, as their labels (including the metric name, as `__name__`) sorted by
+/// name, and their values. Histograms and summaries are split into the series they are exposed
+/// as: buckets or quantiles, sums and counts.
+fn series(families: &[MetricFamily]) -> Vec<(Vec<(String, String)>, f64)> {
+    let mut series = vec![];
+    for mf in families {
+        let name = mf.get_name();
+        for m in mf.get_metric() {
+            let labels: Vec<_> = m
+                .get_label()
+                .iter()
+                .map(|l| (l.get_name().to_string(), l.get_value().to_string()))
+                .collect();
+            let mut push = |suffix: &str, extra: Option<(&str, String)>, value: f64| {
+                let mut labels = labels.clone();
+                labels.push(("__name__".to_string(), format!("{name}{suffix}")));
+                if let Some((label, value)) = extra {
+                    labels.push((label.to_string(), value));
+                }
+                labels.sort();
+                series.push((labels, value));
+            };
+            match mf.get_field_type() {
+                MetricType::COUNTER => push("", None, m.get_counter().get_value()),
+                MetricType::GAUGE => push("", None, m.get_gauge().get_value()),
+                MetricType::UNTYPED => push("", None, m.get_untyped().get_value()),
+                MetricType::HISTOGRAM => {
+                    let h = m.get_histogram();
+                    for b in h.get_bucket() {
+                        let le = Some(("le", b.get_upper_bound().to_string()));
+                        push("_bucket", le, b.get_cumulative_count() as f64);
+                    }
+                    let count = h.get_sample_count() as f64;
+                    push("_bucket", Some(("le", "+Inf".to_string())), count);
+                    push("_sum", None, h.get_sample_sum());
+                    push("_count", None, count);
+                }
+                MetricType::SUMMARY => {
+                    let s = m.get_summary();
+                    for q in s.get_quantile() {
+                        let quantile = Some(("quantile", q.get_quantile().to_string()));
+                        push("", quantile, q.get_value());
+                    }
+                    push("_sum", None, s.get_sample_sum());
+                    push("_count", None, s.get_sample_count() as f64);
+                }
+            }
+        }
+    }
+    series
+}
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_LEN: u8 = 2;
+
+fn put_tag(buf: &mut Vec<u8>, field: u8, wire_type: u8) {
+    buf.push((field << 3) | wire_type);
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn put_bytes(buf: &mut Vec<u8>, field: u8, bytes: &[u8]) {
+    put_tag(buf, field, WIRE_LEN);
+    put_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+/// `data` as a snappy block made of literals only, i.e. uncompressed. Remote write requires
+/// snappy encoding, which any decoder reads back the same, without a compressor dependency here.
+fn snappy_literals(data: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(data.len() + data.len() / 65536 * 3 + 8);
+    put_varint(&mut buf, data.len() as u64);
+    for chunk in data.chunks(65536) {
+        let n = chunk.len() - 1;
+        if n < 60 {
+            buf.push((n as u8) << 2);
+        } else if n < 256 {
+            buf.push(60 << 2);
+            buf.push(n as u8);
+        } else {
+            buf.push(61 << 2);
+            buf.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        buf.extend_from_slice(chunk);
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{
+        register_histogram_with_registry, register_int_counter_vec_with_registry, Registry,
+    };
+
+    fn families() -> Vec<MetricFamily> {
+        let registry = Registry::new();
+        let requests = register_int_counter_vec_with_registry!(
+            "requests",
+            "Requests served",
+            &["method", "host"],
+            registry
+        )
+        .unwrap();
+        requests.with_label_values(&["get", "a"]).inc_by(3);
+        let latency = register_histogram_with_registry!(
+            "latency",
+            "Request latency",
+            vec![0.5, 1.0],
+            registry
+        )
+        .unwrap();
+        latency.observe(0.7);
+        registry.gather()
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_relabel_and_series() {
+        let relabel = Relabel {
+            drop_metrics: vec!["lat".to_string()],
+            drop_labels: vec!["host".to_string()],
+            external_labels: vec![("node".to_string(), "n1".to_string())],
+            ..Default::default()
+        };
+        assert_eq!(
+            series(&relabel.apply(families())),
+            vec![(
+                labels(&[("__name__", "requests"), ("method", "get"), ("node", "n1")]),
+                3.0
+            )]
+        );
+
+        let relabel = Relabel {
+            keep_metrics: vec!["latency".to_string()],
+            ..Default::default()
+        };
+        let series = series(&relabel.apply(families()));
+        assert_eq!(
+            series,
+            vec![
+                (
+                    labels(&[("__name__", "latency_bucket"), ("le", "0.5")]),
+                    0.0
+                ),
+                (labels(&[("__name__", "latency_bucket"), ("le", "1")]), 1.0),
+                (
+                    labels(&[("__name__", "latency_bucket"), ("le", "+Inf")]),
+                    1.0
+                ),
+                (labels(&[("__name__", "latency_sum")]), 0.7),
+                (labels(&[("__name__", "latency_count")]), 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remote_write_body() {
+        let mut family = MetricFamily::default();
+        family.set_name("a".to_string());
+        family.set_field_type(MetricType::GAUGE);
+        let mut metric = prometheus::proto::Metric::default();
+        metric.mut_gauge().set_value(1.0);
+        family.set_metric(vec![metric].into());
+
+        let label = [b"\x0a\x08__name__\x12\x01a".as_slice()].concat();
+        let sample = [b"\x09".as_slice(), &1.0f64.to_le_bytes(), b"\x10\x80\x01"].concat();
+        let timeseries = [
+            b"\x0a\x0d".as_slice(),
+            &label,
+            b"\x12\x0c".as_slice(),
+            &sample,
+        ]
+        .concat();
+        let request = [b"\x0a\x1d".as_slice(), &timeseries].concat();
+        let snappy = [&[31u8, 30 << 2][..], &request].concat();
+        assert_eq!(remote_write_body(&[family], 128), snappy);
+    }
+}
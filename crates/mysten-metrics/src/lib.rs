@@ -18,6 +18,7 @@ use tracing::warn;
 pub use scopeguard;
 use uuid::Uuid;
 
+pub mod export;
 mod guards;
 pub mod histogram;
 pub mod metered_channel;
@@ -363,9 +364,7 @@ pub fn start_prometheus_server(addr: SocketAddr) -> RegistryService {
         return registry_service;
     }
 
-    let app = Router::new()
-        .route(METRICS_ROUTE, get(metrics))
-        .layer(Extension(registry_service.clone()));
+    let app = metrics_router(registry_service.clone());
 
     tokio::spawn(async move {
         axum::Server::bind(&addr)
@@ -377,6 +376,35 @@ pub fn start_prometheus_server(addr: SocketAddr) -> RegistryService {
     registry_service
 }
 
+/// Like [`start_prometheus_server`], but serving on listeners the caller has bound, e.g. to serve
+/// on several addresses. The listeners must be non-blocking.
+pub fn start_prometheus_server_on_listeners(
+    listeners: Vec<std::net::TcpListener>,
+) -> RegistryService {
+    let registry_service = RegistryService::new(Registry::new());
+
+    if cfg!(msim) {
+        warn!("not starting prometheus server in simulator");
+        return registry_service;
+    }
+
+    let app = metrics_router(registry_service.clone());
+    for listener in listeners {
+        let server = axum::Server::from_tcp(listener)
+            .expect("listener is bound")
+            .serve(app.clone().into_make_service());
+        tokio::spawn(async move { server.await.unwrap() });
+    }
+
+    registry_service
+}
+
+fn metrics_router(registry_service: RegistryService) -> Router {
+    Router::new()
+        .route(METRICS_ROUTE, get(metrics))
+        .layer(Extension(registry_service))
+}
+
 pub async fn metrics(
     Extension(registry_service): Extension<RegistryService>,
 ) -> (StatusCode, String) {
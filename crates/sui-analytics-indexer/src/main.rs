@@ -41,6 +41,7 @@ async fn main() -> Result<(), AnalyticsIndexerError> {
         .rest_url(&rest_url)
         .handler(processor)
         .run()
-        .await;
+        .await
+        .map_err(|e| AnalyticsIndexerError::GenericError(e.to_string()))?;
     Ok(())
 }
@@ -167,6 +167,7 @@ mod tests {
             data: vec![event_1.clone(), event_1.clone()],
             next_cursor: Some(event_1.id),
             has_next_page: false,
+            truncated: false,
         };
         add_event_response(&mock, module_foo.clone(), event_1.id, empty_events.clone());
         add_event_response(
@@ -192,6 +193,7 @@ mod tests {
             data: vec![event_2.clone()],
             next_cursor: Some(event_2.id),
             has_next_page: false,
+            truncated: false,
         };
         add_event_response(&mock, module_bar.clone(), event_2.id, empty_events.clone());
 
@@ -61,11 +61,29 @@ pub struct NodeConfig {
     #[serde(default = "default_json_rpc_address")]
     pub json_rpc_address: SocketAddr,
 
+    /// Addresses to serve JSON-RPC on as well as `json-rpc-address`, e.g. `[::]:9000` alongside
+    /// `0.0.0.0:9000` to serve IPv6 clients too, whether or not IPv6 sockets are dual-stack on
+    /// the host.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub json_rpc_additional_addresses: Vec<SocketAddr>,
+
+    /// Serve JSON-RPC over TLS, rather than plain HTTP.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_rpc_tls: Option<JsonRpcTlsConfig>,
+
+    /// Serve JSON-RPC on a Unix domain socket as well, so that services on the same host can
+    /// reach it without going over TCP.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_rpc_unix_socket: Option<JsonRpcUnixSocketConfig>,
+
     #[serde(default)]
     pub enable_experimental_rest_api: bool,
 
     #[serde(default = "default_metrics_address")]
     pub metrics_address: SocketAddr,
+    /// Addresses to serve metrics on as well as `metrics-address`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metrics_additional_addresses: Vec<SocketAddr>,
     #[serde(default = "default_admin_interface_port")]
     pub admin_interface_port: u16,
 
@@ -167,6 +185,135 @@ pub struct NodeConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub run_with_range: Option<RunWithRange>,
+
+    /// Store pre-signed transactions and submit them once the chain reaches a given checkpoint
+    /// or time. Only served by fullnodes. Disabled if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduled_transactions: Option<ScheduledTransactionsConfig>,
+
+    /// Write a report to a local directory when the node panics, or finds on starting that its
+    /// previous run didn't shut down cleanly, for operators to attach to bug reports. Disabled if
+    /// not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crash_reports: Option<CrashReportConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonRpcTlsConfig {
+    /// PEM-encoded certificate chain served to clients.
+    pub cert_path: PathBuf,
+    /// PEM-encoded private key of the certificate.
+    pub key_path: PathBuf,
+    /// How often to check whether the certificate or key changed on disk. They are reloaded
+    /// without restarting the node when they do, so they can be rotated in place.
+    #[serde(default = "default_tls_reload_interval_seconds")]
+    pub reload_interval_seconds: u64,
+    /// Serve ACME HTTP-01 challenges, so that an ACME client (e.g. certbot in webroot mode) can
+    /// provision and renew the certificate into `cert_path` and `key_path`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acme_challenge: Option<AcmeChallengeConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct AcmeChallengeConfig {
+    /// Plain HTTP address to serve challenges on. ACME servers expect port 80.
+    pub listen_address: SocketAddr,
+    /// Webroot the ACME client writes challenge responses to. The response to token `<token>`
+    /// is served from `<webroot>/.well-known/acme-challenge/<token>`.
+    pub webroot: PathBuf,
+}
+
+fn default_tls_reload_interval_seconds() -> u64 {
+    60
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonRpcUnixSocketConfig {
+    /// Path of the socket file. A socket left there by a previous run is replaced.
+    pub path: PathBuf,
+    /// Permissions of the socket file, e.g. `0o660`. Clients need write permission on it to
+    /// connect, so access can be limited to a user or group.
+    #[serde(default = "default_unix_socket_mode")]
+    pub mode: u32,
+}
+
+fn default_unix_socket_mode() -> u32 {
+    0o660
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScheduledTransactionsConfig {
+    /// The most transactions that can be waiting to be submitted at once.
+    #[serde(default = "default_max_scheduled_transactions")]
+    pub max_pending_transactions: usize,
+    /// How many times to try submitting a transaction before giving up on it, e.g. because the
+    /// objects it uses have since been modified.
+    #[serde(default = "default_max_scheduled_submit_attempts")]
+    pub max_submit_attempts: u32,
+    /// How often to check for transactions that are due.
+    #[serde(default = "default_scheduled_transactions_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// The most due transactions submitted at once.
+    #[serde(default = "default_max_concurrent_scheduled_submissions")]
+    pub max_concurrent_submissions: usize,
+    /// How long to wait for a submitted transaction to be executed before counting the attempt
+    /// as failed.
+    #[serde(default = "default_scheduled_submit_timeout_ms")]
+    pub submit_timeout_ms: u64,
+}
+
+impl Default for ScheduledTransactionsConfig {
+    fn default() -> Self {
+        Self {
+            max_pending_transactions: default_max_scheduled_transactions(),
+            max_submit_attempts: default_max_scheduled_submit_attempts(),
+            poll_interval_ms: default_scheduled_transactions_poll_interval_ms(),
+            max_concurrent_submissions: default_max_concurrent_scheduled_submissions(),
+            submit_timeout_ms: default_scheduled_submit_timeout_ms(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrashReportConfig {
+    /// Directory the reports are written to, created if it doesn't exist.
+    pub directory: PathBuf,
+    /// The most reports kept in the directory, the oldest being removed first.
+    #[serde(default = "default_max_crash_reports")]
+    pub max_reports: usize,
+    /// Endpoint to upload reports to, with a POST each, once the node has restarted. Reports are
+    /// kept in the directory whether or not they are uploaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upload_url: Option<String>,
+}
+
+fn default_max_crash_reports() -> usize {
+    20
+}
+
+fn default_max_scheduled_transactions() -> usize {
+    10_000
+}
+
+fn default_max_scheduled_submit_attempts() -> u32 {
+    5
+}
+
+fn default_scheduled_transactions_poll_interval_ms() -> u64 {
+    1_000
+}
+
+fn default_max_concurrent_scheduled_submissions() -> usize {
+    16
+}
+
+fn default_scheduled_submit_timeout_ms() -> u64 {
+    30_000
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -628,6 +775,48 @@ pub struct MetricsConfig {
     pub push_interval_seconds: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub push_url: Option<String>,
+    /// Endpoints to push metrics to as well, for nodes that can't be scraped, e.g. behind NAT.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exports: Vec<MetricsExportConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetricsExportConfig {
+    /// A Prometheus remote-write endpoint, or the base URL of a Pushgateway.
+    pub url: String,
+    #[serde(default)]
+    pub format: MetricsExportFormat,
+    /// How often metrics are pushed, every 60 seconds if unspecified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_seconds: Option<u64>,
+    /// Headers to send with each push, e.g. `Authorization`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// If not empty, only metrics whose names start with one of these are pushed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keep_metrics: Vec<String>,
+    /// Metrics whose names start with one of these are not pushed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub drop_metrics: Vec<String>,
+    /// Labels removed from every metric pushed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub drop_labels: Vec<String>,
+    /// Labels added to every metric pushed, e.g. to tell nodes pushing to the same endpoint
+    /// apart. For a Pushgateway, these are also the grouping key that pushes replace each other
+    /// under.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub external_labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MetricsExportFormat {
+    /// The Prometheus remote-write protocol, as accepted by Prometheus, Mimir, Thanos and others.
+    #[default]
+    RemoteWrite,
+    /// The text format, pushed to a Prometheus Pushgateway.
+    Pushgateway,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -896,6 +1085,15 @@ impl KeyPairWithPath {
             })
             .as_ref()
     }
+
+    /// Read the keypair from its file again, e.g. to pick up a key rotated on disk. Returns
+    /// `None` if the keypair is stored in place, in the config.
+    pub fn reload_from_file(&self) -> Option<Result<SuiKeyPair>> {
+        match &self.location {
+            KeyPairLocation::InPlace { .. } => None,
+            KeyPairLocation::File { path } => Some(read_keypair_from_file(path)),
+        }
+    }
 }
 
 /// Wrapper struct for AuthorityKeyPair that can be deserialized from a file path.
@@ -978,7 +1176,7 @@ mod tests {
     use sui_keys::keypair_file::{write_authority_keypair_to_file, write_keypair_to_file};
     use sui_types::crypto::{get_key_pair_from_rng, AuthorityKeyPair, NetworkKeyPair, SuiKeyPair};
 
-    use super::Genesis;
+    use super::{Genesis, KeyPairWithPath};
     use crate::NodeConfig;
 
     #[test]
@@ -1034,6 +1232,32 @@ mod tests {
             worker_key_pair.public()
         );
     }
+
+    #[test]
+    fn reload_rotated_key_pair_from_file() {
+        let network_key_pair = |seed| {
+            let (_, key_pair): (_, NetworkKeyPair) =
+                get_key_pair_from_rng(&mut StdRng::from_seed([seed; 32]));
+            SuiKeyPair::Ed25519(key_pair)
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("network.key");
+        write_keypair_to_file(&network_key_pair(0), &path).unwrap();
+        let key_pair = KeyPairWithPath::new_from_path(path.clone());
+
+        // The key loaded at startup stays as it was, while reloading picks up the rotated one.
+        write_keypair_to_file(&network_key_pair(1), &path).unwrap();
+        assert_eq!(key_pair.keypair().public(), network_key_pair(0).public());
+        assert_eq!(
+            key_pair.reload_from_file().unwrap().unwrap().public(),
+            network_key_pair(1).public()
+        );
+
+        // Keys in the config itself have nothing to reload from.
+        assert!(KeyPairWithPath::new(network_key_pair(0))
+            .reload_from_file()
+            .is_none());
+    }
 }
 
 // RunWithRange is used to specify the ending epoch/checkpoint to process.
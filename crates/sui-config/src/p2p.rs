@@ -12,7 +12,9 @@ use sui_types::{
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct P2pConfig {
-    /// The address that the p2p network will bind on.
+    /// The address that the p2p network will bind on. This is a single address, as the p2p
+    /// network runs over one UDP socket: use `[::]:<port>` to listen on IPv6, and on IPv4 as well
+    /// on hosts where IPv6 sockets are dual-stack (the default on Linux).
     #[serde(default = "default_listen_address")]
     pub listen_address: SocketAddr,
     /// The external address other nodes can use to reach this node.
@@ -179,6 +181,17 @@ pub struct StateSyncConfig {
     /// If unspecified, this will default to no limit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub get_checkpoint_contents_per_checkpoint_limit: Option<usize>,
+
+    /// The region this node runs in, e.g. `us-east-1`. If set, checkpoint contents are downloaded
+    /// from peers hinted to be in the same region (see `peer_regions`) before any others, to keep
+    /// traffic within the region.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// Hints for the regions that known peers run in. Peers without a hint are assumed to be in a
+    /// different region from this node.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub peer_regions: Vec<PeerRegion>,
 }
 
 impl StateSyncConfig {
@@ -239,6 +252,13 @@ impl StateSyncConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PeerRegion {
+    pub peer_id: anemo::PeerId,
+    pub region: String,
+}
+
 /// Access Type of a node.
 /// AccessType info is shared in the discovery process.
 /// * If the node marks itself as Public, other nodes may try to connect to it.
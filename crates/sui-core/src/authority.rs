@@ -14,6 +14,7 @@ use fastcrypto::encoding::Encoding;
 use fastcrypto::hash::MultisetHash;
 use itertools::Itertools;
 use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::annotated_value::MoveStructLayout;
 use move_core_types::language_storage::ModuleId;
 use mysten_metrics::{TX_TYPE_SHARED_OBJ_TX, TX_TYPE_SINGLE_WRITER_TX};
@@ -133,6 +134,7 @@ use crate::authority::authority_store::{ExecutionLockReadGuard, ObjectLockStatus
 use crate::authority::authority_store_pruner::AuthorityStorePruner;
 use crate::authority::epoch_start_configuration::EpochStartConfigTrait;
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
+use crate::authority::overlay_store::OverlayStore;
 use crate::checkpoints::checkpoint_executor::CheckpointExecutor;
 use crate::checkpoints::CheckpointStore;
 use crate::consensus_adapter::ConsensusAdapter;
@@ -193,6 +195,7 @@ pub mod test_authority_builder;
 
 pub(crate) mod authority_notify_read;
 pub(crate) mod authority_store;
+pub(crate) mod overlay_store;
 
 pub static CHAIN_IDENTIFIER: OnceCell<ChainIdentifier> = OnceCell::new();
 
@@ -1590,7 +1593,120 @@ impl AuthorityState {
         Option<ObjectID>,
     )> {
         let epoch_store = self.load_epoch_store_one_call_per_task();
-        if !self.is_fullnode(&epoch_store) {
+        let (input_object_kinds, receiving_object_refs) =
+            self.check_dry_run_transaction(&transaction, &epoch_store)?;
+
+        let (input_objects, receiving_objects) = self
+            .input_loader
+            .read_objects_for_dry_run_exec(
+                &transaction_digest,
+                &input_object_kinds,
+                &receiving_object_refs,
+                epoch_store.protocol_config(),
+            )
+            .await?;
+
+        let (inner_temp_store, effects, mock_gas) = self.dry_exec_with_inputs(
+            &transaction,
+            transaction_digest,
+            input_objects,
+            receiving_objects,
+            transaction.gas().to_vec(),
+            self.get_backing_store().as_ref(),
+            &epoch_store,
+        )?;
+
+        let module_cache =
+            TemporaryModuleResolver::new(&inner_temp_store, epoch_store.module_cache().clone());
+
+        let mut layout_resolver =
+            epoch_store
+                .executor()
+                .type_layout_resolver(Box::new(TemporaryPackageStore::new(
+                    &inner_temp_store,
+                    self.execution_cache.clone(),
+                )));
+
+        let (response, written_with_kind) = Self::dry_run_response(
+            transaction,
+            &effects,
+            &inner_temp_store,
+            &module_cache,
+            layout_resolver.as_mut(),
+        )?;
+        Ok((response, written_with_kind, effects, mock_gas))
+    }
+
+    /// Dry-run `transactions` in order, each one against the state left by the ones before it, as
+    /// if they had all been executed, e.g. to simulate publishing a package and then calling it.
+    /// Nothing is written to the store. See `OverlayStore::read_objects_for_dry_run_exec` for how
+    /// transactions refer to the objects written by the ones before them.
+    #[allow(clippy::type_complexity)]
+    pub async fn dry_exec_transaction_bundle(
+        &self,
+        transactions: Vec<(TransactionData, TransactionDigest)>,
+    ) -> SuiResult<
+        Vec<(
+            DryRunTransactionBlockResponse,
+            BTreeMap<ObjectID, (ObjectRef, Object, WriteKind)>,
+            TransactionEffects,
+            Option<ObjectID>,
+        )>,
+    > {
+        let epoch_store = self.load_epoch_store_one_call_per_task();
+        let mut overlay = OverlayStore::new(self.get_backing_store().clone());
+
+        let mut results = Vec::with_capacity(transactions.len());
+        for (transaction, transaction_digest) in transactions {
+            let (input_object_kinds, receiving_object_refs) =
+                self.check_dry_run_transaction(&transaction, &epoch_store)?;
+
+            let mut gas_object_refs = transaction.gas().to_vec();
+            let (input_objects, receiving_objects) = overlay.read_objects_for_dry_run_exec(
+                self.get_cache_reader().as_ref(),
+                &input_object_kinds,
+                &receiving_object_refs,
+                &mut gas_object_refs,
+                epoch_store.epoch(),
+            )?;
+
+            let (inner_temp_store, effects, mock_gas) = self.dry_exec_with_inputs(
+                &transaction,
+                transaction_digest,
+                input_objects,
+                receiving_objects,
+                gas_object_refs,
+                &overlay,
+                &epoch_store,
+            )?;
+
+            // Applied before the response is built, so that packages published by the
+            // transaction can be resolved from the overlay.
+            overlay.apply(&inner_temp_store, &effects);
+            let mut layout_resolver = epoch_store
+                .executor()
+                .type_layout_resolver(Box::new(&overlay));
+
+            let (response, written_with_kind) = Self::dry_run_response(
+                transaction,
+                &effects,
+                &inner_temp_store,
+                &overlay,
+                layout_resolver.as_mut(),
+            )?;
+            results.push((response, written_with_kind, effects, mock_gas));
+        }
+        Ok(results)
+    }
+
+    /// Check that `transaction` can be dry-run, returning the kinds of its input objects and the
+    /// references of the objects it receives.
+    fn check_dry_run_transaction(
+        &self,
+        transaction: &TransactionData,
+        epoch_store: &AuthorityPerEpochStore,
+    ) -> SuiResult<(Vec<InputObjectKind>, Vec<ObjectRef>)> {
+        if !self.is_fullnode(epoch_store) {
             return Err(SuiError::UnsupportedFeatureError {
                 error: "dry-exec is only supported on fullnodes".to_string(),
             });
@@ -1610,7 +1726,7 @@ impl AuthorityState {
         let receiving_object_refs = transaction.receiving_objects();
 
         sui_transaction_checks::deny::check_transaction_for_signing(
-            &transaction,
+            transaction,
             &[],
             &input_object_kinds,
             &receiving_object_refs,
@@ -1618,19 +1734,24 @@ impl AuthorityState {
             self.get_backing_package_store().as_ref(),
         )?;
 
-        let (input_objects, receiving_objects) = self
-            .input_loader
-            .read_objects_for_dry_run_exec(
-                &transaction_digest,
-                &input_object_kinds,
-                &receiving_object_refs,
-                epoch_store.protocol_config(),
-            )
-            .await?;
+        Ok((input_object_kinds, receiving_object_refs))
+    }
 
+    /// Dry-run `transaction` against `store`, given its input objects, returning its outputs and
+    /// the ID of the gas coin made up for it, if it was not given one in `gas_object_refs`.
+    #[allow(clippy::too_many_arguments)]
+    fn dry_exec_with_inputs(
+        &self,
+        transaction: &TransactionData,
+        transaction_digest: TransactionDigest,
+        input_objects: InputObjects,
+        receiving_objects: ReceivingObjects,
+        mut gas_object_refs: Vec<ObjectRef>,
+        store: &dyn BackingStore,
+        epoch_store: &AuthorityPerEpochStore,
+    ) -> SuiResult<(InnerTemporaryStore, TransactionEffects, Option<ObjectID>)> {
         // make a gas object if one was not provided
-        let mut gas_object_refs = transaction.gas().to_vec();
-        let ((gas_status, checked_input_objects), mock_gas) = if transaction.gas().is_empty() {
+        let ((gas_status, checked_input_objects), mock_gas) = if gas_object_refs.is_empty() {
             let sender = transaction.sender();
             // use a 1B sui coin
             const MIST_TO_SUI: u64 = 1_000_000_000;
@@ -1648,7 +1769,7 @@ impl AuthorityState {
                 sui_transaction_checks::check_transaction_input_with_given_gas(
                     epoch_store.protocol_config(),
                     epoch_store.reference_gas_price(),
-                    &transaction,
+                    transaction,
                     input_objects,
                     receiving_objects,
                     gas_object,
@@ -1661,7 +1782,7 @@ impl AuthorityState {
                 sui_transaction_checks::check_transaction_input(
                     epoch_store.protocol_config(),
                     epoch_store.reference_gas_price(),
-                    &transaction,
+                    transaction,
                     input_objects,
                     &receiving_objects,
                     &self.metrics.bytecode_verifier_metrics,
@@ -1680,7 +1801,7 @@ impl AuthorityState {
         let expensive_checks = false;
         let (inner_temp_store, _, effects, _execution_error) = executor
             .execute_transaction_to_effects(
-                self.get_backing_store().as_ref(),
+                store,
                 protocol_config,
                 self.metrics.limits_metrics.clone(),
                 expensive_checks,
@@ -1697,18 +1818,23 @@ impl AuthorityState {
                 signer,
                 transaction_digest,
             );
-        let tx_digest = *effects.transaction_digest();
+        Ok((inner_temp_store, effects, mock_gas))
+    }
 
-        let module_cache =
-            TemporaryModuleResolver::new(&inner_temp_store, epoch_store.module_cache().clone());
+    /// The response to a dry run of `transaction`, with the objects it wrote, resolving types
+    /// with `module_cache` and `layout_resolver`.
+    fn dry_run_response(
+        transaction: TransactionData,
+        effects: &TransactionEffects,
+        inner_temp_store: &InnerTemporaryStore,
+        module_cache: &impl GetModule,
+        layout_resolver: &mut dyn LayoutResolver,
+    ) -> SuiResult<(
+        DryRunTransactionBlockResponse,
+        BTreeMap<ObjectID, (ObjectRef, Object, WriteKind)>,
+    )> {
+        let tx_digest = *effects.transaction_digest();
 
-        let mut layout_resolver =
-            epoch_store
-                .executor()
-                .type_layout_resolver(Box::new(TemporaryPackageStore::new(
-                    &inner_temp_store,
-                    self.execution_cache.clone(),
-                )));
         // Returning empty vector here because we recalculate changes in the rpc layer.
         let object_changes = Vec::new();
 
@@ -1740,7 +1866,7 @@ impl AuthorityState {
 
         Ok((
             DryRunTransactionBlockResponse {
-                input: SuiTransactionBlockData::try_from(transaction, &module_cache).map_err(
+                input: SuiTransactionBlockData::try_from(transaction, module_cache).map_err(
                     |e| SuiError::TransactionSerializationError {
                         error: format!(
                             "Failed to convert transaction to SuiTransactionBlockData: {}",
@@ -1753,14 +1879,13 @@ impl AuthorityState {
                     inner_temp_store.events.clone(),
                     tx_digest,
                     None,
-                    layout_resolver.as_mut(),
+                    layout_resolver,
                 )?,
                 object_changes,
                 balance_changes,
+                gas_fiat_value: None,
             },
             written_with_kind,
-            effects,
-            mock_gas,
         ))
     }
 
@@ -0,0 +1,248 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::ModuleId;
+use sui_types::base_types::{EpochId, ObjectID, ObjectRef, SequenceNumber, VersionNumber};
+use sui_types::effects::TransactionEffects;
+use sui_types::error::{SuiError, SuiResult, UserInputError};
+use sui_types::inner_temporary_store::InnerTemporaryStore;
+use sui_types::object::{Object, Owner};
+use sui_types::storage::{
+    get_module_by_id, BackingPackageStore, BackingStore, ChildObjectResolver, ObjectStore,
+    PackageObject, ParentSync,
+};
+use sui_types::transaction::{
+    InputObjectKind, InputObjects, ObjectReadResult, ReceivingObjectReadResult,
+    ReceivingObjectReadResultKind, ReceivingObjects,
+};
+
+use crate::execution_cache::ExecutionCacheRead;
+
+/// A store that overlays the objects written by dry-run transactions on top of the objects in a
+/// backing store, so that a sequence of transactions can be dry-run, each one seeing the effects
+/// of the ones before it, without writing anything to the backing store.
+pub(crate) struct OverlayStore {
+    base: Arc<dyn BackingStore + Send + Sync>,
+    /// The latest version of each object written by the transactions applied so far.
+    written: BTreeMap<ObjectID, Object>,
+    /// Objects deleted or wrapped by the transactions applied so far, and not written since.
+    removed: BTreeSet<ObjectID>,
+}
+
+impl OverlayStore {
+    pub fn new(base: Arc<dyn BackingStore + Send + Sync>) -> Self {
+        Self {
+            base,
+            written: BTreeMap::new(),
+            removed: BTreeSet::new(),
+        }
+    }
+
+    /// Apply the outputs of a dry-run transaction, so that the transactions that follow it see
+    /// them.
+    pub fn apply(&mut self, inner_temp_store: &InnerTemporaryStore, effects: &TransactionEffects) {
+        for (oref, _) in effects.all_removed_objects() {
+            self.written.remove(&oref.0);
+            self.removed.insert(oref.0);
+        }
+        for (id, object) in &inner_temp_store.written {
+            self.removed.remove(id);
+            self.written.insert(*id, object.clone());
+        }
+    }
+
+    /// Read the input and receiving objects of a transaction to dry-run against this store.
+    ///
+    /// Owned objects written by earlier transactions are read at the version they were last
+    /// written at, whatever version and digest the transaction refers to them by, as their digests
+    /// can't be known before those transactions are run. The same goes for the transaction's gas
+    /// coins, in `gas`, which are updated to the versions read.
+    pub fn read_objects_for_dry_run_exec(
+        &self,
+        cache: &dyn ExecutionCacheRead,
+        input_object_kinds: &[InputObjectKind],
+        receiving_objects: &[ObjectRef],
+        gas: &mut [ObjectRef],
+        epoch_id: EpochId,
+    ) -> SuiResult<(InputObjects, ReceivingObjects)> {
+        let mut results = Vec::with_capacity(input_object_kinds.len());
+        for kind in input_object_kinds {
+            let (kind, obj) = match kind {
+                InputObjectKind::MovePackage(id) => (
+                    *kind,
+                    self.get_package_object(id)?.map(|o| o.object().clone()),
+                ),
+                InputObjectKind::SharedMoveObject { id, .. } => (*kind, self.get_object(id)?),
+                InputObjectKind::ImmOrOwnedMoveObject(objref) => {
+                    match self.written.get(&objref.0) {
+                        Some(obj) => {
+                            let written_ref = obj.compute_object_reference();
+                            if let Some(gas_ref) = gas.iter_mut().find(|g| g.0 == objref.0) {
+                                *gas_ref = written_ref;
+                            }
+                            (
+                                InputObjectKind::ImmOrOwnedMoveObject(written_ref),
+                                Some(obj.clone()),
+                            )
+                        }
+                        None => (*kind, self.get_object_by_key(&objref.0, objref.1)?),
+                    }
+                }
+            };
+            let obj = obj.ok_or_else(|| SuiError::from(kind.object_not_found_error()))?;
+            results.push(ObjectReadResult::new(kind, obj.into()));
+        }
+
+        let mut receiving_results = Vec::with_capacity(receiving_objects.len());
+        for objref in receiving_objects {
+            // Note: the digest is checked later in check_transaction_input
+            let (object_id, version, _) = objref;
+
+            if !self.written.contains_key(object_id)
+                && cache.have_received_object_at_version(object_id, *version, epoch_id)?
+            {
+                receiving_results.push(ReceivingObjectReadResult::new(
+                    *objref,
+                    ReceivingObjectReadResultKind::PreviouslyReceivedObject,
+                ));
+                continue;
+            }
+
+            let Some(object) = self.get_object(object_id)? else {
+                return Err(UserInputError::ObjectNotFound {
+                    object_id: *object_id,
+                    version: Some(*version),
+                }
+                .into());
+            };
+
+            receiving_results.push(ReceivingObjectReadResult::new(*objref, object.into()));
+        }
+
+        Ok((results.into(), receiving_results.into()))
+    }
+}
+
+impl ObjectStore for OverlayStore {
+    fn get_object(
+        &self,
+        object_id: &ObjectID,
+    ) -> sui_types::storage::error::Result<Option<Object>> {
+        if let Some(obj) = self.written.get(object_id) {
+            return Ok(Some(obj.clone()));
+        }
+        if self.removed.contains(object_id) {
+            return Ok(None);
+        }
+        self.base.get_object(object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> sui_types::storage::error::Result<Option<Object>> {
+        match self.written.get(object_id) {
+            Some(obj) if obj.version() == version => Ok(Some(obj.clone())),
+            // Earlier versions are still in the backing store.
+            _ => self.base.get_object_by_key(object_id, version),
+        }
+    }
+}
+
+impl BackingPackageStore for OverlayStore {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<PackageObject>> {
+        match self.written.get(package_id) {
+            Some(obj) if obj.is_package() => Ok(Some(PackageObject::new(obj.clone()))),
+            Some(_) => Err(SuiError::BadObjectType {
+                error: format!("Package expected, Move object found: {package_id}"),
+            }),
+            None => self.base.get_package_object(package_id),
+        }
+    }
+}
+
+impl ChildObjectResolver for OverlayStore {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        if self.removed.contains(child) {
+            return Ok(None);
+        }
+        let Some(child_object) = self
+            .written
+            .get(child)
+            .filter(|o| o.version() <= child_version_upper_bound)
+        else {
+            return self
+                .base
+                .read_child_object(parent, child, child_version_upper_bound);
+        };
+
+        let parent = *parent;
+        if child_object.owner != Owner::ObjectOwner(parent.into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: parent,
+                actual_owner: child_object.owner,
+            });
+        }
+        Ok(Some(child_object.clone()))
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        epoch_id: EpochId,
+    ) -> SuiResult<Option<Object>> {
+        if self.removed.contains(receiving_object_id) {
+            return Ok(None);
+        }
+        let Some(recv_object) = self.written.get(receiving_object_id) else {
+            return self.base.get_object_received_at_version(
+                owner,
+                receiving_object_id,
+                receive_object_at_version,
+                epoch_id,
+            );
+        };
+
+        if recv_object.owner != Owner::AddressOwner((*owner).into())
+            || recv_object.version() != receive_object_at_version
+        {
+            return Ok(None);
+        }
+        Ok(Some(recv_object.clone()))
+    }
+}
+
+impl ParentSync for OverlayStore {
+    fn get_latest_parent_entry_ref_deprecated(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiResult<Option<ObjectRef>> {
+        match self.written.get(&object_id) {
+            Some(obj) => Ok(Some(obj.compute_object_reference())),
+            None => self.base.get_latest_parent_entry_ref_deprecated(object_id),
+        }
+    }
+}
+
+impl GetModule for OverlayStore {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> anyhow::Result<Option<Self::Item>, Self::Error> {
+        get_module_by_id(self, id)
+    }
+}
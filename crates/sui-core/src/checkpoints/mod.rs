@@ -23,7 +23,7 @@ use itertools::Itertools;
 use mysten_metrics::{monitored_scope, spawn_monitored_task, MonitoredFutureExt};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use sui_macros::fail_point;
+use sui_macros::{fail_point, fail_point_arg};
 use sui_network::default_mysten_network_config;
 use sui_types::base_types::ConciseableName;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
@@ -776,6 +776,10 @@ impl CheckpointBuilder {
                     checkpoint_commit_height = height,
                     "Making checkpoint at commit height"
                 );
+                // Lets tests simulate slow checkpoint construction on specific validators.
+                if let Some(delay) = fail_point_arg!("checkpoint-builder-delay", &self.state.name) {
+                    tokio::time::sleep(delay).await;
+                }
                 if let Err(e) = self.make_checkpoint(height, pending).await {
                     error!("Error while making checkpoint, will retry in 1s: {:?}", e);
                     tokio::time::sleep(Duration::from_secs(1)).await;
@@ -54,6 +54,28 @@ impl CommitteeStore {
         store
     }
 
+    /// Open the committee store at `path` as a secondary instance, which only reads, and sees the
+    /// primary's writes once it catches up with it.
+    pub fn open_secondary(path: PathBuf, secondary_path: PathBuf) -> Self {
+        let tables = CommitteeStoreTables::open_tables_secondary(
+            path,
+            secondary_path,
+            MetricConf::new("committee_secondary"),
+            None,
+        );
+        Self {
+            tables,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn try_catch_up_with_primary(&self) -> SuiResult {
+        self.tables
+            .committee_map
+            .try_catch_up_with_primary()
+            .map_err(Into::into)
+    }
+
     pub fn new_for_testing(genesis_committee: &Committee) -> Self {
         let dir = std::env::temp_dir();
         let path = dir.join(format!("DB_{:?}", nondeterministic!(ObjectID::random())));
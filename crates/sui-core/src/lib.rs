@@ -26,6 +26,7 @@ pub mod overload_monitor;
 pub(crate) mod post_consensus_tx_reorder;
 pub mod quorum_driver;
 pub mod safe_client;
+pub mod scheduled_transactions;
 mod scoring_decision;
 mod stake_aggregator;
 pub mod state_accumulator;
@@ -0,0 +1,401 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pre-signed transactions that a fullnode stores and submits once the chain reaches a given
+//! checkpoint or time, e.g. for delayed payouts, or for dead-man switches that their owner keeps
+//! cancelling and re-scheduling for as long as they are around.
+//!
+//! A scheduled transaction refers to the versions of its objects at the time it was signed, so
+//! it can only succeed if those objects are unchanged when it is submitted. Only its sender can
+//! cancel a transaction, by signing its digest as a personal message (see [`cancellation`]).
+//! Cancelling a transaction only stops this node from submitting it: anyone else holding the
+//! signed transaction can still submit it.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use parking_lot::Mutex;
+use prometheus::{
+    register_int_counter_with_registry, register_int_gauge_with_registry, IntCounter, IntGauge,
+    Registry,
+};
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::PersonalMessage;
+use sui_config::node::ScheduledTransactionsConfig;
+use sui_types::base_types::TransactionDigest;
+use sui_types::error::{SuiError, SuiResult, UserInputError};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::quorum_driver_types::{
+    ExecuteTransactionRequest, ExecuteTransactionRequestType, QuorumDriverError,
+};
+use sui_types::signature::GenericSignature;
+use sui_types::transaction::{Transaction, TransactionDataAPI};
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+use typed_store::rocks::{DBMap, MetricConf};
+use typed_store::traits::{Map, TableSummary, TypedStoreDebug};
+use typed_store_derive::DBMapUtils;
+
+use crate::authority::AuthorityState;
+use crate::authority_client::AuthorityAPI;
+use crate::quorum_driver::reconfig_observer::{OnsiteReconfigObserver, ReconfigObserver};
+use crate::signature_verifier::SignatureVerifier;
+use crate::transaction_orchestrator::TransactiondOrchestrator;
+
+#[cfg(test)]
+#[path = "unit_tests/scheduled_transactions_tests.rs"]
+mod scheduled_transactions_tests;
+
+/// What the sender of the scheduled transaction with `digest` signs, as a personal message, to
+/// cancel it: the digest's bytes.
+pub fn cancellation(digest: &TransactionDigest) -> PersonalMessage {
+    PersonalMessage {
+        message: digest.inner().to_vec(),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScheduledTransaction {
+    pub transaction: Transaction,
+    /// The transaction is submitted once this checkpoint has been executed.
+    pub not_before_checkpoint: Option<CheckpointSequenceNumber>,
+    /// The transaction is submitted once a checkpoint with this timestamp, or a later one, has
+    /// been executed.
+    pub not_before_timestamp_ms: Option<u64>,
+    /// How many times submitting the transaction has failed.
+    pub submit_attempts: u32,
+}
+
+impl ScheduledTransaction {
+    pub fn digest(&self) -> &TransactionDigest {
+        self.transaction.digest()
+    }
+
+    /// Whether the transaction can be submitted once `checkpoint`, with timestamp `timestamp_ms`,
+    /// has been executed.
+    pub fn is_due(&self, checkpoint: CheckpointSequenceNumber, timestamp_ms: u64) -> bool {
+        self.not_before_checkpoint.map_or(true, |c| checkpoint >= c)
+            && self
+                .not_before_timestamp_ms
+                .map_or(true, |t| timestamp_ms >= t)
+    }
+
+    /// Check that `signature` is the sender's signature of the transaction's [`cancellation`].
+    pub fn verify_cancellation(
+        &self,
+        signature: &GenericSignature,
+        verifier: &SignatureVerifier,
+    ) -> SuiResult {
+        let sender = self.transaction.data().transaction_data().sender();
+        verifier.verify_personal_message(cancellation(self.digest()), signature, sender)
+    }
+}
+
+#[derive(DBMapUtils)]
+struct ScheduledTransactionTables {
+    transactions: DBMap<TransactionDigest, ScheduledTransaction>,
+}
+
+pub struct ScheduledTransactionStore {
+    tables: ScheduledTransactionTables,
+    /// How many transactions are stored, counted once when the store is opened and maintained
+    /// as they are added and removed, rather than counted with a scan of the table.
+    len: AtomicUsize,
+    /// Held while adding and removing transactions, so that `len` stays in step with the table.
+    write: Mutex<()>,
+}
+
+impl ScheduledTransactionStore {
+    pub fn new(path: PathBuf) -> Self {
+        let tables = ScheduledTransactionTables::open_tables_read_write(
+            path,
+            MetricConf::new("scheduled_transactions"),
+            None,
+            None,
+        );
+        let len = tables.transactions.unbounded_iter().count();
+        Self {
+            tables,
+            len: AtomicUsize::new(len),
+            write: Mutex::new(()),
+        }
+    }
+
+    /// Store `transaction`, unless it is already stored or `max_len` transactions already are.
+    /// Returns whether it is stored, including if it already was.
+    pub fn insert(&self, transaction: &ScheduledTransaction, max_len: usize) -> SuiResult<bool> {
+        let _write = self.write.lock();
+        let digest = transaction.digest();
+        if self.tables.transactions.contains_key(digest)? {
+            return Ok(true);
+        }
+        if self.len() >= max_len {
+            return Ok(false);
+        }
+        self.tables.transactions.insert(digest, transaction)?;
+        self.len.fetch_add(1, Ordering::Relaxed);
+        Ok(true)
+    }
+
+    /// Replace the stored transaction with `transaction`, returning whether it was stored. A
+    /// transaction that has been removed is not added back.
+    pub fn update(&self, transaction: &ScheduledTransaction) -> SuiResult<bool> {
+        let _write = self.write.lock();
+        let digest = transaction.digest();
+        if !self.tables.transactions.contains_key(digest)? {
+            return Ok(false);
+        }
+        self.tables.transactions.insert(digest, transaction)?;
+        Ok(true)
+    }
+
+    pub fn get(&self, digest: &TransactionDigest) -> SuiResult<Option<ScheduledTransaction>> {
+        Ok(self.tables.transactions.get(digest)?)
+    }
+
+    /// Remove the transaction, returning whether it was scheduled.
+    pub fn remove(&self, digest: &TransactionDigest) -> SuiResult<bool> {
+        let _write = self.write.lock();
+        if !self.tables.transactions.contains_key(digest)? {
+            return Ok(false);
+        }
+        self.tables.transactions.remove(digest)?;
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn all(&self) -> Vec<ScheduledTransaction> {
+        self.tables
+            .transactions
+            .unbounded_iter()
+            .map(|(_, transaction)| transaction)
+            .collect()
+    }
+}
+
+struct ScheduledTransactionMetrics {
+    pending: IntGauge,
+    submitted: IntCounter,
+    dropped: IntCounter,
+}
+
+impl ScheduledTransactionMetrics {
+    fn new(registry: &Registry) -> Self {
+        Self {
+            pending: register_int_gauge_with_registry!(
+                "scheduled_transactions_pending",
+                "Number of scheduled transactions waiting to be submitted",
+                registry,
+            )
+            .unwrap(),
+            submitted: register_int_counter_with_registry!(
+                "scheduled_transactions_submitted",
+                "Number of scheduled transactions submitted successfully",
+                registry,
+            )
+            .unwrap(),
+            dropped: register_int_counter_with_registry!(
+                "scheduled_transactions_dropped",
+                "Number of scheduled transactions given up on after failing to submit them",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// Stores scheduled transactions, and submits them through the node's transaction orchestrator
+/// when they are due.
+pub struct ScheduledTransactionService<A: Clone> {
+    state: Arc<AuthorityState>,
+    orchestrator: Arc<TransactiondOrchestrator<A>>,
+    store: ScheduledTransactionStore,
+    config: ScheduledTransactionsConfig,
+    metrics: ScheduledTransactionMetrics,
+}
+
+impl<A> ScheduledTransactionService<A>
+where
+    A: AuthorityAPI + Send + Sync + 'static + Clone,
+    OnsiteReconfigObserver: ReconfigObserver<A>,
+{
+    pub fn new(
+        state: Arc<AuthorityState>,
+        orchestrator: Arc<TransactiondOrchestrator<A>>,
+        path: PathBuf,
+        config: ScheduledTransactionsConfig,
+        registry: &Registry,
+    ) -> Self {
+        let store = ScheduledTransactionStore::new(path);
+        let metrics = ScheduledTransactionMetrics::new(registry);
+        metrics.pending.set(store.len() as i64);
+        Self {
+            state,
+            orchestrator,
+            store,
+            config,
+            metrics,
+        }
+    }
+
+    /// Store `transaction`, to be submitted once the chain reaches both `not_before_checkpoint`
+    /// and `not_before_timestamp_ms`, or as soon as possible if neither is given. Its signatures
+    /// are checked now, so that invalid transactions are rejected rather than silently dropped
+    /// when they are due.
+    pub fn schedule(
+        &self,
+        transaction: Transaction,
+        not_before_checkpoint: Option<CheckpointSequenceNumber>,
+        not_before_timestamp_ms: Option<u64>,
+    ) -> SuiResult<TransactionDigest> {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let transaction = epoch_store.verify_transaction(transaction)?.into_inner();
+        let digest = *transaction.digest();
+        let stored = self.store.insert(
+            &ScheduledTransaction {
+                transaction,
+                not_before_checkpoint,
+                not_before_timestamp_ms,
+                submit_attempts: 0,
+            },
+            self.config.max_pending_transactions,
+        )?;
+        if !stored {
+            return Err(SuiError::UserInputError {
+                error: UserInputError::SizeLimitExceeded {
+                    limit: "maximum scheduled transactions".to_string(),
+                    value: self.config.max_pending_transactions.to_string(),
+                },
+            });
+        }
+        self.metrics.pending.set(self.store.len() as i64);
+        Ok(digest)
+    }
+
+    /// Stop this node from submitting the transaction, returning whether it was still scheduled.
+    /// Only its sender can cancel it, with `signature`, its signature of the transaction's
+    /// [`cancellation`].
+    pub fn cancel(
+        &self,
+        digest: &TransactionDigest,
+        signature: &GenericSignature,
+    ) -> SuiResult<bool> {
+        let Some(scheduled) = self.store.get(digest)? else {
+            return Ok(false);
+        };
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        scheduled.verify_cancellation(signature, &epoch_store.signature_verifier)?;
+        self.remove(digest)
+    }
+
+    /// Remove the transaction, once it no longer needs submitting, returning whether it was still
+    /// scheduled.
+    fn remove(&self, digest: &TransactionDigest) -> SuiResult<bool> {
+        let removed = self.store.remove(digest)?;
+        self.metrics.pending.set(self.store.len() as i64);
+        Ok(removed)
+    }
+
+    pub fn get(&self, digest: &TransactionDigest) -> SuiResult<Option<ScheduledTransaction>> {
+        self.store.get(digest)
+    }
+
+    /// Submit transactions as they become due, forever.
+    pub async fn run(self: Arc<Self>) {
+        let mut interval =
+            tokio::time::interval(Duration::from_millis(self.config.poll_interval_ms));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.submit_due_transactions().await {
+                warn!("Failed to submit scheduled transactions: {e}");
+            }
+        }
+    }
+
+    async fn submit_due_transactions(&self) -> SuiResult {
+        if self.store.is_empty() {
+            return Ok(());
+        }
+
+        let checkpoint = self.state.get_latest_checkpoint_sequence_number()?;
+        let timestamp_ms = self
+            .state
+            .get_verified_checkpoint_by_sequence_number(checkpoint)?
+            .timestamp_ms;
+        let due = self
+            .store
+            .all()
+            .into_iter()
+            .filter(|scheduled| scheduled.is_due(checkpoint, timestamp_ms));
+        // Each submission waits for its transaction to be executed, so they are made side by
+        // side, for a slow one not to hold up the rest.
+        futures::stream::iter(due)
+            .for_each_concurrent(
+                self.config.max_concurrent_submissions,
+                |scheduled| async move {
+                    let digest = *scheduled.digest();
+                    if let Err(e) = self.submit(scheduled).await {
+                        warn!(?digest, "Failed to submit scheduled transaction: {e}");
+                    }
+                },
+            )
+            .await;
+        Ok(())
+    }
+
+    async fn submit(&self, mut scheduled: ScheduledTransaction) -> SuiResult {
+        let digest = *scheduled.digest();
+        if self.state.is_tx_already_executed(&digest)? {
+            info!(?digest, "Scheduled transaction was already executed");
+            self.remove(&digest)?;
+            return Ok(());
+        }
+
+        let request = ExecuteTransactionRequest {
+            transaction: scheduled.transaction.clone(),
+            request_type: ExecuteTransactionRequestType::WaitForEffectsCert,
+        };
+        let timeout = Duration::from_millis(self.config.submit_timeout_ms);
+        let result = tokio::time::timeout(
+            timeout,
+            self.orchestrator.execute_transaction_block(request),
+        )
+        .await
+        .unwrap_or_else(|_| Err(QuorumDriverError::TimeoutBeforeFinality));
+        match result {
+            Ok(_) => {
+                info!(?digest, "Submitted scheduled transaction");
+                self.metrics.submitted.inc();
+                self.remove(&digest)?;
+            }
+            Err(e) => {
+                scheduled.submit_attempts += 1;
+                if scheduled.submit_attempts >= self.config.max_submit_attempts {
+                    warn!(
+                        ?digest,
+                        "Giving up on scheduled transaction after {} attempts: {e}",
+                        scheduled.submit_attempts
+                    );
+                    self.metrics.dropped.inc();
+                    self.remove(&digest)?;
+                } else if self.store.update(&scheduled)? {
+                    // The attempt is only recorded if the transaction wasn't cancelled meanwhile.
+                    warn!(?digest, "Failed to submit scheduled transaction: {e}");
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -12,9 +12,10 @@ use lru::LruCache;
 use mysten_metrics::monitored_scope;
 use parking_lot::{Mutex, MutexGuard, RwLock};
 use prometheus::{register_int_counter_with_registry, IntCounter, Registry};
-use shared_crypto::intent::Intent;
+use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
 use std::hash::Hash;
 use std::sync::Arc;
+use sui_types::base_types::SuiAddress;
 use sui_types::digests::SenderSignedDataDigest;
 use sui_types::digests::ZKLoginInputsDigest;
 use sui_types::transaction::SenderSignedData;
@@ -25,7 +26,7 @@ use sui_types::{
     error::{SuiError, SuiResult},
     message_envelope::{AuthenticatedMessage, Message},
     messages_checkpoint::SignedCheckpointSummary,
-    signature::VerifyParams,
+    signature::{AuthenticatorTrait, GenericSignature, VerifyParams},
     transaction::{CertifiedTransaction, VerifiedCertificate},
 };
 use tap::TapFallible;
@@ -353,20 +354,40 @@ impl SignatureVerifier {
             signed_tx.full_message_digest(),
             || {
                 signed_tx.verify_epoch(self.committee.epoch())?;
-                let jwks = self.jwks.read().clone();
-                let verify_params = VerifyParams::new(
-                    jwks,
-                    self.zk_login_params.supported_providers.clone(),
-                    self.zk_login_params.env.clone(),
-                    self.zk_login_params.verify_legacy_zklogin_address,
-                    self.zk_login_params.accept_zklogin_in_multisig,
-                );
-                signed_tx.verify_message_signature(&verify_params)
+                signed_tx.verify_message_signature(&self.verify_params())
             },
             || Ok(()),
         )
     }
 
+    /// Verify that `signature` is `author`'s signature of `message`, as a personal message, in
+    /// the current epoch.
+    pub fn verify_personal_message(
+        &self,
+        message: PersonalMessage,
+        signature: &GenericSignature,
+        author: SuiAddress,
+    ) -> SuiResult {
+        let message = IntentMessage::new(Intent::personal_message(), message);
+        signature.verify_authenticator(
+            &message,
+            author,
+            Some(self.committee.epoch()),
+            &self.verify_params(),
+        )
+    }
+
+    fn verify_params(&self) -> VerifyParams {
+        let jwks = self.jwks.read().clone();
+        VerifyParams::new(
+            jwks,
+            self.zk_login_params.supported_providers.clone(),
+            self.zk_login_params.env.clone(),
+            self.zk_login_params.verify_legacy_zklogin_address,
+            self.zk_login_params.accept_zklogin_in_multisig,
+        )
+    }
+
     pub fn clear_signature_cache(&self) {
         self.certificate_cache.clear();
         self.signed_data_cache.clear();
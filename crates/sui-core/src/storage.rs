@@ -1,16 +1,21 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use itertools::Itertools;
 use parking_lot::Mutex;
+use std::path::Path;
 use std::sync::Arc;
 use sui_types::storage::ObjectStore;
+use typed_store::rocks::MetricConf;
+use typed_store::Map;
 
-use sui_types::base_types::TransactionDigest;
+use sui_types::base_types::{ObjectID, TransactionDigest, VersionNumber};
 use sui_types::committee::Committee;
 use sui_types::committee::EpochId;
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::effects::{TransactionEffects, TransactionEvents};
 use sui_types::error::SuiError;
+use sui_types::messages_checkpoint::CheckpointContents;
 use sui_types::messages_checkpoint::CheckpointContentsDigest;
 use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
@@ -24,6 +29,7 @@ use sui_types::storage::WriteStore;
 use sui_types::storage::{ObjectKey, ReadStore};
 use sui_types::transaction::VerifiedTransaction;
 
+use crate::authority::authority_store_tables::AuthorityPerpetualTables;
 use crate::checkpoints::CheckpointStore;
 use crate::epoch::committee_store::CommitteeStore;
 use crate::execution_cache::ExecutionCacheRead;
@@ -336,3 +342,243 @@ impl WriteStore for RocksDbStore {
         Ok(())
     }
 }
+
+/// A read-only view of a running node's database, opened as a RocksDB secondary instance, for
+/// colocated services to read checkpoints from without going through the node's APIs. It only
+/// sees the node's writes up to the last call to [`Self::try_catch_up_with_primary`].
+pub struct SecondaryRocksDbStore {
+    perpetual_tables: AuthorityPerpetualTables,
+    committee_store: CommitteeStore,
+    checkpoint_store: CheckpointStore,
+}
+
+impl SecondaryRocksDbStore {
+    /// Open the database of the node whose `db-path` is `db_path`. The secondary instance keeps
+    /// its own files under `secondary_path`, which must not be used by anything else.
+    pub fn open(db_path: &Path, secondary_path: &Path) -> Self {
+        let perpetual_path = AuthorityPerpetualTables::path(&db_path.join("store"));
+        Self {
+            perpetual_tables: AuthorityPerpetualTables::open_tables_secondary(
+                perpetual_path,
+                AuthorityPerpetualTables::path(&secondary_path.join("store")),
+                MetricConf::new("perpetual_secondary"),
+                None,
+            ),
+            committee_store: CommitteeStore::open_secondary(
+                db_path.join("epochs"),
+                secondary_path.join("epochs"),
+            ),
+            checkpoint_store: CheckpointStore::open_tables_secondary(
+                db_path.join("checkpoints"),
+                secondary_path.join("checkpoints"),
+                MetricConf::new("checkpoint_secondary"),
+                None,
+            ),
+        }
+    }
+
+    /// Catch up with the node's writes so far. Checkpoints are executed before they are recorded
+    /// as such, so catching up with the node's tables before its checkpoints ensures that
+    /// everything read for a checkpoint up to the latest one is available.
+    pub fn try_catch_up_with_primary(&self) -> Result<(), SuiError> {
+        self.perpetual_tables.objects.try_catch_up_with_primary()?;
+        self.committee_store.try_catch_up_with_primary()?;
+        self.checkpoint_store
+            .certified_checkpoints
+            .try_catch_up_with_primary()?;
+        Ok(())
+    }
+}
+
+impl ReadStore for SecondaryRocksDbStore {
+    fn get_committee(&self, epoch: EpochId) -> Result<Option<Arc<Committee>>, StorageError> {
+        self.committee_store
+            .get_committee(&epoch)
+            .map_err(StorageError::custom)
+    }
+
+    /// The latest checkpoint the node has executed, which, unlike later checkpoints, is
+    /// guaranteed to have all of its transactions, effects, events and objects available.
+    fn get_latest_checkpoint(&self) -> Result<VerifiedCheckpoint, StorageError> {
+        self.checkpoint_store
+            .get_highest_executed_checkpoint()?
+            .ok_or_else(|| StorageError::missing("unable to get latest checkpoint"))
+    }
+
+    fn get_highest_verified_checkpoint(&self) -> Result<VerifiedCheckpoint, StorageError> {
+        self.checkpoint_store
+            .get_highest_verified_checkpoint()?
+            .ok_or_else(|| StorageError::missing("unable to get highest verified checkpoint"))
+    }
+
+    fn get_highest_synced_checkpoint(&self) -> Result<VerifiedCheckpoint, StorageError> {
+        self.checkpoint_store
+            .get_highest_synced_checkpoint()?
+            .ok_or_else(|| StorageError::missing("unable to get highest synced checkpoint"))
+    }
+
+    fn get_lowest_available_checkpoint(&self) -> Result<CheckpointSequenceNumber, StorageError> {
+        self.checkpoint_store
+            .get_highest_pruned_checkpoint_seq_number()
+            .map(|seq| seq + 1)
+            .map_err(Into::into)
+    }
+
+    fn get_checkpoint_by_digest(
+        &self,
+        digest: &CheckpointDigest,
+    ) -> Result<Option<VerifiedCheckpoint>, StorageError> {
+        self.checkpoint_store
+            .get_checkpoint_by_digest(digest)
+            .map_err(Into::into)
+    }
+
+    fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<Option<VerifiedCheckpoint>, StorageError> {
+        self.checkpoint_store
+            .get_checkpoint_by_sequence_number(sequence_number)
+            .map_err(Into::into)
+    }
+
+    fn get_checkpoint_contents_by_digest(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Result<Option<CheckpointContents>, StorageError> {
+        self.checkpoint_store
+            .get_checkpoint_contents(digest)
+            .map_err(Into::into)
+    }
+
+    fn get_checkpoint_contents_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<Option<CheckpointContents>, StorageError> {
+        match self.get_checkpoint_by_sequence_number(sequence_number)? {
+            Some(checkpoint) => self.get_checkpoint_contents_by_digest(&checkpoint.content_digest),
+            None => Ok(None),
+        }
+    }
+
+    fn get_transaction(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<Option<Arc<VerifiedTransaction>>, StorageError> {
+        Ok(self
+            .perpetual_tables
+            .get_transaction(tx_digest)
+            .map_err(StorageError::custom)?
+            .map(|tx| Arc::new(tx.into())))
+    }
+
+    fn get_transaction_effects(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<Option<TransactionEffects>, StorageError> {
+        self.perpetual_tables
+            .get_effects(tx_digest)
+            .map_err(StorageError::custom)
+    }
+
+    fn get_events(
+        &self,
+        event_digest: &TransactionEventsDigest,
+    ) -> Result<Option<TransactionEvents>, StorageError> {
+        let data = self
+            .perpetual_tables
+            .events
+            .safe_range_iter((*event_digest, 0)..=(*event_digest, usize::MAX))
+            .map_ok(|(_, event)| event)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((!data.is_empty()).then_some(TransactionEvents { data }))
+    }
+
+    fn get_full_checkpoint_contents_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<Option<FullCheckpointContents>, StorageError> {
+        self.checkpoint_store
+            .get_full_checkpoint_contents_by_sequence_number(sequence_number)
+            .map_err(Into::into)
+    }
+
+    fn get_full_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Result<Option<FullCheckpointContents>, StorageError> {
+        match self
+            .checkpoint_store
+            .get_sequence_number_by_contents_digest(digest)?
+        {
+            Some(sequence_number) => {
+                self.get_full_checkpoint_contents_by_sequence_number(sequence_number)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl ObjectStore for SecondaryRocksDbStore {
+    fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, StorageError> {
+        self.perpetual_tables.get_object(object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, StorageError> {
+        self.perpetual_tables.get_object_by_key(object_id, version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_swarm_config::test_utils::{empty_contents, CommitteeFixture};
+
+    #[test]
+    fn test_secondary_store_follows_primary() {
+        let db_path = tempfile::tempdir().unwrap();
+        let secondary_path = tempfile::tempdir().unwrap();
+        let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+        let (checkpoints, _, _, _) = committee.make_empty_checkpoints(2, None);
+
+        // The node's databases, as it syncs and executes checkpoints.
+        let _perpetual_tables = AuthorityPerpetualTables::open(&db_path.path().join("store"), None);
+        let _committee_store =
+            CommitteeStore::new(db_path.path().join("epochs"), committee.committee(), None);
+        let checkpoint_store = CheckpointStore::new(&db_path.path().join("checkpoints"));
+        let execute = |checkpoint: &VerifiedCheckpoint| {
+            checkpoint_store
+                .insert_verified_checkpoint(checkpoint)
+                .unwrap();
+            checkpoint_store
+                .insert_verified_checkpoint_contents(checkpoint, empty_contents())
+                .unwrap();
+            checkpoint_store
+                .update_highest_executed_checkpoint(checkpoint)
+                .unwrap();
+        };
+        execute(&checkpoints[0]);
+
+        let store = SecondaryRocksDbStore::open(db_path.path(), secondary_path.path());
+        let latest = store.get_latest_checkpoint().unwrap();
+        assert_eq!(latest.digest(), checkpoints[0].digest());
+        assert_eq!(
+            *store.get_committee(0).unwrap().unwrap(),
+            *committee.committee()
+        );
+        assert!(store
+            .get_full_checkpoint_contents(&latest.content_digest)
+            .unwrap()
+            .is_some());
+
+        // Checkpoints the node executes later are only seen once the store catches up with it.
+        execute(&checkpoints[1]);
+        assert_eq!(*store.get_latest_checkpoint().unwrap().sequence_number(), 0);
+        store.try_catch_up_with_primary().unwrap();
+        assert_eq!(*store.get_latest_checkpoint().unwrap().sequence_number(), 1);
+    }
+}
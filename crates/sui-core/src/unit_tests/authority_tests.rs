@@ -295,6 +295,66 @@ async fn test_dry_run_no_gas_big_transfer() {
     assert_eq!(*dry_run_res.effects.status(), SuiExecutionStatus::Success);
 }
 
+#[tokio::test]
+async fn test_dry_run_bundle() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let gas_object_id = ObjectID::random();
+    let (_, fullnode, _) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![(sender, gas_object_id)]).await;
+    let rgp = fullnode.reference_gas_price_for_testing().unwrap();
+    let gas_object_ref = fullnode
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .compute_object_reference();
+
+    // Both transactions pay with the same gas coin, referring to it at its current version.
+    let transactions = [1000, 2000]
+        .into_iter()
+        .map(|amount| {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.transfer_sui(recipient, Some(amount));
+            let data = TransactionData::new_programmable(
+                sender,
+                vec![gas_object_ref],
+                builder.finish(),
+                rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+                rgp,
+            );
+            let signed = to_sender_signed_transaction(data.clone(), &sender_key);
+            (data, *signed.digest())
+        })
+        .collect();
+
+    let results = fullnode
+        .dry_exec_transaction_bundle(transactions)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    for (response, _, _, _) in &results {
+        assert_eq!(*response.effects.status(), SuiExecutionStatus::Success);
+    }
+
+    // The second transaction pays with the gas coin as the first one left it.
+    let (_, _, first_effects, _) = &results[0];
+    let (_, _, second_effects, _) = &results[1];
+    let first_gas_version = first_effects.gas_object().0 .1;
+    assert!(second_effects
+        .modified_at_versions()
+        .contains(&(gas_object_id, first_gas_version)));
+
+    // Nothing is written to the store.
+    let gas_object_version = fullnode
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .version();
+    assert_eq!(gas_object_version, OBJECT_START_VERSION);
+}
+
 #[tokio::test]
 async fn test_dev_inspect_object_by_bytes() {
     let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
@@ -0,0 +1,128 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::signature_verifier::SignatureVerifierMetrics;
+use crate::test_utils::make_dummy_tx;
+use fastcrypto_zkp::bn254::zk_login_api::ZkLoginEnv;
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_types::committee::Committee;
+use sui_types::crypto::{get_key_pair, AccountKeyPair, Signature};
+use sui_types::utils::create_fake_transaction;
+
+fn scheduled(
+    not_before_checkpoint: Option<CheckpointSequenceNumber>,
+    not_before_timestamp_ms: Option<u64>,
+) -> ScheduledTransaction {
+    ScheduledTransaction {
+        transaction: create_fake_transaction(),
+        not_before_checkpoint,
+        not_before_timestamp_ms,
+        submit_attempts: 0,
+    }
+}
+
+#[test]
+fn test_scheduled_transaction_is_due() {
+    assert!(scheduled(None, None).is_due(0, 0));
+
+    let tx = scheduled(Some(10), None);
+    assert!(!tx.is_due(9, u64::MAX));
+    assert!(tx.is_due(10, 0));
+
+    let tx = scheduled(None, Some(1_000));
+    assert!(!tx.is_due(u64::MAX, 999));
+    assert!(tx.is_due(0, 1_000));
+
+    // Both conditions have to be met.
+    let tx = scheduled(Some(10), Some(1_000));
+    assert!(!tx.is_due(10, 999));
+    assert!(!tx.is_due(9, 1_000));
+    assert!(tx.is_due(11, 1_001));
+}
+
+#[test]
+fn test_scheduled_transaction_store() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = ScheduledTransactionStore::new(temp_dir.path().to_path_buf());
+    assert!(store.is_empty());
+
+    let first = scheduled(Some(10), None);
+    let second = scheduled(None, Some(1_000));
+    assert!(store.insert(&first, 2).unwrap());
+    assert!(store.insert(&second, 2).unwrap());
+    assert_eq!(store.len(), 2);
+
+    // Once full, the store only takes transactions it already has.
+    assert!(!store.insert(&scheduled(None, None), 2).unwrap());
+    assert!(store.insert(&first, 2).unwrap());
+    assert_eq!(store.len(), 2);
+    assert_eq!(
+        store
+            .get(first.digest())
+            .unwrap()
+            .unwrap()
+            .not_before_checkpoint,
+        Some(10)
+    );
+
+    assert!(store.remove(first.digest()).unwrap());
+    // Cancelling again is fine, but reports that it was no longer scheduled.
+    assert!(!store.remove(first.digest()).unwrap());
+    assert!(store.get(first.digest()).unwrap().is_none());
+    assert_eq!(store.len(), 1);
+
+    // Updates don't add back transactions that were removed.
+    let mut attempted = second.clone();
+    attempted.submit_attempts = 1;
+    assert!(store.update(&attempted).unwrap());
+    assert_eq!(
+        store.get(second.digest()).unwrap().unwrap().submit_attempts,
+        1
+    );
+    assert!(!store.update(&first).unwrap());
+    assert!(store.get(first.digest()).unwrap().is_none());
+
+    let remaining: Vec<_> = store.all().iter().map(|tx| *tx.digest()).collect();
+    assert_eq!(remaining, vec![*second.digest()]);
+}
+
+fn sign_cancellation(digest: &TransactionDigest, key: &AccountKeyPair) -> GenericSignature {
+    let message = IntentMessage::new(Intent::personal_message(), cancellation(digest));
+    Signature::new_secure(&message, key).into()
+}
+
+#[test]
+fn test_verify_cancellation() {
+    let (committee, _) = Committee::new_simple_test_committee();
+    let verifier = SignatureVerifier::new(
+        Arc::new(committee),
+        SignatureVerifierMetrics::new(&Registry::new()),
+        vec![],
+        ZkLoginEnv::Test,
+        true,
+        true,
+    );
+
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let (receiver, other_key): (_, AccountKeyPair) = get_key_pair();
+    let tx = ScheduledTransaction {
+        transaction: make_dummy_tx(receiver, sender, &sender_key),
+        not_before_checkpoint: None,
+        not_before_timestamp_ms: None,
+        submit_attempts: 0,
+    };
+
+    // The sender can cancel the transaction.
+    let signature = sign_cancellation(tx.digest(), &sender_key);
+    tx.verify_cancellation(&signature, &verifier).unwrap();
+
+    // No one else can.
+    let signature = sign_cancellation(tx.digest(), &other_key);
+    assert!(tx.verify_cancellation(&signature, &verifier).is_err());
+
+    // Nor can the sender's cancellation of another transaction be replayed against this one.
+    let other = make_dummy_tx(receiver, sender, &sender_key);
+    let signature = sign_cancellation(other.digest(), &sender_key);
+    assert!(tx.verify_cancellation(&signature, &verifier).is_err());
+}
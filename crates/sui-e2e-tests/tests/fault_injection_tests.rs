@@ -0,0 +1,43 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use sui_macros::sim_test;
+use sui_test_transaction_builder::make_transfer_sui_transaction;
+use test_cluster::TestClusterBuilder;
+
+#[sim_test]
+async fn test_liveness_with_isolated_validator() {
+    let test_cluster = Arc::new(TestClusterBuilder::new().build().await);
+    let validators = test_cluster.get_validator_pubkeys();
+
+    let faults = test_cluster.fault_injector();
+    faults.isolate(&validators[0]);
+    faults.delay_checkpoints(&validators[1], Duration::from_secs(1));
+
+    // The remaining validators still form a quorum.
+    let tx = make_transfer_sui_transaction(&test_cluster.wallet, None, None).await;
+    test_cluster.execute_transaction(tx).await;
+
+    faults.heal();
+    faults.clear_checkpoint_delays();
+    let tx = make_transfer_sui_transaction(&test_cluster.wallet, None, None).await;
+    test_cluster.execute_transaction(tx).await;
+}
+
+#[sim_test]
+async fn test_liveness_with_crash_at_round() {
+    let test_cluster = Arc::new(TestClusterBuilder::new().build().await);
+    let validators = test_cluster.get_validator_pubkeys();
+
+    let faults = test_cluster.fault_injector();
+    faults.crash_at_round(validators[0], 5, Some(Duration::from_secs(5)));
+
+    for _ in 0..5 {
+        let tx = make_transfer_sui_transaction(&test_cluster.wallet, None, None).await;
+        test_cluster.execute_transaction(tx).await;
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
@@ -5,7 +5,8 @@ use async_graphql::connection::CursorType;
 use serde::{Deserialize, Serialize};
 use sui_indexer::models::objects::StoredHistoryObject;
 
-use crate::data::Conn;
+use crate::data::{Conn, Db};
+use crate::error::Error;
 use crate::raw_query::RawQuery;
 use crate::types::checkpoint::Checkpoint;
 use crate::types::cursor::{JsonCursor, Page};
@@ -208,3 +209,25 @@ pub(crate) fn consistent_range(
 
     Ok(Some((lhs, rhs)))
 }
+
+/// Cursors are pinned to the checkpoint they were created at, but subsequent pages may be served
+/// by a different replica of the service, which may not have indexed that checkpoint yet. Rather
+/// than silently serving a page from an older view of the chain, this function checks that the
+/// `checkpoint_viewed_at` taken from a cursor has been indexed by this replica, and returns a
+/// retryable error otherwise. The upper bound of the available range only ever increases, so once
+/// this check succeeds, it remains true for the rest of the request.
+pub(crate) async fn ensure_checkpoint_indexed(
+    db: &Db,
+    checkpoint_viewed_at: Option<u64>,
+) -> Result<(), Error> {
+    let Some(checkpoint_viewed_at) = checkpoint_viewed_at else {
+        return Ok(());
+    };
+
+    let latest = Checkpoint::query_latest_checkpoint_sequence_number(db).await?;
+    if latest < checkpoint_viewed_at {
+        return Err(Error::CheckpointNotIndexed(checkpoint_viewed_at, latest));
+    }
+
+    Ok(())
+}
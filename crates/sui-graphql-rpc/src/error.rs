@@ -12,6 +12,7 @@ use sui_json_rpc::name_service::NameServiceError;
 pub(crate) mod code {
     pub const BAD_REQUEST: &str = "BAD_REQUEST";
     pub const BAD_USER_INPUT: &str = "BAD_USER_INPUT";
+    pub const CHECKPOINT_NOT_INDEXED: &str = "CHECKPOINT_NOT_INDEXED";
     pub const INTERNAL_SERVER_ERROR: &str = "INTERNAL_SERVER_ERROR";
     pub const REQUEST_TIMEOUT: &str = "REQUEST_TIMEOUT";
     pub const UNKNOWN: &str = "UNKNOWN";
@@ -71,6 +72,13 @@ pub enum Error {
     CursorNoFirstLast,
     #[error("Connection's page size of {0} exceeds max of {1}")]
     PageTooLarge(u64, u64),
+    /// The cursor was created at a checkpoint that this instance of the service has not indexed
+    /// yet. The request can be retried once the service has caught up.
+    #[error(
+        "Cursor was created at checkpoint {0}, but the latest checkpoint available is {1}. \
+         Retry the request later"
+    )]
+    CheckpointNotIndexed(u64, u64),
     // Catch-all for client-fault errors
     #[error("{0}")]
     Client(String),
@@ -88,6 +96,9 @@ impl ErrorExtensions for Error {
             | Error::Client(_) => {
                 e.set("code", code::BAD_USER_INPUT);
             }
+            Error::CheckpointNotIndexed(_, _) => {
+                e.set("code", code::CHECKPOINT_NOT_INDEXED);
+            }
             Error::Internal(_) => {
                 e.set("code", code::INTERNAL_SERVER_ERROR);
             }
@@ -121,7 +121,8 @@ mod tests {
     use async_graphql::registry::Registry;
     use async_graphql::OutputType;
 
-    use crate::types::query::Query;
+    use crate::plugins::NoPlugins;
+    use crate::types::query::RootQuery;
 
     use super::*;
 
@@ -131,7 +132,7 @@ mod tests {
     /// unimplemented don't appear in the set of unimplemented fields.
     fn test_groups_match_schema() {
         let mut registry = Registry::default();
-        Query::create_type_info(&mut registry);
+        RootQuery::<NoPlugins>::create_type_info(&mut registry);
 
         let unimplemented = BTreeSet::from_iter([
             ("Checkpoint", "addressMetrics"),
@@ -0,0 +1,97 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{MergedObject, ObjectType};
+
+/// Addresses as they appear in the schema, for plugins that accept or return them.
+pub use crate::types::sui_address::SuiAddress;
+
+/// Operator-specific fields (e.g. KYC flags, or internal labels for addresses) that are merged
+/// into the root `Query` type when the service starts, so that deployments can extend the schema
+/// without forking this crate.
+///
+/// A plugin is an ordinary `async_graphql` object: its fields, and the types they mention, are
+/// registered in the schema next to the service's own, so their names must not clash with those
+/// the service already defines.
+pub trait QueryPlugin: ObjectType + 'static {}
+
+impl<T: ObjectType + 'static> QueryPlugin for T {}
+
+/// The plugin that the service runs with unless an operator installs one, which adds no fields.
+#[derive(MergedObject, Default)]
+pub struct NoPlugins;
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{Object, SimpleObject};
+    use serde_json::json;
+    use sui_types::base_types::SuiAddress as NativeSuiAddress;
+
+    use super::*;
+    use crate::server::builder::schema_builder;
+
+    #[derive(SimpleObject)]
+    struct AddressLabel {
+        name: String,
+        verified: bool,
+    }
+
+    /// Labels one address, standing in for a plugin that reads them from an operator's database.
+    struct Labels(NativeSuiAddress);
+
+    #[Object]
+    impl Labels {
+        async fn address_label(&self, address: SuiAddress) -> Option<AddressLabel> {
+            (NativeSuiAddress::from(address) == self.0).then(|| AddressLabel {
+                name: "treasury".to_string(),
+                verified: true,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_plugin_field() {
+        let labelled = NativeSuiAddress::random_for_testing_only();
+        let schema = schema_builder(Labels(labelled)).finish();
+
+        let response = schema
+            .execute(format!(
+                r#"{{
+                    labelled: addressLabel(address: "{labelled}") {{ name verified }}
+                    unlabelled: addressLabel(address: "0x1") {{ name }}
+                }}"#
+            ))
+            .await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "labelled": { "name": "treasury", "verified": true },
+                "unlabelled": null,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_plugin_keeps_service_fields() {
+        let schema = schema_builder(Labels(NativeSuiAddress::ZERO)).finish();
+
+        // Fields of the service are still part of the root query next to the plugin's.
+        let response = schema
+            .execute(r#"{ __type(name: "Query") { fields { name } } }"#)
+            .await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let fields = response.data.into_json().unwrap()["__type"]["fields"].clone();
+        let names: Vec<_> = fields
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["name"].as_str().unwrap().to_string())
+            .collect();
+
+        assert!(names.contains(&"addressLabel".to_string()));
+        assert!(names.contains(&"chainIdentifier".to_string()));
+    }
+}
@@ -8,6 +8,7 @@ use crate::context_data::package_cache::DbPackageStore;
 use crate::data::Db;
 use crate::metrics::Metrics;
 use crate::mutation::Mutation;
+use crate::plugins::{NoPlugins, QueryPlugin};
 use crate::types::move_object::IMoveObject;
 use crate::types::object::IObject;
 use crate::types::owner::IOwner;
@@ -22,12 +23,12 @@ use crate::{
         timeout::Timeout,
     },
     server::version::{check_version_middleware, set_version_middleware},
-    types::query::{Query, SuiGraphQLSchema},
+    types::query::{Query, RootQuery, SuiGraphQLSchema},
 };
 use async_graphql::extensions::ApolloTracing;
 use async_graphql::extensions::Tracing;
 use async_graphql::EmptySubscription;
-use async_graphql::{extensions::ExtensionFactory, Schema, SchemaBuilder};
+use async_graphql::{extensions::ExtensionFactory, SchemaBuilder};
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use axum::extract::FromRef;
 use axum::extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo, State};
@@ -66,9 +67,9 @@ impl Server {
     }
 }
 
-pub(crate) struct ServerBuilder {
+pub(crate) struct ServerBuilder<P: QueryPlugin = NoPlugins> {
     state: AppState,
-    schema: SchemaBuilder<Query, Mutation, EmptySubscription>,
+    schema: SchemaBuilder<RootQuery<P>, Mutation, EmptySubscription>,
     router: Option<Router>,
 }
 
@@ -101,9 +102,26 @@ impl FromRef<AppState> for Metrics {
 
 impl ServerBuilder {
     pub fn new(state: AppState) -> Self {
+        Self::with_plugin(state, NoPlugins)
+    }
+
+    pub async fn from_yaml_config(
+        path: &str,
+        version: &Version,
+    ) -> Result<(Self, ServerConfig), Error> {
+        let config = ServerConfig::from_yaml(path)?;
+        Self::from_config(&config, version, NoPlugins)
+            .await
+            .map(|builder| (builder, config))
+    }
+}
+
+impl<P: QueryPlugin> ServerBuilder<P> {
+    /// Like `new`, but with the fields of `plugin` merged into the root `Query` type.
+    pub fn with_plugin(state: AppState, plugin: P) -> Self {
         Self {
             state,
-            schema: schema_builder(),
+            schema: schema_builder(plugin),
             router: None,
         }
     }
@@ -125,11 +143,11 @@ impl ServerBuilder {
         self
     }
 
-    fn build_schema(self) -> Schema<Query, Mutation, EmptySubscription> {
+    fn build_schema(self) -> SuiGraphQLSchema<P> {
         self.schema.finish()
     }
 
-    fn build_components(self) -> (String, Schema<Query, Mutation, EmptySubscription>, Router) {
+    fn build_components(self) -> (String, SuiGraphQLSchema<P>, Router) {
         let address = self.address();
         let ServerBuilder { schema, router, .. } = self;
         (
@@ -142,8 +160,8 @@ impl ServerBuilder {
     fn init_router(&mut self) {
         if self.router.is_none() {
             let router: Router = Router::new()
-                .route("/", post(graphql_handler))
-                .route("/graphql", post(graphql_handler))
+                .route("/", post(graphql_handler::<P>))
+                .route("/graphql", post(graphql_handler::<P>))
                 .route("/health", axum::routing::get(health_checks))
                 .with_state(self.state.clone())
                 .route_layer(middleware::from_fn_with_state(
@@ -224,17 +242,11 @@ impl ServerBuilder {
         })
     }
 
-    pub async fn from_yaml_config(
-        path: &str,
+    pub async fn from_config(
+        config: &ServerConfig,
         version: &Version,
-    ) -> Result<(Self, ServerConfig), Error> {
-        let config = ServerConfig::from_yaml(path)?;
-        Self::from_config(&config, version)
-            .await
-            .map(|builder| (builder, config))
-    }
-
-    pub async fn from_config(config: &ServerConfig, version: &Version) -> Result<Self, Error> {
+        plugin: P,
+    ) -> Result<Self, Error> {
         // PROMETHEUS
         let prom_addr: SocketAddr = format!(
             "{}:{}",
@@ -259,7 +271,7 @@ impl ServerBuilder {
         // METRICS
         let metrics = Metrics::new(&registry);
         let state = AppState::new(config.connection.clone(), metrics.clone());
-        let mut builder = ServerBuilder::new(state);
+        let mut builder = Self::with_plugin(state, plugin);
 
         let name_service_config = config.name_service.clone();
         let reader = PgManager::reader_with_config(
@@ -333,8 +345,10 @@ impl ServerBuilder {
     }
 }
 
-fn schema_builder() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
-    async_graphql::Schema::build(Query, Mutation, EmptySubscription)
+pub(crate) fn schema_builder<P: QueryPlugin>(
+    plugin: P,
+) -> SchemaBuilder<RootQuery<P>, Mutation, EmptySubscription> {
+    async_graphql::Schema::build(RootQuery(Query, plugin), Mutation, EmptySubscription)
         .register_output_type::<IMoveObject>()
         .register_output_type::<IObject>()
         .register_output_type::<IOwner>()
@@ -342,12 +356,18 @@ fn schema_builder() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
 
 /// Return the string representation of the schema used by this server.
 pub fn export_schema() -> String {
-    schema_builder().finish().sdl()
+    export_schema_with_plugin(NoPlugins)
+}
+
+/// Return the string representation of the schema used by this server, once the fields of
+/// `plugin` have been merged into it.
+pub fn export_schema_with_plugin<P: QueryPlugin>(plugin: P) -> String {
+    schema_builder(plugin).finish().sdl()
 }
 
-async fn graphql_handler(
+async fn graphql_handler<P: QueryPlugin>(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    schema: axum::Extension<SuiGraphQLSchema>,
+    schema: axum::Extension<SuiGraphQLSchema<P>>,
     headers: HeaderMap,
     req: GraphQLRequest,
 ) -> (axum::http::Extensions, GraphQLResponse) {
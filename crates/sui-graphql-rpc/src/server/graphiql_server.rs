@@ -5,6 +5,7 @@ use tracing::info;
 
 use crate::config::{ServerConfig, Version};
 use crate::error::Error;
+use crate::plugins::{NoPlugins, QueryPlugin};
 use crate::server::builder::ServerBuilder;
 
 async fn graphiql(ide_title: axum::Extension<Option<String>>) -> impl axum::response::IntoResponse {
@@ -19,11 +20,21 @@ async fn graphiql(ide_title: axum::Extension<Option<String>>) -> impl axum::resp
 pub async fn start_graphiql_server(
     server_config: &ServerConfig,
     version: &Version,
+) -> Result<(), Error> {
+    start_graphiql_server_with_plugin(server_config, version, NoPlugins).await
+}
+
+/// Like `start_graphiql_server`, but with the fields of an operator-specific `plugin` merged into
+/// the root `Query` type, for deployments that depend on this crate as a library.
+pub async fn start_graphiql_server_with_plugin<P: QueryPlugin>(
+    server_config: &ServerConfig,
+    version: &Version,
+    plugin: P,
 ) -> Result<(), Error> {
     info!("Starting server with config: {:?}", server_config);
     info!("Server version: {:?}", version);
     start_graphiql_server_impl(
-        ServerBuilder::from_config(server_config, version).await?,
+        ServerBuilder::from_config(server_config, version, plugin).await?,
         server_config.ide.ide_title.clone(),
     )
     .await
@@ -38,8 +49,8 @@ pub async fn start_graphiql_server_from_cfg_path(
     start_graphiql_server_impl(server_builder, config.ide.ide_title).await
 }
 
-async fn start_graphiql_server_impl(
-    server_builder: ServerBuilder,
+async fn start_graphiql_server_impl<P: QueryPlugin>(
+    server_builder: ServerBuilder<P>,
     ide_title: String,
 ) -> Result<(), Error> {
     let address = server_builder.address();
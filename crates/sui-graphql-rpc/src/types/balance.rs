@@ -3,7 +3,7 @@
 
 use super::cursor::{self, Page, RawPaginated, Target};
 use super::{big_int::BigInt, move_type::MoveType, sui_address::SuiAddress};
-use crate::consistency::{consistent_range, Checkpointed};
+use crate::consistency::{consistent_range, ensure_checkpoint_indexed, Checkpointed};
 use crate::data::{Db, DbConnection, QueryExecutor};
 use crate::error::Error;
 use crate::raw_query::RawQuery;
@@ -94,6 +94,7 @@ impl Balance {
         // consistent. Otherwise, use the value from the parameter, or set to None. This is so that
         // paginated queries are consistent with the previous query that created the cursor.
         let cursor_viewed_at = page.validate_cursor_consistency()?;
+        ensure_checkpoint_indexed(db, cursor_viewed_at).await?;
         let checkpoint_viewed_at: Option<u64> = cursor_viewed_at.or(checkpoint_viewed_at);
 
         let response = db
@@ -10,7 +10,7 @@ use super::{
     gas::GasCostSummary,
     transaction_block::{self, TransactionBlock, TransactionBlockFilter},
 };
-use crate::consistency::Checkpointed;
+use crate::consistency::{ensure_checkpoint_indexed, Checkpointed};
 use crate::{
     data::{self, Conn, Db, DbConnection, QueryExecutor},
     error::Error,
@@ -274,6 +274,7 @@ impl Checkpoint {
     ) -> Result<Connection<String, Checkpoint>, Error> {
         use checkpoints::dsl;
         let cursor_viewed_at = page.validate_cursor_consistency()?;
+        ensure_checkpoint_indexed(db, cursor_viewed_at).await?;
         let checkpoint_viewed_at: Option<u64> = cursor_viewed_at.or(checkpoint_viewed_at);
 
         let ((prev, next, results), rhs) = db
@@ -16,8 +16,8 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     config::ServiceConfig,
-    consistency::{Checkpointed, ConsistentIndexCursor},
-    data::{Conn, DbConnection, DieselBackend, DieselConn, Query},
+    consistency::{ensure_checkpoint_indexed, Checkpointed, ConsistentIndexCursor},
+    data::{Conn, Db, DbConnection, DieselBackend, DieselConn, Query},
     error::Error,
     raw_query::RawQuery,
 };
@@ -205,11 +205,13 @@ where
 
 impl Page<JsonCursor<ConsistentIndexCursor>> {
     /// Treat the cursors of this Page as indices into a range [0, total). Validates that the
-    /// cursors of the page are consistent, and returns two booleans indicating whether there is a
-    /// previous or next page in the range, the `checkpoint_viewed_at` to set for consistency, and
-    /// an iterator of cursors within that Page.
-    pub(crate) fn paginate_consistent_indices(
+    /// cursors of the page are consistent, and that the checkpoint they were created at has been
+    /// indexed (see [`ensure_checkpoint_indexed`]), and returns two booleans indicating whether
+    /// there is a previous or next page in the range, the `checkpoint_viewed_at` to set for
+    /// consistency, and an iterator of cursors within that Page.
+    pub(crate) async fn paginate_consistent_indices(
         &self,
+        db: &Db,
         total: usize,
         checkpoint_viewed_at: u64,
     ) -> Result<
@@ -222,6 +224,7 @@ impl Page<JsonCursor<ConsistentIndexCursor>> {
         Error,
     > {
         let cursor_viewed_at = self.validate_cursor_consistency()?;
+        ensure_checkpoint_indexed(db, cursor_viewed_at).await?;
         let checkpoint_viewed_at = cursor_viewed_at.unwrap_or(checkpoint_viewed_at);
 
         let mut lo = self.after().map_or(0, |a| a.ix + 1);
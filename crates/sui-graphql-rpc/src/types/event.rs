@@ -11,13 +11,14 @@ use super::{
     address::Address, base64::Base64, date_time::DateTime, move_module::MoveModule,
     move_value::MoveValue, sui_address::SuiAddress,
 };
-use crate::consistency::Checkpointed;
+use crate::consistency::{ensure_checkpoint_indexed, Checkpointed};
 use crate::data::{self, QueryExecutor};
 use crate::{data::Db, error::Error};
 use async_graphql::connection::{Connection, CursorType, Edge};
 use async_graphql::*;
 use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl};
 use serde::{Deserialize, Serialize};
+use sui_indexer::compression::UNCOMPRESSED;
 use sui_indexer::models::{events::StoredEvent, transactions::StoredTransaction};
 use sui_indexer::schema::{events, transactions, tx_senders};
 use sui_types::base_types::ObjectID;
@@ -159,6 +160,7 @@ impl Event {
         checkpoint_viewed_at: Option<u64>,
     ) -> Result<Connection<String, Event>, Error> {
         let cursor_viewed_at = page.validate_cursor_consistency()?;
+        ensure_checkpoint_indexed(db, cursor_viewed_at).await?;
         let checkpoint_viewed_at: Option<u64> = cursor_viewed_at.or(checkpoint_viewed_at);
 
         let ((prev, next, results), checkpoint_viewed_at) = db
@@ -269,6 +271,7 @@ impl Event {
                 .to_canonical_string(/* with_prefix */ true),
             bcs: native_event.contents.clone(),
             timestamp_ms: stored_tx.timestamp_ms,
+            compression: UNCOMPRESSED,
         };
 
         Ok(Self {
@@ -8,7 +8,7 @@ use move_binary_format::binary_views::BinaryIndexedView;
 use move_disassembler::disassembler::Disassembler;
 use move_ir_types::location::Loc;
 
-use crate::consistency::{ConsistentIndexCursor, ConsistentNamedCursor};
+use crate::consistency::{ensure_checkpoint_indexed, ConsistentIndexCursor, ConsistentNamedCursor};
 use crate::data::Db;
 use crate::error::Error;
 use sui_package_resolver::Module as ParsedMoveModule;
@@ -80,7 +80,12 @@ impl MoveModule {
 
         let mut connection = Connection::new(false, false);
         let Some((prev, next, checkpoint_viewed_at, cs)) = page
-            .paginate_consistent_indices(bytecode.friend_decls.len(), self.checkpoint_viewed_at)?
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                bytecode.friend_decls.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
@@ -158,6 +163,7 @@ impl MoveModule {
         let struct_range = self.parsed.structs(after, before);
 
         let cursor_viewed_at = page.validate_cursor_consistency()?;
+        ensure_checkpoint_indexed(ctx.data_unchecked(), cursor_viewed_at).await?;
         let checkpoint_viewed_at = cursor_viewed_at.unwrap_or(self.checkpoint_viewed_at);
 
         let mut connection = Connection::new(false, false);
@@ -222,6 +228,7 @@ impl MoveModule {
         let function_range = self.parsed.functions(after, before);
 
         let cursor_viewed_at = page.validate_cursor_consistency()?;
+        ensure_checkpoint_indexed(ctx.data_unchecked(), cursor_viewed_at).await?;
         let checkpoint_viewed_at = cursor_viewed_at.unwrap_or(self.checkpoint_viewed_at);
 
         let mut connection = Connection::new(false, false);
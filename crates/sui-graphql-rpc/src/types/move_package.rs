@@ -17,7 +17,7 @@ use super::sui_address::SuiAddress;
 use super::suins_registration::SuinsRegistration;
 use super::transaction_block::{self, TransactionBlock, TransactionBlockFilter};
 use super::type_filter::ExactTypeFilter;
-use crate::consistency::ConsistentNamedCursor;
+use crate::consistency::{ensure_checkpoint_indexed, ConsistentNamedCursor};
 use crate::data::Db;
 use crate::error::Error;
 use crate::types::checkpoint::Checkpoint;
@@ -272,6 +272,7 @@ impl MovePackage {
 
         let page = Page::from_params(ctx.data_unchecked(), first, after, last, before)?;
         let cursor_viewed_at = page.validate_cursor_consistency()?;
+        ensure_checkpoint_indexed(ctx.data_unchecked(), cursor_viewed_at).await?;
         let checkpoint_viewed_at = cursor_viewed_at.unwrap_or(self.checkpoint_viewed_at);
 
         let parsed = self.parsed_package()?;
@@ -104,15 +104,25 @@ impl MoveValue {
     ///
     /// This form is offered as a less verbose convenience in cases where the layout of the type is
     /// known by the client.
-    async fn json(&self, ctx: &Context<'_>) -> Result<Json> {
+    ///
+    /// If `typed` is set, every value is instead wrapped in an object of the form
+    /// `{ type: string, value: JSON }`, where `type` is the canonical representation of the
+    /// value's Move type, so that clients do not need to know the layout ahead of time to
+    /// interpret numeric precision, or addresses.
+    async fn json(&self, ctx: &Context<'_>, typed: Option<bool>) -> Result<Json> {
         let resolver = ctx
             .data::<Resolver<PackageCache>>()
             .map_err(|_| Error::Internal("Unable to fetch Package Cache.".to_string()))
             .extend()?;
 
+        let layout = self.type_.layout_impl(resolver).await.extend()?;
+
         // Factor out into its own non-GraphQL, non-async function for better testability
-        self.json_impl(self.type_.layout_impl(resolver).await.extend()?)
-            .extend()
+        if typed.unwrap_or(false) {
+            self.typed_json_impl(layout).extend()
+        } else {
+            self.json_impl(layout).extend()
+        }
     }
 }
 
@@ -141,6 +151,11 @@ impl MoveValue {
     fn json_impl(&self, layout: A::MoveTypeLayout) -> Result<Json, Error> {
         Ok(try_to_json_value(self.value_impl(layout)?)?.into())
     }
+
+    fn typed_json_impl(&self, layout: A::MoveTypeLayout) -> Result<Json, Error> {
+        let value = self.value_impl(layout.clone())?;
+        Ok(try_to_typed_json_value(&layout, value)?.into())
+    }
 }
 
 impl TryFrom<A::MoveValue> for MoveData {
@@ -270,6 +285,90 @@ fn try_to_json_value(value: A::MoveValue) -> Result<Value, Error> {
     })
 }
 
+/// Like `try_to_json_value`, but each value is wrapped in an object that pairs its JSON
+/// representation (under `value`) with its canonical Move type (under `type`). Values are paired
+/// with their layouts so that the element type of empty vectors and `None` options is still
+/// reported.
+fn try_to_typed_json_value(
+    layout: &A::MoveTypeLayout,
+    value: A::MoveValue,
+) -> Result<Value, Error> {
+    use A::MoveTypeLayout as L;
+    use A::MoveValue as V;
+
+    let type_: TypeTag = layout.into();
+    let value = match (layout, value) {
+        (L::Vector(inner), V::Vector(xs)) => Value::List(
+            xs.into_iter()
+                .map(|x| try_to_typed_json_value(inner, x))
+                .collect::<Result<_, _>>()?,
+        ),
+
+        (L::Struct(layout), V::Struct(s)) => {
+            let A::MoveStruct { type_, fields } = s;
+            if is_type(&type_, &STD, MOD_OPTION, TYP_OPTION) {
+                // 0x1::option::Option
+                let inner = option_layout(layout)?;
+                match extract_option(&type_, fields)? {
+                    Some(value) => try_to_typed_json_value(inner, value)?,
+                    None => Value::Null,
+                }
+            } else if is_type(&type_, &STD, MOD_ASCII, TYP_STRING)
+                || is_type(&type_, &STD, MOD_STRING, TYP_STRING)
+                || is_type(&type_, &SUI, MOD_OBJECT, TYP_UID)
+                || is_type(&type_, &SUI, MOD_OBJECT, TYP_ID)
+            {
+                // Strings, UIDs and IDs have the same representation as their untyped
+                // counterparts.
+                try_to_json_value(V::Struct(A::MoveStruct { type_, fields }))?
+            } else {
+                // Arbitrary structs
+                Value::Object(
+                    layout
+                        .fields
+                        .iter()
+                        .zip(fields)
+                        .map(|(field, (name, value))| {
+                            Ok((
+                                Name::new(name.to_string()),
+                                try_to_typed_json_value(&field.layout, value)?,
+                            ))
+                        })
+                        .collect::<Result<_, Error>>()?,
+                )
+            }
+        }
+
+        (_, value) => try_to_json_value(value)?,
+    };
+
+    Ok(Value::Object(
+        [
+            (
+                Name::new("type"),
+                Value::String(type_.to_canonical_string(/* with_prefix */ true)),
+            ),
+            (Name::new("value"), value),
+        ]
+        .into_iter()
+        .collect(),
+    ))
+}
+
+/// Extracts the layout of the element of an `0x1::option::Option<T>`, assuming the struct layout
+/// matches `{ vec: vector<T> }`.
+fn option_layout(layout: &A::MoveStructLayout) -> Result<&A::MoveTypeLayout, Error> {
+    match layout.fields.as_slice() {
+        [A::MoveFieldLayout {
+            layout: A::MoveTypeLayout::Vector(inner),
+            ..
+        }] => Ok(inner),
+        _ => Err(Error::Internal(
+            "Expected Option to have a single vector field.".to_string(),
+        )),
+    }
+}
+
 fn is_type(tag: &StructTag, address: &AccountAddress, module: &IdentStr, name: &IdentStr) -> bool {
     &tag.address == address
         && tag.module.as_ident_str() == module
@@ -479,6 +578,13 @@ mod tests {
         MoveValue { type_, bcs }.json_impl(layout)
     }
 
+    fn typed_json<T: Serialize>(layout: A::MoveTypeLayout, data: T) -> Result<Json, Error> {
+        let tag: TypeTag = (&layout).into();
+        let type_ = MoveType::new(tag);
+        let bcs = Base64(bcs::to_bytes(&data).unwrap());
+        MoveValue { type_, bcs }.typed_json_impl(layout)
+    }
+
     #[test]
     fn bool_data() {
         let v = data(L::Bool, true);
@@ -894,6 +1000,46 @@ mod tests {
         expect.assert_eq(&format!("{v}"));
     }
 
+    #[test]
+    fn u64_typed_json() {
+        let v = typed_json(L::U64, 42_424_242_424u64).unwrap();
+        let expect = expect![[r#"{type: "u64",value: "42424242424"}"#]];
+        expect.assert_eq(&format!("{v}"));
+    }
+
+    #[test]
+    fn address_typed_json() {
+        let v = typed_json(L::Address, address("0x42")).unwrap();
+        let expect = expect![[
+            r#"{type: "address",value: "0x0000000000000000000000000000000000000000000000000000000000000042"}"#
+        ]];
+        expect.assert_eq(&format!("{v}"));
+    }
+
+    #[test]
+    fn empty_vector_typed_json() {
+        let v = typed_json(vector_layout!(L::U128), vec![] as Vec<u128>).unwrap();
+        let expect = expect![[r#"{type: "vector<u128>",value: []}"#]];
+        expect.assert_eq(&format!("{v}"));
+    }
+
+    #[test]
+    fn compound_typed_json() {
+        let l = struct_layout!("0x42::foo::Bar" {
+            "baz": struct_layout!("0x1::option::Option" { "vec": vector_layout!(L::U64) }),
+            "qux": struct_layout!("0x1::ascii::String" {
+                "bytes": vector_layout!(L::U8),
+            }),
+        });
+
+        let v = typed_json(l, (vec![7u64], "Hello, world!")).unwrap();
+
+        let expect = expect![[
+            r#"{type: "0x0000000000000000000000000000000000000000000000000000000000000042::foo::Bar",value: {baz: {type: "0x0000000000000000000000000000000000000000000000000000000000000001::option::Option<u64>",value: {type: "u64",value: "7"}},qux: {type: "0x0000000000000000000000000000000000000000000000000000000000000001::ascii::String",value: "Hello, world!"}}}"#
+        ]];
+        expect.assert_eq(&format!("{v}"));
+    }
+
     #[test]
     fn signer_value() {
         let v = data(L::Signer, address("0x42"));
@@ -37,14 +37,26 @@ use super::{
     type_filter::ExactTypeFilter,
 };
 use crate::{
-    config::ServiceConfig, context_data::db_data_provider::PgManager, data::Db, error::Error,
+    config::ServiceConfig,
+    context_data::db_data_provider::PgManager,
+    data::Db,
+    error::Error,
     mutation::Mutation,
+    plugins::{NoPlugins, QueryPlugin},
 };
 
 pub(crate) struct Query;
-pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, EmptySubscription>;
 
-#[Object]
+// The root `Query` type of the schema: the service's own fields, merged with those of the plugin
+// that the operator installed. It is not documented, so that it has no description in the schema.
+#[derive(MergedObject)]
+#[graphql(name = "Query")]
+pub(crate) struct RootQuery<P: QueryPlugin>(pub(crate) Query, pub(crate) P);
+
+pub(crate) type SuiGraphQLSchema<P = NoPlugins> =
+    async_graphql::Schema<RootQuery<P>, Mutation, EmptySubscription>;
+
+#[Object(name = "ServiceQuery")]
 impl Query {
     /// First four bytes of the network's genesis checkpoint digest (uniquely identifies the
     /// network).
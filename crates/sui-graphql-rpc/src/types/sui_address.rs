@@ -12,10 +12,10 @@ use thiserror::Error;
 const SUI_ADDRESS_LENGTH: usize = 32;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy)]
-pub(crate) struct SuiAddress([u8; SUI_ADDRESS_LENGTH]);
+pub struct SuiAddress([u8; SUI_ADDRESS_LENGTH]);
 
 #[derive(Error, Debug, Eq, PartialEq)]
-pub(crate) enum FromStrError {
+pub enum FromStrError {
     #[error("Invalid SuiAddress. Missing 0x prefix.")]
     NoPrefix,
 
@@ -31,7 +31,7 @@ pub(crate) enum FromStrError {
 }
 
 #[derive(Error, Debug, Eq, PartialEq)]
-pub(crate) enum FromVecError {
+pub enum FromVecError {
     #[error("Expected SuiAddress with {} bytes, received {0}", SUI_ADDRESS_LENGTH)]
     WrongLength(usize),
 }
@@ -27,7 +27,7 @@ use sui_types::{
 };
 
 use crate::{
-    consistency::Checkpointed,
+    consistency::{ensure_checkpoint_indexed, Checkpointed},
     data::{self, Db, DbConnection, QueryExecutor},
     error::Error,
     types::intersect,
@@ -336,6 +336,7 @@ impl TransactionBlock {
         use transactions as tx;
 
         let cursor_viewed_at = page.validate_cursor_consistency()?;
+        ensure_checkpoint_indexed(db, cursor_viewed_at).await?;
         let checkpoint_viewed_at: Option<u64> = cursor_viewed_at.or(checkpoint_viewed_at);
 
         let response = db
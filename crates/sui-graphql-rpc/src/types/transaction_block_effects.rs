@@ -155,8 +155,13 @@ impl TransactionBlockEffects {
 
         let dependencies = self.native().dependencies();
 
-        let Some((prev, next, _, cs)) =
-            page.paginate_consistent_indices(dependencies.len(), self.checkpoint_viewed_at)?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                dependencies.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
@@ -216,7 +221,12 @@ impl TransactionBlockEffects {
         let input_shared_objects = self.native().input_shared_objects();
 
         let Some((prev, next, _, cs)) = page
-            .paginate_consistent_indices(input_shared_objects.len(), self.checkpoint_viewed_at)?
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                input_shared_objects.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
@@ -253,8 +263,13 @@ impl TransactionBlockEffects {
 
         let object_changes = self.native().object_changes();
 
-        let Some((prev, next, _, cs)) =
-            page.paginate_consistent_indices(object_changes.len(), self.checkpoint_viewed_at)?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                object_changes.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
@@ -293,10 +308,13 @@ impl TransactionBlockEffects {
             return Ok(connection);
         };
 
-        let Some((prev, next, _, cs)) = page.paginate_consistent_indices(
-            stored_tx.balance_changes.len(),
-            self.checkpoint_viewed_at,
-        )?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                stored_tx.balance_changes.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
@@ -334,8 +352,9 @@ impl TransactionBlockEffects {
             TransactionBlockEffectsKind::Executed { events, .. }
             | TransactionBlockEffectsKind::DryRun { events, .. } => events.len(),
         };
-        let Some((prev, next, _, cs)) =
-            page.paginate_consistent_indices(len, self.checkpoint_viewed_at)?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(ctx.data_unchecked(), len, self.checkpoint_viewed_at)
+            .await?
         else {
             return Ok(connection);
         };
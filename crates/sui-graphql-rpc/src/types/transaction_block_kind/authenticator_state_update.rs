@@ -66,10 +66,13 @@ impl AuthenticatorStateUpdateTransaction {
         let page = Page::from_params(ctx.data_unchecked(), first, after, last, before)?;
 
         let mut connection = Connection::new(false, false);
-        let Some((prev, next, _, cs)) = page.paginate_consistent_indices(
-            self.native.new_active_jwks.len(),
-            self.checkpoint_viewed_at,
-        )?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                self.native.new_active_jwks.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
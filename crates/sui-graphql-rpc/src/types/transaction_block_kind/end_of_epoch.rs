@@ -98,8 +98,13 @@ impl EndOfEpochTransaction {
         let page = Page::from_params(ctx.data_unchecked(), first, after, last, before)?;
 
         let mut connection = Connection::new(false, false);
-        let Some((prev, next, _, cs)) =
-            page.paginate_consistent_indices(self.native.len(), self.checkpoint_viewed_at)?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                self.native.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
@@ -179,10 +184,13 @@ impl ChangeEpochTransaction {
         let page = Page::from_params(ctx.data_unchecked(), first, after, last, before)?;
 
         let mut connection = Connection::new(false, false);
-        let Some((prev, next, _, cs)) = page.paginate_consistent_indices(
-            self.native.system_packages.len(),
-            self.checkpoint_viewed_at,
-        )?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                self.native.system_packages.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
@@ -215,8 +215,13 @@ impl ProgrammableTransactionBlock {
         let page = Page::from_params(ctx.data_unchecked(), first, after, last, before)?;
 
         let mut connection = Connection::new(false, false);
-        let Some((prev, next, _, cs)) =
-            page.paginate_consistent_indices(self.native.inputs.len(), self.checkpoint_viewed_at)?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                self.native.inputs.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
@@ -245,7 +250,12 @@ impl ProgrammableTransactionBlock {
 
         let mut connection = Connection::new(false, false);
         let Some((prev, next, _, cs)) = page
-            .paginate_consistent_indices(self.native.commands.len(), self.checkpoint_viewed_at)?
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                self.native.commands.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
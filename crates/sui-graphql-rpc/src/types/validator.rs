@@ -229,8 +229,13 @@ impl Validator {
             return Ok(connection);
         };
 
-        let Some((prev, next, _, cs)) =
-            page.paginate_consistent_indices(addresses.len(), self.checkpoint_viewed_at)?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                addresses.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
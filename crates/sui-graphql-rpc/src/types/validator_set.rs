@@ -76,8 +76,13 @@ impl ValidatorSet {
             return Ok(connection);
         };
 
-        let Some((prev, next, _, cs)) =
-            page.paginate_consistent_indices(validators.len(), self.checkpoint_viewed_at)?
+        let Some((prev, next, _, cs)) = page
+            .paginate_consistent_indices(
+                ctx.data_unchecked(),
+                validators.len(),
+                self.checkpoint_viewed_at,
+            )
+            .await?
         else {
             return Ok(connection);
         };
@@ -3,6 +3,7 @@
 
 #[cfg(feature = "pg_integration")]
 mod tests {
+    use async_graphql::connection::{CursorType, OpaqueCursor};
     use fastcrypto::encoding::{Base64, Encoding};
     use rand::rngs::StdRng;
     use rand::SeedableRng;
@@ -716,6 +717,64 @@ mod tests {
             .is_null());
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_cursor_checkpoint_not_indexed() {
+        let rng = StdRng::from_seed([12; 32]);
+        let mut sim = Simulacrum::new_with_rng(rng);
+        sim.create_checkpoint();
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let cluster = sui_graphql_rpc::test_infra::cluster::serve_executor(
+            connection_config,
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(sim),
+            None,
+        )
+        .await;
+        cluster
+            .wait_for_checkpoint_catchup(1, Duration::from_secs(10))
+            .await;
+
+        // Cursors pinned to a checkpoint this service has yet to index, for paginating by name
+        // and by index.
+        let named = OpaqueCursor(json!({ "n": "coin", "c": 1000 })).encode_cursor();
+        let indexed = OpaqueCursor(json!({ "i": 0, "c": 1000 })).encode_cursor();
+        let queries = [
+            format!(
+                r#"{{ package(address: "0x2") {{ modules(after: "{named}") {{ nodes {{ name }} }} }} }}"#
+            ),
+            format!(
+                r#"{{ package(address: "0x2") {{ module(name: "coin") {{ structs(after: "{named}") {{ nodes {{ name }} }} }} }} }}"#
+            ),
+            format!(
+                r#"{{ package(address: "0x2") {{ module(name: "coin") {{ functions(after: "{named}") {{ nodes {{ name }} }} }} }} }}"#
+            ),
+            format!(
+                r#"{{ package(address: "0x2") {{ module(name: "coin") {{ friends(after: "{indexed}") {{ nodes {{ name }} }} }} }} }}"#
+            ),
+            format!(
+                r#"{{ checkpoint(id: {{ sequenceNumber: 0 }}) {{ transactionBlocks {{ nodes {{ kind {{ ... on GenesisTransaction {{ objects(after: "{indexed}") {{ nodes {{ address }} }} }} }} }} }} }} }}"#
+            ),
+        ];
+
+        for query in queries {
+            let res = cluster
+                .graphql_client
+                .execute_to_graphql(query.clone(), true, vec![], vec![])
+                .await
+                .unwrap();
+            let errors = res.errors();
+            assert_eq!(errors.len(), 1, "{query}: {errors:?}");
+            assert!(
+                errors[0]
+                    .message
+                    .starts_with("Cursor was created at checkpoint 1000"),
+                "{query}: {errors:?}"
+            );
+        }
+    }
+
     use sui_graphql_rpc::server::builder::tests::*;
 
     #[tokio::test]
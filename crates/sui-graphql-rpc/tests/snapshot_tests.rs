@@ -1,10 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use async_graphql::{Object, SimpleObject};
 use insta::assert_snapshot;
 use std::fs::write;
 use std::path::PathBuf;
-use sui_graphql_rpc::server::builder::export_schema;
+use sui_graphql_rpc::plugins::SuiAddress;
+use sui_graphql_rpc::server::builder::{export_schema, export_schema_with_plugin};
 
 #[test]
 fn test_schema_sdl_export() {
@@ -17,3 +19,50 @@ fn test_schema_sdl_export() {
 
     assert_snapshot!(sdl);
 }
+
+#[derive(SimpleObject)]
+struct AddressLabel {
+    name: String,
+    verified: bool,
+}
+
+/// Stands in for a plugin that an operator would install to label addresses.
+struct Labels;
+
+#[Object]
+impl Labels {
+    async fn address_label(&self, address: SuiAddress) -> Option<AddressLabel> {
+        (address == SuiAddress::from_array([0; 32])).then(|| AddressLabel {
+            name: "zero".to_string(),
+            verified: true,
+        })
+    }
+}
+
+#[test]
+fn test_schema_sdl_export_with_plugin() {
+    let base = export_schema();
+    let sdl = export_schema_with_plugin(Labels);
+
+    // Keep only what the plugin adds to the schema: the types it introduces, and the fields it
+    // adds to types that already exist.
+    let blocks: Vec<_> = base.split("\n\n").collect();
+    let added = sdl
+        .split("\n\n")
+        .filter(|block| !blocks.contains(block))
+        .map(|block| {
+            let header = block.lines().next().unwrap_or_default();
+            let Some(existing) = blocks.iter().find(|b| b.lines().next() == Some(header)) else {
+                return block.to_string();
+            };
+
+            let mut lines = vec![header];
+            lines.extend(block.lines().filter(|l| !existing.lines().any(|e| e == *l)));
+            lines.push("}");
+            lines.join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    assert_snapshot!(added);
+}
@@ -0,0 +1,111 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{env, path::PathBuf};
+use tonic_build::manual::{Builder, Method, Service};
+
+type Result<T> = ::std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// The methods of the `IndexerRead` service in `proto/indexer.proto`, as (name, route name, input
+/// type, output type, whether the output is streamed).
+const METHODS: &[(&str, &str, &str, &str, bool)] = &[
+    (
+        "get_object",
+        "GetObject",
+        "GetObjectRequest",
+        "Object",
+        false,
+    ),
+    (
+        "multi_get_objects",
+        "MultiGetObjects",
+        "MultiGetObjectsRequest",
+        "MultiGetObjectsResponse",
+        false,
+    ),
+    (
+        "get_transactions",
+        "GetTransactions",
+        "GetTransactionsRequest",
+        "GetTransactionsResponse",
+        false,
+    ),
+    (
+        "get_checkpoint",
+        "GetCheckpoint",
+        "GetCheckpointRequest",
+        "Checkpoint",
+        false,
+    ),
+    (
+        "get_checkpoint_events",
+        "GetCheckpointEvents",
+        "GetCheckpointEventsRequest",
+        "GetCheckpointEventsResponse",
+        false,
+    ),
+    (
+        "get_system_state",
+        "GetSystemState",
+        "GetSystemStateRequest",
+        "SystemState",
+        false,
+    ),
+    (
+        "stream_checkpoints",
+        "StreamCheckpoints",
+        "StreamCheckpointsRequest",
+        "Checkpoint",
+        true,
+    ),
+    (
+        "stream_events",
+        "StreamEvents",
+        "StreamEventsRequest",
+        "Event",
+        true,
+    ),
+];
+
+fn main() -> Result<()> {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto/indexer.proto");
+    println!("cargo:rerun-if-env-changed=BUILD_INDEXER_PROTO");
+
+    // The messages in `src/grpc/proto.rs` are generated from `proto/indexer.proto`, and checked
+    // in so that building does not need protoc. Set this env var to regenerate them, with protoc
+    // installed locally.
+    if env::var("BUILD_INDEXER_PROTO").is_ok() {
+        prost_build::Config::new()
+            .out_dir("src/grpc")
+            .compile_protos(&["proto/indexer.proto"], &["proto/"])?;
+        std::fs::rename("src/grpc/sui.indexer.rs", "src/grpc/proto.rs")?;
+    }
+
+    let out_dir = if env::var("DUMP_GENERATED_GRPC").is_ok() {
+        PathBuf::from("")
+    } else {
+        PathBuf::from(env::var("OUT_DIR")?)
+    };
+
+    let mut service = Service::builder()
+        .name("IndexerRead")
+        .package("sui.indexer")
+        .comment("Reads from the indexer's database");
+    for (name, route_name, input_type, output_type, streaming) in METHODS {
+        let mut method = Method::builder()
+            .name(name)
+            .route_name(route_name)
+            .input_type(format!("crate::grpc::proto::{input_type}"))
+            .output_type(format!("crate::grpc::proto::{output_type}"))
+            .codec_path("tonic::codec::ProstCodec");
+        if *streaming {
+            method = method.server_streaming();
+        }
+        service = service.method(method.build());
+    }
+
+    Builder::new().out_dir(&out_dir).compile(&[service.build()]);
+
+    Ok(())
+}
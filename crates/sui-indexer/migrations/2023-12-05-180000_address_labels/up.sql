@@ -0,0 +1,10 @@
+-- Labels (names and categories) associated with addresses by the operator of the indexer, e.g.
+-- to identify exchanges and well-known contracts in explorers. These are not derived from chain
+-- data, so they are only written through CSV imports and the labels admin API.
+CREATE TABLE address_labels
+(
+    address         BYTEA       PRIMARY KEY,
+    name            TEXT        NOT NULL,
+    category        TEXT
+);
+CREATE INDEX address_labels_category ON address_labels (category);
@@ -0,0 +1,12 @@
+-- Every distinct event type observed on chain, with its field layout as resolved against each
+-- package version that emitted it, and the range of checkpoints it was seen in.
+CREATE TABLE event_schemas
+(
+    event_type              TEXT        NOT NULL,
+    package_id              BYTEA       NOT NULL,
+    layout                  JSONB       NOT NULL,
+    first_seen_checkpoint   BIGINT      NOT NULL,
+    last_seen_checkpoint    BIGINT      NOT NULL,
+    PRIMARY KEY (event_type, package_id)
+);
+CREATE INDEX event_schemas_first_seen_checkpoint ON event_schemas (first_seen_checkpoint);
@@ -0,0 +1,13 @@
+-- Changes to whether (or by whom) an object can be accessed directly: creation, transfers,
+-- wrapping, unwrapping and deletion, one row per object version that a transition produced.
+CREATE TABLE object_transitions
+(
+    object_id                   BYTEA       NOT NULL,
+    object_version              BIGINT      NOT NULL,
+    -- ObjectTransitionKind: 0 created, 1 transferred, 2 wrapped, 3 unwrapped, 4 deleted,
+    -- 5 unwrapped then deleted
+    transition                  SMALLINT    NOT NULL,
+    transaction_digest          BYTEA       NOT NULL,
+    checkpoint_sequence_number  BIGINT      NOT NULL,
+    PRIMARY KEY (object_id, object_version)
+);
@@ -0,0 +1,17 @@
+-- Attempts to use an owned object version in two different transactions, as reported by
+-- validators when the transactions submitted through this indexer were rejected because the
+-- object was already locked by another transaction. These are not derived from checkpoint data,
+-- as equivocating transactions are never certified.
+CREATE TABLE equivocations
+(
+    object_id                   BYTEA         NOT NULL,
+    object_version              BIGINT        NOT NULL,
+    sender                      BYTEA         NOT NULL,
+    -- The transaction that was rejected.
+    transaction_digest          BYTEA         NOT NULL,
+    -- A transaction that had already locked the object version.
+    conflicting_digest          BYTEA         NOT NULL,
+    timestamp_ms                BIGINT        NOT NULL,
+    PRIMARY KEY(object_id, object_version, transaction_digest, conflicting_digest)
+);
+CREATE INDEX equivocations_sender ON equivocations (sender, timestamp_ms);
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE tx_classes (
+    tx_sequence_number          BIGINT       NOT NULL,
+    -- TransactionClass, see `models::tx_indices::class_code`. A transaction may have several.
+    class                       SMALLINT     NOT NULL,
+    PRIMARY KEY(class, tx_sequence_number)
+);
+CREATE INDEX tx_classes_tx_sequence_number_index ON tx_classes (tx_sequence_number ASC);
@@ -0,0 +1,19 @@
+-- Activity of each address per day (UTC), aggregated by the analytical worker from the
+-- transactions it has processed, and kept for a configurable number of days.
+CREATE TABLE address_daily_stats
+(
+    address                     BYTEA         NOT NULL,
+    -- Days since the Unix epoch.
+    day                         BIGINT        NOT NULL,
+    -- Transactions sent by the address.
+    sent_tx_count               BIGINT        NOT NULL,
+    -- Transactions that changed objects owned by the address, after the transaction.
+    received_tx_count           BIGINT        NOT NULL,
+    -- SUI (in MIST) that the address' balance decreased or increased by, summed over transactions.
+    sui_sent                    BIGINT        NOT NULL,
+    sui_received                BIGINT        NOT NULL,
+    -- The latest transaction aggregated into this row, to resume aggregating from.
+    last_tx_sequence_number     BIGINT        NOT NULL,
+    PRIMARY KEY(day, address)
+);
+CREATE INDEX address_daily_stats_last_tx_sequence_number ON address_daily_stats (last_tx_sequence_number);
@@ -0,0 +1,11 @@
+-- Number of addresses that sent transactions, per completed day (UTC), counted by the analytical
+-- worker from address_daily_stats before they are pruned, and kept indefinitely.
+CREATE TABLE active_address_counts
+(
+    -- Days since the Unix epoch.
+    day                         BIGINT        PRIMARY KEY,
+    -- Addresses that sent transactions on the day.
+    daily_active_addresses      BIGINT        NOT NULL,
+    -- Addresses that sent transactions in the 7 days ending with the day.
+    weekly_active_addresses     BIGINT        NOT NULL
+);
@@ -0,0 +1,18 @@
+-- Gas prices paid by programmable transactions in consecutive ranges of checkpoints, computed by
+-- the analytical worker. Ranges don't span epochs.
+CREATE TABLE gas_price_stats
+(
+    first_checkpoint            BIGINT        PRIMARY KEY,
+    last_checkpoint             BIGINT        NOT NULL,
+    epoch                       BIGINT        NOT NULL,
+    -- Timestamp of the last checkpoint.
+    timestamp_ms                BIGINT        NOT NULL,
+    reference_gas_price         BIGINT        NOT NULL,
+    transaction_count           BIGINT        NOT NULL,
+    -- NULL if there were no programmable transactions in the range.
+    mean_gas_price              FLOAT8,
+    median_gas_price            BIGINT,
+    min_gas_price               BIGINT,
+    max_gas_price               BIGINT
+);
+CREATE INDEX gas_price_stats_timestamp_ms ON gas_price_stats (timestamp_ms);
@@ -0,0 +1,10 @@
+-- The APY of each active validator's staking pool in each epoch, computed by the analytical worker
+-- from the pool's exchange rates at the start of the epoch and of the next one.
+CREATE TABLE validator_apys
+(
+    epoch                       BIGINT        NOT NULL,
+    validator_address           BYTEA         NOT NULL,
+    apy                         FLOAT8        NOT NULL,
+    PRIMARY KEY (epoch, validator_address)
+);
+CREATE INDEX validator_apys_validator_address_epoch ON validator_apys (validator_address, epoch);
@@ -0,0 +1,4 @@
+-- How the BCS payloads of each row are compressed: 0 if they aren't, 1 with lz4, 2 with zstd.
+-- See compression.rs.
+ALTER TABLE transactions ADD COLUMN compression SMALLINT NOT NULL DEFAULT 0;
+ALTER TABLE events ADD COLUMN compression SMALLINT NOT NULL DEFAULT 0;
@@ -0,0 +1,19 @@
+-- What happened to each active validator in each epoch, computed by the analytical worker once the
+-- epoch has ended, from the system state at its start and end and its staking events.
+CREATE TABLE validator_history
+(
+    epoch                       BIGINT        NOT NULL,
+    validator_address           BYTEA         NOT NULL,
+    commission_rate             BIGINT        NOT NULL,
+    -- NULL if the validator left at the end of the epoch.
+    next_commission_rate        BIGINT,
+    stake_inflow                BIGINT        NOT NULL,
+    stake_outflow               BIGINT        NOT NULL,
+    -- Validators reporting this one under the tallying rule.
+    reported_by                 BYTEA[]       NOT NULL,
+    slashed                     BOOLEAN       NOT NULL,
+    -- NULL unless the validator was in its low-stake grace period.
+    at_risk_epochs              BIGINT,
+    PRIMARY KEY (epoch, validator_address)
+);
+CREATE INDEX validator_history_validator_address_epoch ON validator_history (validator_address, epoch);
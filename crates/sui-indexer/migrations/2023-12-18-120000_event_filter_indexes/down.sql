@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS events_timestamp_ms;
+DROP INDEX IF EXISTS events_senders;
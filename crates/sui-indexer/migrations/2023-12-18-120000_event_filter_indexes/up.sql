@@ -0,0 +1,4 @@
+-- Indices for conditions on events that can only be combined with others in event filters, which
+-- are compiled into SQL (see `event_filter_clause` in indexer_reader.rs).
+CREATE INDEX events_senders ON events USING GIN (senders);
+CREATE INDEX events_timestamp_ms ON events (timestamp_ms, tx_sequence_number, event_sequence_number);
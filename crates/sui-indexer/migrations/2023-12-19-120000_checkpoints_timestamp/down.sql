@@ -0,0 +1 @@
+DROP INDEX IF EXISTS checkpoints_timestamp_ms;
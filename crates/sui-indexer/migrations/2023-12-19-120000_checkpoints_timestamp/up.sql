@@ -0,0 +1,2 @@
+-- For finding the first checkpoint of the period a table is kept for (see pruner.rs).
+CREATE INDEX checkpoints_timestamp_ms ON checkpoints (timestamp_ms);
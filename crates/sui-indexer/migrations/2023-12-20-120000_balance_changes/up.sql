@@ -0,0 +1,15 @@
+-- Changes that transactions made to the coin balances of addresses, taken by the analytical
+-- worker from the balance changes of the transactions it has processed, so that the history of an
+-- address' balance can be read without decoding every transaction it was involved in.
+CREATE TABLE balance_changes
+(
+    owner                       BYTEA         NOT NULL,
+    -- Canonical type string of the coin, e.g. 0x2::sui::SUI with the address in full.
+    coin_type                   TEXT          NOT NULL,
+    tx_sequence_number          BIGINT        NOT NULL,
+    timestamp_ms                BIGINT        NOT NULL,
+    -- The change to the balance, negative for outflows, saturated to the range of a BIGINT.
+    amount                      BIGINT        NOT NULL,
+    PRIMARY KEY(owner, coin_type, tx_sequence_number)
+);
+CREATE INDEX balance_changes_tx_sequence_number ON balance_changes (tx_sequence_number);
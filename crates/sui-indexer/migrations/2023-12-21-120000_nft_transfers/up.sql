@@ -0,0 +1,21 @@
+-- Changes to who holds objects whose types have a Display, one row per object version that a
+-- change produced. Only changes made while the object's type had a Display are recorded.
+CREATE TABLE nft_transfers
+(
+    object_id                   BYTEA       NOT NULL,
+    object_version              BIGINT      NOT NULL,
+    -- Canonical type string of the object, e.g. 0x2::kiosk::Kiosk with the address in full.
+    object_type                 TEXT        NOT NULL,
+    -- ObjectTransitionKind, encoded as in object_transitions
+    transition                  SMALLINT    NOT NULL,
+    sender                      BYTEA       NOT NULL,
+    -- The address or object that held the object before and after the change, NULL if there was
+    -- none, or if the object was shared or immutable.
+    from_owner                  BYTEA,
+    to_owner                    BYTEA,
+    transaction_digest          BYTEA       NOT NULL,
+    checkpoint_sequence_number  BIGINT      NOT NULL,
+    timestamp_ms                BIGINT      NOT NULL,
+    PRIMARY KEY (object_id, object_version)
+);
+CREATE INDEX nft_transfers_checkpoint_sequence_number ON nft_transfers (checkpoint_sequence_number);
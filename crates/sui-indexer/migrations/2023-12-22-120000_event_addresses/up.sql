@@ -0,0 +1,11 @@
+-- The addresses that each event is relevant to: the sender of the transaction that emitted it,
+-- and the addresses in its payload, other than object IDs. One row per event and address.
+CREATE TABLE event_addresses
+(
+    address                     BYTEA       NOT NULL,
+    tx_sequence_number          BIGINT      NOT NULL,
+    event_sequence_number       BIGINT      NOT NULL,
+    checkpoint_sequence_number  BIGINT      NOT NULL,
+    PRIMARY KEY (address, tx_sequence_number, event_sequence_number)
+);
+CREATE INDEX event_addresses_checkpoint_sequence_number ON event_addresses (checkpoint_sequence_number);
@@ -0,0 +1,21 @@
+-- The calls made to each Move function in each checkpoint, and the gas paid by the transactions
+-- making them. Each package also has a row with an empty module and function name, counting the
+-- transactions that called any of its functions once each, as the rows of its functions count
+-- the gas of a transaction calling several of them more than once.
+CREATE TABLE package_call_metrics
+(
+    package                     BYTEA       NOT NULL,
+    module                      TEXT        NOT NULL,
+    function                    TEXT        NOT NULL,
+    checkpoint_sequence_number  BIGINT      NOT NULL,
+    epoch                       BIGINT      NOT NULL,
+    call_count                  BIGINT      NOT NULL,
+    transaction_count           BIGINT      NOT NULL,
+    computation_cost            BIGINT      NOT NULL,
+    storage_cost                BIGINT      NOT NULL,
+    storage_rebate              BIGINT      NOT NULL,
+    non_refundable_storage_fee  BIGINT      NOT NULL,
+    PRIMARY KEY (package, module, function, checkpoint_sequence_number)
+);
+CREATE INDEX package_call_metrics_package_epoch ON package_call_metrics (package, epoch);
+CREATE INDEX package_call_metrics_checkpoint_sequence_number ON package_call_metrics (checkpoint_sequence_number);
@@ -0,0 +1,9 @@
+-- The epoch of the writer lease (see `leader.rs`), which each writer to take the lease advances.
+-- Committing checkpoints checks that the epoch is still the one the writer took the lease at, so
+-- a writer that lost the lease can't commit checkpoints after another has taken it over.
+CREATE TABLE writer_lease
+(
+    id     BOOLEAN  PRIMARY KEY DEFAULT TRUE CHECK (id),
+    epoch  BIGINT   NOT NULL
+);
+INSERT INTO writer_lease (id, epoch) VALUES (TRUE, 0);
@@ -1,31 +1,55 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::indexer_reader::IndexerReader;
+use crate::prices::PriceOracle;
+use crate::store::IndexerReadStore;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 use sui_json_rpc::coin_api::{parse_to_struct_tag, parse_to_type_tag};
+use sui_json_rpc::error::SuiRpcInputError;
 use sui_json_rpc::SuiRpcModule;
-use sui_json_rpc_api::{cap_page_limit, CoinReadApiServer};
+use sui_json_rpc_api::{cap_page_limit, CoinReadApiServer, QUERY_MAX_RESULT_LIMIT};
 use sui_json_rpc_types::{Balance, CoinPage, Page, SuiCoinMetadata};
 use sui_open_rpc::Module;
 use sui_types::balance::Supply;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::gas_coin::{GAS, TOTAL_SUPPLY_MIST};
 
-pub(crate) struct CoinReadApi {
-    inner: IndexerReader,
+#[derive(Clone)]
+pub(crate) struct CoinReadApi<R> {
+    inner: R,
+    /// What to value balances with, if anything.
+    prices: Option<PriceOracle>,
 }
 
-impl CoinReadApi {
-    pub fn new(inner: IndexerReader) -> Self {
-        Self { inner }
+impl<R: IndexerReadStore> CoinReadApi<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            prices: None,
+        }
+    }
+
+    pub fn with_prices(mut self, prices: Option<PriceOracle>) -> Self {
+        self.prices = prices;
+        self
+    }
+
+    async fn value_balances(&self, balances: &mut [Balance]) {
+        let Some(prices) = &self.prices else {
+            return;
+        };
+        for balance in balances {
+            balance.fiat_value = prices
+                .value(&balance.coin_type, balance.total_balance as i128)
+                .await;
+        }
     }
 }
 
 #[async_trait]
-impl CoinReadApiServer for CoinReadApi {
+impl<R: IndexerReadStore> CoinReadApiServer for CoinReadApi<R> {
     async fn get_coins(
         &self,
         owner: SuiAddress,
@@ -49,7 +73,7 @@ impl CoinReadApiServer for CoinReadApi {
         };
         let mut results = self
             .inner
-            .get_owned_coins_in_blocking_task(owner, Some(coin_type), cursor, limit + 1)
+            .get_owned_coins(owner, Some(coin_type), cursor, limit + 1)
             .await?;
 
         let has_next_page = results.len() > limit;
@@ -59,6 +83,7 @@ impl CoinReadApiServer for CoinReadApi {
             data: results,
             next_cursor,
             has_next_page,
+            truncated: false,
         })
     }
 
@@ -80,7 +105,7 @@ impl CoinReadApiServer for CoinReadApi {
         };
         let mut results = self
             .inner
-            .get_owned_coins_in_blocking_task(owner, None, cursor, limit + 1)
+            .get_owned_coins(owner, None, cursor, limit + 1)
             .await?;
 
         let has_next_page = results.len() > limit;
@@ -90,6 +115,7 @@ impl CoinReadApiServer for CoinReadApi {
             data: results,
             next_cursor,
             has_next_page,
+            truncated: false,
         })
     }
 
@@ -104,25 +130,47 @@ impl CoinReadApiServer for CoinReadApi {
 
         let mut results = self
             .inner
-            .get_coin_balances_in_blocking_task(owner, Some(coin_type.clone()))
+            .get_coin_balances(owner, Some(coin_type.clone()))
             .await?;
-        if results.is_empty() {
-            return Ok(Balance::zero(coin_type));
-        }
-        Ok(results.swap_remove(0))
+        let mut balance = if results.is_empty() {
+            Balance::zero(coin_type)
+        } else {
+            results.swap_remove(0)
+        };
+        self.value_balances(std::slice::from_mut(&mut balance))
+            .await;
+        Ok(balance)
     }
 
     async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
+        let mut balances = self.inner.get_coin_balances(owner, None).await?;
+        self.value_balances(&mut balances).await;
+        Ok(balances)
+    }
+
+    async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<Option<SuiCoinMetadata>> {
+        let coin_struct = parse_to_struct_tag(&coin_type)?;
         self.inner
-            .get_coin_balances_in_blocking_task(owner, None)
+            .get_coin_metadata(coin_struct)
             .await
             .map_err(Into::into)
     }
 
-    async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<Option<SuiCoinMetadata>> {
-        let coin_struct = parse_to_struct_tag(&coin_type)?;
+    async fn multi_get_coin_metadata(
+        &self,
+        coin_types: Vec<String>,
+    ) -> RpcResult<Vec<Option<SuiCoinMetadata>>> {
+        if coin_types.len() > *QUERY_MAX_RESULT_LIMIT {
+            return Err(
+                SuiRpcInputError::SizeLimitExceeded(QUERY_MAX_RESULT_LIMIT.to_string()).into(),
+            );
+        }
+        let coin_structs = coin_types
+            .iter()
+            .map(|coin_type| parse_to_struct_tag(coin_type))
+            .collect::<Result<Vec<_>, _>>()?;
         self.inner
-            .get_coin_metadata_in_blocking_task(coin_struct)
+            .multi_get_coin_metadata(coin_structs)
             .await
             .map_err(Into::into)
     }
@@ -135,14 +183,14 @@ impl CoinReadApiServer for CoinReadApi {
             })
         } else {
             self.inner
-                .get_total_supply_in_blocking_task(coin_struct)
+                .get_total_supply(coin_struct)
                 .await
                 .map_err(Into::into)
         }
     }
 }
 
-impl SuiRpcModule for CoinReadApi {
+impl<R: IndexerReadStore> SuiRpcModule for CoinReadApi<R> {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
     }
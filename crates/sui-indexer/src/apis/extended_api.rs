@@ -1,29 +1,53 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::indexer_reader::IndexerReader;
+use crate::apis::read_api::object_response;
+use crate::errors::IndexerError;
+use crate::models::address_daily_stats::MS_PER_DAY;
+use crate::store::IndexerReadStore;
 use jsonrpsee::{core::RpcResult, RpcModule};
+use move_core_types::annotated_value::MoveStructLayout;
+use sui_json_rpc::coin_api::parse_to_type_tag;
+use sui_json_rpc::error::SuiRpcInputError;
 use sui_json_rpc::SuiRpcModule;
-use sui_json_rpc_api::{validate_limit, ExtendedApiServer, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS};
+use sui_json_rpc_api::{
+    max_page_data_size, validate_limit, ExtendedApiServer, QUERY_MAX_RESULT_LIMIT,
+    QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
+};
 use sui_json_rpc_types::{
-    AddressMetrics, CheckpointedObjectID, EpochInfo, EpochMetrics, EpochMetricsPage, EpochPage,
-    MoveCallMetrics, NetworkMetrics, Page, QueryObjectsPage, SuiObjectResponseQuery,
+    AccountActivity, AccountRanking, AccountStatement, AccountStatementCursor, ActiveAddressCounts,
+    AddressMetrics, BalanceHistoryInterval, BalanceSnapshot, Checkpoint, CheckpointedObjectID,
+    DisplayFieldsResponse, EpochInfo, EpochMetrics, EpochMetricsPage, EpochPage,
+    EquivocationReport, EventPage, EventSchemaCursor, EventSchemaPage, GasPriceStatsPage,
+    MoveCallMetrics, NetworkMetrics, Nft, NftPage, NftTransferHistory, ObjectChange,
+    ObjectLifecycle, ObjectTransactionPage, PackageMetrics, Page, QueryObjectsPage,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, TimestampDirection,
+    TransactionInputObject, TransactionObject, ValidatorApyHistory, ValidatorEpochHistory,
 };
 use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::coin::Coin;
+use sui_types::digests::TransactionDigest;
+use sui_types::error::SuiObjectResponseError;
+use sui_types::event::EventID;
+use sui_types::object::{Object, ObjectRead};
+use sui_types::parse_sui_struct_tag;
 use sui_types::sui_serde::BigInt;
+use sui_types::TypeTag;
 
-pub(crate) struct ExtendedApi {
-    inner: IndexerReader,
+#[derive(Clone)]
+pub(crate) struct ExtendedApi<R> {
+    inner: R,
 }
 
-impl ExtendedApi {
-    pub fn new(inner: IndexerReader) -> Self {
+impl<R: IndexerReadStore> ExtendedApi<R> {
+    pub fn new(inner: R) -> Self {
         Self { inner }
     }
 }
 
 #[async_trait::async_trait]
-impl ExtendedApiServer for ExtendedApi {
+impl<R: IndexerReadStore> ExtendedApiServer for ExtendedApi<R> {
     async fn get_epochs(
         &self,
         cursor: Option<BigInt<u64>>,
@@ -33,13 +57,11 @@ impl ExtendedApiServer for ExtendedApi {
         let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)?;
         let mut epochs = self
             .inner
-            .spawn_blocking(move |this| {
-                this.get_epochs(
-                    cursor.map(|x| *x),
-                    limit + 1,
-                    descending_order.unwrap_or(false),
-                )
-            })
+            .get_epochs(
+                cursor.map(|x| *x),
+                limit + 1,
+                descending_order.unwrap_or(false),
+            )
             .await?;
 
         let has_next_page = epochs.len() > limit;
@@ -49,6 +71,7 @@ impl ExtendedApiServer for ExtendedApi {
             data: epochs,
             next_cursor: next_cursor.map(|id| id.into()),
             has_next_page,
+            truncated: false,
         })
     }
 
@@ -61,13 +84,11 @@ impl ExtendedApiServer for ExtendedApi {
         let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)?;
         let epochs = self
             .inner
-            .spawn_blocking(move |this| {
-                this.get_epochs(
-                    cursor.map(|x| *x),
-                    limit + 1,
-                    descending_order.unwrap_or(false),
-                )
-            })
+            .get_epochs(
+                cursor.map(|x| *x),
+                limit + 1,
+                descending_order.unwrap_or(false),
+            )
             .await?;
 
         let mut epoch_metrics = epochs
@@ -88,15 +109,15 @@ impl ExtendedApiServer for ExtendedApi {
             data: epoch_metrics,
             next_cursor: next_cursor.map(|id| id.into()),
             has_next_page,
+            truncated: false,
         })
     }
 
     async fn get_current_epoch(&self) -> RpcResult<EpochInfo> {
-        let stored_epoch = self
-            .inner
-            .spawn_blocking(|this| this.get_latest_epoch_info_from_db())
-            .await?;
-        EpochInfo::try_from(stored_epoch).map_err(Into::into)
+        self.inner
+            .get_epoch_info(None)
+            .await?
+            .ok_or_else(|| IndexerError::NotFound("No epoch has been indexed".to_string()).into())
     }
 
     async fn query_objects(
@@ -112,33 +133,89 @@ impl ExtendedApiServer for ExtendedApi {
     }
 
     async fn get_network_metrics(&self) -> RpcResult<NetworkMetrics> {
-        let network_metrics = self
-            .inner
-            .spawn_blocking(|this| this.get_latest_network_metrics())
-            .await?;
+        let network_metrics = self.inner.get_latest_network_metrics().await?;
         Ok(network_metrics)
     }
 
-    async fn get_move_call_metrics(&self) -> RpcResult<MoveCallMetrics> {
-        let move_call_metrics = self
+    async fn get_gas_price_history(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<GasPriceStatsPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)?;
+        let mut stats = self
             .inner
-            .spawn_blocking(|this| this.get_latest_move_call_metrics())
+            .get_gas_price_history(
+                cursor.map(|x| *x),
+                limit + 1,
+                descending_order.unwrap_or(false),
+            )
             .await?;
+
+        let has_next_page = stats.len() > limit;
+        stats.truncate(limit);
+        let next_cursor = stats.last().map(|s| s.first_checkpoint.into());
+        Ok(Page {
+            data: stats,
+            next_cursor,
+            has_next_page,
+            truncated: false,
+        })
+    }
+
+    async fn get_validator_apy_history(
+        &self,
+        start_epoch: BigInt<u64>,
+        end_epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<ValidatorApyHistory>> {
+        let (start_epoch, end_epoch) = epoch_range(start_epoch, end_epoch)?;
+        Ok(self
+            .inner
+            .get_validator_apy_history(start_epoch, end_epoch)
+            .await?)
+    }
+
+    async fn get_validator_history(
+        &self,
+        address: SuiAddress,
+        start_epoch: BigInt<u64>,
+        end_epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<ValidatorEpochHistory>> {
+        let (start_epoch, end_epoch) = epoch_range(start_epoch, end_epoch)?;
+        Ok(self
+            .inner
+            .get_validator_history(address, start_epoch, end_epoch)
+            .await?)
+    }
+
+    async fn get_move_call_metrics(&self) -> RpcResult<MoveCallMetrics> {
+        let move_call_metrics = self.inner.get_latest_move_call_metrics().await?;
         Ok(move_call_metrics)
     }
 
-    async fn get_latest_address_metrics(&self) -> RpcResult<AddressMetrics> {
-        let latest_address_metrics = self
+    async fn get_package_metrics(
+        &self,
+        package_id: ObjectID,
+        start_epoch: BigInt<u64>,
+        end_epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<PackageMetrics> {
+        let (start_epoch, end_epoch) = epoch_range(start_epoch, end_epoch)?;
+        Ok(self
             .inner
-            .spawn_blocking(|this| this.get_latest_address_metrics())
-            .await?;
+            .get_package_metrics(package_id, start_epoch, end_epoch)
+            .await?)
+    }
+
+    async fn get_latest_address_metrics(&self) -> RpcResult<AddressMetrics> {
+        let latest_address_metrics = self.inner.get_latest_address_metrics().await?;
         Ok(latest_address_metrics)
     }
 
     async fn get_checkpoint_address_metrics(&self, checkpoint: u64) -> RpcResult<AddressMetrics> {
         let checkpoint_address_metrics = self
             .inner
-            .spawn_blocking(move |this| this.get_checkpoint_address_metrics(checkpoint))
+            .get_checkpoint_address_metrics(checkpoint)
             .await?;
         Ok(checkpoint_address_metrics)
     }
@@ -149,21 +226,385 @@ impl ExtendedApiServer for ExtendedApi {
     ) -> RpcResult<Vec<AddressMetrics>> {
         let all_epoch_address_metrics = self
             .inner
-            .spawn_blocking(move |this| this.get_all_epoch_address_metrics(descending_order))
+            .get_all_epoch_address_metrics(descending_order)
             .await?;
         Ok(all_epoch_address_metrics)
     }
 
+    async fn get_top_accounts(
+        &self,
+        rank_by: AccountRanking,
+        days: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<AccountActivity>> {
+        let days = days.unwrap_or(1);
+        if days == 0 {
+            return Err(SuiRpcInputError::GenericInvalid("days must be positive".into()).into());
+        }
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+
+        Ok(self.inner.get_top_accounts(rank_by, days, limit).await?)
+    }
+
+    async fn get_active_address_counts(
+        &self,
+        start_ms: Option<BigInt<u64>>,
+        end_ms: Option<BigInt<u64>>,
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<ActiveAddressCounts>> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        let day = |ms: BigInt<u64>| (*ms / MS_PER_DAY as u64) as i64;
+        let start_day = start_ms.map_or(0, day);
+        let end_day = end_ms.map_or(i64::MAX, day);
+
+        Ok(self
+            .inner
+            .get_active_address_counts(start_day, end_day, limit)
+            .await?)
+    }
+
     async fn get_total_transactions(&self) -> RpcResult<BigInt<u64>> {
-        let latest_checkpoint = self
+        let latest_checkpoint = self.inner.get_latest_checkpoint().await?;
+        Ok(latest_checkpoint.network_total_transactions.into())
+    }
+
+    async fn get_checkpoint_by_timestamp(
+        &self,
+        timestamp_ms: BigInt<u64>,
+        direction: Option<TimestampDirection>,
+    ) -> RpcResult<Option<Checkpoint>> {
+        let direction = direction.unwrap_or_default();
+        Ok(self
+            .inner
+            .get_checkpoint_by_timestamp(*timestamp_ms, direction)
+            .await?)
+    }
+
+    async fn get_event_schemas(
+        &self,
+        event_type: Option<String>,
+        cursor: Option<EventSchemaCursor>,
+        limit: Option<usize>,
+    ) -> RpcResult<EventSchemaPage> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        let event_type = event_type
+            .map(|t| parse_sui_struct_tag(&t))
+            .transpose()
+            .map_err(|e| SuiRpcInputError::GenericInvalid(e.to_string()))?;
+
+        let mut schemas = self
             .inner
-            .spawn_blocking(|this| this.get_latest_checkpoint())
+            .get_event_schemas(event_type, cursor, limit + 1)
             .await?;
-        Ok(latest_checkpoint.network_total_transactions.into())
+
+        let has_next_page = schemas.len() > limit;
+        schemas.truncate(limit);
+        let next_cursor = schemas.last().map(|s| EventSchemaCursor {
+            event_type: s.event_type.clone(),
+            package_id: s.package_id,
+        });
+        Ok(Page {
+            data: schemas,
+            next_cursor,
+            has_next_page,
+            truncated: false,
+        })
+    }
+
+    async fn multi_get_objects_at_checkpoint(
+        &self,
+        object_ids: Vec<ObjectID>,
+        checkpoint: Option<BigInt<u64>>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiObjectResponse>> {
+        if object_ids.len() > *QUERY_MAX_RESULT_LIMIT {
+            return Err(
+                SuiRpcInputError::SizeLimitExceeded(QUERY_MAX_RESULT_LIMIT.to_string()).into(),
+            );
+        }
+
+        let options = options.unwrap_or_default();
+        let checkpoint = match checkpoint {
+            Some(checkpoint) => *checkpoint,
+            None => self.inner.get_latest_checkpoint().await?.sequence_number,
+        };
+        let object_reads = self
+            .inner
+            .multi_get_objects_at_checkpoint(object_ids, checkpoint)
+            .await?;
+
+        let futures = object_reads
+            .into_iter()
+            .map(|object_read| object_response(&self.inner, object_read, options.clone()));
+        futures::future::join_all(futures)
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    async fn get_events_by_package_version(
+        &self,
+        event_type: String,
+        package_id: ObjectID,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventPage> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        let event_type = parse_sui_struct_tag(&event_type)
+            .map_err(|e| SuiRpcInputError::GenericInvalid(e.to_string()))?
+            .to_canonical_string(/* with_prefix */ true);
+        let type_for_error = event_type.clone();
+        let mut data = self
+            .inner
+            .get_events_by_package_version(
+                event_type,
+                package_id,
+                cursor,
+                limit + 1,
+                descending_order.unwrap_or(false),
+            )
+            .await?
+            .ok_or_else(|| {
+                SuiRpcInputError::GenericNotFound(format!(
+                    "No layout indexed for event type {type_for_error} emitted by package \
+                     {package_id}"
+                ))
+            })?;
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+        let next_cursor = data.last().map(|e| e.id);
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+            truncated: false,
+        })
+    }
+
+    async fn get_events_by_address(
+        &self,
+        address: SuiAddress,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventPage> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        let mut data = self
+            .inner
+            .get_events_by_address(
+                address,
+                cursor,
+                limit + 1,
+                descending_order.unwrap_or(false),
+            )
+            .await?;
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+        let next_cursor = data.last().map(|e| e.id);
+        let mut page = Page {
+            data,
+            next_cursor,
+            has_next_page,
+            truncated: false,
+        };
+        page.truncate_to_size(max_page_data_size(), |e| e.id);
+        Ok(page)
+    }
+
+    async fn get_object_lifecycle(&self, object_id: ObjectID) -> RpcResult<ObjectLifecycle> {
+        self.inner
+            .get_object_lifecycle(object_id)
+            .await?
+            .ok_or_else(|| {
+                SuiRpcInputError::GenericNotFound(format!(
+                    "No transitions indexed for object {object_id}"
+                ))
+                .into()
+            })
+    }
+
+    async fn get_object_transaction_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectTransactionPage> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        let mut data = self
+            .inner
+            .get_object_transaction_history(object_id, cursor, limit + 1)
+            .await?;
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+        let next_cursor = data.last().map(|tx| tx.digest);
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+            truncated: false,
+        })
+    }
+
+    async fn get_transaction_input_objects(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<Vec<TransactionInputObject>> {
+        self.inner
+            .get_transaction_input_objects(digest)
+            .await?
+            .ok_or_else(|| {
+                SuiRpcInputError::GenericNotFound(format!("Transaction {digest} not found")).into()
+            })
+    }
+
+    async fn get_transaction_objects(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<Vec<TransactionObject>> {
+        let changes = self
+            .inner
+            .get_transaction_object_changes(digest)
+            .await?
+            .ok_or_else(|| {
+                SuiRpcInputError::GenericNotFound(format!("Transaction {digest} not found"))
+            })?;
+
+        let futures = changes
+            .into_iter()
+            .map(|(change, object_read)| transaction_object(&self.inner, change, object_read));
+        futures::future::try_join_all(futures).await
+    }
+
+    async fn get_equivocation_reports(
+        &self,
+        senders: Vec<SuiAddress>,
+    ) -> RpcResult<Vec<EquivocationReport>> {
+        if senders.len() > *QUERY_MAX_RESULT_LIMIT {
+            return Err(
+                SuiRpcInputError::SizeLimitExceeded(QUERY_MAX_RESULT_LIMIT.to_string()).into(),
+            );
+        }
+
+        Ok(self.inner.get_equivocation_reports(senders).await?)
+    }
+
+    async fn get_account_statement(
+        &self,
+        address: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<AccountStatementCursor>,
+        limit: Option<usize>,
+    ) -> RpcResult<AccountStatement> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        let coin_type = coin_type
+            .map(|t| parse_to_type_tag(Some(t)))
+            .transpose()?
+            .map(|t| t.to_canonical_string(/* with_prefix */ true));
+
+        Ok(self
+            .inner
+            .get_account_statement(address, coin_type, cursor, limit)
+            .await?)
+    }
+
+    async fn get_balance_history(
+        &self,
+        address: SuiAddress,
+        coin_type: String,
+        interval: BalanceHistoryInterval,
+        start_ms: Option<BigInt<u64>>,
+        end_ms: Option<BigInt<u64>>,
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<BalanceSnapshot>> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        let coin_type =
+            parse_to_type_tag(Some(coin_type))?.to_canonical_string(/* with_prefix */ true);
+        let interval_ms = interval.as_millis() as i64;
+        let ms = |ms: BigInt<u64>| (*ms).min(i64::MAX as u64) as i64;
+
+        Ok(self
+            .inner
+            .get_balance_history(
+                address,
+                coin_type,
+                interval_ms,
+                start_ms.map(ms),
+                end_ms.map(ms),
+                limit,
+            )
+            .await?)
+    }
+
+    async fn get_nfts_by_owner(
+        &self,
+        address: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<NftPage> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        let mut object_reads = self
+            .inner
+            .get_owned_nfts(address, cursor, limit + 1)
+            .await?;
+        let has_next_page = object_reads.len() > limit;
+        object_reads.truncate(limit);
+
+        let futures = object_reads
+            .into_iter()
+            .map(|object_read| nft(&self.inner, object_read));
+        let data: Vec<_> = futures::future::join_all(futures)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+        let next_cursor = data.last().map(|nft| nft.object_id);
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+            truncated: false,
+        })
+    }
+
+    async fn get_nft_transfer_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+    ) -> RpcResult<NftTransferHistory> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        let (object_type, object_read, mut data) = self
+            .inner
+            .get_nft_transfers(object_id, cursor.map(|c| *c), limit + 1)
+            .await?
+            .ok_or_else(|| {
+                SuiRpcInputError::GenericNotFound(format!(
+                    "No transfers indexed for object {object_id}"
+                ))
+            })?;
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+
+        let display = match object_read {
+            Some(ObjectRead::Exists(_, object, layout)) => {
+                display_fields(&self.inner, &object, &layout).await
+            }
+            _ => None,
+        };
+        let next_cursor = data.last().map(|transfer| transfer.version.value().into());
+        Ok(NftTransferHistory {
+            object_id,
+            object_type,
+            display,
+            data,
+            next_cursor,
+            has_next_page,
+        })
     }
 }
 
-impl SuiRpcModule for ExtendedApi {
+impl<R: IndexerReadStore> SuiRpcModule for ExtendedApi<R> {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
     }
@@ -172,3 +613,110 @@ impl SuiRpcModule for ExtendedApi {
         sui_json_rpc_api::ExtendedApiOpenRpc::module_doc()
     }
 }
+
+/// A change that a transaction made to an object, with the Display fields of the object as the
+/// transaction left it, if it is in `object_read`, and the metadata of coins.
+async fn transaction_object<R: IndexerReadStore>(
+    reader: &R,
+    change: ObjectChange,
+    object_read: Option<ObjectRead>,
+) -> RpcResult<TransactionObject> {
+    let display = match object_read {
+        Some(ObjectRead::Exists(_, object, layout)) => {
+            display_fields(reader, &object, &layout).await
+        }
+        _ => None,
+    };
+
+    let object_type = match &change {
+        ObjectChange::Published { .. } => None,
+        ObjectChange::Transferred { object_type, .. }
+        | ObjectChange::Mutated { object_type, .. }
+        | ObjectChange::Deleted { object_type, .. }
+        | ObjectChange::Wrapped { object_type, .. }
+        | ObjectChange::Created { object_type, .. } => Some(object_type),
+    };
+    let coin_type = match object_type {
+        Some(object_type) if Coin::is_coin(object_type) => match object_type.type_params.first() {
+            Some(TypeTag::Struct(coin_type)) => Some(*coin_type.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let metadata = match coin_type {
+        Some(coin_type) => reader.get_coin_metadata(coin_type).await?,
+        None => None,
+    };
+
+    Ok(TransactionObject {
+        change,
+        display,
+        coin_symbol: metadata.as_ref().map(|m| m.symbol.clone()),
+        coin_decimals: metadata.map(|m| m.decimals),
+    })
+}
+
+/// An object whose type has a Display, with its Display fields, if it is in `object_read`.
+async fn nft<R: IndexerReadStore>(reader: &R, object_read: ObjectRead) -> Option<Nft> {
+    let ObjectRead::Exists((object_id, version, digest), object, layout) = object_read else {
+        return None;
+    };
+    let object_type = object.type_()?.to_canonical_string(/* with_prefix */ true);
+    let display = display_fields(reader, &object, &layout)
+        .await
+        .unwrap_or(DisplayFieldsResponse {
+            data: None,
+            error: None,
+        });
+    Some(Nft {
+        object_id,
+        version,
+        digest,
+        object_type,
+        display,
+    })
+}
+
+/// The Display fields of `object`, or the error rendering them. `None` if its type has no
+/// Display.
+async fn display_fields<R: IndexerReadStore>(
+    reader: &R,
+    object: &Object,
+    layout: &Option<MoveStructLayout>,
+) -> Option<DisplayFieldsResponse> {
+    match reader.get_display_fields(object, layout).await {
+        Ok(DisplayFieldsResponse {
+            data: None,
+            error: None,
+        }) => None,
+        Ok(rendered_fields) => Some(rendered_fields),
+        Err(e) => Some(DisplayFieldsResponse {
+            data: None,
+            error: Some(SuiObjectResponseError::DisplayError {
+                error: e.to_string(),
+            }),
+        }),
+    }
+}
+
+/// The epochs from `start_epoch` to `end_epoch` (inclusive), which defaults to the end of the
+/// longest range allowed.
+fn epoch_range(start_epoch: BigInt<u64>, end_epoch: Option<BigInt<u64>>) -> RpcResult<(u64, u64)> {
+    let max_epochs = QUERY_MAX_RESULT_LIMIT_CHECKPOINTS as u64;
+    let start_epoch = *start_epoch;
+    let end_epoch = end_epoch.map_or(start_epoch.saturating_add(max_epochs - 1), |e| *e);
+    if end_epoch < start_epoch {
+        return Err(SuiRpcInputError::GenericInvalid(
+            "end_epoch must not be before start_epoch".into(),
+        )
+        .into());
+    }
+    if end_epoch - start_epoch >= max_epochs {
+        return Err(SuiRpcInputError::GenericInvalid(format!(
+            "Epoch ranges can span at most {max_epochs} epochs"
+        ))
+        .into());
+    }
+
+    Ok((start_epoch, end_epoch))
+}
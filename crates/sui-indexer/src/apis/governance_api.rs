@@ -2,34 +2,92 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::{errors::IndexerError, indexer_reader::IndexerReader};
+use crate::{errors::IndexerError, prices::PriceOracle, store::IndexerReadStore};
 use async_trait::async_trait;
-use jsonrpsee::{core::RpcResult, RpcModule};
+use futures::future::BoxFuture;
+use jsonrpsee::types::SubscriptionResult;
+use jsonrpsee::{core::RpcResult, RpcModule, SubscriptionSink};
+use mysten_metrics::spawn_monitored_task;
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
 
 use cached::{proc_macro::cached, SizedCache};
-use sui_json_rpc::{governance_api::ValidatorExchangeRates, SuiRpcModule};
-use sui_json_rpc_api::GovernanceReadApiServer;
+use sui_json_rpc::governance_api::{
+    estimate_stake_reward, estimated_reward, validator_set_changes, ValidatorExchangeRates,
+};
+use sui_json_rpc::indexer_api::{spawn_subscription, DEFAULT_MAX_SUBSCRIPTIONS};
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_api::{validate_limit, GovernanceReadApiServer, QUERY_MAX_RESULT_LIMIT};
 use sui_json_rpc_types::{
-    DelegatedStake, EpochInfo, StakeStatus, SuiCommittee, SuiObjectDataFilter, ValidatorApys,
+    DelegatedStake, DelegatedStakePage, DelegatedStakeResponse, EpochInfo, Page,
+    StakeRewardEstimate, StakeStatus, SuiCommittee, SuiObjectDataFilter, ValidatorApys,
 };
 use sui_open_rpc::Module;
 use sui_types::{
     base_types::{MoveObjectType, ObjectID, SuiAddress},
     committee::EpochId,
+    dynamic_field::Field,
     governance::StakedSui,
+    messages_checkpoint::CheckpointSequenceNumber,
+    object::ObjectRead,
     sui_serde::BigInt,
     sui_system_state::{sui_system_state_summary::SuiSystemStateSummary, PoolTokenExchangeRate},
 };
 
+/// How often the cache warming task checks for a new epoch.
+const CACHE_WARMING_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Clone)]
-pub struct GovernanceReadApi {
-    inner: IndexerReader,
+pub struct GovernanceReadApi<R> {
+    inner: R,
+    /// What to value stakes with, if anything.
+    prices: Option<PriceOracle>,
+    subscription_semaphore: Arc<Semaphore>,
 }
 
-impl GovernanceReadApi {
-    pub fn new(inner: IndexerReader) -> Self {
-        Self { inner }
+impl<R: IndexerReadStore> GovernanceReadApi<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            prices: None,
+            subscription_semaphore: Arc::new(Semaphore::new(DEFAULT_MAX_SUBSCRIPTIONS)),
+        }
+    }
+
+    pub fn with_prices(mut self, prices: Option<PriceOracle>) -> Self {
+        self.prices = prices;
+        self
+    }
+
+    /// Keep the cached exchange rates and APYs of the current epoch warm in the background, so
+    /// that it's this task rather than the first request of an epoch that waits for them to be
+    /// recomputed.
+    pub fn start_cache_warming(&self) {
+        let api = self.clone();
+        spawn_monitored_task!(async move {
+            let mut warm_epoch = None;
+            let mut interval = tokio::time::interval(CACHE_WARMING_INTERVAL);
+            loop {
+                interval.tick().await;
+                let start = Instant::now();
+                match api.get_validators_apy().await {
+                    Ok(apys) if warm_epoch != Some(apys.epoch) => {
+                        warm_epoch = Some(apys.epoch);
+                        validators_apys_map(apys);
+                        info!(
+                            elapsed = start.elapsed().as_secs_f64(),
+                            "Warmed exchange rates and APYs of epoch {}",
+                            warm_epoch.unwrap()
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to warm exchange rates and APYs: {e}"),
+                }
+            }
+        });
     }
 
     /// Get a validator's APY by its address
@@ -58,11 +116,7 @@ impl GovernanceReadApi {
     }
 
     pub async fn get_epoch_info(&self, epoch: Option<EpochId>) -> Result<EpochInfo, IndexerError> {
-        match self
-            .inner
-            .spawn_blocking(move |this| this.get_epoch_info(epoch))
-            .await
-        {
+        match self.inner.get_epoch_info(epoch).await {
             Ok(Some(epoch_info)) => Ok(epoch_info),
             Ok(None) => Err(IndexerError::InvalidArgumentError(format!(
                 "Missing epoch {epoch:?}"
@@ -72,54 +126,194 @@ impl GovernanceReadApi {
     }
 
     async fn get_latest_sui_system_state(&self) -> Result<SuiSystemStateSummary, IndexerError> {
-        self.inner
-            .spawn_blocking(|this| this.get_latest_sui_system_state())
-            .await
+        self.inner.get_latest_sui_system_state().await
+    }
+
+    /// The system state as of the end of `at_checkpoint` if it is set, or the latest one.
+    async fn get_sui_system_state(
+        &self,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> Result<SuiSystemStateSummary, IndexerError> {
+        match at_checkpoint {
+            Some(checkpoint) => {
+                self.inner
+                    .get_sui_system_state_at_checkpoint(checkpoint)
+                    .await
+            }
+            None => self.get_latest_sui_system_state().await,
+        }
     }
 
     async fn get_stakes_by_ids(
         &self,
         ids: Vec<ObjectID>,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> Result<Vec<DelegatedStake>, IndexerError> {
+        let objects = match at_checkpoint {
+            Some(checkpoint) => self
+                .inner
+                .multi_get_objects_at_checkpoint(ids, checkpoint)
+                .await?
+                .into_iter()
+                .filter_map(|object| match object {
+                    ObjectRead::Exists(_, object, _) => Some(object),
+                    _ => None,
+                })
+                .collect(),
+            None => self.inner.multi_get_objects(ids).await?,
+        };
+
         let mut stakes = vec![];
-        for stored_object in self.inner.multi_get_objects_in_blocking_task(ids).await? {
-            let object = sui_types::object::Object::try_from(stored_object)?;
+        for object in objects {
             let stake_object = StakedSui::try_from(&object)?;
             stakes.push(stake_object);
         }
 
-        self.get_delegated_stakes(stakes).await
+        self.get_delegated_stakes(stakes, at_checkpoint).await
+    }
+
+    async fn try_get_stakes_by_ids(
+        &self,
+        ids: Vec<ObjectID>,
+    ) -> Result<Vec<DelegatedStakeResponse>, IndexerError> {
+        let mut stakes = vec![];
+        for object in self.inner.multi_get_objects(ids.clone()).await? {
+            // Objects that aren't StakedSui are left out, to be reported as not found.
+            if let Ok(stake_object) = StakedSui::try_from(&object) {
+                stakes.push(stake_object);
+            }
+        }
+
+        let stakes = if stakes.is_empty() {
+            vec![]
+        } else {
+            self.get_delegated_stakes(stakes, None).await?
+        };
+        // Only live objects are indexed, so stakes that have been withdrawn can't be told apart
+        // from IDs that were never stakes.
+        Ok(DelegatedStakeResponse::for_ids(
+            &ids,
+            stakes,
+            DelegatedStakeResponse::NotFound,
+        ))
+    }
+
+    async fn get_stake_reward_estimate(
+        &self,
+        staked_sui_id: ObjectID,
+        target_epoch: EpochId,
+    ) -> Result<StakeRewardEstimate, IndexerError> {
+        let Some(object) = self
+            .inner
+            .multi_get_objects(vec![staked_sui_id])
+            .await?
+            .pop()
+        else {
+            return Err(IndexerError::NotFound(format!(
+                "Cannot find StakedSui object {staked_sui_id}"
+            )));
+        };
+        let stake = StakedSui::try_from(&object)?;
+
+        let system_state_summary = self.get_latest_sui_system_state().await?;
+        let epoch = system_state_summary.epoch;
+        if target_epoch < epoch {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Target epoch {target_epoch} is before the current epoch {epoch}"
+            )));
+        }
+
+        let rates = exchange_rates(self, system_state_summary)
+            .await?
+            .into_iter()
+            .find(|rates| rates.pool_id == stake.pool_id())
+            .ok_or_else(|| {
+                IndexerError::NotFound(format!(
+                    "Cannot find rates for staking pool {}",
+                    stake.pool_id()
+                ))
+            })?;
+        let apy = validators_apys_map(self.get_validators_apy().await?)
+            .get(&rates.address)
+            .copied()
+            .unwrap_or_default();
+
+        Ok(estimate_stake_reward(
+            &stake,
+            &rates,
+            apy,
+            epoch,
+            target_epoch,
+        ))
     }
 
     async fn get_staked_by_owner(
         &self,
         owner: SuiAddress,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> Result<Vec<DelegatedStake>, IndexerError> {
-        let mut stakes = vec![];
-        for stored_object in self
+        // Allow querying for up to 1000 staked objects
+        let stakes = self
+            .get_staked_sui(owner, None, 1000, at_checkpoint)
+            .await?;
+        self.get_delegated_stakes(stakes, at_checkpoint).await
+    }
+
+    async fn get_stakes_paginated(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<DelegatedStakePage, IndexerError> {
+        let mut stakes = self.get_staked_sui(owner, cursor, limit + 1, None).await?;
+
+        // stakes here are of size (limit + 1), where the last one is the cursor for the next page
+        let has_next_page = stakes.len() > limit;
+        stakes.truncate(limit);
+        let next_cursor = stakes.last().map_or(cursor, |stake| Some(stake.id()));
+
+        Ok(Page {
+            data: self.get_delegated_stakes(stakes, None).await?,
+            next_cursor,
+            has_next_page,
+            truncated: false,
+        })
+    }
+
+    /// The StakedSui objects owned by `owner`, as of the end of `at_checkpoint` if it is set, in
+    /// order of their IDs, starting after `cursor`.
+    async fn get_staked_sui(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: usize,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> Result<Vec<StakedSui>, IndexerError> {
+        let filter = Some(SuiObjectDataFilter::StructType(
+            MoveObjectType::staked_sui().into(),
+        ));
+        let objects = self
             .inner
-            .get_owned_objects_in_blocking_task(
-                owner,
-                Some(SuiObjectDataFilter::StructType(
-                    MoveObjectType::staked_sui().into(),
-                )),
-                None,
-                // Allow querying for up to 1000 staked objects
-                1000,
-            )
-            .await?
-        {
-            let object = sui_types::object::Object::try_from(stored_object)?;
+            .get_owned_objects(owner, filter, cursor, limit, at_checkpoint)
+            .await?;
+
+        let mut stakes = vec![];
+        for object in objects {
+            let ObjectRead::Exists(_, object, _) = object else {
+                continue;
+            };
             let stake_object = StakedSui::try_from(&object)?;
             stakes.push(stake_object);
         }
-
-        self.get_delegated_stakes(stakes).await
+        Ok(stakes)
     }
 
+    /// The [DelegatedStake]s of `stakes`, valued with the system state and exchange rates as of
+    /// the end of `at_checkpoint` if it is set, or the latest ones.
     pub async fn get_delegated_stakes(
         &self,
         stakes: Vec<StakedSui>,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> Result<Vec<DelegatedStake>, IndexerError> {
         let pools = stakes
             .into_iter()
@@ -128,11 +322,14 @@ impl GovernanceReadApi {
                 pools
             });
 
-        let system_state_summary = self.get_latest_sui_system_state().await?;
+        let system_state_summary = self.get_sui_system_state(at_checkpoint).await?;
         let epoch = system_state_summary.epoch;
 
-        let rates = exchange_rates(self, system_state_summary)
-            .await?
+        let rates = match at_checkpoint {
+            Some(_) => self.get_exchange_rates_at_epoch(epoch).await?,
+            None => exchange_rates(self, system_state_summary).await?,
+        };
+        let rates = rates
             .into_iter()
             .map(|rates| (rates.pool_id, rates))
             .collect::<BTreeMap<_, _>>();
@@ -145,33 +342,25 @@ impl GovernanceReadApi {
                     "Cannot find rates for staking pool {pool_id}".to_string(),
                 )
             })?;
-            let current_rate = rate_table.rates.first().map(|(_, rate)| rate);
 
             let mut delegations = vec![];
             for stake in stakes {
                 let status = if epoch >= stake.activation_epoch() {
-                    let estimated_reward = if let Some(current_rate) = current_rate {
-                        let stake_rate = rate_table
-                            .rates
-                            .iter()
-                            .find_map(|(epoch, rate)| {
-                                if *epoch == stake.activation_epoch() {
-                                    Some(rate.clone())
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap_or_default();
-                        let estimated_reward = ((stake_rate.rate() / current_rate.rate()) - 1.0)
-                            * stake.principal() as f64;
-                        std::cmp::max(0, estimated_reward.round() as u64)
-                    } else {
-                        0
-                    };
-                    StakeStatus::Active { estimated_reward }
+                    StakeStatus::Active {
+                        estimated_reward: estimated_reward(&stake, rate_table),
+                    }
                 } else {
                     StakeStatus::Pending
                 };
+                let fiat_value = match (&self.prices, &status) {
+                    (Some(prices), StakeStatus::Active { estimated_reward }) => {
+                        prices
+                            .value_sui(stake.principal() as i128 + *estimated_reward as i128)
+                            .await
+                    }
+                    (Some(prices), _) => prices.value_sui(stake.principal() as i128).await,
+                    (None, _) => None,
+                };
                 delegations.push(sui_json_rpc_types::Stake {
                     staked_sui_id: stake.id(),
                     // TODO: this might change when we implement warm up period.
@@ -179,6 +368,7 @@ impl GovernanceReadApi {
                     stake_active_epoch: stake.activation_epoch(),
                     principal: stake.principal(),
                     status,
+                    fiat_value,
                 })
             }
             delegated_stakes.push(DelegatedStake {
@@ -189,18 +379,56 @@ impl GovernanceReadApi {
         }
         Ok(delegated_stakes)
     }
+
+    /// The exchange rates of each staking pool as of `epoch`. Rates are only added to a pool's
+    /// table at the start of each epoch, so they are the current epoch's cached rates, without the
+    /// rates of the epochs after `epoch`.
+    async fn get_exchange_rates_at_epoch(
+        &self,
+        epoch: EpochId,
+    ) -> Result<Vec<ValidatorExchangeRates>, IndexerError> {
+        let mut rates = exchange_rates(self, self.get_latest_sui_system_state().await?).await?;
+        for pool in &mut rates {
+            pool.rates.retain(|(rate_epoch, _)| *rate_epoch <= epoch);
+        }
+        Ok(rates)
+    }
 }
 
 /// Cached exchange rates for validators for the given epoch, the cache size is 1, it will be cleared when the epoch changes.
-/// rates are in descending order by epoch.
+/// rates are in descending order by epoch. Requests made while the rates of a new epoch are being
+/// fetched (e.g. by the cache warming task) wait for them rather than fetching them again.
+async fn exchange_rates<R: IndexerReadStore>(
+    state: &GovernanceReadApi<R>,
+    system_state_summary: SuiSystemStateSummary,
+) -> Result<Vec<ValidatorExchangeRates>, IndexerError> {
+    let state = state.clone();
+    cached_exchange_rates(
+        system_state_summary.epoch,
+        Box::pin(async move { fetch_exchange_rates(&state, system_state_summary).await }),
+    )
+    .await
+}
+
+/// The cache behind [exchange_rates], which only runs `fetch` on a miss. The cache is shared by
+/// every store, so it is keyed by the epoch alone.
 #[cached(
     type = "SizedCache<EpochId, Vec<ValidatorExchangeRates>>",
     create = "{ SizedCache::with_size(1) }",
-    convert = "{ system_state_summary.epoch }",
-    result = true
+    convert = "{ epoch }",
+    result = true,
+    sync_writes = true
 )]
-async fn exchange_rates(
-    state: &GovernanceReadApi,
+async fn cached_exchange_rates(
+    epoch: EpochId,
+    fetch: BoxFuture<'static, Result<Vec<ValidatorExchangeRates>, IndexerError>>,
+) -> Result<Vec<ValidatorExchangeRates>, IndexerError> {
+    info!("Fetching exchange rates of epoch {epoch}");
+    fetch.await
+}
+
+async fn fetch_exchange_rates<R: IndexerReadStore>(
+    state: &GovernanceReadApi<R>,
     system_state_summary: SuiSystemStateSummary,
 ) -> Result<Vec<ValidatorExchangeRates>, IndexerError> {
     // Get validator rate tables
@@ -219,7 +447,7 @@ async fn exchange_rates(
     // Get inactive validator rate tables
     for df in state
         .inner
-        .get_dynamic_fields_in_blocking_task(
+        .get_dynamic_fields(
             system_state_summary.inactive_pools_id,
             None,
             system_state_summary.inactive_pools_size as usize,
@@ -231,16 +459,9 @@ async fn exchange_rates(
                 error: e.to_string(),
             }
         })?;
-        let inactive_pools_id = system_state_summary.inactive_pools_id;
         let validator = state
             .inner
-            .spawn_blocking(move |this| {
-                sui_types::sui_system_state::get_validator_from_table(
-                    &this,
-                    inactive_pools_id,
-                    &pool_id,
-                )
-            })
+            .get_validator_from_table(system_state_summary.inactive_pools_id, pool_id)
             .await?;
         tables.push((
             validator.sui_address,
@@ -257,15 +478,14 @@ async fn exchange_rates(
         let mut rates = vec![];
         for df in state
             .inner
-            .get_dynamic_fields_raw_in_blocking_task(
-                exchange_rates_id,
-                None,
-                exchange_rates_size as usize,
-            )
+            .get_dynamic_field_objects(exchange_rates_id, None, exchange_rates_size as usize)
             .await?
         {
             let dynamic_field = df
-                .to_dynamic_field::<EpochId, PoolTokenExchangeRate>()
+                .data
+                .try_as_move()
+                .filter(|object| object.type_().is_dynamic_field())
+                .and_then(|object| object.to_rust::<Field<EpochId, PoolTokenExchangeRate>>())
                 .ok_or_else(|| sui_types::error::SuiError::ObjectDeserializationError {
                     error: "dynamic field malformed".to_owned(),
                 })?;
@@ -296,18 +516,56 @@ fn validators_apys_map(apys: ValidatorApys) -> BTreeMap<SuiAddress, f64> {
 }
 
 #[async_trait]
-impl GovernanceReadApiServer for GovernanceReadApi {
+impl<R: IndexerReadStore> GovernanceReadApiServer for GovernanceReadApi<R> {
     async fn get_stakes_by_ids(
         &self,
         staked_sui_ids: Vec<ObjectID>,
+        at_checkpoint: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<DelegatedStake>> {
+        self.get_stakes_by_ids(staked_sui_ids, at_checkpoint.as_deref().copied())
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn try_get_stakes_by_ids(
+        &self,
+        staked_sui_ids: Vec<ObjectID>,
+    ) -> RpcResult<Vec<DelegatedStakeResponse>> {
+        self.try_get_stakes_by_ids(staked_sui_ids)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_stakes(
+        &self,
+        owner: SuiAddress,
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<Vec<DelegatedStake>> {
-        self.get_stakes_by_ids(staked_sui_ids)
+        self.get_staked_by_owner(owner, at_checkpoint.as_deref().copied())
             .await
             .map_err(Into::into)
     }
 
-    async fn get_stakes(&self, owner: SuiAddress) -> RpcResult<Vec<DelegatedStake>> {
-        self.get_staked_by_owner(owner).await.map_err(Into::into)
+    async fn get_stakes_paginated(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<DelegatedStakePage> {
+        let limit = validate_limit(limit, *QUERY_MAX_RESULT_LIMIT)?;
+        self.get_stakes_paginated(owner, cursor, limit)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn get_stake_reward_estimate(
+        &self,
+        staked_sui_id: ObjectID,
+        target_epoch: BigInt<u64>,
+    ) -> RpcResult<StakeRewardEstimate> {
+        self.get_stake_reward_estimate(staked_sui_id, *target_epoch)
+            .await
+            .map_err(Into::into)
     }
 
     async fn get_committee_info(&self, epoch: Option<BigInt<u64>>) -> RpcResult<SuiCommittee> {
@@ -315,8 +573,13 @@ impl GovernanceReadApiServer for GovernanceReadApi {
         Ok(epoch.committee().map_err(IndexerError::from)?.into())
     }
 
-    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary> {
-        self.get_latest_sui_system_state().await.map_err(Into::into)
+    async fn get_latest_sui_system_state(
+        &self,
+        at_checkpoint: Option<BigInt<u64>>,
+    ) -> RpcResult<SuiSystemStateSummary> {
+        self.get_sui_system_state(at_checkpoint.as_deref().copied())
+            .await
+            .map_err(Into::into)
     }
 
     async fn get_reference_gas_price(&self) -> RpcResult<BigInt<u64>> {
@@ -333,9 +596,27 @@ impl GovernanceReadApiServer for GovernanceReadApi {
     async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
         Ok(self.get_validators_apy().await?)
     }
+
+    fn subscribe_validator_set_changes(&self, sink: SubscriptionSink) -> SubscriptionResult {
+        let permit = self
+            .subscription_semaphore
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| anyhow::anyhow!("Resources exhausted"))?;
+        let api = self.clone();
+        spawn_subscription(
+            sink,
+            Box::pin(validator_set_changes(move || {
+                let api = api.clone();
+                async move { Ok(api.get_latest_sui_system_state().await?) }
+            })),
+            Some(permit),
+        );
+        Ok(())
+    }
 }
 
-impl SuiRpcModule for GovernanceReadApi {
+impl<R: IndexerReadStore> SuiRpcModule for GovernanceReadApi<R> {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
     }
@@ -344,3 +625,35 @@ impl SuiRpcModule for GovernanceReadApi {
         sui_json_rpc_api::GovernanceReadApiOpenRpc::module_doc()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_exchange_rates_fetched_once_per_epoch() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let fetch = || {
+            let fetches = fetches.clone();
+            Box::pin(async move {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                Ok(vec![])
+            }) as BoxFuture<'static, _>
+        };
+
+        // A request arriving while the rates of an epoch are being fetched, e.g. by the cache
+        // warming task, waits for that fetch rather than starting its own.
+        let (warming, request) = tokio::join!(
+            cached_exchange_rates(1000, fetch()),
+            cached_exchange_rates(1000, fetch()),
+        );
+        assert!(warming.is_ok() && request.is_ok());
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // The rates are fetched again once the epoch changes.
+        cached_exchange_rates(1001, fetch()).await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,37 +1,44 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::indexer_reader::IndexerReader;
+use crate::store::IndexerReadStore;
 use crate::IndexerError;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::types::SubscriptionEmptyError;
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
+use sui_json_rpc::dynamic_field_path::{resolve_path, PathReader};
+use sui_json_rpc::dynamic_field_tree::{dynamic_field_tree, TreeReader};
+use sui_json_rpc::error::{Error, SuiRpcInputError};
 use sui_json_rpc::name_service::{Domain, NameRecord, NameServiceConfig};
 use sui_json_rpc::SuiRpcModule;
-use sui_json_rpc_api::{cap_page_limit, IndexerApiServer};
+use sui_json_rpc_api::{cap_page_limit, max_page_data_size, IndexerApiServer};
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
-    TransactionFilter,
+    DynamicFieldPage, DynamicFieldPathSegment, DynamicFieldTree, EventFilter, EventPage,
+    ObjectsPage, Page, SuiMoveStruct, SuiMoveValue, SuiObjectData, SuiObjectDataOptions,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiParsedData, SuiTransactionBlockResponseQuery,
+    TransactionBlocksPage, TransactionEffectsFilter, TransactionFilter,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
-use sui_types::dynamic_field::{DynamicFieldName, Field};
+use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, Field};
 use sui_types::error::SuiObjectResponseError;
 use sui_types::event::EventID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::ObjectRead;
+use sui_types::sui_serde::BigInt;
 use sui_types::TypeTag;
 
-pub(crate) struct IndexerApi {
-    inner: IndexerReader,
+#[derive(Clone)]
+pub(crate) struct IndexerApi<R> {
+    inner: R,
     name_service_config: NameServiceConfig,
 }
 
-impl IndexerApi {
-    pub fn new(inner: IndexerReader) -> Self {
+impl<R: IndexerReadStore> IndexerApi<R> {
+    pub fn new(inner: R) -> Self {
         Self {
             inner,
             // TODO allow configuring for other networks
@@ -45,21 +52,13 @@ impl IndexerApi {
         query: Option<SuiObjectResponseQuery>,
         cursor: Option<ObjectID>,
         limit: usize,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> RpcResult<ObjectsPage> {
         let SuiObjectResponseQuery { filter, options } = query.unwrap_or_default();
         let options = options.unwrap_or_default();
-        let objects = self
-            .inner
-            .get_owned_objects_in_blocking_task(address, filter, cursor, limit + 1)
-            .await?;
         let mut objects = self
             .inner
-            .spawn_blocking(move |this| {
-                objects
-                    .into_iter()
-                    .map(|object| object.try_into_object_read(&this))
-                    .collect::<Result<Vec<_>, _>>()
-            })
+            .get_owned_objects(address, filter, cursor, limit + 1, at_checkpoint)
             .await?;
         let has_next_page = objects.len() > limit;
         objects.truncate(limit);
@@ -104,7 +103,7 @@ impl IndexerApi {
                 }
             }));
         }
-        let data = futures::future::join_all(parallel_tasks)
+        let mut data = futures::future::join_all(parallel_tasks)
             .await
             .into_iter()
             .collect::<Result<Vec<_>, _>>()
@@ -112,29 +111,45 @@ impl IndexerApi {
             .into_iter()
             .collect::<Result<Vec<_>, anyhow::Error>>()?;
 
+        // All objects on the page are owned by `address`, so they share its label.
+        if options.show_labels {
+            let labels = self.inner.get_address_labels(vec![address]).await?;
+            for object in data.iter_mut().filter_map(|o| o.data.as_mut()) {
+                object.labels = Some(labels.clone());
+            }
+        }
+
         Ok(Page {
             data,
             next_cursor,
             has_next_page,
+            truncated: false,
         })
     }
 }
 
 #[async_trait]
-impl IndexerApiServer for IndexerApi {
+impl<R: IndexerReadStore> IndexerApiServer for IndexerApi<R> {
     async fn get_owned_objects(
         &self,
         address: SuiAddress,
         query: Option<SuiObjectResponseQuery>,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<ObjectsPage> {
         let limit = cap_page_limit(limit);
         if limit == 0 {
             return Ok(ObjectsPage::empty());
         }
-        self.get_owned_objects_internal(address, query, cursor, limit)
-            .await
+        self.get_owned_objects_internal(
+            address,
+            query,
+            cursor,
+            limit,
+            at_checkpoint.as_deref().copied(),
+        )
+        .await
     }
 
     async fn query_transaction_blocks(
@@ -143,6 +158,7 @@ impl IndexerApiServer for IndexerApi {
         cursor: Option<TransactionDigest>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<TransactionBlocksPage> {
         let limit = cap_page_limit(limit);
         if limit == 0 {
@@ -150,12 +166,13 @@ impl IndexerApiServer for IndexerApi {
         }
         let mut results = self
             .inner
-            .query_transaction_blocks_in_blocking_task(
+            .query_transaction_blocks(
                 query.filter,
                 query.options.unwrap_or_default(),
                 cursor,
                 limit + 1,
                 descending_order.unwrap_or(false),
+                at_checkpoint.as_deref().copied(),
             )
             .await
             .map_err(|e: IndexerError| anyhow::anyhow!(e))?;
@@ -163,11 +180,14 @@ impl IndexerApiServer for IndexerApi {
         let has_next_page = results.len() > limit;
         results.truncate(limit);
         let next_cursor = results.last().map(|o| o.digest);
-        Ok(Page {
+        let mut page = Page {
             data: results,
             next_cursor,
             has_next_page,
-        })
+            truncated: false,
+        };
+        page.truncate_to_size(max_page_data_size(), |tx| tx.digest);
+        Ok(page)
     }
 
     async fn query_events(
@@ -177,6 +197,7 @@ impl IndexerApiServer for IndexerApi {
         cursor: Option<EventID>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<EventPage> {
         let limit = cap_page_limit(limit);
         if limit == 0 {
@@ -185,17 +206,26 @@ impl IndexerApiServer for IndexerApi {
         let descending_order = descending_order.unwrap_or(false);
         let mut results = self
             .inner
-            .query_events_in_blocking_task(query, cursor, limit + 1, descending_order)
+            .query_events(
+                query,
+                cursor,
+                limit + 1,
+                descending_order,
+                at_checkpoint.as_deref().copied(),
+            )
             .await?;
 
         let has_next_page = results.len() > limit;
         results.truncate(limit);
         let next_cursor = results.last().map(|o| o.id);
-        Ok(Page {
+        let mut page = Page {
             data: results,
             next_cursor,
             has_next_page,
-        })
+            truncated: false,
+        };
+        page.truncate_to_size(max_page_data_size(), |e| e.id);
+        Ok(page)
     }
 
     async fn get_dynamic_fields(
@@ -210,7 +240,7 @@ impl IndexerApiServer for IndexerApi {
         }
         let mut results = self
             .inner
-            .get_dynamic_fields_in_blocking_task(parent_object_id, cursor, limit + 1)
+            .get_dynamic_fields(parent_object_id, cursor, limit + 1)
             .await?;
 
         let has_next_page = results.len() > limit;
@@ -220,6 +250,7 @@ impl IndexerApiServer for IndexerApi {
             data: results,
             next_cursor,
             has_next_page,
+            truncated: false,
         })
     }
 
@@ -228,10 +259,7 @@ impl IndexerApiServer for IndexerApi {
         parent_object_id: ObjectID,
         name: DynamicFieldName,
     ) -> RpcResult<SuiObjectResponse> {
-        let name_bcs_value = self
-            .inner
-            .bcs_name_from_dynamic_field_name_in_blocking_task(&name)
-            .await?;
+        let name_bcs_value = self.inner.bcs_name_from_dynamic_field_name(&name).await?;
 
         // Try as Dynamic Field
         let id = sui_types::dynamic_field::derive_dynamic_field_id(
@@ -242,7 +270,7 @@ impl IndexerApiServer for IndexerApi {
         .expect("deriving dynamic field id can't fail");
 
         let options = sui_json_rpc_types::SuiObjectDataOptions::full_content();
-        match self.inner.get_object_read_in_blocking_task(id).await? {
+        match self.inner.get_object_read(id).await? {
             sui_types::object::ObjectRead::NotExists(_)
             | sui_types::object::ObjectRead::Deleted(_) => {}
             sui_types::object::ObjectRead::Exists(object_ref, o, layout) => {
@@ -262,11 +290,7 @@ impl IndexerApiServer for IndexerApi {
             &name_bcs_value,
         )
         .expect("deriving dynamic field id can't fail");
-        match self
-            .inner
-            .get_object_read_in_blocking_task(dynamic_object_field_id)
-            .await?
-        {
+        match self.inner.get_object_read(dynamic_object_field_id).await? {
             sui_types::object::ObjectRead::NotExists(_)
             | sui_types::object::ObjectRead::Deleted(_) => {}
             sui_types::object::ObjectRead::Exists(object_ref, o, layout) => {
@@ -281,6 +305,23 @@ impl IndexerApiServer for IndexerApi {
         ))
     }
 
+    async fn get_dynamic_field_value_by_path(
+        &self,
+        parent_object_id: ObjectID,
+        path: Vec<DynamicFieldPathSegment>,
+    ) -> RpcResult<Option<SuiMoveValue>> {
+        Ok(resolve_path(self, parent_object_id, path).await?)
+    }
+
+    async fn get_dynamic_field_tree(
+        &self,
+        parent_object_id: ObjectID,
+        max_depth: Option<usize>,
+        limit: Option<usize>,
+    ) -> RpcResult<DynamicFieldTree> {
+        Ok(dynamic_field_tree(self, parent_object_id, max_depth, limit).await?)
+    }
+
     fn subscribe_event(&self, _sink: SubscriptionSink, _filter: EventFilter) -> SubscriptionResult {
         Err(SubscriptionEmptyError)
     }
@@ -293,6 +334,15 @@ impl IndexerApiServer for IndexerApi {
         Err(SubscriptionEmptyError)
     }
 
+    fn subscribe_transaction_effects(
+        &self,
+        _sink: SubscriptionSink,
+        _filter: TransactionEffectsFilter,
+        _cursor: Option<BigInt<u64>>,
+    ) -> SubscriptionResult {
+        Err(SubscriptionEmptyError)
+    }
+
     async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
         // TODO(manos): Implement new logic.
         let domain = name
@@ -301,7 +351,7 @@ impl IndexerApiServer for IndexerApi {
 
         let record_id = self.name_service_config.record_field_id(&domain);
 
-        let field_record_object = match self.inner.get_object_in_blocking_task(record_id).await? {
+        let field_record_object = match self.inner.get_object(record_id).await? {
             Some(o) => o,
             None => return Ok(None),
         };
@@ -322,17 +372,14 @@ impl IndexerApiServer for IndexerApi {
             .name_service_config
             .reverse_record_field_id(address.as_ref());
 
-        let field_reverse_record_object = match self
-            .inner
-            .get_object_in_blocking_task(reverse_record_id)
-            .await?
-        {
+        let field_reverse_record_object = match self.inner.get_object(reverse_record_id).await? {
             Some(o) => o,
             None => {
                 return Ok(Page {
                     data: vec![],
                     next_cursor: None,
                     has_next_page: false,
+                    truncated: false,
                 })
             }
         };
@@ -350,11 +397,55 @@ impl IndexerApiServer for IndexerApi {
             data: vec![domain.to_string()],
             next_cursor: None,
             has_next_page: false,
+            truncated: false,
+        })
+    }
+}
+
+#[async_trait]
+impl<R: IndexerReadStore> PathReader for IndexerApi<R> {
+    async fn object_fields(&self, id: ObjectID) -> Result<Option<SuiMoveStruct>, Error> {
+        let read = self
+            .inner
+            .get_object_read(id)
+            .await
+            .map_err(|e| Error::UnexpectedError(e.to_string()))?;
+        let ObjectRead::Exists(object_ref, o, layout) = read else {
+            return Ok(None);
+        };
+        let options = SuiObjectDataOptions::new().with_content();
+        let data: SuiObjectData = (object_ref, o, layout, options, None).try_into()?;
+        Ok(match data.content {
+            Some(SuiParsedData::MoveObject(object)) => Some(object.fields),
+            _ => None,
         })
     }
+
+    async fn name_bcs(&self, name: DynamicFieldName) -> Result<(TypeTag, Vec<u8>), Error> {
+        let name_bcs_value = self
+            .inner
+            .bcs_name_from_dynamic_field_name(&name)
+            .await
+            .map_err(|e| SuiRpcInputError::GenericInvalid(e.to_string()))?;
+        Ok((name.type_, name_bcs_value))
+    }
+}
+
+#[async_trait]
+impl<R: IndexerReadStore> TreeReader for IndexerApi<R> {
+    async fn dynamic_fields(
+        &self,
+        parent: ObjectID,
+        limit: usize,
+    ) -> Result<Vec<DynamicFieldInfo>, Error> {
+        self.inner
+            .get_dynamic_fields(parent, None, limit)
+            .await
+            .map_err(|e| Error::UnexpectedError(e.to_string()))
+    }
 }
 
-impl SuiRpcModule for IndexerApi {
+impl<R: IndexerReadStore> SuiRpcModule for IndexerApi<R> {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
     }
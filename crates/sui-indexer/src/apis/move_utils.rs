@@ -20,27 +20,27 @@ use sui_open_rpc::Module;
 use sui_types::base_types::ObjectID;
 use sui_types::move_package::normalize_modules;
 
-use crate::indexer_reader::IndexerReader;
+use crate::store::IndexerReadStore;
 
-pub struct MoveUtilsApi {
-    inner: IndexerReader,
+pub struct MoveUtilsApi<R> {
+    inner: R,
 }
 
-impl MoveUtilsApi {
-    pub fn new(inner: IndexerReader) -> Self {
+impl<R: IndexerReadStore> MoveUtilsApi<R> {
+    pub fn new(inner: R) -> Self {
         Self { inner }
     }
 }
 
 #[async_trait]
-impl MoveUtilsServer for MoveUtilsApi {
+impl<R: IndexerReadStore> MoveUtilsServer for MoveUtilsApi<R> {
     async fn get_normalized_move_modules_by_package(
         &self,
         package_id: ObjectID,
     ) -> RpcResult<BTreeMap<String, SuiMoveNormalizedModule>> {
         let package = self
             .inner
-            .get_package_in_blocking_task(package_id)
+            .get_package(package_id)
             .await
             .map_err(|e| SuiRpcInputError::GenericNotFound(e.to_string()))?
             .ok_or_else(|| {
@@ -149,7 +149,7 @@ impl MoveUtilsServer for MoveUtilsApi {
     }
 }
 
-impl SuiRpcModule for MoveUtilsApi {
+impl<R: IndexerReadStore> SuiRpcModule for MoveUtilsApi<R> {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
     }
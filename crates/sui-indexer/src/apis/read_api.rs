@@ -1,21 +1,26 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 use sui_json_rpc::error::SuiRpcInputError;
+use sui_json_rpc::read_api::{validate_display_fields, verify_checkpoint_signature};
 use sui_types::error::SuiObjectResponseError;
 use sui_types::object::ObjectRead;
+use sui_types::parse_sui_struct_tag;
 
 use crate::errors::IndexerError;
-use crate::indexer_reader::IndexerReader;
+use crate::store::IndexerReadStore;
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_api::{ReadApiServer, QUERY_MAX_RESULT_LIMIT};
 use sui_json_rpc_types::{
-    Checkpoint, CheckpointId, CheckpointPage, ProtocolConfigResponse, SuiEvent,
-    SuiGetPastObjectRequest, SuiObjectDataOptions, SuiObjectResponse, SuiPastObjectResponse,
-    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    Checkpoint, CheckpointId, CheckpointPage, DisplayFieldError, ProtocolConfigResponse, SuiEvent,
+    SuiGetPastObjectRequest, SuiObjectData, SuiObjectDataOptions, SuiObjectResponse,
+    SuiPastObjectResponse, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_open_rpc::Module;
 use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
@@ -26,23 +31,19 @@ use sui_types::sui_serde::BigInt;
 use sui_json_rpc_types::SuiLoadedChildObjectsResponse;
 
 #[derive(Clone)]
-pub(crate) struct ReadApi {
-    inner: IndexerReader,
+pub(crate) struct ReadApi<R> {
+    inner: R,
 }
 
-impl ReadApi {
-    pub fn new(inner: IndexerReader) -> Self {
+impl<R: IndexerReadStore> ReadApi<R> {
+    pub fn new(inner: R) -> Self {
         Self { inner }
     }
 
     async fn get_checkpoint(&self, id: CheckpointId) -> Result<Checkpoint, IndexerError> {
-        match self
-            .inner
-            .spawn_blocking(move |this| this.get_checkpoint(id))
-            .await
-        {
+        match self.inner.get_checkpoint(id).await {
             Ok(Some(epoch_info)) => Ok(epoch_info),
-            Ok(None) => Err(IndexerError::InvalidArgumentError(format!(
+            Ok(None) => Err(IndexerError::NotFound(format!(
                 "Checkpoint {id:?} not found"
             ))),
             Err(e) => Err(e),
@@ -50,9 +51,7 @@ impl ReadApi {
     }
 
     async fn get_latest_checkpoint(&self) -> Result<Checkpoint, IndexerError> {
-        self.inner
-            .spawn_blocking(|this| this.get_latest_checkpoint())
-            .await
+        self.inner.get_latest_checkpoint().await
     }
 
     async fn get_chain_identifier(&self) -> RpcResult<ChainIdentifier> {
@@ -62,49 +61,15 @@ impl ReadApi {
 }
 
 #[async_trait]
-impl ReadApiServer for ReadApi {
+impl<R: IndexerReadStore> ReadApiServer for ReadApi<R> {
     async fn get_object(
         &self,
         object_id: ObjectID,
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<SuiObjectResponse> {
         let options = options.unwrap_or_default();
-        let object_read = self
-            .inner
-            .get_object_read_in_blocking_task(object_id)
-            .await?;
-
-        match object_read {
-            ObjectRead::NotExists(id) => Ok(SuiObjectResponse::new_with_error(
-                SuiObjectResponseError::NotExists { object_id: id },
-            )),
-            ObjectRead::Exists(object_ref, o, layout) => {
-                let mut display_fields = None;
-                if options.show_display {
-                    match self.inner.get_display_fields(&o, &layout).await {
-                        Ok(rendered_fields) => display_fields = Some(rendered_fields),
-                        Err(e) => {
-                            return Ok(SuiObjectResponse::new(
-                                Some((object_ref, o, layout, options, None).try_into()?),
-                                Some(SuiObjectResponseError::DisplayError {
-                                    error: e.to_string(),
-                                }),
-                            ));
-                        }
-                    }
-                }
-                Ok(SuiObjectResponse::new_with_data(
-                    (object_ref, o, layout, options, display_fields).try_into()?,
-                ))
-            }
-            ObjectRead::Deleted((object_id, version, digest)) => Ok(
-                SuiObjectResponse::new_with_error(SuiObjectResponseError::Deleted {
-                    object_id,
-                    version,
-                    digest,
-                }),
-            ),
-        }
+        let object_read = self.inner.get_object_read(object_id).await?;
+        object_response(&self.inner, object_read, options).await
     }
 
     // For ease of implementation we just forward to the single object query, although in the
@@ -146,9 +111,9 @@ impl ReadApiServer for ReadApi {
             .multi_get_transaction_blocks(vec![digest], options)
             .await?;
 
-        let txn = txn.pop().ok_or_else(|| {
-            IndexerError::InvalidArgumentError(format!("Transaction {digest} not found"))
-        })?;
+        let txn = txn
+            .pop()
+            .ok_or_else(|| IndexerError::NotFound(format!("Transaction {digest} not found")))?;
 
         Ok(txn)
     }
@@ -168,7 +133,7 @@ impl ReadApiServer for ReadApi {
         let options = options.unwrap_or_default();
         let txns = self
             .inner
-            .multi_get_transaction_block_response_in_blocking_task(digests, options)
+            .multi_get_transaction_block_response(digests, options)
             .await?;
 
         Ok(txns)
@@ -221,7 +186,7 @@ impl ReadApiServer for ReadApi {
 
         let mut checkpoints = self
             .inner
-            .spawn_blocking(move |this| this.get_checkpoints(cursor, limit + 1, descending_order))
+            .get_checkpoints(cursor, limit + 1, descending_order)
             .await?;
 
         let has_next_page = checkpoints.len() > limit;
@@ -233,6 +198,7 @@ impl ReadApiServer for ReadApi {
             data: checkpoints,
             next_cursor,
             has_next_page,
+            truncated: false,
         })
     }
 
@@ -252,7 +218,7 @@ impl ReadApiServer for ReadApi {
 
     async fn get_events(&self, transaction_digest: TransactionDigest) -> RpcResult<Vec<SuiEvent>> {
         self.inner
-            .get_transaction_events_in_blocking_task(transaction_digest)
+            .get_transaction_events(transaction_digest)
             .await
             .map_err(Into::into)
     }
@@ -275,11 +241,7 @@ impl ReadApiServer for ReadApi {
         let version = if let Some(version) = version {
             (*version).into()
         } else {
-            let latest_epoch = self
-                .inner
-                .spawn_blocking(|this| this.get_latest_epoch_info_from_db())
-                .await?;
-            (latest_epoch.protocol_version as u64).into()
+            self.inner.get_latest_protocol_config().await?.version
         };
 
         ProtocolConfig::get_for_version_if_supported(version, chain)
@@ -294,9 +256,107 @@ impl ReadApiServer for ReadApi {
     async fn get_chain_identifier(&self) -> RpcResult<String> {
         self.get_chain_identifier().await.map(|id| id.to_string())
     }
+
+    async fn verify_checkpoint_signature(
+        &self,
+        summary: Base64,
+        signature: Base64,
+        epoch: BigInt<u64>,
+    ) -> RpcResult<bool> {
+        let epoch = *epoch;
+        let Some(epoch_info) = self.inner.get_epoch_info(Some(epoch)).await? else {
+            return Err(
+                IndexerError::NotFound(format!("Committee for epoch {epoch} not found")).into(),
+            );
+        };
+
+        let committee = epoch_info.committee().map_err(IndexerError::from)?;
+        Ok(verify_checkpoint_signature(
+            &summary, &signature, &committee,
+        )?)
+    }
+
+    async fn validate_display(
+        &self,
+        object_type: String,
+        fields: Option<BTreeMap<String, String>>,
+    ) -> RpcResult<Vec<DisplayFieldError>> {
+        let object_type = parse_sui_struct_tag(&object_type)
+            .map_err(|e| IndexerError::InvalidArgumentError(e.to_string()))?;
+        let fields = match fields {
+            Some(fields) => fields,
+            None => {
+                let Some(display) = self.inner.get_display_object_by_type(&object_type).await?
+                else {
+                    return Err(IndexerError::InvalidArgumentError(format!(
+                        "No Display has been published for {object_type}"
+                    ))
+                    .into());
+                };
+                display
+                    .fields
+                    .contents
+                    .into_iter()
+                    .map(|entry| (entry.key, entry.value))
+                    .collect()
+            }
+        };
+
+        let layout = self.inner.get_struct_layout(object_type).await?;
+        Ok(validate_display_fields(&fields, &layout))
+    }
+}
+
+/// The response to a read of an object, with the content asked for in `options`.
+pub(crate) async fn object_response<R: IndexerReadStore>(
+    reader: &R,
+    object_read: ObjectRead,
+    options: SuiObjectDataOptions,
+) -> RpcResult<SuiObjectResponse> {
+    match object_read {
+        ObjectRead::NotExists(id) => Ok(SuiObjectResponse::new_with_error(
+            SuiObjectResponseError::NotExists { object_id: id },
+        )),
+        ObjectRead::Exists(object_ref, o, layout) => {
+            let labels = if options.show_labels {
+                let owner = o.owner.get_owner_address().ok();
+                let labels = reader
+                    .get_address_labels(owner.into_iter().collect())
+                    .await?;
+                Some(labels)
+            } else {
+                None
+            };
+
+            let mut display_fields = None;
+            let mut display_error = None;
+            if options.show_display {
+                match reader.get_display_fields(&o, &layout).await {
+                    Ok(rendered_fields) => display_fields = Some(rendered_fields),
+                    Err(e) => {
+                        display_error = Some(SuiObjectResponseError::DisplayError {
+                            error: e.to_string(),
+                        })
+                    }
+                }
+            }
+
+            let mut data: SuiObjectData =
+                (object_ref, o, layout, options, display_fields).try_into()?;
+            data.labels = labels;
+            Ok(SuiObjectResponse::new(Some(data), display_error))
+        }
+        ObjectRead::Deleted((object_id, version, digest)) => Ok(SuiObjectResponse::new_with_error(
+            SuiObjectResponseError::Deleted {
+                object_id,
+                version,
+                digest,
+            },
+        )),
+    }
 }
 
-impl SuiRpcModule for ReadApi {
+impl<R: IndexerReadStore> SuiRpcModule for ReadApi<R> {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
     }
@@ -305,3 +365,81 @@ impl SuiRpcModule for ReadApi {
         sui_json_rpc_api::ReadApiOpenRpc::module_doc()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::traits::{KeyPair, ToFromBytes};
+    use sui_json_rpc_types::EpochInfo;
+    use sui_types::committee::Committee;
+    use sui_types::crypto::{AuthorityKeyPair, AuthorityStrongQuorumSignInfo};
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CheckpointContents, CheckpointSummary, SignedCheckpointSummary,
+    };
+    use sui_types::sui_system_state::sui_system_state_summary::SuiValidatorSummary;
+
+    /// The epoch as the indexer stores it, with `keys` as its validators.
+    fn epoch_info(epoch: u64, keys: &[AuthorityKeyPair], voting_power: &[u64]) -> EpochInfo {
+        let validators = keys
+            .iter()
+            .zip(voting_power)
+            .map(|(k, p)| SuiValidatorSummary {
+                protocol_pubkey_bytes: k.public().as_bytes().to_vec(),
+                voting_power: *p,
+                ..Default::default()
+            })
+            .collect();
+        EpochInfo {
+            epoch,
+            validators,
+            epoch_total_transactions: 0,
+            first_checkpoint_id: 0,
+            epoch_start_timestamp: 0,
+            end_of_epoch_info: None,
+            reference_gas_price: None,
+        }
+    }
+
+    fn sign_checkpoint(
+        summary: &CheckpointSummary,
+        committee: &Committee,
+        keys: &[AuthorityKeyPair],
+    ) -> Base64 {
+        let sign_infos = keys
+            .iter()
+            .map(|k| SignedCheckpointSummary::sign(committee.epoch, summary, k, k.public().into()))
+            .collect();
+        let signature =
+            AuthorityStrongQuorumSignInfo::new_from_auth_sign_infos(sign_infos, committee).unwrap();
+        Base64::from_bytes(&bcs::to_bytes(&signature).unwrap())
+    }
+
+    #[test]
+    fn test_verify_checkpoint_signature_with_stored_committee() {
+        let (_, keys) = Committee::new_simple_test_committee();
+        let committee = epoch_info(0, &keys, &[2500; 4]).committee().unwrap();
+        let contents = CheckpointContents::new_with_digests_only_for_tests(vec![]);
+        let summary =
+            CheckpointSummary::new(0, 1, 0, &contents, None, GasCostSummary::default(), None, 0);
+        let encoded = Base64::from_bytes(&bcs::to_bytes(&summary).unwrap());
+        let signature = sign_checkpoint(&summary, &committee, &keys[..3]);
+        assert!(verify_checkpoint_signature(&encoded, &signature, &committee).unwrap());
+
+        // A summary other than the one that was signed.
+        let mut tampered = summary.clone();
+        tampered.timestamp_ms += 1;
+        let tampered = Base64::from_bytes(&bcs::to_bytes(&tampered).unwrap());
+        assert!(!verify_checkpoint_signature(&tampered, &signature, &committee).unwrap());
+
+        // The same validators, as stored for another epoch.
+        let next_epoch = epoch_info(1, &keys, &[2500; 4]).committee().unwrap();
+        assert!(!verify_checkpoint_signature(&encoded, &signature, &next_epoch).unwrap());
+
+        // The signers hold less than a quorum of the stored voting power.
+        let reweighted = epoch_info(0, &keys, &[1000, 1000, 1000, 7000])
+            .committee()
+            .unwrap();
+        assert!(!verify_checkpoint_signature(&encoded, &signature, &reweighted).unwrap());
+    }
+}
@@ -2,52 +2,53 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::governance_api::GovernanceReadApi;
-use crate::indexer_reader::IndexerReader;
+use crate::store::IndexerReadStore;
 use async_trait::async_trait;
 use move_core_types::language_storage::StructTag;
 use sui_json_rpc::transaction_builder_api::TransactionBuilderApi as SuiTransactionBuilderApi;
 use sui_json_rpc_types::{SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse};
 use sui_transaction_builder::DataReader;
 use sui_types::base_types::{ObjectID, ObjectInfo, SuiAddress};
-use sui_types::object::Object;
+use sui_types::object::ObjectRead;
 
-pub(crate) struct TransactionBuilderApi {
-    inner: IndexerReader,
+pub(crate) struct TransactionBuilderApi<R> {
+    inner: R,
 }
 
-impl TransactionBuilderApi {
+impl<R: IndexerReadStore> TransactionBuilderApi<R> {
     #[allow(clippy::new_ret_no_self)]
-    pub fn new(inner: IndexerReader) -> SuiTransactionBuilderApi {
+    pub fn new(inner: R) -> SuiTransactionBuilderApi {
         SuiTransactionBuilderApi::new_with_data_reader(std::sync::Arc::new(Self { inner }))
     }
 }
 
 #[async_trait]
-impl DataReader for TransactionBuilderApi {
+impl<R: IndexerReadStore> DataReader for TransactionBuilderApi<R> {
     async fn get_owned_objects(
         &self,
         address: SuiAddress,
         object_type: StructTag,
     ) -> Result<Vec<ObjectInfo>, anyhow::Error> {
-        let stored_objects = self
+        let objects = self
             .inner
-            .get_owned_objects_in_blocking_task(
+            .get_owned_objects(
                 address,
                 Some(SuiObjectDataFilter::StructType(object_type)),
                 None,
                 50, // Limit the number of objects returned to 50
+                None,
             )
             .await?;
 
-        stored_objects
+        Ok(objects
             .into_iter()
-            .map(|object| {
-                let object = Object::try_from(object)?;
-                let object_ref = object.compute_object_reference();
-                let info = ObjectInfo::new(&object_ref, &object);
-                Ok(info)
+            .filter_map(|object| match object {
+                ObjectRead::Exists(object_ref, object, _) => {
+                    Some(ObjectInfo::new(&object_ref, &object))
+                }
+                _ => None,
             })
-            .collect::<Result<Vec<_>, _>>()
+            .collect())
     }
 
     async fn get_object_with_options(
@@ -55,10 +56,7 @@ impl DataReader for TransactionBuilderApi {
         object_id: ObjectID,
         options: SuiObjectDataOptions,
     ) -> Result<SuiObjectResponse, anyhow::Error> {
-        let result = self
-            .inner
-            .get_object_read_in_blocking_task(object_id)
-            .await?;
+        let result = self.inner.get_object_read(object_id).await?;
         Ok((result, options).try_into()?)
     }
 
@@ -1,39 +1,112 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::future::Future;
+
 use async_trait::async_trait;
 use fastcrypto::encoding::Base64;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::core::{Error as RpcError, RpcResult};
 use jsonrpsee::http_client::HttpClient;
 use jsonrpsee::RpcModule;
+use tracing::warn;
 
+use sui_json_rpc::error::SuiRpcInputError;
+use sui_json_rpc::transaction_limits::check_transaction_limits;
+use sui_json_rpc::transaction_lints::lint_transaction;
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_api::{WriteApiClient, WriteApiServer};
 use sui_json_rpc_types::{
-    DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlockResponse,
+    DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse,
+    SuiTransactionBlockEffectsAPI, SuiTransactionBlockLint, SuiTransactionBlockResponse,
     SuiTransactionBlockResponseOptions,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::SuiAddress;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::sui_serde::BigInt;
+use sui_types::transaction::TransactionData;
 
+use crate::dry_run_pool::DryRunPool;
+use crate::equivocation::EquivocationStore;
+use crate::prices::PriceOracle;
+use crate::store::IndexerReadStore;
 use crate::types::SuiTransactionBlockResponseWithOptions;
 
-pub(crate) struct WriteApi {
+pub(crate) struct WriteApi<R> {
     fullnode: HttpClient,
+    /// Where to read the protocol config that transactions are checked against from.
+    reader: R,
+    /// Where to record the equivocations reported when executing transactions, if anywhere.
+    equivocations: Option<EquivocationStore>,
+    /// What to value the gas costs of dry runs with, if anything.
+    prices: Option<PriceOracle>,
+    /// The fullnodes to forward dry runs and dev-inspects to, if not `fullnode`.
+    dry_run_pool: Option<DryRunPool>,
 }
 
-impl WriteApi {
-    pub fn new(fullnode_client: HttpClient) -> Self {
+impl<R: IndexerReadStore> WriteApi<R> {
+    pub fn new(
+        fullnode_client: HttpClient,
+        reader: R,
+        equivocations: Option<EquivocationStore>,
+        prices: Option<PriceOracle>,
+        dry_run_pool: Option<DryRunPool>,
+    ) -> Self {
         Self {
             fullnode: fullnode_client,
+            reader,
+            equivocations,
+            prices,
+            dry_run_pool,
+        }
+    }
+
+    /// Send a dry run or dev-inspect `request` to the dry-run pool if there is one, or to the
+    /// fullnode otherwise.
+    async fn simulate<T, F, Fut>(&self, request: F) -> Result<T, RpcError>
+    where
+        F: Fn(HttpClient) -> Fut,
+        Fut: Future<Output = Result<T, RpcError>>,
+    {
+        match &self.dry_run_pool {
+            Some(pool) => pool.call(request).await,
+            None => request(self.fullnode.clone()).await,
         }
     }
+
+    /// Reject a transaction that exceeds the limits of the latest protocol config, before
+    /// forwarding it to the fullnode. Transactions are forwarded unchecked if the protocol config
+    /// can't be read.
+    async fn check_limits(
+        &self,
+        tx_bytes: &Base64,
+        signatures: &[Base64],
+        check_gas: bool,
+    ) -> RpcResult<()> {
+        let config = match self.reader.get_latest_protocol_config().await {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Failed to read protocol config to check transaction limits: {e}");
+                return Ok(());
+            }
+        };
+
+        let tx_bytes = tx_bytes.to_vec().map_err(SuiRpcInputError::from)?;
+        let tx_data: TransactionData =
+            bcs::from_bytes(&tx_bytes).map_err(SuiRpcInputError::from)?;
+        // The size of the signed transaction, bar the few bytes encoding the signatures' lengths.
+        let tx_size = tx_bytes.len()
+            + signatures
+                .iter()
+                .map(|s| s.to_vec().map_or(0, |s| s.len()))
+                .sum::<usize>();
+        check_transaction_limits(&tx_data, tx_size, &config, check_gas)?;
+        Ok(())
+    }
 }
 
 #[async_trait]
-impl WriteApiServer for WriteApi {
+impl<R: IndexerReadStore> WriteApiServer for WriteApi<R> {
     async fn execute_transaction_block(
         &self,
         tx_bytes: Base64,
@@ -41,11 +114,24 @@ impl WriteApiServer for WriteApi {
         options: Option<SuiTransactionBlockResponseOptions>,
         request_type: Option<ExecuteTransactionRequestType>,
     ) -> RpcResult<SuiTransactionBlockResponse> {
+        self.check_limits(&tx_bytes, &signatures, /* check_gas */ true)
+            .await?;
         let fast_path_options = SuiTransactionBlockResponseOptions::full_content();
         let sui_transaction_response = self
             .fullnode
-            .execute_transaction_block(tx_bytes, signatures, Some(fast_path_options), request_type)
-            .await?;
+            .execute_transaction_block(
+                tx_bytes.clone(),
+                signatures,
+                Some(fast_path_options),
+                request_type,
+            )
+            .await
+            .map_err(|e| {
+                if let Some(store) = &self.equivocations {
+                    store.record_execution_error(&tx_bytes, &e);
+                }
+                e
+            })?;
 
         Ok(SuiTransactionBlockResponseWithOptions {
             response: sui_transaction_response,
@@ -62,26 +148,78 @@ impl WriteApiServer for WriteApi {
         epoch: Option<BigInt<u64>>,
         additional_args: Option<DevInspectArgs>,
     ) -> RpcResult<DevInspectResults> {
-        self.fullnode
-            .dev_inspect_transaction_block(
-                sender_address,
-                tx_bytes,
-                gas_price,
-                epoch,
-                additional_args,
-            )
-            .await
+        self.simulate(|fullnode| {
+            let tx_bytes = tx_bytes.clone();
+            let additional_args = additional_args.clone();
+            async move {
+                fullnode
+                    .dev_inspect_transaction_block(
+                        sender_address,
+                        tx_bytes,
+                        gas_price,
+                        epoch,
+                        additional_args,
+                    )
+                    .await
+            }
+        })
+        .await
     }
 
     async fn dry_run_transaction_block(
         &self,
         tx_bytes: Base64,
     ) -> RpcResult<DryRunTransactionBlockResponse> {
-        self.fullnode.dry_run_transaction_block(tx_bytes).await
+        self.check_limits(&tx_bytes, &[], /* check_gas */ false)
+            .await?;
+        let mut response = self
+            .simulate(|fullnode| {
+                let tx_bytes = tx_bytes.clone();
+                async move { fullnode.dry_run_transaction_block(tx_bytes).await }
+            })
+            .await?;
+        if let Some(prices) = &self.prices {
+            let net_gas_usage = response.effects.gas_cost_summary().net_gas_usage();
+            response.gas_fiat_value = prices.value_sui(net_gas_usage as i128).await;
+        }
+        Ok(response)
+    }
+
+    async fn dry_run_transaction_blocks(
+        &self,
+        tx_bytes: Vec<Base64>,
+    ) -> RpcResult<Vec<DryRunTransactionBlockResponse>> {
+        for tx_bytes in &tx_bytes {
+            self.check_limits(tx_bytes, &[], /* check_gas */ false)
+                .await?;
+        }
+        let mut responses = self
+            .simulate(|fullnode| {
+                let tx_bytes = tx_bytes.clone();
+                async move { fullnode.dry_run_transaction_blocks(tx_bytes).await }
+            })
+            .await?;
+        if let Some(prices) = &self.prices {
+            for response in &mut responses {
+                let net_gas_usage = response.effects.gas_cost_summary().net_gas_usage();
+                response.gas_fiat_value = prices.value_sui(net_gas_usage as i128).await;
+            }
+        }
+        Ok(responses)
+    }
+
+    async fn lint_transaction_block(
+        &self,
+        tx_bytes: Base64,
+    ) -> RpcResult<Vec<SuiTransactionBlockLint>> {
+        let tx_bytes = tx_bytes.to_vec().map_err(SuiRpcInputError::from)?;
+        let tx_data: TransactionData =
+            bcs::from_bytes(&tx_bytes).map_err(SuiRpcInputError::from)?;
+        Ok(lint_transaction(&tx_data))
     }
 }
 
-impl SuiRpcModule for WriteApi {
+impl<R: IndexerReadStore> SuiRpcModule for WriteApi<R> {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
     }
@@ -0,0 +1,188 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-process cache of the metadata of coins, by coin type, so that wallets looking up the
+//! metadata of the same few coins over and over don't each hit the database.
+//!
+//! Metadata is cached until the cache is full, when the least recently used metadata is evicted,
+//! or until the package that defines its coin is upgraded. Upgrades are noticed by polling for
+//! newly indexed package-upgrade transactions, so cached metadata can outlive an upgrade by up to
+//! the polling interval. Coins without metadata aren't cached, so their metadata is served as
+//! soon as it is indexed.
+
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use lru::LruCache;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::StructTag;
+use sui_json_rpc_types::SuiCoinMetadata;
+use tracing::{debug, warn};
+
+use crate::errors::IndexerError;
+use crate::indexer_reader::IndexerReader;
+use crate::metrics::IndexerMetrics;
+
+/// Number of package-upgrade transactions read at a time.
+const UPGRADES_BATCH_SIZE: i64 = 100;
+
+/// The cache of coin metadata. Clones share the same cache.
+#[derive(Clone)]
+pub struct CoinMetadataCache {
+    entries: Arc<Mutex<LruCache<StructTag, SuiCoinMetadata>>>,
+    metrics: IndexerMetrics,
+}
+
+impl CoinMetadataCache {
+    pub fn new(capacity: NonZeroUsize, metrics: IndexerMetrics) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(LruCache::new(capacity))),
+            metrics,
+        }
+    }
+
+    pub fn get(&self, coin_type: &StructTag) -> Option<SuiCoinMetadata> {
+        let metadata = self.entries.lock().unwrap().get(coin_type).cloned();
+        if metadata.is_some() {
+            self.metrics.coin_metadata_cache_hits.inc();
+        } else {
+            self.metrics.coin_metadata_cache_misses.inc();
+        }
+        metadata
+    }
+
+    pub fn insert(&self, coin_type: StructTag, metadata: SuiCoinMetadata) {
+        self.entries.lock().unwrap().put(coin_type, metadata);
+    }
+
+    /// Evict the metadata of every coin defined by the package originally published at
+    /// `package`, returning how many were evicted.
+    pub fn invalidate_package(&self, package: AccountAddress) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let coin_types: Vec<_> = entries
+            .iter()
+            .map(|(coin_type, _)| coin_type)
+            .filter(|coin_type| coin_type.address == package)
+            .cloned()
+            .collect();
+        for coin_type in &coin_types {
+            entries.pop(coin_type);
+        }
+        self.metrics
+            .coin_metadata_cache_invalidations
+            .inc_by(coin_types.len() as u64);
+        coin_types.len()
+    }
+
+    /// Evict the metadata of coins whose packages are upgraded from now on, checking for newly
+    /// indexed upgrades every `interval`.
+    pub async fn invalidate_upgraded(self, reader: IndexerReader, interval: Duration) {
+        let mut last_upgrade = None;
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let result = match last_upgrade {
+                // Nothing is cached from before the reader started, so earlier upgrades are
+                // skipped.
+                None => reader
+                    .spawn_blocking(|this| this.get_latest_package_upgrade())
+                    .await
+                    .map(|latest| last_upgrade = Some(latest.unwrap_or(-1))),
+                Some(after) => self
+                    .invalidate_upgrades_after(&reader, after)
+                    .await
+                    .map(|last| last_upgrade = Some(last)),
+            };
+            if let Err(e) = result {
+                warn!("Failed to check for package upgrades to invalidate coin metadata: {e}");
+            }
+        }
+    }
+
+    /// Evict the metadata of coins whose packages were upgraded by transactions after `after`,
+    /// returning the sequence number of the last upgrade transaction read.
+    async fn invalidate_upgrades_after(
+        &self,
+        reader: &IndexerReader,
+        mut after: i64,
+    ) -> Result<i64, IndexerError> {
+        loop {
+            let upgrades = reader
+                .spawn_blocking(move |this| this.get_package_upgrades(after, UPGRADES_BATCH_SIZE))
+                .await?;
+            let read = upgrades.len();
+            for (tx_sequence_number, packages) in upgrades {
+                for package in packages {
+                    let evicted = self.invalidate_package(package.into());
+                    debug!(
+                        tx_sequence_number,
+                        %package,
+                        evicted,
+                        "Invalidated coin metadata of upgraded package"
+                    );
+                }
+                after = tx_sequence_number;
+            }
+            if read < UPGRADES_BATCH_SIZE as usize {
+                return Ok(after);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+    use sui_types::base_types::ObjectID;
+
+    use super::*;
+
+    fn coin_type(package: &str, name: &str) -> StructTag {
+        sui_types::parse_sui_struct_tag(&format!("{package}::{}::{name}", name.to_lowercase()))
+            .unwrap()
+    }
+
+    fn metadata(symbol: &str) -> SuiCoinMetadata {
+        SuiCoinMetadata {
+            decimals: 9,
+            name: symbol.to_lowercase(),
+            symbol: symbol.to_string(),
+            description: String::new(),
+            icon_url: None,
+            id: Some(ObjectID::ZERO),
+        }
+    }
+
+    #[test]
+    fn test_coin_metadata_cache() {
+        let metrics = IndexerMetrics::new(&Registry::new());
+        let cache = CoinMetadataCache::new(NonZeroUsize::new(3).unwrap(), metrics.clone());
+
+        let a = coin_type("0xa", "A");
+        let a2 = coin_type("0xa", "A2");
+        let b = coin_type("0xb", "B");
+        let c = coin_type("0xc", "C");
+        assert_eq!(cache.get(&a), None);
+        cache.insert(a.clone(), metadata("A"));
+        cache.insert(b.clone(), metadata("B"));
+        assert_eq!(cache.get(&a), Some(metadata("A")));
+
+        // The least recently used metadata is evicted to make room.
+        cache.insert(a2.clone(), metadata("A2"));
+        cache.insert(c.clone(), metadata("C"));
+        assert_eq!(cache.get(&b), None);
+
+        // Upgrading a package evicts the metadata of all of its coins, and only its coins.
+        assert_eq!(cache.invalidate_package(a.address), 2);
+        assert_eq!(cache.get(&a), None);
+        assert_eq!(cache.get(&a2), None);
+        assert_eq!(cache.get(&c), Some(metadata("C")));
+        assert_eq!(cache.invalidate_package(a.address), 0);
+
+        assert_eq!(metrics.coin_metadata_cache_hits.get(), 2);
+        assert_eq!(metrics.coin_metadata_cache_misses.get(), 4);
+        assert_eq!(metrics.coin_metadata_cache_invalidations.get(), 2);
+    }
+}
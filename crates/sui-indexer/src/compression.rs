@@ -0,0 +1,97 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optional compression of the BCS payloads of `transactions` and `events`. Each row records how
+//! its payloads are compressed in its `compression` column, so rows written with different
+//! settings can live side by side, and rows are decompressed as they are loaded.
+
+use crate::errors::IndexerError;
+use crate::types::IndexerResult;
+
+/// The `compression` of rows whose payloads are stored as they are.
+pub const UNCOMPRESSED: i16 = 0;
+const LZ4: i16 = Compression::Lz4 as i16;
+const ZSTD: i16 = Compression::Zstd as i16;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// How payloads are compressed, recorded as the `compression` of the rows they are in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Compression {
+    /// Fast compression, for writers that are bound on CPU.
+    Lz4 = 1,
+    /// Smaller payloads, for a little more CPU.
+    Zstd = 2,
+}
+
+impl Compression {
+    pub fn compress(self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Lz4 => lz4_flex::compress_prepend_size(bytes),
+            Self::Zstd => zstd::bulk::compress(bytes, ZSTD_LEVEL)
+                .expect("Compressing in memory with a valid level can't fail"),
+        }
+    }
+
+    pub fn compress_all(self, payloads: &[Option<Vec<u8>>]) -> Vec<Option<Vec<u8>>> {
+        payloads
+            .iter()
+            .map(|payload| payload.as_deref().map(|bytes| self.compress(bytes)))
+            .collect()
+    }
+}
+
+/// `bytes`, decompressed according to the `compression` of the row they are from.
+pub fn decompress(compression: i16, bytes: Vec<u8>) -> IndexerResult<Vec<u8>> {
+    let decompressed = match compression {
+        UNCOMPRESSED => return Ok(bytes),
+        LZ4 => lz4_flex::decompress_size_prepended(&bytes).map_err(|e| e.to_string()),
+        ZSTD => zstd::decode_all(bytes.as_slice()).map_err(|e| e.to_string()),
+        _ => Err(format!("unknown compression {compression}")),
+    };
+    decompressed.map_err(|e| {
+        IndexerError::PersistentStorageDataCorruptionError(format!(
+            "Failed to decompress payload: {e}"
+        ))
+    })
+}
+
+pub fn decompress_all(
+    compression: i16,
+    payloads: Vec<Option<Vec<u8>>>,
+) -> IndexerResult<Vec<Option<Vec<u8>>>> {
+    payloads
+        .into_iter()
+        .map(|payload| {
+            payload
+                .map(|bytes| decompress(compression, bytes))
+                .transpose()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_round_trip() {
+        let bytes = b"payload payload payload payload".to_vec();
+        assert_eq!(decompress(UNCOMPRESSED, bytes.clone()).unwrap(), bytes);
+        for compression in [Compression::Lz4, Compression::Zstd] {
+            let compressed = compression.compress(&bytes);
+            assert_ne!(compressed, bytes);
+            assert_eq!(decompress(compression as i16, compressed).unwrap(), bytes);
+        }
+
+        let payloads = vec![Some(bytes.clone()), None];
+        let compressed = Compression::Zstd.compress_all(&payloads);
+        assert_eq!(
+            decompress_all(Compression::Zstd as i16, compressed).unwrap(),
+            payloads
+        );
+
+        assert!(decompress(Compression::Zstd as i16, bytes.clone()).is_err());
+        assert!(decompress(3, bytes).is_err());
+    }
+}
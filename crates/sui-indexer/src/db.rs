@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 use anyhow::anyhow;
@@ -10,10 +11,78 @@ use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use tracing::info;
 
 use crate::errors::IndexerError;
+use crate::metrics::IndexerMetrics;
 
-pub type PgConnectionPool = diesel::r2d2::Pool<ConnectionManager<PgConnection>>;
 pub type PgPoolConnection = diesel::r2d2::PooledConnection<ConnectionManager<PgConnection>>;
 
+type Pool = diesel::r2d2::Pool<ConnectionManager<PgConnection>>;
+
+/// A pool of connections to Postgres that can be resized while it is in use. Resizing replaces
+/// the pool's connections: those checked out before are closed once they are returned.
+#[derive(Clone)]
+pub struct PgConnectionPool {
+    pool: Arc<RwLock<Pool>>,
+    db_url: Arc<str>,
+    connection_timeout: Duration,
+    connection_config: PgConnectionConfig,
+}
+
+impl PgConnectionPool {
+    pub fn new(
+        db_url: &str,
+        pool_size: u32,
+        connection_timeout: Duration,
+        connection_config: PgConnectionConfig,
+    ) -> Result<Self, diesel::r2d2::PoolError> {
+        let pool = Self::builder(pool_size, connection_timeout, connection_config)
+            .build(ConnectionManager::new(db_url))?;
+        Ok(Self {
+            pool: Arc::new(RwLock::new(pool)),
+            db_url: db_url.into(),
+            connection_timeout,
+            connection_config,
+        })
+    }
+
+    fn builder(
+        pool_size: u32,
+        connection_timeout: Duration,
+        connection_config: PgConnectionConfig,
+    ) -> diesel::r2d2::Builder<ConnectionManager<PgConnection>> {
+        diesel::r2d2::Pool::builder()
+            .max_size(pool_size)
+            .connection_timeout(connection_timeout)
+            .connection_customizer(Box::new(connection_config))
+    }
+
+    fn current(&self) -> Pool {
+        self.pool.read().unwrap().clone()
+    }
+
+    pub fn get(&self) -> Result<PgPoolConnection, diesel::r2d2::PoolError> {
+        self.current().get()
+    }
+
+    pub fn state(&self) -> diesel::r2d2::State {
+        self.current().state()
+    }
+
+    /// The most connections the pool holds at once.
+    pub fn max_size(&self) -> u32 {
+        self.current().max_size()
+    }
+
+    /// Replace the pool's connections with a pool of up to `size` connections, which are opened in
+    /// the background.
+    pub fn resize(&self, size: u32) {
+        let mut pool = self.pool.write().unwrap();
+        if pool.max_size() != size {
+            *pool = Self::builder(size, self.connection_timeout, self.connection_config)
+                .build_unchecked(ConnectionManager::new(self.db_url.as_ref()));
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct PgConnectionPoolConfig {
     pub pool_size: u32,
@@ -101,20 +170,20 @@ pub fn new_pg_connection_pool(
     pool_size: Option<u32>,
 ) -> Result<PgConnectionPool, IndexerError> {
     let pool_config = PgConnectionPoolConfig::default();
-    let manager = ConnectionManager::<PgConnection>::new(db_url);
 
     let pool_size = pool_size.unwrap_or(pool_config.pool_size);
-    diesel::r2d2::Pool::builder()
-        .max_size(pool_size)
-        .connection_timeout(pool_config.connection_timeout)
-        .connection_customizer(Box::new(pool_config.connection_config()))
-        .build(manager)
-        .map_err(|e| {
-            IndexerError::PgConnectionPoolInitError(format!(
-                "Failed to initialize connection pool with error: {:?}",
-                e
-            ))
-        })
+    PgConnectionPool::new(
+        db_url,
+        pool_size,
+        pool_config.connection_timeout,
+        pool_config.connection_config(),
+    )
+    .map_err(|e| {
+        IndexerError::PgConnectionPoolInitError(format!(
+            "Failed to initialize connection pool with error: {:?}",
+            e
+        ))
+    })
 }
 
 pub fn get_pg_pool_connection(pool: &PgConnectionPool) -> Result<PgPoolConnection, IndexerError> {
@@ -126,6 +195,40 @@ pub fn get_pg_pool_connection(pool: &PgConnectionPool) -> Result<PgPoolConnectio
     })
 }
 
+/// Keep reporting how many connections `pool` holds, and how many of them are idle, labelled as
+/// `name`.
+pub async fn report_connection_pool(
+    name: &'static str,
+    pool: PgConnectionPool,
+    metrics: IndexerMetrics,
+) {
+    loop {
+        let state = pool.state();
+        let max_size = pool.max_size();
+        info!(
+            "DB connection pool {name} size: {} of {max_size}, with idle conn: {}.",
+            state.connections, state.idle_connections
+        );
+        metrics
+            .db_conn_pool_size
+            .with_label_values(&[name])
+            .set(state.connections as i64);
+        metrics
+            .idle_db_conn
+            .with_label_values(&[name])
+            .set(state.idle_connections as i64);
+        metrics
+            .db_conn_pool_max_size
+            .with_label_values(&[name])
+            .set(max_size as i64);
+        metrics
+            .db_conn_in_use
+            .with_label_values(&[name])
+            .set((state.connections - state.idle_connections) as i64);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
 /// Resets the database by reverting all migrations and reapplying them.
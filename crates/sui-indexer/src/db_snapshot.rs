@@ -0,0 +1,562 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Logical snapshots of the indexer's database at an epoch boundary, to seed new replicas without
+//! re-ingesting the chain.
+//!
+//! A snapshot is a directory with one file per table, in Postgres' `COPY` text format, and a
+//! [`MANIFEST_FILE`] describing the checkpoint it was taken at, and each table's file, with its
+//! row count and digest.
+//!
+//! Snapshots are exported from a database whose writer stopped at the end of an epoch (see
+//! `--stop-at-epoch-end`). Every table is read in the same transaction, limited to the rows at or
+//! before the snapshot's checkpoint, and ordered by primary key, so exporting the same database
+//! twice produces identical files. They are imported into an empty database on the same schema
+//! version, e.g. one just reset with `--reset-db`. An import that fails part way leaves the
+//! database partially filled, so it must be reset before trying again.
+//!
+//! Tables are copied with `psql`, which must be on the `PATH`.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::Command;
+
+use diesel::dsl::max;
+use diesel::sql_types::{BigInt, Text};
+use diesel::{Connection, ExpressionMethods, PgConnection, QueryDsl, QueryableByName, RunQueryDsl};
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::db::new_pg_connection_pool;
+use crate::errors::IndexerError;
+use crate::models::checkpoints::StoredCheckpoint;
+use crate::schema::{checkpoints, epochs, objects};
+use crate::store::pg_partition_manager::{EpochPartitionData, PgPartitionManager};
+
+pub const MANIFEST_FILE: &str = "MANIFEST.json";
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    /// Latest migration applied to the exported database.
+    pub schema_version: String,
+    /// The epoch that the snapshot is taken at the end of.
+    pub epoch: u64,
+    /// The last checkpoint of `epoch`.
+    pub checkpoint: u64,
+    pub checkpoint_digest: String,
+    pub network_total_transactions: u64,
+    /// Epochs and the checkpoints they start at, to partition tables by epoch on import.
+    pub epoch_starts: Vec<(u64, u64)>,
+    pub tables: Vec<TableManifest>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableManifest {
+    pub name: String,
+    pub file: String,
+    pub rows: u64,
+    /// Hex-encoded Blake2b256 digest of the file.
+    pub digest: String,
+}
+
+/// Which of a table's rows belong in a snapshot.
+#[derive(Clone, Copy, Debug)]
+enum Rows {
+    /// All of them: the table holds the latest state rather than history, which is only
+    /// consistent with the snapshot's checkpoint because the writer stopped there.
+    All,
+    /// Rows whose column is a checkpoint at or before the snapshot's.
+    UpToCheckpoint(&'static str),
+    /// Rows whose column is a transaction sequence number in or before the snapshot's checkpoint.
+    UpToTransaction(&'static str),
+    /// Rows whose column is an epoch up to the snapshot's.
+    UpToEpoch(&'static str),
+    /// Rows whose column is an epoch up to the one after the snapshot's, which starts right after
+    /// its checkpoint, and is recorded along with it.
+    UpToNextEpoch(&'static str),
+}
+
+/// Every table in the database (other than partitions, which are copied through their parent
+/// table), and which of its rows to snapshot. Exporting fails on tables that are missing here,
+/// so that new tables are not silently left out of snapshots.
+const TABLES: &[(&str, Rows)] = &[
+    ("active_address_counts", Rows::All),
+    ("active_addresses", Rows::All),
+    ("address_daily_stats", Rows::All),
+    ("address_labels", Rows::All),
+    ("address_metrics", Rows::UpToCheckpoint("checkpoint")),
+    ("addresses", Rows::All),
+    (
+        "balance_changes",
+        Rows::UpToTransaction("tx_sequence_number"),
+    ),
+    ("checkpoints", Rows::UpToCheckpoint("sequence_number")),
+    ("display", Rows::All),
+    ("epoch_peak_tps", Rows::UpToEpoch("epoch")),
+    ("epochs", Rows::UpToNextEpoch("epoch")),
+    ("equivocations", Rows::All),
+    (
+        "event_addresses",
+        Rows::UpToCheckpoint("checkpoint_sequence_number"),
+    ),
+    ("event_schemas", Rows::All),
+    ("events", Rows::UpToCheckpoint("checkpoint_sequence_number")),
+    ("gas_price_stats", Rows::UpToCheckpoint("last_checkpoint")),
+    ("move_call_metrics", Rows::UpToEpoch("epoch")),
+    (
+        "move_calls",
+        Rows::UpToCheckpoint("checkpoint_sequence_number"),
+    ),
+    (
+        "nft_transfers",
+        Rows::UpToCheckpoint("checkpoint_sequence_number"),
+    ),
+    (
+        "object_transitions",
+        Rows::UpToCheckpoint("checkpoint_sequence_number"),
+    ),
+    ("objects", Rows::All),
+    (
+        "objects_history",
+        Rows::UpToCheckpoint("checkpoint_sequence_number"),
+    ),
+    ("objects_snapshot", Rows::All),
+    (
+        "package_call_metrics",
+        Rows::UpToCheckpoint("checkpoint_sequence_number"),
+    ),
+    ("packages", Rows::All),
+    (
+        "transactions",
+        Rows::UpToCheckpoint("checkpoint_sequence_number"),
+    ),
+    ("tx_calls", Rows::UpToTransaction("tx_sequence_number")),
+    (
+        "tx_changed_objects",
+        Rows::UpToTransaction("tx_sequence_number"),
+    ),
+    ("tx_classes", Rows::UpToTransaction("tx_sequence_number")),
+    (
+        "tx_count_metrics",
+        Rows::UpToCheckpoint("checkpoint_sequence_number"),
+    ),
+    (
+        "tx_input_objects",
+        Rows::UpToTransaction("tx_sequence_number"),
+    ),
+    ("tx_recipients", Rows::UpToTransaction("tx_sequence_number")),
+    ("tx_senders", Rows::UpToTransaction("tx_sequence_number")),
+    ("validator_apys", Rows::UpToEpoch("epoch")),
+    ("validator_history", Rows::UpToEpoch("epoch")),
+    ("writer_lease", Rows::All),
+];
+
+const LIST_TABLES_SQL: &str = r"
+SELECT c.relname AS name
+FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = 'public'
+  AND c.relkind IN ('r', 'p')
+  AND NOT c.relispartition
+  AND c.relname <> '__diesel_schema_migrations'
+ORDER BY c.relname;
+";
+
+const PRIMARY_KEY_SQL: &str = r"
+SELECT a.attname AS name
+FROM pg_index i
+         JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY (i.indkey)
+WHERE i.indrelid = CAST($1 AS regclass)
+  AND i.indisprimary
+ORDER BY array_position(CAST(i.indkey AS int2[]), a.attnum);
+";
+
+const SCHEMA_VERSION_SQL: &str =
+    "SELECT MAX(version) AS name FROM __diesel_schema_migrations WHERE version IS NOT NULL";
+
+#[derive(QueryableByName)]
+struct Name {
+    #[diesel(sql_type = Text)]
+    name: String,
+}
+
+#[derive(QueryableByName)]
+struct EpochStart {
+    #[diesel(sql_type = BigInt)]
+    epoch: i64,
+    #[diesel(sql_type = BigInt)]
+    first_checkpoint_id: i64,
+}
+
+/// Export a snapshot of the database at `db_url` to `dir`, which must not exist yet or be empty.
+pub fn export_snapshot(db_url: &str, dir: &Path) -> Result<SnapshotManifest, IndexerError> {
+    fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+    if fs::read_dir(dir)
+        .map_err(|e| io_error(dir, e))?
+        .next()
+        .is_some()
+    {
+        return Err(IndexerError::InvalidArgumentError(format!(
+            "Snapshot directory {} is not empty",
+            dir.display()
+        )));
+    }
+    let dir = dir.canonicalize().map_err(|e| io_error(dir, e))?;
+
+    let mut conn = connect(db_url)?;
+    let manifest = conn
+        .build_transaction()
+        .repeatable_read()
+        .read_only()
+        .run::<_, IndexerError, _>(|conn| {
+            // Share this transaction's snapshot with the `psql` sessions copying each table.
+            let snapshot_id = diesel::sql_query("SELECT pg_export_snapshot() AS name")
+                .get_result::<Name>(conn)?
+                .name;
+
+            let checkpoint: StoredCheckpoint = checkpoints::table
+                .order_by(checkpoints::sequence_number.desc())
+                .first(conn)?;
+            if !checkpoint.end_of_epoch {
+                return Err(IndexerError::InvalidArgumentError(format!(
+                    "Latest checkpoint {} is not the last of its epoch: snapshots can only be \
+                     exported once the writer has stopped at the end of an epoch",
+                    checkpoint.sequence_number
+                )));
+            }
+            let latest_object: Option<i64> = objects::table
+                .select(max(objects::checkpoint_sequence_number))
+                .first(conn)?;
+            if latest_object.is_some_and(|c| c > checkpoint.sequence_number) {
+                return Err(IndexerError::InvalidArgumentError(format!(
+                    "Objects have been written past checkpoint {}: snapshots can only be \
+                     exported once the writer has stopped",
+                    checkpoint.sequence_number
+                )));
+            }
+
+            let tables: Vec<String> = diesel::sql_query(LIST_TABLES_SQL)
+                .load::<Name>(conn)?
+                .into_iter()
+                .map(|t| t.name)
+                .collect();
+            if let Some(unknown) = tables
+                .iter()
+                .find(|t| !TABLES.iter().any(|(name, _)| name == t))
+            {
+                return Err(IndexerError::NotSupportedError(format!(
+                    "Don't know which rows of table {unknown} to snapshot"
+                )));
+            }
+
+            let epoch = checkpoint.epoch;
+            let epoch_starts = diesel::sql_query(
+                "SELECT epoch, first_checkpoint_id FROM epochs WHERE epoch <= $1 ORDER BY epoch",
+            )
+            .bind::<BigInt, _>(epoch + 1)
+            .load::<EpochStart>(conn)?
+            .into_iter()
+            .map(|e| (e.epoch as u64, e.first_checkpoint_id as u64))
+            .collect();
+
+            let mut manifest = SnapshotManifest {
+                version: MANIFEST_VERSION,
+                schema_version: schema_version(conn)?,
+                epoch: epoch as u64,
+                checkpoint: checkpoint.sequence_number as u64,
+                checkpoint_digest: Hex::encode(&checkpoint.checkpoint_digest),
+                network_total_transactions: checkpoint.network_total_transactions as u64,
+                epoch_starts,
+                tables: vec![],
+            };
+
+            for (name, rows) in TABLES {
+                if !tables.iter().any(|t| t == name) {
+                    continue;
+                }
+
+                let primary_key: Vec<String> = diesel::sql_query(PRIMARY_KEY_SQL)
+                    .bind::<Text, _>(*name)
+                    .load::<Name>(conn)?
+                    .into_iter()
+                    .map(|c| c.name)
+                    .collect();
+                let query = select_rows(name, *rows, &checkpoint, &primary_key);
+                let file = format!("{name}.copy");
+                let path = dir.join(&file);
+
+                info!("Exporting table {name}");
+                psql(
+                    db_url,
+                    &[
+                        "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY".to_string(),
+                        format!("SET TRANSACTION SNAPSHOT '{snapshot_id}'"),
+                        format!("\\copy ({query}) TO {}", quote_path(&path)),
+                    ],
+                )?;
+
+                let (rows, digest) = summarize(&path)?;
+                manifest.tables.push(TableManifest {
+                    name: name.to_string(),
+                    file,
+                    rows,
+                    digest,
+                });
+            }
+
+            Ok(manifest)
+        })?;
+
+    let path = dir.join(MANIFEST_FILE);
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| IndexerError::SerdeError(e.to_string()))?;
+    fs::write(&path, json).map_err(|e| io_error(&path, e))?;
+
+    info!(
+        "Exported snapshot at the end of epoch {} (checkpoint {}) to {}",
+        manifest.epoch,
+        manifest.checkpoint,
+        dir.display()
+    );
+    Ok(manifest)
+}
+
+/// Import the snapshot in `dir` into the empty database at `db_url`.
+pub fn import_snapshot(db_url: &str, dir: &Path) -> Result<SnapshotManifest, IndexerError> {
+    let dir = dir.canonicalize().map_err(|e| io_error(dir, e))?;
+    let path = dir.join(MANIFEST_FILE);
+    let json = fs::read_to_string(&path).map_err(|e| io_error(&path, e))?;
+    let manifest: SnapshotManifest =
+        serde_json::from_str(&json).map_err(|e| IndexerError::SerdeError(e.to_string()))?;
+    if manifest.version != MANIFEST_VERSION {
+        return Err(IndexerError::NotSupportedError(format!(
+            "Unsupported snapshot manifest version {}",
+            manifest.version
+        )));
+    }
+
+    // Check every file before writing anything.
+    for table in &manifest.tables {
+        let path = dir.join(&table.file);
+        let (rows, digest) = summarize(&path)?;
+        if (rows, &digest) != (table.rows, &table.digest) {
+            return Err(IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Snapshot file {} does not match the manifest: {rows} rows with digest {digest}, \
+                 expected {} rows with digest {}",
+                path.display(),
+                table.rows,
+                table.digest
+            )));
+        }
+    }
+
+    let mut conn = connect(db_url)?;
+    let schema_version = schema_version(&mut conn)?;
+    if schema_version != manifest.schema_version {
+        return Err(IndexerError::NotSupportedError(format!(
+            "Snapshot was exported from schema version {}, but the database is on version \
+             {schema_version}",
+            manifest.schema_version
+        )));
+    }
+    let checkpoints: i64 = checkpoints::table.count().get_result(&mut conn)?;
+    let epochs: i64 = epochs::table.count().get_result(&mut conn)?;
+    if checkpoints > 0 || epochs > 0 {
+        return Err(IndexerError::InvalidArgumentError(
+            "Snapshots can only be imported into an empty database".to_string(),
+        ));
+    }
+    drop(conn);
+
+    create_epoch_partitions(db_url, &manifest.epoch_starts)?;
+
+    // Import every table in one transaction.
+    let commands: Vec<String> = manifest
+        .tables
+        .iter()
+        .map(|table| {
+            let path = dir.join(&table.file);
+            format!("\\copy {} FROM {}", table.name, quote_path(&path))
+        })
+        .collect();
+    info!("Importing {} tables", commands.len());
+    psql(db_url, &commands)?;
+
+    info!(
+        "Imported snapshot at the end of epoch {} (checkpoint {}) from {}",
+        manifest.epoch,
+        manifest.checkpoint,
+        dir.display()
+    );
+    Ok(manifest)
+}
+
+/// The query selecting the rows of `table` to snapshot at `checkpoint`, in a stable order.
+fn select_rows(
+    table: &str,
+    rows: Rows,
+    checkpoint: &StoredCheckpoint,
+    primary_key: &[String],
+) -> String {
+    let filter = match rows {
+        Rows::All => None,
+        Rows::UpToCheckpoint(column) => Some(format!("{column} <= {}", checkpoint.sequence_number)),
+        Rows::UpToTransaction(column) => Some(format!(
+            "{column} < {}",
+            checkpoint.network_total_transactions
+        )),
+        Rows::UpToEpoch(column) => Some(format!("{column} <= {}", checkpoint.epoch)),
+        Rows::UpToNextEpoch(column) => Some(format!("{column} <= {}", checkpoint.epoch + 1)),
+    };
+
+    let mut query = format!("SELECT * FROM {table}");
+    if let Some(filter) = filter {
+        query += &format!(" WHERE {filter}");
+    }
+    if !primary_key.is_empty() {
+        query += &format!(" ORDER BY {}", primary_key.join(", "));
+    }
+    query
+}
+
+/// Create the partitions of tables partitioned by epoch for every epoch in `epoch_starts`, as
+/// the writer would have while ingesting them.
+fn create_epoch_partitions(db_url: &str, epoch_starts: &[(u64, u64)]) -> Result<(), IndexerError> {
+    let manager = PgPartitionManager::new(new_pg_connection_pool(db_url, Some(1))?)?;
+    for (table, mut last_partition) in manager.get_table_partitions()? {
+        for pair in epoch_starts.windows(2) {
+            let [(last_epoch, last_epoch_start_cp), (next_epoch, next_epoch_start_cp)] = pair
+            else {
+                unreachable!("Windows of two");
+            };
+            let data = EpochPartitionData::new(
+                *last_epoch,
+                *last_epoch_start_cp,
+                *next_epoch,
+                *next_epoch_start_cp,
+            );
+            manager.advance_table_epoch_partition(table.clone(), last_partition, &data)?;
+            last_partition = *next_epoch;
+        }
+    }
+    Ok(())
+}
+
+fn schema_version(conn: &mut PgConnection) -> Result<String, IndexerError> {
+    Ok(diesel::sql_query(SCHEMA_VERSION_SQL)
+        .get_result::<Name>(conn)?
+        .name)
+}
+
+/// The number of rows in the `COPY` text format file at `path`, and its digest.
+fn summarize(path: &Path) -> Result<(u64, String), IndexerError> {
+    let mut file = File::open(path).map_err(|e| io_error(path, e))?;
+    let mut hasher = Blake2b256::default();
+    let mut rows = 0;
+    let mut buf = vec![0; 1 << 20];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| io_error(path, e))?;
+        if n == 0 {
+            break;
+        }
+        // Newlines in values are escaped, so every newline ends a row.
+        rows += buf[..n].iter().filter(|b| **b == b'\n').count() as u64;
+        hasher.update(&buf[..n]);
+    }
+    Ok((rows, Hex::encode(hasher.finalize().digest)))
+}
+
+/// Run `commands` in a single transaction with `psql`.
+fn psql(db_url: &str, commands: &[String]) -> Result<(), IndexerError> {
+    let mut cmd = Command::new("psql");
+    cmd.args(["--no-psqlrc", "--quiet", "--single-transaction"])
+        .args(["--set", "ON_ERROR_STOP=1"])
+        .arg("--dbname")
+        .arg(db_url);
+    for command in commands {
+        cmd.arg("--command").arg(command);
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| IndexerError::GenericError(format!("Failed to run psql: {e}")))?;
+    if !output.status.success() {
+        return Err(IndexerError::GenericError(format!(
+            "psql failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// `path` as a quoted argument to a `psql` meta-command.
+fn quote_path(path: &Path) -> String {
+    format!("'{}'", path.display().to_string().replace('\'', "''"))
+}
+
+fn connect(db_url: &str) -> Result<PgConnection, IndexerError> {
+    PgConnection::establish(db_url).map_err(|e| {
+        IndexerError::PgConnectionPoolInitError(format!("Failed to connect to the database: {e}"))
+    })
+}
+
+fn io_error(path: &Path, e: io::Error) -> IndexerError {
+    IndexerError::GenericError(format!("{}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_rows() {
+        let checkpoint = StoredCheckpoint {
+            sequence_number: 100,
+            epoch: 3,
+            network_total_transactions: 2000,
+            end_of_epoch: true,
+            ..Default::default()
+        };
+        let key = |columns: &[&str]| columns.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            select_rows("objects", Rows::All, &checkpoint, &key(&["object_id"])),
+            "SELECT * FROM objects ORDER BY object_id"
+        );
+        assert_eq!(
+            select_rows(
+                "events",
+                Rows::UpToCheckpoint("checkpoint_sequence_number"),
+                &checkpoint,
+                &key(&["tx_sequence_number", "event_sequence_number"]),
+            ),
+            "SELECT * FROM events WHERE checkpoint_sequence_number <= 100 \
+             ORDER BY tx_sequence_number, event_sequence_number"
+        );
+        assert_eq!(
+            select_rows(
+                "tx_senders",
+                Rows::UpToTransaction("tx_sequence_number"),
+                &checkpoint,
+                &key(&["sender", "tx_sequence_number"]),
+            ),
+            "SELECT * FROM tx_senders WHERE tx_sequence_number < 2000 \
+             ORDER BY sender, tx_sequence_number"
+        );
+        assert_eq!(
+            select_rows(
+                "epochs",
+                Rows::UpToNextEpoch("epoch"),
+                &checkpoint,
+                &key(&["epoch"])
+            ),
+            "SELECT * FROM epochs WHERE epoch <= 4 ORDER BY epoch"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Forwarding dry runs and dev-inspects to a pool of fullnodes of their own, rather than to the
+//! fullnode that transactions are executed through, so that consumers can simulate transactions
+//! through the reader without simulation load landing on the fullnode that executes them.
+//!
+//! Requests are spread over the fullnodes in turn. A request that fails in a way another
+//! fullnode may not (a transport error, a timeout, or an error the fullnode reports as transient)
+//! is retried on the next fullnode, up to the configured number of retries. Errors in the request
+//! itself, e.g. a transaction that can't be deserialized, are returned as they are.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::types::error::CallError;
+use tracing::warn;
+
+use sui_json_rpc_api::TRANSIENT_ERROR_CODE;
+
+use crate::errors::IndexerError;
+
+/// Fullnodes to forward dry runs and dev-inspects to. Clones share the same fullnodes.
+#[derive(Clone)]
+pub struct DryRunPool {
+    fullnodes: Arc<Vec<(String, HttpClient)>>,
+    /// The fullnode the next request goes to first.
+    next: Arc<AtomicUsize>,
+    /// How many times a request is retried on the next fullnode.
+    retries: usize,
+}
+
+impl DryRunPool {
+    pub fn new(urls: &[String], retries: usize) -> Result<Self, IndexerError> {
+        let fullnodes = urls
+            .iter()
+            .map(|url| Ok((url.clone(), crate::get_http_client(url)?)))
+            .collect::<Result<Vec<_>, IndexerError>>()?;
+        Ok(Self {
+            fullnodes: Arc::new(fullnodes),
+            next: Arc::default(),
+            retries,
+        })
+    }
+
+    /// Send `request` to the next fullnode, and on failures that another fullnode may not have,
+    /// to the ones after it.
+    pub async fn call<T, F, Fut>(&self, request: F) -> Result<T, RpcError>
+    where
+        F: Fn(HttpClient) -> Fut,
+        Fut: Future<Output = Result<T, RpcError>>,
+    {
+        let first = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut attempt = 0;
+        loop {
+            let (url, client) = &self.fullnodes[(first + attempt) % self.fullnodes.len()];
+            match request(client.clone()).await {
+                Err(e) if attempt < self.retries && is_retryable(&e) => {
+                    warn!("Retrying request that failed on fullnode {url}: {e}");
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Whether a request that failed with `error` may succeed on another fullnode.
+fn is_retryable(error: &RpcError) -> bool {
+    match error {
+        RpcError::Transport(_) | RpcError::RequestTimeout | RpcError::RestartNeeded(_) => true,
+        RpcError::Call(CallError::Custom(error)) => error.code() == TRANSIENT_ERROR_CODE,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::ErrorObject;
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&RpcError::Transport(anyhow::anyhow!(
+            "connection refused"
+        ))));
+        assert!(is_retryable(&RpcError::RequestTimeout));
+
+        let transient = ErrorObject::owned(TRANSIENT_ERROR_CODE, "overloaded", None::<()>);
+        assert!(is_retryable(&RpcError::Call(CallError::Custom(transient))));
+
+        let invalid = CallError::InvalidParams(anyhow::anyhow!("invalid transaction bytes"));
+        assert!(!is_retryable(&RpcError::Call(invalid)));
+    }
+}
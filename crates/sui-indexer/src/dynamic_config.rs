@@ -0,0 +1,388 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuration of the indexer's reader that can be changed without restarting it, read from a
+//! YAML file, e.g.:
+//!
+//! ```yaml
+//! log-filter: "info,sui_indexer=debug"
+//! disabled-methods:
+//!   - suix_queryEvents
+//!   - suix_queryTransactionBlocks
+//! method-aliases:
+//!   sui_getTotalSupply: suix_getTotalSupply
+//! admission:
+//!   max-concurrent-requests: 64
+//!   max-queued-requests-per-key: 16
+//!   default-key:
+//!     max-concurrent-requests: 4
+//!   keys:
+//!     - api-key: "..."
+//!       name: explorer
+//!       max-concurrent-requests: 32
+//!       weight: 4
+//! staleness:
+//!   warn-after-ms: 10000
+//!   max-lag-ms:
+//!     suix_getBalance: 60000
+//!     suix_getAllBalances: 60000
+//! query-cache:
+//!   methods:
+//!     - suix_getBalance
+//!     - suix_getLatestSuiSystemState
+//! redaction:
+//!   exempt-api-keys:
+//!     - "..."
+//!   hide-bcs-types:
+//!     - 0x2::coin::Coin
+//!   mask-balances-below: 1000000000
+//! tenancy:
+//!   reject-unknown-keys: true
+//!   tenants:
+//!     - name: usdc
+//!       api-keys:
+//!         - "..."
+//!       packages:
+//!         - "0xabc"
+//! http-caching:
+//!   immutable-methods:
+//!     - sui_getTransactionBlock
+//!     - sui_getCheckpoint
+//!   live-methods:
+//!     - sui_getObject
+//!   live-max-age-secs: 1
+//! query-deadlines:
+//!   default-ms: 30000
+//!   methods:
+//!     suix_queryEvents: 5000
+//! ```
+//!
+//! Readers built with the `fault_injection` feature also take a `fault-injection` section (see
+//! [`crate::fault_injection`]).
+//!
+//! The file is read when the reader starts, and re-read whenever the process receives `SIGHUP`,
+//! or a reload is requested through the admin API, which only listens on localhost.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use sui_json_rpc::admission::{AdmissionConfig, AdmissionControl};
+use sui_json_rpc::http_caching::{HttpCaching, HttpCachingConfig};
+use sui_json_rpc::method_aliases::MethodAliases;
+use sui_json_rpc::method_toggles::MethodToggles;
+use sui_json_rpc::query_cache::{QueryCache, QueryCacheConfig};
+use sui_json_rpc::redaction::{Redaction, RedactionConfig};
+use sui_json_rpc::staleness::{Staleness, StalenessConfig};
+use sui_json_rpc::tenancy::{Tenancy, TenancyConfig};
+use telemetry_subscribers::TracingHandle;
+
+use crate::errors::IndexerError;
+use crate::query_watchdog::{QueryDeadlinesConfig, QueryWatchdog};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct DynamicConfig {
+    /// Tracing filter directives, in the same format as `RUST_LOG`. The filter is left as is if
+    /// this is unset.
+    pub log_filter: Option<String>,
+    /// RPC methods to reject, e.g. to shed expensive queries. Methods that are removed from this
+    /// list are served again on reload.
+    pub disabled_methods: BTreeSet<String>,
+    /// RPC methods to serve under other names, by the alias they are called by, e.g. to keep
+    /// serving clients that call methods by deprecated names.
+    pub method_aliases: BTreeMap<String, String>,
+    /// Limits on the requests in flight for each API key (see [`sui_json_rpc::admission`]).
+    /// Requests are not limited if this is unset.
+    pub admission: Option<AdmissionConfig>,
+    /// How to serve requests while ingestion is paused or lagging (see
+    /// [`sui_json_rpc::staleness`]).
+    pub staleness: StalenessConfig,
+    /// Methods whose responses are cached until the indexer's checkpoint watermark advances (see
+    /// [`sui_json_rpc::query_cache`]).
+    pub query_cache: QueryCacheConfig,
+    /// Parts of responses hidden from clients without an exempt API key (see
+    /// [`sui_json_rpc::redaction`]).
+    pub redaction: RedactionConfig,
+    /// Packages whose events and objects are visible to the requests of each tenant sharing the
+    /// reader (see [`sui_json_rpc::tenancy`]).
+    pub tenancy: TenancyConfig,
+    /// HTTP caching headers sent for each method, for a CDN in front of the reader (see
+    /// [`sui_json_rpc::http_caching`]).
+    pub http_caching: HttpCachingConfig,
+    /// How long the queries of each method can run before they are cancelled (see
+    /// [`crate::query_watchdog`]).
+    pub query_deadlines: QueryDeadlinesConfig,
+    /// Faults injected into the reader's database queries (see [`crate::fault_injection`]).
+    #[cfg(feature = "fault_injection")]
+    pub fault_injection: crate::fault_injection::FaultInjectionConfig,
+}
+
+impl DynamicConfig {
+    pub fn parse(yaml: &str) -> Result<Self, IndexerError> {
+        serde_yaml::from_str(yaml)
+            .map_err(|e| IndexerError::InvalidArgumentError(format!("Invalid dynamic config: {e}")))
+    }
+}
+
+/// Handles to the parts of the running reader's RPC server that the dynamic config configures.
+#[derive(Clone, Debug, Default)]
+pub struct ServerControls {
+    pub method_toggles: MethodToggles,
+    pub method_aliases: MethodAliases,
+    pub admission: AdmissionControl,
+    pub staleness: Staleness,
+    pub query_cache: QueryCache,
+    pub redaction: Redaction,
+    pub tenancy: Tenancy,
+    pub http_caching: HttpCaching,
+    pub query_watchdog: QueryWatchdog,
+    #[cfg(feature = "fault_injection")]
+    pub fault_injection: crate::fault_injection::FaultInjection,
+}
+
+/// Applies the dynamic config in a file to the parts of the running reader it configures.
+#[derive(Clone)]
+pub struct DynamicConfigReloader {
+    path: PathBuf,
+    tracing: Option<Arc<TracingHandle>>,
+    controls: ServerControls,
+}
+
+impl DynamicConfigReloader {
+    pub fn new(
+        path: PathBuf,
+        tracing: Option<Arc<TracingHandle>>,
+        controls: ServerControls,
+    ) -> Self {
+        Self {
+            path,
+            tracing,
+            controls,
+        }
+    }
+
+    /// Re-read the config file and apply it. Nothing is applied if the file cannot be read or
+    /// parsed, so a bad edit leaves the previous config in place.
+    pub fn reload(&self) -> Result<DynamicConfig, IndexerError> {
+        let yaml = std::fs::read_to_string(&self.path).map_err(|e| {
+            IndexerError::GenericError(format!(
+                "Failed to read dynamic config file {}: {e}",
+                self.path.display()
+            ))
+        })?;
+        let config = DynamicConfig::parse(&yaml)?;
+
+        if let (Some(filter), Some(tracing)) = (&config.log_filter, &self.tracing) {
+            tracing.update_log(filter).map_err(|e| {
+                IndexerError::InvalidArgumentError(format!("Invalid log filter {filter:?}: {e}"))
+            })?;
+        }
+        // Applied before the other controls, so that invalid tenants or types leave them
+        // unchanged too.
+        self.controls
+            .tenancy
+            .set_config(config.tenancy.clone())
+            .map_err(|e| IndexerError::InvalidArgumentError(format!("Invalid tenancy: {e}")))?;
+        self.controls
+            .redaction
+            .set_config(config.redaction.clone())
+            .map_err(|e| IndexerError::InvalidArgumentError(format!("Invalid redaction: {e}")))?;
+        #[cfg(feature = "fault_injection")]
+        self.controls
+            .fault_injection
+            .set_config(config.fault_injection.clone())
+            .map_err(|e| {
+                IndexerError::InvalidArgumentError(format!("Invalid fault injection: {e}"))
+            })?;
+        self.controls
+            .method_toggles
+            .set_disabled(config.disabled_methods.iter().cloned());
+        self.controls
+            .method_aliases
+            .set_aliases(config.method_aliases.clone());
+        self.controls.admission.set_config(config.admission.clone());
+        self.controls.staleness.set_config(config.staleness.clone());
+        self.controls
+            .query_cache
+            .set_config(config.query_cache.clone());
+        self.controls
+            .http_caching
+            .set_config(config.http_caching.clone());
+        self.controls
+            .query_watchdog
+            .set_config(config.query_deadlines.clone());
+
+        info!(
+            "Applied dynamic config from {}: {:?}",
+            self.path.display(),
+            config
+        );
+        Ok(config)
+    }
+
+    /// Reload the config whenever the process receives `SIGHUP`. Failed reloads are logged, and
+    /// keep the previous config.
+    #[cfg(unix)]
+    pub fn reload_on_sighup(&self) -> Result<tokio::task::JoinHandle<()>, IndexerError> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sighup = signal(SignalKind::hangup())
+            .map_err(|e| IndexerError::GenericError(format!("Failed to listen for SIGHUP: {e}")))?;
+        let reloader = self.clone();
+        Ok(tokio::spawn(async move {
+            while sighup.recv().await.is_some() {
+                info!("Received SIGHUP, reloading dynamic config");
+                if let Err(e) = reloader.reload() {
+                    error!("Failed to reload dynamic config: {e}");
+                }
+            }
+        }))
+    }
+}
+
+/// Builds the router serving the dynamic config admin API:
+///
+/// - `POST /config/reload` re-reads the config file, responding with the config applied.
+pub fn config_admin_router(reloader: DynamicConfigReloader) -> Router {
+    Router::new()
+        .route("/config/reload", post(reload_config))
+        .with_state(reloader)
+}
+
+/// Serve the dynamic config admin API on `port`. It is only bound to localhost, because it is
+/// unauthenticated.
+pub async fn start_config_admin_server(
+    reloader: DynamicConfigReloader,
+    port: u16,
+) -> Result<tokio::task::JoinHandle<()>, IndexerError> {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+    let server = axum::Server::try_bind(&addr)
+        .map_err(|e| {
+            IndexerError::GenericError(format!("Failed to bind config admin server: {e}"))
+        })?
+        .serve(config_admin_router(reloader).into_make_service());
+
+    info!(
+        "Sui Indexer config admin server listening on {}",
+        server.local_addr()
+    );
+    Ok(tokio::spawn(async move { server.await.unwrap() }))
+}
+
+async fn reload_config(
+    State(reloader): State<DynamicConfigReloader>,
+) -> Result<Json<DynamicConfig>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || reloader.reload())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .map_err(|e| match e {
+            IndexerError::InvalidArgumentError(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::base_types::ObjectID;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_dynamic_config() {
+        let config = DynamicConfig::parse(
+            "log-filter: info,sui_indexer=debug\n\
+             disabled-methods: [suix_queryEvents]\n\
+             method-aliases: {sui_getTotalSupply: suix_getTotalSupply}\n",
+        )
+        .unwrap();
+        assert_eq!(config.log_filter.as_deref(), Some("info,sui_indexer=debug"));
+        assert_eq!(
+            config.disabled_methods,
+            BTreeSet::from(["suix_queryEvents".to_string()])
+        );
+        assert_eq!(
+            config.method_aliases["sui_getTotalSupply"],
+            "suix_getTotalSupply"
+        );
+
+        assert_eq!(
+            DynamicConfig::parse("{}").unwrap(),
+            DynamicConfig::default()
+        );
+        assert!(DynamicConfig::parse("rate-limit: 10\n").is_err());
+
+        let config = DynamicConfig::parse(
+            "admission:\n  \
+               max-concurrent-requests: 8\n  \
+               max-queued-requests-per-key: 2\n  \
+               default-key: {max-concurrent-requests: 1}\n  \
+               keys: [{api-key: secret, name: explorer, max-concurrent-requests: 4, weight: 3}]\n",
+        )
+        .unwrap();
+        let admission = config.admission.unwrap();
+        assert_eq!(admission.default_key.weight, 1);
+        assert_eq!(admission.keys[0].weight, 3);
+
+        let config = DynamicConfig::parse(
+            "staleness:\n  \
+               warn-after-ms: 1000\n  \
+               max-lag-ms: {suix_getBalance: 5000}\n",
+        )
+        .unwrap();
+        assert_eq!(config.staleness.warn_after_ms, Some(1000));
+        assert_eq!(config.staleness.max_lag_ms["suix_getBalance"], 5000);
+
+        let config = DynamicConfig::parse("query-cache:\n  methods: [suix_getBalance]\n").unwrap();
+        assert!(config.query_cache.methods.contains("suix_getBalance"));
+
+        let config = DynamicConfig::parse(
+            "redaction:\n  \
+               hide-bcs-types: [0x2::coin::Coin]\n  \
+               mask-balances-below: 1000\n",
+        )
+        .unwrap();
+        assert!(config.redaction.hide_bcs_types.contains("0x2::coin::Coin"));
+        assert_eq!(config.redaction.mask_balances_below, Some(1000));
+
+        let config = DynamicConfig::parse(
+            "tenancy:\n  \
+               reject-unknown-keys: true\n  \
+               tenants: [{name: usdc, api-keys: [secret], packages: [\"0xabc\"]}]\n",
+        )
+        .unwrap();
+        assert!(config.tenancy.reject_unknown_keys);
+        assert_eq!(config.tenancy.tenants[0].name, "usdc");
+        assert_eq!(
+            config.tenancy.tenants[0].packages,
+            BTreeSet::from([ObjectID::from_hex_literal("0xabc").unwrap()])
+        );
+
+        let config = DynamicConfig::parse(
+            "http-caching:\n  \
+               immutable-methods: [sui_getTransactionBlock]\n  \
+               live-max-age-secs: 2\n",
+        )
+        .unwrap();
+        assert!(config
+            .http_caching
+            .immutable_methods
+            .contains("sui_getTransactionBlock"));
+        assert_eq!(config.http_caching.live_max_age_secs, 2);
+
+        let config = DynamicConfig::parse(
+            "query-deadlines:\n  \
+               default-ms: 30000\n  \
+               methods: {suix_queryEvents: 5000}\n",
+        )
+        .unwrap();
+        assert_eq!(config.query_deadlines.default_ms, Some(30000));
+        assert_eq!(config.query_deadlines.methods["suix_queryEvents"], 5000);
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resizing connection pools around epoch boundaries. Committing the end of an epoch (persisting
+//! it, and partitioning tables for the next one), and the analytical work that follows it, need
+//! more writer connections than ingesting checkpoints does, while the reader can spare some of
+//! its own. While the writer processes an epoch boundary, and for a while after, the pools of
+//! this process are resized to their epoch sizes, and then back. Pools of other processes sharing
+//! the database are left as they are.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tracing::info;
+
+use crate::db::PgConnectionPool;
+use crate::metrics::IndexerMetrics;
+
+struct ManagedPool {
+    name: &'static str,
+    pool: PgConnectionPool,
+    size: u32,
+    epoch_size: u32,
+}
+
+/// Resizes the pools it manages while the writer processes epoch boundaries. Clones share the
+/// same pools.
+#[derive(Clone)]
+pub struct EpochPoolController {
+    pools: Arc<Mutex<Vec<ManagedPool>>>,
+    /// How long pools keep their epoch sizes once an epoch has been committed.
+    hold: Duration,
+    /// Incremented as each epoch starts being processed, so that pools are only restored once the
+    /// latest epoch's hold is over.
+    epochs_started: Arc<AtomicU64>,
+    metrics: IndexerMetrics,
+}
+
+impl EpochPoolController {
+    pub fn new(hold: Duration, metrics: IndexerMetrics) -> Self {
+        Self {
+            pools: Default::default(),
+            hold,
+            epochs_started: Default::default(),
+            metrics,
+        }
+    }
+
+    /// Resize `pool`, labelled `name` in metrics, to `epoch_size` while epoch boundaries are
+    /// processed, and back to its current size afterwards.
+    pub fn manage(&self, name: &'static str, pool: PgConnectionPool, epoch_size: u32) {
+        let size = pool.max_size();
+        info!("Resizing DB connection pool {name} from {size} to {epoch_size} at epoch boundaries");
+        self.pools.lock().unwrap().push(ManagedPool {
+            name,
+            pool,
+            size,
+            epoch_size,
+        });
+    }
+
+    /// Resize the pools to their epoch sizes, as the writer starts committing the end of an
+    /// epoch.
+    pub fn epoch_started(&self) {
+        self.epochs_started.fetch_add(1, Ordering::SeqCst);
+        self.resize(|pool| pool.epoch_size);
+    }
+
+    /// Resize the pools back once the hold after the epoch that has just been committed is over,
+    /// unless another epoch starts in the meantime.
+    pub fn epoch_committed(&self) {
+        let epochs_started = self.epochs_started.load(Ordering::SeqCst);
+        let controller = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(controller.hold).await;
+            if controller.epochs_started.load(Ordering::SeqCst) == epochs_started {
+                controller.resize(|pool| pool.size);
+            }
+        });
+    }
+
+    fn resize(&self, size: impl Fn(&ManagedPool) -> u32) {
+        for pool in self.pools.lock().unwrap().iter() {
+            let size = size(pool);
+            if pool.pool.max_size() == size {
+                continue;
+            }
+            info!("Resizing DB connection pool {} to {size}", pool.name);
+            pool.pool.resize(size);
+            self.metrics
+                .db_conn_pool_max_size
+                .with_label_values(&[pool.name])
+                .set(size as i64);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Records of owned-object equivocation: attempts to use the same version of an owned object in
+//! two different transactions. Only one of them can be certified, so equivocation never shows up
+//! in checkpoints. Instead, it is detected from the errors validators return when the indexer
+//! forwards transactions to the fullnode for execution: a transaction rejected because some of
+//! its objects were locked by other transactions comes back with the conflicting transactions
+//! and the object versions they locked.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use diesel::RunQueryDsl;
+use fastcrypto::encoding::Base64;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::types::error::CallError;
+use tracing::warn;
+
+use sui_json_rpc_api::TRANSACTION_EXECUTION_CLIENT_ERROR_CODE;
+use sui_types::base_types::ObjectRef;
+use sui_types::crypto::default_hash;
+use sui_types::digests::TransactionDigest;
+use sui_types::transaction::{TransactionData, TransactionDataAPI};
+
+use crate::db::PgConnectionPool;
+use crate::errors::{Context, IndexerError};
+use crate::models::equivocations::{Equivocation, StoredEquivocation};
+use crate::schema::equivocations;
+use crate::store::diesel_macro::transactional_blocking_with_retry;
+
+const MAX_WRITE_ELAPSED: Duration = Duration::from_secs(60);
+
+/// Writes to the `equivocations` table. The indexer's reader only holds read-only connections,
+/// so this needs its own (writable) pool.
+#[derive(Clone)]
+pub struct EquivocationStore {
+    blocking_cp: PgConnectionPool,
+}
+
+impl EquivocationStore {
+    pub fn new(blocking_cp: PgConnectionPool) -> Self {
+        Self { blocking_cp }
+    }
+
+    pub fn record(&self, equivocations: Vec<Equivocation>) -> Result<usize, IndexerError> {
+        if equivocations.is_empty() {
+            return Ok(0);
+        }
+
+        let stored = equivocations
+            .into_iter()
+            .map(StoredEquivocation::from)
+            .collect::<Vec<_>>();
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(equivocations::table)
+                    .values(&stored)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write equivocations to PostgresDB")
+            },
+            MAX_WRITE_ELAPSED
+        )
+    }
+
+    /// Record the equivocation reported by `error`, if the fullnode rejected the transaction in
+    /// `tx_bytes` because its objects were locked by other transactions. Failures to record are
+    /// not surfaced to the client, whose request has failed anyway.
+    pub fn record_execution_error(&self, tx_bytes: &Base64, error: &RpcError) {
+        let Some(equivocations) = equivocations_from_error(tx_bytes, error, now_ms()) else {
+            return;
+        };
+
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = store.record(equivocations) {
+                warn!("Failed to record equivocation: {e}");
+            }
+        });
+    }
+}
+
+/// The equivocations described by an error returned when executing the transaction in
+/// `tx_bytes`, or `None` if the error is not an object lock conflict.
+pub fn equivocations_from_error(
+    tx_bytes: &Base64,
+    error: &RpcError,
+    timestamp_ms: u64,
+) -> Option<Vec<Equivocation>> {
+    let RpcError::Call(CallError::Custom(error)) = error else {
+        return None;
+    };
+    if error.code() != TRANSACTION_EXECUTION_CLIENT_ERROR_CODE {
+        return None;
+    }
+
+    // The fullnode reports the objects each conflicting transaction locked, keyed by digest.
+    let conflicts: BTreeMap<TransactionDigest, Vec<ObjectRef>> =
+        serde_json::from_str(error.data()?.get()).ok()?;
+    let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.to_vec().ok()?).ok()?;
+    let transaction_digest = TransactionDigest::new(default_hash(&tx_data));
+    let sender = tx_data.sender();
+
+    let equivocations: Vec<_> = conflicts
+        .into_iter()
+        .filter(|(conflicting_digest, _)| *conflicting_digest != transaction_digest)
+        .flat_map(|(conflicting_digest, objects)| {
+            objects
+                .into_iter()
+                .map(move |(object_id, version, _)| Equivocation {
+                    object_id,
+                    version,
+                    sender,
+                    transaction_digest,
+                    conflicting_digest,
+                    timestamp_ms,
+                })
+        })
+        .collect();
+
+    (!equivocations.is_empty()).then_some(equivocations)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpsee::types::ErrorObject;
+    use sui_types::base_types::{random_object_ref, SuiAddress};
+    use sui_types::transaction::TEST_ONLY_GAS_UNIT_FOR_TRANSFER;
+
+    use super::*;
+
+    #[test]
+    fn test_equivocations_from_error() {
+        let sender = SuiAddress::random_for_testing_only();
+        let gas = random_object_ref();
+        let tx_data = TransactionData::new_transfer_sui(
+            SuiAddress::random_for_testing_only(),
+            sender,
+            None,
+            gas,
+            TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+            1000,
+        );
+        let tx_bytes = Base64::from_bytes(&bcs::to_bytes(&tx_data).unwrap());
+        let conflicting_digest = TransactionDigest::random();
+
+        let conflicts = BTreeMap::from([(conflicting_digest, vec![gas])]);
+        let error = RpcError::Call(CallError::Custom(ErrorObject::owned(
+            TRANSACTION_EXECUTION_CLIENT_ERROR_CODE,
+            "Failed to sign transaction by a quorum of validators because of locked objects.",
+            Some(conflicts),
+        )));
+
+        let equivocations = equivocations_from_error(&tx_bytes, &error, 42).unwrap();
+        assert_eq!(
+            equivocations,
+            vec![Equivocation {
+                object_id: gas.0,
+                version: gas.1,
+                sender,
+                transaction_digest: TransactionDigest::new(default_hash(&tx_data)),
+                conflicting_digest,
+                timestamp_ms: 42,
+            }]
+        );
+
+        let other = RpcError::Call(CallError::Custom(ErrorObject::owned(
+            TRANSACTION_EXECUTION_CLIENT_ERROR_CODE,
+            "Transaction execution failed",
+            None::<()>,
+        )));
+        assert!(equivocations_from_error(&tx_bytes, &other, 42).is_none());
+    }
+}
@@ -4,9 +4,12 @@
 use fastcrypto::error::FastCryptoError;
 use jsonrpsee::core::Error as RpcError;
 use jsonrpsee::types::error::CallError;
+use jsonrpsee::types::ErrorObject;
+use serde_json::json;
 use sui_json_rpc::name_service::NameServiceError;
 use thiserror::Error;
 
+use sui_json_rpc_api::QUERY_TIMEOUT_ERROR_CODE;
 use sui_types::base_types::ObjectIDParseError;
 use sui_types::error::{SuiError, SuiObjectResponseError, UserInputError};
 
@@ -67,6 +70,9 @@ pub enum IndexerError {
     #[error("Indexer failed to commit changes to PostgresDB with error: `{0}`")]
     PostgresWriteError(String),
 
+    #[error("Indexer's writer lease was taken over by another writer: `{0}`")]
+    WriterLeaseLost(String),
+
     #[error(transparent)]
     PostgresError(#[from] diesel::result::Error),
 
@@ -88,6 +94,9 @@ pub enum IndexerError {
     #[error("Indexer generic error: `{0}`")]
     GenericError(String),
 
+    #[error("Indexer received a checkpoint that does not extend the indexed chain: `{0}`")]
+    CheckpointForkError(String),
+
     #[error("Indexer failed to resolve object to move struct with error: `{0}`")]
     ResolveMoveStructError(String),
 
@@ -109,6 +118,9 @@ pub enum IndexerError {
     #[error("Invalid argument with error: `{0}`")]
     InvalidArgumentError(String),
 
+    #[error("{0}")]
+    NotFound(String),
+
     #[error(transparent)]
     UserInputError(#[from] UserInputError),
 
@@ -129,6 +141,11 @@ pub enum IndexerError {
 
     #[error(transparent)]
     NameServiceError(#[from] NameServiceError),
+
+    #[error(
+        "Query for `{method}` was cancelled after running past its deadline of {deadline_ms}ms"
+    )]
+    QueryTimeout { method: String, deadline_ms: u64 },
 }
 
 pub trait Context<T> {
@@ -143,7 +160,27 @@ impl<T> Context<T> for Result<T, IndexerError> {
 
 impl From<IndexerError> for RpcError {
     fn from(e: IndexerError) -> Self {
-        RpcError::Call(CallError::Failed(e.into()))
+        match e {
+            IndexerError::QueryTimeout {
+                ref method,
+                deadline_ms,
+            } => RpcError::Call(CallError::Custom(ErrorObject::owned(
+                QUERY_TIMEOUT_ERROR_CODE,
+                e.to_string(),
+                Some(json!({ "method": method, "deadlineMs": deadline_ms })),
+            ))),
+            e => RpcError::Call(CallError::Failed(e.into())),
+        }
+    }
+}
+
+impl From<IndexerError> for tonic::Status {
+    fn from(e: IndexerError) -> Self {
+        match e {
+            IndexerError::InvalidArgumentError(msg) => tonic::Status::invalid_argument(msg),
+            IndexerError::NotFound(msg) => tonic::Status::not_found(msg),
+            e => tonic::Status::internal(e.to_string()),
+        }
     }
 }
 
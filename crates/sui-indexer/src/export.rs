@@ -0,0 +1,644 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Exporting tables over a range of checkpoints to CSV or Parquet files, so that analytics can
+//! run offline rather than against the database the indexer serves from.
+//!
+//! Each table is exported to a directory of its own, with a directory per epoch, holding a file
+//! named after the range of checkpoints it covers (`<table>/epoch_<epoch>/<first>_<end>.parquet`,
+//! where `end` is one past the last checkpoint), the same layout as `sui-analytics-indexer`'s.
+//! Epochs without rows in a table get no file. Files are written under a temporary name, and
+//! only renamed once complete.
+//!
+//! Rows are read in batches, ordered by primary key, so that the export holds one batch in
+//! memory at a time, and only runs small queries against the database.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+use clap::Args;
+use diesel::{
+    BoolExpressionMethods, Connection, ExpressionMethods, OptionalExtension, PgConnection,
+    QueryDsl, RunQueryDsl,
+};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use sui_json_rpc_types::BalanceChange;
+use sui_types::base_types::SuiAddress;
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::transaction::{SenderSignedData, TransactionDataAPI};
+use tracing::info;
+
+use crate::compression::decompress;
+use crate::errors::IndexerError;
+use crate::schema::{checkpoints, epochs, events, transactions};
+
+const EPOCH_DIR_PREFIX: &str = "epoch_";
+
+#[derive(Args, Clone, Debug)]
+pub struct ExportConfig {
+    /// Directory to export to.
+    #[clap(long)]
+    pub dir: PathBuf,
+    #[clap(long, value_enum, default_value = "parquet")]
+    pub format: ExportFormat,
+    /// Tables to export, separated by commas.
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "transactions,events,balance-changes"
+    )]
+    pub tables: Vec<ExportedTable>,
+    #[clap(long, default_value = "0")]
+    pub start_checkpoint: u64,
+    /// Last checkpoint to export, the latest one indexed if not set.
+    #[clap(long)]
+    pub end_checkpoint: Option<u64>,
+    /// How many rows are read from the database at a time.
+    #[clap(long, default_value = "10000")]
+    pub batch_size: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportedTable {
+    /// One row per transaction, with what its effects say about its execution.
+    Transactions,
+    /// One row per event, without its contents.
+    Events,
+    /// One row per change to an owner's balance of a coin type made by a transaction.
+    BalanceChanges,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Int(i64),
+    Str(String),
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Int,
+    Str,
+}
+
+type Row = Vec<Value>;
+
+/// Where a table's next batch starts: after the row with this primary key.
+type Cursor = (i64, i64);
+
+type TransactionRow = (i64, Vec<u8>, Vec<u8>, Vec<u8>, i64, i64, i16, i16, i16);
+type EventRow = (
+    i64,
+    i64,
+    Vec<u8>,
+    i64,
+    Vec<Option<Vec<u8>>>,
+    Vec<u8>,
+    String,
+    String,
+    i64,
+);
+type BalanceChangesRow = (i64, Vec<u8>, i64, i64, Vec<Option<Vec<u8>>>);
+
+impl ExportedTable {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Transactions => "transactions",
+            Self::Events => "events",
+            Self::BalanceChanges => "balance_changes",
+        }
+    }
+
+    fn schema(&self) -> &'static [(&'static str, Kind)] {
+        match self {
+            Self::Transactions => &[
+                ("tx_sequence_number", Kind::Int),
+                ("transaction_digest", Kind::Str),
+                ("checkpoint_sequence_number", Kind::Int),
+                ("timestamp_ms", Kind::Int),
+                ("transaction_kind", Kind::Int),
+                ("sender", Kind::Str),
+                ("success", Kind::Int),
+                ("success_command_count", Kind::Int),
+                ("gas_price", Kind::Int),
+                ("computation_cost", Kind::Int),
+                ("storage_cost", Kind::Int),
+                ("storage_rebate", Kind::Int),
+            ],
+            Self::Events => &[
+                ("tx_sequence_number", Kind::Int),
+                ("event_sequence_number", Kind::Int),
+                ("transaction_digest", Kind::Str),
+                ("checkpoint_sequence_number", Kind::Int),
+                ("timestamp_ms", Kind::Int),
+                ("sender", Kind::Str),
+                ("package", Kind::Str),
+                ("module", Kind::Str),
+                ("event_type", Kind::Str),
+            ],
+            Self::BalanceChanges => &[
+                ("tx_sequence_number", Kind::Int),
+                ("transaction_digest", Kind::Str),
+                ("checkpoint_sequence_number", Kind::Int),
+                ("timestamp_ms", Kind::Int),
+                ("owner", Kind::Str),
+                ("coin_type", Kind::Str),
+                ("amount", Kind::Str),
+            ],
+        }
+    }
+
+    /// The next batch of the table's rows in checkpoints `first..=last`, after `cursor`, and the
+    /// cursor to read the batch after it from, if there may be one.
+    fn read_batch(
+        &self,
+        conn: &mut PgConnection,
+        (first, last): (i64, i64),
+        cursor: Cursor,
+        batch_size: usize,
+    ) -> Result<(Vec<Row>, Option<Cursor>), IndexerError> {
+        let limit = batch_size as i64;
+        let (rows, next, read) = match self {
+            Self::Transactions => {
+                let batch: Vec<TransactionRow> = transactions::table
+                    .filter(transactions::checkpoint_sequence_number.between(first, last))
+                    .filter(transactions::tx_sequence_number.gt(cursor.0))
+                    .order(transactions::tx_sequence_number.asc())
+                    .limit(limit)
+                    .select((
+                        transactions::tx_sequence_number,
+                        transactions::transaction_digest,
+                        transactions::raw_transaction,
+                        transactions::raw_effects,
+                        transactions::checkpoint_sequence_number,
+                        transactions::timestamp_ms,
+                        transactions::transaction_kind,
+                        transactions::success_command_count,
+                        transactions::compression,
+                    ))
+                    .load(conn)?;
+                let (next, read) = (batch.last().map(|row| (row.0, 0)), batch.len());
+                let rows = batch
+                    .into_iter()
+                    .map(transaction_row)
+                    .collect::<Result<_, _>>()?;
+                (rows, next, read)
+            }
+            Self::Events => {
+                let batch: Vec<EventRow> = events::table
+                    .filter(events::checkpoint_sequence_number.between(first, last))
+                    .filter(
+                        events::tx_sequence_number
+                            .gt(cursor.0)
+                            .or(events::tx_sequence_number
+                                .eq(cursor.0)
+                                .and(events::event_sequence_number.gt(cursor.1))),
+                    )
+                    .order((
+                        events::tx_sequence_number.asc(),
+                        events::event_sequence_number.asc(),
+                    ))
+                    .limit(limit)
+                    .select((
+                        events::tx_sequence_number,
+                        events::event_sequence_number,
+                        events::transaction_digest,
+                        events::checkpoint_sequence_number,
+                        events::senders,
+                        events::package,
+                        events::module,
+                        events::event_type,
+                        events::timestamp_ms,
+                    ))
+                    .load(conn)?;
+                let (next, read) = (batch.last().map(|row| (row.0, row.1)), batch.len());
+                let rows = batch.into_iter().map(event_row).collect::<Result<_, _>>()?;
+                (rows, next, read)
+            }
+            Self::BalanceChanges => {
+                let batch: Vec<BalanceChangesRow> = transactions::table
+                    .filter(transactions::checkpoint_sequence_number.between(first, last))
+                    .filter(transactions::tx_sequence_number.gt(cursor.0))
+                    .order(transactions::tx_sequence_number.asc())
+                    .limit(limit)
+                    .select((
+                        transactions::tx_sequence_number,
+                        transactions::transaction_digest,
+                        transactions::checkpoint_sequence_number,
+                        transactions::timestamp_ms,
+                        transactions::balance_changes,
+                    ))
+                    .load(conn)?;
+                let (next, read) = (batch.last().map(|row| (row.0, 0)), batch.len());
+                let mut rows = vec![];
+                for row in batch {
+                    rows.extend(balance_change_rows(row)?);
+                }
+                (rows, next, read)
+            }
+        };
+        // A short batch is the last one.
+        Ok((rows, next.filter(|_| read == batch_size)))
+    }
+}
+
+fn digest(bytes: &[u8]) -> Result<String, IndexerError> {
+    TransactionDigest::try_from(bytes)
+        .map(|digest| digest.to_string())
+        .map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to parse transaction digest: {e}"
+            ))
+        })
+}
+
+fn address(bytes: Vec<u8>) -> Result<String, IndexerError> {
+    SuiAddress::from_bytes(bytes)
+        .map(|address| address.to_string())
+        .map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to parse address: {e}"
+            ))
+        })
+}
+
+fn transaction_row(row: TransactionRow) -> Result<Row, IndexerError> {
+    let (
+        tx_sequence_number,
+        transaction_digest,
+        raw_transaction,
+        raw_effects,
+        checkpoint_sequence_number,
+        timestamp_ms,
+        transaction_kind,
+        success_command_count,
+        compression,
+    ) = row;
+    let transaction: SenderSignedData = bcs::from_bytes(&decompress(compression, raw_transaction)?)
+        .map_err(|e| IndexerError::SerdeError(format!("Failed to deserialize transaction: {e}")))?;
+    let effects: TransactionEffects = bcs::from_bytes(&decompress(compression, raw_effects)?)
+        .map_err(|e| IndexerError::SerdeError(format!("Failed to deserialize effects: {e}")))?;
+    let data = transaction.transaction_data();
+    let gas = effects.gas_cost_summary();
+    Ok(vec![
+        Value::Int(tx_sequence_number),
+        Value::Str(digest(&transaction_digest)?),
+        Value::Int(checkpoint_sequence_number),
+        Value::Int(timestamp_ms),
+        Value::Int(transaction_kind as i64),
+        Value::Str(data.sender().to_string()),
+        Value::Int(effects.status().is_ok() as i64),
+        Value::Int(success_command_count as i64),
+        Value::Int(data.gas_price() as i64),
+        Value::Int(gas.computation_cost as i64),
+        Value::Int(gas.storage_cost as i64),
+        Value::Int(gas.storage_rebate as i64),
+    ])
+}
+
+fn event_row(row: EventRow) -> Result<Row, IndexerError> {
+    let (
+        tx_sequence_number,
+        event_sequence_number,
+        transaction_digest,
+        checkpoint_sequence_number,
+        senders,
+        package,
+        module,
+        event_type,
+        timestamp_ms,
+    ) = row;
+    let sender = match senders.into_iter().flatten().next() {
+        Some(sender) => address(sender)?,
+        None => String::new(),
+    };
+    Ok(vec![
+        Value::Int(tx_sequence_number),
+        Value::Int(event_sequence_number),
+        Value::Str(digest(&transaction_digest)?),
+        Value::Int(checkpoint_sequence_number),
+        Value::Int(timestamp_ms),
+        Value::Str(sender),
+        Value::Str(address(package)?),
+        Value::Str(module),
+        Value::Str(event_type),
+    ])
+}
+
+fn balance_change_rows(row: BalanceChangesRow) -> Result<Vec<Row>, IndexerError> {
+    let (tx_sequence_number, transaction_digest, checkpoint_sequence_number, timestamp_ms, changes) =
+        row;
+    let transaction_digest = digest(&transaction_digest)?;
+    changes
+        .into_iter()
+        .flatten()
+        .map(|change| {
+            let change: BalanceChange = bcs::from_bytes(&change).map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Can't convert balance_change bytes into BalanceChange. tx_digest={transaction_digest} Error: {e}"
+                ))
+            })?;
+            let owner = change
+                .owner
+                .get_owner_address()
+                .map_or_else(|_| change.owner.to_string(), |owner| owner.to_string());
+            Ok(vec![
+                Value::Int(tx_sequence_number),
+                Value::Str(transaction_digest.clone()),
+                Value::Int(checkpoint_sequence_number),
+                Value::Int(timestamp_ms),
+                Value::Str(owner),
+                Value::Str(change.coin_type.to_canonical_string(/* with_prefix */ true)),
+                Value::Str(change.amount.to_string()),
+            ])
+        })
+        .collect()
+}
+
+/// A file being exported to, created once the first rows are written to it.
+struct ExportFile {
+    path: PathBuf,
+    format: ExportFormat,
+    schema: &'static [(&'static str, Kind)],
+    writer: Option<Writer>,
+}
+
+enum Writer {
+    Csv(csv::Writer<File>),
+    Parquet(ArrowWriter<File>),
+}
+
+impl ExportFile {
+    fn new(path: PathBuf, format: ExportFormat, schema: &'static [(&'static str, Kind)]) -> Self {
+        Self {
+            path,
+            format,
+            schema,
+            writer: None,
+        }
+    }
+
+    fn temp_path(&self) -> PathBuf {
+        self.path
+            .with_extension(format!("{}.tmp", self.format.extension()))
+    }
+
+    fn create(&self) -> anyhow::Result<Writer> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = File::create(self.temp_path())?;
+        Ok(match self.format {
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(file);
+                writer.write_record(self.schema.iter().map(|(name, _)| name))?;
+                Writer::Csv(writer)
+            }
+            ExportFormat::Parquet => {
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                let schema = record_batch(self.schema, vec![])?.schema();
+                Writer::Parquet(ArrowWriter::try_new(file, schema, Some(properties))?)
+            }
+        })
+    }
+
+    fn write(&mut self, rows: Vec<Row>) -> anyhow::Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        if self.writer.is_none() {
+            self.writer = Some(self.create()?);
+        }
+        let Some(writer) = &mut self.writer else {
+            unreachable!("The writer was just created");
+        };
+        match writer {
+            Writer::Csv(writer) => {
+                for row in rows {
+                    writer.write_record(row.into_iter().map(|value| match value {
+                        Value::Int(value) => value.to_string(),
+                        Value::Str(value) => value,
+                    }))?;
+                }
+            }
+            Writer::Parquet(writer) => writer.write(&record_batch(self.schema, rows)?)?,
+        }
+        Ok(())
+    }
+
+    /// Finish the file, returning whether there was anything to write to it.
+    fn finish(self) -> anyhow::Result<bool> {
+        let temp_path = self.temp_path();
+        match self.writer {
+            None => return Ok(false),
+            Some(Writer::Csv(mut writer)) => writer.flush()?,
+            Some(Writer::Parquet(writer)) => {
+                writer.close()?;
+            }
+        }
+        fs::rename(temp_path, &self.path)?;
+        Ok(true)
+    }
+}
+
+fn record_batch(
+    schema: &'static [(&'static str, Kind)],
+    rows: Vec<Row>,
+) -> anyhow::Result<RecordBatch> {
+    let mut columns: Vec<Vec<Value>> = schema.iter().map(|_| vec![]).collect();
+    for row in rows {
+        for (column, value) in columns.iter_mut().zip(row) {
+            column.push(value);
+        }
+    }
+    let arrays = schema.iter().zip(columns).map(|((name, kind), column)| {
+        let array: ArrayRef = match kind {
+            Kind::Int => Arc::new(Int64Array::from_iter_values(column.into_iter().map(
+                |value| match value {
+                    Value::Int(value) => value,
+                    Value::Str(_) => unreachable!("{name} is an integer column"),
+                },
+            ))),
+            Kind::Str => Arc::new(StringArray::from_iter_values(column.into_iter().map(
+                |value| match value {
+                    Value::Str(value) => value,
+                    Value::Int(_) => unreachable!("{name} is a string column"),
+                },
+            ))),
+        };
+        (*name, array, false)
+    });
+    Ok(RecordBatch::try_from_iter_with_nullable(arrays)?)
+}
+
+/// Export the tables in `config` from the database at `db_url`.
+pub fn export_tables(db_url: &str, config: &ExportConfig) -> Result<(), IndexerError> {
+    let mut conn = PgConnection::establish(db_url).map_err(|e| {
+        IndexerError::PgConnectionPoolInitError(format!("Failed to connect to the database: {e}"))
+    })?;
+    let latest: Option<i64> = checkpoints::table
+        .select(checkpoints::sequence_number)
+        .order(checkpoints::sequence_number.desc())
+        .first(&mut conn)
+        .optional()?;
+    let Some(latest) = latest else {
+        return Err(IndexerError::InvalidArgumentError(
+            "There are no checkpoints to export".to_string(),
+        ));
+    };
+    let start = config.start_checkpoint as i64;
+    let end = config
+        .end_checkpoint
+        .map_or(latest, |end| (end as i64).min(latest));
+    if start > end {
+        return Err(IndexerError::InvalidArgumentError(format!(
+            "There are no checkpoints indexed in {start}..={end} to export"
+        )));
+    }
+
+    let epoch_ranges: Vec<(i64, i64, Option<i64>)> = epochs::table
+        .filter(epochs::first_checkpoint_id.le(end))
+        .filter(
+            epochs::last_checkpoint_id
+                .ge(start)
+                .or(epochs::last_checkpoint_id.is_null()),
+        )
+        .order(epochs::epoch.asc())
+        .select((
+            epochs::epoch,
+            epochs::first_checkpoint_id,
+            epochs::last_checkpoint_id,
+        ))
+        .load(&mut conn)?;
+
+    for (epoch, first, last) in epoch_ranges {
+        let range = (first.max(start), last.unwrap_or(end).min(end));
+        for table in &config.tables {
+            let path = export_path(&config.dir, *table, config.format, epoch, range);
+            let rows = export_range(&mut conn, *table, config, range, path)?;
+            info!(
+                table = table.name(),
+                epoch,
+                first = range.0,
+                last = range.1,
+                rows,
+                "Exported checkpoints"
+            );
+        }
+    }
+    Ok(())
+}
+
+fn export_path(
+    dir: &Path,
+    table: ExportedTable,
+    format: ExportFormat,
+    epoch: i64,
+    (first, last): (i64, i64),
+) -> PathBuf {
+    dir.join(table.name())
+        .join(format!("{EPOCH_DIR_PREFIX}{epoch}"))
+        .join(format!("{first}_{}.{}", last + 1, format.extension()))
+}
+
+/// Export `table`'s rows in checkpoints `range` to `path`, returning how many there were.
+fn export_range(
+    conn: &mut PgConnection,
+    table: ExportedTable,
+    config: &ExportConfig,
+    range: (i64, i64),
+    path: PathBuf,
+) -> Result<usize, IndexerError> {
+    let mut file = ExportFile::new(path, config.format, table.schema());
+    let mut cursor = (-1, -1);
+    let mut exported = 0;
+    loop {
+        let (rows, next) = table.read_batch(conn, range, cursor, config.batch_size.max(1))?;
+        exported += rows.len();
+        file.write(rows)
+            .map_err(|e| IndexerError::GenericError(format!("Failed to export {table:?}: {e}")))?;
+        match next {
+            Some(next) => cursor = next,
+            None => break,
+        }
+    }
+    file.finish()
+        .map_err(|e| IndexerError::GenericError(format!("Failed to export {table:?}: {e}")))?;
+    Ok(exported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<Row> {
+        vec![
+            vec![Value::Int(1), Value::Str("a".to_string())],
+            vec![Value::Int(2), Value::Str("b,c".to_string())],
+        ]
+    }
+
+    const SCHEMA: &[(&str, Kind)] = &[("n", Kind::Int), ("s", Kind::Str)];
+
+    #[test]
+    fn test_export_path() {
+        let path = export_path(
+            Path::new("/export"),
+            ExportedTable::BalanceChanges,
+            ExportFormat::Parquet,
+            3,
+            (100, 199),
+        );
+        assert_eq!(
+            path,
+            Path::new("/export/balance_changes/epoch_3/100_200.parquet")
+        );
+    }
+
+    #[test]
+    fn test_export_file() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let csv = dir.path().join("t/epoch_0/0_1.csv");
+        let mut file = ExportFile::new(csv.clone(), ExportFormat::Csv, SCHEMA);
+        file.write(rows()).unwrap();
+        assert!(!csv.exists());
+        assert!(file.finish().unwrap());
+        assert_eq!(fs::read_to_string(&csv).unwrap(), "n,s\n1,a\n2,\"b,c\"\n");
+
+        let parquet = dir.path().join("t/epoch_0/0_1.parquet");
+        let mut file = ExportFile::new(parquet.clone(), ExportFormat::Parquet, SCHEMA);
+        file.write(rows()).unwrap();
+        file.write(rows()).unwrap();
+        assert!(file.finish().unwrap());
+        assert!(fs::metadata(&parquet).unwrap().len() > 0);
+
+        // Nothing is written for ranges without rows.
+        let empty = dir.path().join("t/epoch_1/1_2.csv");
+        let file = ExportFile::new(empty.clone(), ExportFormat::Csv, SCHEMA);
+        assert!(!file.finish().unwrap());
+        assert!(!empty.exists());
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Faults injected into the reader's database queries, so that operators can check that clients
+//! retry, and that alerts fire, before a real incident does it for them. Only built with the
+//! `fault_injection` feature.
+//!
+//! Each query is, at the configured rates:
+//!
+//! - delayed by a latency picked uniformly between the minimum and the maximum, before it runs,
+//! - failed as if its connection had dropped, without running,
+//! - failed as if it had hit a serialization failure, without running.
+//!
+//! Faults are configured in the `fault-injection` section of the dynamic config (see
+//! [`crate::dynamic_config`]), or, for readers without one, in YAML in the
+//! `SUI_INDEXER_FAULT_INJECTION` environment variable, e.g.
+//! `SUI_INDEXER_FAULT_INJECTION='{latency-rate: 0.1, max-latency-ms: 500}'`.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::errors::IndexerError;
+
+pub const FAULT_INJECTION_ENV_VAR: &str = "SUI_INDEXER_FAULT_INJECTION";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct FaultInjectionConfig {
+    /// Fraction of queries that are delayed.
+    pub latency_rate: f64,
+    pub min_latency_ms: u64,
+    pub max_latency_ms: u64,
+    /// Fraction of queries that fail as if their connection had dropped.
+    pub dropped_connection_rate: f64,
+    /// Fraction of queries that fail as if they had hit a serialization failure.
+    pub serialization_error_rate: f64,
+}
+
+impl FaultInjectionConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        for (name, rate) in [
+            ("latency-rate", self.latency_rate),
+            ("dropped-connection-rate", self.dropped_connection_rate),
+            ("serialization-error-rate", self.serialization_error_rate),
+        ] {
+            anyhow::ensure!(
+                (0.0..=1.0).contains(&rate),
+                "{name} must be between 0 and 1, got {rate}"
+            );
+        }
+        anyhow::ensure!(
+            self.min_latency_ms <= self.max_latency_ms,
+            "min-latency-ms ({}) is greater than max-latency-ms ({})",
+            self.min_latency_ms,
+            self.max_latency_ms
+        );
+        Ok(())
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.latency_rate > 0.0
+            || self.dropped_connection_rate > 0.0
+            || self.serialization_error_rate > 0.0
+    }
+}
+
+/// The faults injected into the reader's queries. Clones share the same config, so the dynamic
+/// config can change the faults of a running reader.
+#[derive(Clone, Debug, Default)]
+pub struct FaultInjection {
+    config: Arc<RwLock<FaultInjectionConfig>>,
+}
+
+impl FaultInjection {
+    /// Faults configured by [`FAULT_INJECTION_ENV_VAR`], if it is set.
+    pub fn from_env() -> Result<Self, IndexerError> {
+        let faults = Self::default();
+        if let Ok(yaml) = std::env::var(FAULT_INJECTION_ENV_VAR) {
+            let config = serde_yaml::from_str(&yaml).map_err(|e| {
+                IndexerError::InvalidArgumentError(format!(
+                    "Invalid {FAULT_INJECTION_ENV_VAR}: {e}"
+                ))
+            })?;
+            faults.set_config(config).map_err(|e| {
+                IndexerError::InvalidArgumentError(format!(
+                    "Invalid {FAULT_INJECTION_ENV_VAR}: {e}"
+                ))
+            })?;
+        }
+        Ok(faults)
+    }
+
+    /// Replace the faults with those in `config`, which are left as they are if it is invalid.
+    pub fn set_config(&self, config: FaultInjectionConfig) -> anyhow::Result<()> {
+        config.validate()?;
+        if config.is_enabled() {
+            warn!("Injecting faults into database queries: {config:?}");
+        }
+        *self.config.write().unwrap() = config;
+        Ok(())
+    }
+
+    /// Inject faults into a query about to run, blocking the thread it runs on for as long as it
+    /// is delayed, and returning the error it fails with, if any.
+    pub(crate) fn inject(&self) -> Result<(), IndexerError> {
+        let config = self.config.read().unwrap().clone();
+        if !config.is_enabled() {
+            return Ok(());
+        }
+        let mut rng = rand::thread_rng();
+
+        if rng.gen_bool(config.latency_rate) {
+            let latency = rng.gen_range(config.min_latency_ms..=config.max_latency_ms);
+            debug!(latency, "Injecting query latency");
+            std::thread::sleep(Duration::from_millis(latency));
+        }
+        if rng.gen_bool(config.dropped_connection_rate) {
+            debug!("Injecting dropped connection");
+            return Err(IndexerError::PgPoolConnectionError(
+                "Failed to get connection from PG connection pool with error: server closed the \
+                 connection unexpectedly (injected fault)"
+                    .to_string(),
+            ));
+        }
+        if rng.gen_bool(config.serialization_error_rate) {
+            debug!("Injecting serialization failure");
+            return Err(IndexerError::PostgresReadError(
+                "could not serialize access due to concurrent update (injected fault)".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fault_injection() {
+        let faults = FaultInjection::default();
+        assert!(faults.inject().is_ok());
+
+        faults
+            .set_config(FaultInjectionConfig {
+                dropped_connection_rate: 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(matches!(
+            faults.inject(),
+            Err(IndexerError::PgPoolConnectionError(_))
+        ));
+
+        faults
+            .set_config(FaultInjectionConfig {
+                serialization_error_rate: 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(matches!(
+            faults.inject(),
+            Err(IndexerError::PostgresReadError(_))
+        ));
+
+        // Invalid configs leave the faults as they are.
+        let invalid = FaultInjectionConfig {
+            latency_rate: 2.0,
+            ..Default::default()
+        };
+        assert!(faults.set_config(invalid).is_err());
+        let invalid = FaultInjectionConfig {
+            min_latency_ms: 10,
+            max_latency_ms: 1,
+            ..Default::default()
+        };
+        assert!(faults.set_config(invalid).is_err());
+        assert!(faults.inject().is_err());
+    }
+}
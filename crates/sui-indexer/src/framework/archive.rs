@@ -0,0 +1,158 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use sui_rest_api::CheckpointData;
+use sui_storage::blob::{Blob, BlobEncoding};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::warn;
+
+/// A directory keeping a copy of every checkpoint the writer ingests, so that a lost database can
+/// be re-ingested from local files rather than by downloading every checkpoint again.
+///
+/// Each checkpoint is kept in a file of its own, named `<sequence number>.chk`, in the same
+/// format that `sui-data-ingestion` reads checkpoints from local files in.
+#[derive(Clone, Debug)]
+pub struct CheckpointArchive {
+    dir: PathBuf,
+}
+
+impl CheckpointArchive {
+    pub fn new(dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| anyhow!("Failed to create checkpoint archive {}: {e}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, sequence_number: CheckpointSequenceNumber) -> PathBuf {
+        self.dir.join(format!("{sequence_number}.chk"))
+    }
+
+    /// The archived checkpoint, if it is in the archive and intact. Files that can't be read back
+    /// (e.g. because they were only partly copied into the archive) are treated as missing, so
+    /// that the checkpoint is downloaded again and the file replaced.
+    pub async fn read(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<Option<CheckpointData>> {
+        let path = self.path(sequence_number);
+        let bytes = match tokio::fs::read(&path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(anyhow!("Failed to read {}: {e}", path.display())),
+        };
+
+        match Blob::from_bytes::<CheckpointData>(&bytes)
+            .and_then(|checkpoint| verify(&checkpoint, sequence_number).map(|_| checkpoint))
+        {
+            Ok(checkpoint) => Ok(Some(checkpoint)),
+            Err(e) => {
+                warn!(
+                    "Ignoring invalid archived checkpoint {}: {e}",
+                    path.display()
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Archive `checkpoint`, returning once it is on disk. The file is written under a temporary
+    /// name first, so that a crash never leaves a partly written checkpoint under its final name.
+    pub async fn write(&self, checkpoint: &CheckpointData) -> Result<()> {
+        let sequence_number = *checkpoint.checkpoint_summary.sequence_number();
+        verify(checkpoint, sequence_number)?;
+        let bytes = Blob::encode(checkpoint, BlobEncoding::Bcs)?.to_bytes();
+        let path = self.path(sequence_number);
+        tokio::task::spawn_blocking(move || write_file(&path, &bytes)).await?
+    }
+}
+
+/// Check that `checkpoint` is checkpoint `sequence_number`, and that its contents are the ones
+/// its summary commits to.
+fn verify(checkpoint: &CheckpointData, sequence_number: CheckpointSequenceNumber) -> Result<()> {
+    let summary = &checkpoint.checkpoint_summary;
+    if *summary.sequence_number() != sequence_number {
+        return Err(anyhow!(
+            "expected checkpoint {sequence_number}, got {}",
+            summary.sequence_number()
+        ));
+    }
+    if checkpoint.checkpoint_contents.digest() != &summary.content_digest {
+        return Err(anyhow!(
+            "contents of checkpoint {sequence_number} don't match its summary"
+        ));
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("chk.tmp");
+    let mut file = std::fs::File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use sui_types::crypto::KeypairTraits;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary, SignedCheckpointSummary,
+    };
+    use sui_types::utils::make_committee_key;
+
+    fn checkpoint(sequence_number: CheckpointSequenceNumber) -> CheckpointData {
+        let (keys, committee) = make_committee_key(&mut StdRng::from_seed([0; 32]));
+        let contents = CheckpointContents::new_with_digests_only_for_tests(vec![]);
+        let summary = CheckpointSummary::new(
+            0,
+            sequence_number,
+            0,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            0,
+        );
+        let signatures: Vec<_> = keys
+            .iter()
+            .map(|k| SignedCheckpointSummary::sign(0, &summary, k, k.public().into()))
+            .collect();
+        CheckpointData {
+            checkpoint_summary: CertifiedCheckpointSummary::new(summary, signatures, &committee)
+                .unwrap(),
+            checkpoint_contents: contents,
+            transactions: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = CheckpointArchive::new(dir.path().join("checkpoints")).unwrap();
+        assert!(archive.read(7).await.unwrap().is_none());
+
+        archive.write(&checkpoint(7)).await.unwrap();
+        let archived = archive.read(7).await.unwrap().unwrap();
+        assert_eq!(*archived.checkpoint_summary.sequence_number(), 7);
+
+        // A truncated file is treated as missing.
+        let path = archive.path(7);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(archive.read(7).await.unwrap().is_none());
+
+        // As is a checkpoint under the wrong name.
+        archive.write(&checkpoint(7)).await.unwrap();
+        std::fs::copy(archive.path(7), archive.path(8)).unwrap();
+        assert!(archive.read(8).await.unwrap().is_none());
+    }
+}
@@ -2,10 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio_util::sync::CancellationToken;
 
 use crate::metrics::IndexerMetrics;
 
-use super::fetcher::CheckpointFetcher;
+use super::fetcher::{CheckpointFetcher, CheckpointSource};
 use super::Handler;
 
 pub struct IndexerBuilder {
@@ -53,7 +54,7 @@ impl IndexerBuilder {
         self
     }
 
-    pub async fn run(self) {
+    pub async fn run(self) -> anyhow::Result<()> {
         let (downloaded_checkpoint_data_sender, downloaded_checkpoint_data_receiver) =
             mysten_metrics::metered_channel::channel(
                 self.checkpoint_buffer_size,
@@ -67,7 +68,7 @@ impl IndexerBuilder {
         // service
         let rest_api_url = format!("{}/rest", self.rest_url.unwrap());
         let fetcher = CheckpointFetcher::new(
-            sui_rest_api::Client::new(rest_api_url),
+            CheckpointSource::Rest(sui_rest_api::Client::new(rest_api_url)),
             self.last_downloaded_checkpoint,
             downloaded_checkpoint_data_sender,
             self.metrics.clone(),
@@ -81,7 +82,8 @@ impl IndexerBuilder {
                 downloaded_checkpoint_data_receiver,
             ),
             self.handlers,
+            CancellationToken::new(),
         )
-        .await;
+        .await
     }
 }
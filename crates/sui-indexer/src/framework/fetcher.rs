@@ -1,40 +1,183 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use sui_core::storage::SecondaryRocksDbStore;
 use sui_rest_api::{CheckpointData, Client};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::storage::ReadStore;
+use tokio::sync::watch;
 use tracing::{info, warn};
 
 use crate::metrics::IndexerMetrics;
 
+use super::archive::CheckpointArchive;
+use super::memory::MemoryBudget;
+
+/// Where checkpoints are fetched from.
+#[derive(Clone)]
+pub enum CheckpointSource {
+    /// A fullnode's REST API.
+    Rest(Client),
+    /// The database of a fullnode running on the same host, which saves serializing checkpoints
+    /// to send them over the network.
+    Local(Arc<SecondaryRocksDbStore>),
+}
+
+impl CheckpointSource {
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<CheckpointSequenceNumber> {
+        match self {
+            Self::Rest(client) => Ok(*client.get_latest_checkpoint().await?.sequence_number()),
+            Self::Local(store) => {
+                let store = store.clone();
+                tokio::task::spawn_blocking(move || -> Result<_> {
+                    store.try_catch_up_with_primary()?;
+                    Ok(*store.get_latest_checkpoint()?.sequence_number())
+                })
+                .await?
+            }
+        }
+    }
+
+    async fn get_full_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<CheckpointData> {
+        match self {
+            Self::Rest(client) => Ok(client.get_full_checkpoint(sequence_number).await?),
+            Self::Local(store) => {
+                let store = store.clone();
+                tokio::task::spawn_blocking(move || -> Result<_> {
+                    let checkpoint = store
+                        .get_checkpoint_by_sequence_number(sequence_number)?
+                        .ok_or_else(|| anyhow!("missing checkpoint {sequence_number}"))?;
+                    let contents = store
+                        .get_checkpoint_contents_by_digest(&checkpoint.content_digest)?
+                        .ok_or_else(|| {
+                            anyhow!("missing contents of checkpoint {sequence_number}")
+                        })?;
+                    store.get_checkpoint_data(checkpoint, contents)
+                })
+                .await?
+            }
+        }
+    }
+}
+
+/// Holds back downloads of checkpoints too far past the latest one committed, so that checkpoints
+/// don't pile up in memory while the database falls behind.
+#[derive(Clone)]
+struct CommitBackpressure {
+    /// How many checkpoints past the latest one committed can be downloaded.
+    max_lag: u64,
+    committed: watch::Receiver<Option<CheckpointSequenceNumber>>,
+    /// The latest checkpoint committed before the fetcher started, which `committed` may not
+    /// report until the next one is committed.
+    floor: Option<CheckpointSequenceNumber>,
+}
+
+impl CommitBackpressure {
+    fn allows(&self, committed: Option<CheckpointSequenceNumber>, checkpoint: u64) -> bool {
+        let next_to_commit = committed.max(self.floor).map_or(0, |c| c + 1);
+        checkpoint < next_to_commit.saturating_add(self.max_lag)
+    }
+
+    /// Wait until `checkpoint` can be downloaded.
+    async fn wait(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+        metrics: &IndexerMetrics,
+    ) -> Result<()> {
+        let mut committed = self.committed.clone();
+        if self.allows(*committed.borrow(), checkpoint) {
+            return Ok(());
+        }
+        metrics.checkpoint_downloads_throttled.inc();
+        committed
+            .wait_for(|committed| self.allows(*committed, checkpoint))
+            .await
+            .map_err(|_| anyhow!("checkpoints are no longer being committed"))?;
+        Ok(())
+    }
+}
+
 pub struct CheckpointFetcher {
-    client: Client,
+    source: CheckpointSource,
     last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
     highest_known_checkpoint: CheckpointSequenceNumber,
     sender: mysten_metrics::metered_channel::Sender<CheckpointData>,
     metrics: IndexerMetrics,
+    archive: Option<CheckpointArchive>,
+    stop_at_epoch_end: bool,
+    stopped: bool,
+    download_concurrency: usize,
+    backpressure: Option<CommitBackpressure>,
+    memory_budget: Option<MemoryBudget>,
 }
 
 impl CheckpointFetcher {
     const INTERVAL_PERIOD: std::time::Duration = std::time::Duration::from_secs(5);
-    const CHECKPOINT_DOWNLOAD_CONCURRENCY: usize = 100;
+    pub const CHECKPOINT_DOWNLOAD_CONCURRENCY: usize = 100;
 
     pub fn new(
-        client: Client,
+        source: CheckpointSource,
         last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
         sender: mysten_metrics::metered_channel::Sender<CheckpointData>,
         metrics: IndexerMetrics,
     ) -> Self {
         Self {
-            client,
+            source,
             last_downloaded_checkpoint,
             highest_known_checkpoint: 0,
             sender,
             metrics,
+            archive: None,
+            stop_at_epoch_end: false,
+            stopped: false,
+            download_concurrency: Self::CHECKPOINT_DOWNLOAD_CONCURRENCY,
+            backpressure: None,
+            memory_budget: None,
         }
     }
 
+    /// Download up to `concurrency` checkpoints at a time.
+    pub fn set_download_concurrency(&mut self, concurrency: usize) {
+        self.download_concurrency = concurrency.max(1);
+    }
+
+    /// Only download checkpoints up to `max_lag` past the latest one committed, as reported by
+    /// `committed`, waiting for commits to catch up before downloading more.
+    pub fn set_commit_backpressure(
+        &mut self,
+        max_lag: u64,
+        committed: watch::Receiver<Option<CheckpointSequenceNumber>>,
+    ) {
+        self.backpressure = Some(CommitBackpressure {
+            max_lag: max_lag.max(1),
+            committed,
+            floor: self.last_downloaded_checkpoint,
+        });
+    }
+
+    /// Charge downloaded checkpoints to `budget`, pausing downloads while it is exceeded.
+    pub fn set_memory_budget(&mut self, budget: MemoryBudget) {
+        self.memory_budget = Some(budget);
+    }
+
+    /// Stop fetching after the last checkpoint of the current epoch, ending the stream of
+    /// checkpoints, so that the database is left at an epoch boundary.
+    pub fn set_stop_at_epoch_end(&mut self, stop_at_epoch_end: bool) {
+        self.stop_at_epoch_end = stop_at_epoch_end;
+    }
+
+    /// Copy every checkpoint to `archive` before passing it on to be indexed, and read
+    /// checkpoints that are already in the archive from it instead of downloading them.
+    pub fn set_archive(&mut self, archive: Option<CheckpointArchive>) {
+        self.archive = archive;
+    }
+
     pub async fn run(mut self) {
         let mut interval = tokio::time::interval(Self::INTERVAL_PERIOD);
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
@@ -44,6 +187,11 @@ impl CheckpointFetcher {
         loop {
             interval.tick().await;
 
+            if self.sender.is_closed() {
+                info!("CheckpointFetcher stopped");
+                return;
+            }
+
             if let Err(e) = self.update_highest_known_checkpoint().await {
                 warn!("error updating highest known checkpoint: {e}");
                 continue;
@@ -53,13 +201,20 @@ impl CheckpointFetcher {
                 warn!("error downloading checkpoints: {e}");
                 continue;
             }
+
+            if self.stopped {
+                info!(
+                    checkpoint = self.last_downloaded_checkpoint,
+                    "CheckpointFetcher stopped at the end of the epoch"
+                );
+                return;
+            }
         }
     }
 
     async fn update_highest_known_checkpoint(&mut self) -> Result<()> {
-        let checkpoint = self.client.get_latest_checkpoint().await?;
-        self.highest_known_checkpoint =
-            std::cmp::max(self.highest_known_checkpoint, *checkpoint.sequence_number());
+        let checkpoint = self.source.get_latest_checkpoint_sequence_number().await?;
+        self.highest_known_checkpoint = std::cmp::max(self.highest_known_checkpoint, checkpoint);
         // NOTE: this metric is used to monitor delta between the highest known checkpoint on FN and in DB,
         // there is an alert based on the delta of these two metrics.
         self.metrics
@@ -81,31 +236,109 @@ impl CheckpointFetcher {
             info!("Starting download of checkpoints {checkpoint_range:?}");
         }
 
+        let (source, archive, metrics) = (&self.source, self.archive.as_ref(), &self.metrics);
+        let backpressure = self.backpressure.as_ref();
+        let memory_budget = self.memory_budget.as_ref();
         let mut checkpoint_stream = checkpoint_range
-            .map(|next| self.client.get_full_checkpoint(next))
+            .map(|next| async move {
+                if let Some(backpressure) = backpressure {
+                    backpressure.wait(next, metrics).await?;
+                }
+                if let Some(budget) = memory_budget {
+                    budget.wait(next).await?;
+                }
+                let checkpoint = fetch_checkpoint(source, archive, metrics, next).await?;
+                let size = bcs::serialized_size(&checkpoint)?;
+                if let Some(budget) = memory_budget {
+                    budget.charge(next, size as u64);
+                }
+                Ok::<_, anyhow::Error>((checkpoint, size))
+            })
             .pipe(futures::stream::iter)
-            .buffered(Self::CHECKPOINT_DOWNLOAD_CONCURRENCY);
+            .buffered(self.download_concurrency);
 
         while let Some(maybe_checkpoint) = checkpoint_stream.next().await {
-            let checkpoint = maybe_checkpoint?;
+            let (checkpoint, checkpoint_bytes_size) = maybe_checkpoint?;
             self.last_downloaded_checkpoint =
                 Some(*checkpoint.checkpoint_summary.sequence_number());
+            let end_of_epoch = checkpoint.checkpoint_summary.end_of_epoch_data.is_some();
 
             info!(
                 checkpoint = checkpoint.checkpoint_summary.sequence_number(),
                 "successfully downloaded checkpoint"
             );
 
-            let checkpoint_bytes_size = bcs::serialized_size(&checkpoint)?;
             self.metrics
                 .checkpoint_download_bytes_size
                 .set(checkpoint_bytes_size as i64);
-            self.sender
-                .send(checkpoint)
-                .await
-                .expect("channel shouldn't be closed");
+            // The receiver is only dropped when the indexer is shutting down.
+            if self.sender.send(checkpoint).await.is_err() {
+                return Ok(());
+            }
+
+            if self.stop_at_epoch_end && end_of_epoch {
+                self.stopped = true;
+                return Ok(());
+            }
         }
 
         Ok(())
     }
 }
+
+/// Checkpoint `sequence_number`, from `archive` if it is there, and otherwise from `source`, in
+/// which case it is archived before it is returned.
+async fn fetch_checkpoint(
+    source: &CheckpointSource,
+    archive: Option<&CheckpointArchive>,
+    metrics: &IndexerMetrics,
+    sequence_number: CheckpointSequenceNumber,
+) -> Result<CheckpointData> {
+    let Some(archive) = archive else {
+        return source.get_full_checkpoint(sequence_number).await;
+    };
+    if let Some(checkpoint) = archive.read(sequence_number).await? {
+        metrics.checkpoints_read_from_archive.inc();
+        return Ok(checkpoint);
+    }
+    let checkpoint = source.get_full_checkpoint(sequence_number).await?;
+    archive.write(&checkpoint).await?;
+    Ok(checkpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use prometheus::Registry;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_commit_backpressure() {
+        let metrics = IndexerMetrics::new(&Registry::new());
+        let (sender, committed) = watch::channel(None);
+        let backpressure = CommitBackpressure {
+            max_lag: 2,
+            committed,
+            floor: Some(9),
+        };
+
+        // Checkpoints committed before the fetcher started count, until later ones are reported.
+        backpressure.wait(11, &metrics).await.unwrap();
+        let waiting = backpressure.wait(12, &metrics);
+        tokio::pin!(waiting);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), &mut waiting)
+                .await
+                .is_err()
+        );
+
+        sender.send(Some(10)).unwrap();
+        waiting.await.unwrap();
+        assert_eq!(metrics.checkpoint_downloads_throttled.get(), 1);
+
+        drop(sender);
+        assert!(backpressure.wait(20, &metrics).await.is_err());
+    }
+}
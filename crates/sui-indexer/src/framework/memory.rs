@@ -0,0 +1,204 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Accounting for the memory held by checkpoints as they pass through the ingestion pipeline, so
+//! that downloads can be paused when bursts of large checkpoints would otherwise pile up until
+//! the indexer runs out of memory.
+//!
+//! A checkpoint is charged the size of its serialized form when it is downloaded, which stands in
+//! for the memory held for it at every stage, and released once it is committed.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::sync::watch;
+
+use crate::metrics::IndexerMetrics;
+
+/// The stages of the pipeline that checkpoints are held in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// Downloaded, and waiting to be indexed, or being indexed.
+    Downloaded,
+    /// Indexed, and waiting to be committed, or being committed.
+    Indexed,
+}
+
+impl Stage {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Downloaded => "downloaded",
+            Self::Indexed => "indexed",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Usage {
+    checkpoints: BTreeMap<CheckpointSequenceNumber, (Stage, u64)>,
+    bytes: u64,
+}
+
+/// The memory budget of the pipeline. Clones share the same budget.
+#[derive(Clone)]
+pub struct MemoryBudget {
+    limit: u64,
+    usage: Arc<watch::Sender<Usage>>,
+    metrics: IndexerMetrics,
+}
+
+impl MemoryBudget {
+    pub fn new(limit: u64, metrics: IndexerMetrics) -> Self {
+        metrics.memory_budget_bytes.set(limit as i64);
+        Self {
+            limit,
+            usage: Arc::new(watch::channel(Usage::default()).0),
+            metrics,
+        }
+    }
+
+    /// Whether `checkpoint` can be downloaded given `usage`. Checkpoints before all of those held
+    /// are always let through, as those held can't be committed (and released) without them.
+    fn allows(&self, usage: &Usage, checkpoint: CheckpointSequenceNumber) -> bool {
+        usage.bytes < self.limit
+            || usage
+                .checkpoints
+                .first_key_value()
+                .map_or(true, |(first, _)| checkpoint < *first)
+    }
+
+    /// Wait until there is room in the budget to download `checkpoint`.
+    pub async fn wait(&self, checkpoint: CheckpointSequenceNumber) -> Result<()> {
+        let mut usage = self.usage.subscribe();
+        if self.allows(&usage.borrow(), checkpoint) {
+            return Ok(());
+        }
+        self.metrics.checkpoint_downloads_paused_for_memory.inc();
+        usage
+            .wait_for(|usage| self.allows(usage, checkpoint))
+            .await
+            .map_err(|_| anyhow!("memory budget is no longer being released"))?;
+        Ok(())
+    }
+
+    /// Charge `bytes` to the budget for `checkpoint`, which was just downloaded.
+    pub fn charge(&self, checkpoint: CheckpointSequenceNumber, bytes: u64) {
+        self.usage.send_modify(|usage| {
+            if let Some((stage, charged)) = usage
+                .checkpoints
+                .insert(checkpoint, (Stage::Downloaded, bytes))
+            {
+                // Downloaded again, e.g. after a failed download of a later checkpoint.
+                usage.bytes -= charged;
+                self.add_to_stage(stage, -(charged as i64));
+            }
+            usage.bytes += bytes;
+        });
+        self.add_to_stage(Stage::Downloaded, bytes as i64);
+        self.report_total();
+    }
+
+    /// Move `checkpoint` on to `stage`.
+    pub fn advance(&self, checkpoint: CheckpointSequenceNumber, stage: Stage) {
+        self.usage.send_if_modified(|usage| {
+            let Some((from, bytes)) = usage.checkpoints.get_mut(&checkpoint) else {
+                return false;
+            };
+            self.add_to_stage(*from, -(*bytes as i64));
+            self.add_to_stage(stage, *bytes as i64);
+            *from = stage;
+            false
+        });
+    }
+
+    /// Release the memory of every checkpoint up to and including `committed`.
+    pub fn release(&self, committed: CheckpointSequenceNumber) {
+        self.usage.send_if_modified(|usage| {
+            let kept = usage.checkpoints.split_off(&(committed + 1));
+            let released = std::mem::replace(&mut usage.checkpoints, kept);
+            for (stage, bytes) in released.values() {
+                usage.bytes -= bytes;
+                self.add_to_stage(*stage, -(*bytes as i64));
+            }
+            !released.is_empty()
+        });
+        self.report_total();
+    }
+
+    /// Release checkpoints as they are reported `committed`, until the committer stops.
+    pub async fn release_committed(
+        self,
+        mut committed: watch::Receiver<Option<CheckpointSequenceNumber>>,
+    ) {
+        loop {
+            if let Some(checkpoint) = *committed.borrow_and_update() {
+                self.release(checkpoint);
+            }
+            if committed.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+
+    fn add_to_stage(&self, stage: Stage, bytes: i64) {
+        self.metrics
+            .memory_budget_used_bytes
+            .with_label_values(&[stage.name()])
+            .add(bytes);
+    }
+
+    fn report_total(&self) {
+        let bytes = self.usage.borrow().bytes;
+        self.metrics
+            .memory_budget_used_bytes
+            .with_label_values(&["total"])
+            .set(bytes as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use prometheus::Registry;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_budget() {
+        let metrics = IndexerMetrics::new(&Registry::new());
+        let budget = MemoryBudget::new(100, metrics.clone());
+        let used = |stage: &str| {
+            metrics
+                .memory_budget_used_bytes
+                .with_label_values(&[stage])
+                .get()
+        };
+
+        budget.wait(10).await.unwrap();
+        budget.charge(10, 60);
+        budget.charge(11, 60);
+        budget.advance(10, Stage::Indexed);
+        assert_eq!(used("downloaded"), 60);
+        assert_eq!(used("indexed"), 60);
+        assert_eq!(used("total"), 120);
+
+        // Over budget, but checkpoints before those held can still be downloaded.
+        budget.wait(9).await.unwrap();
+        let waiting = budget.wait(12);
+        tokio::pin!(waiting);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), &mut waiting)
+                .await
+                .is_err()
+        );
+
+        budget.release(10);
+        waiting.await.unwrap();
+        assert_eq!(used("indexed"), 0);
+        assert_eq!(used("total"), 60);
+        assert_eq!(metrics.checkpoint_downloads_paused_for_memory.get(), 1);
+    }
+}
@@ -1,8 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod archive;
 mod builder;
 pub mod interface;
+pub mod memory;
 
 // TODO remove the pub(crater) once indexer.rs is renamed to lib.rs
 pub(crate) mod fetcher;
@@ -2,10 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use sui_rest_api::CheckpointData;
+use tokio_util::sync::CancellationToken;
 
 use super::interface::Handler;
 
-pub async fn run<S>(stream: S, mut handlers: Vec<Box<dyn Handler>>)
+/// Feed checkpoints from `stream` to `handlers` in batches, until the stream ends or `cancel` is
+/// cancelled. The batch being processed when `cancel` is cancelled is processed in full. Stops,
+/// returning the error, as soon as a handler fails to process a batch.
+pub async fn run<S>(
+    stream: S,
+    mut handlers: Vec<Box<dyn Handler>>,
+    cancel: CancellationToken,
+) -> anyhow::Result<()>
 where
     S: futures::Stream<Item = CheckpointData> + std::marker::Unpin,
 {
@@ -17,13 +25,130 @@ where
         .unwrap();
     tracing::info!("Indexer runner is starting with {batch_size}");
     let mut chunks: futures::stream::ReadyChunks<S> = stream.ready_chunks(batch_size);
-    while let Some(checkpoints) = chunks.next().await {
+    loop {
+        let checkpoints = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => {
+                tracing::info!("Indexer runner is stopping");
+                break;
+            }
+            checkpoints = chunks.next() => match checkpoints {
+                Some(checkpoints) => checkpoints,
+                None => break,
+            },
+        };
         //TODO create tracing spans for processing
-        futures::future::join_all(
+        let results = futures::future::join_all(
             handlers
                 .iter_mut()
-                .map(|handler| async { handler.process_checkpoints(&checkpoints).await.unwrap() }),
+                .map(|handler| async { handler.process_checkpoints(&checkpoints).await }),
         )
         .await;
+        for (handler, result) in handlers.iter().zip(results) {
+            if let Err(e) = result {
+                tracing::error!("Indexer runner is stopping: {} failed: {e}", handler.name());
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use sui_types::crypto::KeypairTraits;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+        CheckpointSummary, SignedCheckpointSummary,
+    };
+    use sui_types::utils::make_committee_key;
+    use tokio_stream::wrappers::ReceiverStream;
+
+    fn checkpoint(sequence_number: CheckpointSequenceNumber) -> CheckpointData {
+        let (keys, committee) = make_committee_key(&mut StdRng::from_seed([0; 32]));
+        let contents = CheckpointContents::new_with_digests_only_for_tests(vec![]);
+        let summary = CheckpointSummary::new(
+            0,
+            sequence_number,
+            0,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            0,
+        );
+        let signatures: Vec<_> = keys
+            .iter()
+            .map(|k| SignedCheckpointSummary::sign(0, &summary, k, k.public().into()))
+            .collect();
+        CheckpointData {
+            checkpoint_summary: CertifiedCheckpointSummary::new(summary, signatures, &committee)
+                .unwrap(),
+            checkpoint_contents: contents,
+            transactions: vec![],
+        }
+    }
+
+    /// Records the checkpoints it processes, and cancels the runner after the first batch.
+    struct CancellingHandler {
+        processed: Arc<Mutex<Vec<CheckpointSequenceNumber>>>,
+        cancel: CancellationToken,
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for CancellingHandler {
+        fn name(&self) -> &str {
+            "cancelling"
+        }
+
+        async fn process_checkpoints(
+            &mut self,
+            checkpoints: &[CheckpointData],
+        ) -> anyhow::Result<()> {
+            self.cancel.cancel();
+            self.processed.lock().unwrap().extend(
+                checkpoints
+                    .iter()
+                    .map(|c| *c.checkpoint_summary.sequence_number()),
+            );
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_when_cancelled() {
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        let processed = Arc::new(Mutex::new(vec![]));
+        let cancel = CancellationToken::new();
+        let handler = CancellingHandler {
+            processed: processed.clone(),
+            cancel: cancel.clone(),
+        };
+
+        sender.send(checkpoint(0)).await.unwrap();
+        sender.send(checkpoint(1)).await.unwrap();
+        let runner = tokio::spawn(run(
+            ReceiverStream::new(receiver),
+            vec![Box::new(handler)],
+            cancel,
+        ));
+
+        // The batch being processed when the runner was cancelled is processed in full, and the
+        // runner stops even though the stream hasn't ended.
+        tokio::time::timeout(Duration::from_secs(5), runner)
+            .await
+            .expect("Runner stops once cancelled")
+            .unwrap()
+            .unwrap();
+        assert_eq!(*processed.lock().unwrap(), [0, 1]);
+
+        // The runner let go of the stream, rather than waiting for more checkpoints.
+        assert!(sender.send(checkpoint(2)).await.is_err());
     }
 }
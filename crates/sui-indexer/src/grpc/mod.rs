@@ -0,0 +1,514 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A gRPC read API over the indexer's read path, for high-volume consumers for whom the overhead
+//! of JSON-RPC dominates. The service, described by `proto/indexer.proto`, covers the core reads
+//! (objects, transactions, checkpoints, events and the system state), carrying chain data as BCS
+//! rather than rendering it as JSON, and streams checkpoints and events as they are indexed.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, Stream, StreamExt, TryStreamExt};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+use sui_json_rpc_api::QUERY_MAX_RESULT_LIMIT;
+use sui_json_rpc_types::CheckpointId;
+use sui_types::base_types::ObjectID;
+use sui_types::digests::{CheckpointDigest, TransactionDigest};
+use sui_types::object::{Object as SuiObject, ObjectRead};
+use sui_types::sui_system_state::sui_system_state_summary::{
+    SuiSystemStateSummary, SuiValidatorSummary,
+};
+
+use crate::errors::IndexerError;
+use crate::indexer_reader::IndexerReader;
+use crate::models::checkpoints::StoredCheckpoint;
+use crate::models::events::StoredEvent;
+use crate::models::transactions::StoredTransaction;
+
+pub mod proto;
+
+mod indexer_read {
+    include!(concat!(env!("OUT_DIR"), "/sui.indexer.IndexerRead.rs"));
+}
+
+pub use indexer_read::indexer_read_client::IndexerReadClient;
+pub use indexer_read::indexer_read_server::{IndexerRead, IndexerReadServer};
+
+use proto::ObjectStatus;
+
+/// How long streams wait before checking for new checkpoints, once they have caught up.
+const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How many checkpoints streams read at a time.
+const STREAM_BATCH_SIZE: usize = 100;
+
+/// Serves the gRPC read API from the indexer's database.
+#[derive(Clone)]
+pub struct IndexerReadService {
+    reader: IndexerReader,
+}
+
+impl IndexerReadService {
+    pub fn new(reader: IndexerReader) -> Self {
+        Self { reader }
+    }
+
+    /// The checkpoint to start streaming from: `start` if set, or the one after the latest.
+    async fn stream_start(&self, start: Option<u64>) -> Result<u64, Status> {
+        if let Some(start) = start {
+            return Ok(start);
+        }
+        let latest = self
+            .reader
+            .spawn_blocking(|this| this.get_checkpoints_from_db(None, 1, true))
+            .await?;
+        Ok(latest
+            .first()
+            .map_or(0, |checkpoint| checkpoint.sequence_number as u64 + 1))
+    }
+}
+
+#[async_trait]
+impl IndexerRead for IndexerReadService {
+    type StreamCheckpointsStream = BoxStream<'static, Result<proto::Checkpoint, Status>>;
+    type StreamEventsStream = BoxStream<'static, Result<proto::Event, Status>>;
+
+    async fn get_object(
+        &self,
+        request: Request<proto::GetObjectRequest>,
+    ) -> Result<Response<proto::Object>, Status> {
+        let object_id = object_id(&request.into_inner().object_id)?;
+        let object_read = self
+            .reader
+            .get_object_read_in_blocking_task(object_id)
+            .await?;
+        Ok(Response::new(object_read.into()))
+    }
+
+    async fn multi_get_objects(
+        &self,
+        request: Request<proto::MultiGetObjectsRequest>,
+    ) -> Result<Response<proto::MultiGetObjectsResponse>, Status> {
+        let request = request.into_inner();
+        check_size(request.object_ids.len())?;
+        let object_ids = request
+            .object_ids
+            .iter()
+            .map(|id| object_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let objects = match request.checkpoint {
+            Some(checkpoint) => self
+                .reader
+                .spawn_blocking(move |this| {
+                    this.multi_get_objects_at_checkpoint(object_ids, checkpoint)
+                })
+                .await?
+                .into_iter()
+                .map(proto::Object::from)
+                .collect(),
+            None => {
+                let stored = self
+                    .reader
+                    .multi_get_objects_in_blocking_task(object_ids.clone())
+                    .await?;
+                let found = stored
+                    .into_iter()
+                    .map(|stored| {
+                        let object = SuiObject::try_from(stored)?;
+                        Ok((object.id(), object))
+                    })
+                    .collect::<Result<HashMap<_, _>, IndexerError>>()?;
+                object_ids
+                    .into_iter()
+                    .map(|id| match found.get(&id) {
+                        Some(object) => object.into(),
+                        None => ObjectRead::NotExists(id).into(),
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(Response::new(proto::MultiGetObjectsResponse { objects }))
+    }
+
+    async fn get_transactions(
+        &self,
+        request: Request<proto::GetTransactionsRequest>,
+    ) -> Result<Response<proto::GetTransactionsResponse>, Status> {
+        let request = request.into_inner();
+        check_size(request.digests.len())?;
+        let digests = request
+            .digests
+            .iter()
+            .map(|digest| {
+                TransactionDigest::try_from(digest.as_slice()).map_err(|_| {
+                    Status::invalid_argument(format!("Invalid transaction digest: {digest:?}"))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let stored = {
+            let digests = digests.clone();
+            self.reader
+                .spawn_blocking(move |this| this.multi_get_transactions(&digests))
+                .await?
+        };
+        let mut found: HashMap<_, _> = stored
+            .into_iter()
+            .map(|tx| (tx.transaction_digest.clone(), tx))
+            .collect();
+        let transactions = digests
+            .iter()
+            .filter_map(|digest| found.remove(digest.inner().as_slice()))
+            .map(proto::Transaction::from)
+            .collect();
+
+        Ok(Response::new(proto::GetTransactionsResponse {
+            transactions,
+        }))
+    }
+
+    async fn get_checkpoint(
+        &self,
+        request: Request<proto::GetCheckpointRequest>,
+    ) -> Result<Response<proto::Checkpoint>, Status> {
+        let request = request.into_inner();
+        let checkpoint_id = match (request.sequence_number, request.digest) {
+            (Some(_), Some(_)) => {
+                return Err(Status::invalid_argument(
+                    "At most one of sequence_number and digest can be set",
+                ))
+            }
+            (Some(sequence_number), None) => Some(CheckpointId::SequenceNumber(sequence_number)),
+            (None, Some(digest)) => {
+                let digest: [u8; 32] = digest.as_slice().try_into().map_err(|_| {
+                    Status::invalid_argument(format!("Invalid checkpoint digest: {digest:?}"))
+                })?;
+                Some(CheckpointId::Digest(CheckpointDigest::new(digest)))
+            }
+            (None, None) => None,
+        };
+
+        let checkpoint = self
+            .reader
+            .spawn_blocking(move |this| match checkpoint_id {
+                Some(checkpoint_id) => this.get_checkpoint_from_db(checkpoint_id),
+                None => Ok(this.get_checkpoints_from_db(None, 1, true)?.pop()),
+            })
+            .await?
+            .ok_or_else(|| Status::not_found("Checkpoint not found"))?;
+        Ok(Response::new(checkpoint.into()))
+    }
+
+    async fn get_checkpoint_events(
+        &self,
+        request: Request<proto::GetCheckpointEventsRequest>,
+    ) -> Result<Response<proto::GetCheckpointEventsResponse>, Status> {
+        let sequence_number = request.into_inner().checkpoint;
+        let events = self
+            .reader
+            .spawn_blocking(move |this| {
+                this.get_checkpoint_from_db(CheckpointId::SequenceNumber(sequence_number))?
+                    .map(|checkpoint| this.get_checkpoint_events(&checkpoint))
+                    .transpose()
+            })
+            .await?
+            .ok_or_else(|| Status::not_found(format!("Checkpoint {sequence_number} not found")))?;
+
+        Ok(Response::new(proto::GetCheckpointEventsResponse {
+            events: events.into_iter().map(proto::Event::from).collect(),
+        }))
+    }
+
+    async fn get_system_state(
+        &self,
+        _request: Request<proto::GetSystemStateRequest>,
+    ) -> Result<Response<proto::SystemState>, Status> {
+        let system_state = self
+            .reader
+            .spawn_blocking(|this| this.get_latest_sui_system_state())
+            .await?;
+        Ok(Response::new(system_state.into()))
+    }
+
+    async fn stream_checkpoints(
+        &self,
+        request: Request<proto::StreamCheckpointsRequest>,
+    ) -> Result<Response<Self::StreamCheckpointsStream>, Status> {
+        let start = self.stream_start(request.into_inner().start).await?;
+        let checkpoints = checkpoints(self.reader.clone(), start).map_ok(proto::Checkpoint::from);
+        Ok(Response::new(checkpoints.boxed()))
+    }
+
+    async fn stream_events(
+        &self,
+        request: Request<proto::StreamEventsRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let request = request.into_inner();
+        let start = self.stream_start(request.start_checkpoint).await?;
+        let event_type = request.event_type;
+
+        let reader = self.reader.clone();
+        let events = checkpoints(self.reader.clone(), start)
+            .and_then(move |checkpoint| {
+                let reader = reader.clone();
+                async move {
+                    let events = reader
+                        .spawn_blocking(move |this| this.get_checkpoint_events(&checkpoint))
+                        .await?;
+                    Ok::<_, Status>(events)
+                }
+            })
+            .map_ok(move |events| {
+                let event_type = event_type.clone();
+                stream::iter(
+                    events
+                        .into_iter()
+                        .filter(move |event| {
+                            event_type.as_ref().map_or(true, |t| &event.event_type == t)
+                        })
+                        .map(|event| Ok(proto::Event::from(event))),
+                )
+            })
+            .try_flatten();
+        Ok(Response::new(events.boxed()))
+    }
+}
+
+/// Start serving the gRPC read API from `reader` on `addr`.
+pub async fn start_grpc_server(
+    reader: IndexerReader,
+    addr: SocketAddr,
+) -> Result<tokio::task::JoinHandle<()>, IndexerError> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| IndexerError::GenericError(format!("Failed to bind gRPC server: {e}")))?;
+    info!(
+        "Sui Indexer gRPC server listening on {}",
+        listener.local_addr().unwrap_or(addr)
+    );
+
+    let server = tonic::transport::Server::builder()
+        .add_service(IndexerReadServer::new(IndexerReadService::new(reader)));
+    Ok(tokio::spawn(async move {
+        server
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+            .unwrap()
+    }))
+}
+
+/// Checkpoints from `start` onwards, polling for new ones once all the checkpoints indexed so far
+/// have been streamed. The stream ends at the first error.
+fn checkpoints(
+    reader: IndexerReader,
+    start: u64,
+) -> impl Stream<Item = Result<StoredCheckpoint, Status>> {
+    stream::unfold(Some((reader, start, VecDeque::new())), |state| async move {
+        let (reader, next, mut buffered) = state?;
+        loop {
+            if let Some(checkpoint) = buffered.pop_front() {
+                let next = checkpoint.sequence_number as u64 + 1;
+                return Some((Ok(checkpoint), Some((reader, next, buffered))));
+            }
+            let cursor = next.checked_sub(1);
+            let batch = reader
+                .spawn_blocking(move |this| {
+                    this.get_checkpoints_from_db(cursor, STREAM_BATCH_SIZE, false)
+                })
+                .await;
+            match batch {
+                Ok(batch) if batch.is_empty() => tokio::time::sleep(STREAM_POLL_INTERVAL).await,
+                Ok(batch) => buffered.extend(batch),
+                Err(e) => return Some((Err(e.into()), None)),
+            }
+        }
+    })
+}
+
+fn check_size(size: usize) -> Result<(), Status> {
+    if size > *QUERY_MAX_RESULT_LIMIT {
+        return Err(Status::invalid_argument(format!(
+            "Requested {size} items, more than the limit of {}",
+            *QUERY_MAX_RESULT_LIMIT
+        )));
+    }
+    Ok(())
+}
+
+fn object_id(bytes: &[u8]) -> Result<ObjectID, Status> {
+    ObjectID::from_bytes(bytes)
+        .map_err(|e| Status::invalid_argument(format!("Invalid object ID {bytes:?}: {e}")))
+}
+
+impl From<&SuiObject> for proto::Object {
+    fn from(object: &SuiObject) -> Self {
+        Self {
+            object_id: object.id().to_vec(),
+            status: ObjectStatus::Exists as i32,
+            version: object.version().value(),
+            digest: object.digest().into_inner().to_vec(),
+            object_bcs: bcs::to_bytes(object).expect("Objects can be serialized"),
+        }
+    }
+}
+
+impl From<ObjectRead> for proto::Object {
+    fn from(object_read: ObjectRead) -> Self {
+        match object_read {
+            ObjectRead::Exists(_, object, _) => (&object).into(),
+            ObjectRead::Deleted((object_id, version, digest)) => Self {
+                object_id: object_id.to_vec(),
+                status: ObjectStatus::Deleted as i32,
+                version: version.value(),
+                digest: digest.into_inner().to_vec(),
+                object_bcs: vec![],
+            },
+            ObjectRead::NotExists(object_id) => Self {
+                object_id: object_id.to_vec(),
+                status: ObjectStatus::NotExists as i32,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl From<StoredTransaction> for proto::Transaction {
+    fn from(tx: StoredTransaction) -> Self {
+        Self {
+            digest: tx.transaction_digest,
+            sequence_number: tx.tx_sequence_number as u64,
+            checkpoint: tx.checkpoint_sequence_number as u64,
+            timestamp_ms: tx.timestamp_ms as u64,
+            transaction_bcs: tx.raw_transaction,
+            effects_bcs: tx.raw_effects,
+            events_bcs: tx.events.into_iter().flatten().collect(),
+        }
+    }
+}
+
+impl From<StoredCheckpoint> for proto::Checkpoint {
+    fn from(checkpoint: StoredCheckpoint) -> Self {
+        Self {
+            sequence_number: checkpoint.sequence_number as u64,
+            digest: checkpoint.checkpoint_digest,
+            previous_digest: checkpoint.previous_checkpoint_digest,
+            epoch: checkpoint.epoch as u64,
+            timestamp_ms: checkpoint.timestamp_ms as u64,
+            network_total_transactions: checkpoint.network_total_transactions as u64,
+            transaction_digests: checkpoint.tx_digests.into_iter().flatten().collect(),
+            end_of_epoch: checkpoint.end_of_epoch,
+            computation_cost: checkpoint.computation_cost as u64,
+            storage_cost: checkpoint.storage_cost as u64,
+            storage_rebate: checkpoint.storage_rebate as u64,
+            non_refundable_storage_fee: checkpoint.non_refundable_storage_fee as u64,
+        }
+    }
+}
+
+impl From<StoredEvent> for proto::Event {
+    fn from(event: StoredEvent) -> Self {
+        Self {
+            transaction_digest: event.transaction_digest,
+            event_sequence_number: event.event_sequence_number as u64,
+            checkpoint: event.checkpoint_sequence_number as u64,
+            timestamp_ms: event.timestamp_ms as u64,
+            senders: event.senders.into_iter().flatten().collect(),
+            package: event.package,
+            module: event.module,
+            event_type: event.event_type,
+            contents_bcs: event.bcs,
+        }
+    }
+}
+
+impl From<SuiSystemStateSummary> for proto::SystemState {
+    fn from(system_state: SuiSystemStateSummary) -> Self {
+        Self {
+            epoch: system_state.epoch,
+            protocol_version: system_state.protocol_version,
+            system_state_version: system_state.system_state_version,
+            reference_gas_price: system_state.reference_gas_price,
+            safe_mode: system_state.safe_mode,
+            epoch_start_timestamp_ms: system_state.epoch_start_timestamp_ms,
+            epoch_duration_ms: system_state.epoch_duration_ms,
+            total_stake: system_state.total_stake,
+            active_validators: system_state
+                .active_validators
+                .into_iter()
+                .map(proto::Validator::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<SuiValidatorSummary> for proto::Validator {
+    fn from(validator: SuiValidatorSummary) -> Self {
+        Self {
+            address: validator.sui_address.to_vec(),
+            name: validator.name,
+            voting_power: validator.voting_power,
+            gas_price: validator.gas_price,
+            commission_rate: validator.commission_rate,
+            next_epoch_stake: validator.next_epoch_stake,
+            next_epoch_gas_price: validator.next_epoch_gas_price,
+            next_epoch_commission_rate: validator.next_epoch_commission_rate,
+            staking_pool_id: validator.staking_pool_id.to_vec(),
+            staking_pool_sui_balance: validator.staking_pool_sui_balance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use sui_types::base_types::SequenceNumber;
+    use sui_types::digests::ObjectDigest;
+
+    use super::*;
+
+    #[test]
+    fn test_object_reads() {
+        let object = SuiObject::immutable_with_id_for_testing(ObjectID::random());
+        let exists = proto::Object::from(ObjectRead::Exists(
+            object.compute_object_reference(),
+            object.clone(),
+            None,
+        ));
+        assert_eq!(exists.status, ObjectStatus::Exists as i32);
+        assert_eq!(exists.version, object.version().value());
+        let decoded: SuiObject = bcs::from_bytes(&exists.object_bcs).unwrap();
+        assert_eq!(decoded, object);
+
+        let id = ObjectID::random();
+        let deleted = proto::Object::from(ObjectRead::Deleted((
+            id,
+            SequenceNumber::from_u64(3),
+            ObjectDigest::OBJECT_DIGEST_DELETED,
+        )));
+        assert_eq!(deleted.status, ObjectStatus::Deleted as i32);
+        assert_eq!(deleted.version, 3);
+        assert!(deleted.object_bcs.is_empty());
+
+        let missing = proto::Object::from(ObjectRead::NotExists(id));
+        assert_eq!(missing.status, ObjectStatus::NotExists as i32);
+        assert_eq!(missing.object_id, id.to_vec());
+
+        // Messages survive a round trip through their wire encoding.
+        let response = proto::MultiGetObjectsResponse {
+            objects: vec![exists, deleted, missing],
+        };
+        let encoded = response.encode_to_vec();
+        assert_eq!(
+            proto::MultiGetObjectsResponse::decode(encoded.as_slice()).unwrap(),
+            response
+        );
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetObjectRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub object_id: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MultiGetObjectsRequest {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub object_ids: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// The checkpoint to read objects as of. Objects are read at their latest versions if this is
+    /// not set.
+    #[prost(uint64, optional, tag = "2")]
+    pub checkpoint: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MultiGetObjectsResponse {
+    /// In the same order as the requested IDs.
+    #[prost(message, repeated, tag = "1")]
+    pub objects: ::prost::alloc::vec::Vec<Object>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Object {
+    #[prost(bytes = "vec", tag = "1")]
+    pub object_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(enumeration = "ObjectStatus", tag = "2")]
+    pub status: i32,
+    /// Not set if the object does not exist.
+    #[prost(uint64, tag = "3")]
+    pub version: u64,
+    #[prost(bytes = "vec", tag = "4")]
+    pub digest: ::prost::alloc::vec::Vec<u8>,
+    /// Only set if the object exists.
+    #[prost(bytes = "vec", tag = "5")]
+    pub object_bcs: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTransactionsRequest {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub digests: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTransactionsResponse {
+    /// In the same order as the requested digests, leaving out those that were not found.
+    #[prost(message, repeated, tag = "1")]
+    pub transactions: ::prost::alloc::vec::Vec<Transaction>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Transaction {
+    #[prost(bytes = "vec", tag = "1")]
+    pub digest: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub sequence_number: u64,
+    #[prost(uint64, tag = "3")]
+    pub checkpoint: u64,
+    #[prost(uint64, tag = "4")]
+    pub timestamp_ms: u64,
+    #[prost(bytes = "vec", tag = "5")]
+    pub transaction_bcs: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub effects_bcs: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "7")]
+    pub events_bcs: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCheckpointRequest {
+    /// At most one of these may be set. The latest checkpoint is returned if neither is.
+    #[prost(uint64, optional, tag = "1")]
+    pub sequence_number: ::core::option::Option<u64>,
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub digest: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Checkpoint {
+    #[prost(uint64, tag = "1")]
+    pub sequence_number: u64,
+    #[prost(bytes = "vec", tag = "2")]
+    pub digest: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub previous_digest: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    #[prost(uint64, tag = "4")]
+    pub epoch: u64,
+    #[prost(uint64, tag = "5")]
+    pub timestamp_ms: u64,
+    #[prost(uint64, tag = "6")]
+    pub network_total_transactions: u64,
+    #[prost(bytes = "vec", repeated, tag = "7")]
+    pub transaction_digests: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bool, tag = "8")]
+    pub end_of_epoch: bool,
+    #[prost(uint64, tag = "9")]
+    pub computation_cost: u64,
+    #[prost(uint64, tag = "10")]
+    pub storage_cost: u64,
+    #[prost(uint64, tag = "11")]
+    pub storage_rebate: u64,
+    #[prost(uint64, tag = "12")]
+    pub non_refundable_storage_fee: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCheckpointEventsRequest {
+    #[prost(uint64, tag = "1")]
+    pub checkpoint: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCheckpointEventsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub events: ::prost::alloc::vec::Vec<Event>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Event {
+    #[prost(bytes = "vec", tag = "1")]
+    pub transaction_digest: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub event_sequence_number: u64,
+    #[prost(uint64, tag = "3")]
+    pub checkpoint: u64,
+    #[prost(uint64, tag = "4")]
+    pub timestamp_ms: u64,
+    #[prost(bytes = "vec", repeated, tag = "5")]
+    pub senders: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub package: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag = "7")]
+    pub module: ::prost::alloc::string::String,
+    #[prost(string, tag = "8")]
+    pub event_type: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "9")]
+    pub contents_bcs: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSystemStateRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SystemState {
+    #[prost(uint64, tag = "1")]
+    pub epoch: u64,
+    #[prost(uint64, tag = "2")]
+    pub protocol_version: u64,
+    #[prost(uint64, tag = "3")]
+    pub system_state_version: u64,
+    #[prost(uint64, tag = "4")]
+    pub reference_gas_price: u64,
+    #[prost(bool, tag = "5")]
+    pub safe_mode: bool,
+    #[prost(uint64, tag = "6")]
+    pub epoch_start_timestamp_ms: u64,
+    #[prost(uint64, tag = "7")]
+    pub epoch_duration_ms: u64,
+    #[prost(uint64, tag = "8")]
+    pub total_stake: u64,
+    #[prost(message, repeated, tag = "9")]
+    pub active_validators: ::prost::alloc::vec::Vec<Validator>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Validator {
+    #[prost(bytes = "vec", tag = "1")]
+    pub address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub voting_power: u64,
+    #[prost(uint64, tag = "4")]
+    pub gas_price: u64,
+    #[prost(uint64, tag = "5")]
+    pub commission_rate: u64,
+    #[prost(uint64, tag = "6")]
+    pub next_epoch_stake: u64,
+    #[prost(uint64, tag = "7")]
+    pub next_epoch_gas_price: u64,
+    #[prost(uint64, tag = "8")]
+    pub next_epoch_commission_rate: u64,
+    #[prost(bytes = "vec", tag = "9")]
+    pub staking_pool_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "10")]
+    pub staking_pool_sui_balance: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamCheckpointsRequest {
+    /// The first checkpoint to stream. Streaming starts after the latest checkpoint if this is not
+    /// set.
+    #[prost(uint64, optional, tag = "1")]
+    pub start: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamEventsRequest {
+    /// The first checkpoint to stream events from. Streaming starts after the latest checkpoint if
+    /// this is not set.
+    #[prost(uint64, optional, tag = "1")]
+    pub start_checkpoint: ::core::option::Option<u64>,
+    /// Only stream events of this type, in its canonical form (with addresses in full), if set.
+    #[prost(string, optional, tag = "2")]
+    pub event_type: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ObjectStatus {
+    NotExists = 0,
+    Exists = 1,
+    Deleted = 2,
+}
+impl ObjectStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ObjectStatus::NotExists => "OBJECT_STATUS_NOT_EXISTS",
+            ObjectStatus::Exists => "OBJECT_STATUS_EXISTS",
+            ObjectStatus::Deleted => "OBJECT_STATUS_DELETED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "OBJECT_STATUS_NOT_EXISTS" => Some(Self::NotExists),
+            "OBJECT_STATUS_EXISTS" => Some(Self::Exists),
+            "OBJECT_STATUS_DELETED" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+}
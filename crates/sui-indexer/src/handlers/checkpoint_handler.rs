@@ -4,12 +4,17 @@
 use crate::handlers::committer::start_tx_checkpoint_commit_task;
 use crate::handlers::tx_processor::IndexingPackageBuffer;
 use crate::models::display::StoredDisplay;
+use crate::models::event_addresses::{payload_addresses, StoredEventAddress};
+use crate::models::event_schemas::StoredEventSchema;
+use crate::models::nft_transfers::StoredNftTransfer;
+use crate::models::object_transitions::{transition_to_i16, StoredObjectTransition};
+use crate::models::package_call_metrics::{package_call_metrics, StoredPackageCallMetrics};
 use async_trait::async_trait;
 use itertools::Itertools;
-use move_core_types::annotated_value::MoveTypeLayout;
+use move_core_types::annotated_value::{MoveTypeLayout, MoveValue};
 use move_core_types::language_storage::{StructTag, TypeTag};
 use mysten_metrics::{get_metrics, spawn_monitored_task};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::{Arc, Mutex};
 use sui_package_resolver::{PackageStore, Resolver};
 use sui_rest_api::CheckpointData;
@@ -18,14 +23,17 @@ use sui_types::base_types::ObjectRef;
 use sui_types::dynamic_field::DynamicFieldInfo;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::dynamic_field::DynamicFieldType;
-use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointContents};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+};
 use sui_types::object::Object;
 
 use tokio::sync::watch;
+use tokio::task::JoinHandle;
 
 use std::collections::hash_map::Entry;
 use std::collections::HashSet;
-use sui_json_rpc_types::SuiMoveValue;
+use sui_json_rpc_types::{ObjectTransitionKind, SuiMoveValue};
 use sui_types::base_types::SequenceNumber;
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
 use sui_types::event::SystemEpochInfoEvent;
@@ -40,16 +48,19 @@ use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
 
 use crate::errors::IndexerError;
 use crate::framework::interface::Handler;
+use crate::framework::memory::{MemoryBudget, Stage};
 use crate::metrics::IndexerMetrics;
 
-use crate::db::PgConnectionPool;
-use crate::store::module_resolver::{IndexerStorePackageModuleResolver, InterimPackageResolver};
-use crate::store::{IndexerStore, PgIndexerStore};
+use crate::store::module_resolver::InterimPackageResolver;
+use crate::store::IndexerStore;
 use crate::types::{
-    IndexedCheckpoint, IndexedDeletedObject, IndexedEpochInfo, IndexedEvent, IndexedObject,
-    IndexedPackage, IndexedTransaction, IndexerResult, TransactionKind, TxIndex,
+    owner_to_owner_info, IndexedCheckpoint, IndexedDeletedObject, IndexedEpochInfo, IndexedEvent,
+    IndexedObject, IndexedPackage, IndexedTransaction, IndexerResult, TransactionKind, TxIndex,
 };
 
+use super::checkpoint_linkage::CheckpointLinkage;
+use super::scope::IndexingScope;
+use super::tx_classes::classify_transaction;
 use super::tx_processor::EpochEndIndexingObjectStore;
 use super::tx_processor::TxChangesProcessor;
 use super::CheckpointDataToCommit;
@@ -58,10 +69,13 @@ use super::TransactionObjectChangesToCommit;
 
 const CHECKPOINT_QUEUE_SIZE: usize = 1000;
 
+/// Returns the checkpoint handler, and the task committing the checkpoints it indexes, which
+/// stops once the handler is dropped and all the checkpoints it indexed are committed.
 pub async fn new_handlers<S>(
     state: S,
     metrics: IndexerMetrics,
-) -> Result<CheckpointHandler<S>, IndexerError>
+    scope: Option<IndexingScope>,
+) -> Result<(CheckpointHandler<S>, JoinHandle<()>), IndexerError>
 where
     S: IndexerStore + Clone + Sync + Send + 'static,
 {
@@ -78,10 +92,15 @@ where
                 .with_label_values(&["checkpoint_indexing"]),
         );
 
+    // Checkpoints ingested from now on must extend the latest one committed.
+    let linkage = CheckpointLinkage::new(state.get_latest_checkpoint_digest().await?);
+    let display_types = Arc::new(state.get_display_object_types().await?);
+
     let state_clone = state.clone();
     let metrics_clone = metrics.clone();
     let (tx, package_tx) = watch::channel(None);
-    spawn_monitored_task!(start_tx_checkpoint_commit_task(
+    let committed = tx.subscribe();
+    let commit_task = spawn_monitored_task!(start_tx_checkpoint_commit_task(
         state_clone,
         metrics_clone,
         indexed_checkpoint_receiver,
@@ -93,9 +112,14 @@ where
         metrics,
         indexed_checkpoint_sender,
         package_buffer: IndexingPackageBuffer::start(package_tx),
+        linkage,
+        scope,
+        display_types,
+        committed,
+        memory_budget: None,
     };
 
-    Ok(checkpoint_handler)
+    Ok((checkpoint_handler, commit_task))
 }
 
 pub struct CheckpointHandler<S> {
@@ -105,6 +129,26 @@ pub struct CheckpointHandler<S> {
     // buffers for packages that are being indexed but not committed to DB,
     // they will be periodically GCed to avoid OOM.
     package_buffer: Arc<Mutex<IndexingPackageBuffer>>,
+    linkage: CheckpointLinkage,
+    /// Only index what is in scope, if set.
+    scope: Option<IndexingScope>,
+    /// The types of the objects that have a Display, as of the last checkpoint indexed, whose
+    /// changes of hands are indexed as NFT transfers.
+    display_types: Arc<HashSet<String>>,
+    committed: watch::Receiver<Option<CheckpointSequenceNumber>>,
+    memory_budget: Option<MemoryBudget>,
+}
+
+impl<S> CheckpointHandler<S> {
+    /// Watches the latest checkpoint committed, once the first checkpoint the handler indexed is.
+    pub fn committed_checkpoints(&self) -> watch::Receiver<Option<CheckpointSequenceNumber>> {
+        self.committed.clone()
+    }
+
+    /// Account for the checkpoints the handler has indexed in `budget` until they are committed.
+    pub fn set_memory_budget(&mut self, budget: MemoryBudget) {
+        self.memory_budget = Some(budget);
+    }
 }
 
 #[async_trait]
@@ -136,15 +180,28 @@ where
             "Checkpoints received by CheckpointHandler"
         );
 
+        // Refuse to index anything past a checkpoint that doesn't extend the chain, halting
+        // ingestion, rather than committing divergent data.
+        for checkpoint in checkpoints {
+            let summary = &checkpoint.checkpoint_summary;
+            if let Err(e) = self.linkage.extend(
+                *summary.sequence_number(),
+                *summary.digest(),
+                summary.previous_digest,
+            ) {
+                error!("Halting ingestion: {e}");
+                self.metrics.checkpoint_fork_detected.set(1);
+                return Err(e.into());
+            }
+        }
+
         let indexing_timer = self.metrics.checkpoint_index_latency.start_timer();
         // It's important to index packages first to populate ModuleResolver
         let packages = Self::index_packages(checkpoints, &self.metrics);
         let package_objects = Self::get_package_objects(checkpoints);
 
-        let pg_blocking_cp = self.pg_blocking_cp()?;
-        let module_package_db_resolver = IndexerStorePackageModuleResolver::new(pg_blocking_cp);
         let in_mem_package_resolver = InterimPackageResolver::new(
-            module_package_db_resolver,
+            self.state.package_store(),
             self.package_buffer.clone(),
             &package_objects,
             self.metrics.clone(),
@@ -165,15 +222,23 @@ where
             let packages = packages_per_checkpoint
                 .remove(checkpoint.checkpoint_summary.sequence_number())
                 .unwrap_or_default();
+            self.update_display_types(checkpoint);
             tasks.push(tokio::task::spawn(Self::index_one_checkpoint(
                 state_clone.clone(),
                 checkpoint.clone(),
                 metrics_clone.clone(),
                 packages,
                 package_resolver.clone(),
+                self.display_types.clone(),
             )));
         }
-        let checkpoint_data_to_commit = futures::future::join_all(tasks)
+        let selections = self.scope.as_ref().map(|scope| {
+            checkpoints
+                .iter()
+                .map(|checkpoint| scope.select(checkpoint))
+                .collect::<Vec<_>>()
+        });
+        let mut checkpoint_data_to_commit = futures::future::join_all(tasks)
             .await
             .into_iter()
             .collect::<Result<Vec<_>, _>>()
@@ -188,6 +253,11 @@ where
             .tap_err(|e| {
                 error!("Failed to index checkpoints with error: {}", e.to_string());
             })?;
+        if let (Some(scope), Some(selections)) = (&self.scope, selections) {
+            for (data, selection) in checkpoint_data_to_commit.iter_mut().zip(&selections) {
+                scope.retain(selection, data);
+            }
+        }
         let elapsed = indexing_timer.stop_and_record();
 
         info!(
@@ -211,6 +281,9 @@ where
                         e
                     )
                 });
+            if let Some(budget) = &self.memory_budget {
+                budget.advance(checkpoint_seq, Stage::Indexed);
+            }
         }
         Ok(())
     }
@@ -220,6 +293,22 @@ impl<S> CheckpointHandler<S>
 where
     S: IndexerStore + Clone + Sync + Send + 'static,
 {
+    /// Add the types that `checkpoint` created a Display for to the types whose objects are
+    /// indexed as NFTs. Objects of those types are only tracked from then on.
+    fn update_display_types(&mut self, checkpoint: &CheckpointData) {
+        let new_types: Vec<_> = checkpoint
+            .transactions
+            .iter()
+            .flat_map(|tx| tx.events.iter().flat_map(|events| &events.data))
+            .flat_map(StoredDisplay::try_from_event)
+            .map(|display| display.object_type)
+            .filter(|object_type| !self.display_types.contains(object_type))
+            .collect();
+        if !new_types.is_empty() {
+            Arc::make_mut(&mut self.display_types).extend(new_types);
+        }
+    }
+
     async fn index_epoch(
         state: Arc<S>,
         data: &CheckpointData,
@@ -303,6 +392,7 @@ where
         metrics: Arc<IndexerMetrics>,
         packages: Vec<IndexedPackage>,
         package_resolver: Arc<Resolver<impl PackageStore>>,
+        display_types: Arc<HashSet<String>>,
     ) -> Result<CheckpointDataToCommit, IndexerError> {
         let checkpoint_seq = data.checkpoint_summary.sequence_number;
         info!(checkpoint_seq, "Indexing checkpoint data blob");
@@ -316,6 +406,17 @@ where
         let object_history_changes: TransactionObjectChangesToCommit =
             Self::index_objects_history(data.clone(), package_resolver.clone()).await?;
 
+        // Index event schemas
+        let event_schemas = Self::index_event_schemas(&data, package_resolver.clone()).await?;
+        let event_addresses = Self::index_event_addresses(&data, package_resolver.clone()).await?;
+
+        // Index object transitions
+        let object_transitions = Self::index_object_transitions(&data);
+        let nft_transfers = Self::index_nft_transfers(&data, &display_types);
+
+        // Index calls to packages
+        let package_call_metrics = Self::index_package_call_metrics(&data);
+
         let (checkpoint, db_transactions, db_events, db_indices, db_displays) = {
             let CheckpointData {
                 transactions,
@@ -351,6 +452,11 @@ where
             events: db_events,
             tx_indices: db_indices,
             display_updates: db_displays,
+            event_schemas,
+            event_addresses,
+            object_transitions,
+            nft_transfers,
+            package_call_metrics,
             object_changes,
             object_history_changes,
             packages,
@@ -504,6 +610,9 @@ where
                 .map(|(p, m, f)| (*<&ObjectID>::clone(p), m.to_string(), f.to_string()))
                 .collect();
 
+            // Classes
+            let classes = classify_transaction(tx, sender_signed_data.data().tx_signatures());
+
             db_indices.push(TxIndex {
                 tx_sequence_number,
                 transaction_digest: tx_digest,
@@ -514,11 +623,259 @@ where
                 payers,
                 recipients,
                 move_calls,
+                classes,
             });
         }
         Ok((db_transactions, db_events, db_indices, db_displays))
     }
 
+    async fn index_event_schemas(
+        data: &CheckpointData,
+        package_resolver: Arc<Resolver<impl PackageStore>>,
+    ) -> IndexerResult<BTreeMap<(String, Vec<u8>), StoredEventSchema>> {
+        let checkpoint_seq = *data.checkpoint_summary.sequence_number() as i64;
+        let event_types = data
+            .transactions
+            .iter()
+            .flat_map(|tx| tx.events.iter().flat_map(|events| &events.data))
+            .map(|event| (event.type_.clone(), event.package_id))
+            .collect::<BTreeSet<_>>();
+
+        let mut event_schemas = BTreeMap::new();
+        for (event_type, package_id) in event_types {
+            let layout = package_resolver
+                .type_layout(TypeTag::Struct(Box::new(event_type.clone())))
+                .await
+                .map_err(|e| {
+                    IndexerError::ResolveMoveStructError(format!(
+                        "Failed to resolve layout of event type {event_type}. Error: {e}"
+                    ))
+                })?;
+            let layout = serde_json::to_value(&layout).map_err(|e| {
+                IndexerError::SerdeError(format!(
+                    "Failed to serialize layout of event type {event_type}: {e}"
+                ))
+            })?;
+
+            let schema = StoredEventSchema {
+                event_type: event_type.to_canonical_string(/* with_prefix */ true),
+                package_id: package_id.to_vec(),
+                layout,
+                first_seen_checkpoint: checkpoint_seq,
+                last_seen_checkpoint: checkpoint_seq,
+            };
+            event_schemas.insert(schema.key(), schema);
+        }
+        Ok(event_schemas)
+    }
+
+    /// Record the addresses that each event is relevant to: the sender of the transaction that
+    /// emitted it, and the addresses in its payload (see [`payload_addresses`]).
+    async fn index_event_addresses(
+        data: &CheckpointData,
+        package_resolver: Arc<Resolver<impl PackageStore>>,
+    ) -> IndexerResult<Vec<StoredEventAddress>> {
+        let checkpoint_seq = *data.checkpoint_summary.sequence_number() as i64;
+        let first_tx_seq =
+            data.checkpoint_summary.network_total_transactions - data.transactions.len() as u64;
+
+        let mut layouts: HashMap<StructTag, MoveTypeLayout> = HashMap::new();
+        let mut event_addresses = vec![];
+        for (i, tx) in data.transactions.iter().enumerate() {
+            let tx_seq = (first_tx_seq + i as u64) as i64;
+            for (event_seq, event) in tx.events.iter().flat_map(|e| &e.data).enumerate() {
+                let layout = match layouts.entry(event.type_.clone()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let layout = package_resolver
+                            .type_layout(TypeTag::Struct(Box::new(event.type_.clone())))
+                            .await
+                            .map_err(|e| {
+                                IndexerError::ResolveMoveStructError(format!(
+                                    "Failed to resolve layout of event type {}. Error: {e}",
+                                    event.type_
+                                ))
+                            })?;
+                        entry.insert(layout)
+                    }
+                };
+                let payload =
+                    MoveValue::simple_deserialize(&event.contents, layout).map_err(|e| {
+                        IndexerError::SerdeError(format!(
+                            "Failed to deserialize event {event_seq} of transaction {tx_seq}: {e}"
+                        ))
+                    })?;
+
+                let mut addresses = payload_addresses(&payload);
+                addresses.insert(event.sender);
+                event_addresses.extend(addresses.into_iter().map(|address| StoredEventAddress {
+                    address: address.to_vec(),
+                    tx_sequence_number: tx_seq,
+                    event_sequence_number: event_seq as i64,
+                    checkpoint_sequence_number: checkpoint_seq,
+                }));
+            }
+        }
+        Ok(event_addresses)
+    }
+
+    /// Record every object that was created, wrapped, unwrapped or deleted, or changed owners.
+    fn index_object_transitions(data: &CheckpointData) -> Vec<StoredObjectTransition> {
+        let checkpoint_seq = *data.checkpoint_summary.sequence_number();
+        let mut transitions = vec![];
+        for tx in &data.transactions {
+            let fx = &tx.effects;
+            let tx_digest = *fx.transaction_digest();
+            let mut record = |id: ObjectID, version: SequenceNumber, kind| {
+                transitions.push(StoredObjectTransition::new(
+                    id,
+                    version,
+                    kind,
+                    tx_digest,
+                    checkpoint_seq,
+                ))
+            };
+
+            for ((id, version, _), _) in fx.created() {
+                record(id, version, ObjectTransitionKind::Created);
+            }
+            for ((id, version, _), _) in fx.unwrapped() {
+                record(id, version, ObjectTransitionKind::Unwrapped);
+            }
+            for (id, version, _) in fx.wrapped() {
+                record(id, version, ObjectTransitionKind::Wrapped);
+            }
+            for (id, version, _) in fx.deleted() {
+                record(id, version, ObjectTransitionKind::Deleted);
+            }
+            for (id, version, _) in fx.unwrapped_then_deleted() {
+                record(id, version, ObjectTransitionKind::UnwrappedThenDeleted);
+            }
+
+            let old_owners: HashMap<_, _> =
+                tx.input_objects.iter().map(|o| (o.id(), o.owner)).collect();
+            for ((id, version, _), owner) in fx.mutated() {
+                if old_owners.get(&id).is_some_and(|old| *old != owner) {
+                    record(id, version, ObjectTransitionKind::Transferred);
+                }
+            }
+        }
+        transitions
+    }
+
+    /// Record the calls made to each function, and to each package, by the transactions of the
+    /// checkpoint, and the gas they paid.
+    fn index_package_call_metrics(data: &CheckpointData) -> Vec<StoredPackageCallMetrics> {
+        let txs = data.transactions.iter().map(|tx| {
+            let calls = tx
+                .transaction
+                .transaction_data()
+                .move_calls()
+                .into_iter()
+                .map(|(package, module, function)| (*package, module.as_str(), function.as_str()))
+                .collect();
+            (calls, tx.effects.gas_cost_summary())
+        });
+        package_call_metrics(
+            *data.checkpoint_summary.sequence_number(),
+            data.checkpoint_summary.epoch,
+            txs,
+        )
+    }
+
+    /// Record every change of hands of the objects whose types are in `display_types`: creation,
+    /// transfers, wrapping, unwrapping and deletion. Objects that were unwrapped and deleted in
+    /// the same transaction are left out, as their types are not known.
+    fn index_nft_transfers(
+        data: &CheckpointData,
+        display_types: &HashSet<String>,
+    ) -> Vec<StoredNftTransfer> {
+        if display_types.is_empty() {
+            return vec![];
+        }
+        let checkpoint_seq = *data.checkpoint_summary.sequence_number();
+        let timestamp_ms = data.checkpoint_summary.timestamp_ms;
+        let mut transfers = vec![];
+        for tx in &data.transactions {
+            let fx = &tx.effects;
+            let sender = tx.transaction.transaction_data().sender();
+            let tx_digest = *fx.transaction_digest();
+            let inputs: HashMap<_, _> = tx.input_objects.iter().map(|o| (o.id(), o)).collect();
+            let outputs: HashMap<_, _> = tx.output_objects.iter().map(|o| (o.id(), o)).collect();
+            let holder = |owner: &Owner| owner_to_owner_info(owner).1.map(|a| a.to_vec());
+            let mut record = |id: ObjectID,
+                              version: SequenceNumber,
+                              object: Option<&&Object>,
+                              kind,
+                              from: Option<&Owner>,
+                              to: Option<&Owner>| {
+                let Some(object_type) = object
+                    .and_then(|o| o.type_())
+                    .map(|t| t.to_canonical_string(/* with_prefix */ true))
+                    .filter(|t| display_types.contains(t))
+                else {
+                    return;
+                };
+                transfers.push(StoredNftTransfer {
+                    object_id: id.to_vec(),
+                    object_version: version.value() as i64,
+                    object_type,
+                    transition: transition_to_i16(kind),
+                    sender: sender.to_vec(),
+                    from_owner: from.and_then(holder),
+                    to_owner: to.and_then(holder),
+                    transaction_digest: tx_digest.into_inner().to_vec(),
+                    checkpoint_sequence_number: checkpoint_seq as i64,
+                    timestamp_ms: timestamp_ms as i64,
+                })
+            };
+
+            for ((id, version, _), owner) in fx.created() {
+                let kind = ObjectTransitionKind::Created;
+                record(id, version, outputs.get(&id), kind, None, Some(&owner));
+            }
+            for ((id, version, _), owner) in fx.unwrapped() {
+                let kind = ObjectTransitionKind::Unwrapped;
+                record(id, version, outputs.get(&id), kind, None, Some(&owner));
+            }
+            for ((id, version, _), owner) in fx.mutated() {
+                let Some(input) = inputs.get(&id) else {
+                    continue;
+                };
+                if input.owner != owner {
+                    let kind = ObjectTransitionKind::Transferred;
+                    let from = Some(&input.owner);
+                    record(id, version, outputs.get(&id), kind, from, Some(&owner));
+                }
+            }
+            for (id, version, _) in fx.wrapped() {
+                let input = inputs.get(&id);
+                let from = input.map(|o| &o.owner);
+                record(
+                    id,
+                    version,
+                    input,
+                    ObjectTransitionKind::Wrapped,
+                    from,
+                    None,
+                );
+            }
+            for (id, version, _) in fx.deleted() {
+                let input = inputs.get(&id);
+                let from = input.map(|o| &o.owner);
+                record(
+                    id,
+                    version,
+                    input,
+                    ObjectTransitionKind::Deleted,
+                    from,
+                    None,
+                );
+            }
+        }
+        transfers
+    }
+
     async fn index_objects(
         data: CheckpointData,
         metrics: &IndexerMetrics,
@@ -749,16 +1106,6 @@ where
             })
             .collect()
     }
-
-    fn pg_blocking_cp(&self) -> Result<PgConnectionPool, IndexerError> {
-        let state_as_any = self.state.as_any();
-        if let Some(pg_state) = state_as_any.downcast_ref::<PgIndexerStore>() {
-            return Ok(pg_state.blocking_cp());
-        }
-        Err(IndexerError::UncategorizedError(anyhow::anyhow!(
-            "Failed to downcast state to PgIndexerStore"
-        )))
-    }
 }
 
 pub fn get_deleted_objects(effects: &TransactionEffects) -> Vec<ObjectRef> {
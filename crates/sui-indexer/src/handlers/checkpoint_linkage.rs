@@ -0,0 +1,84 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_types::digests::CheckpointDigest;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::errors::IndexerError;
+
+/// The tip of the chain of checkpoints indexed so far, which every checkpoint ingested next must
+/// extend. Checkpoints are final, so a checkpoint that does not extend it can only come from a
+/// misbehaving source, and must not be committed.
+#[derive(Clone, Debug, Default)]
+pub struct CheckpointLinkage {
+    tip: Option<(CheckpointSequenceNumber, CheckpointDigest)>,
+}
+
+impl CheckpointLinkage {
+    /// Starting from the latest committed checkpoint, if any.
+    pub fn new(tip: Option<(CheckpointSequenceNumber, CheckpointDigest)>) -> Self {
+        Self { tip }
+    }
+
+    /// Check that the checkpoint `sequence_number`, with digest `digest`, directly follows the
+    /// tip, and links to it through `previous_digest`, and if so, make it the new tip.
+    pub fn extend(
+        &mut self,
+        sequence_number: CheckpointSequenceNumber,
+        digest: CheckpointDigest,
+        previous_digest: Option<CheckpointDigest>,
+    ) -> Result<(), IndexerError> {
+        match self.tip {
+            Some((tip, _)) if sequence_number != tip + 1 => {
+                return Err(IndexerError::CheckpointForkError(format!(
+                    "checkpoint {sequence_number} does not follow the latest indexed checkpoint \
+                     {tip}"
+                )));
+            }
+            Some((tip, tip_digest)) if previous_digest != Some(tip_digest) => {
+                return Err(IndexerError::CheckpointForkError(format!(
+                    "checkpoint {sequence_number} links to previous checkpoint {previous_digest:?}, \
+                     but checkpoint {tip} was indexed with digest {tip_digest}"
+                )));
+            }
+            None if sequence_number == 0 && previous_digest.is_some() => {
+                return Err(IndexerError::CheckpointForkError(format!(
+                    "genesis checkpoint links to previous checkpoint {previous_digest:?}"
+                )));
+            }
+            _ => {}
+        }
+
+        self.tip = Some((sequence_number, digest));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extend() {
+        let digests: Vec<_> = (0..4).map(|_| CheckpointDigest::random()).collect();
+        let mut linkage = CheckpointLinkage::new(Some((10, digests[0])));
+
+        linkage.extend(11, digests[1], Some(digests[0])).unwrap();
+
+        // Skipping a checkpoint, or repeating one.
+        assert!(linkage.extend(13, digests[2], Some(digests[1])).is_err());
+        assert!(linkage.extend(11, digests[2], Some(digests[0])).is_err());
+
+        // Not linking to the tip.
+        assert!(linkage.extend(12, digests[2], Some(digests[3])).is_err());
+        assert!(linkage.extend(12, digests[2], None).is_err());
+
+        // Failed checks leave the tip where it was.
+        linkage.extend(12, digests[2], Some(digests[1])).unwrap();
+
+        let mut linkage = CheckpointLinkage::default();
+        assert!(linkage.extend(0, digests[0], Some(digests[1])).is_err());
+        linkage.extend(0, digests[0], None).unwrap();
+        linkage.extend(1, digests[1], Some(digests[0])).unwrap();
+    }
+}
@@ -2,21 +2,42 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::BTreeMap;
+use std::future::Future;
 
-use tokio::sync::watch;
+use prometheus::HistogramTimer;
+use tokio::sync::{mpsc, watch};
 use tracing::instrument;
 
+use mysten_metrics::spawn_monitored_task;
 use tap::tap::TapFallible;
 use tracing::{error, info};
 
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 use crate::metrics::IndexerMetrics;
+use crate::models::display::StoredDisplay;
+use crate::models::event_addresses::StoredEventAddress;
+use crate::models::event_schemas::StoredEventSchema;
+use crate::models::nft_transfers::StoredNftTransfer;
+use crate::models::object_transitions::StoredObjectTransition;
+use crate::models::package_call_metrics::StoredPackageCallMetrics;
 use crate::store::IndexerStore;
-use crate::types::IndexerResult;
+use crate::types::{
+    IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, IndexerResult, TxIndex,
+};
 
-use super::{CheckpointDataToCommit, EpochToCommit};
+use super::{CheckpointDataToCommit, EpochToCommit, TransactionObjectChangesToCommit};
 
+/// How many batches of checkpoints each table's committer can fall behind by.
+const CHECKPOINT_COMMIT_PIPELINE_DEPTH: usize = 4;
+
+/// Commits indexed checkpoints to the store. Each table (or group of tables written together) has
+/// a committer of its own, committing its part of each batch of checkpoints in order, but
+/// independently of the other tables. Once all tables have committed a batch, its checkpoints
+/// are committed, which marks the batch as committed as a whole.
+///
+/// The tables of a new epoch are partitioned once the previous epoch has been committed, so no
+/// batch is sent to the table committers before all batches of earlier epochs have been committed.
 pub async fn start_tx_checkpoint_commit_task<S>(
     state: S,
     metrics: IndexerMetrics,
@@ -33,6 +54,23 @@ pub async fn start_tx_checkpoint_commit_task<S>(
         .parse::<usize>()
         .unwrap();
     info!("Using checkpoint commit batch size {checkpoint_commit_batch_size}");
+    let pipeline_depth = std::env::var("CHECKPOINT_COMMIT_PIPELINE_DEPTH")
+        .unwrap_or(CHECKPOINT_COMMIT_PIPELINE_DEPTH.to_string())
+        .parse::<usize>()
+        .unwrap()
+        .max(1);
+    info!("Using checkpoint commit pipeline depth {pipeline_depth}");
+
+    let committers = TableCommitters::spawn(&state, pipeline_depth);
+    let mut committed = commit_notifier.subscribe();
+    let (watermark_sender, watermark_receiver) = mpsc::channel(pipeline_depth);
+    let watermark_task = spawn_monitored_task!(commit_watermarks(
+        state,
+        metrics.clone(),
+        committers.progress(),
+        watermark_receiver,
+        commit_notifier,
+    ));
 
     let mut stream = mysten_metrics::metered_channel::ReceiverStream::new(tx_indexing_receiver)
         .ready_chunks(checkpoint_commit_batch_size);
@@ -47,49 +85,66 @@ pub async fn start_tx_checkpoint_commit_task<S>(
             let epoch = indexed_checkpoint.epoch.clone();
             indexed_checkpoint_batch_per_epoch.push(indexed_checkpoint);
             if epoch.is_some() {
-                commit_checkpoints(
-                    &state,
+                let last_checkpoint_seq = commit_checkpoints(
+                    &committers,
+                    &watermark_sender,
                     indexed_checkpoint_batch_per_epoch,
                     epoch,
                     &metrics,
-                    &commit_notifier,
                 )
                 .await;
+                // Wait for the epoch to be committed, and the next one's partitions created.
+                committed
+                    .wait_for(|c| matches!(c, Some(c) if *c >= last_checkpoint_seq))
+                    .await
+                    .expect("Commit watcher should not be closed");
                 indexed_checkpoint_batch_per_epoch = vec![];
             }
         }
         if !indexed_checkpoint_batch_per_epoch.is_empty() {
             commit_checkpoints(
-                &state,
+                &committers,
+                &watermark_sender,
                 indexed_checkpoint_batch_per_epoch,
                 None,
                 &metrics,
-                &commit_notifier,
             )
             .await;
         }
     }
+
+    // Let the committers finish the batches they have been sent.
+    drop(committers);
+    drop(watermark_sender);
+    watermark_task
+        .await
+        .expect("Checkpoint watermark task should not fail");
 }
 
+/// Send `indexed_checkpoint_batch` to the table committers, and to be marked as committed once
+/// they have committed it, returning the sequence number of its last checkpoint.
 // Unwrap: Caller needs to make sure indexed_checkpoint_batch is not empty
 #[instrument(skip_all, fields(
     first = indexed_checkpoint_batch.first().as_ref().unwrap().checkpoint.sequence_number,
     last = indexed_checkpoint_batch.last().as_ref().unwrap().checkpoint.sequence_number
 ))]
-async fn commit_checkpoints<S>(
-    state: &S,
+async fn commit_checkpoints(
+    committers: &TableCommitters,
+    watermark_sender: &mpsc::Sender<(CheckpointSequenceNumber, PendingWatermark)>,
     indexed_checkpoint_batch: Vec<CheckpointDataToCommit>,
     epoch: Option<EpochToCommit>,
     metrics: &IndexerMetrics,
-    commit_notifier: &watch::Sender<Option<CheckpointSequenceNumber>>,
-) where
-    S: IndexerStore + Clone + Sync + Send + 'static,
-{
+) -> CheckpointSequenceNumber {
     let mut checkpoint_batch = vec![];
     let mut tx_batch = vec![];
     let mut events_batch = vec![];
     let mut tx_indices_batch = vec![];
     let mut display_updates_batch = BTreeMap::new();
+    let mut event_schemas_batch = BTreeMap::new();
+    let mut event_addresses_batch = vec![];
+    let mut object_transitions_batch = vec![];
+    let mut nft_transfers_batch = vec![];
+    let mut package_call_metrics_batch = vec![];
     let mut object_changes_batch = vec![];
     let mut object_history_changes_batch = vec![];
     let mut packages_batch = vec![];
@@ -101,6 +156,11 @@ async fn commit_checkpoints<S>(
             events,
             tx_indices,
             display_updates,
+            event_schemas,
+            event_addresses,
+            object_transitions,
+            nft_transfers,
+            package_call_metrics,
             object_changes,
             object_history_changes,
             packages,
@@ -111,51 +171,175 @@ async fn commit_checkpoints<S>(
         events_batch.push(events);
         tx_indices_batch.push(tx_indices);
         display_updates_batch.extend(display_updates.into_iter());
+        for (key, schema) in event_schemas {
+            event_schemas_batch
+                .entry(key)
+                .and_modify(|s: &mut StoredEventSchema| s.merge(&schema))
+                .or_insert(schema);
+        }
+        event_addresses_batch.push(event_addresses);
+        object_transitions_batch.push(object_transitions);
+        nft_transfers_batch.push(nft_transfers);
+        package_call_metrics_batch.push(package_call_metrics);
         object_changes_batch.push(object_changes);
         object_history_changes_batch.push(object_history_changes);
         packages_batch.push(packages);
     }
 
-    let first_checkpoint_seq = checkpoint_batch.first().as_ref().unwrap().sequence_number;
     let last_checkpoint_seq = checkpoint_batch.last().as_ref().unwrap().sequence_number;
 
     let guard = metrics.checkpoint_db_commit_latency.start_timer();
+    let step_1_guard = metrics.checkpoint_db_commit_latency_step_1.start_timer();
     let tx_batch = tx_batch.into_iter().flatten().collect::<Vec<_>>();
     let tx_indices_batch = tx_indices_batch.into_iter().flatten().collect::<Vec<_>>();
     let events_batch = events_batch.into_iter().flatten().collect::<Vec<_>>();
     let packages_batch = packages_batch.into_iter().flatten().collect::<Vec<_>>();
-    let checkpoint_num = checkpoint_batch.len();
+    let event_addresses_batch = event_addresses_batch
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    let object_transitions_batch = object_transitions_batch
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    let nft_transfers_batch = nft_transfers_batch
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    let package_call_metrics_batch = package_call_metrics_batch
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
     let tx_count = tx_batch.len();
 
-    {
-        let _step_1_guard = metrics.checkpoint_db_commit_latency_step_1.start_timer();
-        let mut persist_tasks = vec![
-            state.persist_transactions(tx_batch),
-            state.persist_tx_indices(tx_indices_batch),
-            state.persist_events(events_batch),
-            state.persist_displays(display_updates_batch),
-            state.persist_packages(packages_batch),
-            state.persist_objects(object_changes_batch.clone()),
-            state.persist_object_history(object_history_changes_batch.clone()),
-        ];
-        if let Some(epoch_data) = epoch.clone() {
-            persist_tasks.push(state.persist_epoch(epoch_data));
+    let seq = last_checkpoint_seq;
+    committers.transactions.send(seq, tx_batch).await;
+    committers.tx_indices.send(seq, tx_indices_batch).await;
+    committers.events.send(seq, events_batch).await;
+    committers.displays.send(seq, display_updates_batch).await;
+    committers
+        .event_schemas
+        .send(seq, event_schemas_batch.into_values().collect())
+        .await;
+    committers
+        .event_addresses
+        .send(seq, event_addresses_batch)
+        .await;
+    committers
+        .object_transitions
+        .send(seq, object_transitions_batch)
+        .await;
+    committers
+        .nft_transfers
+        .send(seq, nft_transfers_batch)
+        .await;
+    committers
+        .package_call_metrics
+        .send(seq, package_call_metrics_batch)
+        .await;
+    committers.packages.send(seq, packages_batch).await;
+    committers.objects.send(seq, object_changes_batch).await;
+    committers
+        .objects_history
+        .send(seq, object_history_changes_batch)
+        .await;
+
+    let pending = PendingWatermark {
+        checkpoint_batch,
+        epoch,
+        tx_count,
+        guard,
+        step_1_guard,
+    };
+    watermark_sender
+        .send((last_checkpoint_seq, pending))
+        .await
+        .expect("Checkpoint watermark task should not be closed");
+    last_checkpoint_seq
+}
+
+/// A batch of checkpoints sent to the table committers, to be committed once they have committed
+/// their parts of it.
+struct PendingWatermark {
+    checkpoint_batch: Vec<IndexedCheckpoint>,
+    epoch: Option<EpochToCommit>,
+    tx_count: usize,
+    guard: HistogramTimer,
+    step_1_guard: HistogramTimer,
+}
+
+/// Commit the checkpoints of each batch received from `receiver`, in order, once all tables have
+/// committed the batch (as reported by their `progress`).
+async fn commit_watermarks<S>(
+    state: S,
+    metrics: IndexerMetrics,
+    progress: Vec<watch::Receiver<Option<CheckpointSequenceNumber>>>,
+    receiver: mpsc::Receiver<(CheckpointSequenceNumber, PendingWatermark)>,
+    commit_notifier: watch::Sender<Option<CheckpointSequenceNumber>>,
+) where
+    S: IndexerStore + Clone + Sync + Send + 'static,
+{
+    commit_in_order(progress, receiver, |pending| {
+        commit_watermark(&state, &metrics, &commit_notifier, pending)
+    })
+    .await
+}
+
+/// Pass each batch received from `receiver`, identified by its last checkpoint, to `commit` once
+/// all tables have committed it (as reported by their `progress`). Batches are committed one at a
+/// time, in the order they were received, so a batch at an epoch boundary is only committed after
+/// all earlier batches. Panics if a table committer fails, before committing the batch it failed
+/// on.
+async fn commit_in_order<T, F, Fut>(
+    mut progress: Vec<watch::Receiver<Option<CheckpointSequenceNumber>>>,
+    mut receiver: mpsc::Receiver<(CheckpointSequenceNumber, T)>,
+    mut commit: F,
+) where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    while let Some((last_checkpoint_seq, batch)) = receiver.recv().await {
+        for committed in &mut progress {
+            committed
+                .wait_for(|c| matches!(c, Some(c) if *c >= last_checkpoint_seq))
+                .await
+                .expect("Table committers should not fail");
         }
-        futures::future::join_all(persist_tasks)
-            .await
-            .into_iter()
-            .map(|res| {
-                if res.is_err() {
-                    error!("Failed to persist data with error: {:?}", res);
-                }
-                res
-            })
-            .collect::<IndexerResult<Vec<_>>>()
-            .expect("Persisting data into DB should not fail.");
+        commit(batch).await;
     }
+}
+
+/// Commit the checkpoints of a batch that all tables have committed, along with its epoch if it
+/// ends one, and notify `commit_notifier` of them.
+async fn commit_watermark<S>(
+    state: &S,
+    metrics: &IndexerMetrics,
+    commit_notifier: &watch::Sender<Option<CheckpointSequenceNumber>>,
+    pending: PendingWatermark,
+) where
+    S: IndexerStore + Clone + Sync + Send + 'static,
+{
+    let PendingWatermark {
+        checkpoint_batch,
+        epoch,
+        tx_count,
+        guard,
+        step_1_guard,
+    } = pending;
+    let first_checkpoint_seq = checkpoint_batch.first().as_ref().unwrap().sequence_number;
+    let last_checkpoint_seq = checkpoint_batch.last().as_ref().unwrap().sequence_number;
+    let checkpoint_num = checkpoint_batch.len();
+    step_1_guard.stop_and_record();
 
     // handle partitioning on epoch boundary
     if let Some(epoch_data) = epoch {
+        state
+            .persist_epoch(epoch_data.clone())
+            .await
+            .tap_err(|e| {
+                error!("Failed to persist epoch with error: {}", e.to_string());
+            })
+            .expect("Persisting data into DB should not fail.");
         state
             .advance_epoch(epoch_data)
             .await
@@ -206,3 +390,262 @@ async fn commit_checkpoints<S>(
         .thousand_transaction_avg_db_commit_latency
         .observe(elapsed * 1000.0 / tx_count as f64);
 }
+
+/// The committers of the tables written for each batch of checkpoints, other than `checkpoints`
+/// and `epochs`, which are written once the batch has been committed to all of these.
+struct TableCommitters {
+    transactions: TableCommitter<Vec<IndexedTransaction>>,
+    tx_indices: TableCommitter<Vec<TxIndex>>,
+    events: TableCommitter<Vec<IndexedEvent>>,
+    displays: TableCommitter<BTreeMap<String, StoredDisplay>>,
+    event_schemas: TableCommitter<Vec<StoredEventSchema>>,
+    event_addresses: TableCommitter<Vec<StoredEventAddress>>,
+    object_transitions: TableCommitter<Vec<StoredObjectTransition>>,
+    nft_transfers: TableCommitter<Vec<StoredNftTransfer>>,
+    package_call_metrics: TableCommitter<Vec<StoredPackageCallMetrics>>,
+    packages: TableCommitter<Vec<IndexedPackage>>,
+    objects: TableCommitter<Vec<TransactionObjectChangesToCommit>>,
+    objects_history: TableCommitter<Vec<TransactionObjectChangesToCommit>>,
+}
+
+impl TableCommitters {
+    fn spawn<S>(state: &S, depth: usize) -> Self
+    where
+        S: IndexerStore + Clone + Sync + Send + 'static,
+    {
+        Self {
+            transactions: TableCommitter::spawn(
+                "transactions",
+                state,
+                depth,
+                |s: S, data| async move { s.persist_transactions(data).await },
+            ),
+            tx_indices: TableCommitter::spawn(
+                "tx_indices",
+                state,
+                depth,
+                |s: S, data| async move { s.persist_tx_indices(data).await },
+            ),
+            events: TableCommitter::spawn("events", state, depth, |s: S, data| async move {
+                s.persist_events(data).await
+            }),
+            displays: TableCommitter::spawn("display", state, depth, |s: S, data| async move {
+                s.persist_displays(data).await
+            }),
+            event_schemas: TableCommitter::spawn(
+                "event_schemas",
+                state,
+                depth,
+                |s: S, data| async move { s.persist_event_schemas(data).await },
+            ),
+            event_addresses: TableCommitter::spawn(
+                "event_addresses",
+                state,
+                depth,
+                |s: S, data| async move { s.persist_event_addresses(data).await },
+            ),
+            object_transitions: TableCommitter::spawn(
+                "object_transitions",
+                state,
+                depth,
+                |s: S, data| async move { s.persist_object_transitions(data).await },
+            ),
+            nft_transfers: TableCommitter::spawn(
+                "nft_transfers",
+                state,
+                depth,
+                |s: S, data| async move { s.persist_nft_transfers(data).await },
+            ),
+            package_call_metrics: TableCommitter::spawn(
+                "package_call_metrics",
+                state,
+                depth,
+                |s: S, data| async move { s.persist_package_call_metrics(data).await },
+            ),
+            packages: TableCommitter::spawn("packages", state, depth, |s: S, data| async move {
+                s.persist_packages(data).await
+            }),
+            objects: TableCommitter::spawn("objects", state, depth, |s: S, data| async move {
+                s.persist_objects(data).await
+            }),
+            objects_history: TableCommitter::spawn(
+                "objects_history",
+                state,
+                depth,
+                |s: S, data| async move { s.persist_object_history(data).await },
+            ),
+        }
+    }
+
+    /// The last checkpoint committed by each of the committers.
+    fn progress(&self) -> Vec<watch::Receiver<Option<CheckpointSequenceNumber>>> {
+        vec![
+            self.transactions.committed.clone(),
+            self.tx_indices.committed.clone(),
+            self.events.committed.clone(),
+            self.displays.committed.clone(),
+            self.event_schemas.committed.clone(),
+            self.event_addresses.committed.clone(),
+            self.object_transitions.committed.clone(),
+            self.nft_transfers.committed.clone(),
+            self.package_call_metrics.committed.clone(),
+            self.packages.committed.clone(),
+            self.objects.committed.clone(),
+            self.objects_history.committed.clone(),
+        ]
+    }
+}
+
+/// Commits the data of a table for each batch of checkpoints sent to it, in order, in a task of
+/// its own.
+struct TableCommitter<T> {
+    sender: mpsc::Sender<(CheckpointSequenceNumber, T)>,
+    /// The last checkpoint of the last batch committed.
+    committed: watch::Receiver<Option<CheckpointSequenceNumber>>,
+}
+
+impl<T: Send + 'static> TableCommitter<T> {
+    fn spawn<S, F, Fut>(table: &'static str, state: &S, depth: usize, persist: F) -> Self
+    where
+        S: Clone + Send + 'static,
+        F: Fn(S, T) -> Fut + Send + 'static,
+        Fut: Future<Output = IndexerResult<()>> + Send + 'static,
+    {
+        let (sender, mut receiver) = mpsc::channel::<(CheckpointSequenceNumber, T)>(depth);
+        let (committed_sender, committed) = watch::channel(None);
+        let state = state.clone();
+        spawn_monitored_task!(async move {
+            while let Some((last_checkpoint_seq, data)) = receiver.recv().await {
+                persist(state.clone(), data)
+                    .await
+                    .tap_err(|e| {
+                        error!("Failed to persist {table} with error: {:?}", e);
+                    })
+                    .expect("Persisting data into DB should not fail.");
+                committed_sender.send_replace(Some(last_checkpoint_seq));
+            }
+        });
+        Self { sender, committed }
+    }
+
+    /// Send the data of the batch of checkpoints ending with `last_checkpoint_seq`, waiting if the
+    /// committer is too far behind.
+    async fn send(&self, last_checkpoint_seq: CheckpointSequenceNumber, data: T) {
+        self.sender
+            .send((last_checkpoint_seq, data))
+            .await
+            .expect("Table committer should not be closed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::errors::IndexerError;
+
+    /// Run `commit_in_order` over `progress`, returning a sender of batches to it, a receiver of
+    /// the batches it commits, and its task.
+    fn spawn_commit_in_order(
+        progress: Vec<watch::Receiver<Option<CheckpointSequenceNumber>>>,
+    ) -> (
+        mpsc::Sender<(CheckpointSequenceNumber, CheckpointSequenceNumber)>,
+        mpsc::UnboundedReceiver<CheckpointSequenceNumber>,
+        tokio::task::JoinHandle<()>,
+    ) {
+        let (sender, receiver) = mpsc::channel(8);
+        let (committed_sender, committed) = mpsc::unbounded_channel();
+        let task = tokio::spawn(commit_in_order(progress, receiver, move |seq| {
+            committed_sender.send(seq).unwrap();
+            async {}
+        }));
+        (sender, committed, task)
+    }
+
+    async fn assert_nothing_committed(committed: &mut mpsc::UnboundedReceiver<u64>) {
+        let next = tokio::time::timeout(Duration::from_millis(100), committed.recv()).await;
+        assert!(next.is_err(), "Unexpected commit: {next:?}");
+    }
+
+    #[tokio::test]
+    async fn test_commit_waits_for_every_table() {
+        let tables: Vec<_> = (0..3).map(|_| watch::channel(None)).collect();
+        let progress = tables.iter().map(|(_, r)| r.clone()).collect();
+        let (sender, mut committed, _task) = spawn_commit_in_order(progress);
+
+        sender.send((5, 5)).await.unwrap();
+        sender.send((10, 10)).await.unwrap();
+        tables[0].0.send_replace(Some(10));
+        tables[1].0.send_replace(Some(5));
+        assert_nothing_committed(&mut committed).await;
+
+        // The last table catches up with the first batch, but not the second.
+        tables[2].0.send_replace(Some(7));
+        assert_eq!(committed.recv().await, Some(5));
+        assert_nothing_committed(&mut committed).await;
+
+        tables[1].0.send_replace(Some(10));
+        tables[2].0.send_replace(Some(10));
+        assert_eq!(committed.recv().await, Some(10));
+    }
+
+    #[tokio::test]
+    async fn test_epoch_boundary_waits_for_earlier_batches() {
+        let (table, progress) = watch::channel(None);
+        let (sender, receiver) = mpsc::channel(8);
+        let (started_sender, mut started) = mpsc::unbounded_channel();
+        let (release, released) = oneshot::channel::<()>();
+        let mut released = Some(released);
+        tokio::spawn(commit_in_order(vec![progress], receiver, move |seq| {
+            started_sender.send(seq).unwrap();
+            let released = released.take();
+            async move {
+                // Hold up the commit of the first batch.
+                if let Some(released) = released {
+                    released.await.unwrap();
+                }
+            }
+        }));
+
+        // The batch ending the epoch has been committed to the table, along with the one before
+        // it, but the epoch is only committed once the earlier batch has been.
+        sender.send((5, 5)).await.unwrap();
+        sender.send((10, 10)).await.unwrap();
+        table.send_replace(Some(10));
+        assert_eq!(started.recv().await, Some(5));
+        assert_nothing_committed(&mut started).await;
+
+        release.send(()).unwrap();
+        assert_eq!(started.recv().await, Some(10));
+    }
+
+    #[tokio::test]
+    async fn test_failing_table_stops_commits() {
+        let healthy = TableCommitter::spawn("healthy", &(), 4, |_, _: bool| async { Ok(()) });
+        let failing = TableCommitter::spawn("failing", &(), 4, |_, fail: bool| async move {
+            if fail {
+                Err(IndexerError::PostgresWriteError("closed".into()))
+            } else {
+                Ok(())
+            }
+        });
+        let progress = vec![healthy.committed.clone(), failing.committed.clone()];
+        let (sender, mut committed, task) = spawn_commit_in_order(progress);
+
+        for seq in [5, 10, 15] {
+            sender.send((seq, seq)).await.unwrap();
+        }
+        for (seq, fail) in [(5, false), (10, true)] {
+            healthy.send(seq, false).await;
+            failing.send(seq, fail).await;
+        }
+
+        // Nothing is committed past the last batch the failing table committed.
+        assert_eq!(committed.recv().await, Some(5));
+        assert!(task.await.is_err());
+        assert_eq!(committed.recv().await, None);
+    }
+}
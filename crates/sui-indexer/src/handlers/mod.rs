@@ -4,7 +4,11 @@
 use std::collections::BTreeMap;
 
 use crate::{
-    models::display::StoredDisplay,
+    models::{
+        display::StoredDisplay, event_addresses::StoredEventAddress,
+        event_schemas::StoredEventSchema, nft_transfers::StoredNftTransfer,
+        object_transitions::StoredObjectTransition, package_call_metrics::StoredPackageCallMetrics,
+    },
     types::{
         IndexedCheckpoint, IndexedDeletedObject, IndexedEpochInfo, IndexedEvent, IndexedObject,
         IndexedPackage, IndexedTransaction, TxIndex,
@@ -12,7 +16,10 @@ use crate::{
 };
 
 pub mod checkpoint_handler;
+pub mod checkpoint_linkage;
 pub mod committer;
+pub mod scope;
+pub mod tx_classes;
 pub mod tx_processor;
 
 #[derive(Debug)]
@@ -22,6 +29,11 @@ pub struct CheckpointDataToCommit {
     pub events: Vec<IndexedEvent>,
     pub tx_indices: Vec<TxIndex>,
     pub display_updates: BTreeMap<String, StoredDisplay>,
+    pub event_schemas: BTreeMap<(String, Vec<u8>), StoredEventSchema>,
+    pub event_addresses: Vec<StoredEventAddress>,
+    pub object_transitions: Vec<StoredObjectTransition>,
+    pub nft_transfers: Vec<StoredNftTransfer>,
+    pub package_call_metrics: Vec<StoredPackageCallMetrics>,
     pub object_changes: TransactionObjectChangesToCommit,
     pub object_history_changes: TransactionObjectChangesToCommit,
     pub packages: Vec<IndexedPackage>,
@@ -0,0 +1,202 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Indexing only the data of a set of packages and addresses, so that a project can run an
+//! indexer holding just what its own protocol needs.
+//!
+//! A transaction is in scope if it calls a function of one of the packages, emits an event whose
+//! type mentions one of them, is sent by one of the addresses, or reads or writes an object that
+//! is in scope: one of the packages, an object whose type mentions one of them, or an object
+//! owned by one of the addresses. System transactions are always in scope, so that the system
+//! state and the clock can be read.
+//!
+//! Transactions in scope are indexed with all of their events, and every object they touch,
+//! whatever its type (e.g. the coins they pay with, or the dynamic fields they change), so that
+//! their objects can be read back. Objects that are only in scope because a transaction in scope
+//! touched them are kept at the version it left them at until another transaction in scope
+//! touches them, but deletions are applied whoever makes them. Packages are always indexed, as
+//! they are needed to resolve the layouts of the objects that are.
+//!
+//! Packages are matched by ID, so upgrades of a package must be added to the scope too.
+
+use std::collections::{BTreeSet, HashSet};
+
+use move_core_types::language_storage::{StructTag, TypeTag};
+use sui_rest_api::{CheckpointData, CheckpointTransaction};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::digests::TransactionDigest;
+use sui_types::object::{Object, Owner};
+use sui_types::parse_sui_struct_tag;
+use sui_types::transaction::TransactionDataAPI;
+
+use super::CheckpointDataToCommit;
+
+/// The packages and addresses whose data is indexed.
+#[derive(Clone, Debug, Default)]
+pub struct IndexingScope {
+    packages: BTreeSet<ObjectID>,
+    addresses: BTreeSet<SuiAddress>,
+}
+
+/// What of a checkpoint is in scope.
+#[derive(Debug, Default)]
+pub(crate) struct Selection {
+    transactions: HashSet<TransactionDigest>,
+    /// The objects read or written by transactions in scope.
+    objects: HashSet<ObjectID>,
+}
+
+impl IndexingScope {
+    pub fn new(
+        packages: impl IntoIterator<Item = ObjectID>,
+        addresses: impl IntoIterator<Item = SuiAddress>,
+    ) -> Self {
+        Self {
+            packages: packages.into_iter().collect(),
+            addresses: addresses.into_iter().collect(),
+        }
+    }
+
+    /// The transactions and objects of `data` that are in scope.
+    pub(crate) fn select(&self, data: &CheckpointData) -> Selection {
+        let mut selection = Selection::default();
+        for tx in data.transactions.iter().filter(|tx| self.includes(tx)) {
+            selection.transactions.insert(*tx.transaction.digest());
+            selection.objects.extend(
+                tx.input_objects
+                    .iter()
+                    .chain(tx.output_objects.iter())
+                    .map(Object::id),
+            );
+        }
+        selection
+    }
+
+    fn includes(&self, tx: &CheckpointTransaction) -> bool {
+        let data = tx.transaction.transaction_data();
+        data.is_system_tx()
+            || self.addresses.contains(&data.sender())
+            || data
+                .move_calls()
+                .iter()
+                .any(|(package, _, _)| self.packages.contains(package))
+            || tx
+                .events
+                .iter()
+                .flat_map(|events| &events.data)
+                .any(|event| {
+                    self.packages.contains(&event.package_id) || self.mentions_struct(&event.type_)
+                })
+            || tx
+                .input_objects
+                .iter()
+                .chain(tx.output_objects.iter())
+                .any(|object| self.includes_object(object))
+    }
+
+    fn includes_object(&self, object: &Object) -> bool {
+        if object.is_package() {
+            return self.packages.contains(&object.id());
+        }
+        matches!(object.owner, Owner::AddressOwner(owner) if self.addresses.contains(&owner))
+            || object
+                .struct_tag()
+                .is_some_and(|type_| self.mentions_struct(&type_))
+    }
+
+    /// Whether `type_` or any of its type parameters is defined in one of the packages.
+    fn mentions_struct(&self, type_: &StructTag) -> bool {
+        self.packages.contains(&type_.address.into())
+            || type_.type_params.iter().any(|t| self.mentions(t))
+    }
+
+    fn mentions(&self, type_: &TypeTag) -> bool {
+        match type_ {
+            TypeTag::Struct(type_) => self.mentions_struct(type_),
+            TypeTag::Vector(type_) => self.mentions(type_),
+            _ => false,
+        }
+    }
+
+    fn mentions_type_str(&self, type_: &str) -> bool {
+        parse_sui_struct_tag(type_).is_ok_and(|type_| self.mentions_struct(&type_))
+    }
+
+    /// Drop what is out of scope from `data`, a checkpoint indexed from the one that `selection`
+    /// was made from.
+    pub(crate) fn retain(&self, selection: &Selection, data: &mut CheckpointDataToCommit) {
+        let in_scope = |digest: &TransactionDigest| selection.transactions.contains(digest);
+        data.transactions.retain(|tx| in_scope(&tx.tx_digest));
+        data.events
+            .retain(|event| in_scope(&event.transaction_digest));
+        let tx_sequence_numbers: HashSet<_> = data
+            .transactions
+            .iter()
+            .map(|tx| tx.tx_sequence_number as i64)
+            .collect();
+        data.event_addresses
+            .retain(|address| tx_sequence_numbers.contains(&address.tx_sequence_number));
+        data.tx_indices
+            .retain(|index| in_scope(&index.transaction_digest));
+        data.object_transitions.retain(|transition| {
+            TransactionDigest::try_from(transition.transaction_digest.as_slice())
+                .is_ok_and(|digest| in_scope(&digest))
+        });
+        data.nft_transfers.retain(|transfer| {
+            TransactionDigest::try_from(transfer.transaction_digest.as_slice())
+                .is_ok_and(|digest| in_scope(&digest))
+        });
+        // Every transaction calling one of the packages is in scope, so their metrics are whole.
+        data.package_call_metrics.retain(|metrics| {
+            ObjectID::from_bytes(&metrics.package).is_ok_and(|id| self.packages.contains(&id))
+        });
+
+        let touched = |id: &ObjectID| selection.objects.contains(id);
+        data.object_changes
+            .changed_objects
+            .retain(|object| touched(&object.object_id));
+        data.object_history_changes
+            .changed_objects
+            .retain(|object| touched(&object.object_id));
+        data.object_history_changes
+            .deleted_objects
+            .retain(|object| touched(&object.object_id));
+
+        data.display_updates
+            .retain(|object_type, _| self.mentions_type_str(object_type));
+        data.event_schemas
+            .retain(|(event_type, _), _| self.mentions_type_str(event_type));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sui_types::base_types::dbg_addr;
+
+    use super::*;
+
+    fn scope() -> IndexingScope {
+        IndexingScope::new([ObjectID::from_str("0xabc").unwrap()], [dbg_addr(1)])
+    }
+
+    #[test]
+    fn test_mentions() {
+        let scope = scope();
+        assert!(scope.mentions_type_str("0xabc::usdc::USDC"));
+        assert!(scope.mentions_type_str("0x2::coin::Coin<0xabc::usdc::USDC>"));
+        assert!(scope.mentions_type_str("0x2::table::Table<u64, vector<0xabc::pool::Pool>>"));
+        assert!(!scope.mentions_type_str("0x2::coin::Coin<0x2::sui::SUI>"));
+        assert!(!scope.mentions_type_str("not a type"));
+    }
+
+    #[test]
+    fn test_includes_object() {
+        let scope = scope();
+        let owned = Object::with_id_owner_for_testing(ObjectID::random(), dbg_addr(1));
+        let other = Object::with_id_owner_for_testing(ObjectID::random(), dbg_addr(2));
+        assert!(scope.includes_object(&owned));
+        assert!(!scope.includes_object(&other));
+    }
+}
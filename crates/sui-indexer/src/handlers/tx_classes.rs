@@ -0,0 +1,123 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_json_rpc_types::TransactionClass;
+use sui_types::governance::{
+    ADD_STAKE_FUN_NAME, ADD_STAKE_MUL_COIN_FUN_NAME, WITHDRAW_STAKE_FUN_NAME,
+};
+use sui_types::signature::GenericSignature;
+use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
+use sui_types::transaction::{Command, TransactionData, TransactionDataAPI, TransactionKind};
+use sui_types::{DEEPBOOK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
+
+/// DeepBook modules that orders are placed on, and swaps made through.
+const DEEPBOOK_CLOB_MODULES: &[&str] = &["clob", "clob_v2"];
+
+/// The classes that the transaction `tx`, signed with `signatures`, belongs to, in the order they
+/// are declared in.
+pub fn classify_transaction(
+    tx: &TransactionData,
+    signatures: &[GenericSignature],
+) -> Vec<TransactionClass> {
+    let mut classes = vec![];
+
+    if let TransactionKind::ProgrammableTransaction(pt) = tx.kind() {
+        let (mut transfers, mut calls, mut publishes, mut upgrades) = (false, false, false, false);
+        for command in &pt.commands {
+            match command {
+                Command::TransferObjects(..) => transfers = true,
+                Command::MoveCall(_) => calls = true,
+                Command::Publish(..) => publishes = true,
+                Command::Upgrade(..) => upgrades = true,
+                _ => {}
+            }
+        }
+
+        let move_calls = tx.move_calls();
+        let staking = move_calls.iter().any(|(package, module, function)| {
+            **package == SUI_SYSTEM_PACKAGE_ID
+                && *module == SUI_SYSTEM_MODULE_NAME
+                && [
+                    ADD_STAKE_FUN_NAME,
+                    ADD_STAKE_MUL_COIN_FUN_NAME,
+                    WITHDRAW_STAKE_FUN_NAME,
+                ]
+                .contains(function)
+        });
+        let trading = move_calls.iter().any(|(package, module, function)| {
+            **package == DEEPBOOK_PACKAGE_ID
+                && DEEPBOOK_CLOB_MODULES.contains(&module.as_str())
+                && (function.as_str().starts_with("place_")
+                    || function.as_str().starts_with("swap_exact_"))
+        });
+
+        if transfers && !calls && !publishes && !upgrades {
+            classes.push(TransactionClass::Transfer);
+        }
+        if publishes {
+            classes.push(TransactionClass::Publish);
+        }
+        if upgrades {
+            classes.push(TransactionClass::Upgrade);
+        }
+        if staking {
+            classes.push(TransactionClass::Stake);
+        }
+        if trading {
+            classes.push(TransactionClass::DeepbookTrade);
+        }
+    }
+
+    if tx.is_sponsored_tx() {
+        classes.push(TransactionClass::Sponsored);
+    }
+    if signatures.iter().any(|s| {
+        matches!(
+            s,
+            GenericSignature::MultiSig(_) | GenericSignature::MultiSigLegacy(_)
+        )
+    }) {
+        classes.push(TransactionClass::Multisig);
+    }
+    if signatures.iter().any(|s| s.is_zklogin()) {
+        classes.push(TransactionClass::ZkLogin);
+    }
+
+    classes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_test_transaction_builder::TestTransactionBuilder;
+    use sui_types::base_types::{random_object_ref, SuiAddress};
+
+    #[test]
+    fn test_classify_transaction() {
+        let sender = SuiAddress::random_for_testing_only();
+        let builder = || TestTransactionBuilder::new(sender, random_object_ref(), 1000);
+
+        let transfer = builder()
+            .transfer_sui(Some(1), SuiAddress::random_for_testing_only())
+            .build();
+        assert_eq!(
+            classify_transaction(&transfer, &[]),
+            vec![TransactionClass::Transfer]
+        );
+
+        let stake = builder()
+            .call_staking(random_object_ref(), SuiAddress::random_for_testing_only())
+            .build();
+        assert_eq!(
+            classify_transaction(&stake, &[]),
+            vec![TransactionClass::Stake]
+        );
+
+        let mut sponsored = transfer;
+        sponsored.gas_data_mut().owner = SuiAddress::random_for_testing_only();
+        assert_eq!(
+            classify_transaction(&sponsored, &[]),
+            vec![TransactionClass::Transfer, TransactionClass::Sponsored]
+        );
+    }
+}
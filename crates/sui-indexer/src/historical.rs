@@ -0,0 +1,232 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Families of reads that the reader can serve from a store other than Postgres, so that Postgres
+//! only needs to hold recent data, with historical transactions and checkpoints kept in a
+//! key-value store (e.g. Bigtable, behind the HTTP interface that fullnodes read it through).
+//!
+//! The reader always queries Postgres first, and only asks the historical store for what it did
+//! not find there. Indices are not served from the historical store, so transactions that are
+//! only held there can be looked up by digest, but not queried with filters.
+
+use std::collections::{BTreeSet, HashMap};
+
+use async_trait::async_trait;
+use move_bytecode_utils::module_cache::GetModule;
+use prometheus::Registry;
+
+use sui_json_rpc_types::{
+    Checkpoint, CheckpointId, SuiTransactionBlock, SuiTransactionBlockEffects,
+    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_storage::http_key_value_store::HttpKVStore;
+use sui_storage::key_value_store::TransactionKeyValueStore;
+use sui_storage::key_value_store_metrics::KeyValueStoreMetrics;
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::transaction::Transaction;
+
+use crate::types::IndexerResult;
+
+/// A transaction as held by a historical store, with the checkpoint it was included in.
+pub struct HistoricalTransaction {
+    pub transaction: Transaction,
+    pub effects: TransactionEffects,
+    pub events: TransactionEvents,
+    pub checkpoint: CheckpointSequenceNumber,
+    pub timestamp_ms: u64,
+}
+
+#[async_trait]
+pub trait HistoricalStore: Send + Sync {
+    /// The transactions with `digests`, in the same order, with `None` for those that the store
+    /// does not hold.
+    async fn multi_get_transactions(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> IndexerResult<Vec<Option<HistoricalTransaction>>>;
+
+    async fn get_checkpoint(&self, id: CheckpointId) -> IndexerResult<Option<Checkpoint>>;
+}
+
+/// Serves historical reads from a [`TransactionKeyValueStore`].
+pub struct KeyValueHistoricalStore {
+    kv: TransactionKeyValueStore,
+}
+
+impl KeyValueHistoricalStore {
+    pub fn new(kv: TransactionKeyValueStore) -> Self {
+        Self { kv }
+    }
+
+    /// A store reading from the HTTP key-value store at `base_url`.
+    pub fn new_http(base_url: &str, registry: &Registry) -> IndexerResult<Self> {
+        let metrics = KeyValueStoreMetrics::new(registry);
+        Ok(Self::new(HttpKVStore::new_kv(base_url, metrics)?))
+    }
+}
+
+#[async_trait]
+impl HistoricalStore for KeyValueHistoricalStore {
+    async fn multi_get_transactions(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> IndexerResult<Vec<Option<HistoricalTransaction>>> {
+        let (transactions, effects, _) = self.kv.multi_get(digests, digests, &[]).await?;
+        let checkpoints = self.kv.multi_get_transaction_checkpoint(digests).await?;
+
+        let events_digests: Vec<_> = effects
+            .iter()
+            .flatten()
+            .filter_map(|fx| fx.events_digest().copied())
+            .collect();
+        let events: HashMap<_, _> = events_digests
+            .iter()
+            .copied()
+            .zip(self.kv.multi_get_events(&events_digests).await?)
+            .collect();
+
+        let sequence_numbers: Vec<_> = checkpoints
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let timestamps: HashMap<_, _> = sequence_numbers
+            .iter()
+            .copied()
+            .zip(
+                self.kv
+                    .multi_get_checkpoints_summaries(&sequence_numbers)
+                    .await?,
+            )
+            .filter_map(|(seq, summary)| Some((seq, summary?.timestamp_ms)))
+            .collect();
+
+        Ok(transactions
+            .into_iter()
+            .zip(effects)
+            .zip(checkpoints)
+            .map(|((transaction, effects), checkpoint)| {
+                let (transaction, effects, checkpoint) = (transaction?, effects?, checkpoint?);
+                let events = match effects.events_digest() {
+                    Some(digest) => events.get(digest)?.clone()?,
+                    None => TransactionEvents::default(),
+                };
+                Some(HistoricalTransaction {
+                    transaction,
+                    effects,
+                    events,
+                    checkpoint,
+                    timestamp_ms: *timestamps.get(&checkpoint)?,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_checkpoint(&self, id: CheckpointId) -> IndexerResult<Option<Checkpoint>> {
+        let summary = match id {
+            CheckpointId::SequenceNumber(seq) => {
+                self.kv.multi_get_checkpoints_summaries(&[seq]).await?
+            }
+            CheckpointId::Digest(digest) => {
+                self.kv
+                    .multi_get_checkpoints_summaries_by_digest(&[digest])
+                    .await?
+            }
+        };
+        let Some(summary) = summary.into_iter().next().flatten() else {
+            return Ok(None);
+        };
+        let contents = self
+            .kv
+            .multi_get_checkpoints_contents_by_digest(&[summary.content_digest])
+            .await?;
+        let Some(contents) = contents.into_iter().next().flatten() else {
+            return Ok(None);
+        };
+
+        let signature = summary.auth_sig().signature.clone();
+        Ok(Some((summary.into_data(), contents, signature).into()))
+    }
+}
+
+impl HistoricalTransaction {
+    /// The response to a request for this transaction. Object and balance changes are derived
+    /// from the objects a transaction touched, which the historical store doesn't hold, so they
+    /// are reported as errors if requested.
+    pub fn try_into_sui_transaction_block_response(
+        self,
+        options: &SuiTransactionBlockResponseOptions,
+        module: &impl GetModule,
+    ) -> IndexerResult<SuiTransactionBlockResponse> {
+        let digest = *self.transaction.digest();
+        let sender_signed_data = self.transaction.into_data();
+
+        let raw_transaction = if options.show_raw_input {
+            bcs::to_bytes(&sender_signed_data)?
+        } else {
+            Vec::new()
+        };
+
+        let transaction = if options.show_input {
+            Some(SuiTransactionBlock::try_from(sender_signed_data, module)?)
+        } else {
+            None
+        };
+
+        let raw_effects = if options.show_raw_effects {
+            bcs::to_bytes(&self.effects)?
+        } else {
+            Vec::new()
+        };
+
+        let effects = if options.show_effects {
+            Some(SuiTransactionBlockEffects::try_from(self.effects)?)
+        } else {
+            None
+        };
+
+        let events = if options.show_events {
+            let events = SuiTransactionBlockEvents::try_from_using_module_resolver(
+                self.events,
+                digest,
+                Some(self.timestamp_ms),
+                module,
+            )?;
+            Some(options.project_events(events))
+        } else {
+            None
+        };
+
+        let mut errors = vec![];
+        if options.show_object_changes {
+            errors.push(format!(
+                "Object changes of historical transaction {digest} are not available"
+            ));
+        }
+        if options.show_balance_changes {
+            errors.push(format!(
+                "Balance changes of historical transaction {digest} are not available"
+            ));
+        }
+
+        Ok(SuiTransactionBlockResponse {
+            digest,
+            transaction,
+            raw_transaction,
+            effects,
+            events,
+            object_changes: None,
+            balance_changes: None,
+            timestamp_ms: Some(self.timestamp_ms),
+            checkpoint: Some(self.checkpoint),
+            confirmed_local_execution: None,
+            errors,
+            raw_effects,
+            labels: None,
+        })
+    }
+}
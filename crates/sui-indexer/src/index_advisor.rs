@@ -0,0 +1,700 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reporting the reader's slow queries, and suggesting indexes for them. The advisor samples
+//! `pg_stat_statements` periodically (if the extension is installed), and reports the `SELECT`s
+//! that take longest on average:
+//!
+//! - with the RPC methods they are likely run for: those whose requests rise and fall with the
+//!   query's calls from one sample to the next, as counted by the server's request metrics,
+//! - with the composite index their shape calls for (equality predicates first, then a range
+//!   predicate or the sort order), unless an index already covers it.
+//!
+//! Reports are logged, as each suggestion is first made, and written in full to a file as JSON.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use diesel::sql_types::{BigInt, Bool, Double, Text};
+use diesel::{QueryableByName, RunQueryDsl};
+use prometheus::Registry;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::indexer_reader::IndexerReader;
+use crate::types::IndexerResult;
+
+/// Number of samples of call counts that correlations are computed over, at most.
+const WINDOW: usize = 60;
+
+/// Number of samples of call counts needed before queries can be correlated with methods.
+const MIN_SAMPLES: usize = 5;
+
+/// How closely a method's requests must follow a query's calls for the query to be attributed to
+/// it.
+const MIN_CORRELATION: f64 = 0.7;
+
+/// Number of methods a query is attributed to, at most.
+const MAX_METHODS: usize = 3;
+
+/// Number of slow queries reported, at most.
+const MAX_SLOW_QUERIES: usize = 20;
+
+/// The metric counting the server's requests, by method ("route").
+const REQUESTS_METRIC: &str = "rpc_requests_by_route";
+
+/// Words that can follow a column in a predicate or sort order, but are not columns themselves.
+const KEYWORDS: &[&str] = &[
+    "and", "any", "asc", "between", "desc", "false", "first", "in", "is", "last", "like", "not",
+    "null", "nulls", "or", "true",
+];
+
+#[derive(QueryableByName)]
+struct Installed {
+    #[diesel(sql_type = Bool)]
+    installed: bool,
+}
+
+#[derive(QueryableByName)]
+struct StoredStatement {
+    #[diesel(sql_type = BigInt)]
+    queryid: i64,
+    #[diesel(sql_type = Text)]
+    query: String,
+    #[diesel(sql_type = BigInt)]
+    calls: i64,
+    #[diesel(sql_type = Double)]
+    mean_exec_time: f64,
+}
+
+#[derive(QueryableByName)]
+struct StoredIndex {
+    #[diesel(sql_type = Text)]
+    tablename: String,
+    #[diesel(sql_type = Text)]
+    indexdef: String,
+}
+
+/// A query that is slow on average, in a report of the advisor.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQuery {
+    /// The normalized text of the query, as recorded by `pg_stat_statements`.
+    pub query: String,
+    pub calls: i64,
+    pub mean_exec_time_ms: f64,
+    /// The methods the query is likely run for, most likely first.
+    pub methods: Vec<CorrelatedMethod>,
+    /// An index to create for the query, if it has none that suits it.
+    pub suggested_index: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrelatedMethod {
+    pub method: String,
+    pub correlation: f64,
+}
+
+/// Calls of a statement, or requests of a method, as of the last sample, and their increase over
+/// each of the samples before it.
+struct Counts {
+    last: f64,
+    deltas: VecDeque<f64>,
+}
+
+impl Counts {
+    fn new(count: f64) -> Self {
+        Self {
+            last: count,
+            deltas: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, count: f64) {
+        // Counts go back to zero when statistics are reset.
+        self.deltas.push_back((count - self.last).max(0.0));
+        if self.deltas.len() > WINDOW {
+            self.deltas.pop_front();
+        }
+        self.last = count;
+    }
+}
+
+struct Statement {
+    query: String,
+    mean_exec_time: f64,
+    calls: Counts,
+}
+
+pub struct IndexAdvisor {
+    reader: IndexerReader,
+    registry: Registry,
+    slow_query_ms: f64,
+    output: Option<PathBuf>,
+    statements: HashMap<i64, Statement>,
+    methods: HashMap<String, Counts>,
+    /// Suggestions that have been logged already.
+    suggested: HashSet<String>,
+}
+
+impl IndexAdvisor {
+    /// An advisor reporting the queries run through `reader` that take at least `slow_query_ms`
+    /// on average, correlating them with the request metrics in `registry`, and writing each
+    /// report to `output`, if set.
+    pub fn new(
+        reader: IndexerReader,
+        registry: Registry,
+        slow_query_ms: f64,
+        output: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            reader,
+            registry,
+            slow_query_ms,
+            output,
+            statements: HashMap::new(),
+            methods: HashMap::new(),
+            suggested: HashSet::new(),
+        }
+    }
+
+    /// Sample query statistics every `interval`, reporting once enough samples have been taken.
+    /// Returns straight away if `pg_stat_statements` is not installed.
+    pub async fn run(mut self, interval: Duration) {
+        match self.installed().await {
+            Ok(true) => info!("Starting index advisor..."),
+            Ok(false) => {
+                warn!("pg_stat_statements is not installed, not starting the index advisor");
+                return;
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to check for pg_stat_statements, not starting the index advisor: {e}"
+                );
+                return;
+            }
+        }
+
+        let mut interval = tokio::time::interval(interval);
+        let mut samples = 0;
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sample().await {
+                warn!("Index advisor failed to sample query statistics: {e}");
+                continue;
+            }
+            samples += 1;
+            if samples <= MIN_SAMPLES {
+                continue;
+            }
+
+            match self.report().await {
+                Ok(report) => self.publish(report).await,
+                Err(e) => warn!("Index advisor failed to report: {e}"),
+            }
+        }
+    }
+
+    async fn installed(&self) -> IndexerResult<bool> {
+        let installed = self
+            .reader
+            .run_query_async(|conn| {
+                diesel::sql_query(
+                    "SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'pg_stat_statements') \
+                     AS installed",
+                )
+                .get_result::<Installed>(conn)
+            })
+            .await?;
+        Ok(installed.installed)
+    }
+
+    /// Record the calls of each statement, and the requests of each method, since the last
+    /// sample.
+    async fn sample(&mut self) -> IndexerResult<()> {
+        let stored = self
+            .reader
+            .run_query_async(|conn| {
+                diesel::sql_query(
+                    "SELECT queryid, query, calls, mean_exec_time FROM pg_stat_statements \
+                     WHERE dbid = (SELECT oid FROM pg_database WHERE datname = current_database()) \
+                     AND queryid IS NOT NULL",
+                )
+                .load::<StoredStatement>(conn)
+            })
+            .await?;
+
+        // Statements evicted from `pg_stat_statements` are forgotten.
+        let mut statements = HashMap::with_capacity(stored.len());
+        for stored in stored {
+            let calls = stored.calls as f64;
+            let statement = match self.statements.remove(&stored.queryid) {
+                Some(mut statement) => {
+                    statement.calls.record(calls);
+                    statement.mean_exec_time = stored.mean_exec_time;
+                    statement
+                }
+                None => Statement {
+                    query: stored.query,
+                    mean_exec_time: stored.mean_exec_time,
+                    calls: Counts::new(calls),
+                },
+            };
+            statements.insert(stored.queryid, statement);
+        }
+        self.statements = statements;
+
+        for (method, requests) in self.method_requests() {
+            match self.methods.get_mut(&method) {
+                Some(counts) => counts.record(requests),
+                None => {
+                    self.methods.insert(method, Counts::new(requests));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The number of requests the server has handled for each method.
+    fn method_requests(&self) -> Vec<(String, f64)> {
+        let families = self.registry.gather();
+        let Some(family) = families.iter().find(|f| f.get_name() == REQUESTS_METRIC) else {
+            return vec![];
+        };
+        family
+            .get_metric()
+            .iter()
+            .filter_map(|metric| {
+                let route = metric
+                    .get_label()
+                    .iter()
+                    .find(|label| label.get_name() == "route")?;
+                Some((
+                    route.get_value().to_string(),
+                    metric.get_counter().get_value(),
+                ))
+            })
+            .collect()
+    }
+
+    async fn report(&self) -> IndexerResult<Vec<SlowQuery>> {
+        let mut slow: Vec<_> = self
+            .statements
+            .values()
+            .filter(|s| s.mean_exec_time >= self.slow_query_ms)
+            .filter(|s| s.query.trim_start().to_lowercase().starts_with("select"))
+            .collect();
+        slow.sort_by(|a, b| b.mean_exec_time.total_cmp(&a.mean_exec_time));
+        slow.truncate(MAX_SLOW_QUERIES);
+        if slow.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let stored_indexes = self
+            .reader
+            .run_query_async(|conn| {
+                diesel::sql_query(
+                    "SELECT tablename::TEXT, indexdef FROM pg_indexes \
+                     WHERE schemaname = current_schema()",
+                )
+                .load::<StoredIndex>(conn)
+            })
+            .await?;
+        let mut indexes: HashMap<String, Vec<Vec<String>>> = HashMap::new();
+        for index in stored_indexes {
+            if let Some(columns) = indexed_columns(&index.indexdef) {
+                indexes.entry(index.tablename).or_default().push(columns);
+            }
+        }
+
+        Ok(slow
+            .into_iter()
+            .map(|statement| {
+                let suggested_index = QueryShape::parse(&statement.query).and_then(|shape| {
+                    let existing = indexes.get(&shape.table).map_or(&[][..], Vec::as_slice);
+                    shape.suggested_index(existing)
+                });
+                SlowQuery {
+                    query: statement.query.clone(),
+                    calls: statement.calls.last as i64,
+                    mean_exec_time_ms: statement.mean_exec_time,
+                    methods: self.correlated_methods(&statement.calls),
+                    suggested_index,
+                }
+            })
+            .collect())
+    }
+
+    /// The methods whose requests follow `calls` most closely.
+    fn correlated_methods(&self, calls: &Counts) -> Vec<CorrelatedMethod> {
+        let mut methods: Vec<_> = self
+            .methods
+            .iter()
+            .filter_map(|(method, requests)| {
+                let correlation = correlation(&calls.deltas, &requests.deltas)?;
+                (correlation >= MIN_CORRELATION).then(|| CorrelatedMethod {
+                    method: method.clone(),
+                    correlation,
+                })
+            })
+            .collect();
+        methods.sort_by(|a, b| b.correlation.total_cmp(&a.correlation));
+        methods.truncate(MAX_METHODS);
+        methods
+    }
+
+    async fn publish(&mut self, report: Vec<SlowQuery>) {
+        for slow in &report {
+            let Some(index) = &slow.suggested_index else {
+                continue;
+            };
+            if !self.suggested.insert(index.clone()) {
+                continue;
+            }
+            let methods = slow
+                .methods
+                .iter()
+                .map(|m| m.method.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            info!(
+                "Index advisor suggests `{index}` for a query taking {:.1}ms on average over {} \
+                 calls, run for methods [{methods}]: {}",
+                slow.mean_exec_time_ms, slow.calls, slow.query
+            );
+        }
+
+        let Some(path) = &self.output else {
+            return;
+        };
+        let json = serde_json::to_vec_pretty(&report).expect("Reports can be serialized");
+        if let Err(e) = tokio::fs::write(path, json).await {
+            warn!(
+                "Failed to write index advisor report to {}: {e}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// The columns a single table query filters and sorts on.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct QueryShape {
+    table: String,
+    /// Columns compared with `=` or `IN`.
+    equality: Vec<String>,
+    /// Columns compared with `<`, `>`, `<=`, `>=` or `BETWEEN`.
+    range: Vec<String>,
+    order: Vec<String>,
+}
+
+impl QueryShape {
+    /// The shape of `query`, if it is a `SELECT` from a single table, without joins or
+    /// subqueries.
+    fn parse(query: &str) -> Option<Self> {
+        let tokens = tokenize(query);
+        let count = |word: &str| tokens.iter().filter(|t| *t == word).count();
+        if tokens.first()? != "select"
+            || count("select") != 1
+            || count("from") != 1
+            || count("join") != 0
+            || count("union") != 0
+        {
+            return None;
+        }
+
+        let from = tokens.iter().position(|t| t == "from")?;
+        let table = tokens.get(from + 1)?;
+        let table = table.rsplit('.').next()?.to_string();
+        let mut shape = QueryShape {
+            table,
+            ..Default::default()
+        };
+
+        let clause_end = |start: usize, ends: &[&str]| {
+            tokens[start..]
+                .iter()
+                .position(|t| ends.contains(&t.as_str()))
+                .map_or(tokens.len(), |p| start + p)
+        };
+
+        if let Some(start) = tokens.iter().position(|t| t == "where") {
+            let end = clause_end(start, &["group", "order", "limit", "offset", "for"]);
+            let predicate = &tokens[start + 1..end];
+            for (i, token) in predicate.iter().enumerate() {
+                let Some(column) = column_name(token) else {
+                    continue;
+                };
+                let operand = predicate.get(i + 2).map(String::as_str);
+                match predicate.get(i + 1).map(String::as_str) {
+                    // Comparisons with other columns can't be served by an index on this one.
+                    Some("=") if operand.and_then(column_name).is_none() => {
+                        push_unique(&mut shape.equality, column)
+                    }
+                    Some("in") => push_unique(&mut shape.equality, column),
+                    Some("<" | ">" | "<=" | ">=" | "between") => {
+                        push_unique(&mut shape.range, column)
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(start) = tokens
+            .windows(2)
+            .position(|w| w[0] == "order" && w[1] == "by")
+        {
+            let end = clause_end(start, &["limit", "offset", "for"]);
+            for token in &tokens[start + 2..end] {
+                if let Some(column) = column_name(token) {
+                    push_unique(&mut shape.order, column);
+                }
+            }
+        }
+
+        Some(shape)
+    }
+
+    /// The columns of an index for this shape: its equality columns, then its first range
+    /// column, or its sort order if it has no range.
+    fn index_columns(&self) -> Vec<String> {
+        let mut columns = vec![];
+        for column in &self.equality {
+            push_unique(&mut columns, column);
+        }
+        let rest = match self.range.first() {
+            Some(range) => std::slice::from_ref(range),
+            None => self.order.as_slice(),
+        };
+        for column in rest {
+            push_unique(&mut columns, column);
+        }
+        columns
+    }
+
+    /// The index to create for this shape, unless one of the `existing` indexes on its table
+    /// (given by their columns) already leads with its columns.
+    fn suggested_index(&self, existing: &[Vec<String>]) -> Option<String> {
+        let columns = self.index_columns();
+        if columns.is_empty() || existing.iter().any(|index| index.starts_with(&columns)) {
+            return None;
+        }
+        Some(format!(
+            "CREATE INDEX CONCURRENTLY ON {} ({})",
+            self.table,
+            columns.join(", ")
+        ))
+    }
+}
+
+fn push_unique(columns: &mut Vec<String>, column: &str) {
+    if !columns.iter().any(|c| c == column) {
+        columns.push(column.to_string());
+    }
+}
+
+/// The name of the column `token` refers to, if it refers to one.
+fn column_name(token: &str) -> Option<&str> {
+    let first = token.chars().next()?;
+    if !(first.is_ascii_alphabetic() || first == '_') || KEYWORDS.contains(&token) {
+        return None;
+    }
+    token.rsplit('.').next()
+}
+
+/// The words and operators in `query`, lower cased, with identifiers unquoted. String literals
+/// are replaced by `'`.
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut word = String::new();
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || matches!(c, '_' | '.' | '$') {
+            word.extend(c.to_lowercase());
+            continue;
+        }
+        if c == '"' {
+            continue;
+        }
+        if !word.is_empty() {
+            tokens.push(std::mem::take(&mut word));
+        }
+        match c {
+            '\'' => {
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                }
+                tokens.push("'".to_string());
+            }
+            '<' | '>' | '!' if chars.peek() == Some(&'=') => {
+                chars.next();
+                tokens.push(format!("{c}="));
+            }
+            c if c.is_whitespace() => {}
+            c => tokens.push(c.to_string()),
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+/// The columns of the index defined by `indexdef` (as in `pg_indexes`), in order. Expressions
+/// are kept as they are, so they never match a column.
+fn indexed_columns(indexdef: &str) -> Option<Vec<String>> {
+    let start = indexdef.find(" USING ")?;
+    let open = start + indexdef[start..].find('(')?;
+    let mut depth = 0;
+    let mut columns = vec![];
+    let mut column = String::new();
+    for c in indexdef[open + 1..].chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => break,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                columns.push(std::mem::take(&mut column));
+                continue;
+            }
+            _ => {}
+        }
+        column.push(c);
+    }
+    columns.push(column);
+
+    // Drop sort orders and operator classes.
+    Some(
+        columns
+            .iter()
+            .filter_map(|c| c.split_whitespace().next())
+            .map(|c| c.trim_matches('"').to_string())
+            .collect(),
+    )
+}
+
+/// The Pearson correlation of the most recent values of `xs` and `ys`, if there are enough of
+/// them, and both vary.
+fn correlation(xs: &VecDeque<f64>, ys: &VecDeque<f64>) -> Option<f64> {
+    let n = xs.len().min(ys.len());
+    if n < MIN_SAMPLES {
+        return None;
+    }
+    let xs: Vec<_> = xs.iter().skip(xs.len() - n).collect();
+    let ys: Vec<_> = ys.iter().skip(ys.len() - n).collect();
+    let mean = |vs: &[&f64]| vs.iter().copied().sum::<f64>() / n as f64;
+    let (mx, my) = (mean(&xs), mean(&ys));
+
+    let (mut covariance, mut vx, mut vy) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(&ys) {
+        let (dx, dy) = (*x - mx, *y - my);
+        covariance += dx * dy;
+        vx += dx * dx;
+        vy += dy * dy;
+    }
+    if vx == 0.0 || vy == 0.0 {
+        return None;
+    }
+    Some(covariance / (vx * vy).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_shape() {
+        let query = r#"SELECT "objects"."object_id", "objects"."object_version" FROM "objects"
+            WHERE (("objects"."owner_type" = $1) AND ("objects"."owner_id" = $2))
+            AND ("objects"."object_id" > $3) AND "objects"."coin_type" IN ($4, $5)
+            ORDER BY "objects"."object_id" ASC LIMIT $6"#;
+        let shape = QueryShape::parse(query).unwrap();
+        assert_eq!(
+            shape,
+            QueryShape {
+                table: "objects".to_string(),
+                equality: vec!["owner_type", "owner_id", "coin_type"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                range: vec!["object_id".to_string()],
+                order: vec!["object_id".to_string()],
+            }
+        );
+        assert_eq!(
+            shape.suggested_index(&[]).unwrap(),
+            "CREATE INDEX CONCURRENTLY ON objects (owner_type, owner_id, coin_type, object_id)"
+        );
+
+        // Joins and subqueries are not analysed.
+        assert_eq!(
+            QueryShape::parse("SELECT a.x FROM a JOIN b ON a.id = b.id WHERE a.y = $1"),
+            None
+        );
+        assert_eq!(
+            QueryShape::parse("SELECT x FROM a WHERE y IN (SELECT y FROM b)"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sort_order_without_range() {
+        let shape = QueryShape::parse(
+            "select * from public.events where package = $1 and module = 'm' \
+             order by tx_sequence_number desc, event_sequence_number desc limit $2",
+        )
+        .unwrap();
+        assert_eq!(shape.table, "events");
+        assert_eq!(
+            shape.index_columns(),
+            vec![
+                "package",
+                "module",
+                "tx_sequence_number",
+                "event_sequence_number"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_existing_indexes() {
+        let shape =
+            QueryShape::parse("SELECT * FROM objects WHERE owner_type = $1 AND owner_id = $2")
+                .unwrap();
+
+        let covering = indexed_columns(
+            "CREATE INDEX objects_owner ON public.objects USING btree (owner_type, owner_id) \
+             WHERE (owner_type BETWEEN 1 AND 2)",
+        )
+        .unwrap();
+        assert_eq!(covering, vec!["owner_type", "owner_id"]);
+        assert_eq!(shape.suggested_index(&[covering]), None);
+
+        let other = indexed_columns(
+            "CREATE INDEX objects_owner_id ON public.objects USING btree (owner_id DESC, lower(object_type))",
+        )
+        .unwrap();
+        assert_eq!(other, vec!["owner_id", "lower(object_type)"]);
+        assert!(shape.suggested_index(&[other]).is_some());
+    }
+
+    #[test]
+    fn test_correlation() {
+        let calls: VecDeque<f64> = [1.0, 5.0, 2.0, 8.0, 3.0, 9.0].into();
+        let requests: VecDeque<f64> = [0.0, 0.0, 2.0, 10.0, 4.0, 16.0, 6.0, 18.0].into();
+        let unrelated: VecDeque<f64> = [7.0, 1.0, 6.0, 2.0, 7.0, 1.0].into();
+        let steady: VecDeque<f64> = [3.0; 6].into();
+
+        assert!(correlation(&calls, &requests).unwrap() > 0.99);
+        assert!(correlation(&calls, &unrelated).unwrap() < 0.0);
+        assert_eq!(correlation(&calls, &steady), None);
+        assert_eq!(
+            correlation(&calls.range(..3).copied().collect(), &requests),
+            None
+        );
+    }
+}
@@ -2,25 +2,57 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::env;
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::Result;
 use prometheus::Registry;
-use tracing::info;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 
 use mysten_metrics::spawn_monitored_task;
+use sui_core::storage::SecondaryRocksDbStore;
+use sui_json_rpc::staleness::Staleness;
+use telemetry_subscribers::TracingHandle;
 
 use crate::build_json_rpc_server;
+use crate::coin_metadata::CoinMetadataCache;
+use crate::db::{new_pg_connection_pool, report_connection_pool, PgConnectionPoolConfig};
+use crate::dynamic_config::{start_config_admin_server, DynamicConfigReloader, ServerControls};
+use crate::epoch_pools::EpochPoolController;
+use crate::equivocation::EquivocationStore;
 use crate::errors::IndexerError;
-use crate::framework::fetcher::CheckpointFetcher;
+use crate::framework::archive::CheckpointArchive;
+use crate::framework::fetcher::{CheckpointFetcher, CheckpointSource};
+use crate::framework::memory::MemoryBudget;
+use crate::grpc::start_grpc_server;
 use crate::handlers::checkpoint_handler::new_handlers;
+use crate::handlers::scope::IndexingScope;
+use crate::historical::KeyValueHistoricalStore;
+use crate::index_advisor::IndexAdvisor;
 use crate::indexer_reader::IndexerReader;
+use crate::labels::{start_labels_admin_server, AddressLabelStore};
 use crate::metrics::IndexerMetrics;
+use crate::prices::{PriceOracle, PriceOracleConfig};
 use crate::processors::objects_snapshot_processor::{ObjectsSnapshotProcessor, SnapshotLagConfig};
 use crate::processors::processor_orchestrator::ProcessorOrchestrator;
+use crate::pruner::{Pruner, RetentionConfig};
+use crate::replicas::ReadReplicas;
+use crate::rest::start_rest_server;
 use crate::store::{IndexerStore, PgIndexerAnalyticalStore};
 use crate::IndexerConfig;
 
 const DOWNLOAD_QUEUE_SIZE: usize = 1000;
+const LABELS_POOL_SIZE: u32 = 2;
+const EQUIVOCATIONS_POOL_SIZE: u32 = 2;
+const PRUNER_POOL_SIZE: u32 = 2;
+const WATCHDOG_POOL_SIZE: u32 = 1;
+/// How often the reader measures how far its data is behind the chain.
+const DATA_LAG_INTERVAL: Duration = Duration::from_secs(1);
+/// How often the reader checks for package upgrades that invalidate the coin metadata it caches.
+const COIN_METADATA_INVALIDATION_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct Indexer;
 
@@ -29,16 +61,21 @@ impl Indexer {
         config: &IndexerConfig,
         store: S,
         metrics: IndexerMetrics,
+        cancel: CancellationToken,
     ) -> Result<(), IndexerError> {
         let snapshot_config = SnapshotLagConfig::default();
-        Indexer::start_writer_with_config(config, store, metrics, snapshot_config).await
+        Indexer::start_writer_with_config(config, store, metrics, snapshot_config, cancel).await
     }
 
+    /// Runs until `cancel` is cancelled (or, with `--stop-at-epoch-end`, until the end of the
+    /// epoch is ingested), at which point the writer stops ingesting new checkpoints, and returns once the checkpoints it already indexed have been committed, so
+    /// that it resumes from where it left off when restarted.
     pub async fn start_writer_with_config<S: IndexerStore + Sync + Send + Clone + 'static>(
         config: &IndexerConfig,
         store: S,
         metrics: IndexerMetrics,
         snapshot_config: SnapshotLagConfig,
+        cancel: CancellationToken,
     ) -> Result<(), IndexerError> {
         info!(
             "Sui Indexer Writer (version {:?}) started...",
@@ -63,14 +100,62 @@ impl Indexer {
                     .with_label_values(&["checkpoint_tx_downloading"]),
             );
 
-        let rest_api_url = format!("{}/rest", config.rpc_client_url);
-        let rest_client = sui_rest_api::Client::new(&rest_api_url);
-        let fetcher = CheckpointFetcher::new(
-            rest_client.clone(),
+        let source = match (&config.fullnode_db_path, &config.fullnode_db_secondary_path) {
+            (Some(db_path), Some(secondary_path)) => {
+                info!(
+                    "Ingesting checkpoints from the fullnode DB at {}",
+                    db_path.display()
+                );
+                CheckpointSource::Local(Arc::new(SecondaryRocksDbStore::open(
+                    db_path,
+                    secondary_path,
+                )))
+            }
+            _ => {
+                let rest_api_url = format!("{}/rest", config.rpc_client_url);
+                CheckpointSource::Rest(sui_rest_api::Client::new(rest_api_url))
+            }
+        };
+        let scope =
+            (!config.scope_packages.is_empty() || !config.scope_addresses.is_empty()).then(|| {
+                info!(
+                    packages = ?config.scope_packages,
+                    addresses = ?config.scope_addresses,
+                    "Only indexing the data of the scope"
+                );
+                IndexingScope::new(
+                    config.scope_packages.iter().copied(),
+                    config.scope_addresses.iter().copied(),
+                )
+            });
+        let (mut checkpoint_handler, commit_task) =
+            new_handlers(store.clone(), metrics.clone(), scope).await?;
+
+        let mut fetcher = CheckpointFetcher::new(
+            source,
             last_seq_from_db,
             downloaded_checkpoint_data_sender,
             metrics.clone(),
         );
+        fetcher.set_stop_at_epoch_end(config.stop_at_epoch_end);
+        fetcher.set_download_concurrency(config.checkpoint_download_concurrency);
+        if let Some(max_lag) = config.max_uncommitted_checkpoints {
+            fetcher.set_commit_backpressure(max_lag, checkpoint_handler.committed_checkpoints());
+        }
+        if let Some(limit) = config.memory_budget_bytes {
+            let budget = MemoryBudget::new(limit, metrics.clone());
+            fetcher.set_memory_budget(budget.clone());
+            checkpoint_handler.set_memory_budget(budget.clone());
+            spawn_monitored_task!(
+                budget.release_committed(checkpoint_handler.committed_checkpoints())
+            );
+        }
+        if let Some(dir) = &config.checkpoint_archive_dir {
+            info!("Archiving checkpoints to {}", dir.display());
+            let archive = CheckpointArchive::new(dir.clone())
+                .map_err(|e| IndexerError::GenericError(e.to_string()))?;
+            fetcher.set_archive(Some(archive));
+        }
         spawn_monitored_task!(fetcher.run());
 
         let objects_snapshot_processor = ObjectsSnapshotProcessor::new_with_config(
@@ -80,39 +165,214 @@ impl Indexer {
         );
         spawn_monitored_task!(objects_snapshot_processor.start());
 
-        let checkpoint_handler = new_handlers(store, metrics).await?;
-        crate::framework::runner::run(
+        if let Some(path) = &config.retention_config {
+            let retention = RetentionConfig::read(path)?;
+            let db_url = config
+                .get_db_url()
+                .map_err(|e| IndexerError::PgPoolConnectionError(e.to_string()))?;
+            let blocking_cp = new_pg_connection_pool(&db_url, Some(PRUNER_POOL_SIZE))?;
+            let pruner = Pruner::new(blocking_cp, retention, metrics.clone());
+            spawn_monitored_task!(pruner.run(cancel.clone()));
+        }
+
+        let ingested = crate::framework::runner::run(
             mysten_metrics::metered_channel::ReceiverStream::new(
                 downloaded_checkpoint_data_receiver,
             ),
             vec![Box::new(checkpoint_handler)],
+            cancel,
         )
         .await;
 
-        Ok(())
+        info!("Waiting for indexed checkpoints to be committed...");
+        commit_task
+            .await
+            .map_err(|e| IndexerError::GenericError(format!("Commit task failed: {e}")))?;
+        let watermark = store.get_latest_tx_checkpoint_sequence_number().await?;
+        info!(?watermark, "Sui Indexer Writer stopped");
+
+        ingested.map_err(|e| match e.downcast::<IndexerError>() {
+            Ok(e) => e,
+            Err(e) => IndexerError::UncategorizedError(e),
+        })
     }
 
+    /// Serves RPC requests until `cancel` is cancelled, at which point the reader stops accepting
+    /// connections, and gives in-flight requests up to the configured grace period to complete.
     pub async fn start_reader(
         config: &IndexerConfig,
         registry: &Registry,
+        metrics: IndexerMetrics,
         db_url: String,
+        tracing_handle: Option<Arc<TracingHandle>>,
+        epoch_pools: EpochPoolController,
+        cancel: CancellationToken,
     ) -> Result<(), IndexerError> {
         info!(
             "Sui Indexer Reader (version {:?}) started...",
             env!("CARGO_PKG_VERSION")
         );
-        let indexer_reader = IndexerReader::new(db_url)?;
-        let handle = build_json_rpc_server(registry, indexer_reader, config, None)
-            .await
-            .expect("Json rpc server should not run into errors upon start.");
-        tokio::spawn(async move { handle.stopped().await })
-            .await
-            .expect("Rpc server task failed");
+        let mut pool_config = PgConnectionPoolConfig::default();
+        if let Some(pool_size) = config.reader_pool_size {
+            pool_config.set_pool_size(pool_size);
+        }
+        let mut indexer_reader = IndexerReader::new_with_config(db_url.clone(), pool_config)?;
+        if !config.reader_replica_urls.is_empty() {
+            let replicas = ReadReplicas::new(&config.reader_replica_urls, pool_config)?;
+            spawn_monitored_task!(replicas
+                .clone()
+                .track_apply_lag(indexer_reader.pool().clone(), metrics.clone()));
+            indexer_reader = indexer_reader.with_replicas(replicas);
+        }
+        if let Some(url) = &config.historical_kv_url {
+            let historical = KeyValueHistoricalStore::new_http(url, registry)?;
+            indexer_reader = indexer_reader.with_historical_store(Arc::new(historical));
+        }
+        let controls = ServerControls {
+            #[cfg(feature = "fault_injection")]
+            fault_injection: crate::fault_injection::FaultInjection::from_env()?,
+            ..Default::default()
+        };
+        #[cfg(feature = "fault_injection")]
+        {
+            indexer_reader = indexer_reader.with_fault_injection(controls.fault_injection.clone());
+        }
+        if config.dynamic_config.is_some() {
+            let blocking_cp = new_pg_connection_pool(&db_url, Some(WATCHDOG_POOL_SIZE))?;
+            spawn_monitored_task!(controls
+                .query_watchdog
+                .clone()
+                .run(blocking_cp, metrics.clone()));
+            indexer_reader = indexer_reader.with_query_watchdog(controls.query_watchdog.clone());
+        }
+        if let Some(capacity) = NonZeroUsize::new(config.coin_metadata_cache_size) {
+            let cache = CoinMetadataCache::new(capacity, metrics.clone());
+            spawn_monitored_task!(cache
+                .clone()
+                .invalidate_upgraded(indexer_reader.clone(), COIN_METADATA_INVALIDATION_INTERVAL));
+            indexer_reader = indexer_reader.with_coin_metadata_cache(cache);
+        }
+        spawn_monitored_task!(report_connection_pool(
+            "reader",
+            indexer_reader.pool().clone(),
+            metrics
+        ));
+        if let Some(size) = config.epoch_reader_pool_size {
+            epoch_pools.manage("reader", indexer_reader.pool().clone(), size);
+        }
+
+        // Kept alive until the server stops.
+        let runtime = config
+            .reader_worker_threads
+            .map(|threads| {
+                tokio::runtime::Builder::new_multi_thread()
+                    .worker_threads(threads)
+                    .thread_name("rpc-reader")
+                    .enable_all()
+                    .build()
+            })
+            .transpose()
+            .map_err(|e| {
+                IndexerError::GenericError(format!("Failed to start the reader's runtime: {e}"))
+            })?;
+
+        if let Some(port) = config.rest_server_port {
+            let addr = SocketAddr::new(config.rpc_server_url.parse().unwrap(), port);
+            let server = start_rest_server(indexer_reader.clone(), addr).await?;
+            spawn_monitored_task!(async move {
+                if let Ok(Err(e)) = server.await {
+                    error!("{e}");
+                }
+            });
+        }
+        if let Some(port) = config.grpc_server_port {
+            let addr = SocketAddr::new(config.rpc_server_url.parse().unwrap(), port);
+            start_grpc_server(indexer_reader.clone(), addr).await?;
+        }
+
+        if config.address_labels_csv.is_some() || config.labels_admin_port.is_some() {
+            let blocking_cp = new_pg_connection_pool(&db_url, Some(LABELS_POOL_SIZE))?;
+            let label_store = AddressLabelStore::new(blocking_cp);
+
+            if let Some(path) = &config.address_labels_csv {
+                let imported = label_store.import_csv(path)?;
+                info!("Imported {imported} address labels from {}", path.display());
+            }
+
+            if let Some(port) = config.labels_admin_port {
+                start_labels_admin_server(label_store, port).await?;
+            }
+        }
 
+        spawn_monitored_task!(track_data_lag(
+            indexer_reader.clone(),
+            controls.staleness.clone()
+        ));
+        if let Some(secs) = config.index_advisor_interval_secs {
+            let advisor = IndexAdvisor::new(
+                indexer_reader.clone(),
+                registry.clone(),
+                config.index_advisor_slow_query_ms,
+                config.index_advisor_output.clone(),
+            );
+            spawn_monitored_task!(advisor.run(Duration::from_secs(secs)));
+        }
+        if let Some(path) = &config.dynamic_config {
+            let reloader =
+                DynamicConfigReloader::new(path.clone(), tracing_handle, controls.clone());
+            reloader.reload()?;
+
+            #[cfg(unix)]
+            reloader.reload_on_sighup()?;
+
+            if let Some(port) = config.config_admin_port {
+                start_config_admin_server(reloader, port).await?;
+            }
+        }
+
+        let equivocations = if config.record_equivocations {
+            let blocking_cp = new_pg_connection_pool(&db_url, Some(EQUIVOCATIONS_POOL_SIZE))?;
+            Some(EquivocationStore::new(blocking_cp))
+        } else {
+            None
+        };
+
+        let prices = match &config.price_oracle_config {
+            Some(path) => Some(PriceOracle::from_config(
+                indexer_reader.clone(),
+                PriceOracleConfig::read(path)?,
+            )),
+            None => None,
+        };
+
+        let handle = build_json_rpc_server(
+            registry,
+            indexer_reader,
+            config,
+            controls,
+            equivocations,
+            prices,
+            runtime.as_ref().map(|runtime| runtime.handle().clone()),
+        )
+        .await
+        .expect("Json rpc server should not run into errors upon start.");
+        let grace_period = Duration::from_secs(config.shutdown_grace_period_secs);
+        tokio::spawn(async move {
+            let shutdown = async move { cancel.cancelled().await };
+            handle.shutdown_on(shutdown, grace_period).await
+        })
+        .await
+        .expect("Rpc server task failed");
+        if let Some(runtime) = runtime {
+            runtime.shutdown_background();
+        }
+
+        info!("Sui Indexer Reader stopped");
         Ok(())
     }
 
     pub async fn start_analytical_worker(
+        config: &IndexerConfig,
         store: PgIndexerAnalyticalStore,
         metrics: IndexerMetrics,
     ) -> Result<(), IndexerError> {
@@ -120,8 +380,32 @@ impl Indexer {
             "Sui Indexer Analytical Worker (version {:?}) started...",
             env!("CARGO_PKG_VERSION")
         );
-        let mut processor_orchestrator = ProcessorOrchestrator::new(store, metrics);
+        let mut processor_orchestrator =
+            ProcessorOrchestrator::new(store, metrics, config.address_stats_retention_days);
         processor_orchestrator.run_forever().await;
         Ok(())
     }
 }
+
+/// Keep recording in `staleness` how far the latest checkpoint in the database is behind the
+/// wall clock, which grows while ingestion is paused or lagging.
+async fn track_data_lag(reader: IndexerReader, staleness: Staleness) {
+    let mut interval = tokio::time::interval(DATA_LAG_INTERVAL);
+    loop {
+        interval.tick().await;
+        match reader
+            .spawn_blocking(|this| this.get_latest_checkpoint_from_db())
+            .await
+        {
+            Ok(checkpoint) => {
+                let now_ms = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as i64;
+                let lag_ms = now_ms.saturating_sub(checkpoint.timestamp_ms).max(0);
+                staleness.record_lag(Duration::from_millis(lag_ms as u64));
+            }
+            Err(e) => warn!("Failed to measure how far behind the indexed data is: {e}"),
+        }
+    }
+}
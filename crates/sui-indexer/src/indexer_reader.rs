@@ -2,33 +2,56 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    db::{PgConnectionConfig, PgConnectionPoolConfig, PgPoolConnection},
+    coin_metadata::CoinMetadataCache,
+    compression::decompress_all,
+    db::{PgConnectionConfig, PgConnectionPool, PgConnectionPoolConfig, PgPoolConnection},
     errors::IndexerError,
+    historical::HistoricalStore,
     models::{
+        account_statement::{
+            account_statement_entries, balances_reconcile, StoredStatementTransaction,
+        },
+        address_daily_stats::{QueriedAccountActivity, StoredActiveAddressCounts},
+        address_labels::StoredAddressLabel,
         address_metrics::StoredAddressMetrics,
-        checkpoints::StoredCheckpoint,
+        balance_changes::{balance_snapshots, QueriedBucketChange},
+        checkpoints::{search_by_timestamp, StoredCheckpoint},
         display::StoredDisplay,
         epoch::StoredEpochInfo,
+        equivocations::{Equivocation, StoredEquivocation},
+        event_schemas::StoredEventSchema,
         events::StoredEvent,
+        gas_price_stats::StoredGasPriceStats,
         move_call_metrics::QueriedMoveCallMetrics,
-        network_metrics::StoredNetworkMetrics,
-        objects::{CoinBalance, ObjectRefColumn, StoredObject},
+        network_metrics::{RecentNetworkActivity, StoredNetworkMetrics, RECENT_CHECKPOINTS},
+        nft_transfers::StoredNftTransfer,
+        object_transitions::{transition_to_i16, StoredObjectTransition},
+        objects::{CoinBalance, ObjectRefColumn, StoredHistoryObject, StoredObject},
+        package_call_metrics::{package_metrics, QueriedCallMetrics},
         packages::StoredPackage,
         transactions::StoredTransaction,
-        tx_indices::TxSequenceNumber,
+        tx_indices::{class_code, TxSequenceNumber},
+        validator_apys::{validator_apy_histories, StoredValidatorApy},
+        validator_history::StoredValidatorHistory,
     },
+    query_watchdog::QueryWatchdog,
+    replicas::ReadReplicas,
     schema::{
-        address_metrics, checkpoints, display, epochs, events, move_call_metrics, objects,
-        objects_snapshot, packages, transactions,
+        active_address_counts, address_labels, address_metrics, balance_changes, checkpoints,
+        display, epochs, equivocations, event_schemas, events, gas_price_stats, move_call_metrics,
+        nft_transfers, object_transitions, objects, objects_history, objects_snapshot, packages,
+        transactions, tx_changed_objects, tx_classes, tx_recipients, tx_senders, validator_apys,
+        validator_history,
     },
-    types::{IndexerResult, OwnerType},
+    store::IndexerReadStore,
+    types::{IndexedObjectChange, IndexerResult, ObjectStatus, OwnerType},
 };
 use anyhow::{anyhow, Result};
 use cached::proc_macro::cached;
 use cached::SizedCache;
 use diesel::{
-    dsl::sql, r2d2::ConnectionManager, sql_types::Bool, ExpressionMethods, OptionalExtension,
-    PgConnection, QueryDsl, RunQueryDsl, TextExpressionMethods,
+    dsl::sql, sql_types::Bool, BoolExpressionMethods, ExpressionMethods, JoinOnDsl,
+    NullableExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl,
 };
 use fastcrypto::encoding::Encoding;
 use fastcrypto::encoding::Hex;
@@ -36,18 +59,28 @@ use itertools::{any, Itertools};
 use move_core_types::annotated_value::MoveStructLayout;
 use move_core_types::language_storage::StructTag;
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::{Arc, RwLock},
 };
+use sui_json_rpc::read_your_writes::CheckpointWatermark;
+use sui_json_rpc::request_context::RequestContext;
 use sui_json_rpc_types::DisplayFieldsResponse;
 use sui_json_rpc_types::{
-    AddressMetrics, CheckpointId, EpochInfo, EventFilter, MoveCallMetrics, MoveFunctionName,
-    NetworkMetrics, SuiEvent, SuiObjectDataFilter, SuiTransactionBlockResponse, TransactionFilter,
+    AccountActivity, AccountRanking, AccountStatement, AccountStatementCursor, ActiveAddressCounts,
+    AddressLabels, AddressMetrics, BalanceSnapshot, CheckpointId, EpochInfo, EquivocatedObject,
+    EquivocationReport, EventFilter, EventSchema, EventSchemaCursor, GasPriceStats,
+    MoveCallMetrics, MoveFunctionName, NetworkMetrics, NftTransfer, ObjectChange, ObjectLifecycle,
+    ObjectTransaction, ObjectTransition, ObjectTransitionKind, PackageMetrics, SuiEvent,
+    SuiObjectDataFilter, SuiTransactionBlockResponse, TimestampDirection, TransactionClass,
+    TransactionFilter, TransactionInputObject, ValidatorApyHistory, ValidatorEpochHistory,
 };
 use sui_json_rpc_types::{
     Balance, Coin as SuiCoin, SuiCoinMetadata, SuiTransactionBlockEffects,
     SuiTransactionBlockEffectsAPI,
 };
+use sui_protocol_config::ProtocolConfig;
+use sui_types::digests::ChainIdentifier;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::{
     balance::Supply, coin::TreasuryCap, dynamic_field::DynamicFieldName, object::MoveObject,
 };
@@ -59,7 +92,10 @@ use sui_types::{
     is_system_package,
     move_package::MovePackage,
     object::{Object, ObjectRead},
-    sui_system_state::{sui_system_state_summary::SuiSystemStateSummary, SuiSystemStateTrait},
+    sui_system_state::{
+        sui_system_state_summary::{SuiSystemStateSummary, SuiValidatorSummary},
+        SuiSystemStateTrait,
+    },
 };
 use sui_types::{coin::CoinMetadata, event::EventID};
 
@@ -67,10 +103,22 @@ pub const TX_SEQUENCE_NUMBER_STR: &str = "tx_sequence_number";
 pub const TRANSACTION_DIGEST_STR: &str = "transaction_digest";
 pub const EVENT_SEQUENCE_NUMBER_STR: &str = "event_sequence_number";
 
+/// Reads the indexed data from Postgres, with Diesel. The API modules read through
+/// [`IndexerReadStore`], which this implements, so that other backends can serve them; the gRPC
+/// service still streams the Postgres rows directly.
 #[derive(Clone)]
 pub struct IndexerReader {
-    pool: crate::db::PgConnectionPool,
+    pool: PgConnectionPool,
     package_cache: PackageCache,
+    /// Where to read transactions and checkpoints that are not in Postgres from, if anywhere.
+    historical: Option<Arc<dyn HistoricalStore>>,
+    /// Replicas to serve reads pinned to a checkpoint from, if any.
+    replicas: Option<ReadReplicas>,
+    #[cfg(feature = "fault_injection")]
+    faults: crate::fault_injection::FaultInjection,
+    /// Where to cache the metadata of coins, if anywhere.
+    coin_metadata_cache: Option<CoinMetadataCache>,
+    watchdog: QueryWatchdog,
 }
 
 // Impl for common initialization and utilities
@@ -84,26 +132,61 @@ impl IndexerReader {
         db_url: T,
         config: PgConnectionPoolConfig,
     ) -> Result<Self> {
-        let manager = ConnectionManager::<PgConnection>::new(db_url);
-
         let connection_config = PgConnectionConfig {
             statement_timeout: config.statement_timeout,
             read_only: true,
         };
 
-        let pool = diesel::r2d2::Pool::builder()
-            .max_size(config.pool_size)
-            .connection_timeout(config.connection_timeout)
-            .connection_customizer(Box::new(connection_config))
-            .build(manager)
-            .map_err(|e| anyhow!("Failed to initialize connection pool. Error: {:?}. If Error is None, please check whether the configured pool size (currently {}) exceeds the maximum number of connections allowed by the database.", e, config.pool_size))?;
+        let pool = PgConnectionPool::new(
+            &db_url.into(),
+            config.pool_size,
+            config.connection_timeout,
+            connection_config,
+        )
+        .map_err(|e| anyhow!("Failed to initialize connection pool. Error: {:?}. If Error is None, please check whether the configured pool size (currently {}) exceeds the maximum number of connections allowed by the database.", e, config.pool_size))?;
 
         Ok(Self {
             pool,
             package_cache: Default::default(),
+            historical: None,
+            replicas: None,
+            #[cfg(feature = "fault_injection")]
+            faults: Default::default(),
+            coin_metadata_cache: None,
+            watchdog: Default::default(),
         })
     }
 
+    pub fn with_historical_store(mut self, historical: Arc<dyn HistoricalStore>) -> Self {
+        self.historical = Some(historical);
+        self
+    }
+
+    pub fn with_replicas(mut self, replicas: ReadReplicas) -> Self {
+        self.replicas = Some(replicas);
+        self
+    }
+
+    #[cfg(feature = "fault_injection")]
+    pub fn with_fault_injection(mut self, faults: crate::fault_injection::FaultInjection) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    pub fn with_coin_metadata_cache(mut self, cache: CoinMetadataCache) -> Self {
+        self.coin_metadata_cache = Some(cache);
+        self
+    }
+
+    pub fn with_query_watchdog(mut self, watchdog: QueryWatchdog) -> Self {
+        self.watchdog = watchdog;
+        self
+    }
+
+    pub(crate) fn pool(&self) -> &PgConnectionPool {
+        &self.pool
+    }
+
     fn get_connection(&self) -> Result<PgPoolConnection, IndexerError> {
         self.pool.get().map_err(|e| {
             IndexerError::PgPoolConnectionError(format!(
@@ -113,19 +196,29 @@ impl IndexerReader {
         })
     }
 
+    /// Inject the configured faults into a query about to run (see
+    /// [`crate::fault_injection`]). Nothing is injected without the `fault_injection` feature.
+    fn inject_faults(&self) -> Result<(), IndexerError> {
+        #[cfg(feature = "fault_injection")]
+        self.faults.inject()?;
+        Ok(())
+    }
+
     pub fn run_query<T, E, F>(&self, query: F) -> Result<T, IndexerError>
     where
         F: FnOnce(&mut PgConnection) -> Result<T, E>,
         E: From<diesel::result::Error> + std::error::Error,
     {
         blocking_call_is_ok_or_panic();
+        self.inject_faults()?;
 
         let mut connection = self.get_connection()?;
-        connection
-            .build_transaction()
-            .read_only()
-            .run(query)
-            .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+        self.watch_query(&mut *connection, |conn| {
+            conn.build_transaction()
+                .read_only()
+                .run(query)
+                .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+        })
     }
 
     pub fn run_query_repeatable<T, E, F>(&self, query: F) -> Result<T, IndexerError>
@@ -134,14 +227,59 @@ impl IndexerReader {
         E: From<diesel::result::Error> + std::error::Error,
     {
         blocking_call_is_ok_or_panic();
+        self.inject_faults()?;
 
         let mut connection = self.get_connection()?;
-        connection
-            .build_transaction()
-            .read_only()
-            .repeatable_read()
-            .run(query)
-            .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+        self.watch_query(&mut *connection, |conn| {
+            conn.build_transaction()
+                .read_only()
+                .repeatable_read()
+                .run(query)
+                .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+        })
+    }
+
+    /// Like [`Self::run_query_repeatable`], for queries that only read data up to `checkpoint`,
+    /// which are served by a replica that has applied it, if there is one.
+    pub fn run_query_repeatable_at_checkpoint<T, E, F>(
+        &self,
+        checkpoint: i64,
+        query: F,
+    ) -> Result<T, IndexerError>
+    where
+        F: FnOnce(&mut PgConnection) -> Result<T, E>,
+        E: From<diesel::result::Error> + std::error::Error,
+    {
+        blocking_call_is_ok_or_panic();
+        self.inject_faults()?;
+
+        let replica = self
+            .replicas
+            .as_ref()
+            .and_then(|replicas| replicas.get_at_checkpoint(checkpoint));
+        let run = |conn: &mut PgConnection| {
+            conn.build_transaction()
+                .read_only()
+                .repeatable_read()
+                .run(query)
+                .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+        };
+        match replica {
+            // The watchdog can only cancel queries on the primary.
+            Some(mut connection) => run(&mut *connection),
+            None => self.watch_query(&mut *self.get_connection()?, run),
+        }
+    }
+
+    /// Run `query` on `conn`, cancelling it if it runs past the deadline of the method of the
+    /// request it is run for (see [`crate::query_watchdog`]).
+    fn watch_query<T>(
+        &self,
+        conn: &mut PgConnection,
+        query: impl FnOnce(&mut PgConnection) -> Result<T, IndexerError>,
+    ) -> Result<T, IndexerError> {
+        let request = CURRENT_REQUEST.with(|request| request.borrow().clone());
+        self.watchdog.watch(request, conn, query)
     }
 
     pub async fn spawn_blocking<F, R, E>(&self, f: F) -> Result<R, E>
@@ -152,11 +290,16 @@ impl IndexerReader {
     {
         let this = self.clone();
         let current_span = tracing::Span::current();
+        let request = RequestContext::current();
         tokio::task::spawn_blocking(move || {
             CALLED_FROM_BLOCKING_POOL
                 .with(|in_blocking_pool| *in_blocking_pool.borrow_mut() = true);
+            // Blocking threads are reused, so the request is reset for the next task.
+            CURRENT_REQUEST.with(|current| *current.borrow_mut() = request);
             let _guard = current_span.enter();
-            f(this)
+            let result = f(this);
+            CURRENT_REQUEST.with(|current| *current.borrow_mut() = None);
+            result
         })
         .await
         .expect("propagate any panics")
@@ -184,6 +327,9 @@ impl IndexerReader {
 
 thread_local! {
     static CALLED_FROM_BLOCKING_POOL: std::cell::RefCell<bool> = std::cell::RefCell::new(false);
+    /// The RPC request that the blocking task running on this thread serves, if any.
+    static CURRENT_REQUEST: std::cell::RefCell<Option<RequestContext>> =
+        std::cell::RefCell::new(None);
 }
 
 /// Check that we are in a context conducive to making blocking calls.
@@ -420,6 +566,20 @@ impl IndexerReader {
         Ok(system_state)
     }
 
+    /// The system state as of the end of `checkpoint`, which must be in the consistent read range
+    /// (see [`Self::multi_get_objects_at_checkpoint`]).
+    pub fn get_sui_system_state_at_checkpoint(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<SuiSystemStateSummary, IndexerError> {
+        let objects = ObjectsAtCheckpoint {
+            reader: self,
+            checkpoint,
+        };
+        Ok(sui_types::sui_system_state::get_sui_system_state(&objects)?
+            .into_sui_system_state_summary())
+    }
+
     /// Retrieve the system state data for the given epoch. If no epoch is given,
     /// it will retrieve the latest epoch's data and return the system state.
     /// System state of the an epoch is written at the end of the epoch, so system state
@@ -486,13 +646,46 @@ impl IndexerReader {
         Ok(Some(checkpoint))
     }
 
+    pub async fn get_checkpoint_in_blocking_task(
+        &self,
+        checkpoint_id: CheckpointId,
+    ) -> Result<Option<sui_json_rpc_types::Checkpoint>, IndexerError> {
+        let checkpoint = self
+            .spawn_blocking(move |this| this.get_checkpoint(checkpoint_id))
+            .await?;
+        match (checkpoint, &self.historical) {
+            (None, Some(historical)) => historical.get_checkpoint(checkpoint_id).await,
+            (checkpoint, _) => Ok(checkpoint),
+        }
+    }
+
+    /// The protocol config of the latest epoch indexed, on the chain indexed.
+    pub async fn get_latest_protocol_config_in_blocking_task(
+        &self,
+    ) -> Result<ProtocolConfig, IndexerError> {
+        let genesis = self
+            .get_checkpoint_in_blocking_task(CheckpointId::SequenceNumber(0))
+            .await?
+            .ok_or_else(|| IndexerError::GenericError("Genesis checkpoint not found".into()))?;
+        let chain = ChainIdentifier::from(genesis.digest).chain();
+        let epoch = self
+            .spawn_blocking(|this| this.get_latest_epoch_info_from_db())
+            .await?;
+        let version = epoch.protocol_version as u64;
+        ProtocolConfig::get_for_version_if_supported(version.into(), chain).ok_or_else(|| {
+            IndexerError::GenericError(format!(
+                "Protocol version {version} is not supported by this indexer"
+            ))
+        })
+    }
+
     pub fn get_latest_checkpoint(&self) -> Result<sui_json_rpc_types::Checkpoint, IndexerError> {
         let stored_checkpoint = self.get_latest_checkpoint_from_db()?;
 
         sui_json_rpc_types::Checkpoint::try_from(stored_checkpoint)
     }
 
-    fn get_checkpoints_from_db(
+    pub(crate) fn get_checkpoints_from_db(
         &self,
         cursor: Option<u64>,
         limit: usize,
@@ -533,6 +726,46 @@ impl IndexerReader {
             .collect()
     }
 
+    /// The checkpoint closest to `timestamp_ms` in `direction`, found by binary searching the
+    /// checkpoints by sequence number, which doesn't need their timestamps to be indexed.
+    pub fn get_checkpoint_by_timestamp(
+        &self,
+        timestamp_ms: u64,
+        direction: TimestampDirection,
+    ) -> Result<Option<sui_json_rpc_types::Checkpoint>, IndexerError> {
+        let timestamp_ms = i64::try_from(timestamp_ms).unwrap_or(i64::MAX);
+        let stored_checkpoint = self.run_query(|conn| {
+            let (first, last) = checkpoints::table
+                .select((
+                    diesel::dsl::min(checkpoints::sequence_number),
+                    diesel::dsl::max(checkpoints::sequence_number),
+                ))
+                .first::<(Option<i64>, Option<i64>)>(conn)?;
+            let (Some(first), Some(last)) = (first, last) else {
+                return Ok(None);
+            };
+
+            let sequence_number =
+                search_by_timestamp(first, last, timestamp_ms, direction, |seq| {
+                    checkpoints::table
+                        .select(checkpoints::timestamp_ms)
+                        .filter(checkpoints::sequence_number.eq(seq))
+                        .first::<i64>(conn)
+                })?;
+            sequence_number
+                .map(|seq| {
+                    checkpoints::table
+                        .filter(checkpoints::sequence_number.eq(seq))
+                        .first::<StoredCheckpoint>(conn)
+                })
+                .transpose()
+        })?;
+
+        stored_checkpoint
+            .map(sui_json_rpc_types::Checkpoint::try_from)
+            .transpose()
+    }
+
     fn get_transaction_effects_with_digest(
         &self,
         digest: TransactionDigest,
@@ -559,7 +792,52 @@ impl IndexerReader {
         stored_txn.try_into_sui_transaction_effects()
     }
 
-    fn multi_get_transactions(
+    /// The sequence number of the latest transaction that upgraded a package, if any.
+    pub(crate) fn get_latest_package_upgrade(&self) -> Result<Option<i64>, IndexerError> {
+        let upgrade = class_code(TransactionClass::Upgrade);
+        self.run_query(|conn| {
+            tx_classes::table
+                .select(diesel::dsl::max(tx_classes::tx_sequence_number))
+                .filter(tx_classes::class.eq(upgrade))
+                .first::<Option<i64>>(conn)
+        })
+    }
+
+    /// Up to `limit` transactions after `after` that upgraded packages, in order, with the
+    /// original IDs of the packages each upgraded.
+    pub(crate) fn get_package_upgrades(
+        &self,
+        after: i64,
+        limit: i64,
+    ) -> Result<Vec<(i64, Vec<ObjectID>)>, IndexerError> {
+        let upgrade = class_code(TransactionClass::Upgrade);
+        let upgrades: Vec<i64> = self.run_query(|conn| {
+            tx_classes::table
+                .select(tx_classes::tx_sequence_number)
+                .filter(tx_classes::class.eq(upgrade))
+                .filter(tx_classes::tx_sequence_number.gt(after))
+                .order(tx_classes::tx_sequence_number.asc())
+                .limit(limit)
+                .load(conn)
+        })?;
+
+        upgrades
+            .into_iter()
+            .map(|tx_sequence_number| {
+                let effects =
+                    self.get_transaction_effects_with_sequence_number(tx_sequence_number)?;
+                let mut packages = vec![];
+                for created in effects.created() {
+                    if let Some(package) = self.get_package(&created.object_id())? {
+                        packages.push(package.original_package_id());
+                    }
+                }
+                Ok((tx_sequence_number, packages))
+            })
+            .collect()
+    }
+
+    pub(crate) fn multi_get_transactions(
         &self,
         digests: &[TransactionDigest],
     ) -> Result<Vec<StoredTransaction>, IndexerError> {
@@ -579,9 +857,24 @@ impl IndexerReader {
         stored_txes: Vec<StoredTransaction>,
         options: sui_json_rpc_types::SuiTransactionBlockResponseOptions,
     ) -> IndexerResult<Vec<SuiTransactionBlockResponse>> {
+        let mut labels = if options.show_labels {
+            let tx_sequence_numbers = stored_txes.iter().map(|tx| tx.tx_sequence_number);
+            self.get_transaction_labels(tx_sequence_numbers.collect())?
+        } else {
+            HashMap::new()
+        };
+
         stored_txes
             .into_iter()
-            .map(|stored_tx| stored_tx.try_into_sui_transaction_block_response(&options, self))
+            .map(|stored_tx| {
+                let tx_sequence_number = stored_tx.tx_sequence_number;
+                let mut response =
+                    stored_tx.try_into_sui_transaction_block_response(&options, self)?;
+                if options.show_labels {
+                    response.labels = Some(labels.remove(&tx_sequence_number).unwrap_or_default());
+                }
+                Ok(response)
+            })
             .collect::<IndexerResult<Vec<_>>>()
     }
 
@@ -624,6 +917,7 @@ impl IndexerReader {
         cursor: Option<ObjectID>,
         limit: usize,
     ) -> Result<Vec<StoredObject>, IndexerError> {
+        let filter_clause = filter.as_ref().map(object_filter_clause).transpose()?;
         self.run_query(|conn| {
             let mut query = objects::dsl::objects
                 .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
@@ -631,57 +925,66 @@ impl IndexerReader {
                 .order(objects::dsl::object_id.asc())
                 .limit(limit as i64)
                 .into_boxed();
-            if let Some(filter) = filter {
-                match filter {
-                    SuiObjectDataFilter::StructType (struct_tag ) => {
-                        let object_type = struct_tag.to_canonical_string(/* with_prefix */ true);
-                        query = query.filter(objects::dsl::object_type.like(format!("{}%",object_type)));
-                    },
-                    SuiObjectDataFilter::MatchAny(filters) => {
-                        let mut condition = "(".to_string();
-                        for (i, filter) in filters.iter().enumerate() {
-                            if let SuiObjectDataFilter::StructType (struct_tag) = filter {
-                                let object_type = struct_tag.to_canonical_string(/* with_prefix */ true);
-                                if i == 0 {
-                                    condition += format!("objects.object_type LIKE '{}%'",object_type).as_str();
-                                } else {
-                                    condition += format!(" OR objects.object_type LIKE '{}%'",object_type).as_str();
-                                }
-                            } else {
-                                return Err(IndexerError::InvalidArgumentError(
-                                    "Invalid filter type. Only struct, MatchAny and MatchNone of struct filters are supported.".into(),
-                                ));
-                            }
-                        }
-                        condition += ")";
-                        query = query.filter(sql::<Bool>(&condition));
-                    },
-                    SuiObjectDataFilter::MatchNone(filters) => {
-                        for filter in filters {
-                            if let SuiObjectDataFilter::StructType (struct_tag) = filter {
-                                let object_type = struct_tag.to_canonical_string(/* with_prefix */ true);
-                                query = query.filter(objects::dsl::object_type.not_like(format!("{}%", object_type)));
-                            } else {
-                                return Err(IndexerError::InvalidArgumentError(
-                                    "Invalid filter type. Only struct, MatchAny and MatchNone of struct filters are supported.".into(),
-                                ));
-                            }
-                        }
-                    }
-                    _ => {
-                        return Err(IndexerError::InvalidArgumentError(
-                            "Invalid filter type. Only struct, MatchAny and MatchNone of struct filters are supported.".into(),
-                        ));
-                    }
-                }
+            if let Some(filter_clause) = filter_clause {
+                query = query.filter(sql::<Bool>(&filter_clause));
             }
 
             if let Some(object_cursor) = cursor {
                 query = query.filter(objects::dsl::object_id.gt(object_cursor.to_vec()));
             }
 
-            query.load::<StoredObject>(conn).map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+            query
+                .load::<StoredObject>(conn)
+                .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+        })
+    }
+
+    /// The live objects owned by `address` as of the end of `checkpoint`, matching `filter`, in
+    /// order of their IDs, starting after `cursor`. Like [`Self::multi_get_objects_at_checkpoint`],
+    /// objects are read from `objects_snapshot` overlaid with `objects_history`, so `checkpoint`
+    /// must be in the consistent read range.
+    pub fn get_owned_objects_at_checkpoint(
+        &self,
+        address: SuiAddress,
+        filter: Option<SuiObjectDataFilter>,
+        cursor: Option<ObjectID>,
+        limit: usize,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<Vec<StoredHistoryObject>> {
+        let checkpoint = checkpoint as i64;
+        let filter_clause = filter.as_ref().map(object_filter_clause).transpose()?;
+
+        let (range, objects) = self.run_query_repeatable_at_checkpoint(checkpoint, |conn| {
+            let range = ConsistentReadRange::read(conn)?;
+            if !range.contains(checkpoint) {
+                return Ok::<_, diesel::result::Error>((range, None));
+            }
+            let query = owned_objects_at_checkpoint_query(
+                address,
+                filter_clause.as_deref(),
+                cursor,
+                limit,
+                range.after_snapshot(),
+                checkpoint,
+            );
+            let objects = diesel::sql_query(query).load::<StoredHistoryObject>(conn)?;
+            Ok((range, Some(objects)))
+        })?;
+        objects.ok_or_else(|| range.out_of_range(checkpoint))
+    }
+
+    pub async fn get_owned_objects_at_checkpoint_in_blocking_task(
+        &self,
+        address: SuiAddress,
+        filter: Option<SuiObjectDataFilter>,
+        cursor: Option<ObjectID>,
+        limit: usize,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<Vec<StoredHistoryObject>> {
+        self.spawn_blocking(move |this| {
+            this.get_owned_objects_at_checkpoint(address, filter, cursor, limit, checkpoint)
         })
+        .await
     }
 
     pub async fn query_events_in_blocking_task(
@@ -690,9 +993,10 @@ impl IndexerReader {
         cursor: Option<EventID>,
         limit: usize,
         descending_order: bool,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> IndexerResult<Vec<SuiEvent>> {
         self.spawn_blocking(move |this| {
-            this.query_events_impl(filter, cursor, limit, descending_order)
+            this.query_events_impl(filter, cursor, limit, descending_order, at_checkpoint)
         })
         .await
     }
@@ -744,6 +1048,59 @@ impl IndexerReader {
         })
     }
 
+    /// The objects in `object_ids`, in the same order, as of the end of checkpoint `checkpoint`,
+    /// all read from the same snapshot of the database. Objects are read from `objects_snapshot`,
+    /// overlaid with their versions in `objects_history` up to `checkpoint`, so `checkpoint` must
+    /// be in the consistent read range (see [`Self::get_consistent_read_range`]).
+    pub fn multi_get_objects_at_checkpoint(
+        &self,
+        object_ids: Vec<ObjectID>,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<Vec<ObjectRead>> {
+        let checkpoint = checkpoint as i64;
+        let ids = object_ids.iter().map(|id| id.to_vec()).collect_vec();
+
+        let (range, rows) = self.run_query_repeatable_at_checkpoint(checkpoint, |conn| {
+            let range = ConsistentReadRange::read(conn)?;
+            if !range.contains(checkpoint) {
+                return Ok::<_, diesel::result::Error>((range, None));
+            }
+
+            let snapshot = objects_snapshot::table
+                .filter(objects_snapshot::object_id.eq_any(ids.clone()))
+                .load::<StoredHistoryObject>(conn)?;
+            let history = objects_history::table
+                .filter(objects_history::object_id.eq_any(ids))
+                .filter(objects_history::checkpoint_sequence_number.gt(range.after_snapshot()))
+                .filter(objects_history::checkpoint_sequence_number.le(checkpoint))
+                .distinct_on(objects_history::object_id)
+                .order((
+                    objects_history::object_id,
+                    objects_history::object_version.desc(),
+                ))
+                .load::<StoredHistoryObject>(conn)?;
+            Ok((range, Some((snapshot, history))))
+        })?;
+
+        let Some((snapshot, history)) = rows else {
+            return Err(range.out_of_range(checkpoint));
+        };
+
+        // Versions from `objects_history` are later than the snapshot's.
+        let mut latest: HashMap<Vec<u8>, StoredHistoryObject> = HashMap::new();
+        for object in snapshot.into_iter().chain(history) {
+            latest.insert(object.object_id.clone(), object);
+        }
+
+        object_ids
+            .into_iter()
+            .map(|id| match latest.get(id.as_ref()) {
+                Some(object) => object.clone().try_into_object_read(self),
+                None => Ok(ObjectRead::NotExists(id)),
+            })
+            .collect()
+    }
+
     fn query_transaction_blocks_by_checkpoint_impl(
         &self,
         checkpoint_seq: u64,
@@ -783,9 +1140,17 @@ impl IndexerReader {
         cursor: Option<TransactionDigest>,
         limit: usize,
         is_descending: bool,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> IndexerResult<Vec<SuiTransactionBlockResponse>> {
         self.spawn_blocking(move |this| {
-            this.query_transaction_blocks_impl(filter, options, cursor, limit, is_descending)
+            this.query_transaction_blocks_impl(
+                filter,
+                options,
+                cursor,
+                limit,
+                is_descending,
+                at_checkpoint,
+            )
         })
         .await
     }
@@ -797,7 +1162,11 @@ impl IndexerReader {
         cursor: Option<TransactionDigest>,
         limit: usize,
         is_descending: bool,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> IndexerResult<Vec<SuiTransactionBlockResponse>> {
+        let last_tx_seq = at_checkpoint
+            .map(|checkpoint| self.get_checkpoint_last_tx_sequence_number(checkpoint))
+            .transpose()?;
         let cursor_tx_seq = if let Some(cursor) = cursor {
             Some(self.run_query(|conn| {
                 transactions::dsl::transactions
@@ -817,8 +1186,20 @@ impl IndexerReader {
         } else {
             "".to_string()
         };
+        // Transactions after `at_checkpoint` are left out, whatever the order.
+        let cursor_clause = match last_tx_seq {
+            Some(last_tx_seq) => {
+                format!("{cursor_clause} AND {TX_SEQUENCE_NUMBER_STR} <= {last_tx_seq}")
+            }
+            None => cursor_clause,
+        };
         let order_str = if is_descending { "DESC" } else { "ASC" };
         let (table_name, main_where_clause) = match filter {
+            Some(TransactionFilter::Checkpoint(seq))
+                if at_checkpoint.is_some_and(|checkpoint| seq > checkpoint) =>
+            {
+                return Ok(vec![]);
+            }
             // Processed above
             Some(TransactionFilter::Checkpoint(seq)) => {
                 return self.query_transaction_blocks_by_checkpoint_impl(
@@ -909,6 +1290,12 @@ impl IndexerReader {
                 } else {
                     "".to_string()
                 };
+                let cursor_clause = match last_tx_seq {
+                    Some(last_tx_seq) => format!(
+                        "{cursor_clause} AND tx_senders.{TX_SEQUENCE_NUMBER_STR} <= {last_tx_seq}"
+                    ),
+                    None => cursor_clause,
+                };
                 let inner_query = format!(
                     "(SELECT tx_senders.{TX_SEQUENCE_NUMBER_STR} \
                     FROM tx_senders \
@@ -957,6 +1344,10 @@ impl IndexerReader {
                 );
                 (inner_query, "1 = 1".into())
             }
+            Some(TransactionFilter::TransactionClass(class)) => (
+                "tx_classes".into(),
+                format!("class = {}", class_code(class)),
+            ),
             Some(
                 TransactionFilter::TransactionKind(_) | TransactionFilter::TransactionKindIn(_),
             ) => {
@@ -981,11 +1372,16 @@ impl IndexerReader {
 
         tracing::debug!("query transaction blocks: {}", query);
 
-        let tx_sequence_numbers = self
-            .run_query(|conn| diesel::sql_query(query.clone()).load::<TxSequenceNumber>(conn))?
-            .into_iter()
-            .map(|tsn| tsn.tx_sequence_number)
-            .collect::<Vec<_>>();
+        let load = |conn: &mut PgConnection| {
+            diesel::sql_query(query.clone()).load::<TxSequenceNumber>(conn)
+        };
+        let tx_sequence_numbers = match at_checkpoint {
+            Some(checkpoint) => self.run_query_repeatable_at_checkpoint(checkpoint as i64, load)?,
+            None => self.run_query(load)?,
+        }
+        .into_iter()
+        .map(|tsn| tsn.tx_sequence_number)
+        .collect::<Vec<_>>();
 
         self.multi_get_transaction_block_response_by_sequence_numbers(
             tx_sequence_numbers,
@@ -1020,24 +1416,57 @@ impl IndexerReader {
         digests: Vec<TransactionDigest>,
         options: sui_json_rpc_types::SuiTransactionBlockResponseOptions,
     ) -> Result<Vec<sui_json_rpc_types::SuiTransactionBlockResponse>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.multi_get_transaction_block_response_impl(&digests, options)
-        })
-        .await
+        let mut responses = {
+            let (digests, options) = (digests.clone(), options.clone());
+            self.spawn_blocking(move |this| {
+                this.multi_get_transaction_block_response_impl(&digests, options)
+            })
+            .await?
+        };
+
+        let Some(historical) = &self.historical else {
+            return Ok(responses);
+        };
+        let found: HashSet<_> = responses.iter().map(|response| response.digest).collect();
+        let missing: Vec<_> = digests
+            .iter()
+            .filter(|digest| !found.contains(digest))
+            .copied()
+            .collect();
+        if missing.is_empty() {
+            return Ok(responses);
+        }
+
+        let transactions = historical.multi_get_transactions(&missing).await?;
+        responses.extend(
+            self.spawn_blocking(move |this| {
+                transactions
+                    .into_iter()
+                    .flatten()
+                    .map(|tx| tx.try_into_sui_transaction_block_response(&options, &this))
+                    .collect::<IndexerResult<Vec<_>>>()
+            })
+            .await?,
+        );
+        Ok(responses)
     }
 
     fn get_transaction_events_impl(
         &self,
         digest: TransactionDigest,
     ) -> Result<Vec<sui_json_rpc_types::SuiEvent>, IndexerError> {
-        let (timestamp_ms, serialized_events) = self.run_query(|conn| {
+        let (timestamp_ms, serialized_events, compression) = self.run_query(|conn| {
             transactions::table
                 .filter(transactions::transaction_digest.eq(digest.into_inner().to_vec()))
-                .select((transactions::timestamp_ms, transactions::events))
-                .first::<(i64, Vec<Option<Vec<u8>>>)>(conn)
+                .select((
+                    transactions::timestamp_ms,
+                    transactions::events,
+                    transactions::compression,
+                ))
+                .first::<(i64, Vec<Option<Vec<u8>>>, i16)>(conn)
         })?;
 
-        let events = serialized_events
+        let events = decompress_all(compression, serialized_events)?
             .into_iter()
             .flatten()
             .map(|event| bcs::from_bytes::<sui_types::event::Event>(&event))
@@ -1105,7 +1534,11 @@ impl IndexerReader {
         cursor: Option<EventID>,
         limit: usize,
         descending_order: bool,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> IndexerResult<Vec<SuiEvent>> {
+        let last_tx_seq = at_checkpoint
+            .map(|checkpoint| self.get_checkpoint_last_tx_sequence_number(checkpoint))
+            .transpose()?;
         let (tx_seq, event_seq) = if let Some(cursor) = cursor {
             let EventID {
                 tx_digest,
@@ -1124,12 +1557,15 @@ impl IndexerReader {
                 event_seq,
             )
         } else if descending_order {
-            let max_tx_seq: i64 = self.run_query(|conn| {
-                events::dsl::events
-                    .select(events::tx_sequence_number)
-                    .order(events::dsl::tx_sequence_number.desc())
-                    .first::<i64>(conn)
-            })?;
+            let max_tx_seq: i64 = match last_tx_seq {
+                Some(last_tx_seq) => last_tx_seq,
+                None => self.run_query(|conn| {
+                    events::dsl::events
+                        .select(events::tx_sequence_number)
+                        .order(events::dsl::tx_sequence_number.desc())
+                        .first::<i64>(conn)
+                })?,
+            };
             (max_tx_seq + 1, 0)
         } else {
             (-1, 0)
@@ -1142,6 +1578,12 @@ impl IndexerReader {
             } else {
                 format!("(e.{TX_SEQUENCE_NUMBER_STR} > {} OR (e.{TX_SEQUENCE_NUMBER_STR} = {} AND e.{EVENT_SEQUENCE_NUMBER_STR} > {}))", tx_seq, tx_seq, event_seq)
             };
+            let cursor_clause = match last_tx_seq {
+                Some(last_tx_seq) => {
+                    format!("{cursor_clause} AND e.{TX_SEQUENCE_NUMBER_STR} <= {last_tx_seq}")
+                }
+                None => cursor_clause,
+            };
             let order_clause = if descending_order {
                 format!("e.{TX_SEQUENCE_NUMBER_STR} DESC, e.{EVENT_SEQUENCE_NUMBER_STR} DESC")
             } else {
@@ -1166,49 +1608,19 @@ impl IndexerReader {
         } else if let EventFilter::Transaction(tx_digest) = filter {
             self.query_events_by_tx_digest_query(tx_digest, cursor, limit, descending_order)?
         } else {
-            let main_where_clause = match filter {
-                EventFilter::Package(package_id) => {
-                    format!("package = '\\x{}'::bytea", package_id.to_hex())
-                }
-                EventFilter::MoveModule { package, module } => {
-                    format!(
-                        "package = '\\x{}'::bytea AND module = '{}'",
-                        package.to_hex(),
-                        module,
-                    )
-                }
-                EventFilter::MoveEventType(struct_tag) => {
-                    format!("event_type = '{}'", struct_tag)
-                }
-                EventFilter::MoveEventModule { package, module } => {
-                    let package_module_prefix = format!("{}::{}", package.to_hex_literal(), module);
-                    format!("event_type LIKE '{package_module_prefix}::%'")
-                }
-                EventFilter::Sender(_) => {
-                    // Processed above
-                    unreachable!()
-                }
-                EventFilter::Transaction(_) => {
-                    // Processed above
-                    unreachable!()
-                }
-                EventFilter::MoveEventField { .. }
-                | EventFilter::All(_)
-                | EventFilter::Any(_)
-                | EventFilter::And(_, _)
-                | EventFilter::Or(_, _)
-                | EventFilter::TimeRange { .. } => {
-                    return Err(IndexerError::NotSupportedError(
-                        "This type of EventFilter is not supported.".into(),
-                    ));
-                }
-            };
+            let main_where_clause = event_filter_clause(&filter)?;
 
             let cursor_clause = if descending_order {
                 format!("AND ({TX_SEQUENCE_NUMBER_STR} < {} OR ({TX_SEQUENCE_NUMBER_STR} = {} AND {EVENT_SEQUENCE_NUMBER_STR} < {}))", tx_seq, tx_seq, event_seq)
             } else {
                 format!("AND ({TX_SEQUENCE_NUMBER_STR} > {} OR ({TX_SEQUENCE_NUMBER_STR} = {} AND {EVENT_SEQUENCE_NUMBER_STR} > {}))", tx_seq, tx_seq, event_seq)
             };
+            let cursor_clause = match last_tx_seq {
+                Some(last_tx_seq) => {
+                    format!("{cursor_clause} AND {TX_SEQUENCE_NUMBER_STR} <= {last_tx_seq}")
+                }
+                None => cursor_clause,
+            };
             let order_clause = if descending_order {
                 format!("{TX_SEQUENCE_NUMBER_STR} DESC, {EVENT_SEQUENCE_NUMBER_STR} DESC")
             } else {
@@ -1226,6 +1638,167 @@ impl IndexerReader {
             )
         };
         tracing::debug!("query events: {}", query);
+        let load = |conn: &mut PgConnection| diesel::sql_query(query).load::<StoredEvent>(conn);
+        let stored_events = match at_checkpoint {
+            Some(checkpoint) => self.run_query_repeatable_at_checkpoint(checkpoint as i64, load)?,
+            None => self.run_query(load)?,
+        };
+        stored_events
+            .into_iter()
+            // The events of a transaction after `at_checkpoint`, queried by its digest.
+            .filter(|se| last_tx_seq.map_or(true, |last| se.tx_sequence_number <= last))
+            .map(|se| se.try_into_sui_event(self))
+            .collect()
+    }
+
+    /// Returns the events of type `event_type` (a canonical type string) emitted by package
+    /// `package_id`, after `cursor`, decoded with the layout recorded for the type when events
+    /// of it from that package were indexed. Returns `None` if no layout has been recorded.
+    pub fn get_events_by_package_version(
+        &self,
+        event_type: String,
+        package_id: ObjectID,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Option<Vec<SuiEvent>>> {
+        let package = package_id.to_vec();
+        let cursor = cursor
+            .map(
+                |EventID {
+                     tx_digest,
+                     event_seq,
+                 }| {
+                    let tx_seq = self
+                        .run_query(|conn| {
+                            transactions::table
+                                .select(transactions::tx_sequence_number)
+                                .filter(
+                                    transactions::transaction_digest
+                                        .eq(tx_digest.into_inner().to_vec()),
+                                )
+                                .first::<i64>(conn)
+                                .optional()
+                        })?
+                        .ok_or_else(|| {
+                            IndexerError::InvalidArgumentError(format!(
+                                "Cursor transaction {tx_digest} not found"
+                            ))
+                        })?;
+                    Ok::<_, IndexerError>((tx_seq, event_seq as i64))
+                },
+            )
+            .transpose()?;
+
+        let result = self.run_query_repeatable(|conn| {
+            let Some(schema) = event_schemas::table
+                .filter(event_schemas::event_type.eq(event_type.clone()))
+                .filter(event_schemas::package_id.eq(package.clone()))
+                .first::<StoredEventSchema>(conn)
+                .optional()?
+            else {
+                return Ok(None);
+            };
+
+            let mut query = events::table
+                .filter(events::event_type.eq(event_type))
+                .filter(events::package.eq(package))
+                .limit(limit as i64)
+                .into_boxed();
+            if descending_order {
+                if let Some((tx_seq, event_seq)) = cursor {
+                    query = query.filter(
+                        events::tx_sequence_number
+                            .lt(tx_seq)
+                            .or(events::tx_sequence_number
+                                .eq(tx_seq)
+                                .and(events::event_sequence_number.lt(event_seq))),
+                    );
+                }
+                query = query.order((
+                    events::tx_sequence_number.desc(),
+                    events::event_sequence_number.desc(),
+                ));
+            } else {
+                if let Some((tx_seq, event_seq)) = cursor {
+                    query = query.filter(
+                        events::tx_sequence_number
+                            .gt(tx_seq)
+                            .or(events::tx_sequence_number
+                                .eq(tx_seq)
+                                .and(events::event_sequence_number.gt(event_seq))),
+                    );
+                }
+                query = query.order((
+                    events::tx_sequence_number.asc(),
+                    events::event_sequence_number.asc(),
+                ));
+            }
+            let stored_events = query.load::<StoredEvent>(conn)?;
+            Ok::<_, diesel::result::Error>(Some((schema, stored_events)))
+        })?;
+
+        let Some((schema, stored_events)) = result else {
+            return Ok(None);
+        };
+        let layout = schema.struct_layout()?;
+        stored_events
+            .into_iter()
+            .map(|event| event.try_into_sui_event_with_layout(&layout))
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+
+    /// Returns the events relevant to `address` after `cursor`: those emitted by transactions it
+    /// sent, and those with it in their payload, as recorded in `event_addresses`.
+    pub fn get_events_by_address(
+        &self,
+        address: SuiAddress,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<SuiEvent>> {
+        let cursor_clause = match cursor {
+            Some(EventID {
+                tx_digest,
+                event_seq,
+            }) => {
+                let tx_seq = self
+                    .run_query(|conn| {
+                        transactions::table
+                            .select(transactions::tx_sequence_number)
+                            .filter(
+                                transactions::transaction_digest
+                                    .eq(tx_digest.into_inner().to_vec()),
+                            )
+                            .first::<i64>(conn)
+                            .optional()
+                    })?
+                    .ok_or_else(|| {
+                        IndexerError::InvalidArgumentError(format!(
+                            "Cursor transaction {tx_digest} not found"
+                        ))
+                    })?;
+                let cmp = if descending_order { "<" } else { ">" };
+                format!(
+                    "AND (a.tx_sequence_number, a.event_sequence_number) \
+                     {cmp} ({tx_seq}, {event_seq})"
+                )
+            }
+            None => String::new(),
+        };
+        let order = if descending_order { "DESC" } else { "ASC" };
+        let query = format!(
+            "SELECT e.* FROM event_addresses a \
+             JOIN events e \
+             ON e.tx_sequence_number = a.tx_sequence_number \
+             AND e.event_sequence_number = a.event_sequence_number \
+             WHERE a.address = '\\x{}'::bytea {cursor_clause} \
+             ORDER BY a.tx_sequence_number {order}, a.event_sequence_number {order} \
+             LIMIT {limit}",
+            Hex::encode(address.to_vec()),
+        );
+        tracing::debug!("query events by address: {}", query);
         let stored_events =
             self.run_query(|conn| diesel::sql_query(query).load::<StoredEvent>(conn))?;
         stored_events
@@ -1234,6 +1807,25 @@ impl IndexerReader {
             .collect()
     }
 
+    /// The events emitted by the transactions in `checkpoint`, in the order they were emitted.
+    pub fn get_checkpoint_events(
+        &self,
+        checkpoint: &StoredCheckpoint,
+    ) -> Result<Vec<StoredEvent>, IndexerError> {
+        let end = checkpoint.network_total_transactions;
+        let start = end - checkpoint.tx_digests.len() as i64;
+        self.run_query(|conn| {
+            events::table
+                .filter(events::tx_sequence_number.ge(start))
+                .filter(events::tx_sequence_number.lt(end))
+                .order((
+                    events::tx_sequence_number.asc(),
+                    events::event_sequence_number.asc(),
+                ))
+                .load::<StoredEvent>(conn)
+        })
+    }
+
     pub async fn get_transaction_events_in_blocking_task(
         &self,
         digest: TransactionDigest,
@@ -1287,7 +1879,12 @@ impl IndexerReader {
         let object_refs = self.get_object_refs(dfo_ids)?;
         let mut dynamic_fields = objects
             .into_iter()
-            .map(|object| object.try_into_expectant_dynamic_field_info(self))
+            .map(|mut object| {
+                if object.df_name.is_none() {
+                    object.df_name = Some(rendered_dynamic_field_name(self, &object)?);
+                }
+                object.try_into_expectant_dynamic_field_info(self)
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         for df in dynamic_fields.iter_mut() {
@@ -1477,29 +2074,7 @@ impl IndexerReader {
         // If coin_type is None, look for all coins.
         coin_type: Option<String>,
     ) -> Result<Vec<Balance>, IndexerError> {
-        let coin_type_filter = if let Some(coin_type) = coin_type {
-            format!("= '{}'", coin_type)
-        } else {
-            "IS NOT NULL".to_string()
-        };
-        // Note: important to cast to BIGINT to avoid deserialize confusion
-        let query = format!(
-            "
-            SELECT coin_type, \
-            CAST(COUNT(*) AS BIGINT) AS coin_num, \
-            CAST(SUM(coin_balance) AS BIGINT) AS coin_balance \
-            FROM objects \
-            WHERE owner_type = {} \
-            AND owner_id = '\\x{}'::BYTEA \
-            AND coin_type {} \
-            GROUP BY coin_type \
-            ORDER BY coin_type ASC
-        ",
-            OwnerType::Address as i16,
-            Hex::encode(owner.to_vec()),
-            coin_type_filter,
-        );
-
+        let query = coin_balances_query(owner, coin_type.as_deref());
         tracing::debug!("get coin balances query: {query}");
         let coin_balances =
             self.run_query(|conn| diesel::sql_query(query).load::<CoinBalance>(conn))?;
@@ -1510,25 +2085,131 @@ impl IndexerReader {
     }
 
     pub fn get_latest_network_metrics(&self) -> IndexerResult<NetworkMetrics> {
-        let metrics = self.run_query(|conn| {
-            diesel::sql_query("SELECT * FROM network_metrics;")
-                .get_result::<StoredNetworkMetrics>(conn)
+        let (metrics, recent_checkpoints) = self.run_query(|conn| {
+            let metrics = diesel::sql_query("SELECT * FROM network_metrics;")
+                .get_result::<StoredNetworkMetrics>(conn)?;
+            let recent_checkpoints = checkpoints::table
+                .select((
+                    checkpoints::network_total_transactions,
+                    checkpoints::timestamp_ms,
+                ))
+                .order(checkpoints::sequence_number.desc())
+                .limit(RECENT_CHECKPOINTS)
+                .load::<(i64, i64)>(conn)?;
+            Ok::<_, diesel::result::Error>((metrics, recent_checkpoints))
         })?;
-        Ok(metrics.into())
+        let system_state = self.get_latest_sui_system_state()?;
+        let recent = RecentNetworkActivity::new(
+            &recent_checkpoints,
+            system_state.epoch_start_timestamp_ms,
+            system_state.epoch_duration_ms,
+        );
+        Ok(metrics.into_network_metrics(recent))
     }
 
-    pub fn get_latest_move_call_metrics(&self) -> IndexerResult<MoveCallMetrics> {
-        let latest_3d_move_call_metrics = self.run_query(|conn| {
-            move_call_metrics::table
-                .filter(move_call_metrics::dsl::day.eq(3))
-                .order(move_call_metrics::dsl::id.desc())
-                .limit(10)
-                .load::<QueriedMoveCallMetrics>(conn)
+    pub fn get_gas_price_history(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<GasPriceStats>> {
+        let stats = self.run_query(|conn| {
+            let mut query = gas_price_stats::table.into_boxed();
+            if let Some(cursor) = cursor {
+                if descending_order {
+                    query = query.filter(gas_price_stats::first_checkpoint.lt(cursor as i64));
+                } else {
+                    query = query.filter(gas_price_stats::first_checkpoint.gt(cursor as i64));
+                }
+            }
+            if descending_order {
+                query = query.order_by(gas_price_stats::first_checkpoint.desc());
+            } else {
+                query = query.order_by(gas_price_stats::first_checkpoint.asc());
+            }
+
+            query.limit(limit as i64).load::<StoredGasPriceStats>(conn)
         })?;
-        let latest_7d_move_call_metrics = self.run_query(|conn| {
-            move_call_metrics::table
-                .filter(move_call_metrics::dsl::day.eq(7))
-                .order(move_call_metrics::dsl::id.desc())
+        Ok(stats.into_iter().map(GasPriceStats::from).collect())
+    }
+
+    /// The APYs of each validator in epochs `start_epoch` to `end_epoch` (inclusive).
+    pub fn get_validator_apy_history(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> IndexerResult<Vec<ValidatorApyHistory>> {
+        let apys = self.run_query(|conn| {
+            validator_apys::table
+                .filter(validator_apys::epoch.ge(start_epoch as i64))
+                .filter(validator_apys::epoch.le(end_epoch as i64))
+                .order_by((validator_apys::validator_address, validator_apys::epoch))
+                .load::<StoredValidatorApy>(conn)
+        })?;
+        validator_apy_histories(apys)
+    }
+
+    /// The history of validator `address` in epochs `start_epoch` to `end_epoch` (inclusive).
+    pub fn get_validator_history(
+        &self,
+        address: SuiAddress,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> IndexerResult<Vec<ValidatorEpochHistory>> {
+        let history = self.run_query(|conn| {
+            validator_history::table
+                .filter(validator_history::validator_address.eq(address.to_vec()))
+                .filter(validator_history::epoch.ge(start_epoch as i64))
+                .filter(validator_history::epoch.le(end_epoch as i64))
+                .order_by(validator_history::epoch)
+                .load::<StoredValidatorHistory>(conn)
+        })?;
+        history.into_iter().map(TryInto::try_into).collect()
+    }
+
+    /// The calls made to the functions of `package_id` from `start_epoch` to `end_epoch`
+    /// (inclusive), and the gas paid by the transactions making them.
+    pub fn get_package_metrics(
+        &self,
+        package_id: ObjectID,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> IndexerResult<PackageMetrics> {
+        // Sums of BIGINTs are NUMERIC, and saturated to fit back into BIGINTs.
+        let query = format!(
+            "SELECT
+                module,
+                function,
+                LEAST(SUM(call_count), {max})::BIGINT AS call_count,
+                LEAST(SUM(transaction_count), {max})::BIGINT AS transaction_count,
+                LEAST(SUM(computation_cost), {max})::BIGINT AS computation_cost,
+                LEAST(SUM(storage_cost), {max})::BIGINT AS storage_cost,
+                LEAST(SUM(storage_rebate), {max})::BIGINT AS storage_rebate,
+                LEAST(SUM(non_refundable_storage_fee), {max})::BIGINT AS non_refundable_storage_fee
+              FROM package_call_metrics
+              WHERE package = '\\x{package}'::BYTEA AND epoch BETWEEN {start_epoch} AND {end_epoch}
+              GROUP BY module, function
+              ORDER BY call_count DESC, module ASC, function ASC",
+            max = i64::MAX,
+            package = Hex::encode(package_id.to_vec()),
+        );
+        let queried =
+            self.run_query(|conn| diesel::sql_query(query).load::<QueriedCallMetrics>(conn))?;
+        Ok(package_metrics(package_id, start_epoch, end_epoch, queried))
+    }
+
+    pub fn get_latest_move_call_metrics(&self) -> IndexerResult<MoveCallMetrics> {
+        let latest_3d_move_call_metrics = self.run_query(|conn| {
+            move_call_metrics::table
+                .filter(move_call_metrics::dsl::day.eq(3))
+                .order(move_call_metrics::dsl::id.desc())
+                .limit(10)
+                .load::<QueriedMoveCallMetrics>(conn)
+        })?;
+        let latest_7d_move_call_metrics = self.run_query(|conn| {
+            move_call_metrics::table
+                .filter(move_call_metrics::dsl::day.eq(7))
+                .order(move_call_metrics::dsl::id.desc())
                 .limit(10)
                 .load::<QueriedMoveCallMetrics>(conn)
         })?;
@@ -1622,6 +2303,65 @@ impl IndexerReader {
             .collect())
     }
 
+    /// The `limit` addresses with the highest `rank_by` summed over the last `days` days that
+    /// daily stats have been aggregated for.
+    pub fn get_top_accounts(
+        &self,
+        rank_by: AccountRanking,
+        days: u64,
+        limit: usize,
+    ) -> IndexerResult<Vec<AccountActivity>> {
+        let rank_column = match rank_by {
+            AccountRanking::TransactionsSent => "sent_tx_count",
+            AccountRanking::TransactionsReceived => "received_tx_count",
+            AccountRanking::SuiSent => "sui_sent",
+            AccountRanking::SuiReceived => "sui_received",
+        };
+        // Sums of BIGINTs are NUMERIC, and saturated to fit back into BIGINTs.
+        let top_accounts_query = format!(
+            "SELECT
+                address,
+                LEAST(SUM(sent_tx_count), {max})::BIGINT AS sent_tx_count,
+                LEAST(SUM(received_tx_count), {max})::BIGINT AS received_tx_count,
+                LEAST(SUM(sui_sent), {max})::BIGINT AS sui_sent,
+                LEAST(SUM(sui_received), {max})::BIGINT AS sui_received
+              FROM address_daily_stats
+              WHERE day > (SELECT MAX(day) FROM address_daily_stats) - {days}
+              GROUP BY address
+              ORDER BY {rank_column} DESC, address ASC
+              LIMIT {limit}",
+            max = i64::MAX,
+            days = days.min(i64::MAX as u64),
+        );
+        let top_accounts = self.run_query(|conn| {
+            diesel::sql_query(top_accounts_query).load::<QueriedAccountActivity>(conn)
+        })?;
+
+        top_accounts
+            .into_iter()
+            .map(AccountActivity::try_from)
+            .collect()
+    }
+
+    /// The active address counts of at most `limit` days from `start_day` to `end_day`
+    /// (inclusive), in days since the Unix epoch.
+    pub fn get_active_address_counts(
+        &self,
+        start_day: i64,
+        end_day: i64,
+        limit: usize,
+    ) -> IndexerResult<Vec<ActiveAddressCounts>> {
+        let counts = self.run_query(|conn| {
+            active_address_counts::table
+                .filter(active_address_counts::day.ge(start_day))
+                .filter(active_address_counts::day.le(end_day))
+                .order(active_address_counts::day.asc())
+                .limit(limit as i64)
+                .load::<StoredActiveAddressCounts>(conn)
+        })?;
+        Ok(counts.into_iter().map(ActiveAddressCounts::from).collect())
+    }
+
     pub(crate) async fn get_display_fields(
         &self,
         original_object: &sui_types::object::Object,
@@ -1657,9 +2397,39 @@ impl IndexerReader {
             .await
     }
 
+    pub async fn multi_get_coin_metadata_in_blocking_task(
+        &self,
+        coin_structs: Vec<StructTag>,
+    ) -> Result<Vec<Option<SuiCoinMetadata>>, IndexerError> {
+        self.spawn_blocking(move |this| {
+            coin_structs
+                .into_iter()
+                .map(|coin_struct| this.get_coin_metadata(coin_struct))
+                .collect()
+        })
+        .await
+    }
+
     fn get_coin_metadata(
         &self,
         coin_struct: StructTag,
+    ) -> Result<Option<SuiCoinMetadata>, IndexerError> {
+        let Some(cache) = &self.coin_metadata_cache else {
+            return self.get_coin_metadata_from_db(coin_struct);
+        };
+        if let Some(metadata) = cache.get(&coin_struct) {
+            return Ok(Some(metadata));
+        }
+        let metadata = self.get_coin_metadata_from_db(coin_struct.clone())?;
+        if let Some(metadata) = &metadata {
+            cache.insert(coin_struct, metadata.clone());
+        }
+        Ok(metadata)
+    }
+
+    fn get_coin_metadata_from_db(
+        &self,
+        coin_struct: StructTag,
     ) -> Result<Option<SuiCoinMetadata>, IndexerError> {
         let package_id = coin_struct.address.into();
         let coin_metadata_type =
@@ -1725,123 +2495,1718 @@ impl IndexerReader {
             latest_checkpoint_sequence,
         ))
     }
-}
-
-#[derive(Clone, Default)]
-struct PackageCache {
-    inner: Arc<RwLock<BTreeMap<ObjectID, MovePackage>>>,
-}
 
-impl PackageCache {
-    fn insert(&self, object_id: ObjectID, package: MovePackage) {
-        self.inner.write().unwrap().insert(object_id, package);
+    /// The sequence number of the last transaction in `checkpoint`, which reads pinned to it don't
+    /// go past.
+    fn get_checkpoint_last_tx_sequence_number(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<i64> {
+        let network_total_transactions = self.run_query(|conn| {
+            checkpoints::table
+                .select(checkpoints::network_total_transactions)
+                .filter(checkpoints::sequence_number.eq(checkpoint as i64))
+                .first::<i64>(conn)
+                .optional()
+        })?;
+        network_total_transactions
+            .map(|total| total - 1)
+            .ok_or_else(|| {
+                IndexerError::InvalidArgumentError(format!(
+                    "Checkpoint {checkpoint} has not been indexed"
+                ))
+            })
     }
 
-    fn get(&self, object_id: &ObjectID) -> Option<MovePackage> {
-        self.inner.read().unwrap().get(object_id).cloned()
-    }
-}
+    fn get_address_labels(&self, addresses: Vec<SuiAddress>) -> IndexerResult<AddressLabels> {
+        let addresses = addresses
+            .into_iter()
+            .map(|address| address.to_vec())
+            .collect::<Vec<_>>();
+        let stored_labels = self.run_query(|conn| {
+            address_labels::table
+                .filter(address_labels::address.eq_any(addresses))
+                .load::<StoredAddressLabel>(conn)
+        })?;
 
-impl move_core_types::resolver::ModuleResolver for IndexerReader {
-    type Error = IndexerError;
+        stored_labels
+            .into_iter()
+            .map(StoredAddressLabel::try_into_address_label)
+            .collect()
+    }
 
-    fn get_module(
+    pub async fn get_address_labels_in_blocking_task(
         &self,
-        id: &move_core_types::language_storage::ModuleId,
-    ) -> Result<Option<Vec<u8>>, Self::Error> {
-        let package_id = ObjectID::from(*id.address());
-        let module_name = id.name().to_string();
-        Ok(self
-            .get_package(&package_id)?
-            .and_then(|package| package.serialized_module_map().get(&module_name).cloned()))
+        addresses: Vec<SuiAddress>,
+    ) -> IndexerResult<AddressLabels> {
+        self.spawn_blocking(move |this| this.get_address_labels(addresses))
+            .await
     }
-}
 
-impl sui_types::storage::ObjectStore for IndexerReader {
-    fn get_object(
+    /// Labels of the senders and recipients of the transactions with sequence numbers
+    /// `tx_sequence_numbers`, grouped by transaction.
+    fn get_transaction_labels(
         &self,
-        object_id: &ObjectID,
-    ) -> Result<Option<sui_types::object::Object>, sui_types::storage::error::Error> {
-        self.get_object(object_id, None)
-            .map_err(sui_types::storage::error::Error::custom)
+        tx_sequence_numbers: Vec<i64>,
+    ) -> IndexerResult<HashMap<i64, AddressLabels>> {
+        let senders = self.run_query(|conn| {
+            tx_senders::table
+                .inner_join(
+                    address_labels::table.on(address_labels::address.eq(tx_senders::sender)),
+                )
+                .filter(tx_senders::tx_sequence_number.eq_any(tx_sequence_numbers.clone()))
+                .select((tx_senders::tx_sequence_number, address_labels::all_columns))
+                .load::<(i64, StoredAddressLabel)>(conn)
+        })?;
+        let recipients = self.run_query(|conn| {
+            tx_recipients::table
+                .inner_join(
+                    address_labels::table.on(address_labels::address.eq(tx_recipients::recipient)),
+                )
+                .filter(tx_recipients::tx_sequence_number.eq_any(tx_sequence_numbers))
+                .select((
+                    tx_recipients::tx_sequence_number,
+                    address_labels::all_columns,
+                ))
+                .load::<(i64, StoredAddressLabel)>(conn)
+        })?;
+
+        let mut labels: HashMap<i64, AddressLabels> = HashMap::new();
+        for (tx_sequence_number, stored_label) in senders.into_iter().chain(recipients) {
+            let (address, label) = stored_label.try_into_address_label()?;
+            labels
+                .entry(tx_sequence_number)
+                .or_default()
+                .insert(address, label);
+        }
+        Ok(labels)
     }
 
-    fn get_object_by_key(
+    pub fn get_event_schemas(
         &self,
-        object_id: &ObjectID,
-        version: sui_types::base_types::VersionNumber,
-    ) -> Result<Option<sui_types::object::Object>, sui_types::storage::error::Error> {
-        self.get_object(object_id, Some(version))
-            .map_err(sui_types::storage::error::Error::custom)
+        event_type: Option<StructTag>,
+        cursor: Option<EventSchemaCursor>,
+        limit: usize,
+    ) -> IndexerResult<Vec<EventSchema>> {
+        let mut query = event_schemas::table.into_boxed();
+        if let Some(event_type) = event_type {
+            query = query.filter(
+                event_schemas::event_type
+                    .eq(event_type.to_canonical_string(/* with_prefix */ true)),
+            );
+        }
+        if let Some(cursor) = cursor {
+            let package_id = cursor.package_id.to_vec();
+            query = query.filter(
+                event_schemas::event_type.gt(cursor.event_type.clone()).or(
+                    event_schemas::event_type
+                        .eq(cursor.event_type)
+                        .and(event_schemas::package_id.gt(package_id)),
+                ),
+            );
+        }
+
+        let stored_schemas = self.run_query(|conn| {
+            query
+                .order((
+                    event_schemas::event_type.asc(),
+                    event_schemas::package_id.asc(),
+                ))
+                .limit(limit as i64)
+                .load::<StoredEventSchema>(conn)
+        })?;
+
+        stored_schemas
+            .into_iter()
+            .map(EventSchema::try_from)
+            .collect()
     }
-}
 
-impl move_bytecode_utils::module_cache::GetModule for IndexerReader {
-    type Error = IndexerError;
-    type Item = move_binary_format::CompiledModule;
+    /// Returns `None` if the transaction has not been indexed.
+    pub fn get_transaction_input_objects(
+        &self,
+        digest: TransactionDigest,
+    ) -> IndexerResult<Option<Vec<TransactionInputObject>>> {
+        let stored_txn = self.run_query(|conn| {
+            transactions::table
+                .filter(transactions::transaction_digest.eq(digest.inner().to_vec()))
+                .first::<StoredTransaction>(conn)
+                .optional()
+        })?;
 
-    fn get_module_by_id(
+        stored_txn.map(|tx| tx.try_into_input_objects()).transpose()
+    }
+
+    /// Returns the changes that the transaction with `digest` made to objects, each with the
+    /// object as the transaction left it, read from `objects_history`, or from `objects` if it
+    /// is still at that version. Packages, objects that were deleted or wrapped, and object
+    /// versions that are no longer held come without the object. Returns `None` if the
+    /// transaction is not indexed.
+    pub fn get_transaction_object_changes(
         &self,
-        id: &move_core_types::language_storage::ModuleId,
-    ) -> Result<Option<Self::Item>, Self::Error> {
-        let package_id = ObjectID::from(*id.address());
-        let module_name = id.name().to_string();
-        // TODO: we need a cache here for deserialized module and take care of package upgrades
-        self.get_package(&package_id)?
-            .and_then(|package| package.serialized_module_map().get(&module_name).cloned())
-            .map(|bytes| move_binary_format::CompiledModule::deserialize_with_defaults(&bytes))
-            .transpose()
-            .map_err(|e| {
-                IndexerError::ModuleResolutionError(format!(
-                    "Error deserializing module {}: {}",
-                    id, e
+        digest: TransactionDigest,
+    ) -> IndexerResult<Option<Vec<(ObjectChange, Option<ObjectRead>)>>> {
+        let stored_txn = self.run_query(|conn| {
+            transactions::table
+                .filter(transactions::transaction_digest.eq(digest.inner().to_vec()))
+                .select((
+                    transactions::checkpoint_sequence_number,
+                    transactions::object_changes,
                 ))
+                .first::<(i64, Vec<Option<Vec<u8>>>)>(conn)
+                .optional()
+        })?;
+        let Some((checkpoint, object_changes)) = stored_txn else {
+            return Ok(None);
+        };
+
+        let changes = object_changes
+            .into_iter()
+            .map(|object_change| {
+                let object_change = object_change.ok_or_else(|| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "object_change should not be null, tx_digest={:?}",
+                        digest
+                    ))
+                })?;
+                let object_change: IndexedObjectChange = bcs::from_bytes(&object_change)
+                    .map_err(|e| {
+                        IndexerError::PersistentStorageDataCorruptionError(format!(
+                            "Can't convert object_change bytes into IndexedObjectChange. tx_digest={:?} Error: {e}",
+                            digest
+                        ))
+                    })?;
+                Ok(ObjectChange::from(object_change))
             })
-    }
-}
+            .collect::<IndexerResult<Vec<_>>>()?;
 
-#[cached(
-    type = "SizedCache<String, Option<ObjectID>>",
-    create = "{ SizedCache::with_size(10000) }",
-    convert = r#"{ format!("{}{}", package_id, obj_type) }"#,
-    result = true
-)]
-fn get_single_obj_id_from_package_publish(
-    reader: &IndexerReader,
-    package_id: ObjectID,
-    obj_type: String,
-) -> Result<Option<ObjectID>, IndexerError> {
-    let publish_txn_effects_opt = if is_system_package(package_id) {
-        Some(reader.get_transaction_effects_with_sequence_number(0))
-    } else {
-        reader.get_object(&package_id, None)?.map(|o| {
-            let publish_txn_digest = o.previous_transaction;
-            reader.get_transaction_effects_with_digest(publish_txn_digest)
-        })
-    };
-    if let Some(publish_txn_effects) = publish_txn_effects_opt {
-        let created_objs = publish_txn_effects?
-            .created()
+        // The versions the transaction left objects at, other than packages.
+        let versions: HashSet<(Vec<u8>, i64)> = changes
             .iter()
-            .map(|o| o.object_id())
-            .collect::<Vec<_>>();
-        let obj_ids_with_type =
-            reader.filter_object_id_with_type(created_objs, obj_type.clone())?;
-        if obj_ids_with_type.len() == 1 {
-            Ok(Some(obj_ids_with_type[0]))
-        } else if obj_ids_with_type.is_empty() {
-            // The package exists but no such object is created in that transaction. Or maybe it is wrapped and we don't know yet.
-            Ok(None)
-        } else {
-            // We expect there to be only one object of this type created by the package but more than one is found.
-            tracing::error!(
-                "There are more than one objects found for type {}",
-                obj_type
-            );
-            Ok(None)
+            .filter(|change| {
+                matches!(
+                    change,
+                    ObjectChange::Created { .. }
+                        | ObjectChange::Mutated { .. }
+                        | ObjectChange::Transferred { .. }
+                )
+            })
+            .map(|change| {
+                let (object_id, version, _) = change.object_ref();
+                (object_id.to_vec(), version.value() as i64)
+            })
+            .collect();
+        let ids = versions.iter().map(|(id, _)| id.clone()).collect_vec();
+
+        let (history, latest) = self.run_query_repeatable(|conn| {
+            let history = objects_history::table
+                .filter(objects_history::checkpoint_sequence_number.eq(checkpoint))
+                .filter(objects_history::object_id.eq_any(ids.clone()))
+                .load::<StoredHistoryObject>(conn)?;
+            let latest = objects::table
+                .filter(objects::object_id.eq_any(ids))
+                .load::<StoredObject>(conn)?;
+            Ok::<_, diesel::result::Error>((history, latest))
+        })?;
+
+        let mut objects = HashMap::new();
+        for object in latest {
+            let version = (object.object_id.clone(), object.object_version);
+            if versions.contains(&version) {
+                objects.insert(version, object.try_into_object_read(self)?);
+            }
         }
-    } else {
-        // The coin package does not exist.
-        Ok(None)
+        for object in history {
+            let version = (object.object_id.clone(), object.object_version);
+            if versions.contains(&version) && !objects.contains_key(&version) {
+                objects.insert(version, object.try_into_object_read(self)?);
+            }
+        }
+
+        Ok(Some(
+            changes
+                .into_iter()
+                .map(|change| {
+                    let (object_id, version, _) = change.object_ref();
+                    let object = objects.remove(&(object_id.to_vec(), version.value() as i64));
+                    (change, object)
+                })
+                .collect(),
+        ))
+    }
+
+    /// Returns `None` if the indexer has not seen any transitions of `object_id`.
+    pub fn get_object_lifecycle(
+        &self,
+        object_id: ObjectID,
+    ) -> IndexerResult<Option<ObjectLifecycle>> {
+        let transferred = transition_to_i16(ObjectTransitionKind::Transferred);
+        let (stored_transitions, transfer_count) = self.run_query_repeatable(|conn| {
+            let transitions = object_transitions::table
+                .filter(object_transitions::object_id.eq(object_id.to_vec()))
+                .filter(object_transitions::transition.ne(transferred))
+                .order(object_transitions::object_version.asc())
+                .load::<StoredObjectTransition>(conn)?;
+            let transfer_count = object_transitions::table
+                .filter(object_transitions::object_id.eq(object_id.to_vec()))
+                .filter(object_transitions::transition.eq(transferred))
+                .count()
+                .get_result::<i64>(conn)?;
+            Ok::<_, diesel::result::Error>((transitions, transfer_count))
+        })?;
+
+        if stored_transitions.is_empty() && transfer_count == 0 {
+            return Ok(None);
+        }
+
+        let mut lifecycle = ObjectLifecycle {
+            object_id,
+            created: None,
+            wrapping: vec![],
+            transfer_count: transfer_count as u64,
+            deleted: None,
+        };
+        for stored in stored_transitions {
+            let transition = ObjectTransition::try_from(stored)?;
+            match transition.kind {
+                ObjectTransitionKind::Created => lifecycle.created = Some(transition),
+                ObjectTransitionKind::Wrapped | ObjectTransitionKind::Unwrapped => {
+                    lifecycle.wrapping.push(transition)
+                }
+                ObjectTransitionKind::Deleted | ObjectTransitionKind::UnwrappedThenDeleted => {
+                    lifecycle.deleted = Some(transition)
+                }
+                ObjectTransitionKind::Transferred => {}
+            }
+        }
+        Ok(Some(lifecycle))
+    }
+
+    /// Returns the transactions that changed object `object_id` after `cursor`, oldest first.
+    /// Transactions that created, mutated or unwrapped it are found in `tx_changed_objects`, and
+    /// those that wrapped or deleted it in `object_transitions`.
+    pub fn get_object_transaction_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<TransactionDigest>,
+        limit: usize,
+    ) -> IndexerResult<Vec<ObjectTransaction>> {
+        let cursor = cursor
+            .map(|digest| {
+                self.run_query(|conn| {
+                    transactions::table
+                        .select(transactions::tx_sequence_number)
+                        .filter(transactions::transaction_digest.eq(digest.inner().to_vec()))
+                        .first::<i64>(conn)
+                        .optional()
+                })?
+                .ok_or_else(|| {
+                    IndexerError::InvalidArgumentError(format!(
+                        "Cursor transaction {digest} not found"
+                    ))
+                })
+            })
+            .transpose()?
+            .unwrap_or(-1);
+        let removed = [
+            ObjectTransitionKind::Wrapped,
+            ObjectTransitionKind::Deleted,
+            ObjectTransitionKind::UnwrappedThenDeleted,
+        ]
+        .map(transition_to_i16);
+
+        let stored_txs = self.run_query_repeatable(|conn| {
+            // An object is only wrapped and deleted a handful of times, so these are read in full.
+            let removed_digests = object_transitions::table
+                .filter(object_transitions::object_id.eq(object_id.to_vec()))
+                .filter(object_transitions::transition.eq_any(removed))
+                .select(object_transitions::transaction_digest)
+                .load::<Vec<u8>>(conn)?;
+            let mut tx_seqs = transactions::table
+                .filter(transactions::transaction_digest.eq_any(removed_digests))
+                .filter(transactions::tx_sequence_number.gt(cursor))
+                .select(transactions::tx_sequence_number)
+                .load::<i64>(conn)?;
+            tx_seqs.extend(
+                tx_changed_objects::table
+                    .filter(tx_changed_objects::object_id.eq(object_id.to_vec()))
+                    .filter(tx_changed_objects::tx_sequence_number.gt(cursor))
+                    .order(tx_changed_objects::tx_sequence_number.asc())
+                    .limit(limit as i64)
+                    .select(tx_changed_objects::tx_sequence_number)
+                    .load::<i64>(conn)?,
+            );
+            tx_seqs.sort();
+            tx_seqs.dedup();
+            tx_seqs.truncate(limit);
+
+            transactions::table
+                .filter(transactions::tx_sequence_number.eq_any(tx_seqs))
+                .order(transactions::tx_sequence_number.asc())
+                .load::<StoredTransaction>(conn)
+        })?;
+
+        stored_txs
+            .iter()
+            .filter_map(|tx| tx.try_into_object_transaction(object_id).transpose())
+            .collect()
+    }
+
+    /// Returns the objects owned by `address` whose types have a Display, ordered by object ID,
+    /// starting after `cursor`.
+    pub fn get_owned_nfts(
+        &self,
+        address: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> IndexerResult<Vec<ObjectRead>> {
+        let stored_objects = self.run_query(|conn| {
+            let mut query = objects::table
+                .filter(objects::owner_type.eq(OwnerType::Address as i16))
+                .filter(objects::owner_id.eq(address.to_vec()))
+                .filter(
+                    objects::object_type
+                        .assume_not_null()
+                        .eq_any(display::table.select(display::object_type)),
+                )
+                .order(objects::object_id.asc())
+                .limit(limit as i64)
+                .into_boxed();
+            if let Some(cursor) = cursor {
+                query = query.filter(objects::object_id.gt(cursor.to_vec()));
+            }
+            query.load::<StoredObject>(conn)
+        })?;
+
+        stored_objects
+            .into_iter()
+            .map(|object| object.try_into_object_read(self))
+            .collect()
+    }
+
+    /// Returns the type of `object_id`, the object itself if it is still held, and its transfers
+    /// after version `cursor`, in order of version. Returns `None` if the object is not held and
+    /// has no transfers indexed.
+    pub fn get_nft_transfers(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> IndexerResult<Option<(String, Option<ObjectRead>, Vec<NftTransfer>)>> {
+        let (object, last_type, transfers) = self.run_query_repeatable(|conn| {
+            let object = objects::table
+                .filter(objects::object_id.eq(object_id.to_vec()))
+                .first::<StoredObject>(conn)
+                .optional()?;
+            let last_type = nft_transfers::table
+                .filter(nft_transfers::object_id.eq(object_id.to_vec()))
+                .order(nft_transfers::object_version.desc())
+                .select(nft_transfers::object_type)
+                .first::<String>(conn)
+                .optional()?;
+            let mut query = nft_transfers::table
+                .filter(nft_transfers::object_id.eq(object_id.to_vec()))
+                .order(nft_transfers::object_version.asc())
+                .limit(limit as i64)
+                .into_boxed();
+            if let Some(cursor) = cursor {
+                query = query.filter(nft_transfers::object_version.gt(cursor as i64));
+            }
+            let transfers = query.load::<StoredNftTransfer>(conn)?;
+            Ok::<_, diesel::result::Error>((object, last_type, transfers))
+        })?;
+
+        let Some(object_type) = object
+            .as_ref()
+            .and_then(|o| o.object_type.clone())
+            .or(last_type)
+        else {
+            return Ok(None);
+        };
+        let object = object
+            .map(|object| object.try_into_object_read(self))
+            .transpose()?;
+        let transfers = transfers
+            .into_iter()
+            .map(NftTransfer::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Some((object_type, object, transfers)))
+    }
+
+    /// Reports for the senders in `senders` that have equivocated, ordered by sender.
+    pub fn get_equivocation_reports(
+        &self,
+        senders: Vec<SuiAddress>,
+    ) -> IndexerResult<Vec<EquivocationReport>> {
+        let senders = senders.into_iter().map(|s| s.to_vec()).collect::<Vec<_>>();
+        let stored = self.run_query(|conn| {
+            equivocations::table
+                .filter(equivocations::sender.eq_any(senders))
+                .order((
+                    equivocations::sender.asc(),
+                    equivocations::timestamp_ms.desc(),
+                ))
+                .load::<StoredEquivocation>(conn)
+        })?;
+
+        let mut reports: Vec<EquivocationReport> = vec![];
+        for stored in stored {
+            let e = Equivocation::try_from(stored)?;
+            let report = match reports.last_mut() {
+                Some(report) if report.sender == e.sender => report,
+                _ => {
+                    reports.push(EquivocationReport {
+                        sender: e.sender,
+                        objects: vec![],
+                    });
+                    reports.last_mut().unwrap()
+                }
+            };
+
+            // Rows are ordered by recency, so the first row for an object version is its most
+            // recent rejection.
+            let object = match report
+                .objects
+                .iter_mut()
+                .position(|o| o.object_id == e.object_id && o.version == e.version)
+            {
+                Some(i) => &mut report.objects[i],
+                None => {
+                    report.objects.push(EquivocatedObject {
+                        object_id: e.object_id,
+                        version: e.version,
+                        rejected: vec![],
+                        conflicting: vec![],
+                        last_seen_ms: e.timestamp_ms,
+                    });
+                    report.objects.last_mut().unwrap()
+                }
+            };
+            if !object.rejected.contains(&e.transaction_digest) {
+                object.rejected.push(e.transaction_digest);
+            }
+            if !object.conflicting.contains(&e.conflicting_digest) {
+                object.conflicting.push(e.conflicting_digest);
+            }
+        }
+
+        Ok(reports)
+    }
+
+    /// Returns the page of `address`' account statement following `cursor`, examining at most
+    /// `limit` of the transactions that the address sent or received objects in. `coin_type`
+    /// must be a canonical type string. Gas that the address paid as the sponsor of other
+    /// senders' transactions is not covered, as sponsors are not indexed, and shows up as a
+    /// failure to reconcile.
+    pub fn get_account_statement(
+        &self,
+        address: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<AccountStatementCursor>,
+        limit: usize,
+    ) -> IndexerResult<AccountStatement> {
+        let after = cursor.as_ref().map_or(-1, |c| c.tx_sequence_number as i64);
+        let opening_balances = cursor.map(|c| c.balances).unwrap_or_default();
+        let address_bytes = address.to_vec();
+        let balances_query = coin_balances_query(address, coin_type.as_deref());
+
+        // Read the transactions and current balances in one transaction, so that they can be
+        // reconciled against each other.
+        let (has_next_page, txs, current) = self.run_query_repeatable(|conn| {
+            let mut sequence_numbers: Vec<i64> = tx_senders::table
+                .filter(tx_senders::sender.eq(address_bytes.clone()))
+                .filter(tx_senders::tx_sequence_number.gt(after))
+                .order(tx_senders::tx_sequence_number.asc())
+                .limit(limit as i64 + 1)
+                .select(tx_senders::tx_sequence_number)
+                .load(conn)?;
+            sequence_numbers.extend(
+                tx_recipients::table
+                    .filter(tx_recipients::recipient.eq(address_bytes))
+                    .filter(tx_recipients::tx_sequence_number.gt(after))
+                    .order(tx_recipients::tx_sequence_number.asc())
+                    .limit(limit as i64 + 1)
+                    .select(tx_recipients::tx_sequence_number)
+                    .load::<i64>(conn)?,
+            );
+            sequence_numbers.sort_unstable();
+            sequence_numbers.dedup();
+            let has_next_page = sequence_numbers.len() > limit;
+            sequence_numbers.truncate(limit);
+
+            let txs = transactions::table
+                .filter(transactions::tx_sequence_number.eq_any(sequence_numbers))
+                .order(transactions::tx_sequence_number.asc())
+                .select((
+                    transactions::tx_sequence_number,
+                    transactions::transaction_digest,
+                    transactions::checkpoint_sequence_number,
+                    transactions::timestamp_ms,
+                    transactions::balance_changes,
+                ))
+                .load::<StoredStatementTransaction>(conn)?;
+            let current = if has_next_page {
+                None
+            } else {
+                Some(diesel::sql_query(balances_query).load::<CoinBalance>(conn)?)
+            };
+            Ok::<_, diesel::result::Error>((has_next_page, txs, current))
+        })?;
+
+        let last_tx_sequence_number = txs.last().map(|tx| tx.tx_sequence_number as u64);
+        let mut closing_balances = opening_balances.clone();
+        let data =
+            account_statement_entries(address, coin_type.as_deref(), txs, &mut closing_balances)?;
+        let reconciled = current.map(|current| {
+            let current = current
+                .into_iter()
+                .map(|c| (c.coin_type, c.coin_balance as i128))
+                .collect();
+            balances_reconcile(&closing_balances, &current)
+        });
+        let next_cursor =
+            last_tx_sequence_number
+                .or(after.try_into().ok())
+                .map(|tx_sequence_number| AccountStatementCursor {
+                    tx_sequence_number,
+                    balances: closing_balances.clone(),
+                });
+
+        Ok(AccountStatement {
+            address,
+            data,
+            opening_balances,
+            closing_balances,
+            next_cursor,
+            has_next_page,
+            reconciled,
+        })
+    }
+
+    /// Returns the snapshots of `address`' balance of `coin_type` (a canonical type string) at
+    /// the end of each bucket of `interval_ms` from the one including `start_ms` to the one
+    /// including `end_ms`, at most `limit` of them. The range defaults to start with the
+    /// address' first change to the balance, and to end with the last transaction that balance
+    /// changes have been taken from, and is empty if the balance never changed.
+    pub fn get_balance_history(
+        &self,
+        address: SuiAddress,
+        coin_type: String,
+        interval_ms: i64,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+        limit: usize,
+    ) -> IndexerResult<Vec<BalanceSnapshot>> {
+        let owner = address.to_vec();
+        let history = self.run_query_repeatable(|conn| {
+            let start_ms = match start_ms {
+                Some(start_ms) => Some(start_ms),
+                None => balance_changes::table
+                    .filter(balance_changes::owner.eq(owner.clone()))
+                    .filter(balance_changes::coin_type.eq(coin_type.clone()))
+                    .order(balance_changes::tx_sequence_number.asc())
+                    .select(balance_changes::timestamp_ms)
+                    .first::<i64>(conn)
+                    .optional()?,
+            };
+            let end_ms = match end_ms {
+                Some(end_ms) => Some(end_ms),
+                None => balance_changes::table
+                    .order(balance_changes::tx_sequence_number.desc())
+                    .select(balance_changes::timestamp_ms)
+                    .first::<i64>(conn)
+                    .optional()?,
+            };
+            let (Some(start_ms), Some(end_ms)) = (start_ms, end_ms) else {
+                return Ok(None);
+            };
+
+            let start_bucket = start_ms / interval_ms;
+            let end_bucket = (end_ms / interval_ms)
+                .min(start_bucket.saturating_add(limit as i64).saturating_sub(1));
+            if start_bucket > end_bucket {
+                return Ok(None);
+            }
+            let changes = diesel::sql_query(balance_history_query(
+                &owner,
+                &coin_type,
+                interval_ms,
+                start_bucket,
+                end_bucket,
+            ))
+            .load::<QueriedBucketChange>(conn)?;
+            Ok::<_, diesel::result::Error>(Some((start_bucket, end_bucket, changes)))
+        })?;
+
+        let Some((start_bucket, end_bucket, changes)) = history else {
+            return Ok(vec![]);
+        };
+        // Changes before the range are summed into the bucket right before it.
+        let (opening, changes) = match changes.split_first() {
+            Some((first, rest)) if first.bucket < start_bucket => (first.change as i128, rest),
+            _ => (0, changes.as_slice()),
+        };
+        Ok(balance_snapshots(
+            opening,
+            changes,
+            start_bucket,
+            end_bucket,
+            interval_ms,
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl CheckpointWatermark for IndexerReader {
+    async fn latest_checkpoint(&self) -> anyhow::Result<CheckpointSequenceNumber> {
+        let checkpoint = self
+            .run_query_async(|conn| {
+                checkpoints::table
+                    .select(checkpoints::sequence_number)
+                    .order(checkpoints::sequence_number.desc())
+                    .first::<i64>(conn)
+            })
+            .await?;
+        Ok(checkpoint as CheckpointSequenceNumber)
+    }
+
+    async fn transaction_checkpoint(
+        &self,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+        let checkpoint = self
+            .run_query_async(move |conn| {
+                transactions::table
+                    .select(transactions::checkpoint_sequence_number)
+                    .filter(transactions::transaction_digest.eq(digest.inner().to_vec()))
+                    .first::<i64>(conn)
+                    .optional()
+            })
+            .await?;
+        Ok(checkpoint.map(|c| c as CheckpointSequenceNumber))
+    }
+}
+
+#[async_trait::async_trait]
+impl IndexerReadStore for IndexerReader {
+    async fn get_checkpoint(
+        &self,
+        checkpoint_id: CheckpointId,
+    ) -> IndexerResult<Option<sui_json_rpc_types::Checkpoint>> {
+        self.get_checkpoint_in_blocking_task(checkpoint_id).await
+    }
+
+    async fn get_latest_checkpoint(&self) -> IndexerResult<sui_json_rpc_types::Checkpoint> {
+        self.spawn_blocking(|this| this.get_latest_checkpoint())
+            .await
+    }
+
+    async fn get_checkpoints(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<sui_json_rpc_types::Checkpoint>> {
+        self.spawn_blocking(move |this| this.get_checkpoints(cursor, limit, descending_order))
+            .await
+    }
+
+    async fn get_checkpoint_by_timestamp(
+        &self,
+        timestamp_ms: u64,
+        direction: TimestampDirection,
+    ) -> IndexerResult<Option<sui_json_rpc_types::Checkpoint>> {
+        self.spawn_blocking(move |this| this.get_checkpoint_by_timestamp(timestamp_ms, direction))
+            .await
+    }
+
+    async fn get_epoch_info(&self, epoch: Option<EpochId>) -> IndexerResult<Option<EpochInfo>> {
+        self.spawn_blocking(move |this| this.get_epoch_info(epoch))
+            .await
+    }
+
+    async fn get_epochs(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<EpochInfo>> {
+        self.spawn_blocking(move |this| this.get_epochs(cursor, limit, descending_order))
+            .await
+    }
+
+    async fn get_latest_protocol_config(&self) -> IndexerResult<ProtocolConfig> {
+        self.get_latest_protocol_config_in_blocking_task().await
+    }
+
+    async fn get_latest_sui_system_state(&self) -> IndexerResult<SuiSystemStateSummary> {
+        self.spawn_blocking(|this| this.get_latest_sui_system_state())
+            .await
+    }
+
+    async fn get_sui_system_state_at_checkpoint(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<SuiSystemStateSummary> {
+        self.spawn_blocking(move |this| this.get_sui_system_state_at_checkpoint(checkpoint))
+            .await
+    }
+
+    async fn get_validator_from_table(
+        &self,
+        table_id: ObjectID,
+        key: sui_types::id::ID,
+    ) -> IndexerResult<SuiValidatorSummary> {
+        self.spawn_blocking(move |this| {
+            sui_types::sui_system_state::get_validator_from_table(&this, table_id, &key)
+                .map_err(IndexerError::from)
+        })
+        .await
+    }
+
+    async fn get_object(&self, object_id: ObjectID) -> IndexerResult<Option<Object>> {
+        self.get_object_in_blocking_task(object_id).await
+    }
+
+    async fn get_object_read(&self, object_id: ObjectID) -> IndexerResult<ObjectRead> {
+        self.get_object_read_in_blocking_task(object_id).await
+    }
+
+    async fn multi_get_objects(&self, object_ids: Vec<ObjectID>) -> IndexerResult<Vec<Object>> {
+        self.multi_get_objects_in_blocking_task(object_ids)
+            .await?
+            .into_iter()
+            .map(Object::try_from)
+            .collect()
+    }
+
+    async fn multi_get_objects_at_checkpoint(
+        &self,
+        object_ids: Vec<ObjectID>,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<Vec<ObjectRead>> {
+        self.spawn_blocking(move |this| {
+            this.multi_get_objects_at_checkpoint(object_ids, checkpoint)
+        })
+        .await
+    }
+
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+        filter: Option<SuiObjectDataFilter>,
+        cursor: Option<ObjectID>,
+        limit: usize,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> IndexerResult<Vec<ObjectRead>> {
+        self.spawn_blocking(move |this| {
+            let objects = match at_checkpoint {
+                Some(checkpoint) => this
+                    .get_owned_objects_at_checkpoint(address, filter, cursor, limit, checkpoint)?,
+                None => this
+                    .get_owned_objects_impl(address, filter, cursor, limit)?
+                    .into_iter()
+                    .map(StoredHistoryObject::from)
+                    .collect(),
+            };
+            objects
+                .into_iter()
+                .map(|object| object.try_into_object_read(&this))
+                .collect()
+        })
+        .await
+    }
+
+    async fn get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> IndexerResult<Vec<DynamicFieldInfo>> {
+        self.get_dynamic_fields_in_blocking_task(parent_object_id, cursor, limit)
+            .await
+    }
+
+    async fn get_dynamic_field_objects(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> IndexerResult<Vec<Object>> {
+        self.get_dynamic_fields_raw_in_blocking_task(parent_object_id, cursor, limit)
+            .await?
+            .into_iter()
+            .map(Object::try_from)
+            .collect()
+    }
+
+    async fn bcs_name_from_dynamic_field_name(
+        &self,
+        name: &DynamicFieldName,
+    ) -> IndexerResult<Vec<u8>> {
+        self.bcs_name_from_dynamic_field_name_in_blocking_task(name)
+            .await
+    }
+
+    async fn get_package(&self, package_id: ObjectID) -> IndexerResult<Option<MovePackage>> {
+        self.get_package_in_blocking_task(package_id).await
+    }
+
+    async fn get_struct_layout(&self, struct_tag: StructTag) -> IndexerResult<MoveStructLayout> {
+        self.spawn_blocking(move |this| {
+            MoveObject::get_layout_from_struct_tag(struct_tag, &this).map_err(IndexerError::from)
+        })
+        .await
+    }
+
+    async fn get_display_fields(
+        &self,
+        object: &Object,
+        layout: &Option<MoveStructLayout>,
+    ) -> IndexerResult<DisplayFieldsResponse> {
+        IndexerReader::get_display_fields(self, object, layout).await
+    }
+
+    async fn get_display_object_by_type(
+        &self,
+        object_type: &StructTag,
+    ) -> IndexerResult<Option<sui_types::display::DisplayVersionUpdatedEvent>> {
+        IndexerReader::get_display_object_by_type(self, object_type).await
+    }
+
+    async fn get_address_labels(&self, addresses: Vec<SuiAddress>) -> IndexerResult<AddressLabels> {
+        self.get_address_labels_in_blocking_task(addresses).await
+    }
+
+    async fn multi_get_transaction_block_response(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: sui_json_rpc_types::SuiTransactionBlockResponseOptions,
+    ) -> IndexerResult<Vec<SuiTransactionBlockResponse>> {
+        self.multi_get_transaction_block_response_in_blocking_task(digests, options)
+            .await
+    }
+
+    async fn query_transaction_blocks(
+        &self,
+        filter: Option<TransactionFilter>,
+        options: sui_json_rpc_types::SuiTransactionBlockResponseOptions,
+        cursor: Option<TransactionDigest>,
+        limit: usize,
+        is_descending: bool,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> IndexerResult<Vec<SuiTransactionBlockResponse>> {
+        self.query_transaction_blocks_in_blocking_task(
+            filter,
+            options,
+            cursor,
+            limit,
+            is_descending,
+            at_checkpoint,
+        )
+        .await
+    }
+
+    async fn get_transaction_events(
+        &self,
+        digest: TransactionDigest,
+    ) -> IndexerResult<Vec<SuiEvent>> {
+        self.get_transaction_events_in_blocking_task(digest).await
+    }
+
+    async fn query_events(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> IndexerResult<Vec<SuiEvent>> {
+        self.query_events_in_blocking_task(filter, cursor, limit, descending_order, at_checkpoint)
+            .await
+    }
+
+    async fn get_events_by_address(
+        &self,
+        address: SuiAddress,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<SuiEvent>> {
+        self.spawn_blocking(move |this| {
+            this.get_events_by_address(address, cursor, limit, descending_order)
+        })
+        .await
+    }
+
+    async fn get_events_by_package_version(
+        &self,
+        event_type: String,
+        package_id: ObjectID,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Option<Vec<SuiEvent>>> {
+        self.spawn_blocking(move |this| {
+            this.get_events_by_package_version(
+                event_type,
+                package_id,
+                cursor,
+                limit,
+                descending_order,
+            )
+        })
+        .await
+    }
+
+    async fn get_event_schemas(
+        &self,
+        event_type: Option<StructTag>,
+        cursor: Option<EventSchemaCursor>,
+        limit: usize,
+    ) -> IndexerResult<Vec<EventSchema>> {
+        self.spawn_blocking(move |this| this.get_event_schemas(event_type, cursor, limit))
+            .await
+    }
+
+    async fn get_transaction_input_objects(
+        &self,
+        digest: TransactionDigest,
+    ) -> IndexerResult<Option<Vec<TransactionInputObject>>> {
+        self.spawn_blocking(move |this| this.get_transaction_input_objects(digest))
+            .await
+    }
+
+    async fn get_transaction_object_changes(
+        &self,
+        digest: TransactionDigest,
+    ) -> IndexerResult<Option<Vec<(ObjectChange, Option<ObjectRead>)>>> {
+        self.spawn_blocking(move |this| this.get_transaction_object_changes(digest))
+            .await
+    }
+
+    async fn get_object_lifecycle(
+        &self,
+        object_id: ObjectID,
+    ) -> IndexerResult<Option<ObjectLifecycle>> {
+        self.spawn_blocking(move |this| this.get_object_lifecycle(object_id))
+            .await
+    }
+
+    async fn get_object_transaction_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<TransactionDigest>,
+        limit: usize,
+    ) -> IndexerResult<Vec<ObjectTransaction>> {
+        self.spawn_blocking(move |this| {
+            this.get_object_transaction_history(object_id, cursor, limit)
+        })
+        .await
+    }
+
+    async fn get_equivocation_reports(
+        &self,
+        senders: Vec<SuiAddress>,
+    ) -> IndexerResult<Vec<EquivocationReport>> {
+        self.spawn_blocking(move |this| this.get_equivocation_reports(senders))
+            .await
+    }
+
+    async fn get_owned_coins(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        cursor: ObjectID,
+        limit: usize,
+    ) -> IndexerResult<Vec<SuiCoin>> {
+        self.get_owned_coins_in_blocking_task(owner, coin_type, cursor, limit)
+            .await
+    }
+
+    async fn get_coin_balances(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+    ) -> IndexerResult<Vec<Balance>> {
+        self.get_coin_balances_in_blocking_task(owner, coin_type)
+            .await
+    }
+
+    async fn get_coin_metadata(
+        &self,
+        coin_struct: StructTag,
+    ) -> IndexerResult<Option<SuiCoinMetadata>> {
+        self.get_coin_metadata_in_blocking_task(coin_struct).await
+    }
+
+    async fn multi_get_coin_metadata(
+        &self,
+        coin_structs: Vec<StructTag>,
+    ) -> IndexerResult<Vec<Option<SuiCoinMetadata>>> {
+        self.multi_get_coin_metadata_in_blocking_task(coin_structs)
+            .await
+    }
+
+    async fn get_total_supply(&self, coin_struct: StructTag) -> IndexerResult<Supply> {
+        self.get_total_supply_in_blocking_task(coin_struct).await
+    }
+
+    async fn get_account_statement(
+        &self,
+        address: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<AccountStatementCursor>,
+        limit: usize,
+    ) -> IndexerResult<AccountStatement> {
+        self.spawn_blocking(move |this| {
+            this.get_account_statement(address, coin_type, cursor, limit)
+        })
+        .await
+    }
+
+    async fn get_balance_history(
+        &self,
+        address: SuiAddress,
+        coin_type: String,
+        interval_ms: i64,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+        limit: usize,
+    ) -> IndexerResult<Vec<BalanceSnapshot>> {
+        self.spawn_blocking(move |this| {
+            this.get_balance_history(address, coin_type, interval_ms, start_ms, end_ms, limit)
+        })
+        .await
+    }
+
+    async fn get_owned_nfts(
+        &self,
+        address: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> IndexerResult<Vec<ObjectRead>> {
+        self.spawn_blocking(move |this| this.get_owned_nfts(address, cursor, limit))
+            .await
+    }
+
+    async fn get_nft_transfers(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> IndexerResult<Option<(String, Option<ObjectRead>, Vec<NftTransfer>)>> {
+        self.spawn_blocking(move |this| this.get_nft_transfers(object_id, cursor, limit))
+            .await
+    }
+
+    async fn get_latest_network_metrics(&self) -> IndexerResult<NetworkMetrics> {
+        self.spawn_blocking(|this| this.get_latest_network_metrics())
+            .await
+    }
+
+    async fn get_latest_move_call_metrics(&self) -> IndexerResult<MoveCallMetrics> {
+        self.spawn_blocking(|this| this.get_latest_move_call_metrics())
+            .await
+    }
+
+    async fn get_gas_price_history(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<GasPriceStats>> {
+        self.spawn_blocking(move |this| this.get_gas_price_history(cursor, limit, descending_order))
+            .await
+    }
+
+    async fn get_validator_apy_history(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> IndexerResult<Vec<ValidatorApyHistory>> {
+        self.spawn_blocking(move |this| this.get_validator_apy_history(start_epoch, end_epoch))
+            .await
+    }
+
+    async fn get_validator_history(
+        &self,
+        address: SuiAddress,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> IndexerResult<Vec<ValidatorEpochHistory>> {
+        self.spawn_blocking(move |this| this.get_validator_history(address, start_epoch, end_epoch))
+            .await
+    }
+
+    async fn get_package_metrics(
+        &self,
+        package_id: ObjectID,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> IndexerResult<PackageMetrics> {
+        self.spawn_blocking(move |this| {
+            this.get_package_metrics(package_id, start_epoch, end_epoch)
+        })
+        .await
+    }
+
+    async fn get_latest_address_metrics(&self) -> IndexerResult<AddressMetrics> {
+        self.spawn_blocking(|this| this.get_latest_address_metrics())
+            .await
+    }
+
+    async fn get_checkpoint_address_metrics(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<AddressMetrics> {
+        self.spawn_blocking(move |this| this.get_checkpoint_address_metrics(checkpoint))
+            .await
+    }
+
+    async fn get_all_epoch_address_metrics(
+        &self,
+        descending_order: Option<bool>,
+    ) -> IndexerResult<Vec<AddressMetrics>> {
+        self.spawn_blocking(move |this| this.get_all_epoch_address_metrics(descending_order))
+            .await
+    }
+
+    async fn get_top_accounts(
+        &self,
+        rank_by: AccountRanking,
+        days: u64,
+        limit: usize,
+    ) -> IndexerResult<Vec<AccountActivity>> {
+        self.spawn_blocking(move |this| this.get_top_accounts(rank_by, days, limit))
+            .await
+    }
+
+    async fn get_active_address_counts(
+        &self,
+        start_day: i64,
+        end_day: i64,
+        limit: usize,
+    ) -> IndexerResult<Vec<ActiveAddressCounts>> {
+        self.spawn_blocking(move |this| this.get_active_address_counts(start_day, end_day, limit))
+            .await
+    }
+}
+
+#[derive(Clone, Default)]
+struct PackageCache {
+    inner: Arc<RwLock<BTreeMap<ObjectID, MovePackage>>>,
+}
+
+impl PackageCache {
+    fn insert(&self, object_id: ObjectID, package: MovePackage) {
+        self.inner.write().unwrap().insert(object_id, package);
+    }
+
+    fn get(&self, object_id: &ObjectID) -> Option<MovePackage> {
+        self.inner.read().unwrap().get(object_id).cloned()
+    }
+}
+
+impl move_core_types::resolver::ModuleResolver for IndexerReader {
+    type Error = IndexerError;
+
+    fn get_module(
+        &self,
+        id: &move_core_types::language_storage::ModuleId,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        let package_id = ObjectID::from(*id.address());
+        let module_name = id.name().to_string();
+        Ok(self
+            .get_package(&package_id)?
+            .and_then(|package| package.serialized_module_map().get(&module_name).cloned()))
+    }
+}
+
+impl sui_types::storage::ObjectStore for IndexerReader {
+    fn get_object(
+        &self,
+        object_id: &ObjectID,
+    ) -> Result<Option<sui_types::object::Object>, sui_types::storage::error::Error> {
+        self.get_object(object_id, None)
+            .map_err(sui_types::storage::error::Error::custom)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: sui_types::base_types::VersionNumber,
+    ) -> Result<Option<sui_types::object::Object>, sui_types::storage::error::Error> {
+        self.get_object(object_id, Some(version))
+            .map_err(sui_types::storage::error::Error::custom)
+    }
+}
+
+/// The objects as of the end of a checkpoint, for reading data that spans several objects, like
+/// the system state, as of that checkpoint.
+struct ObjectsAtCheckpoint<'a> {
+    reader: &'a IndexerReader,
+    checkpoint: CheckpointSequenceNumber,
+}
+
+impl sui_types::storage::ObjectStore for ObjectsAtCheckpoint<'_> {
+    fn get_object(
+        &self,
+        object_id: &ObjectID,
+    ) -> Result<Option<sui_types::object::Object>, sui_types::storage::error::Error> {
+        let object = self
+            .reader
+            .multi_get_objects_at_checkpoint(vec![*object_id], self.checkpoint)
+            .map_err(sui_types::storage::error::Error::custom)?
+            .pop();
+        Ok(match object {
+            Some(ObjectRead::Exists(_, object, _)) => Some(object),
+            _ => None,
+        })
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: sui_types::base_types::VersionNumber,
+    ) -> Result<Option<sui_types::object::Object>, sui_types::storage::error::Error> {
+        self.reader
+            .get_object(object_id, Some(version))
+            .map_err(sui_types::storage::error::Error::custom)
+    }
+}
+
+impl move_bytecode_utils::module_cache::GetModule for IndexerReader {
+    type Error = IndexerError;
+    type Item = move_binary_format::CompiledModule;
+
+    fn get_module_by_id(
+        &self,
+        id: &move_core_types::language_storage::ModuleId,
+    ) -> Result<Option<Self::Item>, Self::Error> {
+        let package_id = ObjectID::from(*id.address());
+        let module_name = id.name().to_string();
+        // TODO: we need a cache here for deserialized module and take care of package upgrades
+        self.get_package(&package_id)?
+            .and_then(|package| package.serialized_module_map().get(&module_name).cloned())
+            .map(|bytes| move_binary_format::CompiledModule::deserialize_with_defaults(&bytes))
+            .transpose()
+            .map_err(|e| {
+                IndexerError::ModuleResolutionError(format!(
+                    "Error deserializing module {}: {}",
+                    id, e
+                ))
+            })
+    }
+}
+
+#[cached(
+    type = "SizedCache<String, Option<ObjectID>>",
+    create = "{ SizedCache::with_size(10000) }",
+    convert = r#"{ format!("{}{}", package_id, obj_type) }"#,
+    result = true
+)]
+fn get_single_obj_id_from_package_publish(
+    reader: &IndexerReader,
+    package_id: ObjectID,
+    obj_type: String,
+) -> Result<Option<ObjectID>, IndexerError> {
+    let publish_txn_effects_opt = if is_system_package(package_id) {
+        Some(reader.get_transaction_effects_with_sequence_number(0))
+    } else {
+        reader.get_object(&package_id, None)?.map(|o| {
+            let publish_txn_digest = o.previous_transaction;
+            reader.get_transaction_effects_with_digest(publish_txn_digest)
+        })
+    };
+    if let Some(publish_txn_effects) = publish_txn_effects_opt {
+        let created_objs = publish_txn_effects?
+            .created()
+            .iter()
+            .map(|o| o.object_id())
+            .collect::<Vec<_>>();
+        let obj_ids_with_type =
+            reader.filter_object_id_with_type(created_objs, obj_type.clone())?;
+        if obj_ids_with_type.len() == 1 {
+            Ok(Some(obj_ids_with_type[0]))
+        } else if obj_ids_with_type.is_empty() {
+            // The package exists but no such object is created in that transaction. Or maybe it is wrapped and we don't know yet.
+            Ok(None)
+        } else {
+            // We expect there to be only one object of this type created by the package but more than one is found.
+            tracing::error!(
+                "There are more than one objects found for type {}",
+                obj_type
+            );
+            Ok(None)
+        }
+    } else {
+        // The coin package does not exist.
+        Ok(None)
+    }
+}
+
+/// The BCS of the name of dynamic field `object`, rendered from its serialized object for objects
+/// that were stored without their `df_name`. Each version of an object is immutable, so they are
+/// cached by it.
+#[cached(
+    type = "SizedCache<(Vec<u8>, i64), Vec<u8>>",
+    create = "{ SizedCache::with_size(10000) }",
+    convert = r#"{ (object.object_id.clone(), object.object_version) }"#,
+    result = true
+)]
+fn rendered_dynamic_field_name(
+    reader: &IndexerReader,
+    object: &StoredObject,
+) -> Result<Vec<u8>, IndexerError> {
+    let name = object.render_dynamic_field_name(reader)?;
+    Ok(bcs::to_bytes(&name)?)
+}
+
+/// The checkpoints that objects can be read at, from `objects_snapshot` overlaid with
+/// `objects_history`: from the checkpoint of the snapshot up to the latest one indexed.
+struct ConsistentReadRange {
+    snapshot_cp: Option<i64>,
+    latest_cp: Option<i64>,
+}
+
+impl ConsistentReadRange {
+    fn read(conn: &mut PgConnection) -> Result<Self, diesel::result::Error> {
+        let snapshot_cp = objects_snapshot::table
+            .select(objects_snapshot::checkpoint_sequence_number)
+            .order(objects_snapshot::checkpoint_sequence_number.desc())
+            .first::<i64>(conn)
+            .optional()?;
+        let latest_cp = checkpoints::table
+            .select(checkpoints::sequence_number)
+            .order(checkpoints::sequence_number.desc())
+            .first::<i64>(conn)
+            .optional()?;
+        Ok(Self {
+            snapshot_cp,
+            latest_cp,
+        })
+    }
+
+    fn contains(&self, checkpoint: i64) -> bool {
+        self.latest_cp.is_some_and(|latest| checkpoint <= latest)
+            && self
+                .snapshot_cp
+                .map_or(true, |snapshot| snapshot <= checkpoint)
+    }
+
+    /// The checkpoint that versions in `objects_history` must be after to be newer than the
+    /// snapshot's.
+    fn after_snapshot(&self) -> i64 {
+        self.snapshot_cp.unwrap_or(-1)
+    }
+
+    fn out_of_range(&self, checkpoint: i64) -> IndexerError {
+        IndexerError::InvalidArgumentError(format!(
+            "Checkpoint {checkpoint} is outside of the consistent read range, {}..={}",
+            self.snapshot_cp.unwrap_or_default(),
+            self.latest_cp.unwrap_or_default(),
+        ))
+    }
+}
+
+/// Query for the number and total balance of the coins of each type that `owner` owns, or only
+/// of `coin_type` if it is set.
+fn coin_balances_query(owner: SuiAddress, coin_type: Option<&str>) -> String {
+    let coin_type_filter = if let Some(coin_type) = coin_type {
+        format!("= '{}'", coin_type)
+    } else {
+        "IS NOT NULL".to_string()
+    };
+    // Note: important to cast to BIGINT to avoid deserialize confusion
+    format!(
+        "
+        SELECT coin_type, \
+        CAST(COUNT(*) AS BIGINT) AS coin_num, \
+        CAST(SUM(coin_balance) AS BIGINT) AS coin_balance \
+        FROM objects \
+        WHERE owner_type = {} \
+        AND owner_id = '\\x{}'::BYTEA \
+        AND coin_type {} \
+        GROUP BY coin_type \
+        ORDER BY coin_type ASC
+    ",
+        OwnerType::Address as i16,
+        Hex::encode(owner.to_vec()),
+        coin_type_filter,
+    )
+}
+
+/// The net changes to `owner`'s balance of `coin_type` in each bucket of `interval_ms` from
+/// `start_bucket` to `end_bucket` (inclusive) that it changed in, preceded by the sum of its
+/// changes before `start_bucket`, as the bucket right before it, ordered by bucket. Sums are
+/// saturated to fit into BIGINTs.
+fn balance_history_query(
+    owner: &[u8],
+    coin_type: &str,
+    interval_ms: i64,
+    start_bucket: i64,
+    end_bucket: i64,
+) -> String {
+    format!(
+        "SELECT
+            GREATEST(timestamp_ms / {interval_ms}, {opening_bucket}) AS bucket,
+            LEAST(GREATEST(SUM(amount), {min}), {max})::BIGINT AS change
+          FROM balance_changes
+          WHERE owner = '\\x{owner}'::BYTEA
+            AND coin_type = '{coin_type}'
+            AND timestamp_ms < {end_ms}
+          GROUP BY 1
+          ORDER BY 1 ASC",
+        opening_bucket = start_bucket - 1,
+        min = i64::MIN,
+        max = i64::MAX,
+        owner = Hex::encode(owner),
+        end_ms = end_bucket.saturating_add(1).saturating_mul(interval_ms),
+    )
+}
+
+/// The columns of `objects_snapshot` and `objects_history`.
+const HISTORY_OBJECT_COLUMNS: &str = "object_id, object_version, object_status, object_digest, \
+    checkpoint_sequence_number, owner_type, owner_id, object_type, serialized_object, coin_type, \
+    coin_balance, df_kind, df_name, df_object_type, df_object_id";
+
+/// Query for the live objects owned by `owner` as of the end of `checkpoint`, matching
+/// `filter_clause`, in order of their IDs, starting after `cursor`. The latest versions, up to
+/// `checkpoint`, of the objects that `owner` owned in the snapshot or at any version since, are
+/// read from `objects_snapshot` and the versions in `objects_history` after `snapshot_cp`, and
+/// those that `owner` still owns are kept.
+fn owned_objects_at_checkpoint_query(
+    owner: SuiAddress,
+    filter_clause: Option<&str>,
+    cursor: Option<ObjectID>,
+    limit: usize,
+    snapshot_cp: i64,
+    checkpoint: i64,
+) -> String {
+    let owned = format!(
+        "owner_type = {} AND owner_id = '\\x{}'::BYTEA",
+        OwnerType::Address as i16,
+        Hex::encode(owner.to_vec()),
+    );
+    let cursor = cursor.map_or(String::new(), |cursor| {
+        format!(
+            "AND object_id > '\\x{}'::BYTEA",
+            Hex::encode(cursor.to_vec())
+        )
+    });
+    let filter = filter_clause.map_or(String::new(), |clause| format!("AND {clause}"));
+    let history = format!(
+        "checkpoint_sequence_number > {snapshot_cp} AND checkpoint_sequence_number <= {checkpoint}"
+    );
+    format!(
+        "SELECT {HISTORY_OBJECT_COLUMNS} FROM (
+            SELECT DISTINCT ON (object_id) {HISTORY_OBJECT_COLUMNS} FROM (
+                SELECT {HISTORY_OBJECT_COLUMNS} FROM objects_snapshot
+                UNION ALL
+                SELECT {HISTORY_OBJECT_COLUMNS} FROM objects_history WHERE {history}
+            ) versions
+            WHERE object_id IN (
+                SELECT object_id FROM objects_snapshot WHERE {owned} {cursor}
+                UNION
+                SELECT object_id FROM objects_history WHERE {history} AND {owned} {cursor}
+            )
+            ORDER BY object_id, object_version DESC
+        ) latest
+        WHERE object_status = {active} AND {owned} {filter}
+        ORDER BY object_id ASC
+        LIMIT {limit}",
+        active = ObjectStatus::Active as i16,
+    )
+}
+
+/// A condition on the types of the objects matching `filter`, of the filters that owned objects
+/// can be queried with: struct types, and any or none of them.
+fn object_filter_clause(filter: &SuiObjectDataFilter) -> IndexerResult<String> {
+    fn type_prefix(filter: &SuiObjectDataFilter) -> IndexerResult<String> {
+        match filter {
+            SuiObjectDataFilter::StructType(struct_tag) => {
+                Ok(struct_tag.to_canonical_string(/* with_prefix */ true))
+            }
+            _ => Err(IndexerError::InvalidArgumentError(
+                "Invalid filter type. Only struct, MatchAny and MatchNone of struct filters are supported.".into(),
+            )),
+        }
+    }
+    fn join(
+        filters: &[SuiObjectDataFilter],
+        operator: &str,
+        condition: &str,
+        empty: &str,
+    ) -> IndexerResult<String> {
+        let clauses = filters
+            .iter()
+            .map(|filter| {
+                Ok(format!(
+                    "object_type {condition} '{}%'",
+                    type_prefix(filter)?
+                ))
+            })
+            .collect::<IndexerResult<Vec<_>>>()?;
+        if clauses.is_empty() {
+            return Ok(empty.to_string());
+        }
+        Ok(format!("({})", clauses.join(&format!(" {operator} "))))
+    }
+
+    match filter {
+        SuiObjectDataFilter::MatchAny(filters) => join(filters, "OR", "LIKE", "FALSE"),
+        SuiObjectDataFilter::MatchNone(filters) => join(filters, "AND", "NOT LIKE", "TRUE"),
+        filter => Ok(format!("object_type LIKE '{}%'", type_prefix(filter)?)),
+    }
+}
+
+/// A condition on the rows of `events` matching `filter`, compiling combinations of filters into
+/// the same combinations of their conditions, so that they are all evaluated by the database.
+fn event_filter_clause(filter: &EventFilter) -> IndexerResult<String> {
+    fn join<'a>(
+        filters: impl IntoIterator<Item = &'a EventFilter>,
+        operator: &str,
+        empty: &str,
+    ) -> IndexerResult<String> {
+        let clauses = filters
+            .into_iter()
+            .map(event_filter_clause)
+            .collect::<IndexerResult<Vec<_>>>()?;
+        if clauses.is_empty() {
+            return Ok(empty.to_string());
+        }
+        Ok(format!("({})", clauses.join(&format!(" {operator} "))))
+    }
+
+    Ok(match filter {
+        EventFilter::Sender(sender) => {
+            format!(
+                "senders @> ARRAY['\\x{}'::bytea]",
+                Hex::encode(sender.to_vec())
+            )
+        }
+        EventFilter::Transaction(tx_digest) => {
+            format!(
+                "{TX_SEQUENCE_NUMBER_STR} = (\
+                    SELECT tx_sequence_number FROM transactions \
+                    WHERE transaction_digest = '\\x{}'::bytea\
+                )",
+                Hex::encode(tx_digest.into_inner()),
+            )
+        }
+        EventFilter::Package(package_id) => {
+            format!("package = '\\x{}'::bytea", package_id.to_hex())
+        }
+        EventFilter::MoveModule { package, module } => {
+            format!(
+                "(package = '\\x{}'::bytea AND module = '{}')",
+                package.to_hex(),
+                module,
+            )
+        }
+        EventFilter::MoveEventType(struct_tag) => {
+            format!("event_type = '{}'", struct_tag)
+        }
+        EventFilter::MoveEventModule { package, module } => {
+            let package_module_prefix = format!("{}::{}", package.to_hex_literal(), module);
+            format!("event_type LIKE '{package_module_prefix}::%'")
+        }
+        EventFilter::TimeRange {
+            start_time,
+            end_time,
+        } => {
+            format!("(timestamp_ms >= {start_time} AND timestamp_ms < {end_time})")
+        }
+        EventFilter::And(left, right) => join([&**left, &**right], "AND", "TRUE")?,
+        EventFilter::Or(left, right) => join([&**left, &**right], "OR", "FALSE")?,
+        EventFilter::All(filters) => join(filters, "AND", "TRUE")?,
+        EventFilter::Any(filters) => join(filters, "OR", "FALSE")?,
+        EventFilter::MoveEventField { .. } => {
+            return Err(IndexerError::NotSupportedError(
+                "This type of EventFilter is not supported.".into(),
+            ));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use move_core_types::identifier::Identifier;
+
+    use super::*;
+
+    #[test]
+    fn test_event_filter_clause() {
+        let package = ObjectID::from_str("0xabc").unwrap();
+        let filter = EventFilter::And(
+            Box::new(EventFilter::Any(vec![
+                EventFilter::Package(package),
+                EventFilter::MoveEventModule {
+                    package,
+                    module: Identifier::new("pool").unwrap(),
+                },
+            ])),
+            Box::new(EventFilter::TimeRange {
+                start_time: 10,
+                end_time: 20,
+            }),
+        );
+        assert_eq!(
+            event_filter_clause(&filter).unwrap(),
+            format!(
+                "((package = '\\x{hex}'::bytea OR event_type LIKE '0xabc::pool::%') \
+                 AND (timestamp_ms >= 10 AND timestamp_ms < 20))",
+                hex = package.to_hex(),
+            ),
+        );
+
+        assert_eq!(
+            event_filter_clause(&EventFilter::All(vec![])).unwrap(),
+            "TRUE"
+        );
+        assert!(event_filter_clause(&EventFilter::Or(
+            Box::new(EventFilter::Package(package)),
+            Box::new(EventFilter::MoveEventField {
+                path: "/amount".to_string(),
+                value: serde_json::json!(1),
+            }),
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_object_filter_clause() {
+        let coin = StructTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+        let coin_type = coin.to_canonical_string(/* with_prefix */ true);
+        assert_eq!(
+            object_filter_clause(&SuiObjectDataFilter::StructType(coin.clone())).unwrap(),
+            format!("object_type LIKE '{coin_type}%'"),
+        );
+
+        let staked_sui = StructTag::from_str("0x3::staking_pool::StakedSui").unwrap();
+        let staked_sui_type = staked_sui.to_canonical_string(/* with_prefix */ true);
+        assert_eq!(
+            object_filter_clause(&SuiObjectDataFilter::MatchNone(vec![
+                SuiObjectDataFilter::StructType(coin.clone()),
+                SuiObjectDataFilter::StructType(staked_sui),
+            ]))
+            .unwrap(),
+            format!(
+                "(object_type NOT LIKE '{coin_type}%' AND object_type NOT LIKE '{staked_sui_type}%')"
+            ),
+        );
+
+        assert_eq!(
+            object_filter_clause(&SuiObjectDataFilter::MatchAny(vec![])).unwrap(),
+            "FALSE"
+        );
+        assert!(object_filter_clause(&SuiObjectDataFilter::MatchAny(vec![
+            SuiObjectDataFilter::StructType(coin),
+            SuiObjectDataFilter::Package(ObjectID::ZERO),
+        ]))
+        .is_err());
     }
 }
@@ -0,0 +1,255 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A registry of labels (names and optional categories) that the operator of an indexer associates
+//! with addresses, e.g. to identify exchanges and well-known contracts in explorers. Labels are
+//! joined into transaction and object responses when clients set `showLabels`.
+//!
+//! Labels are not derived from chain data: they are imported from a CSV file with the columns
+//! `address,name,category` when the reader starts, and can be maintained afterwards through a
+//! small admin API that only listens on localhost.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::Path;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path as UrlPath, State},
+    http::StatusCode,
+    routing::{delete, post, put},
+    Json, Router,
+};
+use diesel::{upsert::excluded, ExpressionMethods, RunQueryDsl};
+use serde::Deserialize;
+use tracing::info;
+
+use sui_json_rpc_types::{AddressLabel, AddressLabels};
+use sui_types::base_types::SuiAddress;
+
+use crate::db::PgConnectionPool;
+use crate::errors::{Context, IndexerError};
+use crate::models::address_labels::StoredAddressLabel;
+use crate::schema::address_labels;
+use crate::store::diesel_macro::transactional_blocking_with_retry;
+
+const MAX_WRITE_ELAPSED: Duration = Duration::from_secs(60);
+
+/// Writes to the `address_labels` table. The indexer's reader only holds read-only connections,
+/// so this needs its own (writable) pool.
+#[derive(Clone)]
+pub struct AddressLabelStore {
+    blocking_cp: PgConnectionPool,
+}
+
+impl AddressLabelStore {
+    pub fn new(blocking_cp: PgConnectionPool) -> Self {
+        Self { blocking_cp }
+    }
+
+    /// Add `labels`, replacing the labels of any addresses that were already labelled.
+    pub fn upsert(&self, labels: AddressLabels) -> Result<usize, IndexerError> {
+        let stored_labels = labels
+            .into_iter()
+            .map(|(address, label)| StoredAddressLabel::new(address, label))
+            .collect::<Vec<_>>();
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(address_labels::table)
+                    .values(&stored_labels)
+                    .on_conflict(address_labels::address)
+                    .do_update()
+                    .set((
+                        address_labels::name.eq(excluded(address_labels::name)),
+                        address_labels::category.eq(excluded(address_labels::category)),
+                    ))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write address labels to PostgresDB")
+            },
+            MAX_WRITE_ELAPSED
+        )
+    }
+
+    /// Remove the label of `address`, returning whether it had one.
+    pub fn remove(&self, address: SuiAddress) -> Result<bool, IndexerError> {
+        let deleted = transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::delete(address_labels::table)
+                    .filter(address_labels::address.eq(address.to_vec()))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to delete address label from PostgresDB")
+            },
+            MAX_WRITE_ELAPSED
+        )?;
+
+        Ok(deleted > 0)
+    }
+
+    /// Import the labels in the CSV file at `path` (see [`parse_labels_csv`]).
+    pub fn import_csv(&self, path: &Path) -> Result<usize, IndexerError> {
+        let file = std::fs::File::open(path).map_err(|e| {
+            IndexerError::GenericError(format!(
+                "Failed to open address labels file {}: {e}",
+                path.display()
+            ))
+        })?;
+        self.upsert(parse_labels_csv(file)?)
+    }
+}
+
+#[derive(Deserialize)]
+struct LabelRecord {
+    address: SuiAddress,
+    name: String,
+    #[serde(default)]
+    category: Option<String>,
+}
+
+/// Parse labels from CSV with a header row naming the columns `address`, `name` and (optionally)
+/// `category`. Empty categories are treated as missing, and if an address appears more than once,
+/// its last label wins.
+pub fn parse_labels_csv(reader: impl io::Read) -> Result<AddressLabels, IndexerError> {
+    let mut labels = AddressLabels::new();
+    for (i, record) in csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .comment(Some(b'#'))
+        .from_reader(reader)
+        .deserialize::<LabelRecord>()
+        .enumerate()
+    {
+        let record = record.map_err(|e| {
+            IndexerError::InvalidArgumentError(format!("Invalid address label #{}: {e}", i + 1))
+        })?;
+
+        labels.insert(
+            record.address,
+            AddressLabel {
+                name: record.name,
+                category: record.category.filter(|c| !c.is_empty()),
+            },
+        );
+    }
+
+    Ok(labels)
+}
+
+/// Builds the router serving the labels admin API:
+///
+/// - `PUT /labels` adds or replaces labels, given as a JSON object keyed by address.
+/// - `POST /labels/csv` does the same, given CSV in the format accepted by [`parse_labels_csv`].
+/// - `DELETE /labels/:address` removes an address's label.
+pub fn labels_admin_router(store: AddressLabelStore) -> Router {
+    Router::new()
+        .route("/labels", put(put_labels))
+        .route("/labels/csv", post(post_labels_csv))
+        .route("/labels/:address", delete(delete_label))
+        .with_state(store)
+}
+
+/// Serve the labels admin API on `port`. It is only bound to localhost, because it is
+/// unauthenticated.
+pub async fn start_labels_admin_server(
+    store: AddressLabelStore,
+    port: u16,
+) -> Result<tokio::task::JoinHandle<()>, IndexerError> {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+    let server = axum::Server::try_bind(&addr)
+        .map_err(|e| {
+            IndexerError::GenericError(format!("Failed to bind labels admin server: {e}"))
+        })?
+        .serve(labels_admin_router(store).into_make_service());
+
+    info!(
+        "Sui Indexer labels admin server listening on {}",
+        server.local_addr()
+    );
+    Ok(tokio::spawn(async move { server.await.unwrap() }))
+}
+
+type AdminResult<T> = Result<T, (StatusCode, String)>;
+
+fn admin_error(e: IndexerError) -> (StatusCode, String) {
+    match e {
+        IndexerError::InvalidArgumentError(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, IndexerError> + Send + 'static,
+) -> AdminResult<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(admin_error)
+}
+
+async fn put_labels(
+    State(store): State<AddressLabelStore>,
+    Json(labels): Json<AddressLabels>,
+) -> AdminResult<String> {
+    let count = run_blocking(move || store.upsert(labels)).await?;
+    Ok(format!("Labelled {count} addresses"))
+}
+
+async fn post_labels_csv(
+    State(store): State<AddressLabelStore>,
+    body: String,
+) -> AdminResult<String> {
+    let labels = parse_labels_csv(body.as_bytes()).map_err(admin_error)?;
+    let count = run_blocking(move || store.upsert(labels)).await?;
+    Ok(format!("Labelled {count} addresses"))
+}
+
+async fn delete_label(
+    State(store): State<AddressLabelStore>,
+    UrlPath(address): UrlPath<SuiAddress>,
+) -> AdminResult<StatusCode> {
+    if run_blocking(move || store.remove(address)).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((StatusCode::NOT_FOUND, format!("No label for {address}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_labels_csv() {
+        let a1 = SuiAddress::random_for_testing_only();
+        let a2 = SuiAddress::random_for_testing_only();
+        let csv = format!(
+            "address,name,category\n\
+             # comment\n\
+             {a1}, Exchange A , exchange\n\
+             {a2},Bridge,\n\
+             {a1},Exchange A (hot wallet),exchange\n"
+        );
+
+        let labels = parse_labels_csv(csv.as_bytes()).unwrap();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(
+            labels[&a1],
+            AddressLabel {
+                name: "Exchange A (hot wallet)".to_string(),
+                category: Some("exchange".to_string()),
+            }
+        );
+        assert_eq!(
+            labels[&a2],
+            AddressLabel {
+                name: "Bridge".to_string(),
+                category: None,
+            }
+        );
+
+        assert!(parse_labels_csv("address,name\nnot-an-address,Foo\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Leadership among writers sharing a database, so that of a primary and its standbys (e.g. in
+//! other regions), only one ingests checkpoints at a time.
+//!
+//! The leader holds a Postgres advisory lock, on a connection of its own, for as long as it
+//! writes. Standbys keep trying to take the lock, so they take over as soon as it is released,
+//! which Postgres does when the leader's connection closes, whether because the leader stopped or
+//! because the database stopped hearing from it. The leader checks on every interval that its
+//! session still holds the lock, and stops writing once it finds that it doesn't.
+//!
+//! Noticing takes the leader up to an interval, plus however long its writes already in flight
+//! take to finish, and a leader cut off from the database may not notice for longer still, so
+//! the old and new leaders can both be writing for a while. Commits are fenced instead: taking
+//! the lease advances its epoch, in the `writer_lease` table, and the writer's transactions, to
+//! every table, only commit if they find the epoch unchanged since their writer took the lease
+//! (see [`check_writer_lease`]). An old leader can't commit anything once a new one has taken
+//! over, so neither the checkpoint watermark that readers see nor the latest state of objects
+//! can be set back by its late writes.
+
+use std::future::Future;
+use std::time::Duration;
+
+use diesel::sql_types::{BigInt, Bool};
+use diesel::{Connection, PgConnection, QueryResult, QueryableByName, RunQueryDsl};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use crate::errors::IndexerError;
+
+/// Advisory lock key of the writer lease ("SUI_WRIT").
+const WRITER_LEASE_KEY: i64 = 0x5355_495f_5752_4954;
+
+/// The writer lease, held for as long as this is alive.
+pub struct WriterLease {
+    conn: PgConnection,
+    epoch: i64,
+}
+
+#[derive(QueryableByName)]
+struct Locked {
+    #[diesel(sql_type = Bool)]
+    locked: bool,
+}
+
+#[derive(QueryableByName)]
+struct Epoch {
+    #[diesel(sql_type = BigInt)]
+    epoch: i64,
+}
+
+/// Check, in the transaction on `conn`, that the writer lease is still at `epoch`, the epoch its
+/// writer took it at. The lease's row is locked until the transaction ends, so a writer taking
+/// the lease over waits for the transaction to commit before advancing the epoch.
+pub fn check_writer_lease(conn: &mut PgConnection, epoch: i64) -> Result<(), IndexerError> {
+    let current = diesel::sql_query("SELECT epoch FROM writer_lease FOR SHARE")
+        .get_result::<Epoch>(conn)
+        .map_err(|e| IndexerError::PostgresReadError(e.to_string()))?
+        .epoch;
+    if current != epoch {
+        return Err(IndexerError::WriterLeaseLost(format!(
+            "Writer lease was taken over at epoch {current}, after this writer took it at epoch \
+             {epoch}"
+        )));
+    }
+    Ok(())
+}
+
+/// A statement that checks what [`check_writer_lease`] does, for connections that aren't diesel's
+/// (e.g. the ones rows are copied over), failing if the lease is no longer at `epoch`.
+pub(crate) fn check_writer_lease_statement(epoch: i64) -> String {
+    format!(
+        "DO $$ DECLARE current BIGINT; BEGIN \
+         SELECT epoch INTO current FROM writer_lease FOR SHARE; \
+         IF current <> {epoch} THEN \
+         RAISE EXCEPTION 'Writer lease was taken over at epoch %, after this writer took it at \
+         epoch {epoch}', current; \
+         END IF; END $$"
+    )
+}
+
+/// Take the writer lease on `conn` if it is free, advancing its epoch, which is returned.
+fn try_lock(conn: &mut PgConnection) -> QueryResult<Option<i64>> {
+    let locked = diesel::sql_query("SELECT pg_try_advisory_lock($1) AS locked")
+        .bind::<BigInt, _>(WRITER_LEASE_KEY)
+        .get_result::<Locked>(conn)?
+        .locked;
+    if !locked {
+        return Ok(None);
+    }
+    let epoch = diesel::sql_query("UPDATE writer_lease SET epoch = epoch + 1 RETURNING epoch")
+        .get_result::<Epoch>(conn)?
+        .epoch;
+    Ok(Some(epoch))
+}
+
+/// Whether the session of `conn` holds the writer lease's lock. A bigint key is split into the
+/// lock's `classid` (its high half) and `objid` (its low half).
+fn holds_lock(conn: &mut PgConnection) -> QueryResult<bool> {
+    let held = diesel::sql_query(
+        "SELECT EXISTS (
+            SELECT 1 FROM pg_locks
+            WHERE locktype = 'advisory' AND pid = pg_backend_pid() AND granted
+              AND classid::bigint = ($1 >> 32) AND objid::bigint = ($1 & 4294967295)
+              AND objsubid = 1
+        ) AS locked",
+    )
+    .bind::<BigInt, _>(WRITER_LEASE_KEY)
+    .get_result::<Locked>(conn)?;
+    Ok(held.locked)
+}
+
+impl WriterLease {
+    /// Wait until the writer lease is free, and take it, trying again every `interval`. Returns
+    /// `None` if `cancel` is cancelled first.
+    pub async fn acquire(
+        db_url: &str,
+        interval: Duration,
+        cancel: &CancellationToken,
+    ) -> Result<Option<Self>, IndexerError> {
+        let db_url = db_url.to_string();
+        let mut conn = blocking(move || PgConnection::establish(&db_url))
+            .await
+            .map_err(|e| {
+                IndexerError::PgConnectionPoolInitError(format!(
+                    "Failed to connect to take the writer lease: {e}"
+                ))
+            })?;
+
+        let mut waiting = false;
+        loop {
+            let (returned, locked) = blocking(move || {
+                let locked = try_lock(&mut conn);
+                (conn, locked)
+            })
+            .await;
+            conn = returned;
+
+            match locked {
+                Ok(Some(epoch)) => {
+                    info!(epoch, "Took the writer lease");
+                    return Ok(Some(Self { conn, epoch }));
+                }
+                Ok(None) if !waiting => {
+                    info!("Writer lease is held by another writer, waiting as a standby");
+                    waiting = true;
+                }
+                Ok(None) => {}
+                Err(e) => return Err(IndexerError::PostgresReadError(e.to_string())),
+            }
+
+            tokio::select! {
+                _ = cancel.cancelled() => return Ok(None),
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+    }
+
+    /// The epoch the lease was taken at, which commits are fenced with (see
+    /// [`check_writer_lease`]).
+    pub fn epoch(&self) -> i64 {
+        self.epoch
+    }
+
+    /// Check every `interval` that the lease is still held, until `lost` is cancelled. If the
+    /// lease is lost, cancels `lost` and returns true.
+    async fn monitor(mut self, interval: Duration, lost: CancellationToken) -> bool {
+        loop {
+            tokio::select! {
+                _ = lost.cancelled() => return false,
+                _ = tokio::time::sleep(interval) => {}
+            }
+
+            // Check the session's own locks, rather than just that the connection is alive, as
+            // the connection may have been re-established without the lock (e.g. by a pooler in
+            // between). A check that hangs counts as the lock being lost.
+            let check = blocking(move || {
+                let held = holds_lock(&mut self.conn);
+                (self, held)
+            });
+            match tokio::time::timeout(interval, check).await {
+                Ok((lease, Ok(true))) => self = lease,
+                Ok((_, Ok(false))) => {
+                    error!("Lost the writer lease: its session no longer holds the lock");
+                    break;
+                }
+                Ok((_, Err(e))) => {
+                    error!("Lost the writer lease: {e}");
+                    break;
+                }
+                Err(_) => {
+                    error!("Lost the writer lease: timed out checking the connection holding it");
+                    break;
+                }
+            }
+        }
+
+        lost.cancel();
+        true
+    }
+}
+
+/// Run `writer` once this process holds the writer lease, waiting as a standby until then. The
+/// writer is passed the lease's epoch, to fence its commits with, and a token that is cancelled
+/// when `cancel` is, or when the lease is lost, in which case this returns an error once the
+/// writer has stopped, so the process can be restarted as a standby.
+pub async fn run_as_leader<F, Fut>(
+    db_url: &str,
+    interval: Duration,
+    cancel: CancellationToken,
+    writer: F,
+) -> Result<(), IndexerError>
+where
+    F: FnOnce(CancellationToken, i64) -> Fut,
+    Fut: Future<Output = Result<(), IndexerError>>,
+{
+    let Some(lease) = WriterLease::acquire(db_url, interval, &cancel).await? else {
+        return Ok(());
+    };
+
+    let epoch = lease.epoch();
+    let writer_cancel = cancel.child_token();
+    let monitor = tokio::spawn(lease.monitor(interval, writer_cancel.clone()));
+    let result = writer(writer_cancel.clone(), epoch).await;
+
+    // Stop monitoring, which releases the lease.
+    writer_cancel.cancel();
+    let lost = monitor.await.unwrap_or(true);
+    result?;
+
+    if lost {
+        return Err(IndexerError::GenericError(
+            "Stopped writing after losing the writer lease".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn blocking<T, F>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .expect("propagate any panics")
+}
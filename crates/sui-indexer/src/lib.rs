@@ -3,11 +3,13 @@
 #![recursion_limit = "256"]
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
-use metrics::IndexerMetrics;
+use metrics::{IndexerMetrics, MetricsExportFormat};
 use prometheus::Registry;
 use tokio::runtime::Handle;
 use tracing::warn;
@@ -16,24 +18,52 @@ use url::Url;
 use sui_json_rpc::ServerType;
 use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle};
 use sui_json_rpc_api::CLIENT_SDK_TYPE_HEADER;
+use sui_types::base_types::{ObjectID, SuiAddress};
 
 use crate::apis::{
     CoinReadApi, ExtendedApi, GovernanceReadApi, IndexerApi, MoveUtilsApi, ReadApi,
     TransactionBuilderApi, WriteApi,
 };
-use crate::indexer_reader::IndexerReader;
+use crate::compression::Compression;
+use crate::dry_run_pool::DryRunPool;
+use crate::dynamic_config::ServerControls;
+use crate::equivocation::EquivocationStore;
+use crate::export::ExportConfig;
+use crate::framework::fetcher::CheckpointFetcher;
+use crate::prices::PriceOracle;
+use crate::store::IndexerReadStore;
 use errors::IndexerError;
 
 pub mod apis;
+pub mod coin_metadata;
+pub mod compression;
 pub mod db;
+pub mod db_snapshot;
+pub mod dry_run_pool;
+pub mod dynamic_config;
+pub mod epoch_pools;
+pub mod equivocation;
 pub mod errors;
+pub mod export;
+#[cfg(feature = "fault_injection")]
+pub mod fault_injection;
 pub mod framework;
-mod handlers;
+pub mod grpc;
+pub mod handlers;
+pub mod historical;
+pub mod index_advisor;
 pub mod indexer;
 pub mod indexer_reader;
+pub mod labels;
+pub mod leader;
 pub mod metrics;
 pub mod models;
+pub mod prices;
 pub mod processors;
+pub mod pruner;
+pub mod query_watchdog;
+pub mod replicas;
+pub mod rest;
 pub mod schema;
 pub mod store;
 pub mod test_utils;
@@ -60,14 +90,233 @@ pub struct IndexerConfig {
     pub db_name: Option<String>,
     #[clap(long, default_value = "http://0.0.0.0:9000", global = true)]
     pub rpc_client_url: String,
+    /// Ingest checkpoints straight from the database of a fullnode on the same host, at this path
+    /// (its `db-path`), instead of over its REST API.
+    #[clap(long, requires = "fullnode_db_secondary_path")]
+    pub fullnode_db_path: Option<PathBuf>,
+    /// Where to keep the files of the secondary instance that the fullnode's database is read
+    /// through.
+    #[clap(long)]
+    pub fullnode_db_secondary_path: Option<PathBuf>,
+    /// Keep a copy of every checkpoint the writer ingests in this directory (e.g. on local disk
+    /// or NFS), written before the checkpoint is indexed. Checkpoints that are already in the
+    /// directory are read from it instead of downloaded, so a lost database can be re-ingested
+    /// from the copies (e.g. with `--reset-db`).
+    #[clap(long)]
+    pub checkpoint_archive_dir: Option<PathBuf>,
+    /// How many checkpoints the writer downloads at a time.
+    #[clap(long, default_value_t = CheckpointFetcher::CHECKPOINT_DOWNLOAD_CONCURRENCY)]
+    pub checkpoint_download_concurrency: usize,
+    /// Only download checkpoints up to this many past the latest one committed, holding back
+    /// downloads while the database falls behind instead of buffering checkpoints in memory.
+    /// Downloads are only bounded by the sizes of the queues between the fetcher, indexing and
+    /// commits if this is not set.
+    #[clap(long)]
+    pub max_uncommitted_checkpoints: Option<u64>,
+    /// How many bytes of checkpoints (as serialized) the writer can hold in memory between
+    /// downloading and committing them, pausing downloads while there are more. There is no
+    /// limit if this is not set.
+    #[clap(long)]
+    pub memory_budget_bytes: Option<u64>,
+    /// YAML file of how long to keep the rows of each table for (see [`pruner`]), pruned in the
+    /// background while the writer runs. Nothing is pruned if this is not set.
+    #[clap(long)]
+    pub retention_config: Option<PathBuf>,
+    /// Insert transactions, events and objects history with binary `COPY`s, which Postgres
+    /// ingests faster than `INSERT`s, and upsert objects by copying them into a staging table.
+    /// Chunks that can't be copied (e.g. because some of their rows are already in the database)
+    /// are inserted as usual.
+    #[clap(long)]
+    pub copy_inserts: bool,
+    /// Compress the BCS payloads of transactions and events (their raw transactions, effects and
+    /// events) before storing them. Rows are decompressed as they are read, whether or not they
+    /// were compressed, so this can be turned on or off at any time.
+    #[clap(long, value_enum)]
+    pub payload_compression: Option<Compression>,
+    /// Store objects without their parsed JSON (the names of dynamic fields), only their BCS. The
+    /// JSON is rendered from the BCS as it is read instead, which saves storage at the expense of
+    /// CPU on the read path. Objects stored either way can be read, so this can be turned on or
+    /// off at any time.
+    #[clap(long)]
+    pub skip_parsed_json: bool,
+    /// Only index transactions, events and objects related to this package: those of transactions
+    /// that call it, emit its events, or touch its objects. Can be given more than once, and
+    /// with `--scope-address`.
+    #[clap(long = "scope-package")]
+    pub scope_packages: Vec<ObjectID>,
+    /// Only index transactions, events and objects related to this address: those of
+    /// transactions it sends, or that touch its objects. Can be given more than once, and with
+    /// `--scope-package`.
+    #[clap(long = "scope-address")]
+    pub scope_addresses: Vec<SuiAddress>,
     #[clap(long, default_value = "0.0.0.0", global = true)]
     pub client_metric_host: String,
     #[clap(long, default_value = "9184", global = true)]
     pub client_metric_port: u16,
+    /// Address to serve metrics on as well as `client-metric-host`, e.g. `[::]:9184` to serve
+    /// IPv6 clients too. Can be given more than once.
+    #[clap(long = "client-metric-additional-address", global = true)]
+    pub client_metric_additional_addresses: Vec<SocketAddr>,
+    /// Endpoint to push metrics to as well, for indexers that can't be scraped, e.g. behind NAT
+    /// or in serverless environments: a Prometheus remote-write endpoint, or the base URL of a
+    /// Pushgateway, depending on `metrics-export-format`.
+    #[clap(long, global = true)]
+    pub metrics_export_url: Option<String>,
+    #[clap(long, value_enum, default_value = "remote-write", global = true)]
+    pub metrics_export_format: MetricsExportFormat,
+    #[clap(long, default_value = "60", global = true)]
+    pub metrics_export_interval_seconds: u64,
+    /// Header to send with each push, as `name=value`, e.g. `Authorization=Bearer ...`. Can be
+    /// given more than once.
+    #[clap(long = "metrics-export-header", value_parser = parse_key_value, global = true)]
+    pub metrics_export_headers: Vec<(String, String)>,
+    /// Only push metrics whose names start with this. Can be given more than once.
+    #[clap(long = "metrics-export-keep-metric", global = true)]
+    pub metrics_export_keep_metrics: Vec<String>,
+    /// Don't push metrics whose names start with this. Can be given more than once.
+    #[clap(long = "metrics-export-drop-metric", global = true)]
+    pub metrics_export_drop_metrics: Vec<String>,
+    /// Label to remove from every metric pushed. Can be given more than once.
+    #[clap(long = "metrics-export-drop-label", global = true)]
+    pub metrics_export_drop_labels: Vec<String>,
+    /// Label to add to every metric pushed, as `name=value`, e.g. to tell indexers pushing to the
+    /// same endpoint apart. For a Pushgateway, these are also the grouping key that pushes
+    /// replace each other under. Can be given more than once.
+    #[clap(long = "metrics-export-label", value_parser = parse_key_value, global = true)]
+    pub metrics_export_labels: Vec<(String, String)>,
     #[clap(long, default_value = "0.0.0.0", global = true)]
     pub rpc_server_url: String,
     #[clap(long, default_value = "9000", global = true)]
     pub rpc_server_port: u16,
+    /// Address to serve JSON-RPC on as well as `rpc-server-url`, e.g. `[::]:9000` to serve IPv6
+    /// clients too. Can be given more than once.
+    #[clap(long = "rpc-additional-address", global = true)]
+    pub rpc_additional_addresses: Vec<SocketAddr>,
+    /// Path of a Unix domain socket to serve JSON-RPC on as well, for services on the same host.
+    /// A socket left there by a previous run is replaced.
+    #[clap(long, global = true)]
+    pub rpc_unix_socket: Option<PathBuf>,
+    /// Permissions of the socket file at `rpc-unix-socket`, in octal. Clients need write
+    /// permission on it to connect.
+    #[clap(long, default_value = "660", value_parser = parse_file_mode, global = true)]
+    pub rpc_unix_socket_mode: u32,
+    /// Port to serve the REST facade over the indexer's read path on, alongside JSON-RPC. The
+    /// REST server is disabled if this is not set.
+    #[clap(long, global = true)]
+    pub rest_server_port: Option<u16>,
+    /// Port to serve the gRPC read API on, alongside JSON-RPC. The gRPC server is disabled if
+    /// this is not set.
+    #[clap(long, global = true)]
+    pub grpc_server_port: Option<u16>,
+    /// CSV file of labels to associate with addresses (with columns `address`, `name` and
+    /// `category`), imported when the reader starts.
+    #[clap(long, global = true)]
+    pub address_labels_csv: Option<PathBuf>,
+    /// Port on localhost to serve the admin API for maintaining address labels on. The admin API
+    /// is disabled if this is not set.
+    #[clap(long, global = true)]
+    pub labels_admin_port: Option<u16>,
+    /// YAML file of configuration that can be changed while the reader is running (see
+    /// [`dynamic_config`]), re-read on `SIGHUP`.
+    #[clap(long, global = true)]
+    pub dynamic_config: Option<PathBuf>,
+    /// Port on localhost to serve the admin API for reloading the dynamic config on. The admin
+    /// API is disabled if this is not set.
+    #[clap(long, global = true)]
+    pub config_admin_port: Option<u16>,
+    /// Record the owned-object equivocations that validators report when rejecting transactions
+    /// submitted through the reader, to serve them from `suix_getEquivocationReports`.
+    #[clap(long, global = true)]
+    pub record_equivocations: bool,
+    /// YAML file configuring the price oracles (see [`prices`]) to include fiat valuations of
+    /// balances, stakes and dry-run gas costs in responses with. Valuations are left out if this
+    /// is not set.
+    #[clap(long, global = true)]
+    pub price_oracle_config: Option<PathBuf>,
+    /// URL of a fullnode to forward dry runs and dev-inspects to, instead of the fullnode at
+    /// `rpc-client-url` (see [`dry_run_pool`]). Can be given more than once, to spread them over
+    /// several fullnodes.
+    #[clap(long = "dry-run-fullnode-url", global = true)]
+    pub dry_run_fullnode_urls: Vec<String>,
+    /// How many times a dry run or dev-inspect is retried on the next fullnode, when it fails in
+    /// a way that another fullnode may not.
+    #[clap(long, default_value = "2", global = true)]
+    pub dry_run_retries: usize,
+    /// Number of coin types to cache the metadata of, served by `suix_getCoinMetadata` and
+    /// `suix_multiGetCoinMetadata` (see [`coin_metadata`]). Metadata is not cached if this is 0.
+    #[clap(long, global = true, default_value_t = 10_000)]
+    pub coin_metadata_cache_size: usize,
+    /// Size of the connection pool that the reader serves RPC requests from. Defaults to
+    /// `DB_POOL_SIZE`.
+    #[clap(long, global = true)]
+    pub reader_pool_size: Option<u32>,
+    /// Size to shrink the reader's connection pool to while the writer in the same process
+    /// processes the end of an epoch (see [`epoch_pools`]). The pool is left as it is if this is
+    /// not set.
+    #[clap(long, global = true)]
+    pub epoch_reader_pool_size: Option<u32>,
+    /// URL of a logical replica of the database to serve reads pinned to a checkpoint from, once
+    /// it has applied the checkpoint (see [`replicas`]). Can be given more than once.
+    #[clap(long = "reader-replica-url", global = true)]
+    pub reader_replica_urls: Vec<String>,
+    /// Sample `pg_stat_statements` this often, in seconds, to report the reader's slow queries
+    /// and suggest indexes for them (see [`index_advisor`]). Off if not set.
+    #[clap(long, global = true)]
+    pub index_advisor_interval_secs: Option<u64>,
+    /// Queries taking at least this long on average, in milliseconds, are reported as slow by the
+    /// index advisor.
+    #[clap(long, default_value = "100", global = true)]
+    pub index_advisor_slow_query_ms: f64,
+    /// File to write each report of the index advisor to, as JSON.
+    #[clap(long, global = true)]
+    pub index_advisor_output: Option<PathBuf>,
+    /// Serve RPC requests on a runtime of their own, with this many worker threads, so that read
+    /// traffic can't hold up ingestion when the writer runs in the same process.
+    #[clap(long, global = true)]
+    pub reader_worker_threads: Option<usize>,
+    /// Serve transactions and checkpoints that are not in the database (e.g. because it only
+    /// holds recent data) from the key-value store at this URL, read over the same HTTP interface
+    /// as fullnodes read historical data through (see [`historical`]).
+    #[clap(long, global = true)]
+    pub historical_kv_url: Option<String>,
+    /// How long to wait, on shutdown, for in-flight RPC requests to complete before they are
+    /// dropped.
+    #[clap(long, default_value = "30", global = true)]
+    pub shutdown_grace_period_secs: u64,
+    /// Size of the connection pool that the writer commits checkpoints through, separate from the
+    /// reader's. Defaults to `DB_POOL_SIZE`.
+    #[clap(long)]
+    pub writer_pool_size: Option<u32>,
+    /// Size to grow the writer's connection pool to while it processes the end of an epoch (see
+    /// [`epoch_pools`]). The pool is left as it is if this is not set.
+    #[clap(long)]
+    pub epoch_writer_pool_size: Option<u32>,
+    /// How long pools keep their epoch sizes after the end of an epoch has been committed, while
+    /// the analytical work on the epoch and the reads of its new state settle down.
+    #[clap(long, default_value = "600", global = true)]
+    pub epoch_pool_hold_secs: u64,
+    /// Only run the writer while it holds the writer lease on the database (see [`leader`]), so
+    /// that of several writers sharing a database, one ingests while the others wait as standbys.
+    #[clap(long)]
+    pub writer_lease: bool,
+    /// How often the writer checks that it still holds the writer lease, and standbys try to take
+    /// it.
+    #[clap(long, default_value = "5")]
+    pub writer_lease_interval_secs: u64,
+    /// Stop the writer once it has ingested the last checkpoint of the current epoch, leaving the
+    /// database at an epoch boundary to export a snapshot of.
+    #[clap(long)]
+    pub stop_at_epoch_end: bool,
+    /// Export a snapshot of the database to this directory (see [`db_snapshot`]), and exit. The
+    /// writer must have stopped at the end of an epoch.
+    #[clap(long, conflicts_with = "import_snapshot")]
+    pub export_snapshot: Option<PathBuf>,
+    /// Import the snapshot in this directory into the empty database (e.g. with `--reset-db`),
+    /// and exit.
+    #[clap(long)]
+    pub import_snapshot: Option<PathBuf>,
+    #[clap(subcommand)]
+    pub command: Option<IndexerCommand>,
     #[clap(long)]
     pub reset_db: bool,
     #[clap(long)]
@@ -76,6 +325,30 @@ pub struct IndexerConfig {
     pub rpc_server_worker: bool,
     #[clap(long)]
     pub analytical_worker: bool,
+    /// How many days of per-address activity aggregates the analytical worker keeps, counting
+    /// back from the latest day it has aggregated. Weekly active addresses are undercounted if
+    /// fewer than 8 days are kept.
+    #[clap(long, default_value = "30", value_parser = clap::value_parser!(u64).range(1..))]
+    pub address_stats_retention_days: u64,
+}
+
+fn parse_file_mode(mode: &str) -> Result<u32, String> {
+    let digits = mode.strip_prefix("0o").unwrap_or(mode);
+    u32::from_str_radix(digits, 8).map_err(|e| format!("Invalid file mode {mode}: {e}"))
+}
+
+fn parse_key_value(pair: &str) -> Result<(String, String), String> {
+    let (key, value) = pair
+        .split_once('=')
+        .ok_or_else(|| format!("Expected name=value, got {pair}"))?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum IndexerCommand {
+    /// Export tables over a range of checkpoints to CSV or Parquet files (see [`export`]), and
+    /// exit.
+    Export(ExportConfig),
 }
 
 impl IndexerConfig {
@@ -117,36 +390,133 @@ impl Default for IndexerConfig {
             db_port: None,
             db_name: None,
             rpc_client_url: "http://127.0.0.1:9000".to_string(),
+            fullnode_db_path: None,
+            fullnode_db_secondary_path: None,
+            checkpoint_archive_dir: None,
+            checkpoint_download_concurrency: CheckpointFetcher::CHECKPOINT_DOWNLOAD_CONCURRENCY,
+            max_uncommitted_checkpoints: None,
+            memory_budget_bytes: None,
+            retention_config: None,
+            copy_inserts: false,
+            payload_compression: None,
+            skip_parsed_json: false,
+            scope_packages: vec![],
+            scope_addresses: vec![],
             client_metric_host: "0.0.0.0".to_string(),
             client_metric_port: 9184,
+            client_metric_additional_addresses: vec![],
+            metrics_export_url: None,
+            metrics_export_format: MetricsExportFormat::RemoteWrite,
+            metrics_export_interval_seconds: 60,
+            metrics_export_headers: vec![],
+            metrics_export_keep_metrics: vec![],
+            metrics_export_drop_metrics: vec![],
+            metrics_export_drop_labels: vec![],
+            metrics_export_labels: vec![],
             rpc_server_url: "0.0.0.0".to_string(),
             rpc_server_port: 9000,
+            rpc_additional_addresses: vec![],
+            rpc_unix_socket: None,
+            rpc_unix_socket_mode: 0o660,
+            rest_server_port: None,
+            grpc_server_port: None,
+            address_labels_csv: None,
+            labels_admin_port: None,
+            dynamic_config: None,
+            config_admin_port: None,
+            record_equivocations: false,
+            price_oracle_config: None,
+            dry_run_fullnode_urls: vec![],
+            dry_run_retries: 2,
+            coin_metadata_cache_size: 10_000,
+            reader_pool_size: None,
+            epoch_reader_pool_size: None,
+            reader_replica_urls: vec![],
+            index_advisor_interval_secs: None,
+            index_advisor_slow_query_ms: 100.0,
+            index_advisor_output: None,
+            reader_worker_threads: None,
+            historical_kv_url: None,
+            shutdown_grace_period_secs: 30,
+            writer_pool_size: None,
+            epoch_writer_pool_size: None,
+            epoch_pool_hold_secs: 600,
+            writer_lease: false,
+            writer_lease_interval_secs: 5,
+            stop_at_epoch_end: false,
+            export_snapshot: None,
+            import_snapshot: None,
+            command: None,
             reset_db: false,
             fullnode_sync_worker: true,
             rpc_server_worker: true,
             analytical_worker: false,
+            address_stats_retention_days: 30,
         }
     }
 }
 
-pub async fn build_json_rpc_server(
+pub async fn build_json_rpc_server<R: IndexerReadStore>(
     prometheus_registry: &Registry,
-    reader: IndexerReader,
+    reader: R,
     config: &IndexerConfig,
+    controls: ServerControls,
+    equivocations: Option<EquivocationStore>,
+    prices: Option<PriceOracle>,
     custom_runtime: Option<Handle>,
 ) -> Result<ServerHandle, IndexerError> {
     let mut builder = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
+    builder.set_checkpoint_watermark(Arc::new(reader.clone()));
+    builder.set_method_toggles(controls.method_toggles);
+    builder.set_method_aliases(controls.method_aliases);
+    builder.set_admission_control(controls.admission);
+    builder.set_staleness(controls.staleness);
+    builder.set_query_cache(controls.query_cache);
+    builder.set_redaction(controls.redaction);
+    builder.set_tenancy(controls.tenancy);
+    builder.set_http_caching(controls.http_caching);
     let http_client = crate::get_http_client(config.rpc_client_url.as_str())?;
+    let dry_run_pool = if config.dry_run_fullnode_urls.is_empty() {
+        None
+    } else {
+        Some(DryRunPool::new(
+            &config.dry_run_fullnode_urls,
+            config.dry_run_retries,
+        )?)
+    };
 
-    builder.register_module(WriteApi::new(http_client.clone()))?;
+    builder.register_module(WriteApi::new(
+        http_client.clone(),
+        reader.clone(),
+        equivocations,
+        prices.clone(),
+        dry_run_pool,
+    ))?;
     builder.register_module(IndexerApi::new(reader.clone()))?;
     builder.register_module(TransactionBuilderApi::new(reader.clone()))?;
     builder.register_module(MoveUtilsApi::new(reader.clone()))?;
-    builder.register_module(GovernanceReadApi::new(reader.clone()))?;
+    let governance_api = GovernanceReadApi::new(reader.clone()).with_prices(prices.clone());
+    governance_api.start_cache_warming();
+    builder.register_module(governance_api)?;
     builder.register_module(ReadApi::new(reader.clone()))?;
-    builder.register_module(CoinReadApi::new(reader.clone()))?;
+    builder.register_module(CoinReadApi::new(reader.clone()).with_prices(prices))?;
     builder.register_module(ExtendedApi::new(reader.clone()))?;
 
+    if let Some(path) = &config.rpc_unix_socket {
+        #[cfg(unix)]
+        builder.set_unix_socket(sui_json_rpc::unix_socket::UnixSocketConfig {
+            path: path.clone(),
+            mode: config.rpc_unix_socket_mode,
+        });
+        #[cfg(not(unix))]
+        return Err(IndexerError::InvalidArgumentError(format!(
+            "Can't serve on Unix socket {}: Unix sockets are not supported on this platform",
+            path.display()
+        )));
+    }
+
+    builder.set_additional_addresses(config.rpc_additional_addresses.clone());
+
     let default_socket_addr: SocketAddr = SocketAddr::new(
         // unwrap() here is safe b/c the address is a static config.
         config.rpc_server_url.as_str().parse().unwrap(),
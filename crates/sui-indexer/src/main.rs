@@ -1,22 +1,33 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
 use clap::Parser;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
-use sui_indexer::db::{get_pg_pool_connection, new_pg_connection_pool, reset_database};
+use sui_indexer::db::{
+    get_pg_pool_connection, new_pg_connection_pool, report_connection_pool, reset_database,
+};
+use sui_indexer::db_snapshot::{export_snapshot, import_snapshot};
+use sui_indexer::epoch_pools::EpochPoolController;
 use sui_indexer::errors::IndexerError;
+use sui_indexer::export::export_tables;
 use sui_indexer::indexer::Indexer;
-use sui_indexer::metrics::start_prometheus_server;
+use sui_indexer::leader::run_as_leader;
 use sui_indexer::metrics::IndexerMetrics;
+use sui_indexer::metrics::{start_metrics_export_task, start_prometheus_server};
 use sui_indexer::store::PgIndexerAnalyticalStore;
 use sui_indexer::store::PgIndexerStore;
-use sui_indexer::IndexerConfig;
+use sui_indexer::{IndexerCommand, IndexerConfig};
 
 #[tokio::main]
 async fn main() -> Result<(), IndexerError> {
     // NOTE: this is to print out tracing like info, warn & error.
-    let _guard = telemetry_subscribers::TelemetryConfig::new()
+    let (_guard, tracing_handle) = telemetry_subscribers::TelemetryConfig::new()
         .with_env()
         .init();
 
@@ -29,13 +40,20 @@ async fn main() -> Result<(), IndexerError> {
             e
         ))
     })?;
-    let blocking_cp = new_pg_connection_pool(&db_url, None).map_err(|e| {
-        error!(
-            "Failed creating Postgres connection pool with error {:?}",
+    if indexer_config.reset_db && indexer_config.writer_lease {
+        // A standby resetting the database would wipe it from under the leader.
+        return Err(IndexerError::InvalidArgumentError(
+            "--reset-db can't be used with --writer-lease".to_string(),
+        ));
+    }
+    let blocking_cp =
+        new_pg_connection_pool(&db_url, indexer_config.writer_pool_size).map_err(|e| {
+            error!(
+                "Failed creating Postgres connection pool with error {:?}",
+                e
+            );
             e
-        );
-        e
-    })?;
+        })?;
     if indexer_config.reset_db {
         let mut conn = get_pg_pool_connection(&blocking_cp).map_err(|e| {
             error!(
@@ -54,47 +72,127 @@ async fn main() -> Result<(), IndexerError> {
         })?;
     }
 
-    let (_registry_service, registry) = start_prometheus_server(
-        // NOTE: this parses the input host addr and port number for socket addr,
-        // so unwrap() is safe here.
-        format!(
-            "{}:{}",
-            indexer_config.client_metric_host, indexer_config.client_metric_port
-        )
-        .parse()
-        .unwrap(),
-        indexer_config.rpc_client_url.as_str(),
-    )?;
+    if let Some(IndexerCommand::Export(config)) = indexer_config.command.clone() {
+        let db_url = db_url.clone();
+        tokio::task::spawn_blocking(move || export_tables(&db_url, &config)).await??;
+        return Ok(());
+    } else if let Some(dir) = indexer_config.export_snapshot.clone() {
+        let db_url = db_url.clone();
+        tokio::task::spawn_blocking(move || export_snapshot(&db_url, &dir)).await??;
+        return Ok(());
+    } else if let Some(dir) = indexer_config.import_snapshot.clone() {
+        let db_url = db_url.clone();
+        tokio::task::spawn_blocking(move || import_snapshot(&db_url, &dir)).await??;
+        return Ok(());
+    }
+
+    let mut metrics_addresses = vec![SocketAddr::new(
+        // NOTE: this parses the input host addr, so unwrap() is safe here.
+        indexer_config.client_metric_host.parse().unwrap(),
+        indexer_config.client_metric_port,
+    )];
+    metrics_addresses.extend(&indexer_config.client_metric_additional_addresses);
+    let (registry_service, registry) =
+        start_prometheus_server(&metrics_addresses, indexer_config.rpc_client_url.as_str())?;
+    start_metrics_export_task(&indexer_config, registry_service)?;
     let indexer_metrics = IndexerMetrics::new(&registry);
     mysten_metrics::init_metrics(&registry);
 
-    let report_cp = blocking_cp.clone();
-    let report_metrics = indexer_metrics.clone();
+    tokio::spawn(report_connection_pool(
+        "writer",
+        blocking_cp.clone(),
+        indexer_metrics.clone(),
+    ));
+    let epoch_pools = EpochPoolController::new(
+        Duration::from_secs(indexer_config.epoch_pool_hold_secs),
+        indexer_metrics.clone(),
+    );
+
+    let cancel = CancellationToken::new();
+    let signal_cancel = cancel.clone();
     tokio::spawn(async move {
-        loop {
-            let cp_state = report_cp.state();
-            info!(
-                "DB connection pool size: {}, with idle conn: {}.",
-                cp_state.connections, cp_state.idle_connections
-            );
-            report_metrics
-                .db_conn_pool_size
-                .set(cp_state.connections as i64);
-            report_metrics
-                .idle_db_conn
-                .set(cp_state.idle_connections as i64);
-            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
-        }
+        wait_termination().await;
+        info!("Received shutdown signal, stopping the indexer...");
+        signal_cancel.cancel();
     });
 
-    if indexer_config.fullnode_sync_worker {
-        let store = PgIndexerStore::new(blocking_cp, indexer_metrics.clone());
-        return Indexer::start_writer(&indexer_config, store, indexer_metrics).await;
-    } else if indexer_config.rpc_server_worker {
-        return Indexer::start_reader(&indexer_config, &registry, db_url).await;
+    if indexer_config.fullnode_sync_worker || indexer_config.rpc_server_worker {
+        // The writer and the reader can run side by side, without contending for connections,
+        // as they each have a pool of their own.
+        let writer = async {
+            if !indexer_config.fullnode_sync_worker {
+                return Ok(());
+            }
+
+            if let Some(size) = indexer_config.epoch_writer_pool_size {
+                epoch_pools.manage("writer", blocking_cp.clone(), size);
+            }
+            let mut store = PgIndexerStore::new(blocking_cp, indexer_metrics.clone())
+                .with_epoch_pools(epoch_pools.clone());
+            if indexer_config.copy_inserts {
+                store = store.with_copy_inserts(&db_url)?;
+            }
+            if let Some(compression) = indexer_config.payload_compression {
+                store = store.with_payload_compression(compression);
+            }
+            if indexer_config.skip_parsed_json {
+                store = store.without_parsed_json();
+            }
+            if !indexer_config.writer_lease {
+                return Indexer::start_writer(
+                    &indexer_config,
+                    store,
+                    indexer_metrics.clone(),
+                    cancel.clone(),
+                )
+                .await;
+            }
+
+            let interval = Duration::from_secs(indexer_config.writer_lease_interval_secs);
+            run_as_leader(&db_url, interval, cancel.clone(), |cancel, epoch| {
+                let store = store.with_writer_lease(epoch);
+                Indexer::start_writer(&indexer_config, store, indexer_metrics.clone(), cancel)
+            })
+            .await
+        };
+        let reader = async {
+            if !indexer_config.rpc_server_worker {
+                return Ok(());
+            }
+
+            Indexer::start_reader(
+                &indexer_config,
+                &registry,
+                indexer_metrics.clone(),
+                db_url.clone(),
+                Some(Arc::new(tracing_handle)),
+                epoch_pools.clone(),
+                cancel.clone(),
+            )
+            .await
+        };
+        tokio::try_join!(writer, reader)?;
+        return Ok(());
     } else if indexer_config.analytical_worker {
         let store = PgIndexerAnalyticalStore::new(blocking_cp);
-        return Indexer::start_analytical_worker(store, indexer_metrics.clone()).await;
+        return Indexer::start_analytical_worker(&indexer_config, store, indexer_metrics.clone())
+            .await;
     }
     Ok(())
 }
+
+#[cfg(not(unix))]
+async fn wait_termination() {
+    tokio::signal::ctrl_c().await.unwrap();
+}
+
+#[cfg(unix)]
+async fn wait_termination() {
+    use tokio::signal::unix::*;
+
+    let mut sigterm = signal(SignalKind::terminate()).unwrap();
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = sigterm.recv() => {},
+    }
+}
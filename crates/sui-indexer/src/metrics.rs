@@ -3,22 +3,28 @@
 
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use axum::{extract::Extension, http::StatusCode, routing::get, Router};
 use prometheus::{
-    register_histogram_with_registry, register_int_counter_with_registry,
-    register_int_gauge_with_registry, Histogram, IntCounter, IntGauge,
+    register_histogram_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry,
+    register_int_gauge_with_registry, Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use prometheus::{Registry, TextEncoder};
 use regex::Regex;
 use tracing::{info, warn};
 
+use mysten_metrics::export::{self, Relabel};
 use mysten_metrics::RegistryService;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::IndexerConfig;
 
 const METRICS_ROUTE: &str = "/metrics";
 
 pub fn start_prometheus_server(
-    addr: SocketAddr,
+    addresses: &[SocketAddr],
     fn_url: &str,
 ) -> Result<(RegistryService, Registry), anyhow::Error> {
     let converted_fn_url = convert_url(fn_url);
@@ -39,13 +45,103 @@ pub fn start_prometheus_server(
         .route(METRICS_ROUTE, get(metrics))
         .layer(Extension(registry_service.clone()));
 
+    for listener in sui_json_rpc::listen::bind_all(addresses)? {
+        let server = axum::Server::from_tcp(listener)?.serve(app.clone().into_make_service());
+        tokio::spawn(async move { server.await.unwrap() });
+    }
+    Ok((registry_service, registry))
+}
+
+/// How metrics are pushed to `metrics-export-url`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum MetricsExportFormat {
+    /// The Prometheus remote-write protocol, as accepted by Prometheus, Mimir, Thanos and others.
+    RemoteWrite,
+    /// The text format, pushed to a Prometheus Pushgateway.
+    Pushgateway,
+}
+
+/// Starts a task that periodically pushes metrics to `metrics-export-url`, if it is set.
+pub fn start_metrics_export_task(
+    config: &IndexerConfig,
+    registry_service: RegistryService,
+) -> Result<(), anyhow::Error> {
+    let Some(url) = &config.metrics_export_url else {
+        return Ok(());
+    };
+    let mut url = reqwest::Url::parse(url)?;
+    let default_headers = match config.metrics_export_format {
+        MetricsExportFormat::RemoteWrite => &export::REMOTE_WRITE_HEADERS[..],
+        MetricsExportFormat::Pushgateway => {
+            // Pushes replace the metrics previously pushed under the same job and labels.
+            let mut segments = url
+                .path_segments_mut()
+                .map_err(|_| anyhow::anyhow!("Metrics export URL can't be a base"))?;
+            segments
+                .pop_if_empty()
+                .extend(["metrics", "job", "sui-indexer"]);
+            for (name, value) in &config.metrics_export_labels {
+                segments.extend([name, value]);
+            }
+            &export::PUSHGATEWAY_HEADERS[..]
+        }
+    };
+    let mut headers = HeaderMap::new();
+    let config_headers = config
+        .metrics_export_headers
+        .iter()
+        .map(|(n, v)| (n.as_str(), v.as_str()));
+    for (name, value) in default_headers.iter().copied().chain(config_headers) {
+        headers.insert(
+            HeaderName::from_bytes(name.as_bytes())?,
+            HeaderValue::from_str(value)?,
+        );
+    }
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()?;
+
+    let relabel = Relabel {
+        keep_metrics: config.metrics_export_keep_metrics.clone(),
+        drop_metrics: config.metrics_export_drop_metrics.clone(),
+        drop_labels: config.metrics_export_drop_labels.clone(),
+        external_labels: config.metrics_export_labels.clone(),
+    };
+    let format = config.metrics_export_format;
+    let interval = Duration::from_secs(config.metrics_export_interval_seconds);
+    info!(export_url =% url, interval =? interval, "Starting metrics export");
+
     tokio::spawn(async move {
-        axum::Server::bind(&addr)
-            .serve(app.into_make_service())
-            .await
-            .unwrap();
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+
+            let families = relabel.apply(registry_service.gather_all());
+            let request = match format {
+                MetricsExportFormat::RemoteWrite => {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis() as i64;
+                    client
+                        .post(url.clone())
+                        .body(export::remote_write_body(&families, now))
+                }
+                MetricsExportFormat::Pushgateway => match export::pushgateway_body(&families) {
+                    Ok(body) => client.put(url.clone()).body(body),
+                    Err(e) => {
+                        warn!("Failed to encode metrics for export: {e}");
+                        continue;
+                    }
+                },
+            };
+            if let Err(e) = request.send().await.and_then(|r| r.error_for_status()) {
+                warn!("Failed to export metrics to {url}: {e}");
+            }
+        }
     });
-    Ok((registry_service, registry))
+    Ok(())
 }
 
 async fn metrics(Extension(registry_service): Extension<RegistryService>) -> (StatusCode, String) {
@@ -93,13 +189,26 @@ pub struct IndexerMetrics {
     pub latest_tx_checkpoint_sequence_number: IntGauge,
     pub latest_indexer_object_checkpoint_sequence_number: IntGauge,
     pub latest_object_snapshot_sequence_number: IntGauge,
+    // set when ingestion halts on a checkpoint that does not extend the indexed chain
+    pub checkpoint_fork_detected: IntGauge,
     // analytical
     pub latest_move_call_metrics_tx_seq: IntGauge,
     pub latest_address_metrics_tx_seq: IntGauge,
+    pub latest_address_daily_stats_tx_seq: IntGauge,
+    pub latest_balance_changes_tx_seq: IntGauge,
     pub latest_network_metrics_cp_seq: IntGauge,
+    pub latest_gas_price_stats_cp_seq: IntGauge,
+    pub latest_validator_apys_epoch: IntGauge,
+    pub latest_validator_history_epoch: IntGauge,
     // checkpoint E2E latency is:
     // fullnode_download_latency + checkpoint_index_latency + db_commit_latency
     pub checkpoint_download_bytes_size: IntGauge,
+    pub checkpoints_read_from_archive: IntCounter,
+    pub checkpoint_downloads_throttled: IntCounter,
+    pub checkpoint_downloads_paused_for_memory: IntCounter,
+    pub memory_budget_bytes: IntGauge,
+    pub memory_budget_used_bytes: IntGaugeVec,
+    pub copy_insert_fallbacks: IntCounter,
     pub fullnode_checkpoint_data_download_latency: Histogram,
     pub fullnode_checkpoint_wait_and_download_latency: Histogram,
     pub fullnode_transaction_download_latency: Histogram,
@@ -165,8 +274,20 @@ pub struct IndexerMetrics {
     pub get_dynamic_field_object_latency: Histogram,
     pub get_protocol_config_latency: Histogram,
     // indexer state metrics
-    pub db_conn_pool_size: IntGauge,
-    pub idle_db_conn: IntGauge,
+    // by pool: the writer's and the reader's pools are sized separately
+    pub db_conn_pool_size: IntGaugeVec,
+    pub idle_db_conn: IntGaugeVec,
+    pub db_conn_pool_max_size: IntGaugeVec,
+    pub db_conn_in_use: IntGaugeVec,
+    // by replica, numbered in the order they are configured
+    pub replica_applied_checkpoint: IntGaugeVec,
+    pub replica_apply_lag: IntGaugeVec,
+    pub pruned_rows: IntCounterVec,
+    pub pruned_checkpoint: IntGaugeVec,
+    pub coin_metadata_cache_hits: IntCounter,
+    pub coin_metadata_cache_misses: IntCounter,
+    pub coin_metadata_cache_invalidations: IntCounter,
+    pub query_watchdog_cancellations: IntCounterVec,
 
     pub address_processor_failure: IntCounter,
     pub checkpoint_metrics_processor_failure: IntCounter,
@@ -246,6 +367,11 @@ impl IndexerMetrics {
                 "Latest object snapshot sequence number from the Indexer",
                 registry,
             ).unwrap(),
+            checkpoint_fork_detected: register_int_gauge_with_registry!(
+                "checkpoint_fork_detected",
+                "Set to 1 when ingestion halts on a checkpoint that does not extend the indexed chain",
+                registry,
+            ).unwrap(),
             latest_move_call_metrics_tx_seq: register_int_gauge_with_registry!(
                 "latest_move_call_metrics_tx_seq",
                 "Latest move call metrics tx seq",
@@ -256,16 +382,72 @@ impl IndexerMetrics {
                 "Latest address metrics tx seq",
                 registry,
             ).unwrap(),
+            latest_address_daily_stats_tx_seq: register_int_gauge_with_registry!(
+                "latest_address_daily_stats_tx_seq",
+                "Latest address daily stats tx seq",
+                registry,
+            ).unwrap(),
+            latest_balance_changes_tx_seq: register_int_gauge_with_registry!(
+                "latest_balance_changes_tx_seq",
+                "Latest balance changes tx seq",
+                registry,
+            ).unwrap(),
             latest_network_metrics_cp_seq: register_int_gauge_with_registry!(
                 "latest_network_metrics_cp_seq",
                 "Latest network metrics cp seq",
                 registry,
             ).unwrap(),
+            latest_gas_price_stats_cp_seq: register_int_gauge_with_registry!(
+                "latest_gas_price_stats_cp_seq",
+                "Latest gas price stats cp seq",
+                registry,
+            ).unwrap(),
+            latest_validator_apys_epoch: register_int_gauge_with_registry!(
+                "latest_validator_apys_epoch",
+                "Latest epoch with validator apys",
+                registry,
+            ).unwrap(),
+            latest_validator_history_epoch: register_int_gauge_with_registry!(
+                "latest_validator_history_epoch",
+                "Latest epoch with validator history",
+                registry,
+            ).unwrap(),
             checkpoint_download_bytes_size: register_int_gauge_with_registry!(
                 "checkpoint_download_bytes_size",
                 "Size of the downloaded checkpoint in bytes",
                 registry,
             ).unwrap(),
+            checkpoints_read_from_archive: register_int_counter_with_registry!(
+                "checkpoints_read_from_archive",
+                "Number of checkpoints read from the local checkpoint archive instead of downloaded",
+                registry,
+            ).unwrap(),
+            checkpoint_downloads_throttled: register_int_counter_with_registry!(
+                "checkpoint_downloads_throttled",
+                "Number of checkpoint downloads held back until earlier checkpoints were committed",
+                registry,
+            ).unwrap(),
+            checkpoint_downloads_paused_for_memory: register_int_counter_with_registry!(
+                "checkpoint_downloads_paused_for_memory",
+                "Number of checkpoint downloads held back until the checkpoints in memory fit the memory budget",
+                registry,
+            ).unwrap(),
+            memory_budget_bytes: register_int_gauge_with_registry!(
+                "memory_budget_bytes",
+                "Bytes of checkpoints the ingestion pipeline can hold in memory before pausing downloads",
+                registry,
+            ).unwrap(),
+            memory_budget_used_bytes: register_int_gauge_vec_with_registry!(
+                "memory_budget_used_bytes",
+                "Bytes of checkpoints held in memory by each stage of the ingestion pipeline, and in total",
+                &["stage"],
+                registry,
+            ).unwrap(),
+            copy_insert_fallbacks: register_int_counter_with_registry!(
+                "copy_insert_fallbacks",
+                "Number of chunks that failed to be copied into the DB, and were inserted instead",
+                registry,
+            ).unwrap(),
             fullnode_checkpoint_data_download_latency: register_histogram_with_registry!(
                 "fullnode_checkpoint_data_download_latency",
                 "Time spent in downloading checkpoint and transation for a new checkpoint from the Full Node",
@@ -659,16 +841,75 @@ impl IndexerMetrics {
                 registry
             )
             .unwrap(),
-            db_conn_pool_size: register_int_gauge_with_registry!(
+            db_conn_pool_size: register_int_gauge_vec_with_registry!(
                 "db_conn_pool_size",
                 "Size of the database connection pool",
+                &["pool"],
                 registry
             ).unwrap(),
-            idle_db_conn: register_int_gauge_with_registry!(
+            idle_db_conn: register_int_gauge_vec_with_registry!(
                 "idle_db_conn",
                 "Number of idle database connections",
+                &["pool"],
+                registry
+            ).unwrap(),
+            db_conn_pool_max_size: register_int_gauge_vec_with_registry!(
+                "db_conn_pool_max_size",
+                "Most connections the database connection pool can hold",
+                &["pool"],
+                registry
+            ).unwrap(),
+            db_conn_in_use: register_int_gauge_vec_with_registry!(
+                "db_conn_in_use",
+                "Number of database connections checked out of the pool",
+                &["pool"],
+                registry
+            ).unwrap(),
+            replica_applied_checkpoint: register_int_gauge_vec_with_registry!(
+                "replica_applied_checkpoint",
+                "Latest checkpoint applied by the database replica",
+                &["replica"],
                 registry
             ).unwrap(),
+            replica_apply_lag: register_int_gauge_vec_with_registry!(
+                "replica_apply_lag",
+                "Number of checkpoints the database replica trails the primary by",
+                &["replica"],
+                registry
+            ).unwrap(),
+            pruned_rows: register_int_counter_vec_with_registry!(
+                "pruned_rows",
+                "Number of rows pruned from the table (or group of tables), estimated for dropped partitions",
+                &["table"],
+                registry
+            ).unwrap(),
+            pruned_checkpoint: register_int_gauge_vec_with_registry!(
+                "pruned_checkpoint",
+                "First checkpoint kept by the table (or group of tables) as of the last time it was pruned",
+                &["table"],
+                registry
+            ).unwrap(),
+            coin_metadata_cache_hits: register_int_counter_with_registry!(
+                "coin_metadata_cache_hits",
+                "Number of coin metadata lookups served from the reader's cache",
+                registry,
+            ).unwrap(),
+            coin_metadata_cache_misses: register_int_counter_with_registry!(
+                "coin_metadata_cache_misses",
+                "Number of coin metadata lookups not in the reader's cache, and read from the database",
+                registry,
+            ).unwrap(),
+            coin_metadata_cache_invalidations: register_int_counter_with_registry!(
+                "coin_metadata_cache_invalidations",
+                "Number of coin metadata evicted from the reader's cache because their package was upgraded",
+                registry,
+            ).unwrap(),
+            query_watchdog_cancellations: register_int_counter_vec_with_registry!(
+                "query_watchdog_cancellations",
+                "Number of the reader's queries cancelled for running past the deadline of the method they were run for",
+                &["method"],
+                registry,
+            ).unwrap(),
             address_processor_failure: register_int_counter_with_registry!(
                 "address_processor_failure",
                 "Total number of address processor failure",
@@ -0,0 +1,205 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use diesel::prelude::*;
+
+use sui_json_rpc_types::{AccountStatementEntry, BalanceChange, StatementBalanceChange};
+use sui_types::base_types::SuiAddress;
+use sui_types::digests::TransactionDigest;
+use sui_types::object::Owner;
+
+use crate::errors::IndexerError;
+
+/// The columns of a transaction needed for a line of an account statement.
+#[derive(Clone, Debug, Queryable)]
+pub struct StoredStatementTransaction {
+    pub tx_sequence_number: i64,
+    pub transaction_digest: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+    pub timestamp_ms: i64,
+    pub balance_changes: Vec<Option<Vec<u8>>>,
+}
+
+/// Turn `txs`, in execution order, into the entries of `address`' account statement, adding
+/// their changes to the running `balances`. Only changes to coins of `coin_type` (a canonical
+/// type string) are included if it is set, and transactions that changed none of the address'
+/// included balances are left out.
+pub fn account_statement_entries(
+    address: SuiAddress,
+    coin_type: Option<&str>,
+    txs: Vec<StoredStatementTransaction>,
+    balances: &mut BTreeMap<String, i128>,
+) -> Result<Vec<AccountStatementEntry>, IndexerError> {
+    let mut entries = vec![];
+    for tx in txs {
+        // A transaction has at most one balance change per owner and coin type, but they are
+        // summed anyway, so that the running balances stay correct if that ever changes.
+        let mut amounts: BTreeMap<String, i128> = BTreeMap::new();
+        for balance_change in tx.balance_changes.iter().flatten() {
+            let balance_change: BalanceChange =
+                bcs::from_bytes(balance_change).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Can't convert balance_change bytes into BalanceChange. tx_sequence_number: {:?} Error: {e}",
+                        tx.tx_sequence_number
+                    ))
+                })?;
+            if balance_change.owner != Owner::AddressOwner(address) {
+                continue;
+            }
+            let change_type = balance_change
+                .coin_type
+                .to_canonical_string(/* with_prefix */ true);
+            if coin_type.is_some_and(|t| t != change_type) {
+                continue;
+            }
+            *amounts.entry(change_type).or_default() += balance_change.amount;
+        }
+
+        let changes: Vec<_> = amounts
+            .into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .map(|(coin_type, amount)| {
+                let balance = balances.entry(coin_type.clone()).or_default();
+                *balance += amount;
+                StatementBalanceChange {
+                    coin_type,
+                    amount,
+                    balance: *balance,
+                }
+            })
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+
+        let digest =
+            TransactionDigest::try_from(tx.transaction_digest.as_slice()).map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Can't convert {:?} as tx_digest. Error: {e}",
+                    tx.transaction_digest
+                ))
+            })?;
+        entries.push(AccountStatementEntry {
+            digest,
+            checkpoint: tx.checkpoint_sequence_number as u64,
+            timestamp_ms: tx.timestamp_ms as u64,
+            changes,
+        });
+    }
+    Ok(entries)
+}
+
+/// Whether the running `balances` match the `current` balances of the coins the address owns,
+/// treating missing coin types as zero balances.
+pub fn balances_reconcile(
+    balances: &BTreeMap<String, i128>,
+    current: &BTreeMap<String, i128>,
+) -> bool {
+    balances
+        .keys()
+        .chain(current.keys())
+        .all(|t| balances.get(t).copied().unwrap_or(0) == current.get(t).copied().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::language_storage::TypeTag;
+    use sui_types::gas_coin::GAS;
+
+    fn balance_change(owner: SuiAddress, coin_type: TypeTag, amount: i128) -> Option<Vec<u8>> {
+        let change = BalanceChange {
+            owner: Owner::AddressOwner(owner),
+            coin_type,
+            amount,
+        };
+        Some(bcs::to_bytes(&change).unwrap())
+    }
+
+    fn tx(
+        tx_sequence_number: i64,
+        balance_changes: Vec<Option<Vec<u8>>>,
+    ) -> StoredStatementTransaction {
+        StoredStatementTransaction {
+            tx_sequence_number,
+            transaction_digest: TransactionDigest::random().inner().to_vec(),
+            checkpoint_sequence_number: tx_sequence_number,
+            timestamp_ms: tx_sequence_number * 1000,
+            balance_changes,
+        }
+    }
+
+    #[test]
+    fn test_account_statement_entries() {
+        let (alice, bob) = (
+            SuiAddress::random_for_testing_only(),
+            SuiAddress::random_for_testing_only(),
+        );
+        let sui = GAS::type_tag().to_canonical_string(true);
+        let usdc: TypeTag = "0x2::coin::USDC".parse().unwrap();
+        let txs = vec![
+            tx(
+                1,
+                vec![
+                    balance_change(bob, GAS::type_tag(), -150),
+                    balance_change(alice, GAS::type_tag(), 100),
+                ],
+            ),
+            // Only changed bob's balances.
+            tx(2, vec![balance_change(bob, usdc.clone(), -5)]),
+            tx(
+                3,
+                vec![
+                    balance_change(alice, GAS::type_tag(), -10),
+                    balance_change(alice, usdc.clone(), 7),
+                ],
+            ),
+        ];
+
+        let mut balances = BTreeMap::from([(sui.clone(), 50)]);
+        let entries = account_statement_entries(alice, None, txs.clone(), &mut balances).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].checkpoint, 1);
+        assert_eq!(
+            entries[0].changes,
+            vec![StatementBalanceChange {
+                coin_type: sui.clone(),
+                amount: 100,
+                balance: 150,
+            }]
+        );
+        let usdc = usdc.to_canonical_string(true);
+        assert_eq!(
+            entries[1].changes,
+            vec![
+                StatementBalanceChange {
+                    coin_type: usdc.clone(),
+                    amount: 7,
+                    balance: 7,
+                },
+                StatementBalanceChange {
+                    coin_type: sui.clone(),
+                    amount: -10,
+                    balance: 140,
+                },
+            ]
+        );
+        assert!(balances_reconcile(
+            &balances,
+            &BTreeMap::from([(sui.clone(), 140), (usdc.clone(), 7)])
+        ));
+        assert!(!balances_reconcile(
+            &balances,
+            &BTreeMap::from([(sui.clone(), 140)])
+        ));
+
+        // Restricted to one coin type, only transactions changing it are entries.
+        let mut balances = BTreeMap::new();
+        let entries =
+            account_statement_entries(alice, Some(usdc.as_str()), txs, &mut balances).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(balances, BTreeMap::from([(usdc, 7)]));
+    }
+}
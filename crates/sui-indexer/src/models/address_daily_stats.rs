@@ -0,0 +1,259 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashMap};
+
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bytea};
+
+use sui_json_rpc_types::{AccountActivity, ActiveAddressCounts, BalanceChange};
+use sui_types::base_types::SuiAddress;
+use sui_types::gas_coin::GAS;
+use sui_types::object::Owner;
+
+use crate::errors::IndexerError;
+use crate::models::transactions::StoredTransactionBalanceChanges;
+use crate::models::tx_indices::{StoredTxRecipients, StoredTxSenders};
+use crate::schema::{active_address_counts, address_daily_stats};
+
+pub const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = address_daily_stats)]
+pub struct StoredAddressDailyStats {
+    pub address: Vec<u8>,
+    pub day: i64,
+    pub sent_tx_count: i64,
+    pub received_tx_count: i64,
+    pub sui_sent: i64,
+    pub sui_received: i64,
+    pub last_tx_sequence_number: i64,
+}
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = active_address_counts)]
+pub struct StoredActiveAddressCounts {
+    pub day: i64,
+    pub daily_active_addresses: i64,
+    pub weekly_active_addresses: i64,
+}
+
+impl From<StoredActiveAddressCounts> for ActiveAddressCounts {
+    fn from(counts: StoredActiveAddressCounts) -> Self {
+        Self {
+            day_start_ms: (counts.day * MS_PER_DAY) as u64,
+            daily_active_addresses: counts.daily_active_addresses as u64,
+            weekly_active_addresses: counts.weekly_active_addresses as u64,
+        }
+    }
+}
+
+/// An address' activity summed over days, as queried for leaderboards.
+#[derive(QueryableByName, Debug)]
+pub struct QueriedAccountActivity {
+    #[diesel(sql_type = Bytea)]
+    pub address: Vec<u8>,
+    #[diesel(sql_type = BigInt)]
+    pub sent_tx_count: i64,
+    #[diesel(sql_type = BigInt)]
+    pub received_tx_count: i64,
+    #[diesel(sql_type = BigInt)]
+    pub sui_sent: i64,
+    #[diesel(sql_type = BigInt)]
+    pub sui_received: i64,
+}
+
+impl TryFrom<QueriedAccountActivity> for AccountActivity {
+    type Error = IndexerError;
+
+    fn try_from(queried: QueriedAccountActivity) -> Result<Self, Self::Error> {
+        let address = SuiAddress::from_bytes(&queried.address).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to parse address {:?} of daily stats: {e}",
+                queried.address
+            ))
+        })?;
+
+        Ok(AccountActivity {
+            address,
+            transactions_sent: queried.sent_tx_count as u64,
+            transactions_received: queried.received_tx_count as u64,
+            sui_sent: queried.sui_sent as u64,
+            sui_received: queried.sui_received as u64,
+        })
+    }
+}
+
+/// Aggregate the activity of each address, per day, in transactions `txs`, whose senders and
+/// recipients are `senders` and `recipients`.
+pub fn aggregate_address_daily_stats(
+    txs: &[StoredTransactionBalanceChanges],
+    senders: &[StoredTxSenders],
+    recipients: &[StoredTxRecipients],
+) -> Result<Vec<StoredAddressDailyStats>, IndexerError> {
+    let days: HashMap<i64, i64> = txs
+        .iter()
+        .map(|tx| (tx.tx_sequence_number, tx.timestamp_ms / MS_PER_DAY))
+        .collect();
+    let mut stats = BTreeMap::new();
+
+    for sender in senders {
+        if let Some(day) = days.get(&sender.tx_sequence_number) {
+            stats_of(
+                &mut stats,
+                sender.sender.clone(),
+                *day,
+                sender.tx_sequence_number,
+            )
+            .sent_tx_count += 1;
+        }
+    }
+
+    for recipient in recipients {
+        if let Some(day) = days.get(&recipient.tx_sequence_number) {
+            stats_of(
+                &mut stats,
+                recipient.recipient.clone(),
+                *day,
+                recipient.tx_sequence_number,
+            )
+            .received_tx_count += 1;
+        }
+    }
+
+    let sui = GAS::type_tag();
+    for tx in txs {
+        let day = tx.timestamp_ms / MS_PER_DAY;
+        for balance_change in tx.balance_changes.iter().flatten() {
+            let balance_change: BalanceChange =
+                bcs::from_bytes(balance_change).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Can't convert balance_change bytes into BalanceChange. tx_sequence_number: {:?} Error: {e}",
+                        tx.tx_sequence_number
+                    ))
+                })?;
+            let Owner::AddressOwner(owner) = balance_change.owner else {
+                continue;
+            };
+            if balance_change.coin_type != sui {
+                continue;
+            }
+
+            let amount = i64::try_from(balance_change.amount.unsigned_abs()).unwrap_or(i64::MAX);
+            let stats = stats_of(&mut stats, owner.to_vec(), day, tx.tx_sequence_number);
+            if balance_change.amount < 0 {
+                stats.sui_sent = stats.sui_sent.saturating_add(amount);
+            } else {
+                stats.sui_received = stats.sui_received.saturating_add(amount);
+            }
+        }
+    }
+
+    Ok(stats.into_values().collect())
+}
+
+fn stats_of(
+    stats: &mut BTreeMap<(i64, Vec<u8>), StoredAddressDailyStats>,
+    address: Vec<u8>,
+    day: i64,
+    tx_sequence_number: i64,
+) -> &mut StoredAddressDailyStats {
+    let stats = stats
+        .entry((day, address.clone()))
+        .or_insert_with(|| StoredAddressDailyStats {
+            address,
+            day,
+            sent_tx_count: 0,
+            received_tx_count: 0,
+            sui_sent: 0,
+            sui_received: 0,
+            last_tx_sequence_number: tx_sequence_number,
+        });
+    stats.last_tx_sequence_number = stats.last_tx_sequence_number.max(tx_sequence_number);
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::language_storage::TypeTag;
+
+    fn balance_change(owner: SuiAddress, coin_type: TypeTag, amount: i128) -> Option<Vec<u8>> {
+        let change = BalanceChange {
+            owner: Owner::AddressOwner(owner),
+            coin_type,
+            amount,
+        };
+        Some(bcs::to_bytes(&change).unwrap())
+    }
+
+    #[test]
+    fn test_aggregate_address_daily_stats() {
+        let (alice, bob) = (
+            SuiAddress::random_for_testing_only(),
+            SuiAddress::random_for_testing_only(),
+        );
+        let txs = vec![
+            StoredTransactionBalanceChanges {
+                tx_sequence_number: 1,
+                timestamp_ms: MS_PER_DAY - 1,
+                balance_changes: vec![
+                    balance_change(alice, GAS::type_tag(), -150),
+                    balance_change(bob, GAS::type_tag(), 100),
+                    balance_change(bob, TypeTag::U64, 1000),
+                ],
+            },
+            StoredTransactionBalanceChanges {
+                tx_sequence_number: 2,
+                timestamp_ms: MS_PER_DAY,
+                balance_changes: vec![balance_change(alice, GAS::type_tag(), -10)],
+            },
+        ];
+        let senders: Vec<_> = [1, 2]
+            .into_iter()
+            .map(|tx_sequence_number| StoredTxSenders {
+                tx_sequence_number,
+                sender: alice.to_vec(),
+            })
+            .collect();
+        let recipients = vec![StoredTxRecipients {
+            tx_sequence_number: 1,
+            recipient: bob.to_vec(),
+        }];
+
+        let stats = aggregate_address_daily_stats(&txs, &senders, &recipients).unwrap();
+
+        let mut expected = vec![
+            StoredAddressDailyStats {
+                address: alice.to_vec(),
+                day: 0,
+                sent_tx_count: 1,
+                received_tx_count: 0,
+                sui_sent: 150,
+                sui_received: 0,
+                last_tx_sequence_number: 1,
+            },
+            StoredAddressDailyStats {
+                address: bob.to_vec(),
+                day: 0,
+                sent_tx_count: 0,
+                received_tx_count: 1,
+                sui_sent: 0,
+                sui_received: 100,
+                last_tx_sequence_number: 1,
+            },
+            StoredAddressDailyStats {
+                address: alice.to_vec(),
+                day: 1,
+                sent_tx_count: 1,
+                received_tx_count: 0,
+                sui_sent: 10,
+                sui_received: 0,
+                last_tx_sequence_number: 2,
+            },
+        ];
+        // Aggregates are ordered by day, then address.
+        expected.sort_by(|a, b| (a.day, &a.address).cmp(&(b.day, &b.address)));
+        assert_eq!(stats, expected);
+    }
+}
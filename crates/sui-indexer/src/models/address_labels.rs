@@ -0,0 +1,45 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use sui_json_rpc_types::AddressLabel;
+use sui_types::base_types::SuiAddress;
+
+use crate::errors::IndexerError;
+use crate::schema::address_labels;
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = address_labels)]
+pub struct StoredAddressLabel {
+    pub address: Vec<u8>,
+    pub name: String,
+    pub category: Option<String>,
+}
+
+impl StoredAddressLabel {
+    pub fn new(address: SuiAddress, label: AddressLabel) -> Self {
+        Self {
+            address: address.to_vec(),
+            name: label.name,
+            category: label.category,
+        }
+    }
+
+    pub fn try_into_address_label(self) -> Result<(SuiAddress, AddressLabel), IndexerError> {
+        let address = SuiAddress::from_bytes(&self.address).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to parse labelled address {:?}: {e}",
+                self.address
+            ))
+        })?;
+
+        Ok((
+            address,
+            AddressLabel {
+                name: self.name,
+                category: self.category,
+            },
+        ))
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+
+use sui_json_rpc_types::{BalanceChange, BalanceSnapshot};
+use sui_types::object::Owner;
+
+use crate::errors::IndexerError;
+use crate::models::transactions::StoredTransactionBalanceChanges;
+use crate::schema::balance_changes;
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = balance_changes)]
+pub struct StoredBalanceChange {
+    pub owner: Vec<u8>,
+    pub coin_type: String,
+    pub tx_sequence_number: i64,
+    pub timestamp_ms: i64,
+    pub amount: i64,
+}
+
+/// The net change to an address' balance over a bucket of time, as queried for balance histories.
+#[derive(QueryableByName, Debug, Clone, PartialEq, Eq)]
+pub struct QueriedBucketChange {
+    #[diesel(sql_type = BigInt)]
+    pub bucket: i64,
+    #[diesel(sql_type = BigInt)]
+    pub change: i64,
+}
+
+/// The changes that transactions `txs` made to the balances of addresses, one per address, coin
+/// type and transaction. Changes to the balances of objects and shared coins are left out.
+pub fn stored_balance_changes(
+    txs: &[StoredTransactionBalanceChanges],
+) -> Result<Vec<StoredBalanceChange>, IndexerError> {
+    let mut changes = BTreeMap::new();
+    for tx in txs {
+        for balance_change in tx.balance_changes.iter().flatten() {
+            let balance_change: BalanceChange =
+                bcs::from_bytes(balance_change).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Can't convert balance_change bytes into BalanceChange. tx_sequence_number: {:?} Error: {e}",
+                        tx.tx_sequence_number
+                    ))
+                })?;
+            let Owner::AddressOwner(owner) = balance_change.owner else {
+                continue;
+            };
+            let coin_type = balance_change
+                .coin_type
+                .to_canonical_string(/* with_prefix */ true);
+            let (_, amount) = changes
+                .entry((tx.tx_sequence_number, owner.to_vec(), coin_type))
+                .or_insert((tx.timestamp_ms, 0i128));
+            *amount += balance_change.amount;
+        }
+    }
+
+    Ok(changes
+        .into_iter()
+        .filter(|(_, (_, amount))| *amount != 0)
+        .map(
+            |((tx_sequence_number, owner, coin_type), (timestamp_ms, amount))| {
+                StoredBalanceChange {
+                    owner,
+                    coin_type,
+                    tx_sequence_number,
+                    timestamp_ms,
+                    amount: amount.clamp(i64::MIN as i128, i64::MAX as i128) as i64,
+                }
+            },
+        )
+        .collect())
+}
+
+/// The snapshots of a balance at the end of each bucket from `start_bucket` to `end_bucket`
+/// (inclusive), given the `opening` balance before `start_bucket`, and the net `changes` of the
+/// buckets in the range that the balance changed in, ordered by bucket.
+pub fn balance_snapshots(
+    opening: i128,
+    changes: &[QueriedBucketChange],
+    start_bucket: i64,
+    end_bucket: i64,
+    interval_ms: i64,
+) -> Vec<BalanceSnapshot> {
+    let mut changes = changes.iter().peekable();
+    let mut balance = opening;
+    (start_bucket..=end_bucket)
+        .map(|bucket| {
+            let change = match changes.next_if(|c| c.bucket == bucket) {
+                Some(c) => c.change as i128,
+                None => 0,
+            };
+            balance += change;
+            BalanceSnapshot {
+                bucket_start_ms: (bucket * interval_ms) as u64,
+                balance,
+                change,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::language_storage::TypeTag;
+    use sui_types::base_types::{ObjectID, SuiAddress};
+    use sui_types::gas_coin::GAS;
+
+    fn balance_change(owner: Owner, coin_type: TypeTag, amount: i128) -> Option<Vec<u8>> {
+        let change = BalanceChange {
+            owner,
+            coin_type,
+            amount,
+        };
+        Some(bcs::to_bytes(&change).unwrap())
+    }
+
+    #[test]
+    fn test_stored_balance_changes() {
+        let alice = SuiAddress::random_for_testing_only();
+        let sui = GAS::type_tag();
+        let txs = vec![StoredTransactionBalanceChanges {
+            tx_sequence_number: 7,
+            timestamp_ms: 1000,
+            balance_changes: vec![
+                balance_change(Owner::AddressOwner(alice), sui.clone(), -150),
+                balance_change(Owner::AddressOwner(alice), sui.clone(), 50),
+                balance_change(Owner::AddressOwner(alice), TypeTag::U64, i128::MAX),
+                balance_change(Owner::ObjectOwner(ObjectID::ZERO.into()), sui.clone(), 100),
+            ],
+        }];
+
+        let changes = stored_balance_changes(&txs).unwrap();
+        let mut expected = vec![
+            StoredBalanceChange {
+                owner: alice.to_vec(),
+                coin_type: sui.to_canonical_string(true),
+                tx_sequence_number: 7,
+                timestamp_ms: 1000,
+                amount: -100,
+            },
+            StoredBalanceChange {
+                owner: alice.to_vec(),
+                coin_type: TypeTag::U64.to_canonical_string(true),
+                tx_sequence_number: 7,
+                timestamp_ms: 1000,
+                amount: i64::MAX,
+            },
+        ];
+        expected.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+        assert_eq!(changes, expected);
+    }
+
+    #[test]
+    fn test_balance_snapshots() {
+        let changes = vec![
+            QueriedBucketChange {
+                bucket: 11,
+                change: 30,
+            },
+            QueriedBucketChange {
+                bucket: 13,
+                change: -5,
+            },
+        ];
+        let snapshots = balance_snapshots(100, &changes, 10, 13, 1000);
+        let balances: Vec<_> = snapshots
+            .iter()
+            .map(|s| (s.bucket_start_ms, s.balance, s.change))
+            .collect();
+        // Buckets without changes carry the balance over.
+        assert_eq!(
+            balances,
+            vec![
+                (10000, 100, 0),
+                (11000, 130, 30),
+                (12000, 130, 0),
+                (13000, 125, -5),
+            ]
+        );
+    }
+}
@@ -3,7 +3,7 @@
 
 use diesel::prelude::*;
 
-use sui_json_rpc_types::Checkpoint as RpcCheckpoint;
+use sui_json_rpc_types::{Checkpoint as RpcCheckpoint, TimestampDirection};
 use sui_types::base_types::TransactionDigest;
 use sui_types::digests::CheckpointDigest;
 use sui_types::gas::GasCostSummary;
@@ -153,3 +153,62 @@ impl TryFrom<StoredCheckpoint> for RpcCheckpoint {
         })
     }
 }
+
+/// The sequence number of the checkpoint closest to `timestamp_ms` in `direction`, among the
+/// checkpoints from `first` to `last` inclusive, or `None` if there is none on that side of it.
+/// Checkpoint timestamps never decrease, so this binary searches them, reading the timestamp of
+/// each checkpoint probed with `timestamp_of`.
+pub fn search_by_timestamp<E>(
+    first: i64,
+    last: i64,
+    timestamp_ms: i64,
+    direction: TimestampDirection,
+    mut timestamp_of: impl FnMut(i64) -> Result<i64, E>,
+) -> Result<Option<i64>, E> {
+    // The first checkpoint past the ones on the "before" side of the timestamp.
+    let (mut lo, mut hi) = (first, last + 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let timestamp = timestamp_of(mid)?;
+        let past = match direction {
+            TimestampDirection::Before => timestamp > timestamp_ms,
+            TimestampDirection::After => timestamp >= timestamp_ms,
+        };
+        if past {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    Ok(match direction {
+        TimestampDirection::Before => (lo > first).then_some(lo - 1),
+        TimestampDirection::After => (lo <= last).then_some(lo),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_by_timestamp() {
+        // Checkpoints 10 to 15, two of which share a timestamp.
+        let timestamps = [100, 200, 300, 300, 400, 500];
+        let search = |timestamp_ms, direction| {
+            search_by_timestamp(10, 15, timestamp_ms, direction, |seq| {
+                Ok::<_, ()>(timestamps[(seq - 10) as usize])
+            })
+            .unwrap()
+        };
+
+        assert_eq!(search(300, TimestampDirection::Before), Some(13));
+        assert_eq!(search(300, TimestampDirection::After), Some(12));
+        assert_eq!(search(350, TimestampDirection::Before), Some(13));
+        assert_eq!(search(350, TimestampDirection::After), Some(14));
+        assert_eq!(search(50, TimestampDirection::Before), None);
+        assert_eq!(search(50, TimestampDirection::After), Some(10));
+        assert_eq!(search(500, TimestampDirection::Before), Some(15));
+        assert_eq!(search(600, TimestampDirection::After), None);
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::digests::TransactionDigest;
+
+use crate::errors::IndexerError;
+use crate::schema::equivocations;
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = equivocations)]
+pub struct StoredEquivocation {
+    pub object_id: Vec<u8>,
+    pub object_version: i64,
+    pub sender: Vec<u8>,
+    pub transaction_digest: Vec<u8>,
+    pub conflicting_digest: Vec<u8>,
+    pub timestamp_ms: i64,
+}
+
+/// Transaction `transaction_digest`, sent by `sender`, was rejected because version `version` of
+/// object `object_id` was already locked by transaction `conflicting_digest`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equivocation {
+    pub object_id: ObjectID,
+    pub version: SequenceNumber,
+    pub sender: SuiAddress,
+    pub transaction_digest: TransactionDigest,
+    pub conflicting_digest: TransactionDigest,
+    pub timestamp_ms: u64,
+}
+
+impl From<Equivocation> for StoredEquivocation {
+    fn from(e: Equivocation) -> Self {
+        Self {
+            object_id: e.object_id.to_vec(),
+            object_version: e.version.value() as i64,
+            sender: e.sender.to_vec(),
+            transaction_digest: e.transaction_digest.into_inner().to_vec(),
+            conflicting_digest: e.conflicting_digest.into_inner().to_vec(),
+            timestamp_ms: e.timestamp_ms as i64,
+        }
+    }
+}
+
+impl TryFrom<StoredEquivocation> for Equivocation {
+    type Error = IndexerError;
+
+    fn try_from(stored: StoredEquivocation) -> Result<Self, Self::Error> {
+        let object_id = ObjectID::from_bytes(&stored.object_id).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to decode object id {:?} with err: {e:?}",
+                stored.object_id
+            ))
+        })?;
+        let sender = SuiAddress::from_bytes(&stored.sender).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to decode sender {:?} with err: {e:?}",
+                stored.sender
+            ))
+        })?;
+
+        Ok(Equivocation {
+            object_id,
+            version: SequenceNumber::from_u64(stored.object_version as u64),
+            sender,
+            transaction_digest: decode_digest(&stored.transaction_digest)?,
+            conflicting_digest: decode_digest(&stored.conflicting_digest)?,
+            timestamp_ms: stored.timestamp_ms as u64,
+        })
+    }
+}
+
+fn decode_digest(bytes: &[u8]) -> Result<TransactionDigest, IndexerError> {
+    TransactionDigest::try_from(bytes).map_err(|e| {
+        IndexerError::PersistentStorageDataCorruptionError(format!(
+            "Failed to decode transaction digest {bytes:?} with err: {e:?}"
+        ))
+    })
+}
@@ -0,0 +1,106 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+
+use diesel::prelude::*;
+use move_core_types::annotated_value::MoveValue;
+
+use sui_types::base_types::SuiAddress;
+use sui_types::id::{ID, UID};
+
+use crate::schema::event_addresses;
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = event_addresses)]
+pub struct StoredEventAddress {
+    pub address: Vec<u8>,
+    pub tx_sequence_number: i64,
+    pub event_sequence_number: i64,
+    pub checkpoint_sequence_number: i64,
+}
+
+/// The addresses in an event's decoded payload, `value`, however deeply nested in structs and
+/// vectors. Object IDs (fields of type `ID` or `UID`) are left out, as they are not addresses that
+/// events can be relevant to.
+pub fn payload_addresses(value: &MoveValue) -> BTreeSet<SuiAddress> {
+    let mut addresses = BTreeSet::new();
+    collect_addresses(value, &mut addresses);
+    addresses
+}
+
+fn collect_addresses(value: &MoveValue, addresses: &mut BTreeSet<SuiAddress>) {
+    match value {
+        MoveValue::Address(address) | MoveValue::Signer(address) => {
+            addresses.insert(SuiAddress::from(*address));
+        }
+        MoveValue::Vector(values) => {
+            for value in values {
+                collect_addresses(value, addresses);
+            }
+        }
+        MoveValue::Struct(s) if s.type_ == ID::type_() || s.type_ == UID::type_() => {}
+        MoveValue::Struct(s) => {
+            for (_, value) in &s.fields {
+                collect_addresses(value, addresses);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::annotated_value::MoveStruct;
+    use move_core_types::identifier::Identifier;
+    use move_core_types::language_storage::StructTag;
+    use std::str::FromStr;
+
+    fn move_struct(type_: StructTag, fields: Vec<(&str, MoveValue)>) -> MoveValue {
+        MoveValue::Struct(MoveStruct {
+            type_,
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| (Identifier::new(name).unwrap(), value))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn test_payload_addresses() {
+        let buyer = SuiAddress::random_for_testing_only();
+        let seller = SuiAddress::random_for_testing_only();
+        let recipient = SuiAddress::random_for_testing_only();
+        let object_id = AccountAddress::random();
+        let id = move_struct(ID::type_(), vec![("bytes", MoveValue::Address(object_id))]);
+        let payment = move_struct(
+            StructTag::from_str("0x42::market::Payment").unwrap(),
+            vec![
+                ("amount", MoveValue::U64(100)),
+                (
+                    "recipients",
+                    MoveValue::Vector(vec![
+                        MoveValue::Address(recipient.into()),
+                        MoveValue::Address(seller.into()),
+                    ]),
+                ),
+            ],
+        );
+        let event = move_struct(
+            StructTag::from_str("0x42::market::Sold").unwrap(),
+            vec![
+                ("listing", id),
+                ("buyer", MoveValue::Address(buyer.into())),
+                ("seller", MoveValue::Address(seller.into())),
+                ("payment", payment),
+            ],
+        );
+
+        assert_eq!(
+            payload_addresses(&event),
+            BTreeSet::from([buyer, seller, recipient])
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use move_core_types::annotated_value::{MoveStructLayout, MoveTypeLayout};
+
+use sui_json_rpc_types::EventSchema;
+use sui_types::base_types::ObjectID;
+
+use crate::errors::IndexerError;
+use crate::schema::event_schemas;
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = event_schemas)]
+pub struct StoredEventSchema {
+    pub event_type: String,
+    pub package_id: Vec<u8>,
+    pub layout: serde_json::Value,
+    pub first_seen_checkpoint: i64,
+    pub last_seen_checkpoint: i64,
+}
+
+impl StoredEventSchema {
+    pub fn key(&self) -> (String, Vec<u8>) {
+        (self.event_type.clone(), self.package_id.clone())
+    }
+
+    /// Widen the range of checkpoints this schema was seen in to include `other`'s. Both must
+    /// describe the same event type and package.
+    pub fn merge(&mut self, other: &StoredEventSchema) {
+        self.first_seen_checkpoint = self.first_seen_checkpoint.min(other.first_seen_checkpoint);
+        self.last_seen_checkpoint = self.last_seen_checkpoint.max(other.last_seen_checkpoint);
+    }
+
+    /// The layout of the event type, as recorded for this package.
+    pub fn struct_layout(&self) -> Result<MoveStructLayout, IndexerError> {
+        let layout = serde_json::from_value(self.layout.clone()).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to parse layout of event type {}: {e}",
+                self.event_type
+            ))
+        })?;
+        match layout {
+            MoveTypeLayout::Struct(layout) => Ok(layout),
+            layout => Err(IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Layout of event type {} is not a struct: {layout:?}",
+                self.event_type
+            ))),
+        }
+    }
+}
+
+impl TryFrom<StoredEventSchema> for EventSchema {
+    type Error = IndexerError;
+
+    fn try_from(schema: StoredEventSchema) -> Result<Self, Self::Error> {
+        let package_id = ObjectID::from_bytes(&schema.package_id).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to parse package id {:?} of event type {}: {e}",
+                schema.package_id, schema.event_type
+            ))
+        })?;
+
+        Ok(EventSchema {
+            event_type: schema.event_type,
+            package_id,
+            layout: schema.layout,
+            first_seen_checkpoint: schema.first_seen_checkpoint as u64,
+            last_seen_checkpoint: schema.last_seen_checkpoint as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::annotated_value::MoveFieldLayout;
+    use move_core_types::identifier::Identifier;
+    use move_core_types::language_storage::StructTag;
+
+    fn stored_schema(layout: &MoveTypeLayout) -> StoredEventSchema {
+        StoredEventSchema {
+            event_type: "0x2::test::Event".to_string(),
+            package_id: ObjectID::ZERO.to_vec(),
+            layout: serde_json::to_value(layout).unwrap(),
+            first_seen_checkpoint: 0,
+            last_seen_checkpoint: 0,
+        }
+    }
+
+    #[test]
+    fn test_struct_layout() {
+        let layout = MoveStructLayout {
+            type_: StructTag {
+                address: AccountAddress::TWO,
+                module: Identifier::new("test").unwrap(),
+                name: Identifier::new("Event").unwrap(),
+                type_params: vec![],
+            },
+            fields: vec![MoveFieldLayout::new(
+                Identifier::new("value").unwrap(),
+                MoveTypeLayout::U64,
+            )],
+        };
+        let schema = stored_schema(&MoveTypeLayout::Struct(layout.clone()));
+        let parsed = schema.struct_layout().unwrap();
+        assert_eq!(parsed.type_, layout.type_);
+        assert_eq!(parsed.fields.len(), 1);
+        assert_eq!(parsed.fields[0].name.as_str(), "value");
+
+        assert!(stored_schema(&MoveTypeLayout::U64).struct_layout().is_err());
+    }
+}
@@ -3,9 +3,13 @@
 
 use std::str::FromStr;
 
+use diesel::deserialize::{self, FromStaticSqlRow};
+use diesel::pg::Pg;
 use diesel::prelude::*;
+use diesel::row::NamedRow;
+use diesel::sql_types::{Array, BigInt, Bytea, Nullable, SmallInt, Text};
 use move_bytecode_utils::module_cache::GetModule;
-use move_core_types::annotated_value::MoveStruct;
+use move_core_types::annotated_value::{MoveStruct, MoveStructLayout};
 use move_core_types::identifier::Identifier;
 
 use sui_json_rpc_types::{SuiEvent, SuiMoveStruct};
@@ -15,42 +19,100 @@ use sui_types::event::EventID;
 use sui_types::object::MoveObject;
 use sui_types::parse_sui_struct_tag;
 
+use crate::compression::{decompress, Compression, UNCOMPRESSED};
 use crate::errors::IndexerError;
 use crate::schema::events;
 use crate::types::IndexedEvent;
 
-#[derive(Queryable, QueryableByName, Insertable, Debug, Clone)]
+#[derive(Insertable, Debug, Clone)]
 #[diesel(table_name = events)]
 pub struct StoredEvent {
-    #[diesel(sql_type = diesel::sql_types::BigInt)]
     pub tx_sequence_number: i64,
-
-    #[diesel(sql_type = diesel::sql_types::BigInt)]
     pub event_sequence_number: i64,
-
-    #[diesel(sql_type = diesel::sql_types::Bytea)]
     pub transaction_digest: Vec<u8>,
-
-    #[diesel(sql_type = diesel::sql_types::BigInt)]
     pub checkpoint_sequence_number: i64,
-
-    #[diesel(sql_type = diesel::sql_types::Array<diesel::sql_types::Nullable<diesel::pg::sql_types::Bytea>>)]
     pub senders: Vec<Option<Vec<u8>>>,
-
-    #[diesel(sql_type = diesel::sql_types::Bytea)]
     pub package: Vec<u8>,
-
-    #[diesel(sql_type = diesel::sql_types::Text)]
     pub module: String,
-
-    #[diesel(sql_type = diesel::sql_types::Text)]
     pub event_type: String,
-
-    #[diesel(sql_type = diesel::sql_types::BigInt)]
     pub timestamp_ms: i64,
-
-    #[diesel(sql_type = diesel::sql_types::Bytea)]
     pub bcs: Vec<u8>,
+    /// How `bcs` is compressed. Events are decompressed as they are loaded, so this is only ever
+    /// set on those that are stored.
+    pub compression: i16,
+}
+
+type StoredEventRow = (
+    i64,
+    i64,
+    Vec<u8>,
+    i64,
+    Vec<Option<Vec<u8>>>,
+    Vec<u8>,
+    String,
+    String,
+    i64,
+    Vec<u8>,
+    i16,
+);
+
+impl<ST> Queryable<ST, Pg> for StoredEvent
+where
+    StoredEventRow: FromStaticSqlRow<ST, Pg>,
+{
+    type Row = StoredEventRow;
+
+    fn build(row: Self::Row) -> deserialize::Result<Self> {
+        let (
+            tx_sequence_number,
+            event_sequence_number,
+            transaction_digest,
+            checkpoint_sequence_number,
+            senders,
+            package,
+            module,
+            event_type,
+            timestamp_ms,
+            bcs,
+            compression,
+        ) = row;
+        let event = Self {
+            tx_sequence_number,
+            event_sequence_number,
+            transaction_digest,
+            checkpoint_sequence_number,
+            senders,
+            package,
+            module,
+            event_type,
+            timestamp_ms,
+            bcs,
+            compression,
+        };
+        Ok(event.decompressed()?)
+    }
+}
+
+impl QueryableByName<Pg> for StoredEvent {
+    fn build<'a>(row: &impl NamedRow<'a, Pg>) -> deserialize::Result<Self> {
+        let event = Self {
+            tx_sequence_number: NamedRow::get::<BigInt, _>(row, "tx_sequence_number")?,
+            event_sequence_number: NamedRow::get::<BigInt, _>(row, "event_sequence_number")?,
+            transaction_digest: NamedRow::get::<Bytea, _>(row, "transaction_digest")?,
+            checkpoint_sequence_number: NamedRow::get::<BigInt, _>(
+                row,
+                "checkpoint_sequence_number",
+            )?,
+            senders: NamedRow::get::<Array<Nullable<Bytea>>, _>(row, "senders")?,
+            package: NamedRow::get::<Bytea, _>(row, "package")?,
+            module: NamedRow::get::<Text, _>(row, "module")?,
+            event_type: NamedRow::get::<Text, _>(row, "event_type")?,
+            timestamp_ms: NamedRow::get::<BigInt, _>(row, "timestamp_ms")?,
+            bcs: NamedRow::get::<Bytea, _>(row, "bcs")?,
+            compression: NamedRow::get::<SmallInt, _>(row, "compression")?,
+        };
+        Ok(event.decompressed()?)
+    }
 }
 
 impl From<IndexedEvent> for StoredEvent {
@@ -70,14 +132,44 @@ impl From<IndexedEvent> for StoredEvent {
             event_type: event.event_type.clone(),
             bcs: event.bcs.clone(),
             timestamp_ms: event.timestamp_ms as i64,
+            compression: UNCOMPRESSED,
         }
     }
 }
 
 impl StoredEvent {
+    /// The event, with its contents compressed with `compression`.
+    pub fn compressed(self, compression: Compression) -> Self {
+        debug_assert_eq!(self.compression, UNCOMPRESSED);
+        Self {
+            bcs: compression.compress(&self.bcs),
+            compression: compression as i16,
+            ..self
+        }
+    }
+
+    fn decompressed(self) -> Result<Self, IndexerError> {
+        Ok(Self {
+            bcs: decompress(self.compression, self.bcs)?,
+            compression: UNCOMPRESSED,
+            ..self
+        })
+    }
+
     pub fn try_into_sui_event(
         self,
         module_cache: &impl GetModule,
+    ) -> Result<SuiEvent, IndexerError> {
+        let type_ = parse_sui_struct_tag(&self.event_type)?;
+        let layout = MoveObject::get_layout_from_struct_tag(type_, module_cache)?;
+        self.try_into_sui_event_with_layout(&layout)
+    }
+
+    /// The event, with its contents decoded with `layout` rather than the layout its type
+    /// currently has.
+    pub fn try_into_sui_event_with_layout(
+        self,
+        layout: &MoveStructLayout,
     ) -> Result<SuiEvent, IndexerError> {
         let package_id = ObjectID::from_bytes(self.package.clone()).map_err(|_e| {
             IndexerError::PersistentStorageDataCorruptionError(format!(
@@ -106,9 +198,7 @@ impl StoredEvent {
         };
 
         let type_ = parse_sui_struct_tag(&self.event_type)?;
-
-        let layout = MoveObject::get_layout_from_struct_tag(type_.clone(), module_cache)?;
-        let move_object = MoveStruct::simple_deserialize(&self.bcs, &layout)
+        let move_object = MoveStruct::simple_deserialize(&self.bcs, layout)
             .map_err(|e| IndexerError::SerdeError(e.to_string()))?;
         let parsed_json = SuiMoveStruct::from(move_object).to_json_value();
         let tx_digest =
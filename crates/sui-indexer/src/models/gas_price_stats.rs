@@ -0,0 +1,103 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use sui_json_rpc_types::GasPriceStats;
+
+use crate::schema::gas_price_stats;
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = gas_price_stats)]
+pub struct StoredGasPriceStats {
+    pub first_checkpoint: i64,
+    pub last_checkpoint: i64,
+    pub epoch: i64,
+    pub timestamp_ms: i64,
+    pub reference_gas_price: i64,
+    pub transaction_count: i64,
+    pub mean_gas_price: Option<f64>,
+    pub median_gas_price: Option<i64>,
+    pub min_gas_price: Option<i64>,
+    pub max_gas_price: Option<i64>,
+}
+
+impl StoredGasPriceStats {
+    /// Statistics of the `gas_prices` of the programmable transactions in checkpoints
+    /// `first_checkpoint` to `last_checkpoint` (inclusive), of `epoch`.
+    pub fn new(
+        first_checkpoint: i64,
+        last_checkpoint: i64,
+        epoch: i64,
+        timestamp_ms: i64,
+        reference_gas_price: i64,
+        mut gas_prices: Vec<u64>,
+    ) -> Self {
+        gas_prices.sort_unstable();
+        let count = gas_prices.len();
+        let mean_gas_price =
+            (count > 0).then(|| gas_prices.iter().map(|p| *p as f64).sum::<f64>() / count as f64);
+        let median_gas_price = (count > 0).then(|| {
+            let (low, high) = (gas_prices[(count - 1) / 2], gas_prices[count / 2]);
+            to_i64(low / 2 + high / 2 + (low % 2 + high % 2) / 2)
+        });
+        Self {
+            first_checkpoint,
+            last_checkpoint,
+            epoch,
+            timestamp_ms,
+            reference_gas_price,
+            transaction_count: count as i64,
+            mean_gas_price,
+            median_gas_price,
+            min_gas_price: gas_prices.first().copied().map(to_i64),
+            max_gas_price: gas_prices.last().copied().map(to_i64),
+        }
+    }
+}
+
+fn to_i64(price: u64) -> i64 {
+    i64::try_from(price).unwrap_or(i64::MAX)
+}
+
+impl From<StoredGasPriceStats> for GasPriceStats {
+    fn from(stats: StoredGasPriceStats) -> Self {
+        Self {
+            first_checkpoint: stats.first_checkpoint as u64,
+            last_checkpoint: stats.last_checkpoint as u64,
+            epoch: stats.epoch as u64,
+            timestamp_ms: stats.timestamp_ms as u64,
+            reference_gas_price: stats.reference_gas_price as u64,
+            transaction_count: stats.transaction_count as u64,
+            mean_gas_price: stats.mean_gas_price,
+            median_gas_price: stats.median_gas_price.map(|p| p as u64),
+            min_gas_price: stats.min_gas_price.map(|p| p as u64),
+            max_gas_price: stats.max_gas_price.map(|p| p as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_price_stats() {
+        let stats = StoredGasPriceStats::new(10, 19, 1, 1_000, 750, vec![1_000, 750, 2_000, 751]);
+        assert_eq!(stats.transaction_count, 4);
+        assert_eq!(stats.mean_gas_price, Some(1_125.25));
+        // The mean of the two middle prices, rounded down.
+        assert_eq!(stats.median_gas_price, Some(875));
+        assert_eq!(stats.min_gas_price, Some(750));
+        assert_eq!(stats.max_gas_price, Some(2_000));
+
+        let stats = StoredGasPriceStats::new(10, 19, 1, 1_000, 750, vec![800, 750, 2_000]);
+        assert_eq!(stats.median_gas_price, Some(800));
+
+        let stats = StoredGasPriceStats::new(10, 19, 1, 1_000, 750, vec![]);
+        assert_eq!(stats.transaction_count, 0);
+        assert_eq!(stats.mean_gas_price, None);
+        assert_eq!(stats.median_gas_price, None);
+        assert_eq!(stats.min_gas_price, None);
+    }
+}
@@ -1,15 +1,28 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod account_statement;
+pub mod address_daily_stats;
+pub mod address_labels;
 pub mod address_metrics;
+pub mod balance_changes;
 pub mod checkpoints;
 pub mod display;
 pub mod epoch;
+pub mod equivocations;
+pub mod event_addresses;
+pub mod event_schemas;
 pub mod events;
+pub mod gas_price_stats;
 pub mod move_call_metrics;
 pub mod network_metrics;
+pub mod nft_transfers;
+pub mod object_transitions;
 pub mod objects;
+pub mod package_call_metrics;
 pub mod packages;
 pub mod transactions;
 pub mod tx_count_metrics;
 pub mod tx_indices;
+pub mod validator_apys;
+pub mod validator_history;
@@ -44,22 +44,112 @@ pub struct StoredNetworkMetrics {
     pub current_checkpoint: i64,
 }
 
-impl From<StoredNetworkMetrics> for NetworkMetrics {
-    fn from(db: StoredNetworkMetrics) -> Self {
-        Self {
-            current_tps: db.current_tps,
-            tps_30_days: db.tps_30_days,
-            total_packages: db.total_packages as u64,
-            total_addresses: db.total_addresses as u64,
-            total_objects: db.total_objects as u64,
-            current_epoch: db.current_epoch as u64,
-            current_checkpoint: db.current_checkpoint as u64,
+impl StoredNetworkMetrics {
+    pub fn into_network_metrics(self, recent: RecentNetworkActivity) -> NetworkMetrics {
+        NetworkMetrics {
+            current_tps: self.current_tps,
+            tps_30_days: self.tps_30_days,
+            total_packages: self.total_packages as u64,
+            total_addresses: self.total_addresses as u64,
+            total_objects: self.total_objects as u64,
+            current_epoch: self.current_epoch as u64,
+            current_checkpoint: self.current_checkpoint as u64,
+            rolling_tps: recent.rolling_tps,
+            checkpoints_per_second: recent.checkpoints_per_second,
+            average_checkpoint_size: recent.average_checkpoint_size,
+            epoch_start_timestamp_ms: recent.epoch_start_timestamp_ms,
+            epoch_duration_ms: recent.epoch_duration_ms,
+            epoch_progress: recent.epoch_progress,
         }
     }
 }
 
+/// Number of most recently ingested checkpoints that rolling rates are computed over.
+pub const RECENT_CHECKPOINTS: i64 = 100;
+
+/// Rates computed from the most recently ingested checkpoints, rather than by the analytical
+/// worker, and the progress of the current epoch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecentNetworkActivity {
+    pub rolling_tps: f64,
+    pub checkpoints_per_second: f64,
+    pub average_checkpoint_size: f64,
+    pub epoch_start_timestamp_ms: u64,
+    pub epoch_duration_ms: u64,
+    pub epoch_progress: f64,
+}
+
+impl RecentNetworkActivity {
+    /// `checkpoints` are the network total transactions and timestamps of recent checkpoints,
+    /// latest first.
+    pub fn new(
+        checkpoints: &[(i64, i64)],
+        epoch_start_timestamp_ms: u64,
+        epoch_duration_ms: u64,
+    ) -> Self {
+        let mut activity = Self {
+            epoch_start_timestamp_ms,
+            epoch_duration_ms,
+            ..Default::default()
+        };
+        let (Some(&(latest_txs, latest_ms)), Some(&(oldest_txs, oldest_ms))) =
+            (checkpoints.first(), checkpoints.last())
+        else {
+            return activity;
+        };
+
+        let intervals = (checkpoints.len() - 1) as f64;
+        let transactions = (latest_txs - oldest_txs) as f64;
+        let elapsed_ms = (latest_ms - oldest_ms) as f64;
+        if intervals > 0.0 {
+            activity.average_checkpoint_size = transactions / intervals;
+        }
+        if elapsed_ms > 0.0 {
+            activity.rolling_tps = transactions * 1000.0 / elapsed_ms;
+            activity.checkpoints_per_second = intervals * 1000.0 / elapsed_ms;
+        }
+        if epoch_duration_ms > 0 {
+            let elapsed_in_epoch = (latest_ms as u64).saturating_sub(epoch_start_timestamp_ms);
+            activity.epoch_progress = (elapsed_in_epoch as f64 / epoch_duration_ms as f64).min(1.0);
+        }
+        activity
+    }
+}
+
 #[derive(Debug, QueryableByName)]
 pub struct Tps {
     #[diesel(sql_type = Float8)]
     pub peak_tps: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_network_activity() {
+        // 4 checkpoints over 3 seconds, with 30 transactions after the oldest one.
+        let checkpoints = [(130, 13_000), (120, 12_000), (110, 11_000), (100, 10_000)];
+        let activity = RecentNetworkActivity::new(&checkpoints, 4_000, 36_000);
+        assert_eq!(
+            activity,
+            RecentNetworkActivity {
+                rolling_tps: 10.0,
+                checkpoints_per_second: 1.0,
+                average_checkpoint_size: 10.0,
+                epoch_start_timestamp_ms: 4_000,
+                epoch_duration_ms: 36_000,
+                epoch_progress: 0.25,
+            }
+        );
+
+        // Epochs can run over their duration.
+        let activity = RecentNetworkActivity::new(&checkpoints, 4_000, 1_000);
+        assert_eq!(activity.epoch_progress, 1.0);
+
+        // A single checkpoint has no rates.
+        let activity = RecentNetworkActivity::new(&checkpoints[..1], 4_000, 36_000);
+        assert_eq!(activity.rolling_tps, 0.0);
+        assert_eq!(activity.average_checkpoint_size, 0.0);
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use sui_json_rpc_types::NftTransfer;
+use sui_types::base_types::{SequenceNumber, SuiAddress};
+use sui_types::digests::TransactionDigest;
+
+use crate::errors::IndexerError;
+use crate::models::object_transitions::transition_from_i16;
+use crate::schema::nft_transfers;
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = nft_transfers)]
+pub struct StoredNftTransfer {
+    pub object_id: Vec<u8>,
+    pub object_version: i64,
+    pub object_type: String,
+    pub transition: i16,
+    pub sender: Vec<u8>,
+    pub from_owner: Option<Vec<u8>>,
+    pub to_owner: Option<Vec<u8>>,
+    pub transaction_digest: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+    pub timestamp_ms: i64,
+}
+
+fn decode_address(field: &str, bytes: &[u8]) -> Result<SuiAddress, IndexerError> {
+    SuiAddress::from_bytes(bytes).map_err(|e| {
+        IndexerError::PersistentStorageDataCorruptionError(format!(
+            "Failed to decode {field} {bytes:?} with err: {e:?}"
+        ))
+    })
+}
+
+impl TryFrom<StoredNftTransfer> for NftTransfer {
+    type Error = IndexerError;
+
+    fn try_from(stored: StoredNftTransfer) -> Result<Self, Self::Error> {
+        let transaction_digest = TransactionDigest::try_from(stored.transaction_digest.as_slice())
+            .map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Failed to decode transaction digest {:?} with err: {e:?}",
+                    stored.transaction_digest
+                ))
+            })?;
+
+        Ok(NftTransfer {
+            kind: transition_from_i16(stored.transition)?,
+            version: SequenceNumber::from_u64(stored.object_version as u64),
+            from: stored
+                .from_owner
+                .map(|owner| decode_address("from owner", &owner))
+                .transpose()?,
+            to: stored
+                .to_owner
+                .map(|owner| decode_address("to owner", &owner))
+                .transpose()?,
+            sender: decode_address("sender", &stored.sender)?,
+            transaction_digest,
+            checkpoint: stored.checkpoint_sequence_number as u64,
+            timestamp_ms: stored.timestamp_ms as u64,
+        })
+    }
+}
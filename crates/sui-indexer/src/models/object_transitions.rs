@@ -0,0 +1,87 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use sui_json_rpc_types::{ObjectTransition, ObjectTransitionKind};
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::TransactionDigest;
+
+use crate::errors::IndexerError;
+use crate::schema::object_transitions;
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = object_transitions)]
+pub struct StoredObjectTransition {
+    pub object_id: Vec<u8>,
+    pub object_version: i64,
+    pub transition: i16,
+    pub transaction_digest: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+}
+
+impl StoredObjectTransition {
+    pub fn new(
+        object_id: ObjectID,
+        object_version: SequenceNumber,
+        kind: ObjectTransitionKind,
+        transaction_digest: TransactionDigest,
+        checkpoint_sequence_number: u64,
+    ) -> Self {
+        Self {
+            object_id: object_id.to_vec(),
+            object_version: object_version.value() as i64,
+            transition: transition_to_i16(kind),
+            transaction_digest: transaction_digest.into_inner().to_vec(),
+            checkpoint_sequence_number: checkpoint_sequence_number as i64,
+        }
+    }
+}
+
+pub fn transition_to_i16(kind: ObjectTransitionKind) -> i16 {
+    match kind {
+        ObjectTransitionKind::Created => 0,
+        ObjectTransitionKind::Transferred => 1,
+        ObjectTransitionKind::Wrapped => 2,
+        ObjectTransitionKind::Unwrapped => 3,
+        ObjectTransitionKind::Deleted => 4,
+        ObjectTransitionKind::UnwrappedThenDeleted => 5,
+    }
+}
+
+pub fn transition_from_i16(value: i16) -> Result<ObjectTransitionKind, IndexerError> {
+    Ok(match value {
+        0 => ObjectTransitionKind::Created,
+        1 => ObjectTransitionKind::Transferred,
+        2 => ObjectTransitionKind::Wrapped,
+        3 => ObjectTransitionKind::Unwrapped,
+        4 => ObjectTransitionKind::Deleted,
+        5 => ObjectTransitionKind::UnwrappedThenDeleted,
+        value => {
+            return Err(IndexerError::PersistentStorageDataCorruptionError(format!(
+                "{value} as ObjectTransitionKind"
+            )))
+        }
+    })
+}
+
+impl TryFrom<StoredObjectTransition> for ObjectTransition {
+    type Error = IndexerError;
+
+    fn try_from(stored: StoredObjectTransition) -> Result<Self, Self::Error> {
+        let transaction_digest = TransactionDigest::try_from(stored.transaction_digest.as_slice())
+            .map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Failed to decode transaction digest {:?} with err: {e:?}",
+                    stored.transaction_digest
+                ))
+            })?;
+
+        Ok(ObjectTransition {
+            kind: transition_from_i16(stored.transition)?,
+            version: SequenceNumber::from_u64(stored.object_version as u64),
+            transaction_digest,
+            checkpoint: stored.checkpoint_sequence_number as u64,
+        })
+    }
+}
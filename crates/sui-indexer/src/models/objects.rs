@@ -7,7 +7,7 @@ use sui_json_rpc::coin_api::parse_to_struct_tag;
 
 use diesel::prelude::*;
 use move_bytecode_utils::module_cache::GetModule;
-use sui_json_rpc_types::{Balance, Coin as SuiCoin};
+use sui_json_rpc_types::{Balance, Coin as SuiCoin, SuiMoveValue};
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
 use sui_types::digests::ObjectDigest;
 use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType, Field};
@@ -102,6 +102,62 @@ impl From<StoredObject> for StoredHistoryObject {
     }
 }
 
+impl TryFrom<StoredHistoryObject> for Object {
+    type Error = IndexerError;
+
+    fn try_from(o: StoredHistoryObject) -> Result<Self, Self::Error> {
+        let serialized_object = o.serialized_object.ok_or_else(|| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Object {:?} at version {} has no contents",
+                o.object_id, o.object_version
+            ))
+        })?;
+        bcs::from_bytes(&serialized_object).map_err(|e| {
+            IndexerError::SerdeError(format!(
+                "Failed to deserialize object: {:?}, error: {}",
+                o.object_id, e
+            ))
+        })
+    }
+}
+
+impl StoredHistoryObject {
+    /// The object at this version, or its tombstone if this version wrapped or deleted it.
+    pub fn try_into_object_read(
+        self,
+        module_cache: &impl GetModule,
+    ) -> Result<ObjectRead, IndexerError> {
+        let object_id = ObjectID::from_bytes(&self.object_id).map_err(|_| {
+            IndexerError::SerdeError(format!("Can't convert {:?} to object_id", self.object_id))
+        })?;
+        let version = SequenceNumber::from(self.object_version as u64);
+        if let ObjectStatus::WrappedOrDeleted = ObjectStatus::try_from(self.object_status)? {
+            return Ok(ObjectRead::Deleted((
+                object_id,
+                version,
+                ObjectDigest::OBJECT_DIGEST_DELETED,
+            )));
+        }
+
+        let serialized_object = self.serialized_object.ok_or_else(|| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Object {object_id} at version {version} has no contents"
+            ))
+        })?;
+        let object: Object = bcs::from_bytes(&serialized_object).map_err(|e| {
+            IndexerError::SerdeError(format!(
+                "Failed to deserialize object: {object_id}, error: {e}"
+            ))
+        })?;
+        let layout = object.get_layout(module_cache)?;
+        Ok(ObjectRead::Exists(
+            object.compute_object_reference(),
+            object,
+            layout,
+        ))
+    }
+}
+
 #[derive(Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName)]
 #[diesel(table_name = objects, primary_key(object_id))]
 pub struct StoredDeletedObject {
@@ -225,7 +281,7 @@ impl StoredObject {
                 object_id
             ))
         })?;
-        let df_object_id = if let Some(df_object_id) = self.df_object_id {
+        let df_object_id = if let Some(df_object_id) = &self.df_object_id {
             ObjectID::from_bytes(df_object_id).map_err(|e| {
                 IndexerError::PersistentStorageDataCorruptionError(format!(
                     "object {} has incompatible dynamic field type: df_object_id. Error: {e}",
@@ -248,8 +304,8 @@ impl StoredObject {
                 )))
             }
         };
-        let name = if let Some(field_name) = self.df_name {
-            let name: DynamicFieldName = bcs::from_bytes(&field_name).map_err(|e| {
+        let name = if let Some(field_name) = &self.df_name {
+            let name: DynamicFieldName = bcs::from_bytes(field_name).map_err(|e| {
                 IndexerError::PersistentStorageDataCorruptionError(format!(
                     "object {} has incompatible dynamic field type: df_name. Error: {e}",
                     object_id
@@ -257,10 +313,8 @@ impl StoredObject {
             })?;
             name
         } else {
-            return Err(IndexerError::PersistentStorageDataCorruptionError(format!(
-                "object {} has incompatible dynamic field type: empty df_name",
-                object_id
-            )));
+            // Written without parsed JSON, render the name from the object instead.
+            self.render_dynamic_field_name(module_cache)?
         };
         let layout = move_bytecode_utils::layout::TypeLayoutBuilder::build_with_types(
             &name.type_,
@@ -285,6 +339,42 @@ impl StoredObject {
         }))
     }
 
+    /// Render the name of this dynamic field from its serialized object, for objects that were
+    /// stored without their `df_name`.
+    pub fn render_dynamic_field_name(
+        &self,
+        module_cache: &impl GetModule,
+    ) -> Result<DynamicFieldName, IndexerError> {
+        let object: Object = bcs::from_bytes(&self.serialized_object).map_err(|e| {
+            IndexerError::SerdeError(format!(
+                "Failed to deserialize object: {:?}, error: {}",
+                self.object_id, e
+            ))
+        })?;
+        let move_object = object.data.try_as_move().ok_or_else(|| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Dynamic field {} is not a move object",
+                object.id()
+            ))
+        })?;
+        let layout = move_object.get_layout(module_cache)?;
+        let move_struct = move_object.to_move_struct(&layout)?;
+        let (name_value, type_, _) = DynamicFieldInfo::parse_move_object(&move_struct)?;
+        Ok(DynamicFieldName {
+            type_: move_object.type_().try_extract_field_name(&type_)?,
+            value: SuiMoveValue::from(name_value).to_json_value(),
+        })
+    }
+
+    /// Drop the parsed JSON of this object (the name of a dynamic field), to be rendered from its
+    /// serialized object when it is read.
+    pub fn without_parsed_json(self) -> Self {
+        Self {
+            df_name: None,
+            ..self
+        }
+    }
+
     pub fn get_object_ref(&self) -> Result<ObjectRef, IndexerError> {
         let object_id = ObjectID::from_bytes(self.object_id.clone()).map_err(|_| {
             IndexerError::SerdeError(format!("Can't convert {:?} to object_id", self.object_id))
@@ -385,19 +475,25 @@ impl TryFrom<CoinBalance> for Balance {
             // TODO: deal with overflow
             total_balance: c.coin_balance as u128,
             locked_balance: HashMap::default(),
+            fiat_value: None,
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use move_core_types::value::MoveValue;
     use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
+    use simulacrum::Simulacrum;
     use sui_types::{
         coin::Coin,
         digests::TransactionDigest,
         gas_coin::{GasCoin, GAS},
+        id::UID,
+        in_memory_storage::InMemoryStorage,
         object::{Data, MoveObject, ObjectInner, Owner},
-        Identifier, TypeTag,
+        storage::ObjectStore,
+        Identifier, TypeTag, MOVE_STDLIB_PACKAGE_ID, SUI_FRAMEWORK_PACKAGE_ID,
     };
 
     use super::*;
@@ -498,4 +594,62 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_render_dynamic_field_name() {
+        let sim = Simulacrum::new();
+        let modules = InMemoryStorage::new(
+            [MOVE_STDLIB_PACKAGE_ID, SUI_FRAMEWORK_PACKAGE_ID]
+                .iter()
+                .map(|id| sim.store().get_object(id).unwrap().unwrap())
+                .collect(),
+        );
+
+        // A dynamic field of type u64 -> u64, named 7.
+        let field = Field {
+            id: UID::new(ObjectID::random()),
+            name: 7u64,
+            value: 8u64,
+        };
+        let field_type = DynamicFieldInfo::dynamic_field_type(TypeTag::U64, TypeTag::U64);
+        let object = Object::new_move(
+            unsafe {
+                MoveObject::new_from_execution_with_limit(
+                    field_type.into(),
+                    false,
+                    1.into(),
+                    bcs::to_bytes(&field).unwrap(),
+                    256,
+                )
+            }
+            .unwrap(),
+            Owner::ObjectOwner(ObjectID::random().into()),
+            TransactionDigest::genesis_marker(),
+        );
+        let df_info = DynamicFieldInfo {
+            name: DynamicFieldName {
+                type_: TypeTag::U64,
+                value: SuiMoveValue::from(MoveValue::U64(7)).to_json_value(),
+            },
+            bcs_name: bcs::to_bytes(&7u64).unwrap(),
+            type_: DynamicFieldType::DynamicField,
+            object_type: "u64".to_string(),
+            object_id: object.id(),
+            version: object.version(),
+            digest: object.digest(),
+        };
+        let stored_obj = StoredObject::from(IndexedObject::from_object(1, object, Some(df_info)));
+
+        // Stored without its parsed JSON, the name is rendered as the writer would have parsed it.
+        let stripped = stored_obj.clone().without_parsed_json();
+        assert!(stripped.df_name.is_none());
+        let name = stripped.render_dynamic_field_name(&modules).unwrap();
+        assert_eq!(Some(bcs::to_bytes(&name).unwrap()), stored_obj.df_name);
+
+        let info = stripped
+            .try_into_expectant_dynamic_field_info(&modules)
+            .unwrap();
+        assert_eq!(info.name.type_, TypeTag::U64);
+        assert_eq!(info.name.value, name.value);
+    }
 }
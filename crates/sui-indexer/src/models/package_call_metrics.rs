@@ -0,0 +1,197 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Text};
+
+use sui_json_rpc_types::{FunctionCallMetrics, PackageMetrics};
+use sui_types::base_types::ObjectID;
+use sui_types::gas::GasCostSummary;
+
+use crate::schema::package_call_metrics;
+
+/// The module and function name of a package's own row, which counts each transaction calling
+/// the package once. Move identifiers are never empty, so these can't clash with a function's.
+pub const PACKAGE_ROW: &str = "";
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = package_call_metrics)]
+pub struct StoredPackageCallMetrics {
+    pub package: Vec<u8>,
+    pub module: String,
+    pub function: String,
+    pub checkpoint_sequence_number: i64,
+    pub epoch: i64,
+    pub call_count: i64,
+    pub transaction_count: i64,
+    pub computation_cost: i64,
+    pub storage_cost: i64,
+    pub storage_rebate: i64,
+    pub non_refundable_storage_fee: i64,
+}
+
+impl StoredPackageCallMetrics {
+    fn add(&mut self, calls: i64, gas: &GasCostSummary) {
+        let add = |total: &mut i64, cost: u64| {
+            *total = total.saturating_add(cost.min(i64::MAX as u64) as i64)
+        };
+        self.call_count += calls;
+        self.transaction_count += 1;
+        add(&mut self.computation_cost, gas.computation_cost);
+        add(&mut self.storage_cost, gas.storage_cost);
+        add(&mut self.storage_rebate, gas.storage_rebate);
+        add(
+            &mut self.non_refundable_storage_fee,
+            gas.non_refundable_storage_fee,
+        );
+    }
+}
+
+/// The calls made to each function in a checkpoint, `checkpoint_seq` of `epoch`, by transactions
+/// `txs`, given as the Move calls each makes and the gas it paid, and the row of each package
+/// called (see [`PACKAGE_ROW`]).
+pub fn package_call_metrics<'a>(
+    checkpoint_seq: u64,
+    epoch: u64,
+    txs: impl IntoIterator<Item = (Vec<(ObjectID, &'a str, &'a str)>, &'a GasCostSummary)>,
+) -> Vec<StoredPackageCallMetrics> {
+    let mut metrics: BTreeMap<(ObjectID, &str, &str), StoredPackageCallMetrics> = BTreeMap::new();
+    for (calls, gas) in txs {
+        let mut counts: BTreeMap<(ObjectID, &str, &str), i64> = BTreeMap::new();
+        for (package, module, function) in calls {
+            *counts.entry((package, module, function)).or_default() += 1;
+            *counts
+                .entry((package, PACKAGE_ROW, PACKAGE_ROW))
+                .or_default() += 1;
+        }
+        for (key, calls) in counts {
+            metrics
+                .entry(key)
+                .or_insert_with(|| StoredPackageCallMetrics {
+                    package: key.0.to_vec(),
+                    module: key.1.to_string(),
+                    function: key.2.to_string(),
+                    checkpoint_sequence_number: checkpoint_seq as i64,
+                    epoch: epoch as i64,
+                    call_count: 0,
+                    transaction_count: 0,
+                    computation_cost: 0,
+                    storage_cost: 0,
+                    storage_rebate: 0,
+                    non_refundable_storage_fee: 0,
+                })
+                .add(calls, gas);
+        }
+    }
+    metrics.into_values().collect()
+}
+
+/// A function's, or a package's, calls summed over a range of epochs.
+#[derive(QueryableByName, Debug)]
+pub struct QueriedCallMetrics {
+    #[diesel(sql_type = Text)]
+    pub module: String,
+    #[diesel(sql_type = Text)]
+    pub function: String,
+    #[diesel(sql_type = BigInt)]
+    pub call_count: i64,
+    #[diesel(sql_type = BigInt)]
+    pub transaction_count: i64,
+    #[diesel(sql_type = BigInt)]
+    pub computation_cost: i64,
+    #[diesel(sql_type = BigInt)]
+    pub storage_cost: i64,
+    #[diesel(sql_type = BigInt)]
+    pub storage_rebate: i64,
+    #[diesel(sql_type = BigInt)]
+    pub non_refundable_storage_fee: i64,
+}
+
+impl QueriedCallMetrics {
+    fn gas_used(&self) -> GasCostSummary {
+        GasCostSummary::new(
+            self.computation_cost as u64,
+            self.storage_cost as u64,
+            self.storage_rebate as u64,
+            self.non_refundable_storage_fee as u64,
+        )
+    }
+}
+
+/// The metrics of `package_id` from `start_epoch` to `end_epoch`, out of the sums of its rows,
+/// `queried`, keeping the order of its functions.
+pub fn package_metrics(
+    package_id: ObjectID,
+    start_epoch: u64,
+    end_epoch: u64,
+    queried: Vec<QueriedCallMetrics>,
+) -> PackageMetrics {
+    let mut metrics = PackageMetrics {
+        package_id,
+        start_epoch,
+        end_epoch,
+        call_count: 0,
+        transaction_count: 0,
+        gas_used: GasCostSummary::default(),
+        functions: vec![],
+    };
+    for q in queried {
+        if q.module == PACKAGE_ROW && q.function == PACKAGE_ROW {
+            metrics.call_count = q.call_count as u64;
+            metrics.transaction_count = q.transaction_count as u64;
+            metrics.gas_used = q.gas_used();
+        } else {
+            metrics.functions.push(FunctionCallMetrics {
+                gas_used: q.gas_used(),
+                call_count: q.call_count as u64,
+                transaction_count: q.transaction_count as u64,
+                module: q.module,
+                function: q.function,
+            });
+        }
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_call_metrics() {
+        let (p, q) = (ObjectID::random(), ObjectID::random());
+        let gas = |cost| GasCostSummary::new(cost, 10, 5, 1);
+        let (gas_1, gas_2) = (gas(100), gas(200));
+        let txs = vec![
+            (vec![(p, "m", "f"), (p, "m", "f"), (p, "m", "g")], &gas_1),
+            (vec![(p, "m", "f"), (q, "n", "h")], &gas_2),
+        ];
+
+        let metrics = package_call_metrics(7, 1, txs);
+        let find = |package: ObjectID, module: &str, function: &str| {
+            metrics
+                .iter()
+                .find(|m| {
+                    m.package == package.to_vec() && m.module == module && m.function == function
+                })
+                .unwrap()
+        };
+        assert_eq!(metrics.len(), 5);
+
+        let f = find(p, "m", "f");
+        assert_eq!((f.call_count, f.transaction_count), (3, 2));
+        assert_eq!((f.computation_cost, f.storage_cost), (300, 20));
+        assert_eq!((f.checkpoint_sequence_number, f.epoch), (7, 1));
+
+        // The package's row counts each transaction, and its gas, once.
+        let package = find(p, PACKAGE_ROW, PACKAGE_ROW);
+        assert_eq!((package.call_count, package.transaction_count), (4, 2));
+        assert_eq!(package.computation_cost, 300);
+
+        let g = find(p, "m", "g");
+        assert_eq!((g.call_count, g.computation_cost), (1, 100));
+        assert_eq!(find(q, PACKAGE_ROW, PACKAGE_ROW).computation_cost, 200);
+    }
+}
@@ -1,5 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use std::collections::HashMap;
+
+use diesel::deserialize::{self, FromStaticSqlRow};
+use diesel::pg::Pg;
 use diesel::prelude::*;
 
 use move_bytecode_utils::module_cache::GetModule;
@@ -10,19 +14,23 @@ use sui_json_rpc_types::SuiTransactionBlockEffects;
 use sui_json_rpc_types::SuiTransactionBlockEvents;
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_json_rpc_types::{ObjectTransaction, ObjectTransactionKind};
+use sui_json_rpc_types::{TransactionInputObject, TransactionInputObjectKind};
+use sui_types::base_types::ObjectID;
 use sui_types::digests::TransactionDigest;
-use sui_types::effects::TransactionEffects;
 use sui_types::effects::TransactionEvents;
+use sui_types::effects::{InputSharedObject, TransactionEffects, TransactionEffectsAPI};
 use sui_types::event::Event;
-use sui_types::transaction::SenderSignedData;
+use sui_types::transaction::{InputObjectKind, SenderSignedData, TransactionDataAPI};
 
+use crate::compression::{decompress, decompress_all, Compression, UNCOMPRESSED};
 use crate::errors::IndexerError;
 use crate::schema::transactions;
 use crate::types::IndexedObjectChange;
 use crate::types::IndexedTransaction;
 use crate::types::IndexerResult;
 
-#[derive(Clone, Debug, Queryable, Insertable, QueryableByName)]
+#[derive(Clone, Debug, Insertable)]
 #[diesel(table_name = transactions)]
 pub struct StoredTransaction {
     pub tx_sequence_number: i64,
@@ -36,6 +44,63 @@ pub struct StoredTransaction {
     pub events: Vec<Option<Vec<u8>>>,
     pub transaction_kind: i16,
     pub success_command_count: i16,
+    /// How `raw_transaction`, `raw_effects` and `events` are compressed. Transactions are
+    /// decompressed as they are loaded, so this is only ever set on those that are stored.
+    pub compression: i16,
+}
+
+type StoredTransactionRow = (
+    i64,
+    Vec<u8>,
+    Vec<u8>,
+    Vec<u8>,
+    i64,
+    i64,
+    Vec<Option<Vec<u8>>>,
+    Vec<Option<Vec<u8>>>,
+    Vec<Option<Vec<u8>>>,
+    i16,
+    i16,
+    i16,
+);
+
+impl<ST> Queryable<ST, Pg> for StoredTransaction
+where
+    StoredTransactionRow: FromStaticSqlRow<ST, Pg>,
+{
+    type Row = StoredTransactionRow;
+
+    fn build(row: Self::Row) -> deserialize::Result<Self> {
+        let (
+            tx_sequence_number,
+            transaction_digest,
+            raw_transaction,
+            raw_effects,
+            checkpoint_sequence_number,
+            timestamp_ms,
+            object_changes,
+            balance_changes,
+            events,
+            transaction_kind,
+            success_command_count,
+            compression,
+        ) = row;
+        let transaction = Self {
+            tx_sequence_number,
+            transaction_digest,
+            raw_transaction,
+            raw_effects,
+            checkpoint_sequence_number,
+            timestamp_ms,
+            object_changes,
+            balance_changes,
+            events,
+            transaction_kind,
+            success_command_count,
+            compression,
+        };
+        Ok(transaction.decompressed()?)
+    }
 }
 
 #[derive(Debug, Queryable)]
@@ -55,6 +120,35 @@ pub struct StoredTransactionTimestamp {
     pub timestamp_ms: i64,
 }
 
+#[derive(Clone, Debug, Queryable)]
+pub struct StoredTransactionBalanceChanges {
+    pub tx_sequence_number: i64,
+    pub timestamp_ms: i64,
+    pub balance_changes: Vec<Option<Vec<u8>>>,
+}
+
+#[derive(Clone, Debug, Queryable)]
+pub struct StoredRawTransaction {
+    pub tx_sequence_number: i64,
+    pub raw_transaction: Vec<u8>,
+    pub compression: i16,
+}
+
+impl StoredRawTransaction {
+    /// The gas price that the sender set for the transaction.
+    pub fn gas_price(&self) -> IndexerResult<u64> {
+        let raw_transaction = decompress(self.compression, self.raw_transaction.clone())?;
+        let sender_signed_data: SenderSignedData =
+            bcs::from_bytes(&raw_transaction).map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Can't convert raw_transaction of {} into SenderSignedData. Error: {e}",
+                    self.tx_sequence_number
+                ))
+            })?;
+        Ok(sender_signed_data.transaction_data().gas_price())
+    }
+}
+
 #[derive(Clone, Debug, Queryable)]
 pub struct StoredTransactionCheckpoint {
     pub tx_sequence_number: i64,
@@ -95,11 +189,34 @@ impl From<&IndexedTransaction> for StoredTransaction {
             timestamp_ms: tx.timestamp_ms as i64,
             transaction_kind: tx.transaction_kind.clone() as i16,
             success_command_count: tx.successful_tx_num as i16,
+            compression: UNCOMPRESSED,
         }
     }
 }
 
 impl StoredTransaction {
+    /// The transaction, with its payloads compressed with `compression`.
+    pub fn compressed(self, compression: Compression) -> Self {
+        debug_assert_eq!(self.compression, UNCOMPRESSED);
+        Self {
+            raw_transaction: compression.compress(&self.raw_transaction),
+            raw_effects: compression.compress(&self.raw_effects),
+            events: compression.compress_all(&self.events),
+            compression: compression as i16,
+            ..self
+        }
+    }
+
+    fn decompressed(self) -> IndexerResult<Self> {
+        Ok(Self {
+            raw_transaction: decompress(self.compression, self.raw_transaction)?,
+            raw_effects: decompress(self.compression, self.raw_effects)?,
+            events: decompress_all(self.compression, self.events)?,
+            compression: UNCOMPRESSED,
+            ..self
+        })
+    }
+
     pub fn try_into_sui_transaction_block_response(
         self,
         options: &SuiTransactionBlockResponseOptions,
@@ -162,7 +279,7 @@ impl StoredTransaction {
                 Some(timestamp),
                 module,
             )?;
-            Some(tx_events)
+            Some(options.project_events(tx_events))
         } else {
             None
         };
@@ -218,6 +335,7 @@ impl StoredTransaction {
             confirmed_local_execution: None,
             errors: vec![],
             raw_effects: self.raw_effects,
+            labels: None,
         })
     }
 
@@ -232,6 +350,134 @@ impl StoredTransaction {
         Ok(sender_signed_data)
     }
 
+    /// How this transaction changed object `object_id`, according to its effects, or `None` if
+    /// it didn't.
+    pub fn try_into_object_transaction(
+        &self,
+        object_id: ObjectID,
+    ) -> IndexerResult<Option<ObjectTransaction>> {
+        let effects: TransactionEffects = bcs::from_bytes(&self.raw_effects).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Can't convert raw_effects of {} into TransactionEffects. Error: {e}",
+                self.tx_sequence_number
+            ))
+        })?;
+
+        let written = [
+            (ObjectTransactionKind::Created, effects.created()),
+            (ObjectTransactionKind::Mutated, effects.mutated()),
+            (ObjectTransactionKind::Unwrapped, effects.unwrapped()),
+        ]
+        .into_iter()
+        .flat_map(|(kind, objects)| objects.into_iter().map(move |(oref, _)| (kind, oref)));
+        let removed = [
+            (ObjectTransactionKind::Wrapped, effects.wrapped()),
+            (ObjectTransactionKind::Deleted, effects.deleted()),
+            (
+                ObjectTransactionKind::UnwrappedThenDeleted,
+                effects.unwrapped_then_deleted(),
+            ),
+        ]
+        .into_iter()
+        .flat_map(|(kind, orefs)| orefs.into_iter().map(move |oref| (kind, oref)));
+        let Some((kind, (_, version, _))) = written
+            .chain(removed)
+            .find(|(_, (id, _, _))| *id == object_id)
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(ObjectTransaction {
+            digest: *effects.transaction_digest(),
+            kind,
+            version,
+            checkpoint: self.checkpoint_sequence_number as u64,
+            timestamp_ms: self.timestamp_ms as u64,
+        }))
+    }
+
+    /// The objects this transaction took as input, at the versions it read them.
+    pub fn try_into_input_objects(&self) -> IndexerResult<Vec<TransactionInputObject>> {
+        let sender_signed_data = self.try_into_sender_signed_data()?;
+        let effects: TransactionEffects = bcs::from_bytes(&self.raw_effects).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Can't convert raw_effects of {} into TransactionEffects. Error: {e}",
+                self.tx_sequence_number
+            ))
+        })?;
+        let tx = sender_signed_data.transaction_data();
+
+        // Shared objects are only assigned versions during consensus, so they are recorded in
+        // the effects rather than the transaction.
+        let shared_objects: HashMap<_, _> = effects
+            .input_shared_objects()
+            .into_iter()
+            .map(|shared| match shared {
+                InputSharedObject::Mutate((id, version, digest))
+                | InputSharedObject::ReadOnly((id, version, digest)) => {
+                    (id, (version, Some(digest)))
+                }
+                InputSharedObject::ReadDeleted(id, version)
+                | InputSharedObject::MutateDeleted(id, version) => (id, (version, None)),
+            })
+            .collect();
+
+        let input_objects = tx.input_objects().map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Can't get input objects of {}. Error: {e}",
+                self.tx_sequence_number
+            ))
+        })?;
+
+        let mut result = input_objects
+            .into_iter()
+            .map(|input| match input {
+                InputObjectKind::MovePackage(id) => TransactionInputObject {
+                    object_id: id,
+                    version: None,
+                    digest: None,
+                    kind: TransactionInputObjectKind::MovePackage,
+                },
+                InputObjectKind::ImmOrOwnedMoveObject((id, version, digest)) => {
+                    TransactionInputObject {
+                        object_id: id,
+                        version: Some(version),
+                        digest: Some(digest),
+                        kind: TransactionInputObjectKind::ImmOrOwnedMoveObject,
+                    }
+                }
+                InputObjectKind::SharedMoveObject { id, mutable, .. } => {
+                    let (version, digest) = shared_objects
+                        .get(&id)
+                        .map_or((None, None), |(v, d)| (Some(*v), *d));
+                    TransactionInputObject {
+                        object_id: id,
+                        version,
+                        digest,
+                        kind: if mutable {
+                            TransactionInputObjectKind::MutableSharedMoveObject
+                        } else {
+                            TransactionInputObjectKind::ReadOnlySharedMoveObject
+                        },
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        result.extend(
+            tx.receiving_objects()
+                .into_iter()
+                .map(|(id, version, digest)| TransactionInputObject {
+                    object_id: id,
+                    version: Some(version),
+                    digest: Some(digest),
+                    kind: TransactionInputObjectKind::Receiving,
+                }),
+        );
+
+        Ok(result)
+    }
+
     pub fn try_into_sui_transaction_effects(&self) -> IndexerResult<SuiTransactionBlockEffects> {
         let effects: TransactionEffects = bcs::from_bytes(&self.raw_effects).map_err(|e| {
             IndexerError::PersistentStorageDataCorruptionError(format!(
@@ -243,3 +489,50 @@ impl StoredTransaction {
         Ok(effects)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simulacrum::Simulacrum;
+    use sui_types::base_types::SuiAddress;
+
+    #[test]
+    fn test_input_objects() {
+        let mut sim = Simulacrum::new();
+        let (transaction, _) = sim.transfer_txn(SuiAddress::random_for_testing_only());
+        let (effects, err) = sim.execute_transaction(transaction.clone()).unwrap();
+        assert!(err.is_none());
+
+        let stored = StoredTransaction {
+            tx_sequence_number: 1,
+            transaction_digest: effects.transaction_digest().inner().to_vec(),
+            raw_transaction: bcs::to_bytes(transaction.data()).unwrap(),
+            raw_effects: bcs::to_bytes(&effects).unwrap(),
+            checkpoint_sequence_number: 1,
+            timestamp_ms: 0,
+            object_changes: vec![],
+            balance_changes: vec![],
+            events: vec![],
+            transaction_kind: 1,
+            success_command_count: 2,
+            compression: UNCOMPRESSED,
+        };
+
+        // The transfer only reads its gas coin, at the version it was paid with.
+        let gas = transaction.data().transaction_data().gas().to_vec();
+        let inputs: Vec<_> = stored
+            .try_into_input_objects()
+            .unwrap()
+            .into_iter()
+            .map(|input| {
+                assert_eq!(input.kind, TransactionInputObjectKind::ImmOrOwnedMoveObject);
+                (
+                    input.object_id,
+                    input.version.unwrap(),
+                    input.digest.unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(inputs, gas);
+    }
+}
@@ -2,10 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    schema::{tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders},
+    schema::{
+        tx_calls, tx_changed_objects, tx_classes, tx_input_objects, tx_recipients, tx_senders,
+    },
     types::TxIndex,
 };
 use diesel::prelude::*;
+use sui_json_rpc_types::TransactionClass;
 
 #[derive(QueryableByName)]
 pub struct TxSequenceNumber {
@@ -56,6 +59,27 @@ pub struct StoredTxCalls {
     pub func: String,
 }
 
+#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[diesel(table_name = tx_classes)]
+pub struct StoredTxClass {
+    pub tx_sequence_number: i64,
+    pub class: i16,
+}
+
+/// The code that `class` is stored as in `tx_classes`. Codes are persisted, so must never change.
+pub fn class_code(class: TransactionClass) -> i16 {
+    match class {
+        TransactionClass::Transfer => 0,
+        TransactionClass::Publish => 1,
+        TransactionClass::Upgrade => 2,
+        TransactionClass::Stake => 3,
+        TransactionClass::DeepbookTrade => 4,
+        TransactionClass::Sponsored => 5,
+        TransactionClass::Multisig => 6,
+        TransactionClass::ZkLogin => 7,
+    }
+}
+
 #[allow(clippy::type_complexity)]
 impl TxIndex {
     pub fn split(
@@ -66,6 +90,7 @@ impl TxIndex {
         Vec<StoredTxInputObject>,
         Vec<StoredTxChangedObject>,
         Vec<StoredTxCalls>,
+        Vec<StoredTxClass>,
     ) {
         let tx_sequence_number = self.tx_sequence_number as i64;
         let tx_senders = self
@@ -110,12 +135,21 @@ impl TxIndex {
                 func: f.to_string(),
             })
             .collect();
+        let tx_classes = self
+            .classes
+            .iter()
+            .map(|c| StoredTxClass {
+                tx_sequence_number,
+                class: class_code(*c),
+            })
+            .collect();
         (
             tx_senders,
             tx_recipients,
             tx_input_objects,
             tx_changed_objects,
             tx_calls,
+            tx_classes,
         )
     }
 }
@@ -0,0 +1,108 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use sui_json_rpc::governance_api::calculate_apy;
+use sui_json_rpc_types::{EpochApy, ValidatorApyHistory};
+use sui_types::base_types::SuiAddress;
+use sui_types::sui_system_state::PoolTokenExchangeRate;
+
+use crate::errors::IndexerError;
+use crate::schema::validator_apys;
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = validator_apys)]
+pub struct StoredValidatorApy {
+    pub epoch: i64,
+    pub validator_address: Vec<u8>,
+    pub apy: f64,
+}
+
+impl StoredValidatorApy {
+    /// The APY of `validator`'s staking pool in `epoch`, from the pool's exchange rates at the
+    /// start of `epoch` and at the start of the next epoch. `None` if the rates don't make for a
+    /// finite APY.
+    pub fn new(
+        epoch: i64,
+        validator: SuiAddress,
+        rates: (PoolTokenExchangeRate, PoolTokenExchangeRate),
+    ) -> Option<Self> {
+        let apy = calculate_apy(rates);
+        apy.is_finite().then(|| Self {
+            epoch,
+            validator_address: validator.to_vec(),
+            apy,
+        })
+    }
+}
+
+/// Group `apys`, ordered by validator and then by epoch, into each validator's history.
+pub fn validator_apy_histories(
+    apys: Vec<StoredValidatorApy>,
+) -> Result<Vec<ValidatorApyHistory>, IndexerError> {
+    let mut histories: Vec<ValidatorApyHistory> = vec![];
+    for apy in apys {
+        let address = SuiAddress::from_bytes(&apy.validator_address).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to parse validator address {:?} of APY: {e}",
+                apy.validator_address
+            ))
+        })?;
+        let epoch_apy = EpochApy {
+            epoch: apy.epoch as u64,
+            apy: apy.apy,
+        };
+        match histories.last_mut() {
+            Some(history) if history.address == address => history.apys.push(epoch_apy),
+            _ => histories.push(ValidatorApyHistory {
+                address,
+                apys: vec![epoch_apy],
+            }),
+        }
+    }
+    Ok(histories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validator_apy_histories() {
+        let (a, b) = (SuiAddress::random_for_testing_only(), SuiAddress::ZERO);
+        let apy = |address: SuiAddress, epoch: i64, apy: f64| StoredValidatorApy {
+            epoch,
+            validator_address: address.to_vec(),
+            apy,
+        };
+
+        let histories =
+            validator_apy_histories(vec![apy(a, 3, 0.05), apy(a, 4, 0.06), apy(b, 4, 0.04)])
+                .unwrap();
+        assert_eq!(histories.len(), 2);
+        assert_eq!(histories[0].address, a);
+        assert_eq!(
+            histories[0].apys,
+            vec![
+                EpochApy {
+                    epoch: 3,
+                    apy: 0.05
+                },
+                EpochApy {
+                    epoch: 4,
+                    apy: 0.06
+                },
+            ]
+        );
+        assert_eq!(histories[1].address, b);
+        assert_eq!(histories[1].apys.len(), 1);
+
+        assert!(validator_apy_histories(vec![StoredValidatorApy {
+            epoch: 0,
+            validator_address: vec![1, 2, 3],
+            apy: 0.0,
+        }])
+        .is_err());
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use diesel::prelude::*;
+use move_core_types::ident_str;
+use move_core_types::identifier::IdentStr;
+use move_core_types::language_storage::StructTag;
+use serde::Deserialize;
+
+use sui_json_rpc_types::ValidatorEpochHistory;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::committee::QUORUM_THRESHOLD;
+use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_types::SUI_SYSTEM_ADDRESS;
+
+use crate::errors::IndexerError;
+use crate::models::events::StoredEvent;
+use crate::schema::validator_history;
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = validator_history)]
+pub struct StoredValidatorHistory {
+    pub epoch: i64,
+    pub validator_address: Vec<u8>,
+    pub commission_rate: i64,
+    pub next_commission_rate: Option<i64>,
+    pub stake_inflow: i64,
+    pub stake_outflow: i64,
+    pub reported_by: Vec<Option<Vec<u8>>>,
+    pub slashed: bool,
+    pub at_risk_epochs: Option<i64>,
+}
+
+/// SUI staked with and unstaked from a validator in an epoch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StakeFlow {
+    pub inflow: u64,
+    pub outflow: u64,
+}
+
+/// The contents of a `0x3::validator::StakingRequestEvent`.
+#[derive(Deserialize)]
+struct StakingRequestEvent {
+    _pool_id: ObjectID,
+    validator_address: SuiAddress,
+    _staker_address: SuiAddress,
+    _epoch: u64,
+    amount: u64,
+}
+
+/// The contents of a `0x3::validator::UnstakingRequestEvent`.
+#[derive(Deserialize)]
+struct UnstakingRequestEvent {
+    _pool_id: ObjectID,
+    validator_address: SuiAddress,
+    _staker_address: SuiAddress,
+    _stake_activation_epoch: u64,
+    _unstaking_epoch: u64,
+    principal_amount: u64,
+    reward_amount: u64,
+}
+
+fn validator_event_type(name: &IdentStr) -> String {
+    StructTag {
+        address: SUI_SYSTEM_ADDRESS,
+        module: ident_str!("validator").to_owned(),
+        name: name.to_owned(),
+        type_params: vec![],
+    }
+    .to_canonical_string(/* with_prefix */ true)
+}
+
+/// The `event_type`s of the events that stake flows are tallied from.
+pub fn stake_event_types() -> Vec<String> {
+    vec![
+        validator_event_type(ident_str!("StakingRequestEvent")),
+        validator_event_type(ident_str!("UnstakingRequestEvent")),
+    ]
+}
+
+/// Tally the staking and unstaking requests among `events` into each validator's stake flow.
+pub fn stake_flows(events: &[StoredEvent]) -> Result<HashMap<SuiAddress, StakeFlow>, IndexerError> {
+    let staking_type = validator_event_type(ident_str!("StakingRequestEvent"));
+    let unstaking_type = validator_event_type(ident_str!("UnstakingRequestEvent"));
+    let malformed = |e: bcs::Error| {
+        IndexerError::PersistentStorageDataCorruptionError(format!(
+            "Failed to deserialize staking event: {e}"
+        ))
+    };
+
+    let mut flows: HashMap<SuiAddress, StakeFlow> = HashMap::new();
+    for event in events {
+        if event.event_type == staking_type {
+            let staked: StakingRequestEvent = bcs::from_bytes(&event.bcs).map_err(malformed)?;
+            let flow = flows.entry(staked.validator_address).or_default();
+            flow.inflow = flow.inflow.saturating_add(staked.amount);
+        } else if event.event_type == unstaking_type {
+            let unstaked: UnstakingRequestEvent = bcs::from_bytes(&event.bcs).map_err(malformed)?;
+            let flow = flows.entry(unstaked.validator_address).or_default();
+            flow.outflow = flow
+                .outflow
+                .saturating_add(unstaked.principal_amount)
+                .saturating_add(unstaked.reward_amount);
+        }
+    }
+    Ok(flows)
+}
+
+/// The history in `epoch` of each validator active in it, from the system state at the start of
+/// the epoch, `start`, and at its end (i.e. the start of the next epoch), `end`.
+pub fn validator_histories(
+    epoch: i64,
+    start: &SuiSystemStateSummary,
+    end: &SuiSystemStateSummary,
+    flows: &HashMap<SuiAddress, StakeFlow>,
+) -> Vec<StoredValidatorHistory> {
+    let voting_power: HashMap<_, _> = start
+        .active_validators
+        .iter()
+        .map(|v| (v.sui_address, v.voting_power))
+        .collect();
+    let next_commission_rate: HashMap<_, _> = end
+        .active_validators
+        .iter()
+        .map(|v| (v.sui_address, v.commission_rate))
+        .collect();
+    let reports: HashMap<_, _> = end.validator_report_records.iter().cloned().collect();
+    let at_risk: HashMap<_, _> = start.at_risk_validators.iter().cloned().collect();
+
+    start
+        .active_validators
+        .iter()
+        .map(|validator| {
+            let address = validator.sui_address;
+            let reported_by = reports.get(&address).cloned().unwrap_or_default();
+            let reporter_votes: u64 = reported_by
+                .iter()
+                .filter_map(|reporter| voting_power.get(reporter))
+                .sum();
+            let flow = flows.get(&address).copied().unwrap_or_default();
+
+            StoredValidatorHistory {
+                epoch,
+                validator_address: address.to_vec(),
+                commission_rate: validator.commission_rate as i64,
+                next_commission_rate: next_commission_rate.get(&address).map(|r| *r as i64),
+                stake_inflow: flow.inflow.min(i64::MAX as u64) as i64,
+                stake_outflow: flow.outflow.min(i64::MAX as u64) as i64,
+                reported_by: reported_by.iter().map(|r| Some(r.to_vec())).collect(),
+                slashed: reporter_votes >= QUORUM_THRESHOLD,
+                at_risk_epochs: at_risk.get(&address).map(|e| *e as i64),
+            }
+        })
+        .collect()
+}
+
+impl TryFrom<StoredValidatorHistory> for ValidatorEpochHistory {
+    type Error = IndexerError;
+
+    fn try_from(history: StoredValidatorHistory) -> Result<Self, Self::Error> {
+        let reported_by = history
+            .reported_by
+            .into_iter()
+            .flatten()
+            .map(|reporter| {
+                SuiAddress::from_bytes(&reporter).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Failed to parse reporter {reporter:?} of validator history: {e}"
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(ValidatorEpochHistory {
+            epoch: history.epoch as u64,
+            commission_rate: history.commission_rate as u64,
+            next_commission_rate: history.next_commission_rate.map(|r| r as u64),
+            stake_inflow: history.stake_inflow as u64,
+            stake_outflow: history.stake_outflow as u64,
+            reported_by,
+            slashed: history.slashed,
+            at_risk_epochs: history.at_risk_epochs.map(|e| e as u64),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::sui_system_state::sui_system_state_summary::SuiValidatorSummary;
+
+    use super::*;
+
+    fn validator(
+        address: SuiAddress,
+        voting_power: u64,
+        commission_rate: u64,
+    ) -> SuiValidatorSummary {
+        SuiValidatorSummary {
+            sui_address: address,
+            voting_power,
+            commission_rate,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validator_histories() {
+        let [a, b, c] = [0u8, 1, 2].map(|i| SuiAddress::from_bytes([i; 32]).unwrap());
+        let start = SuiSystemStateSummary {
+            active_validators: vec![
+                validator(a, 5_000, 200),
+                validator(b, 3_000, 100),
+                validator(c, 2_000, 100),
+            ],
+            at_risk_validators: vec![(c, 2)],
+            ..Default::default()
+        };
+        // `c` left at the end of the epoch, and `b` raised its commission.
+        let end = SuiSystemStateSummary {
+            active_validators: vec![validator(a, 6_000, 200), validator(b, 4_000, 300)],
+            validator_report_records: vec![(b, vec![a, c])],
+            ..Default::default()
+        };
+        let flows = HashMap::from([(
+            a,
+            StakeFlow {
+                inflow: 10,
+                outflow: 3,
+            },
+        )]);
+
+        let histories = validator_histories(7, &start, &end, &flows);
+        assert_eq!(histories.len(), 3);
+
+        let a_history = ValidatorEpochHistory::try_from(histories[0].clone()).unwrap();
+        assert_eq!(a_history.epoch, 7);
+        assert_eq!(a_history.next_commission_rate, Some(200));
+        assert_eq!((a_history.stake_inflow, a_history.stake_outflow), (10, 3));
+        assert!(a_history.reported_by.is_empty());
+        assert!(!a_history.slashed);
+
+        let b_history = ValidatorEpochHistory::try_from(histories[1].clone()).unwrap();
+        assert_eq!(b_history.commission_rate, 100);
+        assert_eq!(b_history.next_commission_rate, Some(300));
+        // `a` and `c` hold a quorum of the votes between them.
+        assert_eq!(b_history.reported_by, vec![a, c]);
+        assert!(b_history.slashed);
+        assert_eq!(b_history.at_risk_epochs, None);
+
+        let c_history = ValidatorEpochHistory::try_from(histories[2].clone()).unwrap();
+        assert_eq!(c_history.next_commission_rate, None);
+        assert_eq!(c_history.at_risk_epochs, Some(2));
+        assert_eq!((c_history.stake_inflow, c_history.stake_outflow), (0, 0));
+    }
+}
@@ -0,0 +1,365 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fiat valuations of the amounts of coins that the reader serves, in balances, stakes and dry
+//! runs, priced by the oracles configured in a YAML file, e.g.:
+//!
+//! ```yaml
+//! currency: USD
+//! precision: 2
+//! refresh-interval-secs: 60
+//! http:
+//!   url: "https://prices.example.com/sui"
+//! on-chain:
+//!   - coin-type: "0x2::sui::SUI"
+//!     object-id: "0x..."
+//!     field: "price.value"
+//!     exponent: -8
+//! ```
+//!
+//! The HTTP oracle responds with a JSON object of prices, in `currency`, by coin type, e.g.
+//! `{"0x2::sui::SUI": 1.25}`. On-chain feeds read the price of a coin from a field of an object,
+//! scaled by `10^exponent`, and take precedence over the HTTP oracle. Prices are refreshed in the
+//! background, and a valuation is left out of a response if its coin has no price.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use mysten_metrics::spawn_monitored_task;
+use sui_json_rpc_types::{FiatValue, SuiParsedMoveObject};
+use sui_types::base_types::ObjectID;
+use sui_types::gas_coin::GAS;
+use sui_types::parse_sui_struct_tag;
+
+use crate::errors::IndexerError;
+use crate::indexer_reader::IndexerReader;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PriceOracleConfig {
+    /// The fiat currency that prices are in, e.g. `USD`.
+    pub currency: String,
+    /// Number of decimal places that valuations are rounded to.
+    #[serde(default = "default_precision")]
+    pub precision: usize,
+    #[serde(default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+    pub http: Option<HttpPriceConfig>,
+    #[serde(default)]
+    pub on_chain: Vec<OnChainPriceFeed>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct HttpPriceConfig {
+    pub url: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct OnChainPriceFeed {
+    pub coin_type: String,
+    pub object_id: ObjectID,
+    /// Path to the price among the object's fields, with the names of nested fields separated by
+    /// `.`.
+    pub field: String,
+    #[serde(default)]
+    pub exponent: i32,
+}
+
+fn default_precision() -> usize {
+    2
+}
+
+fn default_refresh_interval_secs() -> u64 {
+    60
+}
+
+impl PriceOracleConfig {
+    pub fn parse(yaml: &str) -> Result<Self, IndexerError> {
+        serde_yaml::from_str(yaml).map_err(|e| {
+            IndexerError::InvalidArgumentError(format!("Invalid price oracle config: {e}"))
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, IndexerError> {
+        let yaml = std::fs::read_to_string(path).map_err(|e| {
+            IndexerError::GenericError(format!(
+                "Failed to read price oracle config file {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&yaml)
+    }
+}
+
+/// A source of the prices of coins.
+#[async_trait]
+pub trait PriceProvider: Send + Sync {
+    /// Prices of the coins this provider knows of, by coin type, which need not be normalized.
+    async fn fetch_prices(&self) -> Result<HashMap<String, f64>, IndexerError>;
+}
+
+/// Prices coins with the JSON object served at a URL.
+pub struct HttpPriceProvider {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpPriceProvider {
+    pub fn new(config: HttpPriceConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: config.url,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceProvider for HttpPriceProvider {
+    async fn fetch_prices(&self) -> Result<HashMap<String, f64>, IndexerError> {
+        let fetch = async {
+            self.client
+                .get(&self.url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<HashMap<String, f64>>()
+                .await
+        };
+        fetch.await.map_err(|e| {
+            IndexerError::GenericError(format!("Failed to fetch prices from {}: {e}", self.url))
+        })
+    }
+}
+
+/// Prices coins with the fields of oracle objects on chain, as indexed.
+pub struct OnChainPriceProvider {
+    reader: IndexerReader,
+    feeds: Vec<OnChainPriceFeed>,
+}
+
+impl OnChainPriceProvider {
+    pub fn new(reader: IndexerReader, feeds: Vec<OnChainPriceFeed>) -> Self {
+        Self { reader, feeds }
+    }
+}
+
+#[async_trait]
+impl PriceProvider for OnChainPriceProvider {
+    async fn fetch_prices(&self) -> Result<HashMap<String, f64>, IndexerError> {
+        let mut prices = HashMap::new();
+        for feed in &self.feeds {
+            let object_read = self
+                .reader
+                .get_object_read_in_blocking_task(feed.object_id)
+                .await?;
+            let object = SuiParsedMoveObject::try_from_object_read(object_read)?;
+            let fields = object.fields.to_json_value();
+            let price = read_price(&fields, &feed.field, feed.exponent).ok_or_else(|| {
+                IndexerError::DataTransformationError(format!(
+                    "Price oracle object {} has no numeric field {}",
+                    feed.object_id, feed.field
+                ))
+            })?;
+            prices.insert(feed.coin_type.clone(), price);
+        }
+        Ok(prices)
+    }
+}
+
+/// Values amounts of coins in a fiat currency, at the prices last fetched from its providers.
+#[derive(Clone)]
+pub struct PriceOracle {
+    reader: IndexerReader,
+    currency: String,
+    precision: usize,
+    /// Prices by normalized coin type.
+    prices: Arc<RwLock<HashMap<String, f64>>>,
+    /// Decimals of coins by normalized coin type, which never change once a coin is published.
+    decimals: Arc<RwLock<HashMap<String, u8>>>,
+}
+
+impl PriceOracle {
+    /// Create an oracle that refreshes its prices from `providers` every `refresh_interval`,
+    /// with the prices of later providers taking precedence.
+    pub fn start(
+        reader: IndexerReader,
+        currency: String,
+        precision: usize,
+        providers: Vec<Box<dyn PriceProvider>>,
+        refresh_interval: Duration,
+    ) -> Self {
+        let oracle = Self {
+            reader,
+            currency,
+            precision,
+            prices: Arc::new(RwLock::new(HashMap::new())),
+            decimals: Arc::new(RwLock::new(HashMap::new())),
+        };
+
+        let prices = oracle.prices.clone();
+        spawn_monitored_task!(async move {
+            // The prices last fetched from each provider, kept while a provider is unavailable.
+            let mut provided = vec![HashMap::new(); providers.len()];
+            let mut interval = tokio::time::interval(refresh_interval);
+            loop {
+                interval.tick().await;
+                for (provider, last) in providers.iter().zip(provided.iter_mut()) {
+                    match provider.fetch_prices().await {
+                        Ok(fetched) => *last = fetched,
+                        Err(e) => warn!("Failed to refresh prices: {e}"),
+                    }
+                }
+                *prices.write().unwrap() = provided
+                    .iter()
+                    .flatten()
+                    .filter_map(|(coin_type, price)| {
+                        Some((normalize_coin_type(coin_type)?, *price))
+                    })
+                    .collect();
+            }
+        });
+        oracle
+    }
+
+    pub fn from_config(reader: IndexerReader, config: PriceOracleConfig) -> Self {
+        let mut providers: Vec<Box<dyn PriceProvider>> = vec![];
+        if let Some(http) = config.http {
+            providers.push(Box::new(HttpPriceProvider::new(http)));
+        }
+        if !config.on_chain.is_empty() {
+            providers.push(Box::new(OnChainPriceProvider::new(
+                reader.clone(),
+                config.on_chain,
+            )));
+        }
+        Self::start(
+            reader,
+            config.currency,
+            config.precision,
+            providers,
+            Duration::from_secs(config.refresh_interval_secs),
+        )
+    }
+
+    /// The value of `amount` of the smallest unit of `coin_type`, or `None` if the coin has no
+    /// price or metadata.
+    pub async fn value(&self, coin_type: &str, amount: i128) -> Option<FiatValue> {
+        let coin_type = normalize_coin_type(coin_type)?;
+        let price = *self.prices.read().unwrap().get(&coin_type)?;
+        let decimals = self.decimals(&coin_type).await?;
+        Some(FiatValue {
+            currency: self.currency.clone(),
+            amount: fiat_amount(amount, decimals, price, self.precision),
+        })
+    }
+
+    /// The value of `amount` MIST.
+    pub async fn value_sui(&self, amount: i128) -> Option<FiatValue> {
+        self.value(
+            &GAS::type_().to_canonical_string(/* with_prefix */ true),
+            amount,
+        )
+        .await
+    }
+
+    async fn decimals(&self, coin_type: &str) -> Option<u8> {
+        if let Some(decimals) = self.decimals.read().unwrap().get(coin_type) {
+            return Some(*decimals);
+        }
+
+        let coin_struct = parse_sui_struct_tag(coin_type).ok()?;
+        let metadata = self
+            .reader
+            .get_coin_metadata_in_blocking_task(coin_struct)
+            .await
+            .map_err(|e| warn!("Failed to get metadata of coin {coin_type}: {e}"))
+            .ok()??;
+        self.decimals
+            .write()
+            .unwrap()
+            .insert(coin_type.to_string(), metadata.decimals);
+        Some(metadata.decimals)
+    }
+}
+
+fn normalize_coin_type(coin_type: &str) -> Option<String> {
+    parse_sui_struct_tag(coin_type)
+        .ok()
+        .map(|tag| tag.to_canonical_string(/* with_prefix */ true))
+}
+
+/// The price at the `.`-separated `path` among `fields`, scaled by `10^exponent`. Numbers that
+/// don't fit in a JSON number are rendered as strings, so either is accepted.
+fn read_price(fields: &Value, path: &str, exponent: i32) -> Option<f64> {
+    let value = path
+        .split('.')
+        .try_fold(fields, |value, field| value.get(field))?;
+    let price = match value {
+        Value::Number(n) => n.as_f64()?,
+        Value::String(s) => s.parse().ok()?,
+        _ => return None,
+    };
+    Some(price * 10f64.powi(exponent))
+}
+
+/// The value of `amount` of a coin's smallest unit, given its `decimals` and its `price` per
+/// whole coin, as a decimal string with `precision` decimal places.
+fn fiat_amount(amount: i128, decimals: u8, price: f64, precision: usize) -> String {
+    let value = amount as f64 / 10f64.powi(decimals as i32) * price;
+    format!("{value:.precision$}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_read_price() {
+        let fields = json!({
+            "price": { "value": "125000000", "timestamp": "1700000000" },
+            "spot": 2.5,
+        });
+        assert_eq!(read_price(&fields, "price.value", -8), Some(1.25));
+        assert_eq!(read_price(&fields, "spot", 0), Some(2.5));
+        assert_eq!(read_price(&fields, "price", 0), None);
+        assert_eq!(read_price(&fields, "price.missing", 0), None);
+    }
+
+    #[test]
+    fn test_fiat_amount() {
+        assert_eq!(fiat_amount(1_500_000_000, 9, 1.2, 2), "1.80");
+        assert_eq!(fiat_amount(-2_000_000, 6, 0.5, 2), "-1.00");
+        assert_eq!(fiat_amount(0, 9, 1.25, 4), "0.0000");
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = PriceOracleConfig::parse(
+            r#"
+            currency: USD
+            http:
+              url: "https://prices.example.com/sui"
+            on-chain:
+              - coin-type: "0x2::sui::SUI"
+                object-id: "0x5"
+                field: "price.value"
+                exponent: -8
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.precision, 2);
+        assert_eq!(config.refresh_interval_secs, 60);
+        assert_eq!(config.on_chain[0].exponent, -8);
+        assert!(PriceOracleConfig::parse("currency: USD\nunknown: 1").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use tap::tap::TapFallible;
+use tracing::{error, info};
+
+use crate::metrics::IndexerMetrics;
+use crate::models::address_daily_stats::MS_PER_DAY;
+use crate::store::IndexerAnalyticalStore;
+use crate::types::IndexerResult;
+
+const ADDRESS_DAILY_STATS_PROCESSOR_BATCH_SIZE: usize = 10000;
+
+/// Aggregates the activity of each address per day into `address_daily_stats`, keeping the
+/// aggregates of the last `retention_days` days, and counts the daily and weekly active addresses
+/// of each completed day into `active_address_counts`.
+pub struct AddressDailyStatsProcessor<S> {
+    pub store: S,
+    metrics: IndexerMetrics,
+    pub address_daily_stats_processor_batch_size: usize,
+    pub retention_days: u64,
+}
+
+impl<S> AddressDailyStatsProcessor<S>
+where
+    S: IndexerAnalyticalStore + Clone + Sync + Send + 'static,
+{
+    pub fn new(store: S, metrics: IndexerMetrics, retention_days: u64) -> Self {
+        let address_daily_stats_processor_batch_size =
+            std::env::var("ADDRESS_DAILY_STATS_PROCESSOR_BATCH_SIZE")
+                .map(|s| {
+                    s.parse::<usize>()
+                        .unwrap_or(ADDRESS_DAILY_STATS_PROCESSOR_BATCH_SIZE)
+                })
+                .unwrap_or(ADDRESS_DAILY_STATS_PROCESSOR_BATCH_SIZE);
+        Self {
+            store,
+            metrics,
+            address_daily_stats_processor_batch_size,
+            retention_days,
+        }
+    }
+
+    pub async fn start(&self) -> IndexerResult<()> {
+        info!("Indexer address daily stats async processor started...");
+        let mut last_processed_tx_seq = self
+            .store
+            .get_address_daily_stats_last_processed_tx_seq()
+            .await?
+            .unwrap_or_default()
+            .seq;
+        loop {
+            // Transactions are aggregated from their indices, which are only known to be fully
+            // committed once the checkpoint including them is.
+            let mut latest_cp = self.store.get_latest_stored_checkpoint().await?;
+            while !matches!(
+                &latest_cp,
+                Some(cp) if cp.network_total_transactions - 1 > last_processed_tx_seq
+            ) {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                latest_cp = self.store.get_latest_stored_checkpoint().await?;
+            }
+            // unwrap is safe here b/c we just checked that it's not None
+            let latest_tx_seq = latest_cp.unwrap().network_total_transactions - 1;
+
+            let start_tx_seq = last_processed_tx_seq + 1;
+            let end_tx_seq = (start_tx_seq + self.address_daily_stats_processor_batch_size as i64)
+                .min(latest_tx_seq + 1);
+            let store = self.store.clone();
+            tokio::task::spawn_blocking(move || {
+                store.persist_address_daily_stats_in_tx_range(start_tx_seq, end_tx_seq)
+            })
+            .await?
+            .tap_err(|e| {
+                error!("Error persisting address daily stats: {:?}", e);
+            })?;
+            last_processed_tx_seq = end_tx_seq - 1;
+            info!(
+                "Persisted address daily stats for tx seq: {}",
+                last_processed_tx_seq
+            );
+            self.metrics
+                .latest_address_daily_stats_tx_seq
+                .set(last_processed_tx_seq);
+
+            // Days are retained relative to the latest day aggregated, rather than the wall
+            // clock, so that the aggregates of a lagging indexer are not pruned before it
+            // catches up. Active addresses are counted for the days before it, which are
+            // complete, before the days they are counted from are pruned.
+            if let Some(tx) = self.store.get_tx(last_processed_tx_seq).await? {
+                let latest_day = tx.timestamp_ms / MS_PER_DAY;
+                self.store.persist_active_address_counts(latest_day).await?;
+                self.store
+                    .prune_address_daily_stats(latest_day + 1 - self.retention_days as i64)
+                    .await?;
+            }
+        }
+    }
+}
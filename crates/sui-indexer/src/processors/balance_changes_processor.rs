@@ -0,0 +1,82 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use tap::tap::TapFallible;
+use tracing::{error, info};
+
+use crate::metrics::IndexerMetrics;
+use crate::store::IndexerAnalyticalStore;
+use crate::types::IndexerResult;
+
+const BALANCE_CHANGES_PROCESSOR_BATCH_SIZE: usize = 10000;
+
+/// Takes the changes that transactions made to the balances of addresses into `balance_changes`,
+/// which balance histories are read from.
+pub struct BalanceChangesProcessor<S> {
+    pub store: S,
+    metrics: IndexerMetrics,
+    pub balance_changes_processor_batch_size: usize,
+}
+
+impl<S> BalanceChangesProcessor<S>
+where
+    S: IndexerAnalyticalStore + Clone + Sync + Send + 'static,
+{
+    pub fn new(store: S, metrics: IndexerMetrics) -> Self {
+        let balance_changes_processor_batch_size =
+            std::env::var("BALANCE_CHANGES_PROCESSOR_BATCH_SIZE")
+                .map(|s| {
+                    s.parse::<usize>()
+                        .unwrap_or(BALANCE_CHANGES_PROCESSOR_BATCH_SIZE)
+                })
+                .unwrap_or(BALANCE_CHANGES_PROCESSOR_BATCH_SIZE);
+        Self {
+            store,
+            metrics,
+            balance_changes_processor_batch_size,
+        }
+    }
+
+    pub async fn start(&self) -> IndexerResult<()> {
+        info!("Indexer balance changes async processor started...");
+        // Resumes from the last transaction that changed an address' balance, so transactions
+        // after it that didn't are processed again, which persists nothing new.
+        let mut last_processed_tx_seq = self
+            .store
+            .get_balance_changes_last_processed_tx_seq()
+            .await?
+            .map_or(-1, |tx_seq| tx_seq.seq);
+        loop {
+            let mut latest_cp = self.store.get_latest_stored_checkpoint().await?;
+            while !matches!(
+                &latest_cp,
+                Some(cp) if cp.network_total_transactions - 1 > last_processed_tx_seq
+            ) {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                latest_cp = self.store.get_latest_stored_checkpoint().await?;
+            }
+            // unwrap is safe here b/c we just checked that it's not None
+            let latest_tx_seq = latest_cp.unwrap().network_total_transactions - 1;
+
+            let start_tx_seq = last_processed_tx_seq + 1;
+            let end_tx_seq = (start_tx_seq + self.balance_changes_processor_batch_size as i64)
+                .min(latest_tx_seq + 1);
+            let store = self.store.clone();
+            tokio::task::spawn_blocking(move || {
+                store.persist_balance_changes_in_tx_range(start_tx_seq, end_tx_seq)
+            })
+            .await?
+            .tap_err(|e| {
+                error!("Error persisting balance changes: {:?}", e);
+            })?;
+            last_processed_tx_seq = end_tx_seq - 1;
+            info!(
+                "Persisted balance changes for tx seq: {}",
+                last_processed_tx_seq
+            );
+            self.metrics
+                .latest_balance_changes_tx_seq
+                .set(last_processed_tx_seq);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use tap::tap::TapFallible;
+use tracing::{error, info};
+
+use crate::metrics::IndexerMetrics;
+use crate::store::IndexerAnalyticalStore;
+use crate::types::IndexerResult;
+
+const GAS_PRICE_STATS_CHECKPOINT_RANGE: usize = 100;
+
+/// Computes statistics of the gas prices paid in consecutive ranges of checkpoints into
+/// `gas_price_stats`. Ranges are cut short at the end of an epoch, so that each of them has one
+/// reference gas price.
+pub struct GasPriceStatsProcessor<S> {
+    pub store: S,
+    metrics: IndexerMetrics,
+    pub gas_price_stats_checkpoint_range: usize,
+}
+
+impl<S> GasPriceStatsProcessor<S>
+where
+    S: IndexerAnalyticalStore + Clone + Sync + Send + 'static,
+{
+    pub fn new(store: S, metrics: IndexerMetrics) -> Self {
+        let gas_price_stats_checkpoint_range = std::env::var("GAS_PRICE_STATS_CHECKPOINT_RANGE")
+            .map(|s| {
+                s.parse::<usize>()
+                    .unwrap_or(GAS_PRICE_STATS_CHECKPOINT_RANGE)
+            })
+            .unwrap_or(GAS_PRICE_STATS_CHECKPOINT_RANGE)
+            .max(1);
+        Self {
+            store,
+            metrics,
+            gas_price_stats_checkpoint_range,
+        }
+    }
+
+    pub async fn start(&self) -> IndexerResult<()> {
+        info!("Indexer gas price stats async processor started...");
+        let mut next_checkpoint = self.store.get_gas_price_stats_next_checkpoint().await?;
+        loop {
+            let end_checkpoint = next_checkpoint + self.gas_price_stats_checkpoint_range as i64;
+            let mut latest_cp = self.store.get_latest_stored_checkpoint().await?;
+            while !matches!(&latest_cp, Some(cp) if cp.sequence_number >= end_checkpoint - 1) {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                latest_cp = self.store.get_latest_stored_checkpoint().await?;
+            }
+
+            let last_checkpoint = self
+                .store
+                .get_checkpoints_in_range(next_checkpoint, end_checkpoint)
+                .await?
+                .iter()
+                .find(|cp| cp.end_of_epoch)
+                .map_or(end_checkpoint - 1, |cp| cp.sequence_number);
+            let store = self.store.clone();
+            let first_checkpoint = next_checkpoint;
+            tokio::task::spawn_blocking(move || {
+                store.persist_gas_price_stats(first_checkpoint, last_checkpoint)
+            })
+            .await?
+            .tap_err(|e| {
+                error!("Error persisting gas price stats: {:?}", e);
+            })?;
+            info!(
+                "Persisted gas price stats for checkpoints {} to {}",
+                first_checkpoint, last_checkpoint
+            );
+            self.metrics
+                .latest_gas_price_stats_cp_seq
+                .set(last_checkpoint);
+            next_checkpoint = last_checkpoint + 1;
+        }
+    }
+}
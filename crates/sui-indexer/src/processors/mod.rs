@@ -1,8 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod address_daily_stats_processor;
 pub mod address_metrics_processor;
+pub mod balance_changes_processor;
+pub mod gas_price_stats_processor;
 pub mod move_call_metrics_processor;
 pub mod network_metrics_processor;
 pub mod objects_snapshot_processor;
 pub mod processor_orchestrator;
+pub mod validator_apy_processor;
+pub mod validator_history_processor;
@@ -7,21 +7,31 @@ use tracing::{error, info};
 use crate::metrics::IndexerMetrics;
 use crate::store::IndexerAnalyticalStore;
 
+use super::address_daily_stats_processor::AddressDailyStatsProcessor;
 use super::address_metrics_processor::AddressMetricsProcessor;
+use super::balance_changes_processor::BalanceChangesProcessor;
+use super::gas_price_stats_processor::GasPriceStatsProcessor;
 use super::move_call_metrics_processor::MoveCallMetricsProcessor;
 use super::network_metrics_processor::NetworkMetricsProcessor;
+use super::validator_apy_processor::ValidatorApyProcessor;
+use super::validator_history_processor::ValidatorHistoryProcessor;
 
 pub struct ProcessorOrchestrator<S> {
     store: S,
     metrics: IndexerMetrics,
+    address_daily_stats_retention_days: u64,
 }
 
 impl<S> ProcessorOrchestrator<S>
 where
     S: IndexerAnalyticalStore + Clone + Send + Sync + 'static,
 {
-    pub fn new(store: S, metrics: IndexerMetrics) -> Self {
-        Self { store, metrics }
+    pub fn new(store: S, metrics: IndexerMetrics, address_daily_stats_retention_days: u64) -> Self {
+        Self {
+            store,
+            metrics,
+            address_daily_stats_retention_days,
+        }
     }
 
     pub async fn run_forever(&mut self) {
@@ -71,10 +81,93 @@ where
             }
         });
 
+        let addr_daily_stats_processor = AddressDailyStatsProcessor::new(
+            self.store.clone(),
+            self.metrics.clone(),
+            self.address_daily_stats_retention_days,
+        );
+        let addr_daily_stats_handle = tokio::task::spawn(async move {
+            loop {
+                let addr_daily_stats_res = addr_daily_stats_processor.start().await;
+                if let Err(e) = addr_daily_stats_res {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    error!(
+                        "Indexer address daily stats processor failed with error {:?}, retrying in 5s...",
+                        e
+                    );
+                }
+            }
+        });
+
+        let balance_changes_processor =
+            BalanceChangesProcessor::new(self.store.clone(), self.metrics.clone());
+        let balance_changes_handle = tokio::task::spawn(async move {
+            loop {
+                let balance_changes_res = balance_changes_processor.start().await;
+                if let Err(e) = balance_changes_res {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    error!(
+                        "Indexer balance changes processor failed with error {:?}, retrying in 5s...",
+                        e
+                    );
+                }
+            }
+        });
+
+        let gas_price_stats_processor =
+            GasPriceStatsProcessor::new(self.store.clone(), self.metrics.clone());
+        let gas_price_stats_handle = tokio::task::spawn(async move {
+            loop {
+                let gas_price_stats_res = gas_price_stats_processor.start().await;
+                if let Err(e) = gas_price_stats_res {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    error!(
+                        "Indexer gas price stats processor failed with error {:?}, retrying in 5s...",
+                        e
+                    );
+                }
+            }
+        });
+
+        let validator_apy_processor =
+            ValidatorApyProcessor::new(self.store.clone(), self.metrics.clone());
+        let validator_apy_handle = tokio::task::spawn(async move {
+            loop {
+                let validator_apy_res = validator_apy_processor.start().await;
+                if let Err(e) = validator_apy_res {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    error!(
+                        "Indexer validator apy processor failed with error {:?}, retrying in 5s...",
+                        e
+                    );
+                }
+            }
+        });
+
+        let validator_history_processor =
+            ValidatorHistoryProcessor::new(self.store.clone(), self.metrics.clone());
+        let validator_history_handle = tokio::task::spawn(async move {
+            loop {
+                let validator_history_res = validator_history_processor.start().await;
+                if let Err(e) = validator_history_res {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    error!(
+                        "Indexer validator history processor failed with error {:?}, retrying in 5s...",
+                        e
+                    );
+                }
+            }
+        });
+
         try_join_all(vec![
             network_metrics_handle,
             addr_metrics_handle,
             move_call_metrics_handle,
+            addr_daily_stats_handle,
+            balance_changes_handle,
+            gas_price_stats_handle,
+            validator_apy_handle,
+            validator_history_handle,
         ])
         .await
         .expect("Processor orchestrator should not run into errors.");
@@ -0,0 +1,50 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use tap::tap::TapFallible;
+use tracing::{error, info};
+
+use crate::metrics::IndexerMetrics;
+use crate::store::IndexerAnalyticalStore;
+use crate::types::IndexerResult;
+
+/// Computes the APY of each validator's staking pool in each epoch into `validator_apys`, once the
+/// epoch has ended.
+pub struct ValidatorApyProcessor<S> {
+    pub store: S,
+    metrics: IndexerMetrics,
+}
+
+impl<S> ValidatorApyProcessor<S>
+where
+    S: IndexerAnalyticalStore + Clone + Sync + Send + 'static,
+{
+    pub fn new(store: S, metrics: IndexerMetrics) -> Self {
+        Self { store, metrics }
+    }
+
+    pub async fn start(&self) -> IndexerResult<()> {
+        info!("Indexer validator apy async processor started...");
+        let mut epoch = self.store.get_validator_apys_next_epoch().await?;
+        loop {
+            let mut latest_cp = self.store.get_latest_stored_checkpoint().await?;
+            while !matches!(&latest_cp, Some(cp) if cp.epoch > epoch) {
+                tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+                latest_cp = self.store.get_latest_stored_checkpoint().await?;
+            }
+
+            let store = self.store.clone();
+            tokio::task::spawn_blocking(move || store.persist_validator_apys(epoch))
+                .await?
+                .tap_err(|e| {
+                    error!(
+                        "Error persisting validator apys of epoch {}: {:?}",
+                        epoch, e
+                    );
+                })?;
+            info!("Persisted validator apys of epoch {}", epoch);
+            self.metrics.latest_validator_apys_epoch.set(epoch);
+            epoch += 1;
+        }
+    }
+}
@@ -0,0 +1,399 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pruning the rows of tables that are older than their retention periods, configured in a YAML
+//! file, e.g.:
+//!
+//! ```yaml
+//! interval-secs: 3600
+//! retention-days:
+//!   events: 30
+//!   objects_history: 7
+//! ```
+//!
+//! Tables without a retention period are kept forever. A table kept for `n` days keeps the rows
+//! of the checkpoints from up to `n` days before the latest checkpoint indexed, rather than
+//! before now, so that an indexer that is catching up doesn't prune what it has just indexed.
+//! Epoch partitions whose checkpoints are all pruned are dropped as a whole, and other rows are
+//! deleted in batches, so that pruning doesn't hold locks the writer waits on for long.
+//!
+//! `objects_history` is only pruned up to the objects snapshot, which is built from it.
+//! Transactions and checkpoints that are pruned can still be served from a historical store (see
+//! [`crate::historical`]).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use diesel::dsl::max;
+use diesel::sql_types::{BigInt, Text};
+use diesel::{OptionalExtension, QueryDsl, QueryableByName, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::db::{get_pg_pool_connection, PgConnectionPool};
+use crate::errors::IndexerError;
+use crate::metrics::IndexerMetrics;
+use crate::schema::objects_snapshot;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RetentionConfig {
+    /// How often tables are pruned.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// Most rows deleted from a table in one transaction.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: u64,
+    /// How many days of each table to keep.
+    #[serde(default)]
+    pub retention_days: BTreeMap<PrunedTable, u64>,
+}
+
+fn default_interval_secs() -> u64 {
+    3600
+}
+
+fn default_batch_size() -> u64 {
+    10_000
+}
+
+impl RetentionConfig {
+    pub fn parse(yaml: &str) -> Result<Self, IndexerError> {
+        serde_yaml::from_str(yaml).map_err(|e| {
+            IndexerError::InvalidArgumentError(format!("Invalid retention config: {e}"))
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, IndexerError> {
+        let yaml = std::fs::read_to_string(path).map_err(|e| {
+            IndexerError::GenericError(format!(
+                "Failed to read retention config file {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&yaml)
+    }
+}
+
+/// The tables (or groups of tables) that can be pruned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrunedTable {
+    Transactions,
+    /// The indices that transactions are queried by (`tx_senders`, `tx_calls`, ...).
+    TxIndices,
+    Events,
+    ObjectsHistory,
+    ObjectTransitions,
+    NftTransfers,
+    PackageCallMetrics,
+}
+
+/// What the rows of a table are pruned by.
+#[derive(Clone, Copy, Debug)]
+enum Key {
+    Checkpoint(&'static str),
+    Transaction(&'static str),
+}
+
+impl PrunedTable {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Transactions => "transactions",
+            Self::TxIndices => "tx_indices",
+            Self::Events => "events",
+            Self::ObjectsHistory => "objects_history",
+            Self::ObjectTransitions => "object_transitions",
+            Self::NftTransfers => "nft_transfers",
+            Self::PackageCallMetrics => "package_call_metrics",
+        }
+    }
+
+    /// The tables pruned, with the column that their rows are pruned by.
+    fn tables(self) -> &'static [(&'static str, Key)] {
+        const TX_SEQUENCE_NUMBER: Key = Key::Transaction("tx_sequence_number");
+        const CHECKPOINT_SEQUENCE_NUMBER: Key = Key::Checkpoint("checkpoint_sequence_number");
+        match self {
+            Self::Transactions => &[("transactions", CHECKPOINT_SEQUENCE_NUMBER)],
+            Self::TxIndices => &[
+                ("tx_senders", TX_SEQUENCE_NUMBER),
+                ("tx_recipients", TX_SEQUENCE_NUMBER),
+                ("tx_input_objects", TX_SEQUENCE_NUMBER),
+                ("tx_changed_objects", TX_SEQUENCE_NUMBER),
+                ("tx_calls", TX_SEQUENCE_NUMBER),
+                ("tx_classes", TX_SEQUENCE_NUMBER),
+            ],
+            Self::Events => &[
+                ("events", CHECKPOINT_SEQUENCE_NUMBER),
+                ("event_addresses", CHECKPOINT_SEQUENCE_NUMBER),
+            ],
+            Self::ObjectsHistory => &[("objects_history", CHECKPOINT_SEQUENCE_NUMBER)],
+            Self::ObjectTransitions => &[("object_transitions", CHECKPOINT_SEQUENCE_NUMBER)],
+            Self::NftTransfers => &[("nft_transfers", CHECKPOINT_SEQUENCE_NUMBER)],
+            Self::PackageCallMetrics => &[("package_call_metrics", CHECKPOINT_SEQUENCE_NUMBER)],
+        }
+    }
+
+    /// Whether the table is partitioned by epoch.
+    fn partitioned(self) -> bool {
+        matches!(self, Self::Transactions | Self::ObjectsHistory)
+    }
+}
+
+/// The first checkpoint, and its first transaction, that a table keeps.
+#[derive(QueryableByName, Clone, Copy, Debug, PartialEq, Eq)]
+struct Boundary {
+    #[diesel(sql_type = BigInt)]
+    checkpoint: i64,
+    #[diesel(sql_type = BigInt)]
+    tx_sequence_number: i64,
+}
+
+pub struct Pruner {
+    cp: PgConnectionPool,
+    config: RetentionConfig,
+    metrics: IndexerMetrics,
+}
+
+impl Pruner {
+    pub fn new(cp: PgConnectionPool, config: RetentionConfig, metrics: IndexerMetrics) -> Self {
+        Self {
+            cp,
+            config,
+            metrics,
+        }
+    }
+
+    /// Prune the tables every `interval-secs`, until `cancel` is cancelled.
+    pub async fn run(self, cancel: CancellationToken) {
+        info!(retention_days = ?self.config.retention_days, "Pruner started");
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.interval_secs));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let pruner = Arc::new(self);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = interval.tick() => {}
+            }
+            for (&table, &days) in &pruner.config.retention_days {
+                let this = pruner.clone();
+                let pruned = tokio::task::spawn_blocking(move || this.prune(table, days))
+                    .await
+                    .map_err(IndexerError::from)
+                    .and_then(|pruned| pruned);
+                if let Err(e) = pruned {
+                    warn!(table = table.name(), "Failed to prune: {e}");
+                }
+            }
+        }
+    }
+
+    /// Prune the rows of `table` from before the checkpoints of the last `days` days.
+    pub fn prune(&self, table: PrunedTable, days: u64) -> Result<(), IndexerError> {
+        let Some(mut boundary) = self.boundary(days)? else {
+            return Ok(());
+        };
+        if table == PrunedTable::ObjectsHistory {
+            // The snapshot is built from the history of the checkpoints after it. The history is
+            // only pruned by checkpoint, so the boundary's transaction is left as it is.
+            let Some(snapshot) = self.snapshot_checkpoint()? else {
+                return Ok(());
+            };
+            boundary.checkpoint = boundary.checkpoint.min(snapshot + 1);
+        }
+
+        let mut pruned = 0;
+        if table.partitioned() {
+            for (name, _) in table.tables() {
+                pruned += self.drop_partitions(name, boundary.checkpoint)?;
+            }
+        }
+        for &(name, key) in table.tables() {
+            let (column, before) = match key {
+                Key::Checkpoint(column) => (column, boundary.checkpoint),
+                Key::Transaction(column) => (column, boundary.tx_sequence_number),
+            };
+            pruned += self.delete_before(name, column, before)?;
+        }
+
+        let label = [table.name()];
+        self.metrics
+            .pruned_rows
+            .with_label_values(&label)
+            .inc_by(pruned);
+        self.metrics
+            .pruned_checkpoint
+            .with_label_values(&label)
+            .set(boundary.checkpoint);
+        info!(
+            table = table.name(),
+            checkpoint = boundary.checkpoint,
+            pruned,
+            "Pruned table"
+        );
+        Ok(())
+    }
+
+    /// The first checkpoint within `days` days of the latest one indexed.
+    fn boundary(&self, days: u64) -> Result<Option<Boundary>, IndexerError> {
+        let mut conn = get_pg_pool_connection(&self.cp)?;
+        let retention_ms = i64::try_from(days)
+            .ok()
+            .and_then(|days| days.checked_mul(DAY_MS))
+            .unwrap_or(i64::MAX);
+        Ok(diesel::sql_query(
+            "SELECT sequence_number AS checkpoint, \
+                    network_total_transactions - cardinality(tx_digests) AS tx_sequence_number \
+             FROM checkpoints \
+             WHERE timestamp_ms >= ( \
+                 SELECT timestamp_ms FROM checkpoints ORDER BY sequence_number DESC LIMIT 1 \
+             ) - $1 \
+             ORDER BY timestamp_ms, sequence_number \
+             LIMIT 1",
+        )
+        .bind::<BigInt, _>(retention_ms)
+        .get_result::<Boundary>(&mut conn)
+        .optional()?)
+    }
+
+    /// The latest checkpoint in the objects snapshot, if there is one.
+    fn snapshot_checkpoint(&self) -> Result<Option<i64>, IndexerError> {
+        let mut conn = get_pg_pool_connection(&self.cp)?;
+        Ok(objects_snapshot::table
+            .select(max(objects_snapshot::checkpoint_sequence_number))
+            .first::<Option<i64>>(&mut conn)?)
+    }
+
+    /// Drop the partitions of `table` for epochs that ended before `checkpoint`, returning how
+    /// many rows they had.
+    fn drop_partitions(&self, table: &str, checkpoint: i64) -> Result<u64, IndexerError> {
+        #[derive(QueryableByName)]
+        struct Partition {
+            #[diesel(sql_type = Text)]
+            name: String,
+            #[diesel(sql_type = BigInt)]
+            rows: i64,
+        }
+        #[derive(QueryableByName)]
+        struct Epoch {
+            #[diesel(sql_type = BigInt)]
+            epoch: i64,
+        }
+
+        let mut conn = get_pg_pool_connection(&self.cp)?;
+        let partitions = diesel::sql_query(
+            "SELECT child.relname::TEXT AS name, GREATEST(child.reltuples, 0)::BIGINT AS rows \
+             FROM pg_inherits \
+             JOIN pg_class parent ON pg_inherits.inhparent = parent.oid \
+             JOIN pg_class child ON pg_inherits.inhrelid = child.oid \
+             WHERE parent.relname = $1",
+        )
+        .bind::<Text, _>(table)
+        .load::<Partition>(&mut conn)?;
+        // Epochs that later epochs started after, at or before `checkpoint`.
+        let ended: BTreeSet<i64> = diesel::sql_query(
+            "SELECT epoch - 1 AS epoch FROM epochs WHERE epoch > 0 AND first_checkpoint_id <= $1",
+        )
+        .bind::<BigInt, _>(checkpoint)
+        .load::<Epoch>(&mut conn)?
+        .into_iter()
+        .map(|e| e.epoch)
+        .collect();
+
+        let prefix = format!("{table}_partition_");
+        let epochs: BTreeMap<i64, Partition> = partitions
+            .into_iter()
+            .filter_map(|p| Some((p.name.strip_prefix(&prefix)?.parse().ok()?, p)))
+            .collect();
+        // The latest partition holds all checkpoints from the start of its epoch on, until the
+        // next epoch's is created.
+        let Some(&latest) = epochs.keys().next_back() else {
+            return Ok(0);
+        };
+
+        let mut dropped = 0;
+        for (epoch, partition) in epochs {
+            if epoch == latest || !ended.contains(&epoch) {
+                continue;
+            }
+            diesel::sql_query(format!("DROP TABLE IF EXISTS {}", partition.name))
+                .execute(&mut conn)?;
+            info!(table, epoch, "Dropped partition");
+            dropped += partition.rows as u64;
+        }
+        Ok(dropped)
+    }
+
+    /// Delete the rows of `table` with `column` before `before`, in batches, returning how many
+    /// were deleted.
+    fn delete_before(&self, table: &str, column: &str, before: i64) -> Result<u64, IndexerError> {
+        let statement = delete_statement(table, column);
+        let mut deleted = 0;
+        loop {
+            let mut conn = get_pg_pool_connection(&self.cp)?;
+            let rows = diesel::sql_query(&statement)
+                .bind::<BigInt, _>(before)
+                .bind::<BigInt, _>(self.config.batch_size as i64)
+                .execute(&mut conn)?;
+            deleted += rows as u64;
+            if rows == 0 {
+                return Ok(deleted);
+            }
+        }
+    }
+}
+
+/// Deletes up to a batch of rows of `table` with `column` before a bound. Rows are picked by
+/// their `ctid`s, which may be shared by rows of different partitions, so the condition is
+/// checked again for the rows deleted.
+fn delete_statement(table: &str, column: &str) -> String {
+    format!(
+        "DELETE FROM {table} WHERE {column} < $1 AND ctid IN ( \
+             SELECT ctid FROM {table} WHERE {column} < $1 LIMIT $2 \
+         )"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retention_config() {
+        let config = RetentionConfig::parse(
+            r#"
+            retention-days:
+              events: 30
+              tx_indices: 30
+              objects_history: 7
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.interval_secs, 3600);
+        assert_eq!(
+            config.retention_days,
+            BTreeMap::from([
+                (PrunedTable::TxIndices, 30),
+                (PrunedTable::Events, 30),
+                (PrunedTable::ObjectsHistory, 7),
+            ])
+        );
+
+        assert!(RetentionConfig::parse("retention-days: {checkpoints: 30}").is_err());
+    }
+
+    #[test]
+    fn test_delete_statement() {
+        assert_eq!(
+            delete_statement("events", "checkpoint_sequence_number"),
+            "DELETE FROM events WHERE checkpoint_sequence_number < $1 AND ctid IN ( \
+             SELECT ctid FROM events WHERE checkpoint_sequence_number < $1 LIMIT $2 \
+             )"
+        );
+    }
+}
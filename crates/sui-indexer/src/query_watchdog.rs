@@ -0,0 +1,230 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cancelling the reader's database queries that run past the deadline of the RPC method they are
+//! run for, configured in the `query-deadlines` section of the dynamic config (see
+//! [`crate::dynamic_config`]), e.g.:
+//!
+//! ```yaml
+//! query-deadlines:
+//!   default-ms: 30000
+//!   methods:
+//!     suix_queryEvents: 5000
+//!     suix_queryTransactionBlocks: 5000
+//! ```
+//!
+//! The watchdog checks on the queries in flight periodically, and cancels those past their
+//! deadline with `pg_cancel_backend`, over a connection of its own, so that it can still cancel
+//! queries when they have exhausted the reader's pool. A request whose query is cancelled fails
+//! with [`IndexerError::QueryTimeout`], and its method and a fingerprint of its parameters are
+//! logged, so that the requests responsible can be found and disabled or indexed for.
+//!
+//! Only queries run for RPC requests, on the primary database, are watched: queries served by
+//! read replicas (see [`crate::replicas`]) run to completion, or to the statement timeout.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use diesel::sql_types::{Bool, Integer};
+use diesel::{PgConnection, QueryableByName, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use sui_json_rpc::request_context::RequestContext;
+
+use crate::db::{get_pg_pool_connection, PgConnectionPool};
+use crate::errors::IndexerError;
+use crate::metrics::IndexerMetrics;
+
+/// How often the watchdog checks for queries past their deadline.
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct QueryDeadlinesConfig {
+    /// Deadline, in milliseconds, of the queries of methods without a deadline of their own.
+    /// Their queries are not cancelled if this is unset.
+    pub default_ms: Option<u64>,
+    /// Deadline, in milliseconds, of the queries of each method.
+    pub methods: BTreeMap<String, u64>,
+}
+
+impl QueryDeadlinesConfig {
+    fn deadline_ms(&self, method: &str) -> Option<u64> {
+        self.methods.get(method).copied().or(self.default_ms)
+    }
+}
+
+#[derive(QueryableByName)]
+struct BackendPid {
+    #[diesel(sql_type = Integer)]
+    pid: i32,
+}
+
+#[derive(QueryableByName)]
+struct Cancelled {
+    #[diesel(sql_type = Bool)]
+    cancelled: bool,
+}
+
+#[derive(Debug)]
+struct RunningQuery {
+    request: RequestContext,
+    backend_pid: i32,
+    deadline_ms: u64,
+    started: Instant,
+    cancelled: bool,
+}
+
+/// The reader's queries in flight, and their deadlines. Clones share the same queries and
+/// config, so the dynamic config can change the deadlines of a running reader.
+#[derive(Clone, Debug, Default)]
+pub struct QueryWatchdog {
+    config: Arc<RwLock<QueryDeadlinesConfig>>,
+    running: Arc<Mutex<HashMap<u64, RunningQuery>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl QueryWatchdog {
+    pub fn set_config(&self, config: QueryDeadlinesConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    /// Run `query` on `conn`, for `request`, cancelling it if it runs past the deadline of the
+    /// request's method.
+    pub(crate) fn watch<T>(
+        &self,
+        request: Option<RequestContext>,
+        conn: &mut PgConnection,
+        query: impl FnOnce(&mut PgConnection) -> Result<T, IndexerError>,
+    ) -> Result<T, IndexerError> {
+        let Some(request) = request else {
+            return query(conn);
+        };
+        let Some(deadline_ms) = self.config.read().unwrap().deadline_ms(&request.method) else {
+            return query(conn);
+        };
+
+        let backend_pid = diesel::sql_query("SELECT pg_backend_pid() AS pid")
+            .get_result::<BackendPid>(conn)
+            .map_err(|e| IndexerError::PostgresReadError(e.to_string()))?
+            .pid;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.running.lock().unwrap().insert(
+            id,
+            RunningQuery {
+                request,
+                backend_pid,
+                deadline_ms,
+                started: Instant::now(),
+                cancelled: false,
+            },
+        );
+
+        let result = query(conn);
+
+        // Removed before the connection is returned to the pool, so that the watchdog can't
+        // cancel the next query to run on it.
+        let running = self.running.lock().unwrap().remove(&id);
+        match running {
+            Some(RunningQuery {
+                request,
+                deadline_ms,
+                cancelled: true,
+                ..
+            }) => Err(IndexerError::QueryTimeout {
+                method: request.method,
+                deadline_ms,
+            }),
+            _ => result,
+        }
+    }
+
+    /// Cancel queries past their deadline every [`WATCHDOG_INTERVAL`], over connections from
+    /// `pool`.
+    pub async fn run(self, pool: PgConnectionPool, metrics: IndexerMetrics) {
+        let mut interval = tokio::time::interval(WATCHDOG_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let watchdog = self.clone();
+            let pool = pool.clone();
+            let metrics = metrics.clone();
+            let result =
+                tokio::task::spawn_blocking(move || watchdog.cancel_overdue(&pool, &metrics)).await;
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!("Failed to cancel queries past their deadline: {e}"),
+                Err(e) => warn!("Failed to cancel queries past their deadline: {e}"),
+            }
+        }
+    }
+
+    fn cancel_overdue(
+        &self,
+        pool: &PgConnectionPool,
+        metrics: &IndexerMetrics,
+    ) -> Result<(), IndexerError> {
+        // Held while cancelling, so that queries that finish in the meantime are not cancelled
+        // after their connection has moved on to another query.
+        let mut running = self.running.lock().unwrap();
+        let mut overdue = running
+            .values_mut()
+            .filter(|query| {
+                !query.cancelled
+                    && query.started.elapsed() >= Duration::from_millis(query.deadline_ms)
+            })
+            .peekable();
+        if overdue.peek().is_none() {
+            return Ok(());
+        }
+
+        let mut conn = get_pg_pool_connection(pool)?;
+        for query in overdue {
+            let cancelled = diesel::sql_query(format!(
+                "SELECT pg_cancel_backend({}) AS cancelled",
+                query.backend_pid
+            ))
+            .get_result::<Cancelled>(&mut conn)
+            .map_err(|e| IndexerError::PostgresReadError(e.to_string()))?
+            .cancelled;
+            query.cancelled = true;
+
+            metrics
+                .query_watchdog_cancellations
+                .with_label_values(&[&query.request.method])
+                .inc();
+            warn!(
+                method = %query.request.method,
+                params_fingerprint = %query.request.params_fingerprint(),
+                deadline_ms = query.deadline_ms,
+                backend_pid = query.backend_pid,
+                cancelled,
+                "Cancelled query past its method's deadline"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_deadlines() {
+        let config: QueryDeadlinesConfig =
+            serde_yaml::from_str("default-ms: 30000\nmethods:\n  suix_queryEvents: 5000\n")
+                .unwrap();
+        assert_eq!(config.deadline_ms("suix_queryEvents"), Some(5000));
+        assert_eq!(config.deadline_ms("suix_getBalance"), Some(30000));
+
+        let config = QueryDeadlinesConfig {
+            methods: BTreeMap::from([("suix_queryEvents".to_string(), 5000)]),
+            ..Default::default()
+        };
+        assert_eq!(config.deadline_ms("suix_getBalance"), None);
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serving reads pinned to a checkpoint from Postgres logical replicas of the indexer's database.
+//!
+//! Each replica's progress is tracked by the latest checkpoint it has applied: the writer commits
+//! a checkpoint only after all of its data, so a replica subscribed to all of the indexer's
+//! tables (in one subscription, so that transactions are applied in the order they committed)
+//! has all of the data of every checkpoint it has applied. A read pinned to a checkpoint is only
+//! sent to a replica that has applied it, and falls back to the primary if none has, so reads
+//! that are not pinned never go to replicas.
+
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use tracing::warn;
+
+use crate::db::{PgConnectionConfig, PgConnectionPool, PgConnectionPoolConfig, PgPoolConnection};
+use crate::errors::IndexerError;
+use crate::metrics::IndexerMetrics;
+use crate::schema::checkpoints;
+
+/// How often replicas are checked for the checkpoints they have applied.
+const APPLY_LAG_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The applied checkpoint of replicas that have not been checked yet, or could not be.
+const UNKNOWN: i64 = -1;
+
+struct Replica {
+    pool: PgConnectionPool,
+    /// The latest checkpoint the replica has applied, as of the last check.
+    applied: AtomicI64,
+}
+
+/// Replicas of the indexer's database to serve reads pinned to a checkpoint from. Clones share
+/// the same replicas.
+#[derive(Clone)]
+pub struct ReadReplicas {
+    replicas: Arc<Vec<Replica>>,
+    /// Where the next search for a replica that has applied a checkpoint starts, so that reads
+    /// are spread over the replicas that have.
+    next: Arc<AtomicUsize>,
+}
+
+impl ReadReplicas {
+    /// Connect to the replicas at `db_urls`, with a pool configured by `config` for each, whose
+    /// connections are read-only, like the reader's.
+    pub fn new(db_urls: &[String], config: PgConnectionPoolConfig) -> Result<Self, IndexerError> {
+        let connection_config = PgConnectionConfig {
+            statement_timeout: config.statement_timeout,
+            read_only: true,
+        };
+        let replicas = db_urls
+            .iter()
+            .map(|db_url| {
+                let pool = PgConnectionPool::new(
+                    db_url,
+                    config.pool_size,
+                    config.connection_timeout,
+                    connection_config,
+                )
+                .map_err(|e| {
+                    IndexerError::PgConnectionPoolInitError(format!(
+                        "Failed to initialize connection pool to replica with error: {e:?}"
+                    ))
+                })?;
+                Ok(Replica {
+                    pool,
+                    applied: AtomicI64::new(UNKNOWN),
+                })
+            })
+            .collect::<Result<_, IndexerError>>()?;
+        Ok(Self {
+            replicas: Arc::new(replicas),
+            next: Arc::default(),
+        })
+    }
+
+    /// A connection to a replica that has applied `checkpoint`, if any has (and a connection to
+    /// it can be had).
+    pub fn get_at_checkpoint(&self, checkpoint: i64) -> Option<PgPoolConnection> {
+        let count = self.replicas.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..count)
+            .map(|i| &self.replicas[(start + i) % count])
+            .filter(|replica| replica.applied.load(Ordering::Relaxed) >= checkpoint)
+            .find_map(|replica| replica.pool.get().ok())
+    }
+
+    /// Keep checking the checkpoint each replica has applied, reporting how many checkpoints it
+    /// trails the primary, behind `primary`, by.
+    pub async fn track_apply_lag(self, primary: PgConnectionPool, metrics: IndexerMetrics) {
+        let mut interval = tokio::time::interval(APPLY_LAG_INTERVAL);
+        loop {
+            interval.tick().await;
+            let this = self.clone();
+            let primary = primary.clone();
+            let metrics = metrics.clone();
+            let checked = tokio::task::spawn_blocking(move || {
+                let latest = latest_checkpoint(&primary)?;
+                for (i, replica) in this.replicas.iter().enumerate() {
+                    let label = i.to_string();
+                    let applied = latest_checkpoint(&replica.pool).unwrap_or_else(|e| {
+                        warn!("Failed to check the checkpoint replica {i} has applied: {e}");
+                        UNKNOWN
+                    });
+                    replica.applied.store(applied, Ordering::Relaxed);
+                    metrics
+                        .replica_applied_checkpoint
+                        .with_label_values(&[&label])
+                        .set(applied);
+                    metrics
+                        .replica_apply_lag
+                        .with_label_values(&[&label])
+                        .set(latest.saturating_sub(applied).max(0));
+                }
+                Ok::<_, IndexerError>(())
+            })
+            .await
+            .map_err(IndexerError::from)
+            .and_then(|checked| checked);
+            if let Err(e) = checked {
+                warn!("Failed to check replicas' apply lag: {e}");
+            }
+        }
+    }
+}
+
+/// The latest checkpoint committed to the database behind `pool`, or [`UNKNOWN`] if there is
+/// none.
+fn latest_checkpoint(pool: &PgConnectionPool) -> Result<i64, IndexerError> {
+    let mut conn = pool
+        .get()
+        .map_err(|e| IndexerError::PgPoolConnectionError(e.to_string()))?;
+    let latest = checkpoints::table
+        .select(checkpoints::sequence_number)
+        .order(checkpoints::sequence_number.desc())
+        .first::<i64>(&mut conn)
+        .optional()?;
+    Ok(latest.unwrap_or(UNKNOWN))
+}
@@ -0,0 +1,701 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A small REST facade over the indexer's read path, exposing the most commonly used reads for
+//! clients that cannot comfortably speak JSON-RPC (e.g. mobile clients). Handlers delegate to the
+//! same API modules that serve JSON-RPC, so responses are shaped identically to their JSON-RPC
+//! counterparts. Every response carries a strong `ETag`, and requests bearing a matching
+//! `If-None-Match` header are answered with `304 Not Modified`.
+
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, MethodRouter},
+    Router,
+};
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::types::error::{CallError, INVALID_PARAMS_CODE};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use sui_json_rpc::error::SuiRpcInputError;
+use sui_json_rpc_api::{
+    CoinReadApiServer, ExtendedApiServer, GovernanceReadApiServer, IndexerApiServer, ReadApiServer,
+};
+use sui_json_rpc_types::{
+    CheckpointId, SuiObjectDataOptions, SuiObjectResponseQuery, SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockResponseQuery, TransactionFilter,
+};
+use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::digests::TransactionDigest;
+
+use crate::apis::{CoinReadApi, ExtendedApi, GovernanceReadApi, IndexerApi, ReadApi};
+use crate::errors::IndexerError;
+use crate::indexer_reader::IndexerReader;
+use crate::store::IndexerReadStore;
+
+#[derive(Clone)]
+struct RestState<R> {
+    read: ReadApi<R>,
+    indexer: IndexerApi<R>,
+    coin: CoinReadApi<R>,
+    governance: GovernanceReadApi<R>,
+    extended: ExtendedApi<R>,
+}
+
+#[derive(Deserialize)]
+struct PageParams {
+    cursor: Option<String>,
+    limit: Option<usize>,
+    #[serde(default)]
+    descending: bool,
+}
+
+#[derive(Deserialize)]
+struct CoinTypeParams {
+    coin_type: Option<String>,
+}
+
+/// Builds the router serving the REST facade, mounted at `/v1`, with its OpenAPI description
+/// served at `/v1/openapi.json`.
+pub fn rest_router<R: IndexerReadStore>(reader: R) -> Router {
+    let state = RestState {
+        read: ReadApi::new(reader.clone()),
+        indexer: IndexerApi::new(reader.clone()),
+        coin: CoinReadApi::new(reader.clone()),
+        governance: GovernanceReadApi::new(reader.clone()),
+        extended: ExtendedApi::new(reader),
+    };
+
+    routes::<R>()
+        .into_iter()
+        .fold(
+            Router::new().route("/v1/openapi.json", get(openapi)),
+            |router, route| router.route(route.path, route.handler),
+        )
+        .with_state(state)
+}
+
+/// A route of the REST facade, along with what its OpenAPI description needs to know about it.
+struct Route<R> {
+    path: &'static str,
+    handler: MethodRouter<RestState<R>>,
+    summary: &'static str,
+    /// The JSON-RPC methods whose results the route responds with.
+    rpc_methods: &'static [&'static str],
+    parameters: serde_json::Value,
+}
+
+fn routes<R: IndexerReadStore>() -> Vec<Route<R>> {
+    use serde_json::json;
+
+    fn path_param(name: &str, description: &str) -> serde_json::Value {
+        json!({
+            "name": name,
+            "in": "path",
+            "required": true,
+            "description": description,
+            "schema": { "type": "string" },
+        })
+    }
+
+    let address = path_param("address", "Sui address of the account");
+    let mut address_with_page = vec![address.clone()];
+    address_with_page.extend([
+        json!({ "name": "cursor", "in": "query", "schema": { "type": "string" } }),
+        json!({ "name": "limit", "in": "query", "schema": { "type": "integer", "minimum": 1 } }),
+        json!({ "name": "descending", "in": "query", "schema": { "type": "boolean" } }),
+    ]);
+
+    vec![
+        Route {
+            path: "/v1/addresses/:address/balances",
+            handler: get(get_balances::<R>),
+            summary: "Coin balances owned by an address",
+            rpc_methods: &["suix_getAllBalances", "suix_getBalance"],
+            parameters: json!([
+                address,
+                { "name": "coin_type", "in": "query", "schema": { "type": "string" } },
+            ]),
+        },
+        Route {
+            path: "/v1/addresses/:address/objects",
+            handler: get(get_owned_objects::<R>),
+            summary: "Objects owned by an address",
+            rpc_methods: &["suix_getOwnedObjects"],
+            parameters: json!(address_with_page),
+        },
+        Route {
+            path: "/v1/addresses/:address/transactions",
+            handler: get(get_transactions::<R>),
+            summary: "Transactions sent or received by an address",
+            rpc_methods: &["suix_queryTransactionBlocks"],
+            parameters: json!(address_with_page),
+        },
+        Route {
+            path: "/v1/addresses/:address/stakes",
+            handler: get(get_stakes::<R>),
+            summary: "Stakes owned by an address",
+            rpc_methods: &["suix_getStakes"],
+            parameters: json!([address]),
+        },
+        Route {
+            path: "/v1/objects/:object_id",
+            handler: get(get_object::<R>),
+            summary: "Latest version of an object",
+            rpc_methods: &["sui_getObject"],
+            parameters: json!([path_param("object_id", "ID of the object")]),
+        },
+        Route {
+            path: "/v1/transactions/:digest",
+            handler: get(get_transaction::<R>),
+            summary: "Transaction by digest",
+            rpc_methods: &["sui_getTransactionBlock"],
+            parameters: json!([path_param("digest", "Digest of the transaction")]),
+        },
+        Route {
+            path: "/v1/checkpoints/latest",
+            handler: get(get_latest_checkpoint::<R>),
+            summary: "Latest indexed checkpoint",
+            rpc_methods: &["sui_getCheckpoint"],
+            parameters: json!([]),
+        },
+        Route {
+            path: "/v1/checkpoints/:sequence_number",
+            handler: get(get_checkpoint::<R>),
+            summary: "Checkpoint by sequence number",
+            rpc_methods: &["sui_getCheckpoint"],
+            parameters: json!([path_param(
+                "sequence_number",
+                "Sequence number of the checkpoint"
+            )]),
+        },
+        Route {
+            path: "/v1/epochs/current",
+            handler: get(get_current_epoch::<R>),
+            summary: "Current epoch",
+            rpc_methods: &["suix_getCurrentEpoch"],
+            parameters: json!([]),
+        },
+        Route {
+            path: "/v1/coins/:coin_type/metadata",
+            handler: get(get_coin_metadata::<R>),
+            summary: "Metadata for a coin type",
+            rpc_methods: &["suix_getCoinMetadata"],
+            parameters: json!([path_param(
+                "coin_type",
+                "Fully qualified coin type, URL-encoded"
+            )]),
+        },
+        Route {
+            path: "/v1/system-state",
+            handler: get(get_system_state::<R>),
+            summary: "Latest Sui system state",
+            rpc_methods: &["suix_getLatestSuiSystemState"],
+            parameters: json!([]),
+        },
+        Route {
+            path: "/v1/reference-gas-price",
+            handler: get(get_reference_gas_price::<R>),
+            summary: "Reference gas price for the current epoch",
+            rpc_methods: &["suix_getReferenceGasPrice"],
+            parameters: json!([]),
+        },
+    ]
+}
+
+pub async fn start_rest_server<R: IndexerReadStore>(
+    reader: R,
+    addr: SocketAddr,
+) -> Result<tokio::task::JoinHandle<Result<(), IndexerError>>, IndexerError> {
+    let app = rest_router(reader);
+    let server = axum::Server::try_bind(&addr)
+        .map_err(|e| IndexerError::GenericError(format!("Failed to bind REST server: {e}")))?
+        .serve(app.into_make_service());
+
+    info!(
+        "Sui Indexer REST server listening on {}",
+        server.local_addr()
+    );
+    Ok(tokio::spawn(async move {
+        server
+            .await
+            .map_err(|e| IndexerError::GenericError(format!("REST server failed: {e}")))
+    }))
+}
+
+/// An error surfaced by the REST facade, rendered as a JSON body with the given status code.
+struct RestError(StatusCode, String);
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct Body {
+            error: String,
+        }
+        (self.0, axum::Json(Body { error: self.1 })).into_response()
+    }
+}
+
+impl From<RpcError> for RestError {
+    fn from(e: RpcError) -> Self {
+        let status = match &e {
+            RpcError::Call(CallError::InvalidParams(e)) => {
+                match e.downcast_ref::<SuiRpcInputError>() {
+                    Some(SuiRpcInputError::GenericNotFound(_)) => StatusCode::NOT_FOUND,
+                    _ => StatusCode::BAD_REQUEST,
+                }
+            }
+            RpcError::Call(CallError::Custom(e)) if e.code() == INVALID_PARAMS_CODE => {
+                StatusCode::BAD_REQUEST
+            }
+            RpcError::Call(CallError::Failed(e)) => e
+                .downcast_ref::<IndexerError>()
+                .map_or(StatusCode::INTERNAL_SERVER_ERROR, status_of),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        RestError(status, e.to_string())
+    }
+}
+
+/// The status to respond to a request that failed with `e` with.
+fn status_of(e: &IndexerError) -> StatusCode {
+    match e {
+        IndexerError::NotFound(_) => StatusCode::NOT_FOUND,
+        IndexerError::InvalidArgumentError(_)
+        | IndexerError::InvalidTransactionDigestError(_)
+        | IndexerError::ObjectIdParseError(_)
+        | IndexerError::UserInputError(_) => StatusCode::BAD_REQUEST,
+        IndexerError::ErrorWithContext(_, e) => status_of(e),
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn bad_request(e: impl std::fmt::Display) -> RestError {
+    RestError(StatusCode::BAD_REQUEST, e.to_string())
+}
+
+/// Serialize `value` as JSON, and attach an `ETag` derived from its contents. If the request's
+/// `If-None-Match` header matches the tag, the body is elided.
+fn respond<T: Serialize>(headers: &HeaderMap, value: &T) -> Result<Response, RestError> {
+    let body = serde_json::to_vec(value)
+        .map_err(|e| RestError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let etag = format!("\"{}\"", Hex::encode(Blake2b256::digest(&body).digest));
+    let etag_value = HeaderValue::from_str(&etag)
+        .map_err(|e| RestError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let matches = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| {
+            v.split(',')
+                .any(|tag| tag.trim() == etag || tag.trim() == "*")
+        });
+
+    if matches {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag_value)]).into_response());
+    }
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::ETAG, etag_value),
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+fn parse<T: FromStr>(s: &str) -> Result<T, RestError>
+where
+    T::Err: std::fmt::Display,
+{
+    T::from_str(s).map_err(bad_request)
+}
+
+async fn openapi(headers: HeaderMap) -> Result<Response, RestError> {
+    // Generating the JSON-RPC modules' schemas isn't free, and they never change.
+    static SPEC: OnceLock<serde_json::Value> = OnceLock::new();
+    respond(&headers, SPEC.get_or_init(openapi_spec))
+}
+
+async fn get_balances<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    Path(address): Path<String>,
+    Query(params): Query<CoinTypeParams>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let owner: SuiAddress = parse(&address)?;
+    if let Some(coin_type) = params.coin_type {
+        let balance = state.coin.get_balance(owner, Some(coin_type)).await?;
+        respond(&headers, &balance)
+    } else {
+        let balances = state.coin.get_all_balances(owner).await?;
+        respond(&headers, &balances)
+    }
+}
+
+async fn get_owned_objects<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    Path(address): Path<String>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let owner: SuiAddress = parse(&address)?;
+    let cursor = params
+        .cursor
+        .as_deref()
+        .map(parse::<ObjectID>)
+        .transpose()?;
+    let query = SuiObjectResponseQuery::new_with_options(
+        SuiObjectDataOptions::new().with_type().with_owner(),
+    );
+    let page = state
+        .indexer
+        .get_owned_objects(owner, Some(query), cursor, params.limit, None)
+        .await?;
+    respond(&headers, &page)
+}
+
+async fn get_transactions<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    Path(address): Path<String>,
+    Query(params): Query<PageParams>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let addr: SuiAddress = parse(&address)?;
+    let cursor = params
+        .cursor
+        .as_deref()
+        .map(parse::<TransactionDigest>)
+        .transpose()?;
+    let query = SuiTransactionBlockResponseQuery::new(
+        Some(TransactionFilter::FromOrToAddress { addr }),
+        Some(SuiTransactionBlockResponseOptions::new().with_effects()),
+    );
+    let page = state
+        .indexer
+        .query_transaction_blocks(query, cursor, params.limit, Some(params.descending), None)
+        .await?;
+    respond(&headers, &page)
+}
+
+async fn get_stakes<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    Path(address): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let owner: SuiAddress = parse(&address)?;
+    let stakes = state.governance.get_stakes(owner, None).await?;
+    respond(&headers, &stakes)
+}
+
+async fn get_object<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    Path(object_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let object_id: ObjectID = parse(&object_id)?;
+    let object = state
+        .read
+        .get_object(object_id, Some(SuiObjectDataOptions::full_content()))
+        .await?;
+    if object.data.is_none() {
+        return Err(RestError(
+            StatusCode::NOT_FOUND,
+            format!("Object {object_id} not found"),
+        ));
+    }
+    respond(&headers, &object)
+}
+
+async fn get_transaction<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    Path(digest): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let digest: TransactionDigest = parse(&digest)?;
+    let tx = state
+        .read
+        .get_transaction_block(
+            digest,
+            Some(SuiTransactionBlockResponseOptions::full_content()),
+        )
+        .await?;
+    respond(&headers, &tx)
+}
+
+async fn get_latest_checkpoint<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let seq = state.read.get_latest_checkpoint_sequence_number().await?;
+    let checkpoint = state
+        .read
+        .get_checkpoint(CheckpointId::SequenceNumber(*seq))
+        .await?;
+    respond(&headers, &checkpoint)
+}
+
+async fn get_checkpoint<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    Path(sequence_number): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let seq: u64 = parse(&sequence_number)?;
+    let checkpoint = state
+        .read
+        .get_checkpoint(CheckpointId::SequenceNumber(seq))
+        .await?;
+    respond(&headers, &checkpoint)
+}
+
+async fn get_current_epoch<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let epoch = state.extended.get_current_epoch().await?;
+    respond(&headers, &epoch)
+}
+
+async fn get_coin_metadata<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    Path(coin_type): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let Some(metadata) = state.coin.get_coin_metadata(coin_type.clone()).await? else {
+        return Err(RestError(
+            StatusCode::NOT_FOUND,
+            format!("No metadata found for coin type {coin_type}"),
+        ));
+    };
+    respond(&headers, &metadata)
+}
+
+async fn get_system_state<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let system_state = state.governance.get_latest_sui_system_state(None).await?;
+    respond(&headers, &system_state)
+}
+
+async fn get_reference_gas_price<R: IndexerReadStore>(
+    State(state): State<RestState<R>>,
+    headers: HeaderMap,
+) -> Result<Response, RestError> {
+    let rgp = state.governance.get_reference_gas_price().await?;
+    respond(&headers, &rgp)
+}
+
+/// The JSON-RPC API modules whose methods the REST facade's routes respond with the results of.
+fn rpc_modules() -> Vec<Module> {
+    vec![
+        sui_json_rpc_api::ReadApiOpenRpc::module_doc(),
+        sui_json_rpc_api::IndexerApiOpenRpc::module_doc(),
+        sui_json_rpc_api::CoinReadApiOpenRpc::module_doc(),
+        sui_json_rpc_api::GovernanceReadApiOpenRpc::module_doc(),
+        sui_json_rpc_api::ExtendedApiOpenRpc::module_doc(),
+    ]
+}
+
+/// The schema of a response from a route responding with the results of `rpc_methods`, or `None`
+/// if any of the methods has no result schema in `modules`.
+fn response_schema(modules: &[Module], rpc_methods: &[&str]) -> Option<serde_json::Value> {
+    let mut schemas = rpc_methods
+        .iter()
+        .map(|method| modules.iter().find_map(|m| m.result_schema(method)))
+        .collect::<Option<Vec<_>>>()?;
+    if schemas.len() == 1 {
+        schemas.pop()
+    } else {
+        Some(serde_json::json!({ "oneOf": schemas }))
+    }
+}
+
+/// Converts a route's path to an OpenAPI path template, e.g. `/v1/objects/:object_id` to
+/// `/v1/objects/{object_id}`.
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(param) => format!("{{{param}}}"),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// OpenAPI 3.1 description of the REST facade. Response schemas are those of the results of the
+/// corresponding JSON-RPC methods, which are also referenced by name in each operation's
+/// description, and the types they refer to are included as components.
+fn openapi_spec() -> serde_json::Value {
+    use serde_json::json;
+
+    let modules = rpc_modules();
+    // The description doesn't depend on the store that the handlers read from.
+    let paths: serde_json::Map<_, _> = routes::<IndexerReader>()
+        .into_iter()
+        .map(|route| {
+            let methods = route
+                .rpc_methods
+                .iter()
+                .map(|m| format!("`{m}`"))
+                .collect::<Vec<_>>()
+                .join(" or ");
+            let schema = response_schema(&modules, route.rpc_methods).unwrap_or_else(|| json!({}));
+            let operation = json!({
+                "get": {
+                    "summary": route.summary,
+                    "description": format!("Response body matches the result of {methods}."),
+                    "parameters": route.parameters,
+                    "responses": {
+                        "200": {
+                            "description": "OK",
+                            "headers": { "ETag": { "schema": { "type": "string" } } },
+                            "content": { "application/json": { "schema": schema } },
+                        },
+                        "304": { "description": "Not Modified" },
+                        "400": { "description": "Invalid request parameters" },
+                        "404": { "description": "Not Found" },
+                    },
+                }
+            });
+            (openapi_path(route.path), operation)
+        })
+        .collect();
+    let schemas: serde_json::Map<_, _> = modules
+        .iter()
+        .flat_map(Module::schemas)
+        .map(|(name, schema)| (name.to_string(), schema))
+        .collect();
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "Sui Indexer REST API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": { "schemas": schemas },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::ErrorObject;
+
+    /// Every schema reference in `value`.
+    fn references(value: &serde_json::Value) -> Vec<&str> {
+        match value {
+            serde_json::Value::Object(map) => map
+                .iter()
+                .flat_map(|(key, value)| match (key.as_str(), value.as_str()) {
+                    ("$ref", Some(reference)) => vec![reference],
+                    _ => references(value),
+                })
+                .collect(),
+            serde_json::Value::Array(values) => values.iter().flat_map(references).collect(),
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_openapi_response_schemas() {
+        let modules = rpc_modules();
+        for route in routes::<IndexerReader>() {
+            assert!(
+                response_schema(&modules, route.rpc_methods).is_some(),
+                "No response schema for {}",
+                route.path
+            );
+        }
+
+        let spec = openapi_spec();
+        let paths = spec["paths"].as_object().unwrap();
+        assert_eq!(paths.len(), routes::<IndexerReader>().len());
+        assert!(paths.contains_key("/v1/objects/{object_id}"));
+        for (path, operation) in paths {
+            let schema =
+                &operation["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+            assert_ne!(
+                schema,
+                &serde_json::json!({}),
+                "No response schema for {path}"
+            );
+        }
+
+        // Every type a response refers to is described.
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        for reference in references(&spec) {
+            let name = reference.strip_prefix("#/components/schemas/").unwrap();
+            assert!(schemas.contains_key(name), "Missing schema for {name}");
+        }
+    }
+
+    fn status(e: RpcError) -> StatusCode {
+        RestError::from(e).0
+    }
+
+    #[test]
+    fn test_error_status() {
+        let failed = |e: IndexerError| RpcError::Call(CallError::Failed(e.into()));
+        let invalid_params =
+            |e: SuiRpcInputError| RpcError::Call(CallError::InvalidParams(e.into()));
+
+        assert_eq!(
+            status(failed(IndexerError::InvalidArgumentError("limit".into()))),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(failed(IndexerError::NotFound(
+                "Checkpoint 7 not found".into()
+            ))),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(failed(IndexerError::ErrorWithContext(
+                "Failed to get checkpoint".into(),
+                Box::new(IndexerError::NotFound("Checkpoint 7 not found".into())),
+            ))),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(failed(IndexerError::PostgresReadError("closed".into()))),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        assert_eq!(
+            status(invalid_params(SuiRpcInputError::SizeLimitExceeded(
+                "50".into()
+            ))),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(invalid_params(SuiRpcInputError::GenericNotFound(
+                "Transaction not found".into()
+            ))),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(RpcError::Call(CallError::Custom(ErrorObject::owned(
+                INVALID_PARAMS_CODE,
+                "Invalid params",
+                None::<()>,
+            )))),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
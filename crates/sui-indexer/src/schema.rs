@@ -2,6 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    active_address_counts (day) {
+        day -> Int8,
+        daily_active_addresses -> Int8,
+        weekly_active_addresses -> Int8,
+    }
+}
+
 diesel::table! {
     active_addresses (address) {
         address -> Bytea,
@@ -12,6 +20,26 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    address_daily_stats (day, address) {
+        address -> Bytea,
+        day -> Int8,
+        sent_tx_count -> Int8,
+        received_tx_count -> Int8,
+        sui_sent -> Int8,
+        sui_received -> Int8,
+        last_tx_sequence_number -> Int8,
+    }
+}
+
+diesel::table! {
+    address_labels (address) {
+        address -> Bytea,
+        name -> Text,
+        category -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     address_metrics (checkpoint) {
         checkpoint -> Int8,
@@ -33,6 +61,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    balance_changes (owner, coin_type, tx_sequence_number) {
+        owner -> Bytea,
+        coin_type -> Text,
+        tx_sequence_number -> Int8,
+        timestamp_ms -> Int8,
+        amount -> Int8,
+    }
+}
+
 diesel::table! {
     checkpoints (sequence_number) {
         sequence_number -> Int8,
@@ -95,6 +133,36 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    equivocations (object_id, object_version, transaction_digest, conflicting_digest) {
+        object_id -> Bytea,
+        object_version -> Int8,
+        sender -> Bytea,
+        transaction_digest -> Bytea,
+        conflicting_digest -> Bytea,
+        timestamp_ms -> Int8,
+    }
+}
+
+diesel::table! {
+    event_addresses (address, tx_sequence_number, event_sequence_number) {
+        address -> Bytea,
+        tx_sequence_number -> Int8,
+        event_sequence_number -> Int8,
+        checkpoint_sequence_number -> Int8,
+    }
+}
+
+diesel::table! {
+    event_schemas (event_type, package_id) {
+        event_type -> Text,
+        package_id -> Bytea,
+        layout -> Jsonb,
+        first_seen_checkpoint -> Int8,
+        last_seen_checkpoint -> Int8,
+    }
+}
+
 diesel::table! {
     events (tx_sequence_number, event_sequence_number) {
         tx_sequence_number -> Int8,
@@ -107,6 +175,22 @@ diesel::table! {
         event_type -> Text,
         timestamp_ms -> Int8,
         bcs -> Bytea,
+        compression -> Int2,
+    }
+}
+
+diesel::table! {
+    gas_price_stats (first_checkpoint) {
+        first_checkpoint -> Int8,
+        last_checkpoint -> Int8,
+        epoch -> Int8,
+        timestamp_ms -> Int8,
+        reference_gas_price -> Int8,
+        transaction_count -> Int8,
+        mean_gas_price -> Nullable<Float8>,
+        median_gas_price -> Nullable<Int8>,
+        min_gas_price -> Nullable<Int8>,
+        max_gas_price -> Nullable<Int8>,
     }
 }
 
@@ -133,6 +217,31 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    nft_transfers (object_id, object_version) {
+        object_id -> Bytea,
+        object_version -> Int8,
+        object_type -> Text,
+        transition -> Int2,
+        sender -> Bytea,
+        from_owner -> Nullable<Bytea>,
+        to_owner -> Nullable<Bytea>,
+        transaction_digest -> Bytea,
+        checkpoint_sequence_number -> Int8,
+        timestamp_ms -> Int8,
+    }
+}
+
+diesel::table! {
+    object_transitions (object_id, object_version) {
+        object_id -> Bytea,
+        object_version -> Int8,
+        transition -> Int2,
+        transaction_digest -> Bytea,
+        checkpoint_sequence_number -> Int8,
+    }
+}
+
 diesel::table! {
     objects (object_id) {
         object_id -> Bytea,
@@ -212,6 +321,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    package_call_metrics (package, module, function, checkpoint_sequence_number) {
+        package -> Bytea,
+        module -> Text,
+        function -> Text,
+        checkpoint_sequence_number -> Int8,
+        epoch -> Int8,
+        call_count -> Int8,
+        transaction_count -> Int8,
+        computation_cost -> Int8,
+        storage_cost -> Int8,
+        storage_rebate -> Int8,
+        non_refundable_storage_fee -> Int8,
+    }
+}
+
 diesel::table! {
     packages (package_id) {
         package_id -> Bytea,
@@ -232,6 +357,7 @@ diesel::table! {
         events -> Array<Nullable<Bytea>>,
         transaction_kind -> Int2,
         success_command_count -> Int2,
+        compression -> Int2,
     }
 }
 
@@ -267,6 +393,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    tx_classes (class, tx_sequence_number) {
+        tx_sequence_number -> Int8,
+        class -> Int2,
+    }
+}
+
 diesel::table! {
     tx_count_metrics (checkpoint_sequence_number) {
         checkpoint_sequence_number -> Int8,
@@ -299,28 +432,64 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    validator_apys (epoch, validator_address) {
+        epoch -> Int8,
+        validator_address -> Bytea,
+        apy -> Float8,
+    }
+}
+
+diesel::table! {
+    validator_history (epoch, validator_address) {
+        epoch -> Int8,
+        validator_address -> Bytea,
+        commission_rate -> Int8,
+        next_commission_rate -> Nullable<Int8>,
+        stake_inflow -> Int8,
+        stake_outflow -> Int8,
+        reported_by -> Array<Nullable<Bytea>>,
+        slashed -> Bool,
+        at_risk_epochs -> Nullable<Int8>,
+    }
+}
+
 diesel::allow_tables_to_appear_in_same_query!(
+    active_address_counts,
     active_addresses,
+    address_daily_stats,
+    address_labels,
     address_metrics,
     addresses,
+    balance_changes,
     checkpoints,
     display,
     epoch_peak_tps,
     epochs,
+    equivocations,
+    event_addresses,
+    event_schemas,
     events,
+    gas_price_stats,
     move_call_metrics,
     move_calls,
+    nft_transfers,
+    object_transitions,
     objects,
     objects_history,
     objects_history_partition_0,
     objects_snapshot,
+    package_call_metrics,
     packages,
     transactions,
     transactions_partition_0,
     tx_calls,
     tx_changed_objects,
+    tx_classes,
     tx_count_metrics,
     tx_input_objects,
     tx_recipients,
     tx_senders,
+    validator_apys,
+    validator_history,
 );
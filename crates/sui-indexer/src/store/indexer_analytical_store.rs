@@ -73,4 +73,44 @@ pub trait IndexerAnalyticalStore {
         end_tx_seq: i64,
     ) -> IndexerResult<()>;
     async fn calculate_and_persist_move_call_metrics(&self, epoch: i64) -> IndexerResult<()>;
+
+    // for address daily stats
+    async fn get_address_daily_stats_last_processed_tx_seq(&self) -> IndexerResult<Option<TxSeq>>;
+    fn persist_address_daily_stats_in_tx_range(
+        &self,
+        start_tx_seq: i64,
+        end_tx_seq: i64,
+    ) -> IndexerResult<()>;
+    async fn prune_address_daily_stats(&self, before_day: i64) -> IndexerResult<()>;
+    /// Count the active addresses of each day before `before_day` that hasn't been counted yet.
+    async fn persist_active_address_counts(&self, before_day: i64) -> IndexerResult<()>;
+
+    // for balance changes
+    async fn get_balance_changes_last_processed_tx_seq(&self) -> IndexerResult<Option<TxSeq>>;
+    fn persist_balance_changes_in_tx_range(
+        &self,
+        start_tx_seq: i64,
+        end_tx_seq: i64,
+    ) -> IndexerResult<()>;
+
+    // for gas price stats
+    /// The checkpoint following the last range that gas price stats have been persisted for.
+    async fn get_gas_price_stats_next_checkpoint(&self) -> IndexerResult<i64>;
+    fn persist_gas_price_stats(
+        &self,
+        first_checkpoint: i64,
+        last_checkpoint: i64,
+    ) -> IndexerResult<()>;
+
+    // for validator apys
+    /// The epoch following the last one that validator APYs have been persisted for.
+    async fn get_validator_apys_next_epoch(&self) -> IndexerResult<i64>;
+    /// Persist the APYs of the validators' staking pools in `epoch`, which must have ended.
+    fn persist_validator_apys(&self, epoch: i64) -> IndexerResult<()>;
+
+    // for validator history
+    /// The epoch following the last one that validator history has been persisted for.
+    async fn get_validator_history_next_epoch(&self) -> IndexerResult<i64>;
+    /// Persist the history of the validators active in `epoch`, which must have ended.
+    fn persist_validator_history(&self, epoch: i64) -> IndexerResult<()>;
 }
@@ -0,0 +1,366 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use move_core_types::annotated_value::MoveStructLayout;
+use move_core_types::language_storage::StructTag;
+use sui_json_rpc::read_your_writes::CheckpointWatermark;
+use sui_json_rpc_types::{
+    AccountActivity, AccountRanking, AccountStatement, AccountStatementCursor, ActiveAddressCounts,
+    AddressLabels, AddressMetrics, Balance, BalanceSnapshot, Checkpoint, CheckpointId,
+    Coin as SuiCoin, DisplayFieldsResponse, EpochInfo, EquivocationReport, EventFilter,
+    EventSchema, EventSchemaCursor, GasPriceStats, MoveCallMetrics, NetworkMetrics, NftTransfer,
+    ObjectChange, ObjectLifecycle, ObjectTransaction, PackageMetrics, SuiCoinMetadata, SuiEvent,
+    SuiObjectDataFilter, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    TimestampDirection, TransactionFilter, TransactionInputObject, ValidatorApyHistory,
+    ValidatorEpochHistory,
+};
+use sui_protocol_config::ProtocolConfig;
+use sui_types::balance::Supply;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::committee::EpochId;
+use sui_types::digests::TransactionDigest;
+use sui_types::display::DisplayVersionUpdatedEvent;
+use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName};
+use sui_types::event::EventID;
+use sui_types::id::ID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::move_package::MovePackage;
+use sui_types::object::{Object, ObjectRead};
+use sui_types::sui_system_state::sui_system_state_summary::{
+    SuiSystemStateSummary, SuiValidatorSummary,
+};
+
+use crate::types::IndexerResult;
+
+/// The storage backend the JSON-RPC and REST APIs read indexed data from, the counterpart of
+/// [`crate::store::IndexerStore`] on the read path. Reads return the types that the APIs serve,
+/// rather than the rows of any one backend, so that a store other than Postgres (see
+/// [`crate::indexer_reader::IndexerReader`]) can serve them.
+///
+/// The store is also the watermark that reads-your-writes waits on: a transaction is visible to
+/// the APIs once the store has caught up to its checkpoint.
+#[async_trait]
+pub trait IndexerReadStore: CheckpointWatermark + Clone + Send + Sync + 'static {
+    // Checkpoints and epochs
+
+    async fn get_checkpoint(
+        &self,
+        checkpoint_id: CheckpointId,
+    ) -> IndexerResult<Option<Checkpoint>>;
+
+    async fn get_latest_checkpoint(&self) -> IndexerResult<Checkpoint>;
+
+    async fn get_checkpoints(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<Checkpoint>>;
+
+    /// The checkpoint closest to `timestamp_ms`, in `direction`.
+    async fn get_checkpoint_by_timestamp(
+        &self,
+        timestamp_ms: u64,
+        direction: TimestampDirection,
+    ) -> IndexerResult<Option<Checkpoint>>;
+
+    /// The epoch `epoch`, or the latest one if it is `None`.
+    async fn get_epoch_info(&self, epoch: Option<EpochId>) -> IndexerResult<Option<EpochInfo>>;
+
+    async fn get_epochs(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<EpochInfo>>;
+
+    /// The protocol config of the latest epoch.
+    async fn get_latest_protocol_config(&self) -> IndexerResult<ProtocolConfig>;
+
+    async fn get_latest_sui_system_state(&self) -> IndexerResult<SuiSystemStateSummary>;
+
+    /// The system state as of the end of `checkpoint`.
+    async fn get_sui_system_state_at_checkpoint(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<SuiSystemStateSummary>;
+
+    /// The validator stored under `key` in the table `table_id`, e.g. an inactive validator by
+    /// the ID of its staking pool.
+    async fn get_validator_from_table(
+        &self,
+        table_id: ObjectID,
+        key: ID,
+    ) -> IndexerResult<SuiValidatorSummary>;
+
+    // Objects and packages
+
+    async fn get_object(&self, object_id: ObjectID) -> IndexerResult<Option<Object>>;
+
+    async fn get_object_read(&self, object_id: ObjectID) -> IndexerResult<ObjectRead>;
+
+    /// The latest versions of the live objects among `object_ids`.
+    async fn multi_get_objects(&self, object_ids: Vec<ObjectID>) -> IndexerResult<Vec<Object>>;
+
+    async fn multi_get_objects_at_checkpoint(
+        &self,
+        object_ids: Vec<ObjectID>,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<Vec<ObjectRead>>;
+
+    /// The objects owned by `address`, as of `at_checkpoint` if it is set, or the latest ones.
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+        filter: Option<SuiObjectDataFilter>,
+        cursor: Option<ObjectID>,
+        limit: usize,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> IndexerResult<Vec<ObjectRead>>;
+
+    async fn get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> IndexerResult<Vec<DynamicFieldInfo>>;
+
+    /// The `Field` objects of the dynamic fields of `parent_object_id`.
+    async fn get_dynamic_field_objects(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> IndexerResult<Vec<Object>>;
+
+    /// The BCS bytes of the value of `name`, which dynamic field IDs are derived from.
+    async fn bcs_name_from_dynamic_field_name(
+        &self,
+        name: &DynamicFieldName,
+    ) -> IndexerResult<Vec<u8>>;
+
+    async fn get_package(&self, package_id: ObjectID) -> IndexerResult<Option<MovePackage>>;
+
+    async fn get_struct_layout(&self, struct_tag: StructTag) -> IndexerResult<MoveStructLayout>;
+
+    async fn get_display_fields(
+        &self,
+        object: &Object,
+        layout: &Option<MoveStructLayout>,
+    ) -> IndexerResult<DisplayFieldsResponse>;
+
+    async fn get_display_object_by_type(
+        &self,
+        object_type: &StructTag,
+    ) -> IndexerResult<Option<DisplayVersionUpdatedEvent>>;
+
+    async fn get_address_labels(&self, addresses: Vec<SuiAddress>) -> IndexerResult<AddressLabels>;
+
+    // Transactions and events
+
+    async fn multi_get_transaction_block_response(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: SuiTransactionBlockResponseOptions,
+    ) -> IndexerResult<Vec<SuiTransactionBlockResponse>>;
+
+    async fn query_transaction_blocks(
+        &self,
+        filter: Option<TransactionFilter>,
+        options: SuiTransactionBlockResponseOptions,
+        cursor: Option<TransactionDigest>,
+        limit: usize,
+        is_descending: bool,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> IndexerResult<Vec<SuiTransactionBlockResponse>>;
+
+    async fn get_transaction_events(
+        &self,
+        digest: TransactionDigest,
+    ) -> IndexerResult<Vec<SuiEvent>>;
+
+    async fn query_events(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+        at_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> IndexerResult<Vec<SuiEvent>>;
+
+    async fn get_events_by_address(
+        &self,
+        address: SuiAddress,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<SuiEvent>>;
+
+    /// The events of type `event_type` emitted by the version `package_id` of its package, or
+    /// `None` if `package_id` is not a version of it.
+    async fn get_events_by_package_version(
+        &self,
+        event_type: String,
+        package_id: ObjectID,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Option<Vec<SuiEvent>>>;
+
+    async fn get_event_schemas(
+        &self,
+        event_type: Option<StructTag>,
+        cursor: Option<EventSchemaCursor>,
+        limit: usize,
+    ) -> IndexerResult<Vec<EventSchema>>;
+
+    async fn get_transaction_input_objects(
+        &self,
+        digest: TransactionDigest,
+    ) -> IndexerResult<Option<Vec<TransactionInputObject>>>;
+
+    async fn get_transaction_object_changes(
+        &self,
+        digest: TransactionDigest,
+    ) -> IndexerResult<Option<Vec<(ObjectChange, Option<ObjectRead>)>>>;
+
+    async fn get_object_lifecycle(
+        &self,
+        object_id: ObjectID,
+    ) -> IndexerResult<Option<ObjectLifecycle>>;
+
+    async fn get_object_transaction_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<TransactionDigest>,
+        limit: usize,
+    ) -> IndexerResult<Vec<ObjectTransaction>>;
+
+    async fn get_equivocation_reports(
+        &self,
+        senders: Vec<SuiAddress>,
+    ) -> IndexerResult<Vec<EquivocationReport>>;
+
+    // Coins and NFTs
+
+    async fn get_owned_coins(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        cursor: ObjectID,
+        limit: usize,
+    ) -> IndexerResult<Vec<SuiCoin>>;
+
+    /// The balances of `owner`, in `coin_type` if it is set, or in every coin otherwise.
+    async fn get_coin_balances(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+    ) -> IndexerResult<Vec<Balance>>;
+
+    async fn get_coin_metadata(
+        &self,
+        coin_struct: StructTag,
+    ) -> IndexerResult<Option<SuiCoinMetadata>>;
+
+    async fn multi_get_coin_metadata(
+        &self,
+        coin_structs: Vec<StructTag>,
+    ) -> IndexerResult<Vec<Option<SuiCoinMetadata>>>;
+
+    async fn get_total_supply(&self, coin_struct: StructTag) -> IndexerResult<Supply>;
+
+    async fn get_account_statement(
+        &self,
+        address: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<AccountStatementCursor>,
+        limit: usize,
+    ) -> IndexerResult<AccountStatement>;
+
+    async fn get_balance_history(
+        &self,
+        address: SuiAddress,
+        coin_type: String,
+        interval_ms: i64,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+        limit: usize,
+    ) -> IndexerResult<Vec<BalanceSnapshot>>;
+
+    async fn get_owned_nfts(
+        &self,
+        address: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> IndexerResult<Vec<ObjectRead>>;
+
+    /// The type of NFT `object_id`, its latest version, and its transfers, or `None` if it is
+    /// not an NFT.
+    async fn get_nft_transfers(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> IndexerResult<Option<(String, Option<ObjectRead>, Vec<NftTransfer>)>>;
+
+    // Metrics
+
+    async fn get_latest_network_metrics(&self) -> IndexerResult<NetworkMetrics>;
+
+    async fn get_latest_move_call_metrics(&self) -> IndexerResult<MoveCallMetrics>;
+
+    async fn get_gas_price_history(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<GasPriceStats>>;
+
+    async fn get_validator_apy_history(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> IndexerResult<Vec<ValidatorApyHistory>>;
+
+    async fn get_validator_history(
+        &self,
+        address: SuiAddress,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> IndexerResult<Vec<ValidatorEpochHistory>>;
+
+    async fn get_package_metrics(
+        &self,
+        package_id: ObjectID,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> IndexerResult<PackageMetrics>;
+
+    async fn get_latest_address_metrics(&self) -> IndexerResult<AddressMetrics>;
+
+    async fn get_checkpoint_address_metrics(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> IndexerResult<AddressMetrics>;
+
+    async fn get_all_epoch_address_metrics(
+        &self,
+        descending_order: Option<bool>,
+    ) -> IndexerResult<Vec<AddressMetrics>>;
+
+    async fn get_top_accounts(
+        &self,
+        rank_by: AccountRanking,
+        days: u64,
+        limit: usize,
+    ) -> IndexerResult<Vec<AccountActivity>>;
+
+    async fn get_active_address_counts(
+        &self,
+        start_day: i64,
+        end_day: i64,
+        limit: usize,
+    ) -> IndexerResult<Vec<ActiveAddressCounts>>;
+}
@@ -4,18 +4,25 @@
 use async_trait::async_trait;
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
-use std::any::Any;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
+use sui_package_resolver::PackageStore;
 
 use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::CheckpointDigest;
 use sui_types::object::ObjectRead;
 
 use crate::errors::IndexerError;
-use crate::handlers::{EpochToCommit, TransactionObjectChangesToCommit};
+use crate::handlers::EpochToCommit;
+pub use crate::handlers::TransactionObjectChangesToCommit;
 
 use crate::models::display::StoredDisplay;
+use crate::models::event_addresses::StoredEventAddress;
+use crate::models::event_schemas::StoredEventSchema;
+use crate::models::nft_transfers::StoredNftTransfer;
+use crate::models::object_transitions::StoredObjectTransition;
 use crate::models::objects::{StoredDeletedObject, StoredObject};
+use crate::models::package_call_metrics::StoredPackageCallMetrics;
 use crate::types::{IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex};
 
 #[allow(clippy::large_enum_variant)]
@@ -24,19 +31,33 @@ pub enum ObjectChangeToCommit {
     DeletedObject(StoredDeletedObject),
 }
 
+/// The storage backend the writer commits indexed checkpoints to, and resolves packages from.
+/// The APIs read it back through [`crate::store::IndexerReadStore`].
 #[async_trait]
-pub trait IndexerStore: Any + Clone + Sync + Send + 'static {
+pub trait IndexerStore: Clone + Sync + Send + 'static {
     type ModuleCache: GetModule<Item = Arc<CompiledModule>, Error = anyhow::Error>
         + Send
         + Sync
         + 'static;
 
+    /// Reads the packages committed to the store, for resolving the layouts of the objects and
+    /// events being indexed.
+    type PackageStore: PackageStore;
+
     async fn get_latest_tx_checkpoint_sequence_number(&self) -> Result<Option<u64>, IndexerError>;
 
+    /// The sequence number and digest of the latest checkpoint committed.
+    async fn get_latest_checkpoint_digest(
+        &self,
+    ) -> Result<Option<(u64, CheckpointDigest)>, IndexerError>;
+
     async fn get_latest_object_snapshot_checkpoint_sequence_number(
         &self,
     ) -> Result<Option<u64>, IndexerError>;
 
+    /// The types of the objects that have a Display committed, as canonical type strings.
+    async fn get_display_object_types(&self) -> Result<HashSet<String>, IndexerError>;
+
     async fn get_object_read(
         &self,
         object_id: ObjectID,
@@ -74,6 +95,31 @@ pub trait IndexerStore: Any + Clone + Sync + Send + 'static {
         display_updates: BTreeMap<String, StoredDisplay>,
     ) -> Result<(), IndexerError>;
 
+    async fn persist_event_schemas(
+        &self,
+        event_schemas: Vec<StoredEventSchema>,
+    ) -> Result<(), IndexerError>;
+
+    async fn persist_event_addresses(
+        &self,
+        addresses: Vec<StoredEventAddress>,
+    ) -> Result<(), IndexerError>;
+
+    async fn persist_object_transitions(
+        &self,
+        transitions: Vec<StoredObjectTransition>,
+    ) -> Result<(), IndexerError>;
+
+    async fn persist_nft_transfers(
+        &self,
+        transfers: Vec<StoredNftTransfer>,
+    ) -> Result<(), IndexerError>;
+
+    async fn persist_package_call_metrics(
+        &self,
+        metrics: Vec<StoredPackageCallMetrics>,
+    ) -> Result<(), IndexerError>;
+
     async fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError>;
 
     async fn persist_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError>;
@@ -87,5 +133,5 @@ pub trait IndexerStore: Any + Clone + Sync + Send + 'static {
 
     fn module_cache(&self) -> Arc<Self::ModuleCache>;
 
-    fn as_any(&self) -> &dyn Any;
+    fn package_store(&self) -> Self::PackageStore;
 }
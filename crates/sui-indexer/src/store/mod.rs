@@ -2,13 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub(crate) use indexer_analytical_store::*;
+pub use indexer_read_store::IndexerReadStore;
 pub(crate) use indexer_store::*;
 pub use pg_indexer_analytical_store::PgIndexerAnalyticalStore;
 pub use pg_indexer_store::PgIndexerStore;
 
 mod indexer_analytical_store;
+mod indexer_read_store;
 pub mod indexer_store;
 pub mod module_resolver;
+mod pg_copy;
 mod pg_indexer_analytical_store;
 mod pg_indexer_store;
 mod pg_partition_manager;
@@ -43,6 +46,11 @@ pub(crate) mod diesel_macro {
                     .read_write()
                     .run($query)
                     .map_err(|e| {
+                        let e: IndexerError = e.into();
+                        // Retrying won't give the lease back.
+                        if matches!(e, IndexerError::WriterLeaseLost(_)) {
+                            return backoff::Error::Permanent(e);
+                        }
                         tracing::error!("Error with persisting data into DB: {:?}", e);
                         backoff::Error::Transient {
                             err: IndexerError::PostgresWriteError(e.to_string()),
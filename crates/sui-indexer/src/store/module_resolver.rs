@@ -130,15 +130,17 @@ impl IndexerStorePackageModuleResolver {
     }
 }
 
-pub struct InterimPackageResolver {
-    package_db_resolver: IndexerStorePackageModuleResolver,
+/// A package resolver that reads packages from those being indexed, falling back to those
+/// committed to the store.
+pub struct InterimPackageResolver<S> {
+    package_db_resolver: S,
     package_buffer: Arc<Mutex<IndexingPackageBuffer>>,
     metrics: IndexerMetrics,
 }
 
-impl InterimPackageResolver {
+impl<S> InterimPackageResolver<S> {
     pub fn new(
-        package_db_resolver: IndexerStorePackageModuleResolver,
+        package_db_resolver: S,
         package_buffer: Arc<Mutex<IndexingPackageBuffer>>,
         new_package_objects: &[(IndexedPackage, Object)],
         metrics: IndexerMetrics,
@@ -156,7 +158,7 @@ impl InterimPackageResolver {
 }
 
 #[async_trait]
-impl PackageStore for InterimPackageResolver {
+impl<S: PackageStore> PackageStore for InterimPackageResolver<S> {
     async fn version(&self, addr: AccountAddress) -> Result<SequenceNumber, PackageResolverError> {
         let package_id = ObjectID::from(addr);
         let maybe_version = {
@@ -0,0 +1,615 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bulk inserts with `COPY ... FROM STDIN (FORMAT binary)`, which Postgres ingests much faster
+//! than multi-row `INSERT`s. Diesel doesn't support `COPY`, so rows are copied over connections of
+//! their own, through libpq (which diesel's connections are built on as well).
+//!
+//! A `COPY` can't update rows that are already in its table, so rows that are upserted are copied
+//! into a temporary staging table first, and upserted into their table from there.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+
+use pq_sys::{
+    ConnStatusType, ExecStatusType, PGconn, PGresult, PQclear, PQconnectdb, PQerrorMessage, PQexec,
+    PQfinish, PQgetResult, PQputCopyData, PQputCopyEnd, PQresultErrorMessage, PQresultStatus,
+    PQstatus,
+};
+
+use crate::errors::IndexerError;
+use crate::leader::check_writer_lease_statement;
+use crate::models::events::StoredEvent;
+use crate::models::objects::{StoredDeletedHistoryObject, StoredHistoryObject, StoredObject};
+use crate::models::transactions::StoredTransaction;
+
+/// How much of a copy's data is sent to Postgres at a time.
+const COPY_DATA_CHUNK_SIZE: usize = 1 << 20;
+
+/// OID of `bytea`, the element type of the array columns that are copied.
+const BYTEA_OID: i32 = 17;
+
+/// Rows that can be copied into `TABLE`, as the values of its `COLUMNS`.
+pub(crate) trait CopyRow {
+    const TABLE: &'static str;
+    const COLUMNS: &'static [&'static str];
+
+    /// The values of the row's `COLUMNS`, in the same order.
+    fn fields(&self) -> Vec<&dyn CopyField>;
+}
+
+/// Values that can be copied into a column, in the binary format Postgres sends and receives
+/// values of the column's type in.
+pub(crate) trait CopyField {
+    fn write(&self, data: &mut Vec<u8>);
+}
+
+impl CopyField for i16 {
+    fn write(&self, data: &mut Vec<u8>) {
+        data.extend(2i32.to_be_bytes());
+        data.extend(self.to_be_bytes());
+    }
+}
+
+impl CopyField for i64 {
+    fn write(&self, data: &mut Vec<u8>) {
+        data.extend(8i32.to_be_bytes());
+        data.extend(self.to_be_bytes());
+    }
+}
+
+impl CopyField for Vec<u8> {
+    fn write(&self, data: &mut Vec<u8>) {
+        write_bytes(self, data)
+    }
+}
+
+impl CopyField for String {
+    fn write(&self, data: &mut Vec<u8>) {
+        write_bytes(self.as_bytes(), data)
+    }
+}
+
+impl<T: CopyField> CopyField for Option<T> {
+    fn write(&self, data: &mut Vec<u8>) {
+        match self {
+            Some(value) => value.write(data),
+            None => data.extend((-1i32).to_be_bytes()),
+        }
+    }
+}
+
+/// A one-dimensional `bytea[]`.
+impl CopyField for Vec<Option<Vec<u8>>> {
+    fn write(&self, data: &mut Vec<u8>) {
+        let mut array = vec![];
+        array.extend(i32::from(!self.is_empty()).to_be_bytes());
+        array.extend(i32::from(self.iter().any(Option::is_none)).to_be_bytes());
+        array.extend(BYTEA_OID.to_be_bytes());
+        if !self.is_empty() {
+            array.extend((self.len() as i32).to_be_bytes());
+            // Lower bound of the dimension.
+            array.extend(1i32.to_be_bytes());
+        }
+        for element in self {
+            element.write(&mut array);
+        }
+        write_bytes(&array, data)
+    }
+}
+
+fn write_bytes(bytes: &[u8], data: &mut Vec<u8>) {
+    data.extend((bytes.len() as i32).to_be_bytes());
+    data.extend(bytes);
+}
+
+/// The binary `COPY` data of `rows`.
+fn copy_data<R: CopyRow>(rows: &[R]) -> Vec<u8> {
+    let mut data = b"PGCOPY\n\xff\r\n\0".to_vec();
+    // Flags, and the length of the header extension.
+    data.extend(0i32.to_be_bytes());
+    data.extend(0i32.to_be_bytes());
+    for row in rows {
+        let fields = row.fields();
+        debug_assert_eq!(fields.len(), R::COLUMNS.len());
+        data.extend((fields.len() as i16).to_be_bytes());
+        for field in fields {
+            field.write(&mut data);
+        }
+    }
+    data.extend((-1i16).to_be_bytes());
+    data
+}
+
+/// Connections to copy rows over, opened as they are needed, and kept for reuse unless a copy
+/// over them fails.
+#[derive(Clone)]
+pub(crate) struct CopyConnectionPool {
+    database_url: Arc<CString>,
+    idle: Arc<Mutex<Vec<CopyConnection>>>,
+}
+
+impl CopyConnectionPool {
+    /// A pool of connections to the database at `database_url`, which is connected to once to
+    /// check that it can be.
+    pub fn new(database_url: &str) -> Result<Self, IndexerError> {
+        let database_url = CString::new(database_url).map_err(|e| {
+            IndexerError::PgConnectionPoolInitError(format!("Invalid database URL: {e}"))
+        })?;
+        let connection = CopyConnection::connect(&database_url)?;
+        Ok(Self {
+            database_url: Arc::new(database_url),
+            idle: Arc::new(Mutex::new(vec![connection])),
+        })
+    }
+
+    /// Copy `rows` into their table in one transaction, so that either all of them are inserted,
+    /// or none of them are (e.g. because one of them is already in the table). If `lease` is set,
+    /// the rows are only copied while the writer lease is still at that epoch.
+    pub fn copy_in<R: CopyRow>(&self, rows: &[R], lease: Option<i64>) -> Result<(), IndexerError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let begin = begin_statement(lease);
+        let statement = copy_statement(R::TABLE, R::COLUMNS);
+        let commit = CString::new("COMMIT").expect("COMMIT has no NUL bytes");
+        let data = copy_data(rows);
+
+        self.with_connection(|connection| {
+            connection.execute(&begin)?;
+            connection.copy_in(&statement, &data)?;
+            connection.execute(&commit)
+        })
+        .map_err(|e| {
+            IndexerError::PostgresWriteError(format!("Failed to copy rows into {}: {e}", R::TABLE))
+        })
+    }
+
+    /// Copy `rows` into a staging table, and upsert them into their table from there, updating
+    /// the rows already in it with the same `key` columns, all in one transaction. Like
+    /// [`Self::copy_in`], this only upserts them while the writer lease is still at `lease`, if
+    /// set.
+    pub fn copy_upsert<R: CopyRow>(
+        &self,
+        rows: &[R],
+        key: &[&str],
+        lease: Option<i64>,
+    ) -> Result<(), IndexerError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let staging = format!("{}_staging", R::TABLE);
+        let begin = begin_statement(lease);
+        let create = CString::new(format!(
+            "CREATE TEMPORARY TABLE {staging} (LIKE {} INCLUDING DEFAULTS) ON COMMIT DROP",
+            R::TABLE
+        ))
+        .expect("Table names have no NUL bytes");
+        let copy = copy_statement(&staging, R::COLUMNS);
+        let upsert = CString::new(upsert_statement(R::TABLE, &staging, R::COLUMNS, key))
+            .expect("Upsert statements have no NUL bytes");
+        let data = copy_data(rows);
+
+        self.with_connection(|connection| {
+            connection.execute(&begin)?;
+            connection.execute(&create)?;
+            connection.copy_in(&copy, &data)?;
+            connection.execute(&upsert)
+        })
+        .map_err(|e| {
+            IndexerError::PostgresWriteError(format!(
+                "Failed to copy rows to upsert into {}: {e}",
+                R::TABLE
+            ))
+        })
+    }
+
+    /// Run `f` over an idle connection, or a new one if there is none, which is kept for reuse
+    /// unless `f` fails (leaving it in whatever state it failed in, e.g. mid-transaction).
+    fn with_connection(
+        &self,
+        f: impl FnOnce(&mut CopyConnection) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let idle = self.idle.lock().unwrap().pop();
+        let mut connection = match idle {
+            Some(connection) => connection,
+            None => CopyConnection::connect(&self.database_url).map_err(|e| e.to_string())?,
+        };
+        f(&mut connection)?;
+        self.idle.lock().unwrap().push(connection);
+        Ok(())
+    }
+}
+
+/// Begin a transaction, checking first that the writer lease is still at `lease`, if set.
+fn begin_statement(lease: Option<i64>) -> CString {
+    let statement = match lease {
+        Some(epoch) => format!("BEGIN; {}", check_writer_lease_statement(epoch)),
+        None => "BEGIN".to_string(),
+    };
+    CString::new(statement).expect("BEGIN statements have no NUL bytes")
+}
+
+/// Upsert the rows in `staging` into `table`, by its `key` columns, and commit.
+fn upsert_statement(table: &str, staging: &str, columns: &[&str], key: &[&str]) -> String {
+    let updates = columns
+        .iter()
+        .filter(|column| !key.contains(*column))
+        .map(|column| format!("{column} = EXCLUDED.{column}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let columns = columns.join(", ");
+    format!(
+        "INSERT INTO {table} ({columns}) SELECT {columns} FROM {staging} \
+         ON CONFLICT ({}) DO UPDATE SET {updates}; \
+         COMMIT",
+        key.join(", ")
+    )
+}
+
+fn copy_statement(table: &str, columns: &[&str]) -> CString {
+    CString::new(format!(
+        "COPY {table} ({}) FROM STDIN (FORMAT binary)",
+        columns.join(", ")
+    ))
+    .expect("COPY statements have no NUL bytes")
+}
+
+struct CopyConnection(NonNull<PGconn>);
+
+// libpq connections can be used from any thread, as long as it's by one thread at a time.
+unsafe impl Send for CopyConnection {}
+
+impl CopyConnection {
+    fn connect(database_url: &CStr) -> Result<Self, IndexerError> {
+        let connection = NonNull::new(unsafe { PQconnectdb(database_url.as_ptr()) })
+            .map(Self)
+            .ok_or_else(|| {
+                IndexerError::PgConnectionPoolInitError(
+                    "Failed to allocate a connection to copy rows over".to_string(),
+                )
+            })?;
+        if !matches!(
+            unsafe { PQstatus(connection.0.as_ptr()) },
+            ConnStatusType::CONNECTION_OK
+        ) {
+            return Err(IndexerError::PgConnectionPoolInitError(format!(
+                "Failed to open a connection to copy rows over: {}",
+                connection.error()
+            )));
+        }
+        Ok(connection)
+    }
+
+    fn error(&self) -> String {
+        let message = unsafe { CStr::from_ptr(PQerrorMessage(self.0.as_ptr())) };
+        message.to_string_lossy().trim_end().to_string()
+    }
+
+    /// Run `statement`, one or more commands that return no rows.
+    fn execute(&mut self, statement: &CStr) -> Result<(), String> {
+        let result = PgResult(unsafe { PQexec(self.0.as_ptr(), statement.as_ptr()) });
+        if !matches!(result.status(), ExecStatusType::PGRES_COMMAND_OK) {
+            return Err(result.error().unwrap_or_else(|| self.error()));
+        }
+        Ok(())
+    }
+
+    /// Run `statement`, a `COPY ... FROM STDIN`, with `data`.
+    fn copy_in(&mut self, statement: &CStr, data: &[u8]) -> Result<(), String> {
+        let connection = self.0.as_ptr();
+        let result = PgResult(unsafe { PQexec(connection, statement.as_ptr()) });
+        if !matches!(result.status(), ExecStatusType::PGRES_COPY_IN) {
+            return Err(result.error().unwrap_or_else(|| self.error()));
+        }
+        drop(result);
+
+        for chunk in data.chunks(COPY_DATA_CHUNK_SIZE) {
+            let sent = unsafe {
+                PQputCopyData(
+                    connection,
+                    chunk.as_ptr() as *const c_char,
+                    chunk.len() as c_int,
+                )
+            };
+            if sent != 1 {
+                return Err(self.error());
+            }
+        }
+        if unsafe { PQputCopyEnd(connection, std::ptr::null()) } != 1 {
+            return Err(self.error());
+        }
+
+        // The outcome of the copy, followed by a null result once there are no more.
+        let mut outcome = Ok(());
+        loop {
+            let result = unsafe { PQgetResult(connection) };
+            if result.is_null() {
+                return outcome;
+            }
+            let result = PgResult(result);
+            if outcome.is_ok() && !matches!(result.status(), ExecStatusType::PGRES_COMMAND_OK) {
+                outcome = Err(result.error().unwrap_or_else(|| self.error()));
+            }
+        }
+    }
+}
+
+impl Drop for CopyConnection {
+    fn drop(&mut self) {
+        unsafe { PQfinish(self.0.as_ptr()) }
+    }
+}
+
+struct PgResult(*mut PGresult);
+
+impl PgResult {
+    fn status(&self) -> ExecStatusType {
+        unsafe { PQresultStatus(self.0) }
+    }
+
+    fn error(&self) -> Option<String> {
+        if self.0.is_null() {
+            return None;
+        }
+        let message = unsafe { CStr::from_ptr(PQresultErrorMessage(self.0)) };
+        let message = message.to_string_lossy().trim_end().to_string();
+        (!message.is_empty()).then_some(message)
+    }
+}
+
+impl Drop for PgResult {
+    fn drop(&mut self) {
+        unsafe { PQclear(self.0) }
+    }
+}
+
+impl CopyRow for StoredTransaction {
+    const TABLE: &'static str = "transactions";
+    const COLUMNS: &'static [&'static str] = &[
+        "tx_sequence_number",
+        "transaction_digest",
+        "raw_transaction",
+        "raw_effects",
+        "checkpoint_sequence_number",
+        "timestamp_ms",
+        "object_changes",
+        "balance_changes",
+        "events",
+        "transaction_kind",
+        "success_command_count",
+        "compression",
+    ];
+
+    fn fields(&self) -> Vec<&dyn CopyField> {
+        vec![
+            &self.tx_sequence_number,
+            &self.transaction_digest,
+            &self.raw_transaction,
+            &self.raw_effects,
+            &self.checkpoint_sequence_number,
+            &self.timestamp_ms,
+            &self.object_changes,
+            &self.balance_changes,
+            &self.events,
+            &self.transaction_kind,
+            &self.success_command_count,
+            &self.compression,
+        ]
+    }
+}
+
+impl CopyRow for StoredEvent {
+    const TABLE: &'static str = "events";
+    const COLUMNS: &'static [&'static str] = &[
+        "tx_sequence_number",
+        "event_sequence_number",
+        "transaction_digest",
+        "checkpoint_sequence_number",
+        "senders",
+        "package",
+        "module",
+        "event_type",
+        "timestamp_ms",
+        "bcs",
+        "compression",
+    ];
+
+    fn fields(&self) -> Vec<&dyn CopyField> {
+        vec![
+            &self.tx_sequence_number,
+            &self.event_sequence_number,
+            &self.transaction_digest,
+            &self.checkpoint_sequence_number,
+            &self.senders,
+            &self.package,
+            &self.module,
+            &self.event_type,
+            &self.timestamp_ms,
+            &self.bcs,
+            &self.compression,
+        ]
+    }
+}
+
+impl CopyRow for StoredObject {
+    const TABLE: &'static str = "objects";
+    const COLUMNS: &'static [&'static str] = &[
+        "object_id",
+        "object_version",
+        "object_digest",
+        "checkpoint_sequence_number",
+        "owner_type",
+        "owner_id",
+        "object_type",
+        "serialized_object",
+        "coin_type",
+        "coin_balance",
+        "df_kind",
+        "df_name",
+        "df_object_type",
+        "df_object_id",
+    ];
+
+    fn fields(&self) -> Vec<&dyn CopyField> {
+        vec![
+            &self.object_id,
+            &self.object_version,
+            &self.object_digest,
+            &self.checkpoint_sequence_number,
+            &self.owner_type,
+            &self.owner_id,
+            &self.object_type,
+            &self.serialized_object,
+            &self.coin_type,
+            &self.coin_balance,
+            &self.df_kind,
+            &self.df_name,
+            &self.df_object_type,
+            &self.df_object_id,
+        ]
+    }
+}
+
+impl CopyRow for StoredHistoryObject {
+    const TABLE: &'static str = "objects_history";
+    const COLUMNS: &'static [&'static str] = &[
+        "object_id",
+        "object_version",
+        "object_status",
+        "object_digest",
+        "checkpoint_sequence_number",
+        "owner_type",
+        "owner_id",
+        "object_type",
+        "serialized_object",
+        "coin_type",
+        "coin_balance",
+        "df_kind",
+        "df_name",
+        "df_object_type",
+        "df_object_id",
+    ];
+
+    fn fields(&self) -> Vec<&dyn CopyField> {
+        vec![
+            &self.object_id,
+            &self.object_version,
+            &self.object_status,
+            &self.object_digest,
+            &self.checkpoint_sequence_number,
+            &self.owner_type,
+            &self.owner_id,
+            &self.object_type,
+            &self.serialized_object,
+            &self.coin_type,
+            &self.coin_balance,
+            &self.df_kind,
+            &self.df_name,
+            &self.df_object_type,
+            &self.df_object_id,
+        ]
+    }
+}
+
+impl CopyRow for StoredDeletedHistoryObject {
+    const TABLE: &'static str = "objects_history";
+    const COLUMNS: &'static [&'static str] = &[
+        "object_id",
+        "object_version",
+        "object_status",
+        "checkpoint_sequence_number",
+    ];
+
+    fn fields(&self) -> Vec<&dyn CopyField> {
+        vec![
+            &self.object_id,
+            &self.object_version,
+            &self.object_status,
+            &self.checkpoint_sequence_number,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_data() {
+        let data = copy_data(&[StoredDeletedHistoryObject {
+            object_id: vec![0xab, 0xcd],
+            object_version: 7,
+            object_status: 1,
+            checkpoint_sequence_number: 300,
+        }]);
+
+        let mut expected = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0".to_vec();
+        expected.extend([0, 4]);
+        expected.extend([0, 0, 0, 2, 0xab, 0xcd]);
+        expected.extend([0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 7]);
+        expected.extend([0, 0, 0, 2, 0, 1]);
+        expected.extend([0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 1, 44]);
+        expected.extend([0xff, 0xff]);
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_upsert_statement() {
+        assert_eq!(
+            upsert_statement(
+                "objects",
+                "objects_staging",
+                &["id", "version", "owner"],
+                &["id"]
+            ),
+            concat!(
+                "INSERT INTO objects (id, version, owner) ",
+                "SELECT id, version, owner FROM objects_staging ",
+                "ON CONFLICT (id) DO UPDATE ",
+                "SET version = EXCLUDED.version, owner = EXCLUDED.owner; ",
+                "COMMIT",
+            )
+        );
+    }
+
+    #[test]
+    fn test_begin_statement() {
+        assert_eq!(begin_statement(None).to_str().unwrap(), "BEGIN");
+
+        // The lease is checked in the transaction, before anything is copied in it.
+        let begin = begin_statement(Some(42));
+        let begin = begin.to_str().unwrap();
+        assert!(begin.starts_with("BEGIN; DO $$"));
+        assert!(begin.contains("FROM writer_lease FOR SHARE"));
+        assert!(begin.contains("IF current <> 42 THEN"));
+    }
+
+    #[test]
+    fn test_copy_bytea_array() {
+        let mut data = vec![];
+        vec![Some(vec![1u8]), None].write(&mut data);
+        assert_eq!(
+            data,
+            [
+                [0, 0, 0, 29].as_slice(),
+                // One dimension, with nulls, of bytea.
+                &[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 17],
+                // Of length 2, starting at 1.
+                &[0, 0, 0, 2, 0, 0, 0, 1],
+                &[0, 0, 0, 1, 1],
+                &[0xff, 0xff, 0xff, 0xff],
+            ]
+            .concat()
+        );
+
+        let mut data = vec![];
+        Vec::<Option<Vec<u8>>>::new().write(&mut data);
+        assert_eq!(
+            data,
+            [0, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 17].to_vec()
+        );
+    }
+}
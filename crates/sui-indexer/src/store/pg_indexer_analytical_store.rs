@@ -1,39 +1,61 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
 use std::time::Duration;
 use tap::tap::TapFallible;
 use tracing::{error, info};
 
 use async_trait::async_trait;
 use core::result::Result::Ok;
-use diesel::dsl::count;
+use diesel::dsl::{count, max, min, sql};
+use diesel::sql_types::BigInt;
+use diesel::upsert::excluded;
 use diesel::{ExpressionMethods, OptionalExtension};
 use diesel::{QueryDsl, RunQueryDsl};
 use sui_types::base_types::ObjectID;
+use sui_types::committee::EpochId;
+use sui_types::dynamic_field::derive_dynamic_field_id;
+use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_types::sui_system_state::PoolTokenExchangeRate;
+use sui_types::TypeTag;
 
 use crate::db::PgConnectionPool;
 use crate::errors::{Context, IndexerError};
+use crate::models::address_daily_stats::aggregate_address_daily_stats;
 use crate::models::address_metrics::StoredAddressMetrics;
+use crate::models::balance_changes::stored_balance_changes;
 use crate::models::checkpoints::StoredCheckpoint;
+use crate::models::events::StoredEvent;
+use crate::models::gas_price_stats::StoredGasPriceStats;
 use crate::models::move_call_metrics::{
     build_move_call_metric_query, QueriedMoveCallMetrics, QueriedMoveMetrics, StoredMoveCallMetrics,
 };
 use crate::models::network_metrics::{StoredEpochPeakTps, Tps};
+use crate::models::objects::StoredObject;
 use crate::models::transactions::{
-    StoredTransaction, StoredTransactionCheckpoint, StoredTransactionSuccessCommandCount,
-    StoredTransactionTimestamp, TxSeq,
+    StoredRawTransaction, StoredTransaction, StoredTransactionBalanceChanges,
+    StoredTransactionCheckpoint, StoredTransactionSuccessCommandCount, StoredTransactionTimestamp,
+    TxSeq,
 };
 use crate::models::tx_count_metrics::StoredTxCountMetrics;
+use crate::models::tx_indices::{StoredTxRecipients, StoredTxSenders};
+use crate::models::validator_apys::StoredValidatorApy;
+use crate::models::validator_history::{stake_event_types, stake_flows, validator_histories};
 use crate::schema::{
-    active_addresses, address_metrics, addresses, checkpoints, epoch_peak_tps, move_call_metrics,
-    move_calls, transactions, tx_count_metrics,
+    active_address_counts, active_addresses, address_daily_stats, address_metrics, addresses,
+    balance_changes, checkpoints, epoch_peak_tps, epochs, events, gas_price_stats,
+    move_call_metrics, move_calls, objects, transactions, tx_count_metrics, tx_recipients,
+    tx_senders, validator_apys, validator_history,
 };
 use crate::store::diesel_macro::{read_only_blocking, transactional_blocking_with_retry};
-use crate::types::IndexerResult;
+use crate::types::{IndexerResult, TransactionKind};
 
 use super::IndexerAnalyticalStore;
 
+const ADDRESS_DAILY_STATS_CHUNK_SIZE: usize = 1000;
+const BALANCE_CHANGES_CHUNK_SIZE: usize = 1000;
+
 #[derive(Clone)]
 pub struct PgIndexerAnalyticalStore {
     blocking_cp: PgConnectionPool,
@@ -470,6 +492,438 @@ impl IndexerAnalyticalStore for PgIndexerAnalyticalStore {
         .context("Failed persisting move call metrics to PostgresDB")?;
         Ok(())
     }
+
+    async fn get_address_daily_stats_last_processed_tx_seq(&self) -> IndexerResult<Option<TxSeq>> {
+        let last_processed_tx_seq = read_only_blocking!(&self.blocking_cp, |conn| {
+            address_daily_stats::table
+                .select(max(address_daily_stats::last_tx_sequence_number))
+                .first::<Option<i64>>(conn)
+        })
+        .context("Failed to read address daily stats last processed tx sequence.")?;
+        Ok(last_processed_tx_seq.map(|seq| TxSeq { seq }))
+    }
+
+    fn persist_address_daily_stats_in_tx_range(
+        &self,
+        start_tx_seq: i64,
+        end_tx_seq: i64,
+    ) -> IndexerResult<()> {
+        let (txs, senders, recipients) = read_only_blocking!(&self.blocking_cp, |conn| {
+            let txs = transactions::table
+                .filter(transactions::tx_sequence_number.ge(start_tx_seq))
+                .filter(transactions::tx_sequence_number.lt(end_tx_seq))
+                .select((
+                    transactions::tx_sequence_number,
+                    transactions::timestamp_ms,
+                    transactions::balance_changes,
+                ))
+                .load::<StoredTransactionBalanceChanges>(conn)?;
+            let senders = tx_senders::table
+                .filter(tx_senders::tx_sequence_number.ge(start_tx_seq))
+                .filter(tx_senders::tx_sequence_number.lt(end_tx_seq))
+                .load::<StoredTxSenders>(conn)?;
+            let recipients = tx_recipients::table
+                .filter(tx_recipients::tx_sequence_number.ge(start_tx_seq))
+                .filter(tx_recipients::tx_sequence_number.lt(end_tx_seq))
+                .load::<StoredTxRecipients>(conn)?;
+            Ok::<_, diesel::result::Error>((txs, senders, recipients))
+        })
+        .context("Failed reading transactions to aggregate address daily stats from PostgresDB")?;
+        let stats = aggregate_address_daily_stats(&txs, &senders, &recipients)?;
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                // Aggregates are added to, so a range must only be persisted once.
+                let last_processed_tx_seq = address_daily_stats::table
+                    .select(max(address_daily_stats::last_tx_sequence_number))
+                    .first::<Option<i64>>(conn)?;
+                if last_processed_tx_seq.is_some_and(|seq| seq >= start_tx_seq) {
+                    return Ok(());
+                }
+
+                for chunk in stats.chunks(ADDRESS_DAILY_STATS_CHUNK_SIZE) {
+                    diesel::insert_into(address_daily_stats::table)
+                        .values(chunk)
+                        .on_conflict((address_daily_stats::day, address_daily_stats::address))
+                        .do_update()
+                        .set((
+                            address_daily_stats::sent_tx_count
+                                .eq(address_daily_stats::sent_tx_count
+                                    + excluded(address_daily_stats::sent_tx_count)),
+                            address_daily_stats::received_tx_count
+                                .eq(address_daily_stats::received_tx_count
+                                    + excluded(address_daily_stats::received_tx_count)),
+                            address_daily_stats::sui_sent
+                                .eq(sql::<BigInt>(&saturating_add("sui_sent"))),
+                            address_daily_stats::sui_received
+                                .eq(sql::<BigInt>(&saturating_add("sui_received"))),
+                            address_daily_stats::last_tx_sequence_number
+                                .eq(excluded(address_daily_stats::last_tx_sequence_number)),
+                        ))
+                        .execute(conn)?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed persisting address daily stats to PostgresDB")?;
+        Ok(())
+    }
+
+    async fn prune_address_daily_stats(&self, before_day: i64) -> IndexerResult<()> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::delete(address_daily_stats::table)
+                    .filter(address_daily_stats::day.lt(before_day))
+                    .execute(conn)
+            },
+            Duration::from_secs(60)
+        )
+        .context("Failed pruning address daily stats from PostgresDB")?;
+        Ok(())
+    }
+
+    async fn persist_active_address_counts(&self, before_day: i64) -> IndexerResult<()> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                let last_counted_day = active_address_counts::table
+                    .select(max(active_address_counts::day))
+                    .first::<Option<i64>>(conn)?;
+                let first_day = match last_counted_day {
+                    Some(day) => day + 1,
+                    None => match address_daily_stats::table
+                        .select(min(address_daily_stats::day))
+                        .first::<Option<i64>>(conn)?
+                    {
+                        Some(day) => day,
+                        None => return Ok(()),
+                    },
+                };
+                for day in first_day..before_day {
+                    diesel::sql_query(construct_active_address_counts_query(day)).execute(conn)?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .context("Failed persisting active address counts to PostgresDB")?;
+        Ok(())
+    }
+
+    async fn get_balance_changes_last_processed_tx_seq(&self) -> IndexerResult<Option<TxSeq>> {
+        let last_processed_tx_seq = read_only_blocking!(&self.blocking_cp, |conn| {
+            balance_changes::table
+                .select(max(balance_changes::tx_sequence_number))
+                .first::<Option<i64>>(conn)
+        })
+        .context("Failed to read balance changes last processed tx sequence.")?;
+        Ok(last_processed_tx_seq.map(|seq| TxSeq { seq }))
+    }
+
+    fn persist_balance_changes_in_tx_range(
+        &self,
+        start_tx_seq: i64,
+        end_tx_seq: i64,
+    ) -> IndexerResult<()> {
+        let txs = read_only_blocking!(&self.blocking_cp, |conn| {
+            transactions::table
+                .filter(transactions::tx_sequence_number.ge(start_tx_seq))
+                .filter(transactions::tx_sequence_number.lt(end_tx_seq))
+                .select((
+                    transactions::tx_sequence_number,
+                    transactions::timestamp_ms,
+                    transactions::balance_changes,
+                ))
+                .load::<StoredTransactionBalanceChanges>(conn)
+        })
+        .context("Failed reading transactions to take balance changes from PostgresDB")?;
+        let changes = stored_balance_changes(&txs)?;
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                // Each row is a transaction's change, so ranges can be persisted more than once.
+                for chunk in changes.chunks(BALANCE_CHANGES_CHUNK_SIZE) {
+                    diesel::insert_into(balance_changes::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed persisting balance changes to PostgresDB")?;
+        Ok(())
+    }
+
+    async fn get_gas_price_stats_next_checkpoint(&self) -> IndexerResult<i64> {
+        let last_checkpoint = read_only_blocking!(&self.blocking_cp, |conn| {
+            gas_price_stats::table
+                .select(max(gas_price_stats::last_checkpoint))
+                .first::<Option<i64>>(conn)
+        })
+        .context("Failed to read gas price stats last processed checkpoint.")?;
+        Ok(last_checkpoint.map_or(0, |cp| cp + 1))
+    }
+
+    fn persist_gas_price_stats(
+        &self,
+        first_checkpoint: i64,
+        last_checkpoint: i64,
+    ) -> IndexerResult<()> {
+        let (txs, (epoch, timestamp_ms), reference_gas_price) =
+            read_only_blocking!(&self.blocking_cp, |conn| {
+                let txs = transactions::table
+                    .filter(transactions::checkpoint_sequence_number.ge(first_checkpoint))
+                    .filter(transactions::checkpoint_sequence_number.le(last_checkpoint))
+                    .filter(
+                        transactions::transaction_kind
+                            .eq(TransactionKind::ProgrammableTransaction as i16),
+                    )
+                    .select((
+                        transactions::tx_sequence_number,
+                        transactions::raw_transaction,
+                        transactions::compression,
+                    ))
+                    .load::<StoredRawTransaction>(conn)?;
+                let checkpoint = checkpoints::table
+                    .filter(checkpoints::sequence_number.eq(last_checkpoint))
+                    .select((checkpoints::epoch, checkpoints::timestamp_ms))
+                    .first::<(i64, i64)>(conn)?;
+                let reference_gas_price = epochs::table
+                    .filter(epochs::epoch.eq(checkpoint.0))
+                    .select(epochs::reference_gas_price)
+                    .first::<i64>(conn)?;
+                Ok::<_, diesel::result::Error>((txs, checkpoint, reference_gas_price))
+            })
+            .context("Failed reading transactions to compute gas price stats from PostgresDB")?;
+        let gas_prices = txs
+            .iter()
+            .map(StoredRawTransaction::gas_price)
+            .collect::<IndexerResult<Vec<_>>>()?;
+        let stats = StoredGasPriceStats::new(
+            first_checkpoint,
+            last_checkpoint,
+            epoch,
+            timestamp_ms,
+            reference_gas_price,
+            gas_prices,
+        );
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(gas_price_stats::table)
+                    .values(&stats)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed persisting gas price stats to PostgresDB")?;
+        Ok(())
+    }
+
+    async fn get_validator_apys_next_epoch(&self) -> IndexerResult<i64> {
+        let (last_epoch, first_indexed_epoch) = read_only_blocking!(&self.blocking_cp, |conn| {
+            let last_epoch = validator_apys::table
+                .select(max(validator_apys::epoch))
+                .first::<Option<i64>>(conn)?;
+            let first_indexed_epoch = epochs::table
+                .select(min(epochs::epoch))
+                .first::<Option<i64>>(conn)?;
+            Ok::<_, diesel::result::Error>((last_epoch, first_indexed_epoch))
+        })
+        .context("Failed to read validator apys last processed epoch.")?;
+        Ok(last_epoch.map_or(first_indexed_epoch.unwrap_or(0), |epoch| epoch + 1))
+    }
+
+    fn persist_validator_apys(&self, epoch: i64) -> IndexerResult<()> {
+        // Validators that left at the end of `epoch` have no exchange rate for the next epoch, so
+        // the validators with APYs in `epoch` are among those active in the next one.
+        let system_state = read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::table
+                .filter(epochs::epoch.eq(epoch + 1))
+                .select(epochs::system_state)
+                .first::<Vec<u8>>(conn)
+        })
+        .context("Failed reading epoch to compute validator apys from PostgresDB")?;
+        let system_state: SuiSystemStateSummary = bcs::from_bytes(&system_state).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to deserialize `system_state` for epoch {}: {e}",
+                epoch + 1
+            ))
+        })?;
+
+        // The exchange rate of a pool at the start of an epoch is the dynamic field of its
+        // exchange rates table keyed by the epoch.
+        let mut rate_ids = HashMap::new();
+        for validator in &system_state.active_validators {
+            for rate_epoch in [epoch as u64, epoch as u64 + 1] {
+                let id = derive_dynamic_field_id(
+                    validator.exchange_rates_id,
+                    &TypeTag::U64,
+                    &bcs::to_bytes(&rate_epoch)?,
+                )?;
+                rate_ids.insert(id, (validator.sui_address, rate_epoch));
+            }
+        }
+        let ids = rate_ids.keys().map(|id| id.to_vec()).collect::<Vec<_>>();
+        let rate_objects = read_only_blocking!(&self.blocking_cp, |conn| {
+            objects::table
+                .filter(objects::object_id.eq_any(ids))
+                .load::<StoredObject>(conn)
+        })
+        .context("Failed reading exchange rates to compute validator apys from PostgresDB")?;
+
+        let mut rates = HashMap::new();
+        for object in rate_objects {
+            let id = ObjectID::from_bytes(&object.object_id).map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Failed to parse object id {:?}: {e}",
+                    object.object_id
+                ))
+            })?;
+            let field = object
+                .to_dynamic_field::<EpochId, PoolTokenExchangeRate>()
+                .ok_or_else(|| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Malformed exchange rate {id}"
+                    ))
+                })?;
+            if let Some(key) = rate_ids.get(&id) {
+                rates.insert(*key, field.value);
+            }
+        }
+
+        // Validators without both rates, e.g. those that joined in the next epoch, are skipped.
+        let apys = system_state
+            .active_validators
+            .iter()
+            .filter_map(|validator| {
+                let address = validator.sui_address;
+                let rate_e = rates.remove(&(address, epoch as u64))?;
+                let rate_e_1 = rates.remove(&(address, epoch as u64 + 1))?;
+                StoredValidatorApy::new(epoch, address, (rate_e, rate_e_1))
+            })
+            .collect::<Vec<_>>();
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(validator_apys::table)
+                    .values(&apys)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed persisting validator apys to PostgresDB")?;
+        Ok(())
+    }
+
+    async fn get_validator_history_next_epoch(&self) -> IndexerResult<i64> {
+        let (last_epoch, first_indexed_epoch) = read_only_blocking!(&self.blocking_cp, |conn| {
+            let last_epoch = validator_history::table
+                .select(max(validator_history::epoch))
+                .first::<Option<i64>>(conn)?;
+            let first_indexed_epoch = epochs::table
+                .select(min(epochs::epoch))
+                .first::<Option<i64>>(conn)?;
+            Ok::<_, diesel::result::Error>((last_epoch, first_indexed_epoch))
+        })
+        .context("Failed to read validator history last processed epoch.")?;
+        // The first indexed epoch is skipped, as the system state at its start is not stored.
+        Ok(last_epoch
+            .or(first_indexed_epoch)
+            .map_or(1, |epoch| epoch + 1))
+    }
+
+    fn persist_validator_history(&self, epoch: i64) -> IndexerResult<()> {
+        // The system state stored for an epoch is the one at its end, i.e. at the start of the
+        // next epoch.
+        let rows = read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::table
+                .filter(epochs::epoch.between(epoch - 1, epoch))
+                .order_by(epochs::epoch)
+                .select((
+                    epochs::system_state,
+                    epochs::first_checkpoint_id,
+                    epochs::last_checkpoint_id,
+                ))
+                .load::<(Vec<u8>, i64, Option<i64>)>(conn)
+        })
+        .context("Failed reading epochs to compute validator history from PostgresDB")?;
+        let [(start, _, _), (end, first_checkpoint, Some(last_checkpoint))] = rows.as_slice()
+        else {
+            return Err(IndexerError::PostgresReadError(format!(
+                "Epochs {} and {epoch} must both have ended to compute validator history",
+                epoch - 1
+            )));
+        };
+        let system_state = |bytes: &[u8], state_epoch: i64| {
+            bcs::from_bytes::<SuiSystemStateSummary>(bytes).map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Failed to deserialize `system_state` for epoch {state_epoch}: {e}"
+                ))
+            })
+        };
+        let start = system_state(start, epoch - 1)?;
+        let end = system_state(end, epoch)?;
+
+        let (first_checkpoint, last_checkpoint) = (*first_checkpoint, *last_checkpoint);
+        let stake_events = read_only_blocking!(&self.blocking_cp, |conn| {
+            events::table
+                .filter(events::event_type.eq_any(stake_event_types()))
+                .filter(
+                    events::checkpoint_sequence_number.between(first_checkpoint, last_checkpoint),
+                )
+                .load::<StoredEvent>(conn)
+        })
+        .context("Failed reading staking events to compute validator history from PostgresDB")?;
+        let flows = stake_flows(&stake_events)?;
+
+        let histories = validator_histories(epoch, &start, &end, &flows);
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(validator_history::table)
+                    .values(&histories)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed persisting validator history to PostgresDB")?;
+        Ok(())
+    }
+}
+
+/// SQL adding the excluded value of `column` to its value in `address_daily_stats`, saturating
+/// rather than overflowing.
+fn saturating_add(column: &str) -> String {
+    format!(
+        "LEAST(address_daily_stats.{column}::NUMERIC + excluded.{column}, {})::BIGINT",
+        i64::MAX
+    )
+}
+
+/// Query counting the addresses that sent transactions on `day`, and in the week ending with it,
+/// from `address_daily_stats`.
+fn construct_active_address_counts_query(day: i64) -> String {
+    format!(
+        "INSERT INTO active_address_counts (day, daily_active_addresses, weekly_active_addresses)
+        SELECT {day},
+            COUNT(DISTINCT address) FILTER (WHERE day = {day}),
+            COUNT(DISTINCT address)
+        FROM address_daily_stats
+        WHERE day > {day} - 7 AND day <= {day} AND sent_tx_count > 0
+        ON CONFLICT (day) DO NOTHING"
+    )
 }
 
 fn construct_checkpoint_tx_count_query(start_checkpoint: i64, end_checkpoint: i64) -> String {
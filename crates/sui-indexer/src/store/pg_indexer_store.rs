@@ -3,50 +3,64 @@
 
 use core::result::Result::Ok;
 use itertools::Itertools;
-use std::any::Any;
 use std::collections::hash_map::Entry;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 use tap::Tap;
 
 use async_trait::async_trait;
-use diesel::dsl::max;
+use diesel::dsl::{max, sql};
+use diesel::sql_types::BigInt;
 use diesel::upsert::excluded;
 use diesel::ExpressionMethods;
 use diesel::OptionalExtension;
+use diesel::PgConnection;
 use diesel::{QueryDsl, RunQueryDsl};
 use move_bytecode_utils::module_cache::SyncModuleCache;
-use tracing::info;
+use tracing::{info, warn};
 
 use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::CheckpointDigest;
 use sui_types::object::ObjectRead;
 
+use crate::compression::Compression;
+use crate::epoch_pools::EpochPoolController;
 use crate::errors::{Context, IndexerError};
 use crate::handlers::EpochToCommit;
 use crate::handlers::TransactionObjectChangesToCommit;
+use crate::leader::check_writer_lease;
 use crate::metrics::IndexerMetrics;
 
 use crate::db::PgConnectionPool;
 use crate::models::checkpoints::StoredCheckpoint;
 use crate::models::display::StoredDisplay;
 use crate::models::epoch::StoredEpochInfo;
+use crate::models::event_addresses::StoredEventAddress;
+use crate::models::event_schemas::StoredEventSchema;
 use crate::models::events::StoredEvent;
+use crate::models::nft_transfers::StoredNftTransfer;
+use crate::models::object_transitions::StoredObjectTransition;
 use crate::models::objects::{
     StoredDeletedHistoryObject, StoredDeletedObject, StoredHistoryObject, StoredObject,
 };
+use crate::models::package_call_metrics::StoredPackageCallMetrics;
 use crate::models::packages::StoredPackage;
 use crate::models::transactions::StoredTransaction;
 use crate::schema::{
-    checkpoints, display, epochs, events, objects, objects_history, objects_snapshot, packages,
-    transactions, tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
+    checkpoints, display, epochs, event_addresses, event_schemas, events, nft_transfers,
+    object_transitions, objects, objects_history, objects_snapshot, package_call_metrics, packages,
+    transactions, tx_calls, tx_changed_objects, tx_classes, tx_input_objects, tx_recipients,
+    tx_senders,
 };
 use crate::store::diesel_macro::{read_only_blocking, transactional_blocking_with_retry};
 use crate::store::module_resolver::IndexerStorePackageModuleResolver;
 use crate::types::{IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex};
 
+use super::pg_copy::{CopyConnectionPool, CopyRow};
 use super::pg_partition_manager::{EpochPartitionData, PgPartitionManager};
 use super::IndexerStore;
 use super::ObjectChangeToCommit;
@@ -112,6 +126,11 @@ pub struct PgIndexerStore {
     parallel_chunk_size: usize,
     parallel_objects_chunk_size: usize,
     partition_manager: PgPartitionManager,
+    copy_pool: Option<CopyConnectionPool>,
+    payload_compression: Option<Compression>,
+    skip_parsed_json: bool,
+    epoch_pools: Option<EpochPoolController>,
+    writer_lease: Option<i64>,
 }
 
 impl PgIndexerStore {
@@ -137,9 +156,72 @@ impl PgIndexerStore {
             parallel_chunk_size,
             parallel_objects_chunk_size,
             partition_manager,
+            copy_pool: None,
+            payload_compression: None,
+            skip_parsed_json: false,
+            epoch_pools: None,
+            writer_lease: None,
         }
     }
 
+    /// Insert transactions, events and objects history, and upsert objects, with binary `COPY`s
+    /// over connections to `database_url`, falling back to `INSERT`s for the chunks that can't be
+    /// copied.
+    pub fn with_copy_inserts(mut self, database_url: &str) -> Result<Self, IndexerError> {
+        self.copy_pool = Some(CopyConnectionPool::new(database_url)?);
+        Ok(self)
+    }
+
+    /// Compress the payloads of transactions and events with `compression` before storing them.
+    pub fn with_payload_compression(mut self, compression: Compression) -> Self {
+        self.payload_compression = Some(compression);
+        self
+    }
+
+    /// Store objects without their parsed JSON, leaving the reader to render it when it is read.
+    pub fn without_parsed_json(mut self) -> Self {
+        self.skip_parsed_json = true;
+        self
+    }
+
+    /// Resize connection pools with `controller` while committing the end of each epoch.
+    pub fn with_epoch_pools(mut self, controller: EpochPoolController) -> Self {
+        self.epoch_pools = Some(controller);
+        self
+    }
+
+    /// Only commit while the writer lease is still at `epoch`, the epoch this store's writer
+    /// took it at (see [`crate::leader`]).
+    pub fn with_writer_lease(mut self, epoch: i64) -> Self {
+        self.writer_lease = Some(epoch);
+        self
+    }
+
+    /// Check, in the transaction on `conn`, that the writer lease is still at the epoch this store
+    /// was given, if any.
+    fn check_writer_lease(&self, conn: &mut PgConnection) -> Result<(), IndexerError> {
+        match self.writer_lease {
+            Some(epoch) => check_writer_lease(conn, epoch),
+            None => Ok(()),
+        }
+    }
+
+    /// Drop the parsed JSON of the mutated objects in `objects`, if the store is set to skip it.
+    fn strip_parsed_json(&self, objects: Vec<ObjectChangeToCommit>) -> Vec<ObjectChangeToCommit> {
+        if !self.skip_parsed_json {
+            return objects;
+        }
+        objects
+            .into_iter()
+            .map(|object| match object {
+                ObjectChangeToCommit::MutatedObject(o) => {
+                    ObjectChangeToCommit::MutatedObject(o.without_parsed_json())
+                }
+                deleted => deleted,
+            })
+            .collect()
+    }
+
     pub fn blocking_cp(&self) -> PgConnectionPool {
         self.blocking_cp.clone()
     }
@@ -154,6 +236,40 @@ impl PgIndexerStore {
         .context("Failed reading latest checkpoint sequence number from PostgresDB")
     }
 
+    fn get_latest_checkpoint_digest(
+        &self,
+    ) -> Result<Option<(u64, CheckpointDigest)>, IndexerError> {
+        let latest = read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::dsl::checkpoints
+                .select((checkpoints::sequence_number, checkpoints::checkpoint_digest))
+                .order(checkpoints::sequence_number.desc())
+                .first::<(i64, Vec<u8>)>(conn)
+                .optional()
+        })
+        .context("Failed reading latest checkpoint digest from PostgresDB")?;
+
+        latest
+            .map(|(seq, digest)| {
+                let digest = CheckpointDigest::try_from(digest).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Failed to decode digest of checkpoint {seq}: {e}"
+                    ))
+                })?;
+                Ok((seq as u64, digest))
+            })
+            .transpose()
+    }
+
+    fn get_display_object_types(&self) -> Result<HashSet<String>, IndexerError> {
+        let types = read_only_blocking!(&self.blocking_cp, |conn| {
+            display::dsl::display
+                .select(display::object_type)
+                .load::<String>(conn)
+        })
+        .context("Failed reading display object types from PostgresDB")?;
+        Ok(types.into_iter().collect())
+    }
+
     fn get_latest_object_snapshot_checkpoint_sequence_number(
         &self,
     ) -> Result<Option<u64>, IndexerError> {
@@ -198,6 +314,7 @@ impl PgIndexerStore {
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
+                self.check_writer_lease(conn)?;
                 diesel::insert_into(display::table)
                     .values(display_updates.values().collect::<Vec<_>>())
                     .on_conflict(display::object_type)
@@ -218,6 +335,132 @@ impl PgIndexerStore {
         Ok(())
     }
 
+    fn persist_event_schemas(
+        &self,
+        event_schemas: Vec<StoredEventSchema>,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                self.check_writer_lease(conn)?;
+                diesel::insert_into(event_schemas::table)
+                    .values(&event_schemas)
+                    .on_conflict((event_schemas::event_type, event_schemas::package_id))
+                    .do_update()
+                    .set((
+                        event_schemas::first_seen_checkpoint.eq(sql::<BigInt>(
+                            "LEAST(event_schemas.first_seen_checkpoint, \
+                             excluded.first_seen_checkpoint)",
+                        )),
+                        event_schemas::last_seen_checkpoint.eq(sql::<BigInt>(
+                            "GREATEST(event_schemas.last_seen_checkpoint, \
+                             excluded.last_seen_checkpoint)",
+                        )),
+                    ))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write event schemas to PostgresDB")?;
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )?;
+
+        Ok(())
+    }
+
+    fn persist_event_addresses(
+        &self,
+        addresses: Vec<StoredEventAddress>,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                self.check_writer_lease(conn)?;
+                for chunk in addresses.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(event_addresses::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write event addresses to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )?;
+
+        Ok(())
+    }
+
+    fn persist_object_transitions(
+        &self,
+        transitions: Vec<StoredObjectTransition>,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                self.check_writer_lease(conn)?;
+                for chunk in transitions.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(object_transitions::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write object transitions to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )?;
+
+        Ok(())
+    }
+
+    fn persist_nft_transfers(&self, transfers: Vec<StoredNftTransfer>) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                self.check_writer_lease(conn)?;
+                for chunk in transfers.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(nft_transfers::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write NFT transfers to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )?;
+
+        Ok(())
+    }
+
+    fn persist_package_call_metrics(
+        &self,
+        metrics: Vec<StoredPackageCallMetrics>,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                self.check_writer_lease(conn)?;
+                for chunk in metrics.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(package_call_metrics::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write package call metrics to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )?;
+
+        Ok(())
+    }
+
     fn persist_objects_chunk(
         &self,
         objects: Vec<ObjectChangeToCommit>,
@@ -240,37 +483,41 @@ impl PgIndexerStore {
             }
         }
 
+        let copied = self.copy_upsert(&mutated_objects, &["object_id"]);
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
-                // Persist mutated objects
-                for mutated_object_change_chunk in
-                    mutated_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
-                {
-                    diesel::insert_into(objects::table)
-                        .values(mutated_object_change_chunk)
-                        .on_conflict(objects::object_id)
-                        .do_update()
-                        .set((
-                            objects::object_id.eq(excluded(objects::object_id)),
-                            objects::object_version.eq(excluded(objects::object_version)),
-                            objects::object_digest.eq(excluded(objects::object_digest)),
-                            objects::checkpoint_sequence_number
-                                .eq(excluded(objects::checkpoint_sequence_number)),
-                            objects::owner_type.eq(excluded(objects::owner_type)),
-                            objects::owner_id.eq(excluded(objects::owner_id)),
-                            objects::object_type.eq(excluded(objects::object_type)),
-                            objects::serialized_object.eq(excluded(objects::serialized_object)),
-                            objects::coin_type.eq(excluded(objects::coin_type)),
-                            objects::coin_balance.eq(excluded(objects::coin_balance)),
-                            objects::df_kind.eq(excluded(objects::df_kind)),
-                            objects::df_name.eq(excluded(objects::df_name)),
-                            objects::df_object_type.eq(excluded(objects::df_object_type)),
-                            objects::df_object_id.eq(excluded(objects::df_object_id)),
-                        ))
-                        .execute(conn)
-                        .map_err(IndexerError::from)
-                        .context("Failed to write object mutation to PostgresDB")?;
+                self.check_writer_lease(conn)?;
+                // Persist mutated objects, unless they have been copied
+                if !copied {
+                    for mutated_object_change_chunk in
+                        mutated_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
+                    {
+                        diesel::insert_into(objects::table)
+                            .values(mutated_object_change_chunk)
+                            .on_conflict(objects::object_id)
+                            .do_update()
+                            .set((
+                                objects::object_id.eq(excluded(objects::object_id)),
+                                objects::object_version.eq(excluded(objects::object_version)),
+                                objects::object_digest.eq(excluded(objects::object_digest)),
+                                objects::checkpoint_sequence_number
+                                    .eq(excluded(objects::checkpoint_sequence_number)),
+                                objects::owner_type.eq(excluded(objects::owner_type)),
+                                objects::owner_id.eq(excluded(objects::owner_id)),
+                                objects::object_type.eq(excluded(objects::object_type)),
+                                objects::serialized_object.eq(excluded(objects::serialized_object)),
+                                objects::coin_type.eq(excluded(objects::coin_type)),
+                                objects::coin_balance.eq(excluded(objects::coin_balance)),
+                                objects::df_kind.eq(excluded(objects::df_kind)),
+                                objects::df_name.eq(excluded(objects::df_name)),
+                                objects::df_object_type.eq(excluded(objects::df_object_type)),
+                                objects::df_object_id.eq(excluded(objects::df_object_id)),
+                            ))
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write object mutation to PostgresDB")?;
+                    }
                 }
 
                 // Persist deleted objects
@@ -306,6 +553,40 @@ impl PgIndexerStore {
         })
     }
 
+    /// Copy `rows` into their table if `COPY` inserts are enabled, returning whether they were
+    /// copied. A copy inserts all of the rows or none of them, so rows that weren't copied can be
+    /// inserted as usual.
+    fn copy_in<R: CopyRow>(&self, rows: &[R]) -> bool {
+        let Some(copy_pool) = &self.copy_pool else {
+            return false;
+        };
+        match copy_pool.copy_in(rows, self.writer_lease) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("{e}, falling back to INSERT");
+                self.metrics.copy_insert_fallbacks.inc();
+                false
+            }
+        }
+    }
+
+    /// Upsert `rows` into their table, by their `key` columns, with a `COPY` through a staging
+    /// table if `COPY` inserts are enabled, returning whether they were upserted. Like a copy,
+    /// this upserts all of the rows or none of them.
+    fn copy_upsert<R: CopyRow>(&self, rows: &[R], key: &[&str]) -> bool {
+        let Some(copy_pool) = &self.copy_pool else {
+            return false;
+        };
+        match copy_pool.copy_upsert(rows, key, self.writer_lease) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("{e}, falling back to INSERT");
+                self.metrics.copy_insert_fallbacks.inc();
+                false
+            }
+        }
+    }
+
     fn persist_objects_history_chunk(
         &self,
         objects: Vec<ObjectChangeToCommit>,
@@ -327,36 +608,45 @@ impl PgIndexerStore {
             }
         }
 
-        transactional_blocking_with_retry!(
-            &self.blocking_cp,
-            |conn| {
-                for mutated_object_change_chunk in
-                    mutated_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
-                {
-                    diesel::insert_into(objects_history::table)
-                        .values(mutated_object_change_chunk)
-                        .on_conflict_do_nothing()
-                        .execute(conn)
-                        .map_err(IndexerError::from)
-                        .context("Failed to write object mutations to objects_history in DB.")?;
-                }
+        let result = if self.copy_in(&mutated_objects) && self.copy_in(&deleted_object_ids) {
+            Ok(())
+        } else {
+            transactional_blocking_with_retry!(
+                &self.blocking_cp,
+                |conn| {
+                    self.check_writer_lease(conn)?;
+                    for mutated_object_change_chunk in
+                        mutated_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
+                    {
+                        diesel::insert_into(objects_history::table)
+                            .values(mutated_object_change_chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context(
+                                "Failed to write object mutations to objects_history in DB.",
+                            )?;
+                    }
 
-                for deleted_objects_chunk in
-                    deleted_object_ids.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
-                {
-                    diesel::insert_into(objects_history::table)
-                        .values(deleted_objects_chunk)
-                        .on_conflict_do_nothing()
-                        .execute(conn)
-                        .map_err(IndexerError::from)
-                        .context("Failed to write object deletions to objects_history in DB.")?;
-                }
+                    for deleted_objects_chunk in
+                        deleted_object_ids.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
+                    {
+                        diesel::insert_into(objects_history::table)
+                            .values(deleted_objects_chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context(
+                                "Failed to write object deletions to objects_history in DB.",
+                            )?;
+                    }
 
-                Ok::<(), IndexerError>(())
-            },
-            Duration::from_secs(60)
-        )
-        .tap(|_| {
+                    Ok::<(), IndexerError>(())
+                },
+                Duration::from_secs(60)
+            )
+        };
+        result.tap(|_| {
             let elapsed = guard.stop_and_record();
             info!(
                 elapsed,
@@ -370,6 +660,7 @@ impl PgIndexerStore {
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
+                self.check_writer_lease(conn)?;
                 RunQueryDsl::execute(
                     diesel::sql_query(UPDATE_OBJECTS_SNAPSHOT_QUERY)
                         .bind::<diesel::sql_types::BigInt, _>(start_cp as i64)
@@ -398,6 +689,7 @@ impl PgIndexerStore {
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
+                self.check_writer_lease(conn)?;
                 for checkpoint_chunk in checkpoints.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
                     diesel::insert_into(checkpoints::table)
                         .values(checkpoint_chunk)
@@ -430,26 +722,37 @@ impl PgIndexerStore {
             .start_timer();
         let transactions = transactions
             .iter()
-            .map(StoredTransaction::from)
+            .map(|tx| {
+                let tx = StoredTransaction::from(tx);
+                match self.payload_compression {
+                    Some(compression) => tx.compressed(compression),
+                    None => tx,
+                }
+            })
             .collect::<Vec<_>>();
         drop(transformation_guard);
 
-        transactional_blocking_with_retry!(
-            &self.blocking_cp,
-            |conn| {
-                for transaction_chunk in transactions.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
-                    diesel::insert_into(transactions::table)
-                        .values(transaction_chunk)
-                        .on_conflict_do_nothing()
-                        .execute(conn)
-                        .map_err(IndexerError::from)
-                        .context("Failed to write transactions to PostgresDB")?;
-                }
-                Ok::<(), IndexerError>(())
-            },
-            Duration::from_secs(60)
-        )
-        .tap(|_| {
+        let result = if self.copy_in(&transactions) {
+            Ok(())
+        } else {
+            transactional_blocking_with_retry!(
+                &self.blocking_cp,
+                |conn| {
+                    self.check_writer_lease(conn)?;
+                    for transaction_chunk in transactions.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        diesel::insert_into(transactions::table)
+                            .values(transaction_chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write transactions to PostgresDB")?;
+                    }
+                    Ok::<(), IndexerError>(())
+                },
+                Duration::from_secs(60)
+            )
+        };
+        result.tap(|_| {
             let elapsed = guard.stop_and_record();
             info!(
                 elapsed,
@@ -467,25 +770,36 @@ impl PgIndexerStore {
         let len = events.len();
         let events = events
             .into_iter()
-            .map(StoredEvent::from)
+            .map(|event| {
+                let event = StoredEvent::from(event);
+                match self.payload_compression {
+                    Some(compression) => event.compressed(compression),
+                    None => event,
+                }
+            })
             .collect::<Vec<_>>();
 
-        transactional_blocking_with_retry!(
-            &self.blocking_cp,
-            |conn| {
-                for event_chunk in events.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
-                    diesel::insert_into(events::table)
-                        .values(event_chunk)
-                        .on_conflict_do_nothing()
-                        .execute(conn)
-                        .map_err(IndexerError::from)
-                        .context("Failed to write events to PostgresDB")?;
-                }
-                Ok::<(), IndexerError>(())
-            },
-            Duration::from_secs(60)
-        )
-        .tap(|_| {
+        let result = if self.copy_in(&events) {
+            Ok(())
+        } else {
+            transactional_blocking_with_retry!(
+                &self.blocking_cp,
+                |conn| {
+                    self.check_writer_lease(conn)?;
+                    for event_chunk in events.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        diesel::insert_into(events::table)
+                            .values(event_chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write events to PostgresDB")?;
+                    }
+                    Ok::<(), IndexerError>(())
+                },
+                Duration::from_secs(60)
+            )
+        };
+        result.tap(|_| {
             let elapsed = guard.stop_and_record();
             info!(elapsed, "Persisted {} chunked events", len)
         })
@@ -506,6 +820,7 @@ impl PgIndexerStore {
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
+                self.check_writer_lease(conn)?;
                 for packages_chunk in packages.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
                     diesel::insert_into(packages::table)
                         .values(packages_chunk)
@@ -535,15 +850,23 @@ impl PgIndexerStore {
             .checkpoint_db_commit_latency_tx_indices_chunks
             .start_timer();
         let len = indices.len();
-        let (senders, recipients, input_objects, changed_objects, calls) =
+        let (senders, recipients, input_objects, changed_objects, calls, classes) =
             indices.into_iter().map(|i| i.split()).fold(
-                (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new()),
+                (
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                ),
                 |(
                     mut tx_senders,
                     mut tx_recipients,
                     mut tx_input_objects,
                     mut tx_changed_objects,
                     mut tx_calls,
+                    mut tx_classes,
                 ),
                  index| {
                     tx_senders.extend(index.0);
@@ -551,6 +874,7 @@ impl PgIndexerStore {
                     tx_input_objects.extend(index.2);
                     tx_changed_objects.extend(index.3);
                     tx_calls.extend(index.4);
+                    tx_classes.extend(index.5);
 
                     (
                         tx_senders,
@@ -558,6 +882,7 @@ impl PgIndexerStore {
                         tx_input_objects,
                         tx_changed_objects,
                         tx_calls,
+                        tx_classes,
                     )
                 },
             );
@@ -570,6 +895,7 @@ impl PgIndexerStore {
             transactional_blocking_with_retry!(
                 &this.blocking_cp,
                 |conn| {
+                    this.check_writer_lease(conn)?;
                     for chunk in senders.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
                         diesel::insert_into(tx_senders::table)
                             .values(chunk)
@@ -606,6 +932,7 @@ impl PgIndexerStore {
             transactional_blocking_with_retry!(
                 &this.blocking_cp,
                 |conn| {
+                    this.check_writer_lease(conn)?;
                     for chunk in input_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
                         diesel::insert_into(tx_input_objects::table)
                             .values(chunk)
@@ -633,6 +960,7 @@ impl PgIndexerStore {
             transactional_blocking_with_retry!(
                 &this.blocking_cp,
                 |conn| {
+                    this.check_writer_lease(conn)?;
                     for chunk in changed_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
                         diesel::insert_into(tx_changed_objects::table)
                             .values(chunk)
@@ -659,6 +987,7 @@ impl PgIndexerStore {
             transactional_blocking_with_retry!(
                 &this.blocking_cp,
                 |conn| {
+                    this.check_writer_lease(conn)?;
                     for chunk in calls.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
                         diesel::insert_into(tx_calls::table)
                             .values(chunk)
@@ -676,6 +1005,33 @@ impl PgIndexerStore {
                 info!(elapsed, "Persisted {} rows to tx_calls tables", calls_len);
             })
         }));
+        futures.push(self.spawn_blocking_task(move |this| {
+            let now = Instant::now();
+            let classes_len = classes.len();
+            transactional_blocking_with_retry!(
+                &this.blocking_cp,
+                |conn| {
+                    this.check_writer_lease(conn)?;
+                    for chunk in classes.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        diesel::insert_into(tx_classes::table)
+                            .values(chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write tx_classes chunk to PostgresDB")?;
+                    }
+                    Ok::<(), IndexerError>(())
+                },
+                Duration::from_secs(60)
+            )
+            .tap(|_| {
+                let elapsed = now.elapsed().as_secs_f64();
+                info!(
+                    elapsed,
+                    "Persisted {} rows to tx_classes table", classes_len
+                );
+            })
+        }));
         futures::future::join_all(futures)
             .await
             .into_iter()
@@ -700,6 +1056,7 @@ impl PgIndexerStore {
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
+                self.check_writer_lease(conn)?;
                 if let Some(last_epoch) = &epoch.last_epoch {
                     let last_epoch_id = last_epoch.epoch;
                     let last_epoch = StoredEpochInfo::from_epoch_end_info(last_epoch);
@@ -850,12 +1207,20 @@ impl PgIndexerStore {
 #[async_trait]
 impl IndexerStore for PgIndexerStore {
     type ModuleCache = SyncModuleCache<IndexerStorePackageModuleResolver>;
+    type PackageStore = IndexerStorePackageModuleResolver;
 
     async fn get_latest_tx_checkpoint_sequence_number(&self) -> Result<Option<u64>, IndexerError> {
         self.execute_in_blocking_worker(|this| this.get_latest_tx_checkpoint_sequence_number())
             .await
     }
 
+    async fn get_latest_checkpoint_digest(
+        &self,
+    ) -> Result<Option<(u64, CheckpointDigest)>, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_latest_checkpoint_digest())
+            .await
+    }
+
     async fn get_latest_object_snapshot_checkpoint_sequence_number(
         &self,
     ) -> Result<Option<u64>, IndexerError> {
@@ -865,6 +1230,11 @@ impl IndexerStore for PgIndexerStore {
         .await
     }
 
+    async fn get_display_object_types(&self) -> Result<HashSet<String>, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_display_object_types())
+            .await
+    }
+
     async fn get_object_read(
         &self,
         object_id: ObjectID,
@@ -885,7 +1255,7 @@ impl IndexerStore for PgIndexerStore {
             .metrics
             .checkpoint_db_commit_latency_objects
             .start_timer();
-        let objects = make_final_list_of_objects_to_commit(object_changes);
+        let objects = self.strip_parsed_json(make_final_list_of_objects_to_commit(object_changes));
         let len = objects.len();
         let chunks = chunk!(objects, self.parallel_objects_chunk_size);
         let futures = chunks
@@ -923,7 +1293,7 @@ impl IndexerStore for PgIndexerStore {
         if object_changes.is_empty() {
             return Ok(());
         }
-        let objects = make_objects_history_to_commit(object_changes);
+        let objects = self.strip_parsed_json(make_objects_history_to_commit(object_changes));
         let guard = self
             .metrics
             .checkpoint_db_commit_latency_objects_history
@@ -1063,6 +1433,66 @@ impl IndexerStore for PgIndexerStore {
             .await?
     }
 
+    async fn persist_event_schemas(
+        &self,
+        event_schemas: Vec<StoredEventSchema>,
+    ) -> Result<(), IndexerError> {
+        if event_schemas.is_empty() {
+            return Ok(());
+        }
+
+        self.spawn_blocking_task(move |this| this.persist_event_schemas(event_schemas))
+            .await?
+    }
+
+    async fn persist_event_addresses(
+        &self,
+        addresses: Vec<StoredEventAddress>,
+    ) -> Result<(), IndexerError> {
+        if addresses.is_empty() {
+            return Ok(());
+        }
+
+        self.spawn_blocking_task(move |this| this.persist_event_addresses(addresses))
+            .await?
+    }
+
+    async fn persist_object_transitions(
+        &self,
+        transitions: Vec<StoredObjectTransition>,
+    ) -> Result<(), IndexerError> {
+        if transitions.is_empty() {
+            return Ok(());
+        }
+
+        self.spawn_blocking_task(move |this| this.persist_object_transitions(transitions))
+            .await?
+    }
+
+    async fn persist_nft_transfers(
+        &self,
+        transfers: Vec<StoredNftTransfer>,
+    ) -> Result<(), IndexerError> {
+        if transfers.is_empty() {
+            return Ok(());
+        }
+
+        self.spawn_blocking_task(move |this| this.persist_nft_transfers(transfers))
+            .await?
+    }
+
+    async fn persist_package_call_metrics(
+        &self,
+        metrics: Vec<StoredPackageCallMetrics>,
+    ) -> Result<(), IndexerError> {
+        if metrics.is_empty() {
+            return Ok(());
+        }
+
+        self.spawn_blocking_task(move |this| this.persist_package_call_metrics(metrics))
+            .await?
+    }
+
     async fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError> {
         if packages.is_empty() {
             return Ok(());
@@ -1106,13 +1536,23 @@ impl IndexerStore for PgIndexerStore {
     }
 
     async fn persist_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError> {
+        if let Some(epoch_pools) = &self.epoch_pools {
+            epoch_pools.epoch_started();
+        }
         self.execute_in_blocking_worker(move |this| this.persist_epoch(epoch))
             .await
     }
 
     async fn advance_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError> {
-        self.execute_in_blocking_worker(move |this| this.advance_epoch(epoch))
-            .await
+        let result = self
+            .execute_in_blocking_worker(move |this| this.advance_epoch(epoch))
+            .await;
+        // Pools are restored after the hold even if advancing failed, as the epoch will start
+        // being processed again when it is retried.
+        if let Some(epoch_pools) = &self.epoch_pools {
+            epoch_pools.epoch_committed();
+        }
+        result
     }
 
     async fn get_network_total_transactions_by_end_of_epoch(
@@ -1129,8 +1569,8 @@ impl IndexerStore for PgIndexerStore {
         self.module_cache.clone()
     }
 
-    fn as_any(&self) -> &dyn Any {
-        self
+    fn package_store(&self) -> Self::PackageStore {
+        IndexerStorePackageModuleResolver::new(self.blocking_cp.clone())
     }
 }
 
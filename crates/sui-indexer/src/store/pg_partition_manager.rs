@@ -39,6 +39,20 @@ pub struct EpochPartitionData {
 }
 
 impl EpochPartitionData {
+    pub fn new(
+        last_epoch: u64,
+        last_epoch_start_cp: u64,
+        next_epoch: u64,
+        next_epoch_start_cp: u64,
+    ) -> Self {
+        Self {
+            last_epoch,
+            next_epoch,
+            last_epoch_start_cp,
+            next_epoch_start_cp,
+        }
+    }
+
     pub fn compose_data(epoch: EpochToCommit, last_db_epoch: StoredEpochInfo) -> Self {
         let last_epoch = last_db_epoch.epoch as u64;
         let last_epoch_start_cp = last_db_epoch.first_checkpoint_id as u64;
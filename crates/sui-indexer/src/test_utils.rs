@@ -4,13 +4,16 @@
 use diesel::connection::SimpleConnection;
 use mysten_metrics::init_metrics;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 use std::env;
 use std::net::SocketAddr;
+use std::time::Duration;
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 use tracing::info;
 
 use crate::db::{new_pg_connection_pool, reset_database};
+use crate::epoch_pools::EpochPoolController;
 use crate::errors::IndexerError;
 use crate::indexer::Indexer;
 use crate::processors::objects_snapshot_processor::SnapshotLagConfig;
@@ -114,7 +117,22 @@ pub async fn start_test_indexer_impl(
             config.rpc_server_worker = true;
             config.rpc_server_url = reader_mode_rpc_url.ip().to_string();
             config.rpc_server_port = reader_mode_rpc_url.port();
-            tokio::spawn(async move { Indexer::start_reader(&config, &registry, db_url).await })
+            let epoch_pools = EpochPoolController::new(
+                Duration::from_secs(config.epoch_pool_hold_secs),
+                indexer_metrics.clone(),
+            );
+            tokio::spawn(async move {
+                Indexer::start_reader(
+                    &config,
+                    &registry,
+                    indexer_metrics,
+                    db_url,
+                    None,
+                    epoch_pools,
+                    CancellationToken::new(),
+                )
+                .await
+            })
         }
         ReaderWriterConfig::Writer { snapshot_config } => {
             if config.reset_db {
@@ -128,6 +146,7 @@ pub async fn start_test_indexer_impl(
                     store_clone,
                     indexer_metrics,
                     snapshot_config,
+                    CancellationToken::new(),
                 )
                 .await
             })
@@ -6,7 +6,7 @@ use move_core_types::language_storage::StructTag;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sui_json_rpc_types::{
-    ObjectChange, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    ObjectChange, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions, TransactionClass,
 };
 use sui_types::base_types::{ObjectDigest, SequenceNumber};
 use sui_types::base_types::{ObjectID, SuiAddress};
@@ -357,6 +357,7 @@ pub struct TxIndex {
     pub senders: Vec<SuiAddress>,
     pub recipients: Vec<SuiAddress>,
     pub move_calls: Vec<(ObjectID, String, String)>,
+    pub classes: Vec<TransactionClass>,
 }
 
 // ObjectChange is not bcs deserializable, IndexedObjectChange is.
@@ -626,6 +627,7 @@ impl From<SuiTransactionBlockResponseWithOptions> for SuiTransactionBlockRespons
                 .show_raw_effects
                 .then_some(response.raw_effects)
                 .unwrap_or_default(),
+            labels: response.labels,
         }
     }
 }
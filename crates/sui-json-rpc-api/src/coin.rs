@@ -63,6 +63,15 @@ pub trait CoinReadApi {
         coin_type: String,
     ) -> RpcResult<Option<SuiCoinMetadata>>;
 
+    /// Return metadata(e.g., symbol, decimals) for each of a list of coins, in the order of the
+    /// list, with `null` for coins without metadata
+    #[method(name = "multiGetCoinMetadata")]
+    async fn multi_get_coin_metadata(
+        &self,
+        /// type names for the coins (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC)
+        coin_types: Vec<String>,
+    ) -> RpcResult<Vec<Option<SuiCoinMetadata>>>;
+
     /// Return total supply for a coin
     #[method(name = "getTotalSupply")]
     async fn get_total_supply(
@@ -5,10 +5,18 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
 use sui_json_rpc_types::{
-    AddressMetrics, CheckpointedObjectID, EpochInfo, EpochMetricsPage, EpochPage, MoveCallMetrics,
-    NetworkMetrics, QueryObjectsPage, SuiObjectResponseQuery,
+    AccountActivity, AccountRanking, AccountStatement, AccountStatementCursor, ActiveAddressCounts,
+    AddressMetrics, BalanceHistoryInterval, BalanceSnapshot, Checkpoint, CheckpointedObjectID,
+    EpochInfo, EpochMetricsPage, EpochPage, EquivocationReport, EventPage, EventSchemaCursor,
+    EventSchemaPage, GasPriceStatsPage, MoveCallMetrics, NetworkMetrics, NftPage,
+    NftTransferHistory, ObjectLifecycle, ObjectTransactionPage, PackageMetrics, QueryObjectsPage,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, TimestampDirection,
+    TransactionInputObject, TransactionObject, ValidatorApyHistory, ValidatorEpochHistory,
 };
 use sui_open_rpc_macros::open_rpc;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::digests::TransactionDigest;
+use sui_types::event::EventID;
 use sui_types::sui_serde::BigInt;
 
 #[open_rpc(namespace = "suix", tag = "Extended API")]
@@ -54,14 +62,74 @@ pub trait ExtendedApi {
         limit: Option<usize>,
     ) -> RpcResult<QueryObjectsPage>;
 
-    /// Return Network metrics
+    /// Return Network metrics, including rolling rates over the most recently indexed
+    /// checkpoints and the progress of the current epoch
     #[method(name = "getNetworkMetrics")]
     async fn get_network_metrics(&self) -> RpcResult<NetworkMetrics>;
 
-    /// Return Network metrics
+    /// Return Network metrics, including rolling rates over the most recently indexed
+    /// checkpoints and the progress of the current epoch
     #[method(name = "getMoveCallMetrics")]
     async fn get_move_call_metrics(&self) -> RpcResult<MoveCallMetrics>;
 
+    /// Return the calls made to each of a package's functions from `start_epoch` to `end_epoch`,
+    /// and the gas paid by the transactions making them, e.g. for measuring a package's adoption.
+    /// Calls are counted from every transaction, failed ones included. Calls to other versions of
+    /// the package are not included. Ranges span at most 100 epochs.
+    #[method(name = "getPackageMetrics")]
+    async fn get_package_metrics(
+        &self,
+        /// the ID of the package
+        package_id: ObjectID,
+        /// the first epoch to count calls in
+        start_epoch: BigInt<u64>,
+        /// the last epoch to count calls in (inclusive), defaults to 99 epochs after
+        /// `start_epoch`
+        end_epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<PackageMetrics>;
+
+    /// Return the gas prices paid by programmable transactions, and the reference gas price, in
+    /// consecutive ranges of checkpoints, e.g. for charting gas prices. Ranges don't span epochs.
+    #[method(name = "getGasPriceHistory")]
+    async fn get_gas_price_history(
+        &self,
+        /// optional paging cursor, the first checkpoint of the last range of the previous page
+        cursor: Option<BigInt<u64>>,
+        /// maximum number of ranges per page
+        limit: Option<usize>,
+        /// flag to return results in descending order
+        descending_order: Option<bool>,
+    ) -> RpcResult<GasPriceStatsPage>;
+
+    /// Return the APY of each validator's staking pool in each epoch from `start_epoch` to
+    /// `end_epoch`, as computed from the pool's exchange rates at the end of the epoch, e.g. for
+    /// charting validators' APYs. An epoch's APYs are known once the next epoch has started.
+    /// Ranges span at most 100 epochs.
+    #[method(name = "getValidatorApyHistory")]
+    async fn get_validator_apy_history(
+        &self,
+        /// the first epoch to return APYs of
+        start_epoch: BigInt<u64>,
+        /// the last epoch to return APYs of (inclusive), defaults to 99 epochs after `start_epoch`
+        end_epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<ValidatorApyHistory>>;
+
+    /// Return what happened to a validator in each epoch from `start_epoch` to `end_epoch` that it
+    /// was active in: its commission rate, the stake that flowed in and out of its staking pool,
+    /// the tallying rule reports against it, and whether it was in its low-stake grace period. An
+    /// epoch's history is known once the epoch has ended. Ranges span at most 100 epochs.
+    #[method(name = "getValidatorHistory")]
+    async fn get_validator_history(
+        &self,
+        /// the validator's Sui address
+        address: SuiAddress,
+        /// the first epoch to return the history of
+        start_epoch: BigInt<u64>,
+        /// the last epoch to return the history of (inclusive), defaults to 99 epochs after
+        /// `start_epoch`
+        end_epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<ValidatorEpochHistory>>;
+
     /// Address related metrics
     #[method(name = "getLatestAddressMetrics")]
     async fn get_latest_address_metrics(&self) -> RpcResult<AddressMetrics>;
@@ -73,6 +141,222 @@ pub trait ExtendedApi {
         descending_order: Option<bool>,
     ) -> RpcResult<Vec<AddressMetrics>>;
 
+    /// Return the most active addresses over the last `days` days (including the current one, in
+    /// UTC) that the indexer has aggregated activity for, most active first. Only days within the
+    /// indexer's retention of aggregates are covered.
+    #[method(name = "getTopAccounts")]
+    async fn get_top_accounts(
+        &self,
+        /// what to rank addresses by
+        rank_by: AccountRanking,
+        /// number of days to sum activity over, defaults to 1
+        days: Option<u64>,
+        /// maximum number of addresses to return
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<AccountActivity>>;
+
+    /// Return the number of addresses that sent transactions on each completed day (UTC) in a
+    /// range, and in the week ending with it, oldest day first.
+    #[method(name = "getActiveAddressCounts")]
+    async fn get_active_address_counts(
+        &self,
+        /// optional timestamp within the first day to return, the first day counted if not specified
+        start_ms: Option<BigInt<u64>>,
+        /// optional timestamp within the last day to return, the latest day counted if not specified
+        end_ms: Option<BigInt<u64>>,
+        /// maximum number of days to return
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<ActiveAddressCounts>>;
+
     #[method(name = "getTotalTransactions")]
     async fn get_total_transactions(&self) -> RpcResult<BigInt<u64>>;
+
+    /// Return the checkpoint closest to a timestamp, among the checkpoints the indexer holds, or
+    /// null if there is none on the requested side of it.
+    #[method(name = "getCheckpointByTimestamp")]
+    async fn get_checkpoint_by_timestamp(
+        &self,
+        /// the timestamp, in milliseconds since the Unix epoch
+        timestamp_ms: BigInt<u64>,
+        /// whether to return the last checkpoint at or before the timestamp, or the first one at
+        /// or after it, defaults to before
+        direction: Option<TimestampDirection>,
+    ) -> RpcResult<Option<Checkpoint>>;
+
+    /// Return the layouts of the event types observed on chain, one for each event type and
+    /// package version that emitted it, ordered by event type.
+    #[method(name = "getEventSchemas")]
+    async fn get_event_schemas(
+        &self,
+        /// optional event type (struct tag) to return the schemas of
+        event_type: Option<String>,
+        /// optional paging cursor
+        cursor: Option<EventSchemaCursor>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+    ) -> RpcResult<EventSchemaPage>;
+
+    /// Return the objects with the given IDs as they were at the end of a checkpoint, all read from
+    /// the same snapshot so that they are consistent with each other. The checkpoint must be in
+    /// the server's consistent read range, which trails its latest checkpoint.
+    #[method(name = "multiGetObjectsAtCheckpoint")]
+    async fn multi_get_objects_at_checkpoint(
+        &self,
+        /// the IDs of the queried objects
+        object_ids: Vec<ObjectID>,
+        /// the checkpoint to read the objects as of, the latest checkpoint if not specified
+        checkpoint: Option<BigInt<u64>>,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiObjectResponse>>;
+
+    /// Return the events of a type that a version of a package emitted, decoded with the layout
+    /// the type had when they were indexed, rather than its latest layout, so that events
+    /// emitted before an upgrade decode as they were emitted.
+    #[method(name = "getEventsByPackageVersion")]
+    async fn get_events_by_package_version(
+        &self,
+        /// the event type, e.g. 0x2::coin::CoinMetadata<0x2::sui::SUI>
+        event_type: String,
+        /// the ID of the package version that emitted the events
+        package_id: ObjectID,
+        /// optional paging cursor
+        cursor: Option<EventID>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+        /// query result ordering, default to false (ascending order), oldest record first
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventPage>;
+
+    /// Return the events relevant to an address, of any type: those emitted by transactions the
+    /// address sent, and those with the address anywhere in their payload, other than as an
+    /// object ID.
+    #[method(name = "getEventsByAddress")]
+    async fn get_events_by_address(
+        &self,
+        /// the address to query events for
+        address: SuiAddress,
+        /// optional paging cursor
+        cursor: Option<EventID>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+        /// query result ordering, default to false (ascending order), oldest record first
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventPage>;
+
+    /// Return the transactions that created, wrapped, unwrapped and deleted an object, and the
+    /// number of times it changed owners.
+    #[method(name = "getObjectLifecycle")]
+    async fn get_object_lifecycle(&self, object_id: ObjectID) -> RpcResult<ObjectLifecycle>;
+
+    /// Return the transactions that created, mutated, wrapped, unwrapped or deleted an object,
+    /// oldest first, each with the version it left the object at.
+    #[method(name = "getObjectTransactionHistory")]
+    async fn get_object_transaction_history(
+        &self,
+        /// the ID of the queried object
+        object_id: ObjectID,
+        /// optional paging cursor
+        cursor: Option<TransactionDigest>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectTransactionPage>;
+
+    /// Return the objects a transaction took as input, at the versions it read them, including
+    /// the versions of shared objects assigned by consensus.
+    #[method(name = "getTransactionInputObjects")]
+    async fn get_transaction_input_objects(
+        &self,
+        /// the digest of the queried transaction
+        digest: TransactionDigest,
+    ) -> RpcResult<Vec<TransactionInputObject>>;
+
+    /// Return the objects a transaction changed, each with its Display fields as the transaction
+    /// left it, and the symbol and decimals of coins, so that the transaction can be rendered
+    /// without reading each object and coin type separately.
+    #[method(name = "getTransactionObjects")]
+    async fn get_transaction_objects(
+        &self,
+        /// the digest of the queried transaction
+        digest: TransactionDigest,
+    ) -> RpcResult<Vec<TransactionObject>>;
+
+    /// Return, for each of the given senders that has tried to use an owned object version in
+    /// more than one transaction, the object versions involved. Only transactions submitted
+    /// through this server, and rejected by validators for it, are reported.
+    #[method(name = "getEquivocationReports")]
+    async fn get_equivocation_reports(
+        &self,
+        /// the senders to report on
+        senders: Vec<SuiAddress>,
+    ) -> RpcResult<Vec<EquivocationReport>>;
+
+    /// Return an address' transactions that changed its coin balances, in execution order, with
+    /// the running balance of each coin type after each transaction, as taken from the balance
+    /// changes in the transactions' effects. The last page reports whether the closing balances
+    /// match the coins the address currently owns. Gas paid by the address as the sponsor of
+    /// other senders' transactions is not included.
+    #[method(name = "getAccountStatement")]
+    async fn get_account_statement(
+        &self,
+        /// the address to produce the statement for
+        address: SuiAddress,
+        /// optional coin type to restrict the statement to, all coin types if not specified
+        coin_type: Option<String>,
+        /// optional paging cursor, the `nextCursor` of the previous page
+        cursor: Option<AccountStatementCursor>,
+        /// maximum number of transactions to examine for this page
+        limit: Option<usize>,
+    ) -> RpcResult<AccountStatement>;
+
+    /// Return an address' balance of a coin type at the end of each bucket of time in a range,
+    /// oldest first, e.g. for charting the value of a portfolio. Buckets that the balance didn't
+    /// change in are included, with the balance carried over. Balances are only known up to the
+    /// last transaction whose balance changes the server has indexed, which can trail its latest
+    /// checkpoint.
+    #[method(name = "getBalanceHistory")]
+    async fn get_balance_history(
+        &self,
+        /// the owner's Sui address
+        address: SuiAddress,
+        /// type name for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC)
+        coin_type: String,
+        /// the length of each bucket
+        interval: BalanceHistoryInterval,
+        /// optional timestamp within the first bucket to return, the bucket of the address' first
+        /// change to the balance if not specified
+        start_ms: Option<BigInt<u64>>,
+        /// optional timestamp within the last bucket to return, the bucket of the latest indexed
+        /// balance change if not specified
+        end_ms: Option<BigInt<u64>>,
+        /// maximum number of buckets to return
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<BalanceSnapshot>>;
+
+    /// Return the objects owned by an address whose types have a Display, with their Display
+    /// fields rendered, in order of object ID.
+    #[method(name = "getNftsByOwner")]
+    async fn get_nfts_by_owner(
+        &self,
+        /// the owner's Sui address
+        address: SuiAddress,
+        /// optional paging cursor, the `nextCursor` of the previous page
+        cursor: Option<ObjectID>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+    ) -> RpcResult<NftPage>;
+
+    /// Return the changes to who held an object whose type has a Display, oldest first, with the
+    /// object's current Display fields. Only changes made while the object's type had a Display
+    /// are included.
+    #[method(name = "getNftTransferHistory")]
+    async fn get_nft_transfer_history(
+        &self,
+        /// the ID of the queried object
+        object_id: ObjectID,
+        /// optional paging cursor, the `nextCursor` of the previous page
+        cursor: Option<BigInt<u64>>,
+        /// maximum number of transfers per page
+        limit: Option<usize>,
+    ) -> RpcResult<NftTransferHistory>;
 }
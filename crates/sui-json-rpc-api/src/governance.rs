@@ -4,7 +4,10 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
-use sui_json_rpc_types::{DelegatedStake, SuiCommittee, ValidatorApys};
+use sui_json_rpc_types::{
+    DelegatedStake, DelegatedStakePage, DelegatedStakeResponse, StakeRewardEstimate, SuiCommittee,
+    ValidatorApys, ValidatorSetChanges,
+};
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::sui_serde::BigInt;
@@ -18,11 +21,54 @@ pub trait GovernanceReadApi {
     async fn get_stakes_by_ids(
         &self,
         staked_sui_ids: Vec<ObjectID>,
+        /// Read the stakes as of the end of this checkpoint rather than the latest one. Only
+        /// supported by the indexer.
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<Vec<DelegatedStake>>;
 
+    /// Return the [DelegatedStake] of each StakedSui object, in the order of `staked_sui_ids`.
+    /// Unlike getStakesByIds, IDs that don't resolve to a StakedSui object (e.g. because the stake
+    /// has been withdrawn) get a status of their own, instead of failing the whole request.
+    #[method(name = "tryGetStakesByIds")]
+    async fn try_get_stakes_by_ids(
+        &self,
+        staked_sui_ids: Vec<ObjectID>,
+    ) -> RpcResult<Vec<DelegatedStakeResponse>>;
+
     /// Return all [DelegatedStake].
     #[method(name = "getStakes")]
-    async fn get_stakes(&self, owner: SuiAddress) -> RpcResult<Vec<DelegatedStake>>;
+    async fn get_stakes(
+        &self,
+        owner: SuiAddress,
+        /// Read the stakes as of the end of this checkpoint rather than the latest one. Only
+        /// supported by the indexer.
+        at_checkpoint: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<DelegatedStake>>;
+
+    /// Return a page of [DelegatedStake], for owners with too many StakedSui objects to return at
+    /// once. Stakes are paginated in order of their StakedSui object ID, and grouped by staking
+    /// pool within each page, so a pool can appear on more than one page.
+    #[method(name = "getStakesPaginated")]
+    async fn get_stakes_paginated(
+        &self,
+        /// the owner's Sui address
+        owner: SuiAddress,
+        /// optional paging cursor
+        cursor: Option<ObjectID>,
+        /// maximum number of StakedSui objects per page
+        limit: Option<usize>,
+    ) -> RpcResult<DelegatedStakePage>;
+
+    /// Return the reward a StakedSui object has earned so far, and the reward it's projected to
+    /// have earned if it's held until `target_epoch`, at its validator's current APY.
+    #[method(name = "getStakeRewardEstimate")]
+    async fn get_stake_reward_estimate(
+        &self,
+        /// The ID of the StakedSui object
+        staked_sui_id: ObjectID,
+        /// The epoch to project the reward to, which must not be before the current epoch
+        target_epoch: BigInt<u64>,
+    ) -> RpcResult<StakeRewardEstimate>;
 
     /// Return the committee information for the asked `epoch`.
     #[method(name = "getCommitteeInfo")]
@@ -34,7 +80,12 @@ pub trait GovernanceReadApi {
 
     /// Return the latest SUI system state object on-chain.
     #[method(name = "getLatestSuiSystemState")]
-    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary>;
+    async fn get_latest_sui_system_state(
+        &self,
+        /// Read the system state as of the end of this checkpoint rather than the latest one. Only
+        /// supported by the indexer.
+        at_checkpoint: Option<BigInt<u64>>,
+    ) -> RpcResult<SuiSystemStateSummary>;
 
     /// Return the reference gas price for the network
     #[method(name = "getReferenceGasPrice")]
@@ -43,4 +94,10 @@ pub trait GovernanceReadApi {
     /// Return the validator APY
     #[method(name = "getValidatorsApy")]
     async fn get_validators_apy(&self) -> RpcResult<ValidatorApys>;
+
+    /// Subscribe to a stream of the changes to the active validator set: the validators that
+    /// joined and left it, and those whose stake or commission rate changed. An item is sent at
+    /// the start of each epoch after the subscription is opened.
+    #[subscription(name = "subscribeValidatorSetChanges", item = ValidatorSetChanges)]
+    fn subscribe_validator_set_changes(&self);
 }
@@ -6,15 +6,17 @@ use jsonrpsee::proc_macros::rpc;
 
 use sui_json_rpc_types::SuiTransactionBlockEffects;
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiEvent, SuiObjectResponse,
+    CheckpointedTransactionEffects, DynamicFieldPage, DynamicFieldPathSegment, DynamicFieldTree,
+    EventFilter, EventPage, ObjectsPage, Page, SuiEvent, SuiMoveValue, SuiObjectResponse,
     SuiObjectResponseQuery, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
-    TransactionFilter,
+    TransactionEffectsFilter, TransactionFilter,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
+use sui_types::sui_serde::BigInt;
 
 #[open_rpc(namespace = "suix", tag = "Extended API")]
 #[rpc(server, client, namespace = "suix")]
@@ -35,6 +37,9 @@ pub trait IndexerApi {
         cursor: Option<ObjectID>,
         /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
         limit: Option<usize>,
+        /// Return the objects owned as of the end of this checkpoint, so that queries pinned to the
+        /// same checkpoint are consistent with each other. Only supported by the indexer.
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<ObjectsPage>;
 
     /// Return list of transactions for a specified query criteria.
@@ -49,6 +54,9 @@ pub trait IndexerApi {
         limit: Option<usize>,
         /// query result ordering, default to false (ascending order), oldest record first.
         descending_order: Option<bool>,
+        /// Only return transactions up to the end of this checkpoint, so that queries pinned to the
+        /// same checkpoint are consistent with each other. Only supported by the indexer.
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<TransactionBlocksPage>;
 
     /// Return list of events for a specified query criteria.
@@ -63,6 +71,9 @@ pub trait IndexerApi {
         limit: Option<usize>,
         /// query result ordering, default to false (ascending order), oldest record first.
         descending_order: Option<bool>,
+        /// Only return events up to the end of this checkpoint, so that queries pinned to the same
+        /// checkpoint are consistent with each other. Only supported by the indexer.
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<EventPage>;
 
     /// Subscribe to a stream of Sui event
@@ -77,6 +88,19 @@ pub trait IndexerApi {
     #[subscription(name = "subscribeTransaction", item = SuiTransactionBlockEffects)]
     fn subscribe_transaction(&self, filter: TransactionFilter);
 
+    /// Subscribe to a stream of the effects of every transaction touching a package, in
+    /// checkpoint order. Each item carries its checkpoint, so that a client can resume after the
+    /// last checkpoint it processed by passing it as the cursor.
+    #[subscription(name = "subscribeTransactionEffects", item = CheckpointedTransactionEffects)]
+    fn subscribe_transaction_effects(
+        &self,
+        /// The filter criteria of the transaction stream.
+        filter: TransactionEffectsFilter,
+        /// Stream transactions from the checkpoints after this one, which must not be ahead of the
+        /// latest executed checkpoint. Streams from the next checkpoint to be executed if not specified.
+        cursor: Option<BigInt<u64>>,
+    );
+
     /// Return the list of dynamic field objects owned by an object.
     #[method(name = "getDynamicFields")]
     async fn get_dynamic_fields(
@@ -99,6 +123,33 @@ pub trait IndexerApi {
         name: DynamicFieldName,
     ) -> RpcResult<SuiObjectResponse>;
 
+    /// Return the value at the end of a path through the Move fields and dynamic fields of an
+    /// object, e.g. `["config", 3, "fees"]`, resolved in one call rather than a call per dynamic
+    /// field. Returns null if any step of the path doesn't exist.
+    #[method(name = "getDynamicFieldValueByPath")]
+    async fn get_dynamic_field_value_by_path(
+        &self,
+        /// The ID of the object the path starts from
+        parent_object_id: ObjectID,
+        /// The Move fields, vector indices, and dynamic field names to follow
+        path: Vec<DynamicFieldPathSegment>,
+    ) -> RpcResult<Option<SuiMoveValue>>;
+
+    /// Return the dynamic fields of an object, with their values, and the dynamic fields under
+    /// those in turn, down to `max_depth` levels. Fields are listed breadth first, up to `limit`
+    /// in all; wherever the tree is cut off, `hasNextPage` is set and the rest of the fields can be
+    /// listed with `suix_getDynamicFields`.
+    #[method(name = "getDynamicFieldTree")]
+    async fn get_dynamic_field_tree(
+        &self,
+        /// The ID of the object whose dynamic fields are walked
+        parent_object_id: ObjectID,
+        /// Levels of dynamic fields to walk, default to 3 if not specified, and at most 16
+        max_depth: Option<usize>,
+        /// Maximum number of dynamic fields in the tree, default to 1000 if not specified
+        limit: Option<usize>,
+    ) -> RpcResult<DynamicFieldTree>;
+
     /// Return the resolved address given resolver and name
     #[method(name = "resolveNameServiceAddress")]
     async fn resolve_name_service_address(
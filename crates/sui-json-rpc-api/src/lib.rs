@@ -24,6 +24,9 @@ use prometheus::{register_int_counter_with_registry, IntCounter};
 pub use read::ReadApiClient;
 pub use read::ReadApiOpenRpc;
 pub use read::ReadApiServer;
+pub use scheduled::ScheduledTransactionApiClient;
+pub use scheduled::ScheduledTransactionApiOpenRpc;
+pub use scheduled::ScheduledTransactionApiServer;
 use tap::TapFallible;
 use tracing::warn;
 pub use transaction_builder::TransactionBuilderClient;
@@ -39,6 +42,7 @@ mod governance;
 mod indexer;
 mod move_utils;
 mod read;
+mod scheduled;
 mod transaction_builder;
 mod write;
 
@@ -49,6 +53,24 @@ pub static QUERY_MAX_RESULT_LIMIT: Lazy<usize> = Lazy::new(|| {
     read_size_from_env(RPC_QUERY_MAX_RESULT_LIMIT).unwrap_or(DEFAULT_RPC_QUERY_MAX_RESULT_LIMIT)
 });
 
+const RPC_MAX_RESPONSE_SIZE: &str = "RPC_MAX_RESPONSE_SIZE";
+const DEFAULT_RPC_MAX_RESPONSE_SIZE: usize = 2 << 30;
+
+/// The maximum size of a response, in bytes. Requests whose responses would exceed it fail,
+/// except for paginated queries whose pages can be truncated to fit (see
+/// `Page::truncate_to_size`).
+pub static MAX_RESPONSE_SIZE: Lazy<usize> = Lazy::new(|| {
+    read_size_from_env(RPC_MAX_RESPONSE_SIZE).unwrap_or(DEFAULT_RPC_MAX_RESPONSE_SIZE)
+});
+
+const PAGE_ENVELOPE_SIZE: usize = 4 << 10;
+
+/// How much of [`MAX_RESPONSE_SIZE`] truncated pages can spend on their items, leaving room for
+/// the rest of the response (its cursor and the JSON-RPC envelope).
+pub fn max_page_data_size() -> usize {
+    MAX_RESPONSE_SIZE.saturating_sub(PAGE_ENVELOPE_SIZE)
+}
+
 // TODOD(chris): make this configurable
 pub const QUERY_MAX_RESULT_LIMIT_CHECKPOINTS: usize = 100;
 
@@ -297,6 +319,25 @@ pub const CLIENT_SDK_VERSION_HEADER: &str = "client-sdk-version";
 /// The RPC API version that the client is targeting. Different SDK versions may target the same
 /// API version.
 pub const CLIENT_TARGET_API_VERSION_HEADER: &str = "client-target-api-version";
+/// The RPC API version that the server implements, returned on every response.
+pub const SERVER_API_VERSION_HEADER: &str = "server-api-version";
+/// The oldest RPC API version that the server still serves requests for, if it has one. Requests
+/// targeting older versions are rejected.
+pub const SERVER_MIN_SUPPORTED_API_VERSION_HEADER: &str = "server-min-supported-api-version";
+/// Set on a transaction execution request to ask for a [`MIN_CHECKPOINT_HEADER`] token in the
+/// response.
+pub const REQUEST_MIN_CHECKPOINT_HEADER: &str = "request-min-checkpoint";
+/// On a transaction execution response, the checkpoint that includes the executed transaction.
+/// On a read request, the checkpoint the server must have caught up to before serving the read.
+pub const MIN_CHECKPOINT_HEADER: &str = "min-checkpoint";
+/// Identifies the client making a request, for servers that limit how many requests each client
+/// can have in flight.
+pub const API_KEY_HEADER: &str = "x-api-key";
+/// Set on responses served from data that has fallen behind the chain, to how far behind it is, in
+/// milliseconds.
+pub const DATA_LAG_HEADER: &str = "data-lag-ms";
 
 pub const TRANSIENT_ERROR_CODE: i32 = -32050;
 pub const TRANSACTION_EXECUTION_CLIENT_ERROR_CODE: i32 = -32002;
+/// Returned when a server gives up on a request whose queries ran past its method's deadline.
+pub const QUERY_TIMEOUT_ERROR_CODE: i32 = -32051;
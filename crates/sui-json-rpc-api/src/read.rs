@@ -1,11 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
+use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
 use sui_json_rpc_types::{
-    Checkpoint, CheckpointId, CheckpointPage, SuiEvent, SuiGetPastObjectRequest,
+    Checkpoint, CheckpointId, CheckpointPage, DisplayFieldError, SuiEvent, SuiGetPastObjectRequest,
     SuiObjectDataOptions, SuiObjectResponse, SuiPastObjectResponse, SuiTransactionBlockResponse,
     SuiTransactionBlockResponseOptions,
 };
@@ -152,4 +155,32 @@ pub trait ReadApi {
     /// Return the first four bytes of the chain's genesis checkpoint digest.
     #[method(name = "getChainIdentifier")]
     async fn get_chain_identifier(&self) -> RpcResult<String>;
+
+    /// Verify a checkpoint certificate against the committee of the given epoch, as known to the
+    /// server. Returns whether the signature is a valid quorum signature over the checkpoint
+    /// summary, allowing data that was served out-of-band (e.g. from an archive) to be verified
+    /// without running a node.
+    #[method(name = "verifyCheckpointSignature")]
+    async fn verify_checkpoint_signature(
+        &self,
+        /// BCS encoded CheckpointSummary
+        summary: Base64,
+        /// BCS encoded AuthorityStrongQuorumSignInfo certifying the checkpoint summary
+        signature: Base64,
+        /// the epoch of the committee expected to have signed the checkpoint
+        epoch: BigInt<u64>,
+    ) -> RpcResult<bool>;
+
+    /// Check Display templates against the fields of the type they display, returning the
+    /// problems that would stop each field from rendering, e.g. a template referencing a field
+    /// that the type doesn't have. Checks the latest Display published for the type if no fields
+    /// are given.
+    #[method(name = "validateDisplay")]
+    async fn validate_display(
+        &self,
+        /// the type of the objects displayed, e.g. `0x2::kiosk::Kiosk`
+        object_type: String,
+        /// the Display fields to check, by key
+        fields: Option<BTreeMap<String, String>>,
+    ) -> RpcResult<Vec<DisplayFieldError>>;
 }
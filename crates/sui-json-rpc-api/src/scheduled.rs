@@ -0,0 +1,56 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::encoding::Base64;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+
+use sui_json_rpc_types::SuiScheduledTransactionBlock;
+use sui_open_rpc_macros::open_rpc;
+use sui_types::digests::TransactionDigest;
+use sui_types::sui_serde::BigInt;
+
+/// Pre-signed transactions that the node stores and submits once the chain reaches a given
+/// checkpoint or time. Only served by fullnodes configured to schedule transactions.
+#[open_rpc(namespace = "sui", tag = "Scheduled Transaction API")]
+#[rpc(server, client, namespace = "sui")]
+pub trait ScheduledTransactionApi {
+    /// Store a signed transaction, to be submitted by the node once the latest executed
+    /// checkpoint is at least `not_before_checkpoint` and its timestamp is at least
+    /// `not_before_timestamp_ms`, e.g. for delayed payouts or dead-man switches. The transaction
+    /// is only submitted successfully if the objects it uses are unchanged by then. Returns the
+    /// digest to cancel the transaction with.
+    #[method(name = "scheduleTransactionBlock")]
+    async fn schedule_transaction_block(
+        &self,
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        tx_bytes: Base64,
+        /// A list of signatures (`flag || signature || pubkey` bytes, as base-64 encoded string). Signature is committed to the intent message of the transaction data, as base-64 encoded string.
+        signatures: Vec<Base64>,
+        /// The checkpoint to submit the transaction after. No constraint if not specified.
+        not_before_checkpoint: Option<BigInt<u64>>,
+        /// The checkpoint timestamp to submit the transaction after. No constraint if not specified.
+        not_before_timestamp_ms: Option<BigInt<u64>>,
+    ) -> RpcResult<TransactionDigest>;
+
+    /// Stop the node from submitting a scheduled transaction. Only its sender can cancel it, by
+    /// signing its digest as a personal message. Returns whether the transaction was still
+    /// scheduled. Anyone else holding the signed transaction can still submit it.
+    #[method(name = "cancelScheduledTransactionBlock")]
+    async fn cancel_scheduled_transaction_block(
+        &self,
+        /// The digest of the scheduled transaction.
+        digest: TransactionDigest,
+        /// The sender's signature (`flag || signature || pubkey` bytes, as base-64 encoded string) of the digest's bytes, as a personal message.
+        signature: Base64,
+    ) -> RpcResult<bool>;
+
+    /// Return a transaction that is waiting to be submitted, or null if it isn't scheduled,
+    /// e.g. because it has been submitted or cancelled.
+    #[method(name = "getScheduledTransactionBlock")]
+    async fn get_scheduled_transaction_block(
+        &self,
+        /// The digest of the scheduled transaction.
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<SuiScheduledTransactionBlock>>;
+}
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
@@ -154,6 +156,23 @@ pub trait TransactionBuilder {
         gas_budget: BigInt<u64>,
     ) -> RpcResult<TransactionBlockBytes>;
 
+    /// Create an unsigned transaction to publish a Move package, linked against the on-chain
+    /// packages that its modules depend on, directly or transitively, as resolved by the server.
+    #[method(name = "publishWithDependencyResolution")]
+    async fn publish_with_dependency_resolution(
+        &self,
+        /// the transaction signer's Sui address
+        sender: SuiAddress,
+        /// the compiled bytes of a Move package
+        compiled_modules: Vec<Base64>,
+        /// the versions of dependencies to link against, from the ID each dependency was originally published at to the ID of the version to use. Immediate dependencies default to their original version, and transitive ones to the latest version their dependents were linked against.
+        pinned_dependencies: Option<BTreeMap<ObjectID, ObjectID>>,
+        /// gas object to be used in this transaction, node will pick one from the signer's possession if not provided
+        gas: Option<ObjectID>,
+        /// the gas budget, the transaction will fail if the gas cost exceed the budget
+        gas_budget: BigInt<u64>,
+    ) -> RpcResult<TransactionBlockBytes>;
+
     /// Create an unsigned transaction to split a coin object into multiple coins.
     #[method(name = "splitCoin")]
     async fn split_coin(
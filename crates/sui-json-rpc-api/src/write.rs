@@ -6,8 +6,8 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
 use sui_json_rpc_types::{
-    DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlockLint,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::SuiAddress;
@@ -64,4 +64,26 @@ pub trait WriteApi {
         &self,
         tx_bytes: Base64,
     ) -> RpcResult<DryRunTransactionBlockResponse>;
+
+    /// Return the execution effects of a bundle of transactions, dry-run in order, each one
+    /// against the state left by the ones before it, e.g. to simulate publishing a package and
+    /// then calling it. None of the effects are committed to the chain. Owned objects written by
+    /// earlier transactions in the bundle are read at the version they were left at, whatever
+    /// version and digest later transactions refer to them by.
+    #[method(name = "dryRunTransactionBlocks")]
+    async fn dry_run_transaction_blocks(
+        &self,
+        /// BCS serialized TransactionData of each transaction, in the order to dry-run them in.
+        tx_bytes: Vec<Base64>,
+    ) -> RpcResult<Vec<DryRunTransactionBlockResponse>>;
+
+    /// Return likely mistakes in a transaction, found by analyzing it without executing it, e.g.
+    /// for wallets to warn about before the transaction is signed. An empty list doesn't mean
+    /// that the transaction will succeed.
+    #[method(name = "lintTransactionBlock")]
+    async fn lint_transaction_block(
+        &self,
+        /// BCS serialized TransactionData
+        tx_bytes: Base64,
+    ) -> RpcResult<Vec<SuiTransactionBlockLint>>;
 }
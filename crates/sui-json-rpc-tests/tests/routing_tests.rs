@@ -131,6 +131,46 @@ async fn test_disable_routing() {
     assert!(response.is_err());
 }
 
+#[test]
+fn test_serve_on_custom_runtime() {
+    let server_runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .unwrap();
+    let caller_runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = caller_runtime
+        .block_on(builder.start(address, Some(server_runtime.handle().clone()), None))
+        .unwrap();
+
+    // Nothing drives the caller's runtime from here on, so requests are only answered if they are
+    // served on the custom runtime.
+    let client_runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+    let response: String = client_runtime
+        .block_on(async {
+            let client = HttpClientBuilder::default().build(&url).unwrap();
+            tokio::time::timeout(
+                std::time::Duration::from_secs(10),
+                client.request("test_foo", rpc_params!(true)),
+            )
+            .await
+        })
+        .expect("Request is served on the custom runtime")
+        .unwrap();
+    assert_eq!("Some string", response);
+}
+
 // TODO(chris): clean up this after March 27th, 2023
 // #[tokio::test]
 // async fn test_rpc_backward_compatibility_batched_request() {
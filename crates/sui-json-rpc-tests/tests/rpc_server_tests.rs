@@ -1,7 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeMap;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 #[cfg(not(msim))]
 use std::str::FromStr;
@@ -14,12 +15,14 @@ use sui_json_rpc_api::{
 use sui_json_rpc_types::ObjectChange;
 use sui_json_rpc_types::ObjectsPage;
 use sui_json_rpc_types::{
-    Balance, CoinPage, DelegatedStake, StakeStatus, SuiCoinMetadata, SuiExecutionStatus,
-    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockEffectsAPI,
-    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions, TransactionBlockBytes,
+    Balance, CoinPage, DelegatedStake, DelegatedStakeResponse, StakeStatus, SuiCoinMetadata,
+    SuiExecutionStatus, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery,
+    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    TransactionBlockBytes,
 };
 use sui_macros::sim_test;
 use sui_move_build::BuildConfig;
+use sui_open_rpc::ExamplePairing;
 use sui_swarm_config::genesis_config::{DEFAULT_GAS_AMOUNT, DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT};
 use sui_types::balance::Supply;
 use sui_types::base_types::ObjectID;
@@ -28,6 +31,8 @@ use sui_types::coin::{TreasuryCap, COIN_MODULE_NAME};
 use sui_types::digests::ObjectDigest;
 use sui_types::gas_coin::GAS;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::sui_serde::BigInt;
+use sui_types::transaction::{Command, TransactionDataAPI, TransactionKind};
 use sui_types::{parse_sui_struct_tag, SUI_FRAMEWORK_ADDRESS};
 use test_cluster::TestClusterBuilder;
 use tokio::time::sleep;
@@ -39,18 +44,24 @@ async fn test_get_objects() -> Result<(), anyhow::Error> {
     let http_client = cluster.rpc_client();
     let address = cluster.get_address_0();
 
+    let query = SuiObjectResponseQuery::new_with_options(SuiObjectDataOptions::new());
     let objects = http_client
-        .get_owned_objects(
-            address,
-            Some(SuiObjectResponseQuery::new_with_options(
-                SuiObjectDataOptions::new(),
-            )),
-            None,
-            None,
-        )
+        .get_owned_objects(address, Some(query.clone()), None, None, None)
         .await?;
     assert_eq!(5, objects.data.len());
 
+    ExamplePairing::new(
+        "Gets the objects owned by an address.",
+        vec![
+            ("address", json!(address)),
+            ("query", json!(query)),
+            ("cursor", json!(null)),
+            ("limit", json!(null)),
+        ],
+        json!(objects),
+    )
+    .record("suix_getOwnedObjects")?;
+
     // Multiget objectIDs test
     let object_digests = objects
         .data
@@ -102,6 +113,7 @@ async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -184,6 +196,7 @@ async fn test_publish() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?;
     let gas = objects.data.first().unwrap().object().unwrap();
@@ -221,6 +234,57 @@ async fn test_publish() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_publish_with_dependency_resolution() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    let compiled_package = BuildConfig::new_for_testing()
+        .build(Path::new("../../sui_programmability/examples/fungible_tokens").to_path_buf())?;
+    let compiled_modules_bytes =
+        compiled_package.get_package_base64(/* with_unpublished_deps */ false);
+    let dependencies: BTreeSet<_> = compiled_package
+        .get_dependency_original_package_ids()
+        .into_iter()
+        .collect();
+
+    // Without pins, the server links against the same packages the build resolved.
+    let transaction_bytes: TransactionBlockBytes = http_client
+        .publish_with_dependency_resolution(
+            address,
+            compiled_modules_bytes,
+            None,
+            None,
+            100_000_000.into(),
+        )
+        .await?;
+    let data = transaction_bytes.to_data()?;
+    let TransactionKind::ProgrammableTransaction(pt) = data.kind() else {
+        panic!("Expected a programmable transaction");
+    };
+    let [Command::Publish(_, dep_ids)] = pt.commands.as_slice() else {
+        panic!("Expected a single publish command");
+    };
+    assert_eq!(
+        dep_ids.iter().copied().collect::<BTreeSet<_>>(),
+        dependencies
+    );
+
+    let tx = cluster.wallet.sign_transaction(&data);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    let tx_response = http_client
+        .execute_transaction_block(
+            tx_bytes,
+            signatures,
+            Some(SuiTransactionBlockResponseOptions::new().with_effects()),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert!(tx_response.effects.unwrap().status().is_ok());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_move_call() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await;
@@ -238,6 +302,7 @@ async fn test_move_call() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -298,6 +363,7 @@ async fn test_get_object_info() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -330,6 +396,7 @@ async fn test_get_object_data_with_content() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -441,6 +508,7 @@ async fn test_get_metadata() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -526,6 +594,7 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -663,16 +732,17 @@ async fn test_staking() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?;
     assert_eq!(5, objects.data.len());
 
     // Check StakedSui object before test
-    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address).await?;
+    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address, None).await?;
     assert!(staked_sui.is_empty());
 
     let validator = http_client
-        .get_latest_sui_system_state()
+        .get_latest_sui_system_state(None)
         .await?
         .active_validators[0]
         .sui_address;
@@ -705,7 +775,7 @@ async fn test_staking() -> Result<(), anyhow::Error> {
         .await?;
 
     // Check DelegatedStake object
-    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address).await?;
+    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address, None).await?;
     assert_eq!(1, staked_sui.len());
     assert_eq!(1000000000, staked_sui[0].stakes[0].principal);
     assert!(matches!(
@@ -713,12 +783,99 @@ async fn test_staking() -> Result<(), anyhow::Error> {
         StakeStatus::Pending
     ));
     let staked_sui_copy = http_client
-        .get_stakes_by_ids(vec![staked_sui[0].stakes[0].staked_sui_id])
+        .get_stakes_by_ids(vec![staked_sui[0].stakes[0].staked_sui_id], None)
         .await?;
     assert_eq!(
         staked_sui[0].stakes[0].staked_sui_id,
         staked_sui_copy[0].stakes[0].staked_sui_id
     );
+
+    // Looked up alongside an ID that isn't a StakedSui object, the stake is still found.
+    let missing_id = ObjectID::random();
+    let responses = http_client
+        .try_get_stakes_by_ids(vec![missing_id, staked_sui[0].stakes[0].staked_sui_id])
+        .await?;
+    assert_eq!(2, responses.len());
+    assert!(matches!(
+        &responses[0],
+        DelegatedStakeResponse::NotFound(id) if *id == missing_id
+    ));
+    assert!(matches!(
+        &responses[1],
+        DelegatedStakeResponse::Found(stake)
+            if stake.stakes[0].staked_sui_id == staked_sui[0].stakes[0].staked_sui_id
+                && matches!(stake.stakes[0].status, StakeStatus::Pending)
+    ));
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_stakes_paginated() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    let coins: CoinPage = http_client.get_coins(address, None, None, None).await?;
+    let validator = http_client
+        .get_latest_sui_system_state(None)
+        .await?
+        .active_validators[0]
+        .sui_address;
+
+    // Stake twice, from different coins, for two StakedSui objects.
+    for coin in &coins.data[..2] {
+        let transaction_bytes: TransactionBlockBytes = http_client
+            .request_add_stake(
+                address,
+                vec![coin.coin_object_id],
+                Some(1000000000.into()),
+                validator,
+                Some(coins.data[2].coin_object_id),
+                100_000_000.into(),
+            )
+            .await?;
+        let tx = cluster
+            .wallet
+            .sign_transaction(&transaction_bytes.to_data()?);
+        let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+        http_client
+            .execute_transaction_block(
+                tx_bytes,
+                signatures,
+                Some(SuiTransactionBlockResponseOptions::new()),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?;
+    }
+
+    // Pages of one stake each, in order of StakedSui object ID.
+    let first = http_client
+        .get_stakes_paginated(address, None, Some(1))
+        .await?;
+    assert!(first.has_next_page);
+    assert_eq!(1, first.data.len());
+    assert_eq!(1, first.data[0].stakes.len());
+    let first_id = first.data[0].stakes[0].staked_sui_id;
+    assert_eq!(first.next_cursor, Some(first_id));
+
+    let second = http_client
+        .get_stakes_paginated(address, first.next_cursor, Some(1))
+        .await?;
+    assert!(!second.has_next_page);
+    assert_eq!(1, second.data.len());
+    let second_id = second.data[0].stakes[0].staked_sui_id;
+    assert!(first_id < second_id);
+
+    // Together, the pages hold every stake.
+    let mut all: Vec<_> = http_client
+        .get_stakes(address, None)
+        .await?
+        .iter()
+        .flat_map(|stake| stake.stakes.iter().map(|s| s.staked_sui_id))
+        .collect();
+    all.sort();
+    assert_eq!(all, [first_id, second_id]);
     Ok(())
 }
 
@@ -737,11 +894,11 @@ async fn test_unstaking() -> Result<(), anyhow::Error> {
     assert_eq!(5, coins.data.len());
 
     // Check StakedSui object before test
-    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address).await?;
+    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address, None).await?;
     assert!(staked_sui.is_empty());
 
     let validator = http_client
-        .get_latest_sui_system_state()
+        .get_latest_sui_system_state(None)
         .await?
         .active_validators[0]
         .sui_address;
@@ -774,18 +931,21 @@ async fn test_unstaking() -> Result<(), anyhow::Error> {
             .await?;
     }
     // Check DelegatedStake object
-    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address).await?;
+    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address, None).await?;
     assert_eq!(1, staked_sui.len());
     assert_eq!(1000000000, staked_sui[0].stakes[0].principal);
 
     sleep(Duration::from_millis(10000)).await;
 
     let staked_sui_copy = http_client
-        .get_stakes_by_ids(vec![
-            staked_sui[0].stakes[0].staked_sui_id,
-            staked_sui[0].stakes[1].staked_sui_id,
-            staked_sui[0].stakes[2].staked_sui_id,
-        ])
+        .get_stakes_by_ids(
+            vec![
+                staked_sui[0].stakes[0].staked_sui_id,
+                staked_sui[0].stakes[1].staked_sui_id,
+                staked_sui[0].stakes[2].staked_sui_id,
+            ],
+            None,
+        )
         .await?;
 
     assert!(matches!(
@@ -833,11 +993,14 @@ async fn test_unstaking() -> Result<(), anyhow::Error> {
     sleep(Duration::from_millis(20000)).await;
 
     let staked_sui_copy = http_client
-        .get_stakes_by_ids(vec![
-            staked_sui[0].stakes[0].staked_sui_id,
-            staked_sui[0].stakes[1].staked_sui_id,
-            staked_sui[0].stakes[2].staked_sui_id,
-        ])
+        .get_stakes_by_ids(
+            vec![
+                staked_sui[0].stakes[0].staked_sui_id,
+                staked_sui[0].stakes[1].staked_sui_id,
+                staked_sui[0].stakes[2].staked_sui_id,
+            ],
+            None,
+        )
         .await?;
 
     assert!(matches!(
@@ -856,6 +1019,54 @@ async fn test_unstaking() -> Result<(), anyhow::Error> {
         &staked_sui_copy[0].stakes[2].status,
         StakeStatus::Unstaked
     ));
+
+    let missing_id = ObjectID::random();
+    let responses = http_client
+        .try_get_stakes_by_ids(vec![
+            staked_sui[0].stakes[2].staked_sui_id,
+            missing_id,
+            staked_sui[0].stakes[0].staked_sui_id,
+        ])
+        .await?;
+    assert_eq!(3, responses.len());
+    assert!(matches!(
+        &responses[0],
+        DelegatedStakeResponse::Found(stake)
+            if matches!(stake.stakes[0].status, StakeStatus::Unstaked)
+    ));
+    assert!(matches!(
+        &responses[1],
+        DelegatedStakeResponse::NotFound(id) if *id == missing_id
+    ));
+    assert!(matches!(
+        &responses[2],
+        DelegatedStakeResponse::Found(stake)
+            if stake.stakes[0].staked_sui_id == staked_sui[0].stakes[0].staked_sui_id
+    ));
+
+    let epoch = http_client.get_latest_sui_system_state(None).await?.epoch;
+    let estimate = http_client
+        .get_stake_reward_estimate(
+            staked_sui[0].stakes[0].staked_sui_id,
+            BigInt::from(epoch + 365),
+        )
+        .await?;
+    assert_eq!(
+        estimate.staked_sui_id,
+        staked_sui[0].stakes[0].staked_sui_id
+    );
+    assert_eq!(estimate.principal, staked_sui[0].stakes[0].principal);
+    assert_eq!(estimate.epoch, epoch);
+    assert!(estimate.projected_reward >= estimate.accrued_reward);
+    if epoch > 0 {
+        assert!(http_client
+            .get_stake_reward_estimate(
+                staked_sui[0].stakes[0].staked_sui_id,
+                BigInt::from(epoch - 1),
+            )
+            .await
+            .is_err());
+    }
     Ok(())
 }
 
@@ -872,11 +1083,11 @@ async fn test_staking_multiple_coins() -> Result<(), anyhow::Error> {
     let genesis_coin_amount = coins.data[0].balance;
 
     // Check StakedSui object before test
-    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address).await?;
+    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address, None).await?;
     assert!(staked_sui.is_empty());
 
     let validator = http_client
-        .get_latest_sui_system_state()
+        .get_latest_sui_system_state(None)
         .await?
         .active_validators[0]
         .sui_address;
@@ -931,7 +1142,7 @@ async fn test_staking_multiple_coins() -> Result<(), anyhow::Error> {
     );
 
     // Check DelegatedStake object
-    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address).await?;
+    let staked_sui: Vec<DelegatedStake> = http_client.get_stakes(address, None).await?;
     assert_eq!(1, staked_sui.len());
     assert_eq!(1000000000, staked_sui[0].stakes[0].principal);
     assert!(matches!(
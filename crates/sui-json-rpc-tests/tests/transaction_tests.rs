@@ -31,6 +31,7 @@ async fn test_get_transaction_block() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -114,6 +115,7 @@ async fn test_get_raw_transaction() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 
 pub use balance_changes::*;
 pub use object_changes::*;
+pub use projection::FieldProjection;
 pub use sui_checkpoint::*;
 pub use sui_coin::*;
 pub use sui_event::*;
@@ -25,6 +26,7 @@ mod rpc_types_tests;
 mod balance_changes;
 mod displays;
 mod object_changes;
+mod projection;
 mod sui_checkpoint;
 mod sui_coin;
 mod sui_event;
@@ -45,6 +47,11 @@ pub struct Page<T, C> {
     pub data: Vec<T>,
     pub next_cursor: Option<C>,
     pub has_next_page: bool,
+    /// Whether the page holds fewer items than requested, because returning them all would have
+    /// exceeded the server's response size limit. The remaining items can be read by following
+    /// `next_cursor`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
 }
 
 impl<T, C> Page<T, C> {
@@ -53,6 +60,52 @@ impl<T, C> Page<T, C> {
             data: vec![],
             next_cursor: None,
             has_next_page: false,
+            truncated: false,
         }
     }
 }
+
+impl<T: Serialize, C> Page<T, C> {
+    /// Drop items from the end of the page until its items serialize to at most `max_size`
+    /// bytes of JSON, pointing `next_cursor` at the last item kept (as identified by `cursor`).
+    /// The first item is always kept, so that clients can make progress.
+    ///
+    /// Only suitable for pages whose cursor identifies their last item, so that reading from
+    /// the new cursor resumes with the first item that was dropped.
+    pub fn truncate_to_size(&mut self, max_size: usize, cursor: impl Fn(&T) -> C) {
+        let mut size = 0;
+        let mut len = 0;
+        for item in &self.data {
+            let mut counter = ByteCounter(0);
+            // Serializing into a counter only fails if the item can't be serialized at all, in
+            // which case the whole response will fail to serialize anyway.
+            let _ = serde_json::to_writer(&mut counter, item);
+            // Count the separator between items as well.
+            size += counter.0 + 1;
+            if len > 0 && size > max_size {
+                break;
+            }
+            len += 1;
+        }
+
+        if len < self.data.len() {
+            self.data.truncate(len);
+            self.next_cursor = self.data.last().map(cursor);
+            self.has_next_page = true;
+            self.truncated = true;
+        }
+    }
+}
+
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
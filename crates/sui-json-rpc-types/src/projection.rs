@@ -0,0 +1,110 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::{SuiMoveStruct, SuiMoveValue};
+
+/// The fields of parsed Move values that a client asked to be shown, as paths with the names of
+/// nested fields separated by `.`. A field is shown whole if it is named without any of its
+/// nested fields, and fields that no path names are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldProjection {
+    fields: BTreeMap<String, FieldProjection>,
+}
+
+impl FieldProjection {
+    pub fn new<S: AsRef<str>>(paths: &[S]) -> Self {
+        let mut projection = Self::default();
+        for path in paths {
+            let mut node = &mut projection;
+            for field in path.as_ref().split('.').filter(|f| !f.is_empty()) {
+                node = node.fields.entry(field.to_string()).or_default();
+            }
+        }
+        projection
+    }
+
+    /// Whether this projection shows a value whole.
+    fn is_whole(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn project_json(&self, value: Value) -> Value {
+        if self.is_whole() {
+            return value;
+        }
+        match value {
+            Value::Object(mut object) => Value::Object(
+                self.fields
+                    .iter()
+                    .filter_map(|(field, projection)| {
+                        let value = object.remove(field)?;
+                        Some((field.clone(), projection.project_json(value)))
+                    })
+                    .collect(),
+            ),
+            Value::Array(values) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| self.project_json(value))
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+
+    pub fn project_move_struct(&self, move_struct: SuiMoveStruct) -> SuiMoveStruct {
+        if self.is_whole() {
+            return move_struct;
+        }
+        match move_struct {
+            SuiMoveStruct::WithTypes { type_, fields } => SuiMoveStruct::WithTypes {
+                type_,
+                fields: self.project_move_fields(fields),
+            },
+            SuiMoveStruct::WithFields(fields) => {
+                SuiMoveStruct::WithFields(self.project_move_fields(fields))
+            }
+            SuiMoveStruct::Runtime(values) => SuiMoveStruct::Runtime(
+                values
+                    .into_iter()
+                    .map(|value| self.project_move_value(value))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn project_move_fields(
+        &self,
+        mut fields: BTreeMap<String, SuiMoveValue>,
+    ) -> BTreeMap<String, SuiMoveValue> {
+        self.fields
+            .iter()
+            .filter_map(|(field, projection)| {
+                let value = fields.remove(field)?;
+                Some((field.clone(), projection.project_move_value(value)))
+            })
+            .collect()
+    }
+
+    fn project_move_value(&self, value: SuiMoveValue) -> SuiMoveValue {
+        match value {
+            SuiMoveValue::Struct(move_struct) => {
+                SuiMoveValue::Struct(self.project_move_struct(move_struct))
+            }
+            SuiMoveValue::Vector(values) => SuiMoveValue::Vector(
+                values
+                    .into_iter()
+                    .map(|value| self.project_move_value(value))
+                    .collect(),
+            ),
+            SuiMoveValue::Option(value) => {
+                SuiMoveValue::Option(Box::new(value.map(|value| self.project_move_value(value))))
+            }
+            value => value,
+        }
+    }
+}
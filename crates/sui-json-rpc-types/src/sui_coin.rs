@@ -32,6 +32,10 @@ pub struct Balance {
     #[schemars(with = "HashMap<BigInt<u64>, BigInt<u128>>")]
     #[serde_as(as = "HashMap<BigInt<u64>, BigInt<u128>>")]
     pub locked_balance: HashMap<EpochId, u128>,
+    /// Value of the total balance in fiat, if the node serving the request is configured with a
+    /// price for the coin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fiat_value: Option<FiatValue>,
 }
 
 impl Balance {
@@ -41,10 +45,19 @@ impl Balance {
             coin_object_count: 0,
             total_balance: 0,
             locked_balance: HashMap::new(),
+            fiat_value: None,
         }
     }
 }
 
+/// An amount of a fiat currency, as a decimal string, e.g. `{"currency": "USD", "amount": "1.50"}`.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FiatValue {
+    pub currency: String,
+    pub amount: String,
+}
+
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, JsonSchema, PartialEq, Eq, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -8,20 +8,28 @@ use move_core_types::identifier::Identifier;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 
 use sui_types::base_types::AuthorityName;
-use sui_types::base_types::{EpochId, ObjectID};
+use sui_types::base_types::{EpochId, ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::committee::Committee;
+use sui_types::digests::TransactionDigest;
+use sui_types::gas::GasCostSummary;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::sui_serde::BigInt;
+use sui_types::sui_serde::SequenceNumber as AsSequenceNumber;
 use sui_types::sui_system_state::sui_system_state_summary::SuiValidatorSummary;
 
-use crate::Page;
+use crate::{DisplayFieldsResponse, ObjectChange, Page};
 
 pub type EpochPage = Page<EpochInfo, BigInt<u64>>;
 pub type EpochMetricsPage = Page<EpochMetrics, BigInt<u64>>;
+pub type EventSchemaPage = Page<EventSchema, EventSchemaCursor>;
+pub type GasPriceStatsPage = Page<GasPriceStats, BigInt<u64>>;
+pub type NftPage = Page<Nft, ObjectID>;
+pub type ObjectTransactionPage = Page<ObjectTransaction, TransactionDigest>;
 
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -153,6 +161,116 @@ pub struct NetworkMetrics {
     #[schemars(with = "BigInt<u64>")]
     #[serde_as(as = "BigInt<u64>")]
     pub current_checkpoint: u64,
+    /// TPS over the most recently ingested checkpoints
+    pub rolling_tps: f64,
+    /// Checkpoints produced per second, over the most recently ingested checkpoints
+    pub checkpoints_per_second: f64,
+    /// Transaction blocks per checkpoint, over the most recently ingested checkpoints
+    pub average_checkpoint_size: f64,
+    /// When the current epoch started
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch_start_timestamp_ms: u64,
+    /// How long epochs are meant to last
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch_duration_ms: u64,
+    /// Fraction of the current epoch's duration that has passed as of the latest checkpoint,
+    /// between 0 and 1
+    pub epoch_progress: f64,
+}
+
+/// The gas prices that programmable transactions paid in a range of checkpoints of one epoch.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GasPriceStats {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub first_checkpoint: CheckpointSequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub last_checkpoint: CheckpointSequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    /// Timestamp of the last checkpoint
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub timestamp_ms: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub reference_gas_price: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub transaction_count: u64,
+    /// The gas price statistics are not set if there were no programmable transactions
+    pub mean_gas_price: Option<f64>,
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub median_gas_price: Option<u64>,
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub min_gas_price: Option<u64>,
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_gas_price: Option<u64>,
+}
+
+/// The APYs of a validator's staking pool over a range of epochs.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorApyHistory {
+    pub address: SuiAddress,
+    /// In ascending order of epoch, skipping the epochs the validator wasn't active in
+    pub apys: Vec<EpochApy>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochApy {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    pub apy: f64,
+}
+
+/// What happened to a validator in an epoch it was active in.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorEpochHistory {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    /// Commission rate in the epoch, in basis points
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub commission_rate: u64,
+    /// Commission rate from the next epoch, unless the validator left at the end of this one
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub next_commission_rate: Option<u64>,
+    /// Total SUI staked with the validator in the epoch
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub stake_inflow: u64,
+    /// Total SUI, principal and rewards, unstaked from the validator in the epoch
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub stake_outflow: u64,
+    /// Validators reporting this one under the tallying rule at the end of the epoch
+    pub reported_by: Vec<SuiAddress>,
+    /// Whether the reports reached a quorum of voting power, slashing the validator's rewards for
+    /// the epoch
+    pub slashed: bool,
+    /// Number of epochs the validator had been below the minimum stake, if it was in its
+    /// low-stake grace period in the epoch
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub at_risk_epochs: Option<u64>,
 }
 
 #[serde_as]
@@ -183,6 +301,50 @@ pub struct MoveFunctionName {
     pub function: Identifier,
 }
 
+/// The calls made to a package's functions in a range of epochs, and the gas paid by the
+/// transactions making them, whoever sent them and whether or not they succeeded.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageMetrics {
+    pub package_id: ObjectID,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub start_epoch: EpochId,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub end_epoch: EpochId,
+    /// Calls to any of the package's functions
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub call_count: u64,
+    /// Transactions calling any of the package's functions
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub transaction_count: u64,
+    /// Gas paid by the transactions calling any of the package's functions
+    pub gas_used: GasCostSummary,
+    /// The functions called in the range, most called first
+    pub functions: Vec<FunctionCallMetrics>,
+}
+
+/// The calls made to a Move function, and the gas paid by the transactions making them. A
+/// transaction calling several functions counts towards the gas of each.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCallMetrics {
+    pub module: String,
+    pub function: String,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub call_count: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub transaction_count: u64,
+    pub gas_used: GasCostSummary,
+}
+
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -194,3 +356,430 @@ pub struct AddressMetrics {
     pub cumulative_active_addresses: u64,
     pub daily_active_addresses: u64,
 }
+
+/// Which side of a timestamp to look for the checkpoint closest to it on.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum TimestampDirection {
+    /// The last checkpoint at or before the timestamp.
+    #[default]
+    Before,
+    /// The first checkpoint at or after the timestamp.
+    After,
+}
+
+/// What to rank accounts by, in leaderboards of the most active accounts.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountRanking {
+    TransactionsSent,
+    TransactionsReceived,
+    SuiSent,
+    SuiReceived,
+}
+
+/// An address' activity, summed over a range of days.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountActivity {
+    pub address: SuiAddress,
+    /// Transactions sent by the address
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub transactions_sent: u64,
+    /// Transactions that left objects owned by the address
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub transactions_received: u64,
+    /// SUI (in MIST) that the address' balance decreased by, including gas
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub sui_sent: u64,
+    /// SUI (in MIST) that the address' balance increased by
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub sui_received: u64,
+}
+
+/// The number of addresses that sent transactions on a day (UTC), and in the week ending with it.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveAddressCounts {
+    /// The start of the day
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub day_start_ms: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub daily_active_addresses: u64,
+    /// Addresses that sent transactions in the 7 days ending with this one
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub weekly_active_addresses: u64,
+}
+
+/// The layout of an event type's fields, as resolved against a package version that emitted
+/// events of that type, and the range of checkpoints in which such events were seen.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSchema {
+    /// The event's struct tag
+    pub event_type: String,
+    /// The package (version) that emitted events of this type
+    pub package_id: ObjectID,
+    /// Annotated layout of the event's fields
+    pub layout: Value,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub first_seen_checkpoint: CheckpointSequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub last_seen_checkpoint: CheckpointSequenceNumber,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSchemaCursor {
+    pub event_type: String,
+    pub package_id: ObjectID,
+}
+
+/// A change to whether (or by whom) an object can be accessed directly.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ObjectTransitionKind {
+    Created,
+    /// The object changed owners
+    Transferred,
+    /// The object was wrapped in another object
+    Wrapped,
+    /// The object was taken out of the object it was wrapped in
+    Unwrapped,
+    Deleted,
+    /// The object was taken out of the object it was wrapped in and deleted, in one transaction
+    UnwrappedThenDeleted,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectTransition {
+    pub kind: ObjectTransitionKind,
+    /// The object's version after the transaction
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub version: SequenceNumber,
+    pub transaction_digest: TransactionDigest,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+}
+
+/// The history of an object's existence: when it was created, wrapped and unwrapped, and
+/// deleted, and how many times it changed owners.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectLifecycle {
+    pub object_id: ObjectID,
+    pub created: Option<ObjectTransition>,
+    /// Every time the object was wrapped or unwrapped, in order
+    pub wrapping: Vec<ObjectTransition>,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub transfer_count: u64,
+    pub deleted: Option<ObjectTransition>,
+}
+
+/// How a transaction changed an object.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ObjectTransactionKind {
+    Created,
+    Mutated,
+    /// The object was wrapped in another object
+    Wrapped,
+    /// The object was taken out of the object it was wrapped in
+    Unwrapped,
+    Deleted,
+    /// The object was taken out of the object it was wrapped in and deleted, in one transaction
+    UnwrappedThenDeleted,
+}
+
+/// A transaction that changed an object, and the version it left the object at.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectTransaction {
+    pub digest: TransactionDigest,
+    pub kind: ObjectTransactionKind,
+    /// The object's version after the transaction
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub version: SequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub timestamp_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionInputObjectKind {
+    MovePackage,
+    ImmOrOwnedMoveObject,
+    MutableSharedMoveObject,
+    ReadOnlySharedMoveObject,
+    /// An object that was sent to an object the transaction accessed, and could be received
+    Receiving,
+}
+
+/// An object that was an input to a transaction, at the version the transaction read it at.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInputObject {
+    pub object_id: ObjectID,
+    /// The version the transaction read, which for shared objects is the version assigned by
+    /// consensus. Not set for packages, as they are identified by their ID.
+    #[schemars(with = "Option<AsSequenceNumber>")]
+    #[serde_as(as = "Option<AsSequenceNumber>")]
+    pub version: Option<SequenceNumber>,
+    /// Not set for packages, or for shared objects that had been deleted by the time the
+    /// transaction was executed.
+    pub digest: Option<ObjectDigest>,
+    pub kind: TransactionInputObjectKind,
+}
+
+/// A change that a transaction made to an object, with what is needed to render the object: its
+/// Display fields, and for coins, the symbol and decimals of the coin type.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionObject {
+    pub change: ObjectChange,
+    /// The Display fields of the object as the transaction left it. Not set for packages, for
+    /// objects that were deleted or wrapped, or whose type has no Display, or for object
+    /// versions that are no longer held.
+    pub display: Option<DisplayFieldsResponse>,
+    /// For coins, the symbol of the coin type, from its metadata
+    pub coin_symbol: Option<String>,
+    /// For coins, the number of decimals of the coin type, from its metadata
+    pub coin_decimals: Option<u8>,
+}
+
+/// An object whose type has a Display, with its Display fields rendered.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Nft {
+    pub object_id: ObjectID,
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub version: SequenceNumber,
+    pub digest: ObjectDigest,
+    pub object_type: String,
+    pub display: DisplayFieldsResponse,
+}
+
+/// A change to who holds an object whose type has a Display.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NftTransfer {
+    pub kind: ObjectTransitionKind,
+    /// The object's version after the transaction
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub version: SequenceNumber,
+    /// The address or object that held the object before the transaction. Not set for objects
+    /// that were created or unwrapped, or that were shared or immutable.
+    pub from: Option<SuiAddress>,
+    /// The address or object that holds the object after the transaction. Not set for objects
+    /// that were wrapped or deleted, or that are shared or immutable.
+    pub to: Option<SuiAddress>,
+    pub sender: SuiAddress,
+    pub transaction_digest: TransactionDigest,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub timestamp_ms: u64,
+}
+
+/// The changes to who held an object whose type has a Display, from the time its type had a
+/// Display, in order of version.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NftTransferHistory {
+    pub object_id: ObjectID,
+    /// The object's type, as of its last transfer if it is no longer held, e.g. because it was
+    /// deleted or wrapped
+    pub object_type: String,
+    /// The object's current Display fields, unless it is no longer held
+    pub display: Option<DisplayFieldsResponse>,
+    pub data: Vec<NftTransfer>,
+    /// The version of the last transfer in `data`
+    pub next_cursor: Option<BigInt<u64>>,
+    pub has_next_page: bool,
+}
+
+/// An owned object version that transactions were rejected for trying to use, because other
+/// transactions had already locked it.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EquivocatedObject {
+    pub object_id: ObjectID,
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub version: SequenceNumber,
+    /// The transactions that were rejected
+    pub rejected: Vec<TransactionDigest>,
+    /// The transactions that had already locked the object version
+    pub conflicting: Vec<TransactionDigest>,
+    /// When the last of the rejections was observed
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub last_seen_ms: u64,
+}
+
+/// The owned object versions that a sender tried to use in more than one transaction, most
+/// recently observed first. Equivocation usually points at a signer reusing stale object
+/// references, e.g. by submitting transactions concurrently from several processes.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EquivocationReport {
+    pub sender: SuiAddress,
+    pub objects: Vec<EquivocatedObject>,
+}
+
+/// A name (and optionally a category, e.g. "exchange" or "bridge") that the operator of an RPC
+/// server has associated with an address, for display in explorers and wallets. Labels are not
+/// part of chain state, and are only returned by servers that maintain a label registry.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressLabel {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+/// Labels of the addresses referenced by a response, keyed by address.
+pub type AddressLabels = BTreeMap<SuiAddress, AddressLabel>;
+
+/// The change that a transaction made to an address' balance of one coin type.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementBalanceChange {
+    pub coin_type: String,
+    /// The change to the balance, negative for outflows, including gas
+    #[schemars(with = "String")]
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount: i128,
+    /// The running balance, after the transaction
+    #[schemars(with = "String")]
+    #[serde_as(as = "DisplayFromStr")]
+    pub balance: i128,
+}
+
+/// A transaction that changed an address' balances, as a line of its account statement.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStatementEntry {
+    pub digest: TransactionDigest,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub timestamp_ms: u64,
+    /// One change per coin type whose balance the transaction changed
+    pub changes: Vec<StatementBalanceChange>,
+}
+
+/// Where an account statement continues from: the last transaction that the previous page
+/// covered, and the running balances after it.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStatementCursor {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub tx_sequence_number: u64,
+    #[schemars(with = "BTreeMap<String, String>")]
+    #[serde_as(as = "BTreeMap<_, DisplayFromStr>")]
+    pub balances: BTreeMap<String, i128>,
+}
+
+/// A page of an address' transactions in execution order, with the running balance of each coin
+/// type they changed, e.g. for reconciling deposits and withdrawals against an exchange's books.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStatement {
+    pub address: SuiAddress,
+    pub data: Vec<AccountStatementEntry>,
+    /// Balances before the first entry, keyed by coin type
+    #[schemars(with = "BTreeMap<String, String>")]
+    #[serde_as(as = "BTreeMap<_, DisplayFromStr>")]
+    pub opening_balances: BTreeMap<String, i128>,
+    /// Balances after the last entry, keyed by coin type
+    #[schemars(with = "BTreeMap<String, String>")]
+    #[serde_as(as = "BTreeMap<_, DisplayFromStr>")]
+    pub closing_balances: BTreeMap<String, i128>,
+    /// Continues the statement after this page. Set on the last page as well, so that the
+    /// statement can be continued as new transactions come in.
+    pub next_cursor: Option<AccountStatementCursor>,
+    pub has_next_page: bool,
+    /// Only set on the last page: whether the closing balances match the balances of the coins
+    /// the address currently owns. A mismatch means the statement did not start from the
+    /// address' first transaction, or was continued with balances it did not return.
+    pub reconciled: Option<bool>,
+}
+
+/// The length of the buckets of time that a balance history is divided into. Buckets are aligned
+/// to the Unix epoch, so days start at midnight UTC, and weeks on Thursdays.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BalanceHistoryInterval {
+    Hour,
+    Day,
+    Week,
+}
+
+impl BalanceHistoryInterval {
+    pub fn as_millis(&self) -> u64 {
+        const MS_PER_HOUR: u64 = 60 * 60 * 1000;
+        match self {
+            Self::Hour => MS_PER_HOUR,
+            Self::Day => 24 * MS_PER_HOUR,
+            Self::Week => 7 * 24 * MS_PER_HOUR,
+        }
+    }
+}
+
+/// An address' balance of a coin type at the end of a bucket of time.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceSnapshot {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub bucket_start_ms: u64,
+    /// The balance after the last transaction in the bucket
+    #[schemars(with = "String")]
+    #[serde_as(as = "DisplayFromStr")]
+    pub balance: i128,
+    /// The net change to the balance over the bucket, negative for outflows, including gas
+    #[schemars(with = "String")]
+    #[serde_as(as = "DisplayFromStr")]
+    pub change: i128,
+}
@@ -1,12 +1,21 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::{BTreeMap, HashMap};
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sui_types::base_types::{AuthorityName, EpochId, ObjectID, SuiAddress};
 use sui_types::committee::{Committee, StakeUnit};
 use sui_types::sui_serde::BigInt;
+use sui_types::sui_system_state::sui_system_state_summary::{
+    SuiSystemStateSummary, SuiValidatorSummary,
+};
+
+use crate::{FiatValue, Page};
+
+pub type DelegatedStakePage = Page<DelegatedStake, ObjectID>;
 
 /// RPC representation of the [Committee] type.
 #[serde_as]
@@ -40,6 +49,50 @@ pub struct DelegatedStake {
     pub stakes: Vec<Stake>,
 }
 
+/// The stake held by a StakedSui object, looked up by the object's ID.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "status", content = "details")]
+pub enum DelegatedStakeResponse {
+    /// The stake, with the staking pool it's in. Its status is `Unstaked` if it has been
+    /// withdrawn.
+    Found(DelegatedStake),
+    /// The object no longer exists (e.g. because the stake has been withdrawn), and what it held
+    /// isn't known.
+    Unstaked(ObjectID),
+    /// There is no StakedSui object with this ID.
+    NotFound(ObjectID),
+}
+
+impl DelegatedStakeResponse {
+    /// A response for each of `ids`, in the same order: the stake with the ID if it's one of
+    /// `stakes`, or `missing(id)` if it isn't.
+    pub fn for_ids(
+        ids: &[ObjectID],
+        stakes: Vec<DelegatedStake>,
+        missing: impl Fn(ObjectID) -> Self,
+    ) -> Vec<Self> {
+        let mut found = HashMap::new();
+        for delegated_stake in stakes {
+            for stake in delegated_stake.stakes {
+                found.insert(
+                    stake.staked_sui_id,
+                    DelegatedStake {
+                        validator_address: delegated_stake.validator_address,
+                        staking_pool: delegated_stake.staking_pool,
+                        stakes: vec![stake],
+                    },
+                );
+            }
+        }
+        ids.iter()
+            .map(|id| match found.get(id) {
+                Some(stake) => Self::Found(stake.clone()),
+                None => missing(*id),
+            })
+            .collect()
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(tag = "status")]
@@ -71,6 +124,46 @@ pub struct Stake {
     pub principal: u64,
     #[serde(flatten)]
     pub status: StakeStatus,
+    /// Value of the principal and estimated reward in fiat, if the node serving the request is
+    /// configured with a price for SUI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fiat_value: Option<FiatValue>,
+}
+
+/// What a StakedSui object has earned so far, and is projected to earn if it's held until a later
+/// epoch.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeRewardEstimate {
+    /// ID of the StakedSui receipt object.
+    pub staked_sui_id: ObjectID,
+    /// Validator's Address.
+    pub validator_address: SuiAddress,
+    /// Staking pool object id.
+    pub staking_pool: ObjectID,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub principal: u64,
+    /// The epoch the estimate is made in.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    /// The reward earned up to `epoch`, which is 0 until the stake is active.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub accrued_reward: u64,
+    /// The epoch the reward is projected to.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub target_epoch: EpochId,
+    /// The validator's APY that the reward is projected at.
+    pub apy: f64,
+    /// The reward projected to have been earned if the stake is held until `target_epoch`,
+    /// including `accrued_reward`.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub projected_reward: u64,
 }
 
 #[serde_as]
@@ -88,3 +181,98 @@ pub struct ValidatorApy {
     pub address: SuiAddress,
     pub apy: f64,
 }
+
+/// How the active validator set changed at the start of an epoch, relative to the previous epoch.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorSetChanges {
+    /// The epoch the changes took effect in.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    /// Validators that joined the active set.
+    pub joined: Vec<SuiValidatorSummary>,
+    /// Addresses of the validators that left the active set.
+    pub departed: Vec<SuiAddress>,
+    /// Validators that stayed in the active set, and whose stake changed.
+    pub stake_changes: Vec<ValidatorStakeChange>,
+    /// Validators that stayed in the active set, and whose commission rate changed.
+    pub commission_changes: Vec<ValidatorCommissionChange>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorStakeChange {
+    pub validator_address: SuiAddress,
+    /// SUI in the validator's staking pool in the previous epoch.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub previous_stake: u64,
+    /// SUI in the validator's staking pool in this epoch.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub stake: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorCommissionChange {
+    pub validator_address: SuiAddress,
+    /// Commission rate in the previous epoch, in basis points.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub previous_rate: u64,
+    /// Commission rate in this epoch, in basis points.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub rate: u64,
+}
+
+impl ValidatorSetChanges {
+    /// The changes from the active validators of `previous` to those of `current`, in the order
+    /// of the validators' addresses.
+    pub fn between(previous: &SuiSystemStateSummary, current: &SuiSystemStateSummary) -> Self {
+        let by_address = |state: &SuiSystemStateSummary| {
+            state
+                .active_validators
+                .iter()
+                .map(|v| (v.sui_address, v.clone()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let previous_validators = by_address(previous);
+        let mut current_validators = by_address(current);
+
+        let mut changes = Self {
+            epoch: current.epoch,
+            joined: vec![],
+            departed: vec![],
+            stake_changes: vec![],
+            commission_changes: vec![],
+        };
+        for (address, before) in previous_validators {
+            let Some(after) = current_validators.remove(&address) else {
+                changes.departed.push(address);
+                continue;
+            };
+            if before.staking_pool_sui_balance != after.staking_pool_sui_balance {
+                changes.stake_changes.push(ValidatorStakeChange {
+                    validator_address: address,
+                    previous_stake: before.staking_pool_sui_balance,
+                    stake: after.staking_pool_sui_balance,
+                });
+            }
+            if before.commission_rate != after.commission_rate {
+                changes.commission_changes.push(ValidatorCommissionChange {
+                    validator_address: address,
+                    previous_rate: before.commission_rate,
+                    rate: after.commission_rate,
+                });
+            }
+        }
+        changes.joined = current_validators.into_values().collect();
+        changes
+    }
+}
@@ -26,6 +26,7 @@ use sui_types::base_types::{
     ObjectDigest, ObjectID, ObjectInfo, ObjectRef, ObjectType, SequenceNumber, SuiAddress,
     TransactionDigest,
 };
+use sui_types::dynamic_field::{DynamicFieldName, DynamicFieldType};
 use sui_types::error::{ExecutionError, SuiObjectResponseError, UserInputError, UserInputResult};
 use sui_types::gas_coin::GasCoin;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
@@ -35,7 +36,7 @@ use sui_types::sui_serde::BigInt;
 use sui_types::sui_serde::SequenceNumber as AsSequenceNumber;
 use sui_types::sui_serde::SuiStructTag;
 
-use crate::{Page, SuiMoveStruct, SuiMoveValue};
+use crate::{AddressLabels, FieldProjection, Page, SuiMoveStruct, SuiMoveValue};
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
 pub struct SuiObjectResponse {
@@ -165,6 +166,15 @@ pub struct DisplayFieldsResponse {
     pub error: Option<SuiObjectResponseError>,
 }
 
+/// A problem with the template of a Display field, that would stop it from rendering.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayFieldError {
+    /// The key of the field whose template has the problem.
+    pub key: String,
+    pub error: String,
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq)]
 #[serde(rename_all = "camelCase", rename = "ObjectData")]
@@ -207,6 +217,10 @@ pub struct SuiObjectData {
     /// Move object content or package content in BCS, default to be None unless SuiObjectDataOptions.showBcs is set to true
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bcs: Option<SuiRawData>,
+    /// Label of the object's owner, default to be None unless SuiObjectDataOptions.showLabels is set
+    /// to true and the server maintains a label registry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<AddressLabels>,
 }
 
 impl SuiObjectData {
@@ -339,6 +353,14 @@ pub struct SuiObjectDataOptions {
     pub show_bcs: bool,
     /// Whether to show the storage rebate of the object. Default to be False
     pub show_storage_rebate: bool,
+    /// Whether to show the label associated with the object's owner, if the server maintains a
+    /// label registry. Default to be False
+    pub show_labels: bool,
+    /// Paths of the fields of the object's parsed content to show, with the names of nested
+    /// fields separated by `.`, e.g. `["balance", "metadata.name"]`. Only applies if
+    /// `show_content` is set. Default to show all fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<String>>,
 }
 
 impl SuiObjectDataOptions {
@@ -356,6 +378,8 @@ impl SuiObjectDataOptions {
             show_display: false,
             show_content: false,
             show_storage_rebate: true,
+            show_labels: false,
+            fields: None,
         }
     }
 
@@ -369,6 +393,8 @@ impl SuiObjectDataOptions {
             show_display: false,
             show_content: true,
             show_storage_rebate: true,
+            show_labels: false,
+            fields: None,
         }
     }
 
@@ -402,6 +428,16 @@ impl SuiObjectDataOptions {
         self
     }
 
+    pub fn with_labels(mut self) -> Self {
+        self.show_labels = true;
+        self
+    }
+
+    pub fn with_fields(mut self, fields: Vec<String>) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
     pub fn is_not_in_object_info(&self) -> bool {
         self.show_bcs || self.show_content || self.show_display || self.show_storage_rebate
     }
@@ -457,6 +493,7 @@ impl TryFrom<(ObjectInfo, SuiObjectDataOptions)> for SuiObjectResponse {
             display: None,
             content: None,
             bcs: None,
+            labels: None,
         }))
     }
 }
@@ -486,6 +523,7 @@ impl
             show_content,
             show_bcs,
             show_storage_rebate,
+            fields,
             ..
         } = options;
 
@@ -520,7 +558,14 @@ impl
                     let layout = layout.ok_or_else(|| {
                         anyhow!("Layout is required to convert Move object to json")
                     })?;
-                    SuiParsedData::try_from_object(m, layout)?
+                    match (SuiParsedData::try_from_object(m, layout)?, &fields) {
+                        (SuiParsedData::MoveObject(mut object), Some(fields)) => {
+                            object.fields =
+                                FieldProjection::new(fields).project_move_struct(object.fields);
+                            SuiParsedData::MoveObject(object)
+                        }
+                        (data, _) => data,
+                    }
                 }
                 Data::Package(p) => SuiParsedData::try_from_package(p)?,
             };
@@ -548,6 +593,7 @@ impl
             content,
             bcs,
             display: None,
+            labels: None,
         })
     }
 }
@@ -1128,6 +1174,47 @@ pub struct SuiGetPastObjectRequest {
     pub version: SequenceNumber,
 }
 
+/// A step along a path from an object to a value nested in its Move fields and dynamic fields.
+/// Names and indices are resolved as a Move field or vector element if there is one, and as the
+/// name of a dynamic field of the value reached so far otherwise. Names are looked up as
+/// `0x1::string::String` and `0x1::ascii::String` dynamic field names, and indices as `u64` ones,
+/// so dynamic fields whose names have other types must be named in full.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum DynamicFieldPathSegment {
+    Index(u64),
+    Name(String),
+    DynamicField(DynamicFieldName),
+}
+
+/// The dynamic fields under an object, and the dynamic fields under those in turn, down to a
+/// given depth.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamicFieldTree {
+    /// The ID that the dynamic fields hang off.
+    pub parent_object_id: ObjectID,
+    pub fields: Vec<DynamicFieldTreeNode>,
+    /// Cursor for `suix_getDynamicFields` on `parentObjectId`, to list the fields after `fields`.
+    pub next_cursor: Option<ObjectID>,
+    /// Whether there may be fields that are not in `fields`, because the tree was cut off at the
+    /// requested depth or node limit.
+    pub has_next_page: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamicFieldTreeNode {
+    pub name: DynamicFieldName,
+    pub type_: DynamicFieldType,
+    pub object_type: String,
+    pub object_id: ObjectID,
+    /// The value of the field, or the object it holds if it is a dynamic object field.
+    pub value: Option<SuiMoveValue>,
+    /// The dynamic fields under the value, if it has a UID of its own.
+    pub dynamic_fields: Option<DynamicFieldTree>,
+}
+
 #[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum SuiObjectDataFilter {
@@ -4,7 +4,7 @@
 use crate::balance_changes::BalanceChange;
 use crate::object_changes::ObjectChange;
 use crate::sui_transaction::GenericSignature::Signature;
-use crate::{Filter, Page, SuiEvent, SuiObjectRef};
+use crate::{AddressLabels, FiatValue, FieldProjection, Filter, Page, SuiEvent, SuiObjectRef};
 use enum_dispatch::enum_dispatch;
 use fastcrypto::encoding::Base64;
 use move_binary_format::access::ModuleAccess;
@@ -107,6 +107,14 @@ pub struct SuiTransactionBlockResponseOptions {
     pub show_balance_changes: bool,
     /// Whether to show raw transaction effects. Default to be False
     pub show_raw_effects: bool,
+    /// Whether to show labels associated with the sender and recipients of the transaction, if
+    /// the server maintains a label registry. Default to be False
+    pub show_labels: bool,
+    /// Paths of the fields of each event's parsed JSON to show, with the names of nested fields
+    /// separated by `.`, e.g. `["amount", "pool.id"]`. Only applies if `show_events` is set.
+    /// Default to show all fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<String>>,
 }
 
 impl SuiTransactionBlockResponseOptions {
@@ -125,6 +133,8 @@ impl SuiTransactionBlockResponseOptions {
             // This field is added for graphql execution. We keep it false here
             // so current users of `full_content` will not get raw effects unexpectedly.
             show_raw_effects: false,
+            show_labels: false,
+            fields: None,
         }
     }
 
@@ -163,6 +173,16 @@ impl SuiTransactionBlockResponseOptions {
         self
     }
 
+    pub fn with_labels(mut self) -> Self {
+        self.show_labels = true;
+        self
+    }
+
+    pub fn with_fields(mut self, fields: Vec<String>) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
     /// default to return `WaitForEffectsCert` unless some options require
     /// local execution
     pub fn default_execution_request_type(&self) -> ExecuteTransactionRequestType {
@@ -193,6 +213,20 @@ impl SuiTransactionBlockResponseOptions {
     pub fn only_digest(&self) -> bool {
         self == &Self::default()
     }
+
+    /// `events`, leaving out the fields of their parsed JSON that `fields` doesn't name.
+    pub fn project_events(
+        &self,
+        mut events: SuiTransactionBlockEvents,
+    ) -> SuiTransactionBlockEvents {
+        if let Some(fields) = &self.fields {
+            let projection = FieldProjection::new(fields);
+            for event in &mut events.data {
+                event.parsed_json = projection.project_json(std::mem::take(&mut event.parsed_json));
+            }
+        }
+        events
+    }
 }
 
 #[serde_as]
@@ -233,6 +267,11 @@ pub struct SuiTransactionBlockResponse {
     pub errors: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub raw_effects: Vec<u8>,
+    /// Labels of the transaction's sender and recipients, returned if
+    /// `TransactionBlockResponseOptions.showLabels` is set and the server maintains a label
+    /// registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<AddressLabels>,
 }
 
 impl SuiTransactionBlockResponse {
@@ -963,6 +1002,33 @@ pub struct DryRunTransactionBlockResponse {
     pub object_changes: Vec<ObjectChange>,
     pub balance_changes: Vec<BalanceChange>,
     pub input: SuiTransactionBlockData,
+    /// Value of the net gas cost in fiat, if the node serving the request is configured with a
+    /// price for SUI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_fiat_value: Option<FiatValue>,
+}
+
+/// A likely mistake in a transaction block, found without executing it.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", rename = "TransactionBlockLint")]
+pub struct SuiTransactionBlockLint {
+    pub kind: SuiTransactionBlockLintKind,
+    /// Index of the command the lint is about.
+    pub command: u16,
+    pub message: String,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", rename = "TransactionBlockLintKind")]
+pub enum SuiTransactionBlockLintKind {
+    /// A value created by a command is never used, so the transaction fails unless the value can
+    /// be dropped.
+    UnusedResult,
+    /// Objects are transferred to the sender of the transaction, which already owns them.
+    TransferToSelf,
+    /// The gas coin is passed to a function whose signature the linter doesn't know, and fails
+    /// the transaction if the function takes it by value.
+    GasCoinByValue,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -2167,6 +2233,32 @@ pub enum TransactionFilter {
     TransactionKind(String),
     /// Query transactions of any given kind in the input.
     TransactionKindIn(Vec<String>),
+    /// Query by the class of transaction, as classified by the indexer when it ingests the
+    /// transaction. Only supported by the indexer.
+    TransactionClass(TransactionClass),
+}
+
+/// What a transaction does, or how it was signed and paid for, for the purposes of filtering
+/// transactions. A transaction may belong to several classes, e.g. a sponsored transfer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionClass {
+    /// Transfers objects, without calling Move functions or publishing packages.
+    Transfer,
+    /// Publishes a package.
+    Publish,
+    /// Upgrades a package.
+    Upgrade,
+    /// Adds or withdraws stake.
+    Stake,
+    /// Places an order on, or swaps through, DeepBook.
+    DeepbookTrade,
+    /// Has its gas paid for by an address other than its sender.
+    Sponsored,
+    /// Signed by a multisig.
+    Multisig,
+    /// Signed with zkLogin.
+    ZkLogin,
 }
 
 impl Filter<EffectsWithInput> for TransactionFilter {
@@ -2210,6 +2302,71 @@ impl Filter<EffectsWithInput> for TransactionFilter {
             // these filters are not supported, rpc will reject these filters on subscription
             TransactionFilter::Checkpoint(_) => false,
             TransactionFilter::FromOrToAddress { addr: _ } => false,
+            TransactionFilter::TransactionClass(_) => false,
+        }
+    }
+}
+
+/// Filter for `suix_subscribeTransactionEffects`.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+pub enum TransactionEffectsFilter {
+    /// Transactions that call a function of the package, publish it, or upgrade it.
+    Package(ObjectID),
+}
+
+impl Filter<EffectsWithInput> for TransactionEffectsFilter {
+    fn matches(&self, item: &EffectsWithInput) -> bool {
+        let _scope = monitored_scope("TransactionEffectsFilter::matches");
+        match self {
+            TransactionEffectsFilter::Package(package) => {
+                let calls_package = item
+                    .input
+                    .move_calls()
+                    .into_iter()
+                    .any(|(p, _, _)| p == package);
+                let upgrades_package = match item.input.kind() {
+                    TransactionKind::ProgrammableTransaction(pt) => pt.commands.iter().any(
+                        |command| matches!(command, Command::Upgrade(_, _, p, _) if p == package),
+                    ),
+                    _ => false,
+                };
+                let publishes_package = item
+                    .effects
+                    .created()
+                    .iter()
+                    .any(|oref| &oref.reference.object_id == package);
+                calls_package || upgrades_package || publishes_package
+            }
         }
     }
 }
+
+/// The effects of a transaction, and the checkpoint it was included in, which can be passed as
+/// the cursor of a new subscription to resume after this checkpoint.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointedTransactionEffects {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+    pub effects: SuiTransactionBlockEffects,
+}
+
+/// A pre-signed transaction that a node will submit once the chain reaches a given checkpoint or
+/// time.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename = "ScheduledTransactionBlock", rename_all = "camelCase")]
+pub struct SuiScheduledTransactionBlock {
+    pub digest: TransactionDigest,
+    pub sender: SuiAddress,
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub not_before_checkpoint: Option<CheckpointSequenceNumber>,
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub not_before_timestamp_ms: Option<u64>,
+    /// How many times submitting the transaction has failed so far.
+    pub submit_attempts: u32,
+}
@@ -14,9 +14,15 @@ use sui_types::base_types::{ObjectDigest, SequenceNumber};
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::gas_coin::GasCoin;
 use sui_types::object::{MoveObject, Owner};
+use sui_types::sui_system_state::sui_system_state_summary::{
+    SuiSystemStateSummary, SuiValidatorSummary,
+};
 use sui_types::{parse_sui_struct_tag, MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
 
-use crate::{ObjectChange, SuiMoveStruct, SuiMoveValue};
+use crate::{
+    FieldProjection, ObjectChange, Page, SuiMoveStruct, SuiMoveValue, ValidatorCommissionChange,
+    ValidatorSetChanges, ValidatorStakeChange,
+};
 
 #[test]
 fn test_move_value_to_sui_coin() {
@@ -196,3 +202,132 @@ fn test_type_tag_struct_tag_devnet_inc_222() {
         assert_eq!(oc, deser);
     }
 }
+
+#[test]
+fn test_page_truncate_to_size() {
+    let page = |data: Vec<String>| Page::<String, usize> {
+        next_cursor: Some(data.len() - 1),
+        data,
+        has_next_page: false,
+        truncated: false,
+    };
+    // Each item takes 13 bytes: 10 characters, two quotes and a separating comma.
+    let items = vec!["a".repeat(10), "b".repeat(10), "c".repeat(10)];
+
+    let mut fits = page(items.clone());
+    fits.truncate_to_size(39, |s| s.len());
+    assert_eq!(fits, page(items.clone()));
+    assert!(!json!(fits).as_object().unwrap().contains_key("truncated"));
+
+    let mut truncated = page(items.clone());
+    truncated.truncate_to_size(30, |s| s.len());
+    assert_eq!(truncated.data, items[..2]);
+    assert_eq!(truncated.next_cursor, Some(10));
+    assert!(truncated.has_next_page);
+    assert!(truncated.truncated);
+    assert_eq!(json!(truncated)["truncated"], json!(true));
+
+    // The first item is kept even if it exceeds the limit on its own.
+    let mut first = page(items.clone());
+    first.truncate_to_size(1, |s| s.len());
+    assert_eq!(first.data, items[..1]);
+}
+
+#[test]
+fn test_field_projection() {
+    let projection = FieldProjection::new(&["balance", "metadata.name", "missing"]);
+
+    let value = json!({
+        "balance": "100",
+        "id": { "id": "0x1" },
+        "metadata": [{ "name": "a", "decimals": 9 }, { "name": "b" }],
+    });
+    assert_eq!(
+        projection.project_json(value),
+        json!({
+            "balance": "100",
+            "metadata": [{ "name": "a" }, { "name": "b" }],
+        })
+    );
+
+    let move_struct = SuiMoveStruct::WithFields(
+        [
+            ("balance".to_string(), SuiMoveValue::Number(100)),
+            ("id".to_string(), SuiMoveValue::UID { id: ObjectID::ZERO }),
+            (
+                "metadata".to_string(),
+                SuiMoveValue::Struct(SuiMoveStruct::WithFields(
+                    [
+                        ("name".to_string(), SuiMoveValue::String("a".to_string())),
+                        ("decimals".to_string(), SuiMoveValue::Number(9)),
+                    ]
+                    .into(),
+                )),
+            ),
+        ]
+        .into(),
+    );
+    assert_eq!(
+        projection.project_move_struct(move_struct).to_json_value(),
+        json!({ "balance": 100, "metadata": { "name": "a" } })
+    );
+
+    // Values are shown whole if no fields are named.
+    let value = json!({ "balance": "100" });
+    assert_eq!(
+        FieldProjection::new::<&str>(&[]).project_json(value.clone()),
+        value
+    );
+}
+
+#[test]
+fn test_validator_set_changes() {
+    let validator = |address: u8, stake: u64, commission_rate: u64| SuiValidatorSummary {
+        sui_address: SuiAddress::from(ObjectID::from_single_byte(address)),
+        staking_pool_sui_balance: stake,
+        commission_rate,
+        ..Default::default()
+    };
+    let previous = SuiSystemStateSummary {
+        epoch: 1,
+        active_validators: vec![validator(1, 100, 200), validator(2, 100, 200)],
+        ..Default::default()
+    };
+    let current = SuiSystemStateSummary {
+        epoch: 2,
+        active_validators: vec![validator(3, 100, 200), validator(1, 150, 300)],
+        ..Default::default()
+    };
+
+    let changes = ValidatorSetChanges::between(&previous, &current);
+    assert_eq!(changes.epoch, 2);
+    assert_eq!(
+        changes
+            .joined
+            .iter()
+            .map(|v| v.sui_address)
+            .collect::<Vec<_>>(),
+        vec![validator(3, 0, 0).sui_address]
+    );
+    assert_eq!(changes.departed, vec![validator(2, 0, 0).sui_address]);
+    assert_eq!(
+        changes.stake_changes,
+        vec![ValidatorStakeChange {
+            validator_address: validator(1, 0, 0).sui_address,
+            previous_stake: 100,
+            stake: 150,
+        }]
+    );
+    assert_eq!(
+        changes.commission_changes,
+        vec![ValidatorCommissionChange {
+            validator_address: validator(1, 0, 0).sui_address,
+            previous_rate: 200,
+            rate: 300,
+        }]
+    );
+
+    let unchanged = ValidatorSetChanges::between(&current, &current);
+    assert!(unchanged.joined.is_empty() && unchanged.departed.is_empty());
+    assert!(unchanged.stake_changes.is_empty() && unchanged.commission_changes.is_empty());
+}
@@ -0,0 +1,449 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Admission control for RPC requests, so that a single heavy client can't take up all of a
+//! server's capacity (and the database connections behind it). Clients are identified by the API
+//! key they send in the [`API_KEY_HEADER`], and requests without one share a single anonymous key.
+//! Requests over HTTP and over WebSocket (with the key sent when the connection is opened), and
+//! subscriptions over SSE, are all admitted the same way, so switching transports doesn't get a
+//! client around its limit. Subscriptions only hold their capacity while they are set up (and,
+//! over SSE, while they replay past events), as they are limited in number separately.
+//!
+//! Each key can have at most its configured number of requests in flight, and the server as a
+//! whole at most `max-concurrent-requests`. Requests over either limit wait in a queue per key, and
+//! are rejected if that queue is full. When capacity frees up, it goes to the waiting key that has
+//! been served least relative to its weight (weighted fair queueing), so keys with more weight get
+//! a proportionally larger share of a saturated server.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, IntCounterVec,
+    IntGaugeVec, Registry,
+};
+use serde::{Deserialize, Serialize};
+use sui_json_rpc_api::API_KEY_HEADER;
+use tokio::sync::oneshot;
+
+/// Metrics label for requests from keys that are not configured.
+const DEFAULT_KEY_LABEL: &str = "default";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct AdmissionConfig {
+    /// Requests served concurrently across all keys.
+    pub max_concurrent_requests: usize,
+    /// Requests that can wait for each key, beyond which its requests are rejected.
+    pub max_queued_requests_per_key: usize,
+    /// Limits of keys that are not listed in `keys`, including the anonymous key.
+    pub default_key: KeyLimit,
+    #[serde(default)]
+    pub keys: Vec<KeyConfig>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct KeyLimit {
+    pub max_concurrent_requests: usize,
+    /// Share of a saturated server that the key gets, relative to other keys.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct KeyConfig {
+    pub api_key: String,
+    /// Name to report the key's metrics under, so the key itself isn't exposed.
+    pub name: String,
+    pub max_concurrent_requests: usize,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl AdmissionConfig {
+    fn key(&self, api_key: &str) -> Option<&KeyConfig> {
+        self.keys.iter().find(|k| k.api_key == api_key)
+    }
+
+    fn limit(&self, api_key: &str) -> KeyLimit {
+        self.key(api_key).map_or(self.default_key, |k| KeyLimit {
+            max_concurrent_requests: k.max_concurrent_requests,
+            weight: k.weight,
+        })
+    }
+
+    fn label(&self, api_key: &str) -> &str {
+        self.key(api_key).map_or(DEFAULT_KEY_LABEL, |k| &k.name)
+    }
+}
+
+/// Admission state of a server. Clones share the same state, so a handle kept by the operator's
+/// tooling can update the config of a running server. Requests are admitted without limits until
+/// a config is set.
+#[derive(Clone, Debug, Default)]
+pub struct AdmissionControl {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    config: Option<Arc<AdmissionConfig>>,
+    in_flight: usize,
+    /// Virtual time of the fair queue: the start tag of the request admitted last.
+    clock: f64,
+    /// State of keys with requests in flight or waiting.
+    keys: HashMap<String, KeyState>,
+}
+
+#[derive(Debug, Default)]
+struct KeyState {
+    in_flight: usize,
+    /// Virtual time at which the key's last admitted request finishes being served.
+    finish: f64,
+    waiters: VecDeque<oneshot::Sender<AdmissionPermit>>,
+}
+
+/// A request's claim on capacity, released when it is dropped.
+#[derive(Debug)]
+pub struct AdmissionPermit {
+    held: Option<(AdmissionControl, String)>,
+}
+
+/// Why a request was not admitted.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Rejected {
+    /// Metrics label of the request's key.
+    pub label: String,
+}
+
+impl AdmissionControl {
+    /// Replace the config, or remove all limits if `config` is `None`. Requests in flight keep
+    /// their capacity, and waiting requests are admitted if the new limits allow it.
+    pub fn set_config(&self, config: Option<AdmissionConfig>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.config = config.map(Arc::new);
+        self.dispatch(&mut inner);
+    }
+
+    pub fn config(&self) -> Option<AdmissionConfig> {
+        self.inner.lock().unwrap().config.as_deref().cloned()
+    }
+
+    /// Wait for capacity to serve a request from `api_key`, or reject it straight away if too
+    /// many of the key's requests are already waiting.
+    pub async fn acquire(&self, api_key: Option<&str>) -> Result<AdmissionPermit, Rejected> {
+        let api_key = api_key.unwrap_or_default();
+        let permit = {
+            let mut guard = self.inner.lock().unwrap();
+            let inner = &mut *guard;
+            let Some(config) = inner.config.clone() else {
+                return Ok(self.admit(inner, api_key, 1));
+            };
+
+            let limit = config.limit(api_key);
+            let has_room = inner.in_flight < config.max_concurrent_requests;
+            let key = inner.keys.entry(api_key.to_owned()).or_default();
+            key.waiters.retain(|waiter| !waiter.is_closed());
+            if has_room && key.waiters.is_empty() && key.in_flight < limit.max_concurrent_requests {
+                return Ok(self.admit(inner, api_key, limit.weight));
+            }
+
+            if key.waiters.len() >= config.max_queued_requests_per_key {
+                let rejected = Rejected {
+                    label: config.label(api_key).to_owned(),
+                };
+                Self::forget_if_idle(inner, api_key);
+                return Err(rejected);
+            }
+
+            // A key that starts waiting is tagged from now, so it can't claim capacity for the
+            // time it was idle.
+            if key.waiters.is_empty() {
+                key.finish = key.finish.max(inner.clock);
+            }
+            let (tx, rx) = oneshot::channel();
+            key.waiters.push_back(tx);
+            rx
+        };
+
+        // Waiters are only dropped without being sent a permit along with the admission state.
+        permit.await.map_err(|_| Rejected {
+            label: DEFAULT_KEY_LABEL.to_owned(),
+        })
+    }
+
+    /// The label that metrics about requests from `api_key` are reported under.
+    pub fn label(&self, api_key: Option<&str>) -> String {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .config
+            .as_ref()
+            .map_or(DEFAULT_KEY_LABEL, |c| c.label(api_key.unwrap_or_default()))
+            .to_owned()
+    }
+
+    fn admit(&self, inner: &mut Inner, api_key: &str, weight: u32) -> AdmissionPermit {
+        let clock = inner.clock;
+        let key = inner.keys.entry(api_key.to_owned()).or_default();
+        let start = key.finish.max(clock);
+        key.finish = start + 1.0 / f64::from(weight.max(1));
+        key.in_flight += 1;
+        inner.in_flight += 1;
+        inner.clock = start;
+
+        AdmissionPermit {
+            held: Some((self.clone(), api_key.to_owned())),
+        }
+    }
+
+    fn release(&self, api_key: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.in_flight -= 1;
+        if let Some(key) = inner.keys.get_mut(api_key) {
+            key.in_flight -= 1;
+        }
+        self.dispatch(&mut inner);
+        Self::forget_if_idle(&mut inner, api_key);
+    }
+
+    /// Admit waiting requests while there is capacity, picking the key with the earliest finish
+    /// tag each time.
+    fn dispatch(&self, inner: &mut Inner) {
+        loop {
+            let config = inner.config.clone();
+            if let Some(config) = &config {
+                if inner.in_flight >= config.max_concurrent_requests {
+                    return;
+                }
+            }
+
+            let next = inner
+                .keys
+                .iter()
+                .filter(|(api_key, key)| {
+                    !key.waiters.is_empty()
+                        && config.as_ref().map_or(true, |c| {
+                            key.in_flight < c.limit(api_key).max_concurrent_requests
+                        })
+                })
+                .map(|(api_key, key)| {
+                    let weight = config.as_ref().map_or(1, |c| c.limit(api_key).weight);
+                    let finish = key.finish + 1.0 / f64::from(weight.max(1));
+                    (finish, api_key.clone(), weight)
+                })
+                .min_by(|(a, a_key, _), (b, b_key, _)| a.total_cmp(b).then(a_key.cmp(b_key)));
+            let Some((_, api_key, weight)) = next else {
+                return;
+            };
+
+            let waiter = inner
+                .keys
+                .get_mut(&api_key)
+                .and_then(|key| key.waiters.pop_front())
+                .expect("Key has waiters");
+            let permit = self.admit(inner, &api_key, weight);
+            if let Err(mut permit) = waiter.send(permit) {
+                // The request stopped waiting, so give its capacity back without re-entering
+                // `release`, which would deadlock on the lock held here.
+                permit.held = None;
+                inner.in_flight -= 1;
+                if let Some(key) = inner.keys.get_mut(&api_key) {
+                    key.in_flight -= 1;
+                }
+                Self::forget_if_idle(inner, &api_key);
+            }
+        }
+    }
+
+    fn forget_if_idle(inner: &mut Inner, api_key: &str) {
+        if inner
+            .keys
+            .get(api_key)
+            .is_some_and(|key| key.in_flight == 0 && key.waiters.is_empty())
+        {
+            inner.keys.remove(api_key);
+        }
+    }
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        if let Some((control, api_key)) = self.held.take() {
+            control.release(&api_key);
+        }
+    }
+}
+
+/// Admission control for a server, with its metrics.
+#[derive(Clone, Debug)]
+pub(crate) struct Admission {
+    control: AdmissionControl,
+    admitted: IntCounterVec,
+    rejected: IntCounterVec,
+    waiting: IntGaugeVec,
+}
+
+impl Admission {
+    pub fn new(control: AdmissionControl, registry: &Registry) -> Self {
+        Self {
+            control,
+            admitted: register_int_counter_vec_with_registry!(
+                "rpc_admission_admitted_requests",
+                "Number of requests admitted, by API key name",
+                &["key"],
+                registry,
+            )
+            .unwrap(),
+            rejected: register_int_counter_vec_with_registry!(
+                "rpc_admission_rejected_requests",
+                "Number of requests rejected because too many were queued, by API key name",
+                &["key"],
+                registry,
+            )
+            .unwrap(),
+            waiting: register_int_gauge_vec_with_registry!(
+                "rpc_admission_waiting_requests",
+                "Number of requests waiting to be admitted, by API key name",
+                &["key"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Admit a request that sent `api_key`, returning a message for the client if it is
+    /// rejected.
+    pub async fn admit(&self, api_key: Option<&str>) -> Result<AdmissionPermit, String> {
+        let label = self.control.label(api_key);
+        let waiting = self.waiting.with_label_values(&[&label]);
+        waiting.inc();
+        let result = self.control.acquire(api_key).await;
+        waiting.dec();
+
+        match result {
+            Ok(permit) => {
+                self.admitted.with_label_values(&[&label]).inc();
+                Ok(permit)
+            }
+            Err(Rejected { label }) => {
+                self.rejected.with_label_values(&[&label]).inc();
+                Err(format!(
+                    "Too many concurrent requests for this {API_KEY_HEADER}, retry later"
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    fn config(max_concurrent_requests: usize, keys: Vec<KeyConfig>) -> AdmissionConfig {
+        AdmissionConfig {
+            max_concurrent_requests,
+            max_queued_requests_per_key: 2,
+            default_key: KeyLimit {
+                max_concurrent_requests: 2,
+                weight: 1,
+            },
+            keys,
+        }
+    }
+
+    fn key(api_key: &str, weight: u32) -> KeyConfig {
+        KeyConfig {
+            api_key: api_key.to_owned(),
+            name: api_key.to_uppercase(),
+            max_concurrent_requests: 10,
+            weight,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_per_key_limit_and_queue() {
+        let control = AdmissionControl::default();
+        control.set_config(Some(config(10, vec![])));
+
+        let _a = control.acquire(Some("a")).await.unwrap();
+        let b = control.acquire(Some("a")).await.unwrap();
+
+        // The key is at its limit, so its requests wait, and are rejected once its queue is
+        // full. Other keys are unaffected.
+        let mut queued = Box::pin(control.acquire(Some("a")));
+        assert!(queued.as_mut().now_or_never().is_none());
+        let mut also_queued = Box::pin(control.acquire(Some("a")));
+        assert!(also_queued.as_mut().now_or_never().is_none());
+        assert_eq!(
+            control
+                .acquire(Some("a"))
+                .now_or_never()
+                .unwrap()
+                .unwrap_err(),
+            Rejected {
+                label: DEFAULT_KEY_LABEL.to_owned()
+            }
+        );
+        let _other = control.acquire(Some("b")).now_or_never().unwrap().unwrap();
+
+        // Releasing a permit admits the request that waited longest.
+        drop(b);
+        let _c = queued.now_or_never().unwrap().unwrap();
+        assert!(also_queued.as_mut().now_or_never().is_none());
+
+        // Removing the limits admits everything that is waiting.
+        control.set_config(None);
+        also_queued.now_or_never().unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_weighted_fair_queueing() {
+        let control = AdmissionControl::default();
+        control.set_config(Some(AdmissionConfig {
+            max_queued_requests_per_key: 10,
+            ..config(1, vec![key("heavy", 1), key("paying", 4)])
+        }));
+
+        // Saturate the server, then queue requests from both keys, heavy first.
+        let mut permit = control.acquire(None).await.unwrap();
+        let mut queued: Vec<_> = ["heavy"; 5]
+            .into_iter()
+            .chain(["paying"; 5])
+            .map(|api_key| (api_key, Box::pin(control.acquire(Some(api_key)))))
+            .collect();
+        for (_, acquire) in &mut queued {
+            assert!(acquire.as_mut().now_or_never().is_none());
+        }
+
+        // Free up capacity one request at a time, and record which key gets it.
+        let mut order = vec![];
+        while !queued.is_empty() {
+            drop(permit);
+            let admitted: Vec<_> = queued
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, (_, acquire))| Some((i, acquire.as_mut().now_or_never()?)))
+                .collect();
+            let [(i, result)] = <[_; 1]>::try_from(admitted).unwrap();
+            permit = result.unwrap();
+            order.push(queued.remove(i).0);
+        }
+
+        // Paying gets four times heavy's share while both are waiting, ties going to the key
+        // that sorts first.
+        assert_eq!(
+            order,
+            [
+                "paying", "paying", "paying", "heavy", "paying", "paying", "heavy", "heavy",
+                "heavy", "heavy"
+            ]
+        );
+    }
+}
@@ -123,6 +123,19 @@ pub trait StateRead: Send + Sync {
         Option<ObjectID>,
     )>;
 
+    #[allow(clippy::type_complexity)]
+    async fn dry_exec_transaction_bundle(
+        &self,
+        transactions: Vec<(TransactionData, TransactionDigest)>,
+    ) -> StateReadResult<
+        Vec<(
+            DryRunTransactionBlockResponse,
+            BTreeMap<ObjectID, (ObjectRef, Object, WriteKind)>,
+            TransactionEffects,
+            Option<ObjectID>,
+        )>,
+    >;
+
     async fn dev_inspect_transaction_block(
         &self,
         sender: SuiAddress,
@@ -359,6 +372,21 @@ impl StateRead for AuthorityState {
             .await?)
     }
 
+    #[allow(clippy::type_complexity)]
+    async fn dry_exec_transaction_bundle(
+        &self,
+        transactions: Vec<(TransactionData, TransactionDigest)>,
+    ) -> StateReadResult<
+        Vec<(
+            DryRunTransactionBlockResponse,
+            BTreeMap<ObjectID, (ObjectRef, Object, WriteKind)>,
+            TransactionEffects,
+            Option<ObjectID>,
+        )>,
+    > {
+        Ok(self.dry_exec_transaction_bundle(transactions).await?)
+    }
+
     async fn dev_inspect_transaction_block(
         &self,
         sender: SuiAddress,
@@ -4,6 +4,7 @@
 use std::sync::Arc;
 
 use axum::extract::Json;
+use axum::extract::Query;
 use axum::extract::State;
 use futures::StreamExt;
 use hyper::HeaderMap;
@@ -13,15 +14,41 @@ use jsonrpsee::core::server::helpers::MethodSink;
 use jsonrpsee::core::server::rpc_module::MethodKind;
 use jsonrpsee::server::logger::{self, TransportProtocol};
 use jsonrpsee::server::RandomIntegerIdProvider;
-use jsonrpsee::types::error::{ErrorCode, BATCHES_NOT_SUPPORTED_CODE, BATCHES_NOT_SUPPORTED_MSG};
+use jsonrpsee::types::error::{
+    ErrorCode, BATCHES_NOT_SUPPORTED_CODE, BATCHES_NOT_SUPPORTED_MSG, INVALID_REQUEST_CODE,
+    METHOD_NOT_FOUND_CODE,
+};
 use jsonrpsee::types::{ErrorObject, Id, InvalidRequest, Params, Request};
 use jsonrpsee::{core::server::rpc_module::Methods, server::logger::Logger};
 use serde_json::value::RawValue;
+use sui_types::digests::TransactionDigest;
 
+use crate::admission::Admission;
+use crate::http_caching::{not_modified, CacheHeaders, HttpCaching};
+use crate::method_aliases::{Aliases, MethodAliases};
+use crate::method_toggles::MethodToggles;
+use crate::query_cache::{CachedQueries, Lookup};
+use crate::read_your_writes::ReadYourWrites;
+use crate::redaction::Redaction;
+use crate::request_context::RequestContext;
 use crate::routing_layer::RpcRouter;
-use sui_json_rpc_api::CLIENT_TARGET_API_VERSION_HEADER;
+use crate::staleness::Staleness;
+use crate::tenancy::{Tenancy, Tenant};
+use crate::versioning::ApiVersioning;
+use sui_json_rpc_api::{
+    API_KEY_HEADER, CLIENT_TARGET_API_VERSION_HEADER, DATA_LAG_HEADER, MAX_RESPONSE_SIZE,
+    MIN_CHECKPOINT_HEADER, REQUEST_MIN_CHECKPOINT_HEADER, TRANSIENT_ERROR_CODE,
+};
 
-pub const MAX_RESPONSE_SIZE: u32 = 2 << 30;
+const EXECUTE_TRANSACTION_BLOCK_METHOD: &str = "sui_executeTransactionBlock";
+
+/// The request headers that cacheable responses vary by: the API version targeted, the API key
+/// (through redaction and tenancy), and the checkpoint that the data read must be as of.
+const VARY_HEADERS: &str = "client-target-api-version, x-api-key, min-checkpoint";
+
+fn max_response_size() -> u32 {
+    u32::try_from(*MAX_RESPONSE_SIZE).unwrap_or(u32::MAX)
+}
 
 #[derive(Clone, Debug)]
 pub struct JsonRpcService<L> {
@@ -32,15 +59,51 @@ pub struct JsonRpcService<L> {
     /// Registered server methods.
     methods: Methods,
     rpc_router: RpcRouter,
+    versioning: Arc<ApiVersioning>,
+    read_your_writes: Option<ReadYourWrites>,
+    method_toggles: MethodToggles,
+    aliases: Aliases,
+    admission: Admission,
+    staleness: Staleness,
+    query_cache: Option<CachedQueries>,
+    redaction: Redaction,
+    tenancy: Tenancy,
+    http_caching: HttpCaching,
+    sse_clients: sse::SseClients,
 }
 
 impl<L> JsonRpcService<L> {
-    pub fn new(methods: Methods, rpc_router: RpcRouter, logger: L) -> Self {
+    pub(crate) fn new(
+        methods: Methods,
+        rpc_router: RpcRouter,
+        versioning: ApiVersioning,
+        read_your_writes: Option<ReadYourWrites>,
+        method_toggles: MethodToggles,
+        aliases: Aliases,
+        admission: Admission,
+        staleness: Staleness,
+        query_cache: Option<CachedQueries>,
+        redaction: Redaction,
+        tenancy: Tenancy,
+        http_caching: HttpCaching,
+        logger: L,
+    ) -> Self {
         Self {
             methods,
             rpc_router,
+            versioning: Arc::new(versioning),
+            read_your_writes,
+            method_toggles,
+            aliases,
+            admission,
+            staleness,
+            query_cache,
+            redaction,
+            tenancy,
+            http_caching,
             logger,
             id_provider: Arc::new(RandomIntegerIdProvider),
+            sse_clients: Default::default(),
         }
     }
 }
@@ -51,7 +114,7 @@ impl<L: Logger> JsonRpcService<L> {
             logger: &self.logger,
             methods: &self.methods,
             rpc_router: &self.rpc_router,
-            max_response_body_size: MAX_RESPONSE_SIZE,
+            max_response_body_size: max_response_size(),
             request_start: self.logger.on_request(TransportProtocol::Http),
         }
     }
@@ -64,13 +127,59 @@ impl<L: Logger> JsonRpcService<L> {
         ws::WsCallData {
             logger: &self.logger,
             methods: &self.methods,
-            max_response_body_size: MAX_RESPONSE_SIZE,
+            max_response_body_size: max_response_size(),
             request_start: self.logger.on_request(TransportProtocol::Http),
             bounded_subscriptions,
             id_provider: &*self.id_provider,
             sink,
         }
     }
+
+    /// The tenant that made a request with `headers`, if any, or a message for the client if the
+    /// request is rejected.
+    fn tenant(&self, headers: &HeaderMap) -> Result<Option<Arc<Tenant>>, String> {
+        let api_key = headers.get(API_KEY_HEADER).and_then(|h| h.to_str().ok());
+        self.tenancy.tenant(api_key)
+    }
+
+    /// Point `request` at the method it calls, if it calls a method by an alias, returning the
+    /// alias.
+    fn resolve_alias(&self, request: &mut Request) -> Option<String> {
+        let target = self.aliases.resolve(&request.method)?;
+        let alias = std::mem::replace(&mut request.method, target.into());
+        Some(alias.into_owned())
+    }
+
+    /// An error response for `request`, if it calls a method that has been disabled.
+    fn reject_disabled(&self, request: &Request) -> Option<MethodResponse> {
+        if !self.method_toggles.is_disabled(&request.method) {
+            return None;
+        }
+
+        let message = MethodToggles::disabled_message(&request.method);
+        Some(MethodResponse::error(
+            request.id.clone().into_owned(),
+            ErrorObject::owned(METHOD_NOT_FOUND_CODE, message, None::<()>),
+        ))
+    }
+
+    /// Warn the client that the data it reads may be stale, in `headers`, if it has fallen far
+    /// enough behind.
+    fn warn_stale(&self, headers: &mut HeaderMap) {
+        if let Some(lag_ms) = self.staleness.warning() {
+            headers.insert(DATA_LAG_HEADER, hyper::header::HeaderValue::from(lag_ms));
+        }
+    }
+
+    /// An error response for `request`, if it calls a method that isn't served while this
+    /// server's data is as far behind as it currently is.
+    fn reject_stale(&self, request: &Request) -> Option<MethodResponse> {
+        let message = self.staleness.reject(&request.method)?;
+        Some(MethodResponse::error(
+            request.id.clone().into_owned(),
+            ErrorObject::owned(TRANSIENT_ERROR_CODE, message, None::<()>),
+        ))
+    }
 }
 
 /// Create a response body.
@@ -102,30 +211,281 @@ pub async fn json_rpc_handler<L: Logger>(
     headers: HeaderMap,
     Json(raw_request): Json<Box<RawValue>>,
 ) -> impl axum::response::IntoResponse {
+    handle_call(&service, &headers, raw_request.get()).await
+}
+
+/// A method call made with a `GET` request, so that HTTP caches can cache its response.
+#[derive(serde::Deserialize)]
+pub struct GetCall {
+    method: String,
+    /// JSON array of the method's params.
+    params: Option<String>,
+}
+
+pub async fn json_rpc_get_handler<L: Logger>(
+    State(service): State<JsonRpcService<L>>,
+    headers: HeaderMap,
+    Query(call): Query<GetCall>,
+) -> impl axum::response::IntoResponse {
+    const TEXT: &str = "text/plain; charset=utf-8";
+    let method = service
+        .aliases
+        .target(&call.method)
+        .unwrap_or_else(|| call.method.clone());
+    if !service.http_caching.is_cacheable(&method) {
+        let message = format!(
+            "{} can't be called with GET as its responses can't be cached, call it with POST",
+            call.method
+        );
+        let mut response = from_template(hyper::StatusCode::METHOD_NOT_ALLOWED, message, TEXT);
+        response.headers_mut().insert(
+            hyper::header::ALLOW,
+            hyper::header::HeaderValue::from_static("POST"),
+        );
+        return response;
+    }
+
+    let params: Vec<serde_json::Value> =
+        match serde_json::from_str(call.params.as_deref().unwrap_or("[]")) {
+            Ok(params) => params,
+            Err(e) => {
+                let message = format!("params must be a JSON array: {e}");
+                return from_template(hyper::StatusCode::BAD_REQUEST, message, TEXT);
+            }
+        };
+
+    let raw_request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": call.method,
+        "params": params,
+    });
+    handle_call(&service, &headers, &raw_request.to_string()).await
+}
+
+/// Respond to the JSON-RPC request `raw_request`, made over HTTP with `headers`.
+async fn handle_call<L: Logger>(
+    service: &JsonRpcService<L>,
+    headers: &HeaderMap,
+    raw_request: &str,
+) -> hyper::Response<hyper::Body> {
+    const TEXT: &str = "text/plain; charset=utf-8";
+    let api_key = headers.get(API_KEY_HEADER).and_then(|h| h.to_str().ok());
+    let tenant = match service.tenant(headers) {
+        Ok(tenant) => tenant,
+        Err(message) => return from_template(hyper::StatusCode::FORBIDDEN, message, TEXT),
+    };
+    let _permit = match service.admission.admit(api_key).await {
+        Ok(permit) => permit,
+        Err(message) => return from_template(hyper::StatusCode::TOO_MANY_REQUESTS, message, TEXT),
+    };
+
     // Get version from header.
     let api_version = headers
         .get(CLIENT_TARGET_API_VERSION_HEADER)
         .and_then(|h| h.to_str().ok());
-    let response = process_raw_request(&service, api_version, raw_request.get()).await;
+    let min_checkpoint = headers
+        .get(MIN_CHECKPOINT_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.parse().ok());
+    let (response, called) =
+        process_raw_request(service, api_version, min_checkpoint, raw_request).await;
+    let method = called.as_ref().map(|called| called.method.as_str());
+
+    // Wait for the executed transaction to be included in a checkpoint, to tell the client which
+    // checkpoint it must read from to observe its effects.
+    let token = match &service.read_your_writes {
+        Some(ryw)
+            if response.success
+                && method == Some(EXECUTE_TRANSACTION_BLOCK_METHOD)
+                && headers.contains_key(REQUEST_MIN_CHECKPOINT_HEADER) =>
+        {
+            match executed_digest(&response.result) {
+                Some(digest) => ryw.wait_for_transaction(digest).await,
+                None => None,
+            }
+        }
+        _ => None,
+    };
+
+    let success = response.success;
+    let mut body = service.redaction.redact(api_key, response.result);
+    if let Some(tenant) = &tenant {
+        body = tenant.isolate(body);
+    }
+    let caching = service.http_caching.headers(method, success, &body);
 
-    ok_response(response.result)
+    let mut response = ok_response(body);
+    service
+        .versioning
+        .add_headers(method, response.headers_mut());
+    if let Some(Called {
+        method,
+        alias: Some(alias),
+    }) = &called
+    {
+        let warning = MethodAliases::warning(alias, method);
+        if let Ok(value) = hyper::header::HeaderValue::from_str(&warning) {
+            let headers = response.headers_mut();
+            headers.insert(hyper::header::WARNING, value);
+            headers.insert(
+                hyper::header::HeaderName::from_static("deprecation"),
+                hyper::header::HeaderValue::from_static("true"),
+            );
+        }
+    }
+    if let Some(checkpoint) = token {
+        response.headers_mut().insert(
+            MIN_CHECKPOINT_HEADER,
+            hyper::header::HeaderValue::from(checkpoint),
+        );
+    }
+    service.warn_stale(response.headers_mut());
+    if let Some(CacheHeaders {
+        cache_control,
+        etag,
+    }) = caching
+    {
+        add_caching_headers(headers, &mut response, &cache_control, etag.as_deref());
+    }
+    response
 }
 
+/// Add caching headers to `response`, replacing it with `304 Not Modified` if the request's
+/// headers show that the client already has it.
+fn add_caching_headers(
+    request_headers: &HeaderMap,
+    response: &mut hyper::Response<hyper::Body>,
+    cache_control: &str,
+    etag: Option<&str>,
+) {
+    let headers = response.headers_mut();
+    if let Ok(value) = hyper::header::HeaderValue::from_str(cache_control) {
+        headers.insert(hyper::header::CACHE_CONTROL, value);
+    }
+
+    let Some(etag) = etag else {
+        return;
+    };
+    let Ok(value) = hyper::header::HeaderValue::from_str(etag) else {
+        return;
+    };
+    headers.insert(hyper::header::ETAG, value);
+    headers.insert(
+        hyper::header::VARY,
+        hyper::header::HeaderValue::from_static(VARY_HEADERS),
+    );
+
+    let unchanged = request_headers
+        .get(hyper::header::IF_NONE_MATCH)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| not_modified(h, etag));
+    if unchanged {
+        *response.status_mut() = hyper::StatusCode::NOT_MODIFIED;
+        *response.body_mut() = hyper::Body::empty();
+    }
+}
+
+/// The digest of the transaction executed by a successful call to execute a transaction block.
+fn executed_digest(response: &str) -> Option<TransactionDigest> {
+    #[derive(serde::Deserialize)]
+    struct Envelope {
+        result: Executed,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Executed {
+        digest: TransactionDigest,
+    }
+
+    serde_json::from_str::<Envelope>(response)
+        .ok()
+        .map(|e| e.result.digest)
+}
+
+/// The method that a request called, and the alias it called it by, if any.
+struct Called {
+    method: String,
+    alias: Option<String>,
+}
+
+/// Process a single request, returning the response and the method that was called, if the
+/// request could be parsed.
 async fn process_raw_request<L: Logger>(
     service: &JsonRpcService<L>,
     api_version: Option<&str>,
+    min_checkpoint: Option<u64>,
     raw_request: &str,
-) -> MethodResponse {
-    if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
-        process_request(request, api_version, service.call_data()).await
+) -> (MethodResponse, Option<Called>) {
+    if let Ok(mut request) = serde_json::from_str::<Request>(raw_request) {
+        let alias = service.resolve_alias(&mut request);
+        let called = Called {
+            method: request.method.to_string(),
+            alias,
+        };
+        let known = service.methods.method_with_name(&called.method).is_some();
+        service
+            .versioning
+            .record_request(&called.method, known, api_version);
+
+        if !service.versioning.is_supported(api_version) {
+            let message = service
+                .versioning
+                .unsupported_message(api_version.unwrap_or_default());
+            let response = MethodResponse::error(
+                request.id,
+                ErrorObject::owned(INVALID_REQUEST_CODE, message, None::<()>),
+            );
+            return (response, Some(called));
+        }
+
+        if let Some(response) = service.reject_disabled(&request) {
+            return (response, Some(called));
+        }
+
+        if let Some(response) = service.reject_stale(&request) {
+            return (response, Some(called));
+        }
+
+        if let (Some(min_checkpoint), Some(ryw)) = (min_checkpoint, &service.read_your_writes) {
+            if let Err(latest) = ryw.wait_for_checkpoint(min_checkpoint).await {
+                let message = format!(
+                    "Data as of checkpoint {min_checkpoint} is not yet available, latest \
+                     available checkpoint is {}",
+                    latest.map_or_else(|| "unknown".to_string(), |l| l.to_string()),
+                );
+                let response = MethodResponse::error(
+                    request.id,
+                    ErrorObject::owned(TRANSIENT_ERROR_CODE, message, None::<()>),
+                );
+                return (response, Some(called));
+            }
+        }
+
+        let slot = match &service.query_cache {
+            Some(cache) => match cache.lookup(&request, api_version, min_checkpoint).await {
+                Lookup::Hit(response) => return (response, Some(called)),
+                Lookup::Miss(slot) => Some((cache, slot)),
+                Lookup::Uncached => None,
+            },
+            None => None,
+        };
+
+        let response = process_request(request, api_version, service.call_data()).await;
+        if let Some((cache, slot)) = slot {
+            cache.insert(slot, &response).await;
+        }
+
+        (response, Some(called))
     } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
-        MethodResponse::error(
+        let response = MethodResponse::error(
             Id::Null,
             ErrorObject::borrowed(BATCHES_NOT_SUPPORTED_CODE, &BATCHES_NOT_SUPPORTED_MSG, None),
-        )
+        );
+        (response, None)
     } else {
         let (id, code) = prepare_error(raw_request);
-        MethodResponse::error(id, ErrorObject::from(code))
+        (MethodResponse::error(id, ErrorObject::from(code)), None)
     }
 }
 
@@ -143,7 +503,8 @@ async fn process_request<L: Logger>(
     } = call;
     let conn_id = 0; // unused
 
-    let params = Params::new(req.params.map(|params| params.get()));
+    let raw_params = req.params.map(|params| params.get());
+    let params = Params::new(raw_params);
     let name = rpc_router.route(&req.method, api_version);
     let id = req.id;
 
@@ -177,8 +538,17 @@ async fn process_request<L: Logger>(
 
                 let id = id.into_owned();
                 let params = params.into_owned();
+                let context = RequestContext::new(name, raw_params);
 
-                (callback)(id, params, conn_id, max_response_body_size as usize, None).await
+                context
+                    .scope((callback)(
+                        id,
+                        params,
+                        conn_id,
+                        max_response_body_size as usize,
+                        None,
+                    ))
+                    .await
             }
             MethodKind::Subscription(_) | MethodKind::Unsubscription(_) => {
                 logger.on_call(
@@ -221,13 +591,15 @@ pub(crate) struct CallData<'a, L: Logger> {
     request_start: L::Instant,
 }
 
+pub mod sse;
+
 pub mod ws {
     use axum::{
         extract::{
             ws::{Message, WebSocket},
             WebSocketUpgrade,
         },
-        response::Response,
+        response::{IntoResponse, Response},
     };
     use futures::channel::mpsc;
     use jsonrpsee::{
@@ -258,14 +630,38 @@ pub mod ws {
     pub async fn ws_json_rpc_upgrade<L: Logger>(
         ws: WebSocketUpgrade,
         State(service): State<JsonRpcService<L>>,
+        headers: HeaderMap,
     ) -> Response {
-        ws.on_upgrade(|ws| ws_json_rpc_handler(ws, service))
+        let tenant = match service.tenant(&headers) {
+            Ok(tenant) => tenant,
+            Err(message) => {
+                const TEXT: &str = "text/plain; charset=utf-8";
+                return from_template(hyper::StatusCode::FORBIDDEN, message, TEXT).into_response();
+            }
+        };
+        let api_key = headers
+            .get(API_KEY_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .map(str::to_owned);
+        let mut response = ws.on_upgrade({
+            let service = service.clone();
+            |ws| ws_json_rpc_handler(ws, service, api_key, tenant)
+        });
+        service.warn_stale(response.headers_mut());
+        response
     }
 
-    async fn ws_json_rpc_handler<L: Logger>(mut socket: WebSocket, service: JsonRpcService<L>) {
+    /// Serve the requests sent over `socket`, redacting for `api_key`, and isolating `tenant`, if
+    /// set, in everything sent back on it: responses and subscription notifications alike.
+    async fn ws_json_rpc_handler<L: Logger>(
+        mut socket: WebSocket,
+        service: JsonRpcService<L>,
+        api_key: Option<String>,
+        tenant: Option<Arc<Tenant>>,
+    ) {
         #[allow(clippy::disallowed_methods)]
         let (tx, mut rx) = mpsc::unbounded::<String>();
-        let sink = MethodSink::new_with_limit(tx, MAX_RESPONSE_SIZE, MAX_RESPONSE_SIZE);
+        let sink = MethodSink::new_with_limit(tx, max_response_size(), max_response_size());
         let bounded_subscriptions = BoundedSubscriptions::new(100);
 
         loop {
@@ -273,8 +669,14 @@ pub mod ws {
                 maybe_message = socket.recv() => {
                     if let Some(Ok(message)) = maybe_message {
                         if let Message::Text(msg) = message {
-                            let response =
-                                process_raw_request(&service, &msg, bounded_subscriptions.clone(), &sink).await;
+                            let response = process_raw_request(
+                                &service,
+                                api_key.as_deref(),
+                                &msg,
+                                bounded_subscriptions.clone(),
+                                &sink,
+                            )
+                            .await;
                             if let Some(response) = response {
                                 let _ = sink.send_raw(response.result);
                             }
@@ -284,6 +686,14 @@ pub mod ws {
                     }
                 },
                 Some(response) = rx.next() => {
+                    let response = service.redaction.redact(api_key.as_deref(), response);
+                    let response = match &tenant {
+                        Some(tenant) => match tenant.isolate_message(response) {
+                            Some(response) => response,
+                            None => continue,
+                        },
+                        None => response,
+                    };
                     if socket.send(Message::Text(response)).await.is_err() {
                         break;
                     }
@@ -294,11 +704,29 @@ pub mod ws {
 
     async fn process_raw_request<L: Logger>(
         service: &JsonRpcService<L>,
+        api_key: Option<&str>,
         raw_request: &str,
         bounded_subscriptions: BoundedSubscriptions,
         sink: &MethodSink,
     ) -> Option<MethodResponse> {
-        if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
+        if let Ok(mut request) = serde_json::from_str::<Request>(raw_request) {
+            service.resolve_alias(&mut request);
+            if let Some(response) = service.reject_disabled(&request) {
+                return Some(response);
+            }
+            if let Some(response) = service.reject_stale(&request) {
+                return Some(response);
+            }
+            // Subscriptions only hold their permit while they are set up.
+            let _permit = match service.admission.admit(api_key).await {
+                Ok(permit) => permit,
+                Err(message) => {
+                    return Some(MethodResponse::error(
+                        request.id,
+                        ErrorObject::owned(TRANSIENT_ERROR_CODE, message, None::<()>),
+                    ))
+                }
+            };
             process_request(request, service.ws_call_data(bounded_subscriptions, sink)).await
         } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
             Some(MethodResponse::error(
@@ -326,7 +754,8 @@ pub mod ws {
         } = call;
         let conn_id = 0; // unused
 
-        let params = Params::new(req.params.map(|params| params.get()));
+        let raw_params = req.params.map(|params| params.get());
+        let params = Params::new(raw_params);
         let name = &req.method;
         let id = req.id;
 
@@ -363,9 +792,17 @@ pub mod ws {
 
                     let id = id.into_owned();
                     let params = params.into_owned();
+                    let context = RequestContext::new(name, raw_params);
 
                     Some(
-                        (callback)(id, params, conn_id, max_response_body_size as usize, None)
+                        context
+                            .scope((callback)(
+                                id,
+                                params,
+                                conn_id,
+                                max_response_body_size as usize,
+                                None,
+                            ))
                             .await,
                     )
                 }
@@ -423,3 +860,343 @@ pub mod ws {
         response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use jsonrpsee::core::client::{ClientT, Subscription, SubscriptionClientT};
+    use jsonrpsee::http_client::{HeaderMap, HeaderValue};
+    use jsonrpsee::rpc_params;
+    use jsonrpsee::ws_client::WsClientBuilder;
+    use jsonrpsee::RpcModule;
+    use prometheus::Registry;
+    use serde_json::{json, Value};
+    use sui_open_rpc::{Module, RpcModuleDocBuilder};
+    use sui_types::base_types::ObjectID;
+    use tower::ServiceExt;
+
+    use crate::indexer_api::spawn_subscription;
+    use crate::redaction::RedactionConfig;
+    use crate::staleness::StalenessConfig;
+    use crate::tenancy::{TenancyConfig, TenantConfig};
+    use crate::{JsonRpcServerBuilder, SuiRpcModule};
+
+    use super::*;
+
+    fn event(package: &str, type_: &str, seq: u64) -> Value {
+        json!({
+            "id": {"txDigest": "11111111111111111111111111111111", "eventSeq": seq.to_string()},
+            "packageId": package,
+            "transactionModule": "m",
+            "sender": "0x1",
+            "type": type_,
+            "parsedJson": {},
+            "bcs": "AQID",
+        })
+    }
+
+    /// What the test subscription sends: an event of the tenant's package, an event of another
+    /// package, and transaction effects, which tenants can't be shown.
+    fn notifications() -> Vec<Value> {
+        vec![
+            event("0xabc", "0xabc::usdc::Minted", 0),
+            event("0xdef", "0xdef::nft::Minted", 1),
+            json!({
+                "transactionDigest": "11111111111111111111111111111111",
+                "status": {"status": "success"},
+            }),
+        ]
+    }
+
+    struct TestApi;
+
+    impl SuiRpcModule for TestApi {
+        fn rpc(self) -> RpcModule<Self> {
+            let mut module = RpcModule::new(self);
+            module
+                .register_method("test_events", |_, _| Ok(notifications()))
+                .unwrap();
+            module
+                .register_subscription(
+                    "test_subscribe",
+                    "test_subscribe",
+                    "test_unsubscribe",
+                    |_, sink, _| {
+                        spawn_subscription(sink, futures::stream::iter(notifications()), None);
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            module
+        }
+
+        fn rpc_doc_module() -> Module {
+            RpcModuleDocBuilder::default().build()
+        }
+    }
+
+    fn router(configure: impl FnOnce(&mut JsonRpcServerBuilder)) -> axum::Router {
+        let mut builder = JsonRpcServerBuilder::new("1.0.0", &Registry::new());
+        builder.register_module(TestApi).unwrap();
+        configure(&mut builder);
+        builder.to_router(None).unwrap()
+    }
+
+    fn tenant_router() -> axum::Router {
+        let tenancy = Tenancy::default();
+        tenancy
+            .set_config(TenancyConfig {
+                tenants: vec![TenantConfig {
+                    name: "usdc".to_string(),
+                    api_keys: ["usdc-key".to_string()].into(),
+                    packages: [ObjectID::from_str("0xabc").unwrap()].into(),
+                }],
+                reject_unknown_keys: true,
+            })
+            .unwrap();
+        router(|builder| builder.set_tenancy(tenancy))
+    }
+
+    /// A router hiding the BCS of the tenant's events from everyone but the holders of
+    /// "secret-key".
+    fn redaction_router() -> axum::Router {
+        let redaction = Redaction::default();
+        redaction
+            .set_config(RedactionConfig {
+                exempt_api_keys: ["secret-key".to_string()].into(),
+                hide_bcs_types: ["0xabc::usdc::Minted".to_string()].into(),
+                ..Default::default()
+            })
+            .unwrap();
+        router(|builder| builder.set_redaction(redaction))
+    }
+
+    /// A router whose data is a minute behind, which is too far behind to serve the test methods.
+    fn stale_router() -> axum::Router {
+        let staleness = Staleness::default();
+        staleness.set_config(StalenessConfig {
+            warn_after_ms: Some(1_000),
+            max_lag_ms: [
+                ("test_events".to_string(), 10_000),
+                ("test_subscribe".to_string(), 10_000),
+            ]
+            .into(),
+        });
+        staleness.record_lag(Duration::from_secs(60));
+        router(|builder| builder.set_staleness(staleness))
+    }
+
+    fn types(items: &[Value]) -> Vec<&str> {
+        items.iter().filter_map(|i| i["type"].as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_isolate_tenant_over_ws() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(tenant_router().into_make_service());
+        tokio::spawn(server);
+
+        // Connections without the key of a tenant are rejected.
+        assert!(WsClientBuilder::default().build(&url).await.is_err());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("usdc-key"));
+        let client = WsClientBuilder::default()
+            .set_headers(headers)
+            .build(&url)
+            .await
+            .unwrap();
+
+        let events: Vec<Value> = client.request("test_events", rpc_params![]).await.unwrap();
+        assert_eq!(types(&events), vec!["0xabc::usdc::Minted"]);
+
+        let mut subscription: Subscription<Value> = client
+            .subscribe("test_subscribe", rpc_params![], "test_unsubscribe")
+            .await
+            .unwrap();
+        let mut received = vec![];
+        while let Ok(Some(Ok(item))) =
+            tokio::time::timeout(Duration::from_secs(1), subscription.next()).await
+        {
+            received.push(item);
+        }
+        assert_eq!(received.len(), 1);
+        assert_eq!(types(&received), vec!["0xabc::usdc::Minted"]);
+    }
+
+    #[tokio::test]
+    async fn test_isolate_tenant_over_sse() {
+        let request = |api_key: &'static str| {
+            hyper::Request::get("/sse?method=test_subscribe")
+                .header(API_KEY_HEADER, api_key)
+                .body(hyper::Body::empty())
+                .unwrap()
+        };
+
+        let response = tenant_router().oneshot(request("other-key")).await.unwrap();
+        assert_eq!(response.status(), hyper::StatusCode::FORBIDDEN);
+
+        let response = tenant_router().oneshot(request("usdc-key")).await.unwrap();
+        assert_eq!(response.status(), hyper::StatusCode::OK);
+        let body = tokio::time::timeout(
+            Duration::from_secs(10),
+            hyper::body::to_bytes(response.into_body()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("0xabc::usdc::Minted"));
+        assert!(!body.contains("0xdef::nft::Minted"));
+        assert!(!body.contains("transactionDigest"));
+    }
+
+    #[tokio::test]
+    async fn test_redact_over_ws() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(redaction_router().into_make_service());
+        tokio::spawn(server);
+
+        let connect = |api_key: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(API_KEY_HEADER, HeaderValue::from_static(api_key));
+            WsClientBuilder::default().set_headers(headers).build(&url)
+        };
+        let bcs = |items: &[Value]| items.iter().map(|i| i["bcs"].clone()).collect::<Vec<_>>();
+
+        // Responses and notifications alike are redacted.
+        let client = connect("public-key").await.unwrap();
+        let events: Vec<Value> = client.request("test_events", rpc_params![]).await.unwrap();
+        assert_eq!(bcs(&events[..2]), vec![Value::Null, json!("AQID")]);
+
+        let mut subscription: Subscription<Value> = client
+            .subscribe("test_subscribe", rpc_params![], "test_unsubscribe")
+            .await
+            .unwrap();
+        let mut received = vec![];
+        while let Ok(Some(Ok(item))) =
+            tokio::time::timeout(Duration::from_secs(1), subscription.next()).await
+        {
+            received.push(item);
+        }
+        assert_eq!(bcs(&received[..2]), vec![Value::Null, json!("AQID")]);
+
+        // Exempt keys get everything.
+        let client = connect("secret-key").await.unwrap();
+        let events: Vec<Value> = client.request("test_events", rpc_params![]).await.unwrap();
+        assert_eq!(bcs(&events[..2]), vec![json!("AQID"), json!("AQID")]);
+    }
+
+    #[tokio::test]
+    async fn test_redact_over_sse() {
+        let request = hyper::Request::get("/sse?method=test_subscribe")
+            .header(API_KEY_HEADER, "public-key")
+            .body(hyper::Body::empty())
+            .unwrap();
+        let response = redaction_router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), hyper::StatusCode::OK);
+        let body = tokio::time::timeout(
+            Duration::from_secs(10),
+            hyper::body::to_bytes(response.into_body()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let events: Vec<Value> = String::from_utf8(body.to_vec())
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|data| serde_json::from_str(data.trim()).unwrap())
+            .collect();
+        assert_eq!(
+            types(&events),
+            vec!["0xabc::usdc::Minted", "0xdef::nft::Minted"]
+        );
+        assert_eq!(events[0]["bcs"], Value::Null);
+        assert_eq!(events[1]["bcs"], "AQID");
+    }
+
+    #[tokio::test]
+    async fn test_reject_stale_over_ws() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(stale_router().into_make_service());
+        tokio::spawn(server);
+
+        let client = WsClientBuilder::default().build(&url).await.unwrap();
+        let error = client
+            .request::<Vec<Value>, _>("test_events", rpc_params![])
+            .await
+            .unwrap_err();
+        let jsonrpsee::core::Error::Call(jsonrpsee::types::error::CallError::Custom(error)) = error
+        else {
+            panic!("Expected an error object, got {error:?}");
+        };
+        assert_eq!(error.code(), TRANSIENT_ERROR_CODE);
+        assert!(error.message().contains("60000ms behind"));
+
+        let subscription = client
+            .subscribe::<Value, _>("test_subscribe", rpc_params![], "test_unsubscribe")
+            .await;
+        assert!(subscription.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reject_stale_over_sse() {
+        let request = hyper::Request::get("/sse?method=test_subscribe")
+            .body(hyper::Body::empty())
+            .unwrap();
+        let response = stale_router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), hyper::StatusCode::OK);
+        assert_eq!(response.headers()[DATA_LAG_HEADER], "60000");
+        let body = tokio::time::timeout(
+            Duration::from_secs(10),
+            hyper::body::to_bytes(response.into_body()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.starts_with("event: error\n"));
+        assert!(body.contains("60000ms behind"));
+        assert!(!body.contains("0xabc::usdc::Minted"));
+    }
+
+    #[tokio::test]
+    async fn test_get_only_cacheable_methods() {
+        let caching = HttpCaching::default();
+        caching.set_config(crate::http_caching::HttpCachingConfig {
+            immutable_methods: ["test_events".to_string()].into(),
+            ..Default::default()
+        });
+        let get = |method: &str| {
+            router(|builder| builder.set_http_caching(caching.clone())).oneshot(
+                hyper::Request::get(format!("/json-rpc?method={method}"))
+                    .body(hyper::Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        let response = get("test_events").await.unwrap();
+        assert_eq!(response.status(), hyper::StatusCode::OK);
+        let vary = response.headers()[hyper::header::VARY].to_str().unwrap();
+        assert!(vary.contains(MIN_CHECKPOINT_HEADER));
+
+        // Methods that aren't cacheable, and may change state, can't be called with GET.
+        let response = get("sui_executeTransactionBlock").await.unwrap();
+        assert_eq!(response.status(), hyper::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[hyper::header::ALLOW], "POST");
+    }
+}
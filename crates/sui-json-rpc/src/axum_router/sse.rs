@@ -0,0 +1,491 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Server-Sent Events bridge to the subscription API, for clients that can't open WebSockets. A
+//! subscription is opened with a GET request naming the subscription method, and its params as
+//! they would be sent over a WebSocket:
+//!
+//! ```text
+//! GET /sse?method=suix_subscribeEvent&params=[{"Sender":"0x.."}]
+//! ```
+//!
+//! Each notification is sent as an SSE event whose data is the notification's result. If the
+//! subscription fails, an `error` event is sent with the error message, and the stream ends.
+//!
+//! Event subscriptions can be resumed: each event is sent with its `EventID` as its SSE id, and a
+//! client that reconnects with a `Last-Event-ID` header (or a `cursor` query parameter) is first
+//! sent the events after that cursor, read with `suix_queryEvents`, and then live events. Only
+//! clients that are up to [`MAX_RESUMED_EVENTS`] events behind can resume this way, others have
+//! to catch up with `suix_queryEvents` first.
+//!
+//! Each client, told apart by its API key, or else by its address, can have up to
+//! [`MAX_SUBSCRIPTIONS_PER_CLIENT`] subscriptions open at a time. Clients that don't keep up with
+//! their notifications are disconnected rather than having them buffered, and can resume from the
+//! last event they received.
+
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::{ConnectInfo, Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures::channel::mpsc;
+use futures::{SinkExt, StreamExt};
+use hyper::{HeaderMap, StatusCode};
+use jsonrpsee::core::server::helpers::{BoundedSubscriptions, MethodSink};
+use jsonrpsee::core::server::rpc_module::{ConnState, MethodKind};
+use jsonrpsee::server::logger::{self, Logger, TransportProtocol};
+use jsonrpsee::types::{Id, Params};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{max_response_size, JsonRpcService};
+use crate::admission::AdmissionPermit;
+use crate::method_toggles::MethodToggles;
+use crate::redaction::Redaction;
+use crate::tenancy::Tenant;
+use sui_json_rpc_api::API_KEY_HEADER;
+
+const SUBSCRIBE_EVENT_METHOD: &str = "suix_subscribeEvent";
+const QUERY_EVENTS_METHOD: &str = "suix_queryEvents";
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// Number of past events read per query when resuming an event subscription.
+const RESUME_PAGE_SIZE: usize = 100;
+/// Number of past events that can be sent when resuming an event subscription.
+pub const MAX_RESUMED_EVENTS: usize = 1000;
+/// Number of events buffered for a client before the subscription waits for it to catch up.
+const STREAM_CAPACITY: usize = 128;
+/// How long the subscription waits for a client to catch up before disconnecting it.
+const SLOW_CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Number of subscriptions a client can have open at a time.
+pub const MAX_SUBSCRIPTIONS_PER_CLIENT: usize = 32;
+
+type SseSender = mpsc::Sender<Result<Event, Infallible>>;
+
+#[derive(Deserialize)]
+pub struct SseSubscription {
+    method: String,
+    /// JSON array of the subscription method's params.
+    params: Option<String>,
+    /// JSON `EventID` to resume an event subscription after.
+    cursor: Option<String>,
+}
+
+/// The subscriptions open for each client.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SseClients {
+    open: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+/// A client's claim on one of its subscriptions, released when it is dropped.
+struct OpenSubscription {
+    clients: SseClients,
+    client: String,
+}
+
+impl SseClients {
+    /// Count a new subscription for `client`, unless it already has as many open as it can.
+    fn open(&self, client: String) -> Option<OpenSubscription> {
+        let mut open = self.open.lock().unwrap();
+        let count = open.entry(client.clone()).or_default();
+        if *count >= MAX_SUBSCRIPTIONS_PER_CLIENT {
+            return None;
+        }
+        *count += 1;
+        Some(OpenSubscription {
+            clients: self.clone(),
+            client,
+        })
+    }
+}
+
+/// The client a subscription's items are sent to, which decides what it is sent of them.
+struct Recipient {
+    api_key: Option<String>,
+    redaction: Redaction,
+    tenant: Option<Arc<Tenant>>,
+}
+
+impl Recipient {
+    /// Redact `item` for the client, and remove what its tenant, if any, can't see from it,
+    /// returning whether it can be sent at all.
+    fn prepare(&self, item: &mut Value) -> bool {
+        self.redaction.redact_item(self.api_key.as_deref(), item);
+        match &self.tenant {
+            Some(tenant) => tenant.isolate_item(item),
+            None => true,
+        }
+    }
+}
+
+impl Drop for OpenSubscription {
+    fn drop(&mut self) {
+        let mut open = self.clients.open.lock().unwrap();
+        if let Some(count) = open.get_mut(&self.client) {
+            *count -= 1;
+            if *count == 0 {
+                open.remove(&self.client);
+            }
+        }
+    }
+}
+
+pub async fn sse_subscribe<L: Logger>(
+    State(service): State<JsonRpcService<L>>,
+    headers: HeaderMap,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    Query(query): Query<SseSubscription>,
+) -> Response {
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, message).into_response();
+
+    if service.method_toggles.is_disabled(&query.method) {
+        return bad_request(MethodToggles::disabled_message(&query.method));
+    }
+    match service.methods.method_with_name(&query.method) {
+        Some((_, method)) if matches!(method.inner(), MethodKind::Subscription(_)) => {}
+        _ => return bad_request(format!("{} is not a subscription method", query.method)),
+    }
+
+    // Subscriptions that can't be served from data this far behind fail as they would once open.
+    if let Some(message) = service.staleness.reject(&query.method) {
+        let error = futures::stream::once(async move { Ok::<_, Infallible>(error_event(message)) });
+        let mut response = Sse::new(error).into_response();
+        service.warn_stale(response.headers_mut());
+        return response;
+    }
+
+    let params: Vec<Value> = match serde_json::from_str(query.params.as_deref().unwrap_or("[]")) {
+        Ok(params) => params,
+        Err(e) => return bad_request(format!("params must be a JSON array: {e}")),
+    };
+
+    let cursor = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_owned)
+        .or(query.cursor);
+    let cursor: Option<Value> = match cursor {
+        Some(_) if query.method != SUBSCRIBE_EVENT_METHOD => {
+            return bad_request(format!("Only {SUBSCRIBE_EVENT_METHOD} can be resumed"));
+        }
+        Some(cursor) => match serde_json::from_str(&cursor) {
+            Ok(cursor) => Some(cursor),
+            Err(e) => return bad_request(format!("Invalid cursor: {e}")),
+        },
+        None => None,
+    };
+
+    let tenant = match service.tenant(&headers) {
+        Ok(tenant) => tenant,
+        Err(message) => return (StatusCode::FORBIDDEN, message).into_response(),
+    };
+    let api_key = headers.get(API_KEY_HEADER).and_then(|h| h.to_str().ok());
+    let client = match (api_key, peer) {
+        (Some(api_key), _) => format!("key:{api_key}"),
+        (None, Some(ConnectInfo(address))) => format!("ip:{}", address.ip()),
+        (None, None) => String::new(),
+    };
+    let Some(open) = service.sse_clients.open(client) else {
+        let message = format!(
+            "Too many subscriptions open, at most {MAX_SUBSCRIPTIONS_PER_CLIENT} are allowed"
+        );
+        return (StatusCode::TOO_MANY_REQUESTS, message).into_response();
+    };
+    let permit = match service.admission.admit(api_key).await {
+        Ok(permit) => permit,
+        Err(message) => return (StatusCode::TOO_MANY_REQUESTS, message).into_response(),
+    };
+
+    let recipient = Recipient {
+        api_key: api_key.map(str::to_owned),
+        redaction: service.redaction.clone(),
+        tenant,
+    };
+    let (tx, rx) = mpsc::channel(STREAM_CAPACITY);
+    // The subscription counts against the client's limit until its stream is dropped, when the
+    // client goes away.
+    let stream = rx.map(move |event| {
+        let _open = &open;
+        event
+    });
+    let mut response = Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response();
+    service.warn_stale(response.headers_mut());
+
+    tokio::spawn(forward_subscription(
+        service,
+        query.method,
+        params,
+        cursor,
+        recipient,
+        permit,
+        tx,
+    ));
+    response
+}
+
+/// Subscribe with `method`, and forward its notifications to `tx` until either the subscription
+/// or the client goes away, preceded by the events after `cursor`, if set, each prepared for its
+/// `recipient`. The subscription's admission `permit` is held until it is set up and has caught
+/// up with the events after `cursor`.
+async fn forward_subscription<L: Logger>(
+    service: JsonRpcService<L>,
+    method: String,
+    params: Vec<Value>,
+    cursor: Option<Value>,
+    recipient: Recipient,
+    permit: AdmissionPermit,
+    mut tx: SseSender,
+) {
+    let Some((name, callback)) = service.methods.method_with_name(&method) else {
+        return;
+    };
+    let MethodKind::Subscription(callback) = callback.inner() else {
+        return;
+    };
+
+    #[allow(clippy::disallowed_methods)]
+    let (sink_tx, mut sink_rx) = mpsc::unbounded::<String>();
+    let sink = MethodSink::new_with_limit(sink_tx, max_response_size(), max_response_size());
+    let bounded_subscriptions = BoundedSubscriptions::new(1);
+    let conn_state = ConnState {
+        conn_id: 0,
+        close_notify: bounded_subscriptions
+            .acquire()
+            .expect("First subscription of the connection"),
+        id_provider: &*service.id_provider,
+    };
+
+    // Subscribe before reading past events, so no event is missed in between. Live events that
+    // were also read as past events are skipped.
+    let params_json = Value::Array(params.clone()).to_string();
+    let rpc_params = Params::new(Some(&params_json));
+    service.logger.on_call(
+        name,
+        rpc_params.clone(),
+        logger::MethodKind::Subscription,
+        TransportProtocol::Http,
+    );
+    callback(Id::Number(0), rpc_params, sink, conn_state, None).await;
+
+    let mut resumed = HashSet::new();
+    if let Some(cursor) = cursor {
+        let filter = params.into_iter().next().unwrap_or(Value::Null);
+        match send_events_after(&service, &recipient, &filter, cursor, &mut tx).await {
+            Ok(Some(sent)) => resumed = sent,
+            Ok(None) => return,
+            Err(message) => {
+                let _ = tx.send(Ok(error_event(message))).await;
+                return;
+            }
+        }
+    }
+    drop(permit);
+
+    while let Some(message) = sink_rx.next().await {
+        let (event, last) = match serde_json::from_str::<SinkMessage>(&message) {
+            Ok(SinkMessage {
+                params: Some(Notification { mut result }),
+                ..
+            }) => {
+                let id = sse_id(&result);
+                if !resumed.is_empty() {
+                    // Once a live event wasn't read as a past event, none of the following were.
+                    if id.as_ref().is_some_and(|id| resumed.contains(id)) {
+                        continue;
+                    }
+                    resumed.clear();
+                }
+                if !recipient.prepare(&mut result) {
+                    continue;
+                }
+                let event = Event::default().data(result.to_string());
+                match id {
+                    Some(id) => (event.id(id), false),
+                    None => (event, false),
+                }
+            }
+            Ok(SinkMessage {
+                error: Some(error), ..
+            }) => (error_event(error.message), true),
+            // The response to the subscription request, carrying its id.
+            Ok(_) => continue,
+            Err(e) => (error_event(format!("Malformed notification: {e}")), true),
+        };
+
+        // Notifications pile up in the subscription while the client's buffer is full, so a
+        // client that doesn't make room in time is disconnected.
+        let sent = tokio::time::timeout(SLOW_CLIENT_TIMEOUT, tx.send(Ok(event))).await;
+        if !matches!(sent, Ok(Ok(()))) || last {
+            return;
+        }
+    }
+}
+
+/// Send the events matching `filter` after `cursor` to `tx`, prepared for `recipient`, leaving
+/// out those it can't be sent, returning the SSE ids of the events read, or `None` if the client
+/// went away. Nothing is sent if there are more than [`MAX_RESUMED_EVENTS`] events after `cursor`.
+async fn send_events_after<L: Logger>(
+    service: &JsonRpcService<L>,
+    recipient: &Recipient,
+    filter: &Value,
+    mut cursor: Value,
+    tx: &mut SseSender,
+) -> Result<Option<HashSet<String>>, String> {
+    let mut events = vec![];
+    loop {
+        let page = query_events(service, filter, &cursor).await?;
+        events.extend(page.data);
+        if events.len() > MAX_RESUMED_EVENTS {
+            return Err(format!(
+                "More than {MAX_RESUMED_EVENTS} events to resume after the cursor, catch up \
+                 with {QUERY_EVENTS_METHOD} before subscribing"
+            ));
+        }
+
+        match page.next_cursor {
+            Some(next) if page.has_next_page => cursor = next,
+            _ => break,
+        }
+    }
+
+    let mut sent = HashSet::new();
+    for mut event in events {
+        let id = sse_id(&event).ok_or("Event without an id")?;
+        if !recipient.prepare(&mut event) {
+            sent.insert(id);
+            continue;
+        }
+        let data = Event::default().data(event.to_string()).id(id.clone());
+        if tx.send(Ok(data)).await.is_err() {
+            return Ok(None);
+        }
+        sent.insert(id);
+    }
+    Ok(Some(sent))
+}
+
+async fn query_events<L: Logger>(
+    service: &JsonRpcService<L>,
+    filter: &Value,
+    cursor: &Value,
+) -> Result<EventPage, String> {
+    let unsupported = || "This server can't resume event subscriptions".to_string();
+    let (_, method) = service
+        .methods
+        .method_with_name(QUERY_EVENTS_METHOD)
+        .ok_or_else(unsupported)?;
+    let MethodKind::Async(callback) = method.inner() else {
+        return Err(unsupported());
+    };
+
+    let params = serde_json::json!([filter, cursor, RESUME_PAGE_SIZE, false]).to_string();
+    let response = callback(
+        Id::Number(0),
+        Params::new(Some(&params)).into_owned(),
+        0,
+        max_response_size() as usize,
+        None,
+    )
+    .await;
+
+    let response: QueryResponse = serde_json::from_str(&response.result)
+        .map_err(|e| format!("Malformed {QUERY_EVENTS_METHOD} response: {e}"))?;
+    match response {
+        QueryResponse {
+            result: Some(page), ..
+        } => Ok(page),
+        QueryResponse {
+            error: Some(error), ..
+        } => Err(error.message),
+        _ => Err(format!("Empty {QUERY_EVENTS_METHOD} response")),
+    }
+}
+
+/// The SSE id of a subscription item: the `EventID` of events, as JSON, or the digest of
+/// transactions.
+fn sse_id(item: &Value) -> Option<String> {
+    item.get("id").map(Value::to_string).or_else(|| {
+        item.get("digest")
+            .and_then(Value::as_str)
+            .map(str::to_owned)
+    })
+}
+
+fn error_event(message: String) -> Event {
+    Event::default().event("error").data(message)
+}
+
+/// A message sent by a subscription: either the response to the subscription request, or a
+/// notification.
+#[derive(Deserialize)]
+struct SinkMessage {
+    params: Option<Notification>,
+    error: Option<ErrorMessage>,
+}
+
+#[derive(Deserialize)]
+struct Notification {
+    result: Value,
+}
+
+#[derive(Deserialize)]
+struct ErrorMessage {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct QueryResponse {
+    result: Option<EventPage>,
+    error: Option<ErrorMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventPage {
+    data: Vec<Value>,
+    next_cursor: Option<Value>,
+    has_next_page: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriptions_per_client() {
+        let clients = SseClients::default();
+        let open: Vec<_> = (0..MAX_SUBSCRIPTIONS_PER_CLIENT)
+            .map(|_| clients.open("key:a".to_string()).unwrap())
+            .collect();
+        assert!(clients.open("key:a".to_string()).is_none());
+        // Other clients have their own limit.
+        let _other = clients.open("key:b".to_string()).unwrap();
+
+        // Closing a subscription makes room for another.
+        drop(open.into_iter().next());
+        assert!(clients.open("key:a".to_string()).is_some());
+    }
+
+    #[test]
+    fn test_sse_id() {
+        let event = serde_json::json!({
+            "id": {"txDigest": "11111111111111111111111111111111", "eventSeq": "0"},
+            "type": "0x2::coin::Foo",
+        });
+        let id = sse_id(&event).unwrap();
+        // Ids round-trip as cursors.
+        assert_eq!(serde_json::from_str::<Value>(&id).unwrap(), event["id"]);
+
+        let transaction = serde_json::json!({"digest": "11111111111111111111111111111111"});
+        assert_eq!(
+            sse_id(&transaction).as_deref(),
+            Some("11111111111111111111111111111111")
+        );
+    }
+}
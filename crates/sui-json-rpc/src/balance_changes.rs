@@ -188,22 +188,7 @@ impl<P> ObjectProviderCache<P> {
     ) -> Self {
         let mut object_cache = BTreeMap::new();
         let mut last_version_cache = BTreeMap::new();
-
-        for (object_id, (object_ref, object, _)) in written_objects {
-            let key = (object_id, object_ref.1);
-            object_cache.insert(key, object.clone());
-
-            match last_version_cache.get_mut(&key) {
-                Some(existing_seq_number) => {
-                    if object_ref.1 > *existing_seq_number {
-                        *existing_seq_number = object_ref.1
-                    }
-                }
-                None => {
-                    last_version_cache.insert(key, object_ref.1);
-                }
-            }
-        }
+        cache_written_objects(&mut object_cache, &mut last_version_cache, written_objects);
 
         Self {
             object_cache: RwLock::new(object_cache),
@@ -211,6 +196,41 @@ impl<P> ObjectProviderCache<P> {
             provider,
         }
     }
+
+    /// Add objects written by a transaction to the cache, e.g. so that the changes made by
+    /// transactions dry-run after it can be computed from the same cache.
+    pub async fn insert_written_objects(
+        &self,
+        written_objects: BTreeMap<ObjectID, (ObjectRef, Object, WriteKind)>,
+    ) {
+        cache_written_objects(
+            &mut *self.object_cache.write().await,
+            &mut *self.last_version_cache.write().await,
+            written_objects,
+        );
+    }
+}
+
+fn cache_written_objects(
+    object_cache: &mut BTreeMap<(ObjectID, SequenceNumber), Object>,
+    last_version_cache: &mut BTreeMap<(ObjectID, SequenceNumber), SequenceNumber>,
+    written_objects: BTreeMap<ObjectID, (ObjectRef, Object, WriteKind)>,
+) {
+    for (object_id, (object_ref, object, _)) in written_objects {
+        let key = (object_id, object_ref.1);
+        object_cache.insert(key, object.clone());
+
+        match last_version_cache.get_mut(&key) {
+            Some(existing_seq_number) => {
+                if object_ref.1 > *existing_seq_number {
+                    *existing_seq_number = object_ref.1
+                }
+            }
+            None => {
+                last_version_cache.insert(key, object_ref.1);
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -16,7 +16,9 @@ use tracing::{debug, info, instrument};
 
 use mysten_metrics::spawn_monitored_task;
 use sui_core::authority::AuthorityState;
-use sui_json_rpc_api::{cap_page_limit, CoinReadApiOpenRpc, CoinReadApiServer, JsonRpcMetrics};
+use sui_json_rpc_api::{
+    cap_page_limit, CoinReadApiOpenRpc, CoinReadApiServer, JsonRpcMetrics, QUERY_MAX_RESULT_LIMIT,
+};
 use sui_json_rpc_types::Balance;
 use sui_json_rpc_types::{CoinPage, SuiCoinMetadata};
 use sui_open_rpc::Module;
@@ -67,6 +69,18 @@ impl CoinReadApi {
             )),
         }
     }
+
+    async fn find_coin_metadata(&self, coin_struct: StructTag) -> Option<SuiCoinMetadata> {
+        let metadata_object = self
+            .internal
+            .find_package_object(
+                &coin_struct.address.into(),
+                CoinMetadata::type_(coin_struct),
+            )
+            .await
+            .ok();
+        metadata_object.and_then(|v: Object| v.try_into().ok())
+    }
 }
 
 impl SuiRpcModule for CoinReadApi {
@@ -173,6 +187,7 @@ impl CoinReadApiServer for CoinReadApi {
                 total_balance: balance.balance as u128,
                 // note: LockedCoin is deprecated
                 locked_balance: Default::default(),
+                fiat_value: None,
             })
         })
     }
@@ -192,6 +207,7 @@ impl CoinReadApiServer for CoinReadApi {
                         total_balance: balance.balance as u128,
                         // note: LockedCoin is deprecated
                         locked_balance: Default::default(),
+                        fiat_value: None,
                     }
                 })
                 .collect())
@@ -202,15 +218,31 @@ impl CoinReadApiServer for CoinReadApi {
     async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<Option<SuiCoinMetadata>> {
         with_tracing!(async move {
             let coin_struct = parse_to_struct_tag(&coin_type)?;
-            let metadata_object = self
-                .internal
-                .find_package_object(
-                    &coin_struct.address.into(),
-                    CoinMetadata::type_(coin_struct),
-                )
-                .await
-                .ok();
-            Ok(metadata_object.and_then(|v: Object| v.try_into().ok()))
+            Ok(self.find_coin_metadata(coin_struct).await)
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn multi_get_coin_metadata(
+        &self,
+        coin_types: Vec<String>,
+    ) -> RpcResult<Vec<Option<SuiCoinMetadata>>> {
+        with_tracing!(async move {
+            if coin_types.len() > *QUERY_MAX_RESULT_LIMIT {
+                Err(SuiRpcInputError::SizeLimitExceeded(
+                    QUERY_MAX_RESULT_LIMIT.to_string(),
+                ))?
+            }
+            let coin_structs = coin_types
+                .iter()
+                .map(|coin_type| parse_to_struct_tag(coin_type))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(futures::future::join_all(
+                coin_structs
+                    .into_iter()
+                    .map(|coin_struct| self.find_coin_metadata(coin_struct)),
+            )
+            .await)
         })
     }
 
@@ -392,6 +424,7 @@ impl CoinReadInternal for CoinReadInternalImpl {
             data,
             next_cursor,
             has_next_page,
+            truncated: false,
         })
     }
 }
@@ -589,6 +622,7 @@ mod tests {
                     data: vec![gas_coin.clone()],
                     next_cursor: Some(gas_coin.coin_object_id),
                     has_next_page: false,
+                    truncated: false,
                 }
             );
         }
@@ -626,6 +660,7 @@ mod tests {
                     data: coins[..limit].to_vec(),
                     next_cursor: Some(coins[limit - 1].coin_object_id),
                     has_next_page: true,
+                    truncated: false,
                 }
             );
         }
@@ -664,6 +699,7 @@ mod tests {
                     data: vec![coin.clone()],
                     next_cursor: Some(coin.coin_object_id),
                     has_next_page: false,
+                    truncated: false,
                 }
             );
         }
@@ -708,6 +744,7 @@ mod tests {
                     data: coins[..limit].to_vec(),
                     next_cursor: Some(coins[limit - 1].coin_object_id),
                     has_next_page: true,
+                    truncated: false,
                 }
             );
         }
@@ -966,7 +1003,8 @@ mod tests {
                     coin_type: gas_coin.coin_type,
                     coin_object_count: 9,
                     total_balance: 7,
-                    locked_balance: Default::default()
+                    locked_balance: Default::default(),
+                    fiat_value: None,
                 }
             );
         }
@@ -1002,7 +1040,8 @@ mod tests {
                     coin_type: coin.coin_type,
                     coin_object_count: 11,
                     total_balance: 10,
-                    locked_balance: Default::default()
+                    locked_balance: Default::default(),
+                    fiat_value: None,
                 }
             );
         }
@@ -1126,12 +1165,14 @@ mod tests {
                     coin_object_count: 9,
                     total_balance: 7,
                     locked_balance: Default::default(),
+                    fiat_value: None,
                 },
                 Balance {
                     coin_type: usdc_coin.coin_type,
                     coin_object_count: 11,
                     total_balance: 10,
                     locked_balance: Default::default(),
+                    fiat_value: None,
                 },
             ];
             // This is because the underlying result is a hashmap, so order is not guaranteed
@@ -1275,6 +1316,61 @@ mod tests {
             let result = response.unwrap();
             assert!(result.is_none());
         }
+
+        #[tokio::test]
+        async fn test_multi_get_coin_metadata() {
+            let package_id = get_test_package_id();
+            let coin_name = get_test_coin_type(package_id);
+            let input_coin_struct = parse_sui_struct_tag(&coin_name).expect("should not fail");
+            let coin_metadata = CoinMetadata {
+                id: UID::new(get_test_package_id()),
+                decimals: 2,
+                name: "test_coin".to_string(),
+                symbol: "TEST".to_string(),
+                description: "test coin".to_string(),
+                icon_url: None,
+            };
+            let coin_metadata_object =
+                Object::coin_metadata_for_testing(input_coin_struct, coin_metadata);
+            let metadata = SuiCoinMetadata::try_from(coin_metadata_object.clone()).unwrap();
+            let mut mock_internal = MockCoinReadInternal::new();
+            mock_internal
+                .expect_find_package_object()
+                .returning(move |object_id, _| {
+                    if object_id == &package_id {
+                        Ok(coin_metadata_object.clone())
+                    } else {
+                        Err(SuiRpcInputError::GenericNotFound("not found".to_string()).into())
+                    }
+                });
+
+            let coin_read_api = CoinReadApi {
+                internal: Box::new(mock_internal),
+            };
+
+            // Results are in the order of the request, with gaps for coins without metadata.
+            let response = coin_read_api
+                .multi_get_coin_metadata(vec![
+                    "0x2::sui::SUI".to_string(),
+                    coin_name.clone(),
+                    coin_name.clone(),
+                ])
+                .await;
+            assert_eq!(
+                response.unwrap(),
+                vec![None, Some(metadata.clone()), Some(metadata)]
+            );
+
+            let response = coin_read_api
+                .multi_get_coin_metadata(vec![coin_name; *QUERY_MAX_RESULT_LIMIT + 1])
+                .await;
+            assert!(response.is_err());
+
+            let response = coin_read_api
+                .multi_get_coin_metadata(vec!["0x2::invalid".to_string()])
+                .await;
+            assert!(response.is_err());
+        }
     }
 
     mod get_total_supply_tests {
@@ -0,0 +1,268 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolving paths through the Move fields and dynamic fields of an object, so that clients can
+//! read a value nested several dynamic fields deep (e.g. an entry in a table held by another
+//! table) in one call, rather than reading each dynamic field in turn.
+
+use async_trait::async_trait;
+use move_core_types::identifier::IdentStr;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use sui_json_rpc_types::{DynamicFieldPathSegment, SuiMoveStruct, SuiMoveValue};
+use sui_types::base_types::{
+    ObjectID, MOVE_STDLIB_ADDRESS, STD_ASCII_MODULE_NAME, STD_ASCII_STRUCT_NAME,
+    STD_UTF8_MODULE_NAME, STD_UTF8_STRUCT_NAME,
+};
+use sui_types::dynamic_field::{derive_dynamic_field_id, DynamicFieldInfo, DynamicFieldName};
+
+use crate::error::{Error, SuiRpcInputError};
+
+/// Longest path resolved, as each segment can take several reads.
+pub const MAX_PATH_LENGTH: usize = 16;
+
+/// Where the objects along a path are read from.
+#[async_trait]
+pub trait PathReader: Send + Sync {
+    /// The parsed fields of the Move object with `id`, if it exists.
+    async fn object_fields(&self, id: ObjectID) -> Result<Option<SuiMoveStruct>, Error>;
+
+    /// The type and BCS bytes of a dynamic field's `name`.
+    async fn name_bcs(&self, name: DynamicFieldName) -> Result<(TypeTag, Vec<u8>), Error>;
+}
+
+/// The value reached by following `path` from the object with `parent_object_id`, or `None` if
+/// any segment of the path doesn't exist.
+pub async fn resolve_path(
+    reader: &impl PathReader,
+    parent_object_id: ObjectID,
+    path: Vec<DynamicFieldPathSegment>,
+) -> Result<Option<SuiMoveValue>, Error> {
+    if path.len() > MAX_PATH_LENGTH {
+        return Err(SuiRpcInputError::GenericInvalid(format!(
+            "Path has {} segments, the maximum is {MAX_PATH_LENGTH}",
+            path.len()
+        ))
+        .into());
+    }
+
+    let Some(fields) = reader.object_fields(parent_object_id).await? else {
+        return Ok(None);
+    };
+    let mut value = SuiMoveValue::Struct(fields);
+    for segment in path {
+        match step(reader, value, segment).await? {
+            Some(next) => value = next,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(value))
+}
+
+/// The value reached from `value` by following `segment`.
+async fn step(
+    reader: &impl PathReader,
+    value: SuiMoveValue,
+    segment: DynamicFieldPathSegment,
+) -> Result<Option<SuiMoveValue>, Error> {
+    let id = uid(&value);
+    match (value, &segment) {
+        (
+            SuiMoveValue::Struct(
+                SuiMoveStruct::WithTypes { mut fields, .. } | SuiMoveStruct::WithFields(mut fields),
+            ),
+            DynamicFieldPathSegment::Name(name),
+        ) => {
+            if let Some(field) = fields.remove(name) {
+                return Ok(Some(field));
+            }
+        }
+        (SuiMoveValue::Vector(mut values), DynamicFieldPathSegment::Index(i)) => {
+            let i = *i as usize;
+            return Ok((i < values.len()).then(|| values.swap_remove(i)));
+        }
+        _ => {}
+    }
+
+    let Some(id) = id else {
+        return Ok(None);
+    };
+    let names = match segment {
+        DynamicFieldPathSegment::Index(i) => vec![(TypeTag::U64, bcs::to_bytes(&i)?)],
+        DynamicFieldPathSegment::Name(name) => {
+            let bytes = bcs::to_bytes(&name)?;
+            vec![
+                (
+                    string_type(STD_UTF8_MODULE_NAME, STD_UTF8_STRUCT_NAME),
+                    bytes.clone(),
+                ),
+                (
+                    string_type(STD_ASCII_MODULE_NAME, STD_ASCII_STRUCT_NAME),
+                    bytes,
+                ),
+            ]
+        }
+        DynamicFieldPathSegment::DynamicField(name) => vec![reader.name_bcs(name).await?],
+    };
+    for (name_type, name_bcs) in names {
+        if let Some(value) = dynamic_field_value(reader, id, name_type, &name_bcs).await? {
+            return Ok(Some(value));
+        }
+    }
+    Ok(None)
+}
+
+/// The value of the dynamic field of `parent` with the given name, or the object it holds if it
+/// is a dynamic object field.
+async fn dynamic_field_value(
+    reader: &impl PathReader,
+    parent: ObjectID,
+    name_type: TypeTag,
+    name_bcs: &[u8],
+) -> Result<Option<SuiMoveValue>, Error> {
+    let id = derive_dynamic_field_id(parent, &name_type, name_bcs)?;
+    if let Some(field) = reader.object_fields(id).await? {
+        return Ok(field_value(field));
+    }
+
+    let wrapper = TypeTag::Struct(Box::new(DynamicFieldInfo::dynamic_object_field_wrapper(
+        name_type,
+    )));
+    let id = derive_dynamic_field_id(parent, &wrapper, name_bcs)?;
+    let Some(field) = reader.object_fields(id).await? else {
+        return Ok(None);
+    };
+    let Some(SuiMoveValue::Address(object_id)) = field_value(field) else {
+        return Ok(None);
+    };
+    Ok(reader
+        .object_fields(object_id.into())
+        .await?
+        .map(SuiMoveValue::Struct))
+}
+
+/// The `value` of a `0x2::dynamic_field::Field`.
+pub(crate) fn field_value(field: SuiMoveStruct) -> Option<SuiMoveValue> {
+    match field {
+        SuiMoveStruct::WithTypes { mut fields, .. } | SuiMoveStruct::WithFields(mut fields) => {
+            fields.remove("value")
+        }
+        SuiMoveStruct::Runtime(_) => None,
+    }
+}
+
+/// The ID of `value`, if it is a UID or a struct with one, that its dynamic fields hang off.
+pub(crate) fn uid(value: &SuiMoveValue) -> Option<ObjectID> {
+    match value {
+        SuiMoveValue::UID { id } => Some(*id),
+        SuiMoveValue::Struct(
+            SuiMoveStruct::WithTypes { fields, .. } | SuiMoveStruct::WithFields(fields),
+        ) => match fields.get("id") {
+            Some(SuiMoveValue::UID { id }) => Some(*id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn string_type(module: &IdentStr, name: &IdentStr) -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: MOVE_STDLIB_ADDRESS,
+        module: module.to_owned(),
+        name: name.to_owned(),
+        type_params: vec![],
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, HashMap};
+    use sui_types::base_types::SuiAddress;
+
+    /// Objects by ID.
+    struct TestReader(HashMap<ObjectID, SuiMoveStruct>);
+
+    #[async_trait]
+    impl PathReader for TestReader {
+        async fn object_fields(&self, id: ObjectID) -> Result<Option<SuiMoveStruct>, Error> {
+            Ok(self.0.get(&id).cloned())
+        }
+
+        async fn name_bcs(&self, name: DynamicFieldName) -> Result<(TypeTag, Vec<u8>), Error> {
+            Ok((name.type_, bcs::to_bytes(name.value.as_str().unwrap())?))
+        }
+    }
+
+    fn object(fields: impl IntoIterator<Item = (&'static str, SuiMoveValue)>) -> SuiMoveStruct {
+        SuiMoveStruct::WithFields(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_resolve_path() {
+        let parent = ObjectID::random();
+        let table = ObjectID::random();
+        let fees = ObjectID::random();
+        let mut objects = HashMap::new();
+
+        // An object holding a table in its `config` field, with a dynamic object field in the
+        // table at key 3, holding the fees.
+        objects.insert(
+            parent,
+            object([
+                ("id", SuiMoveValue::UID { id: parent }),
+                (
+                    "config",
+                    SuiMoveValue::Struct(object([
+                        ("id", SuiMoveValue::UID { id: table }),
+                        ("size", SuiMoveValue::String("1".to_string())),
+                    ])),
+                ),
+            ]),
+        );
+        let wrapper = TypeTag::Struct(Box::new(DynamicFieldInfo::dynamic_object_field_wrapper(
+            TypeTag::U64,
+        )));
+        let field =
+            derive_dynamic_field_id(table, &wrapper, &bcs::to_bytes(&3u64).unwrap()).unwrap();
+        objects.insert(
+            field,
+            object([("value", SuiMoveValue::Address(SuiAddress::from(fees)))]),
+        );
+        objects.insert(
+            fees,
+            object([
+                ("id", SuiMoveValue::UID { id: fees }),
+                ("fees", SuiMoveValue::Vector(vec![SuiMoveValue::Number(5)])),
+            ]),
+        );
+        let reader = TestReader(objects);
+
+        let path = |path: serde_json::Value| serde_json::from_value(path).unwrap();
+        assert_eq!(
+            resolve_path(
+                &reader,
+                parent,
+                path(serde_json::json!(["config", 3, "fees", 0]))
+            )
+            .await
+            .unwrap(),
+            Some(SuiMoveValue::Number(5))
+        );
+        assert_eq!(
+            resolve_path(&reader, parent, path(serde_json::json!(["config", 4])))
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(
+            resolve_path(&reader, parent, vec![DynamicFieldPathSegment::Index(0); 17])
+                .await
+                .is_err()
+        );
+    }
+}
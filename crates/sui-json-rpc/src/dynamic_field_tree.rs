@@ -0,0 +1,325 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Walking the dynamic fields under an object, and the dynamic fields under those in turn, so that
+//! tooling inspecting large tables and bags can read them in one call, rather than listing and
+//! reading each level of dynamic fields in turn.
+//!
+//! The tree is walked breadth first, so that when it is cut off by the node limit, it is the
+//! deepest fields that are left out.
+
+use std::collections::VecDeque;
+
+use async_trait::async_trait;
+use futures::future;
+use sui_json_rpc_types::{DynamicFieldTree, DynamicFieldTreeNode, SuiMoveValue};
+use sui_types::base_types::ObjectID;
+use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldType};
+
+use crate::dynamic_field_path::{field_value, uid, PathReader};
+use crate::error::{Error, SuiRpcInputError};
+
+/// Depth walked to if none is requested.
+pub const DEFAULT_TREE_DEPTH: usize = 3;
+
+/// Deepest tree walked.
+pub const MAX_TREE_DEPTH: usize = 16;
+
+/// Most dynamic fields in a tree, as each takes a read of its own.
+pub const MAX_TREE_NODES: usize = 1000;
+
+/// Where the dynamic fields in a tree are listed and read from.
+#[async_trait]
+pub trait TreeReader: PathReader {
+    /// The first `limit` dynamic fields of `parent`.
+    async fn dynamic_fields(
+        &self,
+        parent: ObjectID,
+        limit: usize,
+    ) -> Result<Vec<DynamicFieldInfo>, Error>;
+}
+
+/// The dynamic fields under `parent_object_id`, down to `max_depth` levels of dynamic fields,
+/// and no more than `limit` of them in all.
+pub async fn dynamic_field_tree(
+    reader: &impl TreeReader,
+    parent_object_id: ObjectID,
+    max_depth: Option<usize>,
+    limit: Option<usize>,
+) -> Result<DynamicFieldTree, Error> {
+    let max_depth = max_depth.unwrap_or(DEFAULT_TREE_DEPTH);
+    if max_depth == 0 || max_depth > MAX_TREE_DEPTH {
+        return Err(SuiRpcInputError::GenericInvalid(format!(
+            "Depth must be between 1 and {MAX_TREE_DEPTH}, got {max_depth}"
+        ))
+        .into());
+    }
+    let mut remaining = match limit {
+        Some(limit) if limit > 0 && limit < MAX_TREE_NODES => limit,
+        _ => MAX_TREE_NODES,
+    };
+
+    // Trees and nodes refer to each other by index until the walk is over, so that the trees
+    // still to be listed can be queued.
+    let mut trees = vec![Listing::new(parent_object_id)];
+    let mut nodes = vec![];
+    let mut queue = VecDeque::from([(0, 1)]);
+    while let Some((tree, depth)) = queue.pop_front() {
+        if remaining == 0 {
+            break;
+        }
+
+        let listing = &mut trees[tree];
+        let mut fields = reader
+            .dynamic_fields(listing.parent_object_id, remaining + 1)
+            .await?;
+        listing.has_next_page = fields.len() > remaining;
+        fields.truncate(remaining);
+        listing.next_cursor = listing
+            .has_next_page
+            .then(|| fields.last().map(|f| f.object_id))
+            .flatten();
+        remaining -= fields.len();
+
+        let values =
+            future::try_join_all(fields.iter().map(|info| node_value(reader, info))).await?;
+        for (info, value) in fields.into_iter().zip(values) {
+            let dynamic_fields = value.as_ref().and_then(uid).map(|id| {
+                trees.push(Listing::new(id));
+                trees.len() - 1
+            });
+            if let Some(subtree) = dynamic_fields.filter(|_| depth < max_depth) {
+                queue.push_back((subtree, depth + 1));
+            }
+            trees[tree].fields.push(nodes.len());
+            nodes.push(Some(Node {
+                info,
+                value,
+                dynamic_fields,
+            }));
+        }
+    }
+
+    let mut trees = trees.into_iter().map(Some).collect::<Vec<_>>();
+    Ok(assemble(0, &mut trees, &mut nodes))
+}
+
+/// The dynamic fields of an ID, as listed so far.
+struct Listing {
+    parent_object_id: ObjectID,
+    fields: Vec<usize>,
+    next_cursor: Option<ObjectID>,
+    /// Set until the fields are listed, as there may be some.
+    has_next_page: bool,
+}
+
+impl Listing {
+    fn new(parent_object_id: ObjectID) -> Self {
+        Self {
+            parent_object_id,
+            fields: vec![],
+            next_cursor: None,
+            has_next_page: true,
+        }
+    }
+}
+
+struct Node {
+    info: DynamicFieldInfo,
+    value: Option<SuiMoveValue>,
+    dynamic_fields: Option<usize>,
+}
+
+/// The value of the dynamic field described by `info`, or the object it holds if it is a dynamic
+/// object field.
+async fn node_value(
+    reader: &impl TreeReader,
+    info: &DynamicFieldInfo,
+) -> Result<Option<SuiMoveValue>, Error> {
+    let Some(fields) = reader.object_fields(info.object_id).await? else {
+        return Ok(None);
+    };
+    Ok(match info.type_ {
+        DynamicFieldType::DynamicField => field_value(fields),
+        DynamicFieldType::DynamicObject => Some(SuiMoveValue::Struct(fields)),
+    })
+}
+
+fn assemble(
+    tree: usize,
+    trees: &mut [Option<Listing>],
+    nodes: &mut [Option<Node>],
+) -> DynamicFieldTree {
+    let listing = trees[tree].take().expect("each tree is assembled once");
+    let fields = listing
+        .fields
+        .into_iter()
+        .map(|node| {
+            let node = nodes[node].take().expect("each node is assembled once");
+            DynamicFieldTreeNode {
+                name: node.info.name,
+                type_: node.info.type_,
+                object_type: node.info.object_type,
+                object_id: node.info.object_id,
+                value: node.value,
+                dynamic_fields: node
+                    .dynamic_fields
+                    .map(|subtree| assemble(subtree, trees, nodes)),
+            }
+        })
+        .collect();
+    DynamicFieldTree {
+        parent_object_id: listing.parent_object_id,
+        fields,
+        next_cursor: listing.next_cursor,
+        has_next_page: listing.has_next_page,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::language_storage::TypeTag;
+    use std::collections::{BTreeMap, HashMap};
+    use sui_json_rpc_types::SuiMoveStruct;
+    use sui_types::base_types::{ObjectDigest, SequenceNumber};
+    use sui_types::dynamic_field::DynamicFieldName;
+
+    /// Objects, and the dynamic fields of each parent, by ID.
+    #[derive(Default)]
+    struct TestReader {
+        objects: HashMap<ObjectID, SuiMoveStruct>,
+        fields: HashMap<ObjectID, Vec<DynamicFieldInfo>>,
+    }
+
+    #[async_trait]
+    impl PathReader for TestReader {
+        async fn object_fields(&self, id: ObjectID) -> Result<Option<SuiMoveStruct>, Error> {
+            Ok(self.objects.get(&id).cloned())
+        }
+
+        async fn name_bcs(&self, name: DynamicFieldName) -> Result<(TypeTag, Vec<u8>), Error> {
+            Ok((name.type_, bcs::to_bytes(name.value.as_str().unwrap())?))
+        }
+    }
+
+    #[async_trait]
+    impl TreeReader for TestReader {
+        async fn dynamic_fields(
+            &self,
+            parent: ObjectID,
+            limit: usize,
+        ) -> Result<Vec<DynamicFieldInfo>, Error> {
+            let fields = self.fields.get(&parent).cloned().unwrap_or_default();
+            Ok(fields.into_iter().take(limit).collect())
+        }
+    }
+
+    impl TestReader {
+        /// Add a dynamic field of `parent` at `key`, holding `value`.
+        fn add_field(&mut self, parent: ObjectID, key: u64, value: SuiMoveValue) {
+            let id = ObjectID::random();
+            self.objects.insert(id, object([("value", value)]));
+            self.fields.entry(parent).or_default().push(info(
+                key,
+                DynamicFieldType::DynamicField,
+                id,
+            ));
+        }
+
+        /// Add a dynamic object field of `parent` at `key`, holding an empty object.
+        fn add_object_field(&mut self, parent: ObjectID, key: u64) -> ObjectID {
+            let id = ObjectID::random();
+            self.objects
+                .insert(id, object([("id", SuiMoveValue::UID { id })]));
+            self.fields.entry(parent).or_default().push(info(
+                key,
+                DynamicFieldType::DynamicObject,
+                id,
+            ));
+            id
+        }
+    }
+
+    fn object(fields: impl IntoIterator<Item = (&'static str, SuiMoveValue)>) -> SuiMoveStruct {
+        SuiMoveStruct::WithFields(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    }
+
+    fn info(key: u64, type_: DynamicFieldType, object_id: ObjectID) -> DynamicFieldInfo {
+        DynamicFieldInfo {
+            name: DynamicFieldName {
+                type_: TypeTag::U64,
+                value: serde_json::json!(key.to_string()),
+            },
+            bcs_name: bcs::to_bytes(&key).unwrap(),
+            type_,
+            object_type: "0x42::m::T".to_string(),
+            object_id,
+            version: SequenceNumber::new(),
+            digest: ObjectDigest::random(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_field_tree() {
+        let parent = ObjectID::random();
+        let mut reader = TestReader::default();
+
+        // A bag holding a number, and an object with two dynamic fields of its own, that are
+        // tables.
+        reader.add_field(parent, 0, SuiMoveValue::Number(7));
+        let holder = reader.add_object_field(parent, 1);
+        for key in 0..2 {
+            let inner = ObjectID::random();
+            reader.add_field(
+                holder,
+                key,
+                SuiMoveValue::Struct(object([("id", SuiMoveValue::UID { id: inner })])),
+            );
+            reader.add_field(inner, 0, SuiMoveValue::Number(key as u32));
+        }
+
+        let tree = dynamic_field_tree(&reader, parent, None, None)
+            .await
+            .unwrap();
+        assert_eq!(tree.parent_object_id, parent);
+        assert!(!tree.has_next_page);
+        assert_eq!(tree.fields.len(), 2);
+        assert_eq!(tree.fields[0].value, Some(SuiMoveValue::Number(7)));
+        assert!(tree.fields[0].dynamic_fields.is_none());
+
+        let holder = tree.fields[1].dynamic_fields.as_ref().unwrap();
+        assert_eq!(holder.fields.len(), 2);
+        let leaves = holder.fields[1].dynamic_fields.as_ref().unwrap();
+        assert_eq!(leaves.fields[0].value, Some(SuiMoveValue::Number(1)));
+        assert!(!leaves.has_next_page);
+
+        // Cut off at the depth: the tables at the bottom are not listed.
+        let tree = dynamic_field_tree(&reader, parent, Some(2), None)
+            .await
+            .unwrap();
+        let holder = tree.fields[1].dynamic_fields.as_ref().unwrap();
+        let leaves = holder.fields[0].dynamic_fields.as_ref().unwrap();
+        assert!(leaves.fields.is_empty());
+        assert!(leaves.has_next_page);
+        assert_eq!(leaves.next_cursor, None);
+
+        // Cut off at the node limit: the shallowest fields are listed first.
+        let tree = dynamic_field_tree(&reader, parent, None, Some(3))
+            .await
+            .unwrap();
+        let holder = tree.fields[1].dynamic_fields.as_ref().unwrap();
+        assert_eq!(holder.fields.len(), 1);
+        assert!(holder.has_next_page);
+        assert_eq!(holder.next_cursor, Some(holder.fields[0].object_id));
+
+        assert!(dynamic_field_tree(&reader, parent, Some(17), None)
+            .await
+            .is_err());
+    }
+}
@@ -2,24 +2,38 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::cmp::max;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
+use anyhow::anyhow;
 use async_trait::async_trait;
 use cached::proc_macro::cached;
 use cached::SizedCache;
+use futures::{stream, Stream};
 use itertools::Itertools;
 use jsonrpsee::core::RpcResult;
-use jsonrpsee::RpcModule;
-use tracing::{info, instrument};
+use jsonrpsee::types::SubscriptionResult;
+use jsonrpsee::{RpcModule, SubscriptionSink};
+use tokio::sync::Semaphore;
+use tracing::{info, instrument, warn};
 
 use mysten_metrics::spawn_monitored_task;
 use sui_core::authority::AuthorityState;
-use sui_json_rpc_api::{GovernanceReadApiOpenRpc, GovernanceReadApiServer, JsonRpcMetrics};
-use sui_json_rpc_types::{DelegatedStake, Stake, StakeStatus};
-use sui_json_rpc_types::{SuiCommittee, ValidatorApy, ValidatorApys};
+use sui_json_rpc_api::{
+    validate_limit, GovernanceReadApiOpenRpc, GovernanceReadApiServer, JsonRpcMetrics,
+    QUERY_MAX_RESULT_LIMIT,
+};
+use sui_json_rpc_types::{
+    DelegatedStake, DelegatedStakePage, DelegatedStakeResponse, Page, Stake, StakeRewardEstimate,
+    StakeStatus,
+};
+use sui_json_rpc_types::{
+    SuiCommittee, SuiObjectDataFilter, ValidatorApy, ValidatorApys, ValidatorSetChanges,
+};
 use sui_open_rpc::Module;
-use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::base_types::{MoveObjectType, ObjectID, SuiAddress};
 use sui_types::committee::EpochId;
 use sui_types::dynamic_field::get_dynamic_field_from_store;
 use sui_types::error::{SuiError, UserInputError};
@@ -34,17 +48,67 @@ use sui_types::sui_system_state::{get_validator_from_table, SuiSystemState};
 
 use crate::authority_state::StateRead;
 use crate::error::{Error, RpcInterimResult, SuiRpcInputError};
+use crate::indexer_api::{reject_at_checkpoint, spawn_subscription, DEFAULT_MAX_SUBSCRIPTIONS};
 use crate::{with_tracing, ObjectProvider, SuiRpcModule};
 
+/// How long to wait before checking again whether a new epoch has started, for subscriptions to
+/// validator set changes.
+const EPOCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The changes to the active validator set at the start of each epoch after the one that
+/// `read_system_state` first returns. Ends if the system state can't be read.
+pub fn validator_set_changes<F, Fut>(
+    read_system_state: F,
+) -> impl Stream<Item = ValidatorSetChanges> + Send
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = anyhow::Result<SuiSystemStateSummary>> + Send,
+{
+    stream::unfold(
+        (read_system_state, None),
+        |(read_system_state, previous): (F, Option<SuiSystemStateSummary>)| async move {
+            let read = || {
+                let system_state = read_system_state();
+                async move {
+                    match system_state.await {
+                        Ok(system_state) => Some(system_state),
+                        Err(e) => {
+                            warn!("Failed to read the system state for subscription: {e}");
+                            None
+                        }
+                    }
+                }
+            };
+            let previous = match previous {
+                Some(previous) => previous,
+                None => read().await?,
+            };
+            loop {
+                tokio::time::sleep(EPOCH_POLL_INTERVAL).await;
+                let current = read().await?;
+                if current.epoch > previous.epoch {
+                    let changes = ValidatorSetChanges::between(&previous, &current);
+                    return Some((changes, (read_system_state, Some(current))));
+                }
+            }
+        },
+    )
+}
+
 #[derive(Clone)]
 pub struct GovernanceReadApi {
     state: Arc<dyn StateRead>,
     pub metrics: Arc<JsonRpcMetrics>,
+    subscription_semaphore: Arc<Semaphore>,
 }
 
 impl GovernanceReadApi {
     pub fn new(state: Arc<AuthorityState>, metrics: Arc<JsonRpcMetrics>) -> Self {
-        Self { state, metrics }
+        Self {
+            state,
+            metrics,
+            subscription_semaphore: Arc::new(Semaphore::new(DEFAULT_MAX_SUBSCRIPTIONS)),
+        }
     }
 
     async fn get_staked_sui(&self, owner: SuiAddress) -> Result<Vec<StakedSui>, Error> {
@@ -109,6 +173,67 @@ impl GovernanceReadApi {
         self.get_delegated_stakes(stakes).await
     }
 
+    async fn try_get_stakes_by_ids(
+        &self,
+        staked_sui_ids: Vec<ObjectID>,
+    ) -> Result<Vec<DelegatedStakeResponse>, Error> {
+        let state = self.state.clone();
+        let ids = staked_sui_ids.clone();
+        let stakes_read = spawn_monitored_task!(async move {
+            ids.iter()
+                .map(|id| state.get_object_read(id))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await??;
+
+        // Objects that aren't StakedSui are left out, to be reported as not found.
+        let mut stakes: Vec<(StakedSui, bool)> = vec![];
+        let mut unstaked = HashSet::new();
+        for stake in stakes_read.into_iter() {
+            match stake {
+                ObjectRead::Exists(_, o, _) => {
+                    if let Ok(stake) = StakedSui::try_from(&o) {
+                        stakes.push((stake, true));
+                    }
+                }
+                ObjectRead::Deleted(oref) => {
+                    match self
+                        .state
+                        .find_object_lt_or_eq_version(&oref.0, &oref.1.one_before().unwrap())
+                        .await?
+                    {
+                        Some(o) => {
+                            if let Ok(stake) = StakedSui::try_from(&o) {
+                                stakes.push((stake, false));
+                            }
+                        }
+                        None => {
+                            unstaked.insert(oref.0);
+                        }
+                    }
+                }
+                ObjectRead::NotExists(_) => {}
+            }
+        }
+
+        let stakes = if stakes.is_empty() {
+            vec![]
+        } else {
+            self.get_delegated_stakes(stakes).await?
+        };
+        Ok(DelegatedStakeResponse::for_ids(
+            &staked_sui_ids,
+            stakes,
+            |id| {
+                if unstaked.contains(&id) {
+                    DelegatedStakeResponse::Unstaked(id)
+                } else {
+                    DelegatedStakeResponse::NotFound(id)
+                }
+            },
+        ))
+    }
+
     async fn get_stakes(&self, owner: SuiAddress) -> Result<Vec<DelegatedStake>, Error> {
         let timer = self.metrics.get_stake_sui_latency.start_timer();
         let stakes = self.get_staked_sui(owner).await?;
@@ -126,6 +251,48 @@ impl GovernanceReadApi {
         .await?
     }
 
+    async fn get_stakes_paginated(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<DelegatedStakePage, Error> {
+        let state = self.state.clone();
+        let (stakes, next_cursor, has_next_page) = spawn_monitored_task!(async move {
+            let filter = SuiObjectDataFilter::StructType(MoveObjectType::staked_sui().into());
+            let mut objects =
+                state.get_owner_objects_with_limit(owner, cursor, limit + 1, Some(filter))?;
+
+            // objects here are of size (limit + 1), where the last one is the cursor for the next page
+            let has_next_page = objects.len() > limit;
+            objects.truncate(limit);
+            let next_cursor = objects
+                .last()
+                .map_or(cursor, |o_info| Some(o_info.object_id));
+
+            let mut stakes = vec![];
+            for o_info in objects {
+                if let ObjectRead::Exists(_, o, _) = state.get_object_read(&o_info.object_id)? {
+                    stakes.push((StakedSui::try_from(&o)?, true));
+                }
+            }
+            Ok::<_, Error>((stakes, next_cursor, has_next_page))
+        })
+        .await??;
+
+        let data = if stakes.is_empty() {
+            vec![]
+        } else {
+            self.get_delegated_stakes(stakes).await?
+        };
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+            truncated: false,
+        })
+    }
+
     async fn get_delegated_stakes(
         &self,
         stakes: Vec<(StakedSui, bool)>,
@@ -159,32 +326,15 @@ impl GovernanceReadApi {
                     "Cannot find rates for staking pool {pool_id}".to_string(),
                 )
             })?;
-            let current_rate = rate_table.rates.first().map(|(_, rate)| rate);
 
             let mut delegations = vec![];
             for (stake, exists) in stakes {
                 let status = if !exists {
                     StakeStatus::Unstaked
                 } else if system_state_summary.epoch >= stake.activation_epoch() {
-                    let estimated_reward = if let Some(current_rate) = current_rate {
-                        let stake_rate = rate_table
-                            .rates
-                            .iter()
-                            .find_map(|(epoch, rate)| {
-                                if *epoch == stake.activation_epoch() {
-                                    Some(rate.clone())
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap_or_default();
-                        let estimated_reward = ((stake_rate.rate() / current_rate.rate()) - 1.0)
-                            * stake.principal() as f64;
-                        max(0, estimated_reward.round() as u64)
-                    } else {
-                        0
-                    };
-                    StakeStatus::Active { estimated_reward }
+                    StakeStatus::Active {
+                        estimated_reward: estimated_reward(&stake, rate_table),
+                    }
                 } else {
                     StakeStatus::Pending
                 };
@@ -195,6 +345,7 @@ impl GovernanceReadApi {
                     stake_active_epoch: stake.activation_epoch(),
                     principal: stake.principal(),
                     status,
+                    fiat_value: None,
                 })
             }
             delegated_stakes.push(DelegatedStake {
@@ -206,6 +357,59 @@ impl GovernanceReadApi {
         Ok(delegated_stakes)
     }
 
+    async fn get_stake_reward_estimate(
+        &self,
+        staked_sui_id: ObjectID,
+        target_epoch: EpochId,
+    ) -> Result<StakeRewardEstimate, Error> {
+        let state = self.state.clone();
+        let stake =
+            match spawn_monitored_task!(async move { state.get_object_read(&staked_sui_id) })
+                .await??
+            {
+                ObjectRead::Exists(_, o, _) => StakedSui::try_from(&o)?,
+                _ => Err(SuiRpcInputError::UserInputError(
+                    UserInputError::ObjectNotFound {
+                        object_id: staked_sui_id,
+                        version: None,
+                    },
+                ))?,
+            };
+
+        let system_state_summary = self.get_system_state()?.into_sui_system_state_summary();
+        let epoch = system_state_summary.epoch;
+        if target_epoch < epoch {
+            Err(SuiRpcInputError::GenericInvalid(format!(
+                "Target epoch {target_epoch} is before the current epoch {epoch}"
+            )))?
+        }
+
+        let rates = exchange_rates(&self.state, epoch)
+            .await?
+            .into_iter()
+            .find(|rates| rates.pool_id == stake.pool_id())
+            .ok_or_else(|| {
+                SuiRpcInputError::GenericNotFound(format!(
+                    "Cannot find rates for staking pool {}",
+                    stake.pool_id()
+                ))
+            })?;
+        let apy = calculate_apys(
+            system_state_summary.stake_subsidy_start_epoch,
+            vec![rates.clone()],
+        )
+        .first()
+        .map_or(0.0, |apy| apy.apy);
+
+        Ok(estimate_stake_reward(
+            &stake,
+            &rates,
+            apy,
+            epoch,
+            target_epoch,
+        ))
+    }
+
     fn get_system_state(&self) -> Result<SuiSystemState, Error> {
         Ok(self.state.get_system_state()?)
     }
@@ -217,13 +421,58 @@ impl GovernanceReadApiServer for GovernanceReadApi {
     async fn get_stakes_by_ids(
         &self,
         staked_sui_ids: Vec<ObjectID>,
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<Vec<DelegatedStake>> {
-        with_tracing!(async move { self.get_stakes_by_ids(staked_sui_ids).await })
+        with_tracing!(async move {
+            reject_at_checkpoint(at_checkpoint)?;
+            self.get_stakes_by_ids(staked_sui_ids).await
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn try_get_stakes_by_ids(
+        &self,
+        staked_sui_ids: Vec<ObjectID>,
+    ) -> RpcResult<Vec<DelegatedStakeResponse>> {
+        with_tracing!(async move { self.try_get_stakes_by_ids(staked_sui_ids).await })
     }
 
     #[instrument(skip(self))]
-    async fn get_stakes(&self, owner: SuiAddress) -> RpcResult<Vec<DelegatedStake>> {
-        with_tracing!(async move { self.get_stakes(owner).await })
+    async fn get_stakes(
+        &self,
+        owner: SuiAddress,
+        at_checkpoint: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<DelegatedStake>> {
+        with_tracing!(async move {
+            reject_at_checkpoint(at_checkpoint)?;
+            self.get_stakes(owner).await
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_stakes_paginated(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<DelegatedStakePage> {
+        with_tracing!(async move {
+            let limit =
+                validate_limit(limit, *QUERY_MAX_RESULT_LIMIT).map_err(SuiRpcInputError::from)?;
+            self.get_stakes_paginated(owner, cursor, limit).await
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_stake_reward_estimate(
+        &self,
+        staked_sui_id: ObjectID,
+        target_epoch: BigInt<u64>,
+    ) -> RpcResult<StakeRewardEstimate> {
+        with_tracing!(async move {
+            self.get_stake_reward_estimate(staked_sui_id, *target_epoch)
+                .await
+        })
     }
 
     #[instrument(skip(self))]
@@ -237,8 +486,12 @@ impl GovernanceReadApiServer for GovernanceReadApi {
     }
 
     #[instrument(skip(self))]
-    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary> {
+    async fn get_latest_sui_system_state(
+        &self,
+        at_checkpoint: Option<BigInt<u64>>,
+    ) -> RpcResult<SuiSystemStateSummary> {
         with_tracing!(async move {
+            reject_at_checkpoint(at_checkpoint)?;
             Ok(self
                 .state
                 .get_system_state()
@@ -259,7 +512,7 @@ impl GovernanceReadApiServer for GovernanceReadApi {
     async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
         info!("get_validator_apy");
         let system_state_summary: SuiSystemStateSummary =
-            self.get_latest_sui_system_state().await?;
+            self.get_latest_sui_system_state(None).await?;
 
         let exchange_rate_table = exchange_rates(&self.state, system_state_summary.epoch)
             .await
@@ -275,6 +528,24 @@ impl GovernanceReadApiServer for GovernanceReadApi {
             epoch: system_state_summary.epoch,
         })
     }
+
+    fn subscribe_validator_set_changes(&self, sink: SubscriptionSink) -> SubscriptionResult {
+        let permit = self
+            .subscription_semaphore
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| anyhow!("Resources exhausted"))?;
+        let state = self.state.clone();
+        spawn_subscription(
+            sink,
+            Box::pin(validator_set_changes(move || {
+                let state = state.clone();
+                async move { Ok(state.get_system_state()?.into_sui_system_state_summary()) }
+            })),
+            Some(permit),
+        );
+        Ok(())
+    }
 }
 
 pub fn calculate_apys(
@@ -352,10 +623,126 @@ fn test_apys_calculation_filter_outliers() {
 }
 
 // APY_e = (ER_e+1 / ER_e) ^ 365
-fn calculate_apy((rate_e, rate_e_1): (PoolTokenExchangeRate, PoolTokenExchangeRate)) -> f64 {
+pub fn calculate_apy((rate_e, rate_e_1): (PoolTokenExchangeRate, PoolTokenExchangeRate)) -> f64 {
     (rate_e.rate() / rate_e_1.rate()).powf(365.0) - 1.0
 }
 
+/// The reward `stake` has earned by the latest of its pool's `rates`, once it's active.
+pub fn estimated_reward(stake: &StakedSui, rates: &ValidatorExchangeRates) -> u64 {
+    let Some((_, current_rate)) = rates.rates.first() else {
+        return 0;
+    };
+    let stake_rate = rates
+        .rates
+        .iter()
+        .find_map(|(epoch, rate)| {
+            if *epoch == stake.activation_epoch() {
+                Some(rate.clone())
+            } else {
+                None
+            }
+        })
+        .unwrap_or_default();
+    let estimated_reward =
+        ((stake_rate.rate() / current_rate.rate()) - 1.0) * stake.principal() as f64;
+    max(0, estimated_reward.round() as u64)
+}
+
+/// Estimate the reward of `stake` in `epoch`, from its pool's `rates`, and project it to
+/// `target_epoch` at the pool's `apy`. Rewards are compounded each epoch, at the rate that adds
+/// up to `apy` over a year of daily epochs, as in [calculate_apy].
+pub fn estimate_stake_reward(
+    stake: &StakedSui,
+    rates: &ValidatorExchangeRates,
+    apy: f64,
+    epoch: EpochId,
+    target_epoch: EpochId,
+) -> StakeRewardEstimate {
+    let accrued_reward = if epoch >= stake.activation_epoch() {
+        estimated_reward(stake, rates)
+    } else {
+        0
+    };
+    let epochs = target_epoch.saturating_sub(max(epoch, stake.activation_epoch()));
+    let projected_value =
+        (stake.principal() + accrued_reward) as f64 * (1.0 + apy).powf(epochs as f64 / 365.0);
+    let projected_reward = max(
+        accrued_reward,
+        (projected_value.round() as u64).saturating_sub(stake.principal()),
+    );
+    StakeRewardEstimate {
+        staked_sui_id: stake.id(),
+        validator_address: rates.address,
+        staking_pool: rates.pool_id,
+        principal: stake.principal(),
+        epoch,
+        accrued_reward,
+        target_epoch,
+        apy,
+        projected_reward,
+    }
+}
+
+#[cfg(test)]
+fn stake_reward_fixture(activation_epoch: EpochId) -> (StakedSui, ValidatorExchangeRates) {
+    let pool_id = ObjectID::random();
+    // A StakedSui's fields are private, so it is built from its BCS encoding: its UID, pool ID,
+    // activation epoch and principal balance.
+    let stake = bcs::to_bytes(&(ObjectID::random(), pool_id, activation_epoch, 1_000_000u64));
+    let stake: StakedSui = bcs::from_bytes(&stake.unwrap()).unwrap();
+    // Every SUI staked at epoch 8 was worth 10% more by epoch 10.
+    let rates = serde_json::from_value(serde_json::json!([
+        [10, {"sui_amount": 1100, "pool_token_amount": 1000}],
+        [9, {"sui_amount": 1050, "pool_token_amount": 1000}],
+        [8, {"sui_amount": 1000, "pool_token_amount": 1000}],
+    ]))
+    .unwrap();
+    let rates = ValidatorExchangeRates {
+        address: SuiAddress::random_for_testing_only(),
+        pool_id,
+        active: true,
+        rates,
+    };
+    (stake, rates)
+}
+
+#[test]
+fn test_estimate_active_stake_reward() {
+    let (stake, rates) = stake_reward_fixture(8);
+    assert_eq!(estimated_reward(&stake, &rates), 100_000);
+
+    // A year of epochs at 5% grows both the principal and the reward accrued so far.
+    let estimate = estimate_stake_reward(&stake, &rates, 0.05, 10, 375);
+    assert_eq!(estimate.accrued_reward, 100_000);
+    assert_eq!(estimate.projected_reward, 155_000);
+
+    // Half a year compounds to the square root of the yearly growth.
+    let estimate = estimate_stake_reward(&stake, &rates, 0.05, 10, 10 + 365 / 2);
+    let expected = 1_100_000.0 * 1.05_f64.powf(182.0 / 365.0) - 1_000_000.0;
+    assert_eq!(estimate.projected_reward, expected.round() as u64);
+}
+
+#[test]
+fn test_estimate_pending_stake_reward() {
+    let (stake, rates) = stake_reward_fixture(12);
+
+    // Nothing has accrued yet, and the stake only earns from its activation epoch on.
+    let estimate = estimate_stake_reward(&stake, &rates, 0.05, 10, 12 + 365);
+    assert_eq!(estimate.accrued_reward, 0);
+    assert_eq!(estimate.projected_reward, 50_000);
+
+    let estimate = estimate_stake_reward(&stake, &rates, 0.05, 10, 12);
+    assert_eq!(estimate.projected_reward, 0);
+}
+
+#[test]
+fn test_estimate_stake_reward_in_current_epoch() {
+    let (stake, rates) = stake_reward_fixture(8);
+    let estimate = estimate_stake_reward(&stake, &rates, 0.05, 10, 10);
+    assert_eq!(estimate.accrued_reward, 100_000);
+    assert_eq!(estimate.projected_reward, 100_000);
+}
+
 /// Cached exchange rates for validators for the given epoch, the cache size is 1, it will be cleared when the epoch changes.
 /// rates are in descending order by epoch.
 #[cached(
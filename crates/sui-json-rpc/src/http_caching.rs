@@ -0,0 +1,197 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! HTTP caching headers, so that a server can sit behind a CDN or caching proxy. Methods are put
+//! into categories by config:
+//!
+//! - immutable methods read data that never changes once it exists (e.g. a transaction by its
+//!   digest), so their successful responses can be cached indefinitely,
+//! - live methods read data that changes as the chain advances (e.g. an object's latest
+//!   version), so their successful responses can only be cached briefly,
+//! - all other methods (e.g. executing a transaction), and all errors, are never cached.
+//!
+//! Cacheable responses carry an `ETag` derived from their body, and requests whose
+//! `If-None-Match` matches it are answered with `304 Not Modified` and no body. As most caches
+//! only cache `GET` requests, immutable and live methods can also be called with one, passing the
+//! request's params as a JSON array:
+//!
+//! ```text
+//! GET /json-rpc?method=sui_getTransactionBlock&params=["0x.."]
+//! ```
+//!
+//! Other methods can only be called with `POST`, so that a `GET` never changes any state.
+//!
+//! No caching headers are sent until a config is set.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use serde::{Deserialize, Serialize};
+
+/// How long immutable responses can be cached for: a year, the longest that caches honour.
+const IMMUTABLE_MAX_AGE_SECS: u64 = 365 * 24 * 60 * 60;
+
+/// Number of bytes of the body's digest used in its `ETag`.
+const ETAG_BYTES: usize = 16;
+
+const NO_STORE: &str = "no-store";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct HttpCachingConfig {
+    /// Methods whose successful responses never change.
+    pub immutable_methods: BTreeSet<String>,
+    /// Methods whose successful responses can be served for up to `live-max-age-secs`.
+    pub live_methods: BTreeSet<String>,
+    /// How long, in seconds, caches can serve the responses of live methods before revalidating
+    /// them.
+    pub live_max_age_secs: u64,
+}
+
+/// The caching headers for a response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CacheHeaders {
+    pub cache_control: String,
+    /// Only set for responses that can be cached.
+    pub etag: Option<String>,
+}
+
+/// The caching headers to send with responses, according to a config that can be updated while
+/// the server is running. Clones share the same config.
+#[derive(Clone, Debug, Default)]
+pub struct HttpCaching {
+    config: Arc<ArcSwap<HttpCachingConfig>>,
+}
+
+impl HttpCaching {
+    pub fn set_config(&self, config: HttpCachingConfig) {
+        self.config.store(Arc::new(config));
+    }
+
+    /// Whether the successful responses of `method` can be cached, so that it can be called with
+    /// a `GET` request.
+    pub(crate) fn is_cacheable(&self, method: &str) -> bool {
+        let config = self.config.load();
+        config.immutable_methods.contains(method) || config.live_methods.contains(method)
+    }
+
+    /// The caching headers for a response to a call to `method` (if the request could be
+    /// parsed), with `body`.
+    pub(crate) fn headers(
+        &self,
+        method: Option<&str>,
+        success: bool,
+        body: &str,
+    ) -> Option<CacheHeaders> {
+        let config = self.config.load();
+        if config.immutable_methods.is_empty() && config.live_methods.is_empty() {
+            return None;
+        }
+
+        let cache_control = match method {
+            Some(method) if success && config.immutable_methods.contains(method) => {
+                format!("public, max-age={IMMUTABLE_MAX_AGE_SECS}, immutable")
+            }
+            Some(method) if success && config.live_methods.contains(method) => {
+                format!("public, max-age={}", config.live_max_age_secs)
+            }
+            _ => {
+                return Some(CacheHeaders {
+                    cache_control: NO_STORE.to_string(),
+                    etag: None,
+                })
+            }
+        };
+
+        Some(CacheHeaders {
+            cache_control,
+            etag: Some(etag(body)),
+        })
+    }
+}
+
+/// A strong `ETag` for a response with `body`.
+fn etag(body: &str) -> String {
+    let digest = Blake2b256::digest(body.as_bytes());
+    format!("\"{}\"", Hex::encode(&digest.digest[..ETAG_BYTES]))
+}
+
+/// Whether an `If-None-Match` header with value `if_none_match` matches `etag`, meaning the
+/// client already has the response.
+pub(crate) fn not_modified(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        // Weak comparison, as caches may weaken the tags they pass on.
+        candidate == "*" || candidate.trim_start_matches("W/") == etag
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories() {
+        let caching = HttpCaching::default();
+        let body = r#"{"jsonrpc":"2.0","result":{},"id":1}"#;
+
+        // Nothing is sent until a config is set.
+        assert_eq!(
+            caching.headers(Some("sui_getTransactionBlock"), true, body),
+            None
+        );
+
+        caching.set_config(HttpCachingConfig {
+            immutable_methods: BTreeSet::from(["sui_getTransactionBlock".to_string()]),
+            live_methods: BTreeSet::from(["sui_getObject".to_string()]),
+            live_max_age_secs: 2,
+        });
+
+        let immutable = caching
+            .headers(Some("sui_getTransactionBlock"), true, body)
+            .unwrap();
+        assert_eq!(
+            immutable.cache_control,
+            "public, max-age=31536000, immutable"
+        );
+
+        let live = caching.headers(Some("sui_getObject"), true, body).unwrap();
+        assert_eq!(live.cache_control, "public, max-age=2");
+        assert_eq!(live.etag, immutable.etag);
+
+        let other = caching
+            .headers(Some("sui_executeTransactionBlock"), true, body)
+            .unwrap();
+        assert_eq!(other.cache_control, NO_STORE);
+        assert_eq!(other.etag, None);
+
+        // Only cacheable methods can be called with GET.
+        assert!(caching.is_cacheable("sui_getTransactionBlock"));
+        assert!(caching.is_cacheable("sui_getObject"));
+        assert!(!caching.is_cacheable("sui_executeTransactionBlock"));
+
+        // Errors are never cached.
+        let error = caching
+            .headers(Some("sui_getTransactionBlock"), false, body)
+            .unwrap();
+        assert_eq!(error.cache_control, NO_STORE);
+        assert_eq!(caching.headers(None, false, body).unwrap().etag, None);
+
+        // Different bodies get different tags.
+        let changed = caching
+            .headers(Some("sui_getObject"), true, r#"{"id":2}"#)
+            .unwrap();
+        assert_ne!(changed.etag, live.etag);
+    }
+
+    #[test]
+    fn test_not_modified() {
+        let tag = etag("body");
+        assert!(not_modified(&tag, &tag));
+        assert!(not_modified(&format!("\"other\", W/{tag}"), &tag));
+        assert!(not_modified("*", &tag));
+        assert!(!not_modified("\"other\"", &tag));
+    }
+}
@@ -3,7 +3,7 @@
 
 use anyhow::bail;
 use async_trait::async_trait;
-use futures::{future, Stream};
+use futures::{future, stream, Stream, StreamExt};
 use jsonrpsee::{
     core::{error::SubscriptionClosed, RpcResult},
     types::SubscriptionResult,
@@ -14,31 +14,38 @@ use move_core_types::language_storage::TypeTag;
 use mysten_metrics::spawn_monitored_task;
 use serde::Serialize;
 use std::sync::Arc;
+use std::time::Duration;
 use sui_core::authority::AuthorityState;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_api::{
-    cap_page_limit, validate_limit, IndexerApiOpenRpc, IndexerApiServer, JsonRpcMetrics,
-    ReadApiServer, QUERY_MAX_RESULT_LIMIT,
+    cap_page_limit, max_page_data_size, validate_limit, IndexerApiOpenRpc, IndexerApiServer,
+    JsonRpcMetrics, ReadApiServer, QUERY_MAX_RESULT_LIMIT,
 };
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectDataOptions,
-    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseQuery, TransactionBlocksPage, TransactionFilter,
+    CheckpointedTransactionEffects, DynamicFieldPage, DynamicFieldPathSegment, DynamicFieldTree,
+    EffectsWithInput, EventFilter, EventPage, Filter, ObjectsPage, Page, SuiMoveStruct,
+    SuiMoveValue, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiParsedData,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
+    TransactionEffectsFilter, TransactionFilter,
 };
 use sui_open_rpc::Module;
 use sui_storage::key_value_store::TransactionKeyValueStore;
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
     digests::TransactionDigest,
-    dynamic_field::{DynamicFieldName, Field},
+    dynamic_field::{DynamicFieldInfo, DynamicFieldName, Field},
     error::SuiObjectResponseError,
     event::EventID,
+    messages_checkpoint::CheckpointSequenceNumber,
+    sui_serde::BigInt,
 };
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::{debug, instrument, warn};
 
 use crate::{
     authority_state::{StateRead, StateReadResult},
+    dynamic_field_path::{resolve_path, PathReader},
+    dynamic_field_tree::{dynamic_field_tree, TreeReader},
     error::{Error, SuiRpcInputError},
     name_service::{Domain, NameRecord, NameServiceConfig, NameServiceError},
     with_tracing, SuiRpcModule,
@@ -70,7 +77,91 @@ pub fn spawn_subscription<S, T>(
         };
     });
 }
-const DEFAULT_MAX_SUBSCRIPTIONS: usize = 100;
+pub const DEFAULT_MAX_SUBSCRIPTIONS: usize = 100;
+
+/// Fullnodes only serve their latest state, so reads pinned to a checkpoint are left to the
+/// indexer, which keeps the history needed to serve them.
+pub(crate) fn reject_at_checkpoint(
+    at_checkpoint: Option<BigInt<u64>>,
+) -> Result<(), SuiRpcInputError> {
+    match at_checkpoint {
+        Some(checkpoint) => Err(SuiRpcInputError::GenericInvalid(format!(
+            "Reads at checkpoint {checkpoint} are not supported by fullnodes, only by the indexer"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// How long to wait before checking again for the next checkpoint to stream, once a subscription
+/// has caught up with the latest executed checkpoint.
+const CHECKPOINT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The effects of the transactions matching `filter` in each checkpoint from `next_checkpoint`
+/// on, as the checkpoints are executed. Ends if a checkpoint can't be read, e.g. because it has
+/// been pruned.
+fn checkpointed_transaction_effects(
+    state: Arc<dyn StateRead>,
+    filter: TransactionEffectsFilter,
+    next_checkpoint: CheckpointSequenceNumber,
+) -> impl Stream<Item = CheckpointedTransactionEffects> + Send {
+    stream::unfold(next_checkpoint, move |checkpoint| {
+        let state = state.clone();
+        let filter = filter.clone();
+        async move {
+            loop {
+                match state.get_latest_checkpoint_sequence_number() {
+                    Ok(latest) if latest >= checkpoint => break,
+                    Ok(_) => tokio::time::sleep(CHECKPOINT_POLL_INTERVAL).await,
+                    Err(e) => {
+                        warn!("Failed to get the latest checkpoint for subscription: {e}");
+                        return None;
+                    }
+                }
+            }
+            match checkpoint_transaction_effects(state.as_ref(), &filter, checkpoint).await {
+                Ok(effects) => Some((stream::iter(effects), checkpoint + 1)),
+                Err(e) => {
+                    warn!(
+                        checkpoint,
+                        "Failed to read checkpoint for subscription: {e}"
+                    );
+                    None
+                }
+            }
+        }
+    })
+    .flatten()
+}
+
+/// The effects of the transactions in `checkpoint` that match `filter`, in execution order.
+async fn checkpoint_transaction_effects(
+    state: &dyn StateRead,
+    filter: &TransactionEffectsFilter,
+    checkpoint: CheckpointSequenceNumber,
+) -> anyhow::Result<Vec<CheckpointedTransactionEffects>> {
+    let summary = state.get_verified_checkpoint_by_sequence_number(checkpoint)?;
+    let contents = state.get_checkpoint_contents(summary.content_digest)?;
+    let digests: Vec<_> = contents.iter().map(|d| d.transaction).collect();
+    let (transactions, effects, _) = state.multi_get(&digests, &digests, &[]).await?;
+
+    let mut matching = vec![];
+    for ((digest, transaction), effects) in digests.iter().zip(transactions).zip(effects) {
+        let (Some(transaction), Some(effects)) = (transaction, effects) else {
+            bail!("Transaction {digest} of checkpoint {checkpoint} not found");
+        };
+        let item = EffectsWithInput {
+            input: transaction.data().transaction_data().clone(),
+            effects: effects.try_into()?,
+        };
+        if filter.matches(&item) {
+            matching.push(CheckpointedTransactionEffects {
+                checkpoint,
+                effects: item.effects,
+            });
+        }
+    }
+    Ok(matching)
+}
 
 pub struct IndexerApi<R> {
     state: Arc<dyn StateRead>,
@@ -123,6 +214,21 @@ impl<R: ReadApiServer> IndexerApi<R> {
         }
     }
 
+    /// The first checkpoint to stream for a subscription resuming after `cursor`.
+    fn next_subscription_checkpoint(
+        &self,
+        cursor: Option<BigInt<u64>>,
+    ) -> anyhow::Result<CheckpointSequenceNumber> {
+        let latest = self.state.get_latest_checkpoint_sequence_number()?;
+        match cursor.map(|c| *c) {
+            Some(cursor) if cursor > latest => {
+                bail!("Cursor {cursor} is ahead of the latest executed checkpoint {latest}")
+            }
+            Some(cursor) => Ok(cursor + 1),
+            None => Ok(latest + 1),
+        }
+    }
+
     fn get_latest_checkpoint_timestamp_ms(&self) -> StateReadResult<u64> {
         let latest_checkpoint = self.state.get_latest_checkpoint_sequence_number()?;
 
@@ -143,8 +249,10 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         query: Option<SuiObjectResponseQuery>,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<ObjectsPage> {
         with_tracing!(async move {
+            reject_at_checkpoint(at_checkpoint)?;
             let limit =
                 validate_limit(limit, *QUERY_MAX_RESULT_LIMIT).map_err(SuiRpcInputError::from)?;
             self.metrics.get_owned_objects_limit.report(limit as u64);
@@ -186,6 +294,7 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                 data,
                 next_cursor,
                 has_next_page,
+                truncated: false,
             })
         })
     }
@@ -198,8 +307,10 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         cursor: Option<TransactionDigest>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<TransactionBlocksPage> {
         with_tracing!(async move {
+            reject_at_checkpoint(at_checkpoint)?;
             let limit = cap_page_limit(limit);
             self.metrics.query_tx_blocks_limit.report(limit as u64);
             let descending = descending_order.unwrap_or_default();
@@ -234,17 +345,21 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                     .await?
             };
 
+            let mut page = Page {
+                data,
+                next_cursor,
+                has_next_page,
+                truncated: false,
+            };
+            page.truncate_to_size(max_page_data_size(), |tx| tx.digest);
+
             self.metrics
                 .query_tx_blocks_result_size
-                .report(data.len() as u64);
+                .report(page.data.len() as u64);
             self.metrics
                 .query_tx_blocks_result_size_total
-                .inc_by(data.len() as u64);
-            Ok(Page {
-                data,
-                next_cursor,
-                has_next_page,
-            })
+                .inc_by(page.data.len() as u64);
+            Ok(page)
         })
     }
     #[instrument(skip(self))]
@@ -255,8 +370,10 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         cursor: Option<EventID>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        at_checkpoint: Option<BigInt<u64>>,
     ) -> RpcResult<EventPage> {
         with_tracing!(async move {
+            reject_at_checkpoint(at_checkpoint)?;
             let descending = descending_order.unwrap_or_default();
             let limit = cap_page_limit(limit);
             self.metrics.query_events_limit.report(limit as u64);
@@ -275,17 +392,21 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
             let has_next_page = data.len() > limit;
             data.truncate(limit);
             let next_cursor = data.last().map_or(cursor, |e| Some(e.id));
+            let mut page = EventPage {
+                data,
+                next_cursor,
+                has_next_page,
+                truncated: false,
+            };
+            page.truncate_to_size(max_page_data_size(), |e| e.id);
+
             self.metrics
                 .query_events_result_size
-                .report(data.len() as u64);
+                .report(page.data.len() as u64);
             self.metrics
                 .query_events_result_size_total
-                .inc_by(data.len() as u64);
-            Ok(EventPage {
-                data,
-                next_cursor,
-                has_next_page,
-            })
+                .inc_by(page.data.len() as u64);
+            Ok(page)
         })
     }
 
@@ -318,6 +439,26 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         Ok(())
     }
 
+    fn subscribe_transaction_effects(
+        &self,
+        sink: SubscriptionSink,
+        filter: TransactionEffectsFilter,
+        cursor: Option<BigInt<u64>>,
+    ) -> SubscriptionResult {
+        let next_checkpoint = self.next_subscription_checkpoint(cursor)?;
+        let permit = self.acquire_subscribe_permit()?;
+        spawn_subscription(
+            sink,
+            Box::pin(checkpointed_transaction_effects(
+                self.state.clone(),
+                filter,
+                next_checkpoint,
+            )),
+            Some(permit),
+        );
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     async fn get_dynamic_fields(
         &self,
@@ -346,6 +487,7 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                 data: data.into_iter().map(|(_, w)| w).collect(),
                 next_cursor,
                 has_next_page,
+                truncated: false,
             })
         })
     }
@@ -377,6 +519,27 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         })
     }
 
+    #[instrument(skip(self))]
+    async fn get_dynamic_field_value_by_path(
+        &self,
+        parent_object_id: ObjectID,
+        path: Vec<DynamicFieldPathSegment>,
+    ) -> RpcResult<Option<SuiMoveValue>> {
+        with_tracing!(async move { resolve_path(self, parent_object_id, path).await })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_dynamic_field_tree(
+        &self,
+        parent_object_id: ObjectID,
+        max_depth: Option<usize>,
+        limit: Option<usize>,
+    ) -> RpcResult<DynamicFieldTree> {
+        with_tracing!(
+            async move { dynamic_field_tree(self, parent_object_id, max_depth, limit).await }
+        )
+    }
+
     #[instrument(skip(self))]
     async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
         with_tracing!(async move {
@@ -460,6 +623,7 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                 data: vec![],
                 next_cursor: None,
                 has_next_page: false,
+                truncated: false,
             };
 
             let Some(field_reverse_record_object) =
@@ -495,6 +659,36 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
     }
 }
 
+#[async_trait]
+impl<R: ReadApiServer> PathReader for IndexerApi<R> {
+    async fn object_fields(&self, id: ObjectID) -> Result<Option<SuiMoveStruct>, Error> {
+        let response = self
+            .read_api
+            .get_object(id, Some(SuiObjectDataOptions::new().with_content()))
+            .await?;
+        Ok(match response.data.and_then(|data| data.content) {
+            Some(SuiParsedData::MoveObject(object)) => Some(object.fields),
+            _ => None,
+        })
+    }
+
+    async fn name_bcs(&self, name: DynamicFieldName) -> Result<(TypeTag, Vec<u8>), Error> {
+        Ok(self.extract_values_from_dynamic_field_name(name)?)
+    }
+}
+
+#[async_trait]
+impl<R: ReadApiServer> TreeReader for IndexerApi<R> {
+    async fn dynamic_fields(
+        &self,
+        parent: ObjectID,
+        limit: usize,
+    ) -> Result<Vec<DynamicFieldInfo>, Error> {
+        let fields = self.state.get_dynamic_fields(parent, None, limit)?;
+        Ok(fields.into_iter().map(|(_, info)| info).collect())
+    }
+}
+
 impl<R: ReadApiServer> SuiRpcModule for IndexerApi<R> {
     fn rpc(self) -> RpcModule<Self> {
         self.into_rpc()
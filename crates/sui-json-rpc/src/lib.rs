@@ -1,10 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
 use std::env;
+use std::future::Future;
 use std::net::SocketAddr;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
+use futures::future::BoxFuture;
+use futures::FutureExt;
 use hyper::header::HeaderName;
 use hyper::header::HeaderValue;
 use hyper::Body;
@@ -13,37 +19,71 @@ use hyper::Request;
 use jsonrpsee::RpcModule;
 use prometheus::Registry;
 use tokio::runtime::Handle;
+use tokio::sync::oneshot;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{info, warn};
 
 pub use balance_changes::*;
 pub use object_changes::*;
 use sui_json_rpc_api::{
-    CLIENT_SDK_TYPE_HEADER, CLIENT_SDK_VERSION_HEADER, CLIENT_TARGET_API_VERSION_HEADER,
+    API_KEY_HEADER, CLIENT_SDK_TYPE_HEADER, CLIENT_SDK_VERSION_HEADER,
+    CLIENT_TARGET_API_VERSION_HEADER, DATA_LAG_HEADER, MIN_CHECKPOINT_HEADER,
+    REQUEST_MIN_CHECKPOINT_HEADER, SERVER_API_VERSION_HEADER,
+    SERVER_MIN_SUPPORTED_API_VERSION_HEADER,
 };
 use sui_open_rpc::{Module, Project};
 
+use crate::admission::{Admission, AdmissionControl};
 use crate::error::Error;
+use crate::http_caching::HttpCaching;
+use crate::method_aliases::{Aliases, MethodAliases};
+use crate::method_toggles::MethodToggles;
 use crate::metrics::MetricsLogger;
+use crate::query_cache::{CachedQueries, QueryCache};
+use crate::read_your_writes::{CheckpointWatermark, ReadYourWrites};
+use crate::redaction::Redaction;
 use crate::routing_layer::RpcRouter;
+use crate::staleness::Staleness;
+use crate::tenancy::Tenancy;
+use crate::versioning::ApiVersioning;
+pub use crate::versioning::MethodDeprecation;
 
+pub mod admission;
 pub mod authority_state;
 pub mod axum_router;
 mod balance_changes;
 pub mod coin_api;
+pub mod dynamic_field_path;
+pub mod dynamic_field_tree;
 pub mod error;
 pub mod governance_api;
+pub mod http_caching;
 pub mod indexer_api;
+pub mod listen;
 pub mod logger;
+pub mod method_aliases;
+pub mod method_toggles;
 mod metrics;
 pub mod move_utils;
 pub mod name_service;
 mod object_changes;
+pub mod query_cache;
 pub mod read_api;
+pub mod read_your_writes;
+pub mod redaction;
+pub mod request_context;
 mod routing_layer;
+pub mod scheduled_transaction_api;
+pub mod staleness;
+pub mod tenancy;
 pub mod transaction_builder_api;
 pub mod transaction_execution_api;
+pub mod transaction_limits;
+pub mod transaction_lints;
+#[cfg(unix)]
+pub mod unix_socket;
+mod versioning;
 
 pub const APP_NAME_HEADER: &str = "app-name";
 
@@ -53,6 +93,21 @@ pub struct JsonRpcServerBuilder {
     module: RpcModule<()>,
     rpc_doc: Project,
     registry: Registry,
+    version: String,
+    min_supported_api_version: Option<String>,
+    deprecations: HashMap<String, MethodDeprecation>,
+    checkpoint_watermark: Option<Arc<dyn CheckpointWatermark>>,
+    method_toggles: MethodToggles,
+    method_aliases: MethodAliases,
+    admission: AdmissionControl,
+    staleness: Staleness,
+    query_cache: QueryCache,
+    redaction: Redaction,
+    tenancy: Tenancy,
+    http_caching: HttpCaching,
+    additional_addresses: Vec<SocketAddr>,
+    #[cfg(unix)]
+    unix_socket: Option<unix_socket::UnixSocketConfig>,
 }
 
 pub fn sui_rpc_doc(version: &str) -> Project {
@@ -79,9 +134,103 @@ impl JsonRpcServerBuilder {
             module: RpcModule::new(()),
             rpc_doc: sui_rpc_doc(version),
             registry: prometheus_registry.clone(),
+            version: version.to_string(),
+            min_supported_api_version: None,
+            deprecations: HashMap::new(),
+            checkpoint_watermark: None,
+            method_toggles: MethodToggles::default(),
+            method_aliases: MethodAliases::default(),
+            admission: AdmissionControl::default(),
+            staleness: Staleness::default(),
+            query_cache: QueryCache::default(),
+            redaction: Redaction::default(),
+            tenancy: Tenancy::default(),
+            http_caching: HttpCaching::default(),
+            additional_addresses: vec![],
+            #[cfg(unix)]
+            unix_socket: None,
         }
     }
 
+    /// Support read-your-writes consistency tokens (see [`read_your_writes`]), using `watermark`
+    /// to track the progress of the data this server reads from.
+    pub fn set_checkpoint_watermark(&mut self, watermark: Arc<dyn CheckpointWatermark>) {
+        self.checkpoint_watermark = Some(watermark);
+    }
+
+    /// Reject calls to the methods disabled in `toggles`, which can be updated while the server is
+    /// running.
+    pub fn set_method_toggles(&mut self, toggles: MethodToggles) {
+        self.method_toggles = toggles;
+    }
+
+    /// Serve methods under the aliases in `aliases`, which can be updated while the server is
+    /// running.
+    pub fn set_method_aliases(&mut self, aliases: MethodAliases) {
+        self.method_aliases = aliases;
+    }
+
+    /// Limit the requests in flight for each client, according to the config of `admission`,
+    /// which can be updated while the server is running.
+    pub fn set_admission_control(&mut self, admission: AdmissionControl) {
+        self.admission = admission;
+    }
+
+    /// Flag responses, and reject calls to some methods, when the data this server reads from
+    /// falls behind, according to the lag and config recorded in `staleness`.
+    pub fn set_staleness(&mut self, staleness: Staleness) {
+        self.staleness = staleness;
+    }
+
+    /// Cache the responses of the methods configured in `cache` until the checkpoint watermark
+    /// advances. Nothing is cached unless a watermark is set.
+    pub fn set_query_cache(&mut self, cache: QueryCache) {
+        self.query_cache = cache;
+    }
+
+    /// Redact the responses and notifications sent over every transport according to the config
+    /// of `redaction`, which can be updated while the server is running.
+    pub fn set_redaction(&mut self, redaction: Redaction) {
+        self.redaction = redaction;
+    }
+
+    /// Isolate the tenants in the config of `tenancy` from each other in the responses and
+    /// notifications sent over every transport, which can be updated while the server is running.
+    pub fn set_tenancy(&mut self, tenancy: Tenancy) {
+        self.tenancy = tenancy;
+    }
+
+    /// Send HTTP caching headers with responses, and answer conditional requests, according to
+    /// the config of `caching`, which can be updated while the server is running.
+    pub fn set_http_caching(&mut self, caching: HttpCaching) {
+        self.http_caching = caching;
+    }
+
+    /// Serve on these addresses as well as on the listen address, e.g. an IPv6 address alongside
+    /// an IPv4 one (see [`listen`]).
+    pub fn set_additional_addresses(&mut self, addresses: Vec<SocketAddr>) {
+        self.additional_addresses = addresses;
+    }
+
+    /// Serve on a Unix domain socket as well as on the listen address (see [`unix_socket`]).
+    #[cfg(unix)]
+    pub fn set_unix_socket(&mut self, config: unix_socket::UnixSocketConfig) {
+        self.unix_socket = Some(config);
+    }
+
+    /// Reject requests from clients targeting an API version older than `version`. Can also be
+    /// configured through the `MIN_SUPPORTED_API_VERSION` environment variable.
+    pub fn set_min_supported_api_version(&mut self, version: &str) {
+        self.min_supported_api_version = Some(version.to_string());
+    }
+
+    /// Advertise `method` as deprecated, according to `deprecation`. Methods that are marked
+    /// deprecated in their RPC definition are advertised as deprecated (without a schedule)
+    /// automatically.
+    pub fn deprecate_method(&mut self, method: &str, deprecation: MethodDeprecation) {
+        self.deprecations.insert(method.to_string(), deprecation);
+    }
+
     pub fn register_module<T: SuiRpcModule>(&mut self, module: T) -> Result<(), Error> {
         self.rpc_doc.add_module(T::rpc_doc_module());
         Ok(self.module.merge(module.rpc())?)
@@ -101,8 +250,9 @@ impl JsonRpcServerBuilder {
         info!(?acl);
 
         let cors = CorsLayer::new()
-            // Allow `POST` when accessing the resource
-            .allow_methods([Method::POST])
+            // Allow `POST` when accessing the resource, and `GET` to call methods cacheably and to
+            // subscribe over SSE
+            .allow_methods([Method::POST, Method::GET])
             // Allow requests from any origin
             .allow_origin(acl)
             .allow_headers([
@@ -111,6 +261,21 @@ impl JsonRpcServerBuilder {
                 HeaderName::from_static(CLIENT_SDK_VERSION_HEADER),
                 HeaderName::from_static(CLIENT_TARGET_API_VERSION_HEADER),
                 HeaderName::from_static(APP_NAME_HEADER),
+                HeaderName::from_static(MIN_CHECKPOINT_HEADER),
+                HeaderName::from_static(REQUEST_MIN_CHECKPOINT_HEADER),
+                HeaderName::from_static("last-event-id"),
+                HeaderName::from_static(API_KEY_HEADER),
+                hyper::header::IF_NONE_MATCH,
+            ])
+            .expose_headers([
+                HeaderName::from_static(SERVER_API_VERSION_HEADER),
+                HeaderName::from_static(SERVER_MIN_SUPPORTED_API_VERSION_HEADER),
+                HeaderName::from_static("deprecation"),
+                HeaderName::from_static("sunset"),
+                hyper::header::LINK,
+                HeaderName::from_static(MIN_CHECKPOINT_HEADER),
+                HeaderName::from_static(DATA_LAG_HEADER),
+                hyper::header::ETAG,
             ]);
         Ok(cors)
     }
@@ -158,6 +323,21 @@ impl JsonRpcServerBuilder {
         );
         let rpc_router = RpcRouter::new(routing, disable_routing);
 
+        let min_supported_api_version = self
+            .min_supported_api_version
+            .clone()
+            .or_else(|| env::var("MIN_SUPPORTED_API_VERSION").ok());
+        let mut deprecations = self.deprecations.clone();
+        for method in self.rpc_doc.deprecated_methods() {
+            deprecations.entry(method.to_string()).or_default();
+        }
+        let versioning = ApiVersioning::new(
+            &self.version,
+            min_supported_api_version,
+            deprecations,
+            &self.registry,
+        );
+
         let rpc_docs = self.rpc_doc.clone();
         let mut module = self.module.clone();
         module.register_method("rpc.discover", move |_, _| Ok(rpc_docs.clone()))?;
@@ -169,8 +349,23 @@ impl JsonRpcServerBuilder {
             .layer(Self::trace_layer())
             .layer(Self::cors()?);
 
-        let service =
-            crate::axum_router::JsonRpcService::new(module.into(), rpc_router, metrics_logger);
+        let service = crate::axum_router::JsonRpcService::new(
+            module.into(),
+            rpc_router,
+            versioning,
+            self.checkpoint_watermark.clone().map(ReadYourWrites::new),
+            self.method_toggles.clone(),
+            Aliases::new(self.method_aliases.clone(), &self.registry),
+            Admission::new(self.admission.clone(), &self.registry),
+            self.staleness.clone(),
+            self.checkpoint_watermark.clone().map(|watermark| {
+                CachedQueries::new(self.query_cache.clone(), watermark, &self.registry)
+            }),
+            self.redaction.clone(),
+            self.tenancy.clone(),
+            self.http_caching.clone(),
+            metrics_logger,
+        );
 
         let mut router = axum::Router::new();
 
@@ -190,6 +385,14 @@ impl JsonRpcServerBuilder {
                     .route(
                         "/json-rpc",
                         axum::routing::post(crate::axum_router::json_rpc_handler),
+                    )
+                    .route(
+                        "/json-rpc",
+                        axum::routing::get(crate::axum_router::json_rpc_get_handler),
+                    )
+                    .route(
+                        "/sse",
+                        axum::routing::get(crate::axum_router::sse::sse_subscribe),
                     );
             }
             None => {
@@ -205,6 +408,14 @@ impl JsonRpcServerBuilder {
                     .route(
                         "/json-rpc",
                         axum::routing::post(crate::axum_router::json_rpc_handler),
+                    )
+                    .route(
+                        "/json-rpc",
+                        axum::routing::get(crate::axum_router::json_rpc_get_handler),
+                    )
+                    .route(
+                        "/sse",
+                        axum::routing::get(crate::axum_router::sse::sse_subscribe),
                     );
             }
         }
@@ -219,26 +430,69 @@ impl JsonRpcServerBuilder {
     pub async fn start(
         self,
         listen_address: SocketAddr,
-        _custom_runtime: Option<Handle>,
+        custom_runtime: Option<Handle>,
         server_type: Option<ServerType>,
     ) -> Result<ServerHandle, Error> {
         let app = self.to_router(server_type)?;
 
-        let server = axum::Server::bind(&listen_address).serve(app.into_make_service());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let runtime = custom_runtime.unwrap_or_else(Handle::current);
+        let handle = {
+            // Serve requests on the custom runtime, if there is one, rather than the caller's.
+            let _guard = runtime.enter();
+            let shutdown = async move {
+                // Only shut down when asked to, not when the handle is dropped.
+                if shutdown_rx.await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            }
+            .shared();
+
+            let mut servers: Vec<BoxFuture<'static, hyper::Result<()>>> = vec![];
+            #[cfg(unix)]
+            if let Some(config) = &self.unix_socket {
+                let listener = unix_socket::bind(&config.path, config.mode).map_err(|e| {
+                    Error::UnexpectedError(format!(
+                        "Failed to bind Unix socket {}: {e}",
+                        config.path.display()
+                    ))
+                })?;
+                info!(path =? config.path, "Sui JSON-RPC server listening on Unix socket");
+                servers.push(unix_socket::serve(listener, app.clone(), shutdown.clone()).boxed());
+            }
 
-        let addr = server.local_addr();
-        let handle = tokio::spawn(async move { server.await.unwrap() });
+            let mut addresses = vec![listen_address];
+            addresses.extend(&self.additional_addresses);
+            let listeners =
+                listen::bind_all(&addresses).map_err(|e| Error::UnexpectedError(e.to_string()))?;
+            for listener in listeners {
+                let server = axum::Server::from_tcp(listener)
+                    .map_err(|e| Error::UnexpectedError(e.to_string()))?
+                    .serve(
+                        app.clone()
+                            .into_make_service_with_connect_info::<SocketAddr>(),
+                    );
+                let addr = server.local_addr();
+                info!(local_addr =? addr, "Sui JSON-RPC server listening on {addr}");
+                servers.push(server.with_graceful_shutdown(shutdown.clone()).boxed());
+            }
+            runtime.spawn(async move {
+                for result in futures::future::join_all(servers).await {
+                    result.unwrap();
+                }
+            })
+        };
 
-        let handle = ServerHandle {
+        Ok(ServerHandle {
             handle: ServerHandleInner::Axum(handle),
-        };
-        info!(local_addr =? addr, "Sui JSON-RPC server listening on {addr}");
-        Ok(handle)
+            shutdown: shutdown_tx,
+        })
     }
 }
 
 pub struct ServerHandle {
     handle: ServerHandleInner,
+    shutdown: oneshot::Sender<()>,
 }
 
 impl ServerHandle {
@@ -247,6 +501,32 @@ impl ServerHandle {
             ServerHandleInner::Axum(handle) => handle.await.unwrap(),
         }
     }
+
+    /// Stop accepting new connections, and give in-flight requests up to `drain_timeout` to
+    /// complete before stopping the server forcibly.
+    pub async fn shutdown(self, drain_timeout: Duration) {
+        self.shutdown_on(async {}, drain_timeout).await
+    }
+
+    /// Wait for the server to stop, shutting it down gracefully (see [`Self::shutdown`]) if
+    /// `signal` completes first.
+    pub async fn shutdown_on(self, signal: impl Future<Output = ()>, drain_timeout: Duration) {
+        let ServerHandleInner::Axum(mut handle) = self.handle;
+        tokio::select! {
+            result = &mut handle => return result.unwrap(),
+            _ = signal => {}
+        }
+
+        info!("Shutting down JSON-RPC server, draining in-flight requests");
+        let _ = self.shutdown.send(());
+        if tokio::time::timeout(drain_timeout, &mut handle)
+            .await
+            .is_err()
+        {
+            warn!("JSON-RPC server did not drain within {drain_timeout:?}, stopping it");
+            handle.abort();
+        }
+    }
 }
 
 enum ServerHandleInner {
@@ -0,0 +1,83 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Binding servers to several addresses, e.g. to serve both IPv4 and IPv6 clients, or to serve
+//! only on some of a host's interfaces.
+//!
+//! An IPv6 wildcard address (`[::]`) takes IPv4 connections as well on hosts where IPv6 sockets
+//! are dual-stack, which is the default on Linux, and only IPv6 ones elsewhere. Listing both
+//! `[::]:<port>` and `0.0.0.0:<port>` serves both families either way: the IPv4 wildcard is
+//! skipped if the IPv6 listener already holds its port for IPv4.
+
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+
+use tracing::info;
+
+/// Bind a listener to each of `addresses`, set to non-blocking so that they can be served from an
+/// async runtime.
+pub fn bind_all(addresses: &[SocketAddr]) -> io::Result<Vec<TcpListener>> {
+    // IPv6 wildcards go first, so that an IPv4 wildcard on the same port can't keep them from
+    // binding on a dual-stack host.
+    let mut addresses = addresses.to_vec();
+    addresses.sort_by_key(|a| !(a.is_ipv6() && a.ip().is_unspecified()));
+
+    let mut listeners: Vec<TcpListener> = vec![];
+    for address in addresses {
+        let listener = match TcpListener::bind(address) {
+            Ok(listener) => listener,
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse && dual_stacked(&listeners, address) => {
+                info!("Not binding {address}: the IPv6 wildcard on its port takes IPv4 already");
+                continue;
+            }
+            Err(e) => {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("Failed to bind {address}: {e}"),
+                ))
+            }
+        };
+        listener.set_nonblocking(true)?;
+        listeners.push(listener);
+    }
+    Ok(listeners)
+}
+
+/// Whether IPv4 wildcard `address` may be covered by one of `listeners`, bound to the IPv6
+/// wildcard on the same port.
+fn dual_stacked(listeners: &[TcpListener], address: SocketAddr) -> bool {
+    address.is_ipv4()
+        && address.ip().is_unspecified()
+        && listeners.iter().any(|l| {
+            l.local_addr().is_ok_and(|local| {
+                local.is_ipv6() && local.ip().is_unspecified() && local.port() == address.port()
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_all() {
+        // Find a free port, if the host has IPv6 at all.
+        let Ok(probe) = TcpListener::bind("[::]:0") else {
+            return;
+        };
+        let port = probe.local_addr().unwrap().port();
+        drop(probe);
+
+        let v4: SocketAddr = format!("0.0.0.0:{port}").parse().unwrap();
+        let v6: SocketAddr = format!("[::]:{port}").parse().unwrap();
+        let listeners = bind_all(&[v4, v6]).unwrap();
+        assert_eq!(listeners[0].local_addr().unwrap(), v6);
+        // Depending on whether IPv6 sockets are dual-stack on this host, the IPv4 wildcard is
+        // bound separately or skipped.
+        assert!(listeners.len() <= 2);
+
+        // Other clashes are errors.
+        let localhost: SocketAddr = format!("127.0.0.1:{port}").parse().unwrap();
+        assert!(bind_all(&[localhost]).is_err());
+    }
+}
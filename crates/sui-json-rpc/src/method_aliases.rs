@@ -0,0 +1,103 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serving methods under other names while a server is running, e.g. so that clients still
+//! calling a method by a deprecated name keep working while they migrate to its replacement.
+//! Calls to an alias are dispatched to its target, and their responses carry headers warning
+//! that the alias is deprecated. An alias takes precedence over a method of the same name, so an
+//! old method can also be redirected to a new handler.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+
+/// The aliases that a server currently serves, by alias. Clones share the same aliases, so a
+/// handle kept by the operator's tooling can update the aliases served by a running server.
+#[derive(Clone, Debug, Default)]
+pub struct MethodAliases {
+    aliases: Arc<ArcSwap<HashMap<String, String>>>,
+}
+
+impl MethodAliases {
+    /// Replace the aliases served with `aliases`, as pairs of an alias and the method it calls.
+    pub fn set_aliases(&self, aliases: impl IntoIterator<Item = (String, String)>) {
+        self.aliases.store(Arc::new(aliases.into_iter().collect()));
+    }
+
+    /// The method called by `alias`, if it is an alias.
+    pub fn target(&self, alias: &str) -> Option<String> {
+        self.aliases.load().get(alias).cloned()
+    }
+
+    pub(crate) fn warning(alias: &str, target: &str) -> String {
+        format!("299 - \"Method {alias} is deprecated, call {target} instead\"")
+    }
+}
+
+/// The aliases of a server, with their metrics.
+#[derive(Clone, Debug)]
+pub(crate) struct Aliases {
+    aliases: MethodAliases,
+    requests: IntCounterVec,
+}
+
+impl Aliases {
+    pub fn new(aliases: MethodAliases, registry: &Registry) -> Self {
+        Self {
+            aliases,
+            requests: register_int_counter_vec_with_registry!(
+                "rpc_method_alias_requests",
+                "Number of calls to methods by an alias, by alias",
+                &["alias"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    /// The method that a call to `method` is dispatched to, if `method` is an alias.
+    pub fn resolve(&self, method: &str) -> Option<String> {
+        let target = self.aliases.target(method)?;
+        self.requests.with_label_values(&[method]).inc();
+        Some(target)
+    }
+
+    /// The method that `method` is an alias of, if it is one, without counting it as a call.
+    pub fn target(&self, method: &str) -> Option<String> {
+        self.aliases.target(method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases_are_shared() {
+        let handle = MethodAliases::default();
+        let aliases = Aliases::new(handle.clone(), &Registry::new());
+        assert_eq!(aliases.resolve("sui_getTotalSupply"), None);
+
+        handle.set_aliases([(
+            "sui_getTotalSupply".to_string(),
+            "suix_getTotalSupply".to_string(),
+        )]);
+        assert_eq!(
+            aliases.resolve("sui_getTotalSupply").as_deref(),
+            Some("suix_getTotalSupply")
+        );
+        assert_eq!(aliases.resolve("suix_getTotalSupply"), None);
+        assert_eq!(
+            aliases
+                .requests
+                .with_label_values(&["sui_getTotalSupply"])
+                .get(),
+            1
+        );
+
+        handle.set_aliases([]);
+        assert_eq!(aliases.resolve("sui_getTotalSupply"), None);
+    }
+}
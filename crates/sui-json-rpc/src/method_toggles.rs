@@ -0,0 +1,58 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Switching methods off while a server is running, e.g. to shed expensive queries during an
+//! incident. Disabled methods are rejected before they are dispatched, with a "method not found"
+//! error explaining that they have been disabled.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+/// The set of methods that a server currently rejects. Clones share the same set, so a handle
+/// kept by the operator's tooling can update the set served by a running server.
+#[derive(Clone, Debug, Default)]
+pub struct MethodToggles {
+    disabled: Arc<ArcSwap<HashSet<String>>>,
+}
+
+impl MethodToggles {
+    /// Replace the set of disabled methods with `methods`, re-enabling any methods that are not
+    /// in it.
+    pub fn set_disabled(&self, methods: impl IntoIterator<Item = String>) {
+        self.disabled.store(Arc::new(methods.into_iter().collect()));
+    }
+
+    pub fn is_disabled(&self, method: &str) -> bool {
+        self.disabled.load().contains(method)
+    }
+
+    /// The methods that are currently disabled, in no particular order.
+    pub fn disabled(&self) -> Vec<String> {
+        self.disabled.load().iter().cloned().collect()
+    }
+
+    pub(crate) fn disabled_message(method: &str) -> String {
+        format!("Method {method} is temporarily disabled on this server")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggles_are_shared() {
+        let toggles = MethodToggles::default();
+        let handle = toggles.clone();
+        assert!(!toggles.is_disabled("suix_queryEvents"));
+
+        handle.set_disabled(["suix_queryEvents".to_string()]);
+        assert!(toggles.is_disabled("suix_queryEvents"));
+        assert!(!toggles.is_disabled("sui_getObject"));
+
+        handle.set_disabled([]);
+        assert!(!toggles.is_disabled("suix_queryEvents"));
+    }
+}
@@ -0,0 +1,391 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Caching the responses to HTTP calls of idempotent methods, so that calls repeated with the
+//! same params (e.g. by many clients polling the same page of an explorer) are served without
+//! going to the data behind the server.
+//!
+//! Each response is cached with the checkpoint that the server's data had reached (its
+//! [`CheckpointWatermark`]) when the response was computed, and is only served until the
+//! watermark advances past that checkpoint. The watermark is re-read at most every
+//! [`WATERMARK_REFRESH_INTERVAL`], so a cached response is never served for longer than that
+//! after the data it was computed from has changed.
+//!
+//! Only the methods named in the config are cached, because only the operator knows which of a
+//! server's methods are idempotent for a given checkpoint. Responses are kept in memory by
+//! default, and can be shared between servers by plugging in a [`QueryCacheStore`] backed by a
+//! shared cache (e.g. Redis). Nothing is cached on servers without a watermark.
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+use async_trait::async_trait;
+use jsonrpsee::core::server::helpers::MethodResponse;
+use jsonrpsee::types::Request;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::read_your_writes::CheckpointWatermark;
+
+/// How long the watermark read for one request is reused for the requests after it.
+pub const WATERMARK_REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Number of responses kept by the default, in-memory store.
+const DEFAULT_MAX_ENTRIES: u64 = 10_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct QueryCacheConfig {
+    /// Methods whose responses are cached. Nothing is cached if this is empty.
+    pub methods: BTreeSet<String>,
+}
+
+/// A cached response, and the checkpoint that it is valid for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedResponse {
+    pub checkpoint: CheckpointSequenceNumber,
+    /// The `result` of the response, as JSON.
+    pub result: String,
+}
+
+/// Where cached responses are kept.
+#[async_trait]
+pub trait QueryCacheStore: Send + Sync + 'static {
+    async fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    async fn insert(&self, key: String, response: CachedResponse);
+}
+
+/// Keeps the most recently used responses in memory.
+pub struct InMemoryQueryCacheStore {
+    cache: moka::sync::Cache<String, CachedResponse>,
+}
+
+impl InMemoryQueryCacheStore {
+    pub fn new(max_entries: u64) -> Self {
+        Self {
+            cache: moka::sync::Cache::new(max_entries),
+        }
+    }
+}
+
+#[async_trait]
+impl QueryCacheStore for InMemoryQueryCacheStore {
+    async fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.cache.get(key)
+    }
+
+    async fn insert(&self, key: String, response: CachedResponse) {
+        self.cache.insert(key, response);
+    }
+}
+
+/// The query cache of a server. Clones share the same state, so a handle kept by the operator's
+/// tooling can change which methods a running server caches.
+#[derive(Clone)]
+pub struct QueryCache {
+    config: Arc<ArcSwap<QueryCacheConfig>>,
+    store: Arc<dyn QueryCacheStore>,
+}
+
+impl Default for QueryCache {
+    fn default() -> Self {
+        Self::new(Arc::new(InMemoryQueryCacheStore::new(DEFAULT_MAX_ENTRIES)))
+    }
+}
+
+impl std::fmt::Debug for QueryCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryCache")
+            .field("config", &self.config.load())
+            .finish_non_exhaustive()
+    }
+}
+
+impl QueryCache {
+    pub fn new(store: Arc<dyn QueryCacheStore>) -> Self {
+        Self {
+            config: Default::default(),
+            store,
+        }
+    }
+
+    pub fn set_config(&self, config: QueryCacheConfig) {
+        self.config.store(Arc::new(config));
+    }
+
+    fn caches(&self, method: &str) -> bool {
+        self.config.load().methods.contains(method)
+    }
+}
+
+/// The query cache of a server, with the watermark it invalidates responses by, and its metrics.
+#[derive(Clone)]
+pub(crate) struct CachedQueries {
+    cache: QueryCache,
+    watermark: Arc<dyn CheckpointWatermark>,
+    /// The watermark last read, and when.
+    latest: Arc<Mutex<Option<(CheckpointSequenceNumber, Instant)>>>,
+    hits: IntCounterVec,
+    misses: IntCounterVec,
+}
+
+impl std::fmt::Debug for CachedQueries {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedQueries")
+            .field("cache", &self.cache)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CachedQueries {
+    pub fn new(
+        cache: QueryCache,
+        watermark: Arc<dyn CheckpointWatermark>,
+        registry: &Registry,
+    ) -> Self {
+        Self {
+            cache,
+            watermark,
+            latest: Default::default(),
+            hits: register_int_counter_vec_with_registry!(
+                "rpc_query_cache_hits",
+                "Number of calls served from the query cache, by method",
+                &["method"],
+                registry,
+            )
+            .unwrap(),
+            misses: register_int_counter_vec_with_registry!(
+                "rpc_query_cache_misses",
+                "Number of calls to cached methods that missed the query cache, by method",
+                &["method"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    /// The cached response to `request`, or where to cache its response if its method is
+    /// cached. Responses are only served if they are as of the latest checkpoint, and of
+    /// `min_checkpoint`, if the request asked to read data as of at least that checkpoint.
+    pub async fn lookup(
+        &self,
+        request: &Request<'_>,
+        api_version: Option<&str>,
+        min_checkpoint: Option<u64>,
+    ) -> Lookup {
+        if !self.cache.caches(&request.method) {
+            return Lookup::Uncached;
+        }
+        let Some(checkpoint) = self.latest_checkpoint().await else {
+            return Lookup::Uncached;
+        };
+
+        let key = cache_key(request, api_version);
+        let at_least = checkpoint.max(min_checkpoint.unwrap_or_default());
+        let cached = self
+            .cache
+            .store
+            .get(&key)
+            .await
+            .filter(|cached| cached.checkpoint >= at_least)
+            .and_then(|cached| RawValue::from_string(cached.result).ok());
+        match cached {
+            Some(result) => {
+                self.hits
+                    .with_label_values(&[request.method.as_ref()])
+                    .inc();
+                Lookup::Hit(MethodResponse::response(
+                    request.id.clone().into_owned(),
+                    result,
+                    usize::MAX,
+                ))
+            }
+            None => {
+                self.misses
+                    .with_label_values(&[request.method.as_ref()])
+                    .inc();
+                Lookup::Miss(CacheSlot { key, checkpoint })
+            }
+        }
+    }
+
+    /// Cache `response`, if it succeeded, in `slot`.
+    pub async fn insert(&self, slot: CacheSlot, response: &MethodResponse) {
+        #[derive(Deserialize)]
+        struct Success<'a> {
+            #[serde(borrow)]
+            result: &'a RawValue,
+        }
+
+        if !response.success {
+            return;
+        }
+        let Ok(success) = serde_json::from_str::<Success>(&response.result) else {
+            return;
+        };
+        self.cache
+            .store
+            .insert(
+                slot.key,
+                CachedResponse {
+                    checkpoint: slot.checkpoint,
+                    result: success.result.get().to_string(),
+                },
+            )
+            .await;
+    }
+
+    async fn latest_checkpoint(&self) -> Option<CheckpointSequenceNumber> {
+        if let Some((checkpoint, read_at)) = *self.latest.lock().unwrap() {
+            if read_at.elapsed() < WATERMARK_REFRESH_INTERVAL {
+                return Some(checkpoint);
+            }
+        }
+
+        let checkpoint = self.watermark.latest_checkpoint().await.ok()?;
+        *self.latest.lock().unwrap() = Some((checkpoint, Instant::now()));
+        Some(checkpoint)
+    }
+}
+
+pub(crate) enum Lookup {
+    /// The request's method is not cached.
+    Uncached,
+    Hit(MethodResponse),
+    Miss(CacheSlot),
+}
+
+/// Where the response to a request that missed the cache goes.
+pub(crate) struct CacheSlot {
+    key: String,
+    checkpoint: CheckpointSequenceNumber,
+}
+
+/// Calls are cached by method, params, and API version, which can change how a method is routed.
+fn cache_key(request: &Request<'_>, api_version: Option<&str>) -> String {
+    let params = request.params.as_ref().map_or("", |params| params.get());
+    format!(
+        "{}:{}:{params}",
+        request.method,
+        api_version.unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::Id;
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use sui_types::digests::TransactionDigest;
+
+    fn request<'a>(method: &'a str, params: &'a RawValue, id: u64) -> Request<'a> {
+        Request::new(method.into(), Some(params), Id::Number(id))
+    }
+
+    struct TestWatermark(AtomicU64);
+
+    #[async_trait]
+    impl CheckpointWatermark for TestWatermark {
+        async fn latest_checkpoint(&self) -> anyhow::Result<CheckpointSequenceNumber> {
+            Ok(self.0.load(Ordering::Relaxed))
+        }
+
+        async fn transaction_checkpoint(
+            &self,
+            _digest: TransactionDigest,
+        ) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalidated_by_watermark() {
+        let watermark = Arc::new(TestWatermark(AtomicU64::new(10)));
+        let cache = QueryCache::default();
+        cache.set_config(QueryCacheConfig {
+            methods: BTreeSet::from(["suix_getBalance".to_string()]),
+        });
+        let queries = CachedQueries::new(cache, watermark.clone(), &Registry::new());
+
+        let params = RawValue::from_string(r#"["0x1"]"#.to_string()).unwrap();
+        let Lookup::Miss(slot) = queries
+            .lookup(&request("suix_getBalance", &params, 1), None, None)
+            .await
+        else {
+            panic!("Expected a miss");
+        };
+        let response = MethodResponse::response(Id::Number(1), 42, usize::MAX);
+        queries.insert(slot, &response).await;
+
+        // Served from the cache, with the id of the new request.
+        let Lookup::Hit(hit) = queries
+            .lookup(&request("suix_getBalance", &params, 2), None, None)
+            .await
+        else {
+            panic!("Expected a hit");
+        };
+        let hit: Value = serde_json::from_str(&hit.result).unwrap();
+        assert_eq!((&hit["result"], &hit["id"]), (&json!(42), &json!(2)));
+
+        // Methods that aren't configured are never cached.
+        assert!(matches!(
+            queries
+                .lookup(&request("sui_getObject", &params, 3), None, None)
+                .await,
+            Lookup::Uncached
+        ));
+
+        // The response is invalidated once the watermark advances, and the watermark is re-read.
+        watermark.0.store(11, Ordering::Relaxed);
+        tokio::time::sleep(WATERMARK_REFRESH_INTERVAL).await;
+        assert!(matches!(
+            queries
+                .lookup(&request("suix_getBalance", &params, 4), None, None)
+                .await,
+            Lookup::Miss(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_min_checkpoint_past_cached_response() {
+        let watermark = Arc::new(TestWatermark(AtomicU64::new(10)));
+        let cache = QueryCache::default();
+        cache.set_config(QueryCacheConfig {
+            methods: BTreeSet::from(["suix_getBalance".to_string()]),
+        });
+        let queries = CachedQueries::new(cache, watermark.clone(), &Registry::new());
+
+        let params = RawValue::from_string(r#"["0x1"]"#.to_string()).unwrap();
+        let Lookup::Miss(slot) = queries
+            .lookup(&request("suix_getBalance", &params, 1), None, None)
+            .await
+        else {
+            panic!("Expected a miss");
+        };
+        let response = MethodResponse::response(Id::Number(1), 42, usize::MAX);
+        queries.insert(slot, &response).await;
+
+        // A response cached as of checkpoint 10 is served to requests for data as of at most
+        // that checkpoint...
+        assert!(matches!(
+            queries
+                .lookup(&request("suix_getBalance", &params, 2), None, Some(10))
+                .await,
+            Lookup::Hit(_)
+        ));
+
+        // ...but not to requests for later data, even before the watermark is re-read.
+        assert!(matches!(
+            queries
+                .lookup(&request("suix_getBalance", &params, 3), None, Some(11))
+                .await,
+            Lookup::Miss(_)
+        ));
+    }
+}
@@ -1,11 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
 use futures::future::join_all;
 use indexmap::map::IndexMap;
 use itertools::Itertools;
@@ -24,11 +25,11 @@ use sui_json_rpc_api::{
     QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
 };
 use sui_json_rpc_types::{
-    BalanceChange, Checkpoint, CheckpointId, CheckpointPage, DisplayFieldsResponse, EventFilter,
-    ObjectChange, ProtocolConfigResponse, SuiEvent, SuiGetPastObjectRequest, SuiMoveStruct,
-    SuiMoveValue, SuiObjectDataOptions, SuiObjectResponse, SuiPastObjectResponse,
-    SuiTransactionBlock, SuiTransactionBlockEvents, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    BalanceChange, Checkpoint, CheckpointId, CheckpointPage, DisplayFieldError,
+    DisplayFieldsResponse, EventFilter, ObjectChange, ProtocolConfigResponse, SuiEvent,
+    SuiGetPastObjectRequest, SuiMoveStruct, SuiMoveValue, SuiObjectDataOptions, SuiObjectResponse,
+    SuiPastObjectResponse, SuiTransactionBlock, SuiTransactionBlockEvents,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_json_rpc_types::{SuiLoadedChildObject, SuiLoadedChildObjectsResponse};
 use sui_open_rpc::Module;
@@ -36,16 +37,20 @@ use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
 use sui_storage::key_value_store::TransactionKeyValueStore;
 use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
 use sui_types::collection_types::VecMap;
-use sui_types::crypto::AggregateAuthoritySignature;
+use sui_types::committee::Committee;
+use sui_types::crypto::{AggregateAuthoritySignature, AuthorityStrongQuorumSignInfo};
 use sui_types::digests::TransactionEventsDigest;
-use sui_types::display::DisplayVersionUpdatedEvent;
+use sui_types::display::{
+    validate_display_template, DisplayVersionUpdatedEvent, MAX_DISPLAY_NESTED_LEVEL,
+};
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
 use sui_types::error::{SuiError, SuiObjectResponseError};
 use sui_types::messages_checkpoint::{
-    CheckpointContents, CheckpointContentsDigest, CheckpointSequenceNumber, CheckpointSummary,
-    CheckpointTimestamp,
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointContentsDigest,
+    CheckpointSequenceNumber, CheckpointSummary, CheckpointTimestamp,
 };
-use sui_types::object::{Object, ObjectRead, PastObjectRead};
+use sui_types::object::{MoveObject, Object, ObjectRead, PastObjectRead};
+use sui_types::parse_sui_struct_tag;
 use sui_types::sui_serde::BigInt;
 use sui_types::transaction::Transaction;
 use sui_types::transaction::TransactionDataAPI;
@@ -57,8 +62,6 @@ use crate::{
     get_balance_changes_from_effect, get_object_changes, ObjectProviderCache, SuiRpcModule,
 };
 
-const MAX_DISPLAY_NESTED_LEVEL: usize = 10;
-
 // An implementation of the read portion of the JSON-RPC interface intended for use in
 // Fullnodes.
 #[derive(Clone)]
@@ -783,7 +786,7 @@ impl ReadApiServer for ReadApi {
                         .await
                         .map_err(Error::from)??;
                     match to_sui_transaction_events(self, digest, events) {
-                        Ok(e) => temp_response.events = Some(e),
+                        Ok(e) => temp_response.events = Some(opts.project_events(e)),
                         Err(e) => temp_response.errors.push(e.to_string()),
                     };
                 } else {
@@ -974,6 +977,7 @@ impl ReadApiServer for ReadApi {
                 data,
                 next_cursor,
                 has_next_page,
+                truncated: false,
             })
         })
     }
@@ -1052,6 +1056,64 @@ impl ReadApiServer for ReadApi {
             Ok(ci.to_string())
         })
     }
+
+    #[instrument(skip(self))]
+    async fn verify_checkpoint_signature(
+        &self,
+        summary: Base64,
+        signature: Base64,
+        epoch: BigInt<u64>,
+    ) -> RpcResult<bool> {
+        with_tracing!(async move {
+            let committee = self.state.get_or_latest_committee(Some(epoch))?;
+            if committee.epoch != *epoch {
+                return Err(Error::from(SuiRpcInputError::GenericNotFound(format!(
+                    "Committee for epoch {epoch} not found"
+                ))));
+            }
+
+            Ok(verify_checkpoint_signature(
+                &summary, &signature, &committee,
+            )?)
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn validate_display(
+        &self,
+        object_type: String,
+        fields: Option<BTreeMap<String, String>>,
+    ) -> RpcResult<Vec<DisplayFieldError>> {
+        with_tracing!(async move {
+            let object_type = parse_sui_struct_tag(&object_type)
+                .map_err(|e| SuiRpcInputError::GenericInvalid(e.to_string()))?;
+            let fields = match fields {
+                Some(fields) => fields,
+                None => {
+                    let display =
+                        get_display_object_by_type(&self.transaction_kv_store, self, &object_type)
+                            .await
+                            .map_err(|e| Error::UnexpectedError(e.to_string()))?;
+                    let Some(display) = display else {
+                        return Err(Error::from(SuiRpcInputError::GenericNotFound(format!(
+                            "No Display has been published for {object_type}"
+                        ))));
+                    };
+                    display
+                        .fields
+                        .contents
+                        .into_iter()
+                        .map(|entry| (entry.key, entry.value))
+                        .collect()
+                }
+            };
+
+            let epoch_store = self.state.load_epoch_store_one_call_per_task();
+            let layout =
+                MoveObject::get_layout_from_struct_tag(object_type, epoch_store.module_cache())?;
+            Ok(validate_display_fields(&fields, &layout))
+        })
+    }
 }
 
 impl SuiRpcModule for ReadApi {
@@ -1215,6 +1277,24 @@ pub fn get_rendered_fields(
     Err(ObjectDisplayError::NotMoveStruct)?
 }
 
+/// The problems with the templates of Display `fields`, for objects whose type has `layout`.
+pub fn validate_display_fields<'a>(
+    fields: impl IntoIterator<Item = (&'a String, &'a String)>,
+    layout: &MoveStructLayout,
+) -> Vec<DisplayFieldError> {
+    fields
+        .into_iter()
+        .flat_map(|(key, template)| {
+            validate_display_template(template, layout)
+                .into_iter()
+                .map(|error| DisplayFieldError {
+                    key: key.clone(),
+                    error,
+                })
+        })
+        .collect()
+}
+
 fn parse_template(template: &str, move_struct: &SuiMoveStruct) -> Result<String, Error> {
     let mut output = template.to_string();
     let mut var_name = String::new();
@@ -1309,6 +1389,21 @@ fn get_value_from_move_struct(
     }
 }
 
+/// Check whether `signature` (a BCS encoded [AuthorityStrongQuorumSignInfo]) is a valid quorum
+/// signature by `committee` over `summary` (a BCS encoded [CheckpointSummary]). Inputs that fail
+/// to deserialize are rejected as invalid parameters.
+pub fn verify_checkpoint_signature(
+    summary: &Base64,
+    signature: &Base64,
+    committee: &Committee,
+) -> Result<bool, SuiRpcInputError> {
+    let summary: CheckpointSummary = bcs::from_bytes(&summary.to_vec()?)?;
+    let signature: AuthorityStrongQuorumSignInfo = bcs::from_bytes(&signature.to_vec()?)?;
+
+    let certified = CertifiedCheckpointSummary::new_from_data_and_sig(summary, signature);
+    Ok(certified.verify_authority_signatures(committee).is_ok())
+}
+
 fn convert_to_response(
     cache: IntermediateTransactionResponse,
     opts: &SuiTransactionBlockResponseOptions,
@@ -1345,7 +1440,7 @@ fn convert_to_response(
     response.timestamp_ms = cache.timestamp;
 
     if opts.show_events {
-        response.events = cache.events;
+        response.events = cache.events.map(|events| opts.project_events(events));
     }
 
     if opts.show_balance_changes {
@@ -1400,6 +1495,10 @@ fn calculate_checkpoint_numbers(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use fastcrypto::traits::KeyPair;
+    use sui_types::crypto::AuthorityKeyPair;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::SignedCheckpointSummary;
 
     #[test]
     fn test_calculate_checkpoint_numbers() {
@@ -1478,4 +1577,56 @@ mod tests {
 
         assert_eq!(checkpoint_numbers, (0..=15).rev().collect::<Vec<_>>());
     }
+
+    fn committee(epoch: u64, keys: &[AuthorityKeyPair], weights: &[u64]) -> Committee {
+        let voting_weights = keys
+            .iter()
+            .zip(weights)
+            .map(|(k, w)| (k.public().into(), *w))
+            .collect();
+        Committee::new_for_testing_with_normalized_voting_power(epoch, voting_weights)
+    }
+
+    fn sign_checkpoint(
+        summary: &CheckpointSummary,
+        committee: &Committee,
+        keys: &[AuthorityKeyPair],
+    ) -> Base64 {
+        let sign_infos = keys
+            .iter()
+            .map(|k| SignedCheckpointSummary::sign(committee.epoch, summary, k, k.public().into()))
+            .collect();
+        let signature =
+            AuthorityStrongQuorumSignInfo::new_from_auth_sign_infos(sign_infos, committee).unwrap();
+        Base64::from_bytes(&bcs::to_bytes(&signature).unwrap())
+    }
+
+    #[test]
+    fn test_verify_checkpoint_signature() {
+        let (_, keys) = Committee::new_simple_test_committee();
+        let signers = committee(0, &keys, &[1, 1, 1, 1]);
+        let contents = CheckpointContents::new_with_digests_only_for_tests(vec![]);
+        let summary =
+            CheckpointSummary::new(0, 1, 0, &contents, None, GasCostSummary::default(), None, 0);
+        let encoded = Base64::from_bytes(&bcs::to_bytes(&summary).unwrap());
+        let signature = sign_checkpoint(&summary, &signers, &keys[..3]);
+        assert!(verify_checkpoint_signature(&encoded, &signature, &signers).unwrap());
+
+        // A summary other than the one that was signed.
+        let mut tampered = summary.clone();
+        tampered.timestamp_ms += 1;
+        let tampered = Base64::from_bytes(&bcs::to_bytes(&tampered).unwrap());
+        assert!(!verify_checkpoint_signature(&tampered, &signature, &signers).unwrap());
+
+        // The same validators, as the committee of another epoch.
+        let next_epoch = committee(1, &keys, &[1, 1, 1, 1]);
+        assert!(!verify_checkpoint_signature(&encoded, &signature, &next_epoch).unwrap());
+
+        // The signers no longer hold a quorum of the committee's stake.
+        let reweighted = committee(0, &keys, &[1, 1, 1, 7]);
+        assert!(!verify_checkpoint_signature(&encoded, &signature, &reweighted).unwrap());
+
+        // Inputs that aren't BCS encoded summaries and signatures are rejected.
+        assert!(verify_checkpoint_signature(&signature, &encoded, &signers).is_err());
+    }
 }
@@ -0,0 +1,175 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read-your-writes consistency across RPC servers.
+//!
+//! Applications that submit transactions to one server and read from another (e.g. a fullnode
+//! for writes and an indexer for reads) can observe stale data if the reader has not caught up
+//! with the checkpoint that includes their transaction. To avoid this, clients can ask for a
+//! "minimum checkpoint" token when executing a transaction (by setting the
+//! [`REQUEST_MIN_CHECKPOINT_HEADER`]), which is returned in the [`MIN_CHECKPOINT_HEADER`] once
+//! the transaction has been included in a checkpoint. Supplying that token in the
+//! [`MIN_CHECKPOINT_HEADER`] of subsequent reads makes the server serving them wait (briefly)
+//! until it has caught up to that checkpoint.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sui_types::digests::TransactionDigest;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::time::{sleep, Instant};
+
+pub use sui_json_rpc_api::{MIN_CHECKPOINT_HEADER, REQUEST_MIN_CHECKPOINT_HEADER};
+
+use crate::authority_state::StateRead;
+
+/// How long to wait for a server to catch up to a requested checkpoint, or for a transaction to
+/// be included in a checkpoint, before giving up.
+const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often to poll the watermark while waiting.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The progress of the data backing an RPC server.
+#[async_trait]
+pub trait CheckpointWatermark: Send + Sync + 'static {
+    /// The latest checkpoint whose contents are visible to reads served by this server.
+    async fn latest_checkpoint(&self) -> anyhow::Result<CheckpointSequenceNumber>;
+
+    /// The checkpoint that includes transaction `digest`, if it has been included in one, and
+    /// this server has caught up to it.
+    async fn transaction_checkpoint(
+        &self,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<Option<CheckpointSequenceNumber>>;
+}
+
+#[derive(Clone)]
+pub(crate) struct ReadYourWrites {
+    watermark: Arc<dyn CheckpointWatermark>,
+    timeout: Duration,
+}
+
+impl ReadYourWrites {
+    pub(crate) fn new(watermark: Arc<dyn CheckpointWatermark>) -> Self {
+        Self {
+            watermark,
+            timeout: DEFAULT_WAIT_TIMEOUT,
+        }
+    }
+
+    /// Wait until the server has caught up to checkpoint `min_checkpoint`. On timeout, returns
+    /// the latest checkpoint that the server did reach, as an error.
+    pub(crate) async fn wait_for_checkpoint(
+        &self,
+        min_checkpoint: CheckpointSequenceNumber,
+    ) -> Result<(), Option<CheckpointSequenceNumber>> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let latest = self.watermark.latest_checkpoint().await.ok();
+            if latest.is_some_and(|l| l >= min_checkpoint) {
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(latest);
+            }
+
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Wait until transaction `digest` has been included in a checkpoint, returning that
+    /// checkpoint, or `None` if this does not happen before the timeout.
+    pub(crate) async fn wait_for_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> Option<CheckpointSequenceNumber> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            if let Ok(Some(checkpoint)) = self.watermark.transaction_checkpoint(digest).await {
+                return Some(checkpoint);
+            }
+
+            if Instant::now() >= deadline {
+                return None;
+            }
+
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Watermark for servers reading from the fullnode's own state.
+pub struct StateCheckpointWatermark {
+    state: Arc<dyn StateRead>,
+}
+
+impl StateCheckpointWatermark {
+    pub fn new(state: Arc<dyn StateRead>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl CheckpointWatermark for StateCheckpointWatermark {
+    async fn latest_checkpoint(&self) -> anyhow::Result<CheckpointSequenceNumber> {
+        Ok(self.state.get_latest_checkpoint_sequence_number()?)
+    }
+
+    async fn transaction_checkpoint(
+        &self,
+        digest: TransactionDigest,
+    ) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+        let Some((_, checkpoint)) = self.state.deprecated_get_transaction_checkpoint(&digest)?
+        else {
+            return Ok(None);
+        };
+
+        // Only report the checkpoint once the node has executed it, so that reads pinned to it
+        // observe its effects.
+        let latest = self.state.get_latest_checkpoint_sequence_number()?;
+        Ok((checkpoint <= latest).then_some(checkpoint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+
+    struct Counter(AtomicU64);
+
+    #[async_trait]
+    impl CheckpointWatermark for Counter {
+        async fn latest_checkpoint(&self) -> anyhow::Result<CheckpointSequenceNumber> {
+            Ok(self.0.fetch_add(1, Ordering::SeqCst))
+        }
+
+        async fn transaction_checkpoint(
+            &self,
+            _digest: TransactionDigest,
+        ) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_checkpoint() {
+        let ryw = ReadYourWrites::new(Arc::new(Counter(AtomicU64::new(0))));
+        assert_eq!(ryw.wait_for_checkpoint(3).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_checkpoint_timeout() {
+        let mut ryw = ReadYourWrites::new(Arc::new(Counter(AtomicU64::new(0))));
+        ryw.timeout = Duration::from_millis(0);
+        assert_eq!(ryw.wait_for_checkpoint(3).await, Err(Some(0)));
+        assert_eq!(
+            ryw.wait_for_transaction(TransactionDigest::ZERO).await,
+            None
+        );
+    }
+}
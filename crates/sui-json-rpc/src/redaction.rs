@@ -0,0 +1,319 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Redacting parts of responses that a server's operator doesn't want to expose to every client,
+//! e.g. on a public endpoint of an otherwise private deployment. Requests with one of the
+//! configured exempt API keys get full responses, and for all other requests:
+//!
+//! - the raw BCS of objects and events of the configured types is replaced by `null`, while
+//!   their parsed contents are left as they are,
+//! - the balances of coins (and of an address' holdings of a coin type) below the configured
+//!   threshold are replaced by `null`.
+//!
+//! Responses to requests over HTTP and WebSocket (with the API key sent when the connection is
+//! opened) are redacted, and so are the notifications of subscriptions over WebSocket and SSE.
+//! Nothing is redacted until a config is set.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use move_core_types::language_storage::StructTag;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sui_types::parse_sui_struct_tag;
+
+/// The fields holding the raw BCS of an object or event.
+const BCS_FIELDS: &[&str] = &["bcs", "bcsBytes"];
+
+/// The fields holding balances, in objects that also have a `coinType`.
+const BALANCE_FIELDS: &[&str] = &["balance", "totalBalance", "lockedBalance"];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct RedactionConfig {
+    /// API keys whose requests get responses without redactions.
+    pub exempt_api_keys: BTreeSet<String>,
+    /// Types whose raw BCS is hidden. Types given without type parameters (e.g.
+    /// `0x2::coin::Coin`) match all of their instantiations.
+    pub hide_bcs_types: BTreeSet<String>,
+    /// Balances below this amount are hidden. No balances are hidden if this is unset.
+    pub mask_balances_below: Option<u128>,
+}
+
+/// The rules of a config, parsed once when it is set.
+#[derive(Debug, Default)]
+struct Rules {
+    exempt_api_keys: BTreeSet<String>,
+    hide_bcs_types: Vec<StructTag>,
+    mask_balances_below: Option<u128>,
+}
+
+/// The redactions a server applies to its responses. Clones share the same config, so a handle
+/// kept by the operator's tooling can update the redactions of a running server.
+#[derive(Clone, Debug, Default)]
+pub struct Redaction {
+    rules: Arc<ArcSwap<Rules>>,
+}
+
+impl Redaction {
+    /// Replace the redactions with those in `config`. The previous redactions are kept if any of
+    /// its types can't be parsed.
+    pub fn set_config(&self, config: RedactionConfig) -> anyhow::Result<()> {
+        let hide_bcs_types = config
+            .hide_bcs_types
+            .iter()
+            .map(|t| {
+                parse_sui_struct_tag(t).map_err(|e| anyhow::anyhow!("Invalid type {t:?}: {e}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        self.rules.store(Arc::new(Rules {
+            exempt_api_keys: config.exempt_api_keys,
+            hide_bcs_types,
+            mask_balances_below: config.mask_balances_below,
+        }));
+        Ok(())
+    }
+
+    /// Redact the `result` of `message`, a serialized JSON-RPC response or subscription
+    /// notification sent to a client with `api_key`. The message is returned as is if nothing in
+    /// it needs redacting.
+    pub(crate) fn redact(&self, api_key: Option<&str>, message: String) -> String {
+        let Some(rules) = self.rules_for(api_key) else {
+            return message;
+        };
+        let Ok(mut json) = serde_json::from_str::<Value>(&message) else {
+            return message;
+        };
+        let result = if json.get("params").is_some() {
+            json["params"].get_mut("result")
+        } else {
+            json.get_mut("result")
+        };
+        match result {
+            Some(result) if rules.redact(result) => serde_json::to_string(&json).unwrap_or(message),
+            _ => message,
+        }
+    }
+
+    /// Redact `item`, an item sent on its own to a subscriber with `api_key` (e.g. an event over
+    /// SSE).
+    pub(crate) fn redact_item(&self, api_key: Option<&str>, item: &mut Value) {
+        if let Some(rules) = self.rules_for(api_key) {
+            rules.redact(item);
+        }
+    }
+
+    /// The rules that apply to a client with `api_key`, unless there is nothing to redact for it.
+    fn rules_for(&self, api_key: Option<&str>) -> Option<Arc<Rules>> {
+        let rules = self.rules.load_full();
+        if rules.hide_bcs_types.is_empty() && rules.mask_balances_below.is_none() {
+            return None;
+        }
+        if api_key.is_some_and(|key| rules.exempt_api_keys.contains(key)) {
+            return None;
+        }
+        Some(rules)
+    }
+}
+
+impl Rules {
+    /// Redact `value` and everything nested in it, returning whether anything was redacted.
+    fn redact(&self, value: &mut Value) -> bool {
+        match value {
+            Value::Array(values) => values
+                .iter_mut()
+                .fold(false, |redacted, value| self.redact(value) | redacted),
+            Value::Object(fields) => {
+                let redacted = self.hide_bcs(fields) | self.mask_balances(fields);
+                fields
+                    .values_mut()
+                    .fold(redacted, |redacted, value| self.redact(value) | redacted)
+            }
+            _ => false,
+        }
+    }
+
+    fn hide_bcs(&self, fields: &mut Map<String, Value>) -> bool {
+        let Some(Value::String(type_)) = fields.get("type") else {
+            return false;
+        };
+        let Ok(type_) = parse_sui_struct_tag(type_) else {
+            return false;
+        };
+        if !self.hide_bcs_types.iter().any(|t| type_matches(t, &type_)) {
+            return false;
+        }
+        null_fields(fields, BCS_FIELDS)
+    }
+
+    fn mask_balances(&self, fields: &mut Map<String, Value>) -> bool {
+        let Some(threshold) = self.mask_balances_below else {
+            return false;
+        };
+        if !fields.contains_key("coinType") {
+            return false;
+        }
+        let balance = ["totalBalance", "balance"]
+            .iter()
+            .find_map(|field| balance_amount(fields.get(*field)?));
+        match balance {
+            Some(balance) if balance < threshold => null_fields(fields, BALANCE_FIELDS),
+            _ => false,
+        }
+    }
+}
+
+/// Whether `type_` is `pattern`, or an instantiation of it if `pattern` has no type parameters.
+fn type_matches(pattern: &StructTag, type_: &StructTag) -> bool {
+    if !pattern.type_params.is_empty() {
+        return pattern == type_;
+    }
+    pattern.address == type_.address && pattern.module == type_.module && pattern.name == type_.name
+}
+
+/// Balances are serialized as strings, because they may not fit in a JSON number.
+fn balance_amount(value: &Value) -> Option<u128> {
+    match value {
+        Value::String(amount) => amount.parse().ok(),
+        Value::Number(amount) => amount.as_u64().map(u128::from),
+        _ => None,
+    }
+}
+
+/// Replace the `names` fields that are set in `fields` by `null`, returning whether any were.
+fn null_fields(fields: &mut Map<String, Value>, names: &[&str]) -> bool {
+    let mut redacted = false;
+    for name in names {
+        if let Some(value) = fields.get_mut(*name) {
+            if !value.is_null() {
+                *value = Value::Null;
+                redacted = true;
+            }
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(result: Value) -> String {
+        json!({"jsonrpc": "2.0", "id": 1, "result": result}).to_string()
+    }
+
+    fn redaction(config: RedactionConfig) -> Redaction {
+        let redaction = Redaction::default();
+        redaction.set_config(config).unwrap();
+        redaction
+    }
+
+    #[test]
+    fn test_hide_bcs() {
+        let redaction = redaction(RedactionConfig {
+            hide_bcs_types: ["0x2::coin::Coin".to_string()].into(),
+            ..Default::default()
+        });
+        let object = |type_: &str| {
+            json!({
+                "objectId": "0x5",
+                "bcs": {"dataType": "moveObject", "type": type_, "bcsBytes": "AQID"},
+            })
+        };
+
+        // Matches all instantiations of the type.
+        let coin = response(json!({"data": [object("0x2::coin::Coin<0x2::sui::SUI>")]}));
+        let redacted: Value = serde_json::from_str(&redaction.redact(None, coin)).unwrap();
+        assert_eq!(
+            redacted["result"]["data"][0]["bcs"]["bcsBytes"],
+            Value::Null
+        );
+        assert_eq!(
+            redacted["result"]["data"][0]["bcs"]["type"],
+            "0x2::coin::Coin<0x2::sui::SUI>"
+        );
+
+        let other = response(json!({"data": [object("0x2::kiosk::Kiosk")]}));
+        assert_eq!(redaction.redact(None, other.clone()), other);
+    }
+
+    #[test]
+    fn test_mask_balances() {
+        let redaction = redaction(RedactionConfig {
+            exempt_api_keys: ["secret".to_string()].into(),
+            mask_balances_below: Some(1_000),
+            ..Default::default()
+        });
+        let balances = response(json!([
+            {"coinType": "0x2::sui::SUI", "totalBalance": "999", "lockedBalance": {}},
+            {"coinType": "0x2::coin::USDC", "totalBalance": "1000", "lockedBalance": {}},
+        ]));
+
+        let redacted: Value =
+            serde_json::from_str(&redaction.redact(Some("public"), balances.clone())).unwrap();
+        assert_eq!(redacted["result"][0]["totalBalance"], Value::Null);
+        assert_eq!(redacted["result"][0]["lockedBalance"], Value::Null);
+        assert_eq!(redacted["result"][1]["totalBalance"], "1000");
+
+        assert_eq!(redaction.redact(Some("secret"), balances.clone()), balances);
+        redaction.set_config(RedactionConfig::default()).unwrap();
+        assert_eq!(redaction.redact(None, balances.clone()), balances);
+    }
+
+    #[test]
+    fn test_redact_notifications() {
+        let redaction = redaction(RedactionConfig {
+            exempt_api_keys: ["secret".to_string()].into(),
+            hide_bcs_types: ["0x2::coin::Coin".to_string()].into(),
+            ..Default::default()
+        });
+        let object = json!({
+            "objectId": "0x5",
+            "bcs": {
+                "dataType": "moveObject",
+                "type": "0x2::coin::Coin<0x2::sui::SUI>",
+                "bcsBytes": "AQID",
+            },
+        });
+
+        // Notifications carry their result in their params.
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "suix_subscribeObject",
+            "params": {"subscription": 1, "result": object},
+        })
+        .to_string();
+        let redacted: Value =
+            serde_json::from_str(&redaction.redact(None, notification.clone())).unwrap();
+        assert_eq!(redacted["params"]["result"]["bcs"]["bcsBytes"], Value::Null);
+        assert_eq!(
+            redaction.redact(Some("secret"), notification.clone()),
+            notification
+        );
+
+        // Items sent on their own, e.g. over SSE.
+        let mut item = object.clone();
+        redaction.redact_item(None, &mut item);
+        assert_eq!(item["bcs"]["bcsBytes"], Value::Null);
+        let mut item = object.clone();
+        redaction.redact_item(Some("secret"), &mut item);
+        assert_eq!(item, object);
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        let redaction = redaction(RedactionConfig {
+            mask_balances_below: Some(1),
+            ..Default::default()
+        });
+        assert!(redaction
+            .set_config(RedactionConfig {
+                hide_bcs_types: ["not a type".to_string()].into(),
+                ..Default::default()
+            })
+            .is_err());
+        assert_eq!(redaction.rules.load().mask_balances_below, Some(1));
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The method and parameters of the request that a server is serving, so that the backends that
+//! serve it can attribute their work to it, e.g. to give up on a database query that runs past
+//! its method's deadline. The context is set for the duration of each method call, on the task
+//! making it.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+
+/// Number of bytes of the hash of a request's parameters kept in its fingerprint.
+const FINGERPRINT_BYTES: usize = 8;
+
+tokio::task_local! {
+    static REQUEST: RequestContext;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestContext {
+    /// The method called, after aliases are resolved.
+    pub method: String,
+    params: Option<Arc<str>>,
+}
+
+impl RequestContext {
+    pub fn new(method: &str, params: Option<&str>) -> Self {
+        Self {
+            method: method.to_string(),
+            params: params.map(Into::into),
+        }
+    }
+
+    /// The context of the request being served by the current task, if any.
+    pub fn current() -> Option<Self> {
+        REQUEST.try_with(Clone::clone).ok()
+    }
+
+    /// Run `f` with this as the context of the request being served.
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        REQUEST.scope(self, f).await
+    }
+
+    /// A short hash of the request's parameters, identifying requests with the same parameters
+    /// without revealing them, e.g. in logs.
+    pub fn params_fingerprint(&self) -> String {
+        let params = self.params.as_deref().unwrap_or_default();
+        let digest = Blake2b256::digest(params.as_bytes());
+        Hex::encode(&digest.digest[..FINGERPRINT_BYTES])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_context() {
+        assert_eq!(RequestContext::current(), None);
+
+        let context = RequestContext::new("suix_getBalance", Some(r#"["0x1"]"#));
+        let current = context
+            .clone()
+            .scope(async { RequestContext::current() })
+            .await;
+        assert_eq!(current, Some(context.clone()));
+        assert_eq!(RequestContext::current(), None);
+
+        let same = RequestContext::new("suix_getAllBalances", Some(r#"["0x1"]"#));
+        let other = RequestContext::new("suix_getBalance", Some(r#"["0x2"]"#));
+        assert_eq!(context.params_fingerprint().len(), 2 * FINGERPRINT_BYTES);
+        assert_eq!(context.params_fingerprint(), same.params_fingerprint());
+        assert_ne!(context.params_fingerprint(), other.params_fingerprint());
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
+use fastcrypto::traits::ToFromBytes;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+
+use sui_core::authority_client::NetworkAuthorityClient;
+use sui_core::scheduled_transactions::{ScheduledTransaction, ScheduledTransactionService};
+use sui_json_rpc_api::{ScheduledTransactionApiOpenRpc, ScheduledTransactionApiServer};
+use sui_json_rpc_types::SuiScheduledTransactionBlock;
+use sui_open_rpc::Module;
+use sui_types::digests::TransactionDigest;
+use sui_types::signature::GenericSignature;
+use sui_types::sui_serde::BigInt;
+use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
+use tracing::instrument;
+
+use crate::error::{Error, SuiRpcInputError};
+use crate::{with_tracing, SuiRpcModule};
+
+pub struct ScheduledTransactionApi {
+    service: Arc<ScheduledTransactionService<NetworkAuthorityClient>>,
+}
+
+impl ScheduledTransactionApi {
+    pub fn new(service: Arc<ScheduledTransactionService<NetworkAuthorityClient>>) -> Self {
+        Self { service }
+    }
+}
+
+fn signed_transaction(
+    tx_bytes: Base64,
+    signatures: Vec<Base64>,
+) -> Result<Transaction, SuiRpcInputError> {
+    let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.to_vec()?)?;
+    let mut sigs = Vec::new();
+    for sig in signatures {
+        sigs.push(GenericSignature::from_bytes(&sig.to_vec()?)?);
+    }
+    Ok(Transaction::from_generic_sig_data(tx_data, sigs))
+}
+
+fn cancellation_signature(signature: Base64) -> Result<GenericSignature, SuiRpcInputError> {
+    Ok(GenericSignature::from_bytes(&signature.to_vec()?)?)
+}
+
+impl From<ScheduledTransaction> for SuiScheduledTransactionBlock {
+    fn from(scheduled: ScheduledTransaction) -> Self {
+        Self {
+            digest: *scheduled.digest(),
+            sender: scheduled.transaction.data().transaction_data().sender(),
+            not_before_checkpoint: scheduled.not_before_checkpoint,
+            not_before_timestamp_ms: scheduled.not_before_timestamp_ms,
+            submit_attempts: scheduled.submit_attempts,
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduledTransactionApiServer for ScheduledTransactionApi {
+    #[instrument(skip_all)]
+    async fn schedule_transaction_block(
+        &self,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+        not_before_checkpoint: Option<BigInt<u64>>,
+        not_before_timestamp_ms: Option<BigInt<u64>>,
+    ) -> RpcResult<TransactionDigest> {
+        with_tracing!(async move {
+            let transaction = signed_transaction(tx_bytes, signatures)?;
+            Ok(self.service.schedule(
+                transaction,
+                not_before_checkpoint.map(|c| *c),
+                not_before_timestamp_ms.map(|t| *t),
+            )?)
+        })
+    }
+
+    #[instrument(skip(self, signature))]
+    async fn cancel_scheduled_transaction_block(
+        &self,
+        digest: TransactionDigest,
+        signature: Base64,
+    ) -> RpcResult<bool> {
+        with_tracing!(async move {
+            let signature = cancellation_signature(signature)?;
+            Ok(self.service.cancel(&digest, &signature)?)
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_scheduled_transaction_block(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<Option<SuiScheduledTransactionBlock>> {
+        with_tracing!(async move {
+            Ok(self
+                .service
+                .get(&digest)?
+                .map(SuiScheduledTransactionBlock::from))
+        })
+    }
+}
+
+impl SuiRpcModule for ScheduledTransactionApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        ScheduledTransactionApiOpenRpc::module_doc()
+    }
+}
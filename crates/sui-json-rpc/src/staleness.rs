@@ -0,0 +1,129 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serving requests from data that has fallen behind the chain, e.g. because an indexer's
+//! ingestion is paused or lagging. The server's owner records how far behind its data is, and
+//! once that lag passes the configured thresholds:
+//!
+//! - responses carry the lag in the [`DATA_LAG_HEADER`], warning clients that they may be stale
+//!   (as do the responses opening WebSocket connections and SSE streams),
+//! - methods whose results would be misleading when stale (e.g. balances that must be consistent
+//!   with recent transactions) are rejected with a transient error, so clients retry elsewhere,
+//!   whether they are called over HTTP or WebSocket. Subscriptions to them over SSE fail with an
+//!   `error` event instead.
+//!
+//! Nothing is flagged or rejected until a lag has been recorded.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use serde::{Deserialize, Serialize};
+
+pub use sui_json_rpc_api::DATA_LAG_HEADER;
+
+/// Lag recorded before the first measurement.
+const UNKNOWN_LAG: u64 = u64::MAX;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct StalenessConfig {
+    /// Lag, in milliseconds, beyond which responses carry the [`DATA_LAG_HEADER`]. Responses are
+    /// never flagged if this is unset.
+    pub warn_after_ms: Option<u64>,
+    /// Lag, in milliseconds, beyond which each method is rejected.
+    pub max_lag_ms: BTreeMap<String, u64>,
+}
+
+/// How far behind a server's data is, and what to do about it. Clones share the same state, so
+/// the task measuring the lag and the operator's tooling can update a running server.
+#[derive(Clone, Debug)]
+pub struct Staleness {
+    config: Arc<ArcSwap<StalenessConfig>>,
+    lag_ms: Arc<AtomicU64>,
+}
+
+impl Default for Staleness {
+    fn default() -> Self {
+        Self {
+            config: Default::default(),
+            lag_ms: Arc::new(AtomicU64::new(UNKNOWN_LAG)),
+        }
+    }
+}
+
+impl Staleness {
+    pub fn set_config(&self, config: StalenessConfig) {
+        self.config.store(Arc::new(config));
+    }
+
+    /// Record that the server's data is `lag` behind the chain.
+    pub fn record_lag(&self, lag: Duration) {
+        let lag_ms = u64::try_from(lag.as_millis()).unwrap_or(u64::MAX);
+        self.lag_ms
+            .store(lag_ms.min(UNKNOWN_LAG - 1), Ordering::Relaxed);
+    }
+
+    /// The lag last recorded, if any.
+    pub fn lag(&self) -> Option<Duration> {
+        match self.lag_ms.load(Ordering::Relaxed) {
+            UNKNOWN_LAG => None,
+            lag_ms => Some(Duration::from_millis(lag_ms)),
+        }
+    }
+
+    /// The lag to warn clients about, in milliseconds, if it passes the warning threshold.
+    pub(crate) fn warning(&self) -> Option<u64> {
+        let warn_after_ms = self.config.load().warn_after_ms?;
+        let lag_ms = self.lag()?.as_millis() as u64;
+        (lag_ms > warn_after_ms).then_some(lag_ms)
+    }
+
+    /// An error message for calls to `method`, if the lag passes the method's threshold.
+    pub(crate) fn reject(&self, method: &str) -> Option<String> {
+        let max_lag_ms = *self.config.load().max_lag_ms.get(method)?;
+        let lag_ms = self.lag()?.as_millis() as u64;
+        (lag_ms > max_lag_ms).then(|| {
+            format!(
+                "Method {method} is unavailable while this server's data is {lag_ms}ms behind \
+                 the chain (limit {max_lag_ms}ms)"
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thresholds() {
+        let staleness = Staleness::default();
+        staleness.set_config(StalenessConfig {
+            warn_after_ms: Some(1_000),
+            max_lag_ms: BTreeMap::from([("suix_getBalance".to_string(), 5_000)]),
+        });
+
+        // Nothing is flagged until the lag is known.
+        assert_eq!(staleness.warning(), None);
+        assert_eq!(staleness.reject("suix_getBalance"), None);
+
+        staleness.record_lag(Duration::from_millis(500));
+        assert_eq!(staleness.warning(), None);
+
+        staleness.record_lag(Duration::from_millis(2_000));
+        assert_eq!(staleness.warning(), Some(2_000));
+        assert_eq!(staleness.reject("suix_getBalance"), None);
+
+        staleness.record_lag(Duration::from_secs(10));
+        assert!(staleness.reject("suix_getBalance").is_some());
+        assert_eq!(staleness.reject("sui_getObject"), None);
+
+        // Config changes apply to the lag already recorded.
+        staleness.set_config(StalenessConfig::default());
+        assert_eq!(staleness.warning(), None);
+        assert_eq!(staleness.reject("suix_getBalance"), None);
+    }
+}
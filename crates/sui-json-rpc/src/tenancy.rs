@@ -0,0 +1,617 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Isolating the tenants of a server shared between several projects, e.g. a hosted indexer
+//! serving each of its customers from the same database. Each tenant is configured with the API
+//! keys its requests are made with, and the packages it can see. In the responses and
+//! subscription notifications sent to a tenant:
+//!
+//! - events are only kept if they were emitted by one of its packages, or their type mentions one
+//!   of them,
+//! - objects are reported as not existing unless their type mentions one of its packages (e.g.
+//!   `0x2::coin::Coin<0xabc::usdc::USDC>` is visible to a tenant with package `0xabc`), or they
+//!   are one of its packages. Objects whose type isn't in the response can't be told apart, so
+//!   they are hidden too,
+//! - object changes, balance changes, balances and coins are only kept if their type mentions one
+//!   of its packages, or they publish one of them. A balance asked for on its own is reported as
+//!   zero,
+//! - transactions are only kept if they call one of its packages, or the tenant can see any of
+//!   their events, object changes or balance changes. A transaction asked for on its own is cut
+//!   down to its digest. Transaction effects don't show which packages they touch, so they are
+//!   not sent to tenants as notifications.
+//!
+//! Items are removed from pages after they are read, so a tenant's pages may have fewer items
+//! than it asked for, but their cursors can still be followed. Requests with API keys of no
+//! tenant get full responses, unless they are configured to be rejected.
+//!
+//! Requests over HTTP and WebSocket (with the API key sent when the connection is opened), and
+//! subscriptions over SSE, are all isolated. Nothing is isolated until a config is set.
+
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sui_types::base_types::ObjectID;
+use sui_types::{parse_sui_struct_tag, parse_sui_type_tag};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct TenancyConfig {
+    pub tenants: Vec<TenantConfig>,
+    /// Reject requests made without the API key of a tenant.
+    pub reject_unknown_keys: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct TenantConfig {
+    pub name: String,
+    /// API keys the tenant's requests are made with.
+    pub api_keys: BTreeSet<String>,
+    /// Packages whose events and objects the tenant can see.
+    pub packages: BTreeSet<ObjectID>,
+}
+
+/// A tenant, as far as isolating it goes.
+#[derive(Debug)]
+pub(crate) struct Tenant {
+    name: String,
+    packages: BTreeSet<AccountAddress>,
+}
+
+/// The rules of a config, set up for looking tenants up by API key.
+#[derive(Debug, Default)]
+struct Rules {
+    tenants: HashMap<String, Arc<Tenant>>,
+    reject_unknown_keys: bool,
+}
+
+/// The tenants a server isolates from each other. Clones share the same config, so a handle kept
+/// by the operator's tooling can update the tenants of a running server.
+#[derive(Clone, Debug, Default)]
+pub struct Tenancy {
+    rules: Arc<ArcSwap<Rules>>,
+}
+
+impl Tenancy {
+    /// Replace the tenants with those in `config`. The previous tenants are kept if an API key
+    /// is given to more than one tenant.
+    pub fn set_config(&self, config: TenancyConfig) -> anyhow::Result<()> {
+        let mut tenants = HashMap::new();
+        for tenant in config.tenants {
+            let isolated = Arc::new(Tenant {
+                name: tenant.name,
+                packages: tenant.packages.into_iter().map(Into::into).collect(),
+            });
+            for api_key in tenant.api_keys {
+                if let Some(other) = tenants.insert(api_key, isolated.clone()) {
+                    anyhow::bail!(
+                        "An API key is given to both tenants {:?} and {:?}",
+                        other.name,
+                        isolated.name
+                    );
+                }
+            }
+        }
+        self.rules.store(Arc::new(Rules {
+            tenants,
+            reject_unknown_keys: config.reject_unknown_keys,
+        }));
+        Ok(())
+    }
+
+    /// The tenant that made a request with `api_key`, if any, or a message for the client if the
+    /// request is rejected.
+    pub(crate) fn tenant(&self, api_key: Option<&str>) -> Result<Option<Arc<Tenant>>, String> {
+        let rules = self.rules.load();
+        match api_key.and_then(|key| rules.tenants.get(key)) {
+            Some(tenant) => Ok(Some(tenant.clone())),
+            None if rules.reject_unknown_keys => {
+                Err("Requests must be made with the API key of a tenant".to_string())
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Fields of a transaction response, by which transactions are told apart from other items.
+const TRANSACTION_FIELDS: &[&str] = &[
+    "digest",
+    "transaction",
+    "rawTransaction",
+    "effects",
+    "events",
+    "objectChanges",
+    "balanceChanges",
+    "timestampMs",
+    "confirmedLocalExecution",
+    "checkpoint",
+    "errors",
+    "rawEffects",
+    "labels",
+];
+
+impl Tenant {
+    /// Remove what the tenant can't see from the `result` of `response`, a serialized JSON-RPC
+    /// response to one of its requests. The response is returned as is if there is nothing to
+    /// remove from it.
+    pub(crate) fn isolate(&self, response: String) -> String {
+        let Ok(mut json) = serde_json::from_str::<Value>(&response) else {
+            return response;
+        };
+        match json.get_mut("result") {
+            Some(result) if self.filter(result) => serde_json::to_string(&json).unwrap_or(response),
+            _ => response,
+        }
+    }
+
+    /// Remove what the tenant can't see from `message`, a serialized JSON-RPC response or
+    /// subscription notification sent to it, returning `None` if it is a notification of an item
+    /// the tenant can't see.
+    pub(crate) fn isolate_message(&self, message: String) -> Option<String> {
+        let Ok(mut json) = serde_json::from_str::<Value>(&message) else {
+            return Some(message);
+        };
+        let Some(result) = json.get_mut("params").and_then(|p| p.get_mut("result")) else {
+            return Some(self.isolate(message));
+        };
+        let filtered = self.filter(result);
+        if !self.sees_item(result) {
+            return None;
+        }
+        Some(if filtered {
+            serde_json::to_string(&json).unwrap_or(message)
+        } else {
+            message
+        })
+    }
+
+    /// Remove what the tenant can't see from `item`, an item sent on its own to a subscriber
+    /// (e.g. an event), returning whether the tenant can see it at all.
+    pub(crate) fn isolate_item(&self, item: &mut Value) -> bool {
+        self.filter(item);
+        self.sees_item(item)
+    }
+
+    /// Whether the tenant can see `item`, sent on its own to a subscriber, once filtered.
+    fn sees_item(&self, item: &Value) -> bool {
+        let Value::Object(fields) = item else {
+            return true;
+        };
+        if is_transaction(fields) {
+            return self.sees_transaction(fields);
+        }
+        // Transaction effects, on their own or with their checkpoint, can't be told apart.
+        if fields.contains_key("transactionDigest") || fields.contains_key("effects") {
+            return false;
+        }
+        !self.hides(fields)
+    }
+
+    /// Remove what the tenant can't see from `value` and everything nested in it, returning
+    /// whether anything was removed.
+    fn filter(&self, value: &mut Value) -> bool {
+        match value {
+            Value::Array(values) => {
+                // Nested items are filtered first, so that transactions are judged by what the
+                // tenant can see of them.
+                let filtered = values
+                    .iter_mut()
+                    .fold(false, |filtered, value| self.filter(value) | filtered);
+                let len = values.len();
+                values.retain(|value| match value {
+                    Value::Object(fields) => !self.hides(fields),
+                    _ => true,
+                });
+                filtered | (values.len() != len)
+            }
+            Value::Object(fields) => {
+                if let Some(object_id) = self.hidden_object(fields) {
+                    *value = json!({"error": {"code": "notExists", "object_id": object_id}});
+                    return true;
+                }
+                let filtered = fields
+                    .values_mut()
+                    .fold(false, |filtered, value| self.filter(value) | filtered);
+                if is_transaction(fields) && !self.sees_transaction(fields) {
+                    fields.retain(|field, _| field == "digest");
+                    return true;
+                }
+                if fields.contains_key("totalBalance") && self.hides_coin_typed(fields) {
+                    fields.insert("coinObjectCount".to_string(), json!(0));
+                    fields.insert("totalBalance".to_string(), json!("0"));
+                    fields.insert("lockedBalance".to_string(), json!({}));
+                    fields.remove("fiatValue");
+                    return true;
+                }
+                filtered
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `fields` are those of an item that is removed from lists the tenant is sent.
+    fn hides(&self, fields: &Map<String, Value>) -> bool {
+        self.hides_event(fields)
+            || self.hides_object_change(fields)
+            || self.hides_coin_typed(fields)
+            || (is_transaction(fields) && !self.sees_transaction(fields))
+    }
+
+    /// Whether `fields` are those of an event the tenant can't see.
+    fn hides_event(&self, fields: &Map<String, Value>) -> bool {
+        let (Some(Value::String(package)), Some(Value::String(type_)), true) = (
+            fields.get("packageId"),
+            fields.get("type"),
+            fields.contains_key("transactionModule"),
+        ) else {
+            return false;
+        };
+        let emitted_by_package = self.is_package(package);
+        let of_package = parse_sui_struct_tag(type_).is_ok_and(|t| self.mentions_struct(&t));
+        !(emitted_by_package || of_package)
+    }
+
+    /// Whether `fields` are those of an object change the tenant can't see: a change to an object
+    /// of a type that doesn't mention its packages, or the publication of another package.
+    fn hides_object_change(&self, fields: &Map<String, Value>) -> bool {
+        let Some(Value::String(change)) = fields.get("type") else {
+            return false;
+        };
+        match (
+            change.as_str(),
+            fields.get("objectType"),
+            fields.get("packageId"),
+        ) {
+            ("published", _, Some(Value::String(package))) => !self.is_package(package),
+            (
+                "created" | "mutated" | "transferred" | "deleted" | "wrapped",
+                Some(Value::String(type_)),
+                _,
+            ) => !parse_sui_struct_tag(type_).is_ok_and(|t| self.mentions_struct(&t)),
+            _ => false,
+        }
+    }
+
+    /// Whether `fields` are those of a balance change, balance or coin of a coin type that
+    /// doesn't mention the tenant's packages.
+    fn hides_coin_typed(&self, fields: &Map<String, Value>) -> bool {
+        let Some(Value::String(coin_type)) = fields.get("coinType") else {
+            return false;
+        };
+        !parse_sui_type_tag(coin_type).is_ok_and(|t| self.mentions(&t))
+    }
+
+    /// Whether the tenant can see the transaction in `fields`, whose events and changes have
+    /// already been filtered.
+    fn sees_transaction(&self, fields: &Map<String, Value>) -> bool {
+        let non_empty = |field: &str| {
+            fields
+                .get(field)
+                .and_then(Value::as_array)
+                .is_some_and(|values| !values.is_empty())
+        };
+        non_empty("events")
+            || non_empty("objectChanges")
+            || non_empty("balanceChanges")
+            || fields
+                .get("transaction")
+                .is_some_and(|transaction| self.calls_package(transaction))
+    }
+
+    /// Whether `value`, a transaction or part of one, calls one of the tenant's packages.
+    fn calls_package(&self, value: &Value) -> bool {
+        match value {
+            Value::Array(values) => values.iter().any(|value| self.calls_package(value)),
+            Value::Object(fields) => {
+                let called = fields
+                    .get("MoveCall")
+                    .and_then(|call| call.get("package"))
+                    .and_then(Value::as_str)
+                    .is_some_and(|package| self.is_package(package));
+                called || fields.values().any(|value| self.calls_package(value))
+            }
+            _ => false,
+        }
+    }
+
+    /// The ID of the object in `fields`, an object response (e.g. of `sui_getObject`), if the
+    /// tenant can't see it.
+    fn hidden_object(&self, fields: &Map<String, Value>) -> Option<Value> {
+        let Some(Value::Object(data)) = fields.get("data") else {
+            return None;
+        };
+        let object_id = data.get("objectId")?;
+        let visible = match data.get("type") {
+            Some(Value::String(type_)) if type_ == "package" => {
+                object_id.as_str().is_some_and(|id| self.is_package(id))
+            }
+            Some(Value::String(type_)) => {
+                parse_sui_struct_tag(type_).is_ok_and(|t| self.mentions_struct(&t))
+            }
+            _ => false,
+        };
+        (!visible).then(|| object_id.clone())
+    }
+
+    fn is_package(&self, id: &str) -> bool {
+        AccountAddress::from_str(id).is_ok_and(|id| self.packages.contains(&id))
+    }
+
+    /// Whether `type_` or any of its type parameters is defined in one of the tenant's packages.
+    fn mentions_struct(&self, type_: &StructTag) -> bool {
+        self.packages.contains(&type_.address) || type_.type_params.iter().any(|t| self.mentions(t))
+    }
+
+    fn mentions(&self, type_: &TypeTag) -> bool {
+        match type_ {
+            TypeTag::Struct(type_) => self.mentions_struct(type_),
+            TypeTag::Vector(type_) => self.mentions(type_),
+            _ => false,
+        }
+    }
+}
+
+/// Whether `fields` are those of a transaction response.
+fn is_transaction(fields: &Map<String, Value>) -> bool {
+    fields.contains_key("digest")
+        && fields
+            .keys()
+            .all(|f| TRANSACTION_FIELDS.contains(&f.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(result: Value) -> String {
+        json!({"jsonrpc": "2.0", "id": 1, "result": result}).to_string()
+    }
+
+    fn tenancy() -> Tenancy {
+        let tenancy = Tenancy::default();
+        tenancy
+            .set_config(TenancyConfig {
+                tenants: vec![TenantConfig {
+                    name: "usdc".to_string(),
+                    api_keys: ["usdc-key".to_string()].into(),
+                    packages: [ObjectID::from_str("0xabc").unwrap()].into(),
+                }],
+                reject_unknown_keys: false,
+            })
+            .unwrap();
+        tenancy
+    }
+
+    fn event(package: &str, type_: &str) -> Value {
+        json!({
+            "id": {"txDigest": "11111111111111111111111111111111", "eventSeq": "0"},
+            "packageId": package,
+            "transactionModule": "m",
+            "type": type_,
+        })
+    }
+
+    fn object(object_id: &str, type_: &str) -> Value {
+        json!({"data": {"objectId": object_id, "version": "1", "type": type_}})
+    }
+
+    #[test]
+    fn test_isolate_events() {
+        let tenant = tenancy().tenant(Some("usdc-key")).unwrap().unwrap();
+        let page = response(json!({
+            "data": [
+                event("0xabc", "0xabc::usdc::Minted"),
+                event("0xdef", "0xdef::nft::Minted"),
+                event("0x2", "0x2::coin::CurrencyCreated<0xabc::usdc::USDC>"),
+                event("0xabc", "0x2::display::DisplayCreated<0xdef::nft::NFT>"),
+            ],
+            "nextCursor": {"txDigest": "11111111111111111111111111111111", "eventSeq": "3"},
+            "hasNextPage": true,
+        }));
+
+        let isolated: Value = serde_json::from_str(&tenant.isolate(page)).unwrap();
+        let events = isolated["result"]["data"].as_array().unwrap();
+        let types: Vec<_> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "0xabc::usdc::Minted",
+                "0x2::coin::CurrencyCreated<0xabc::usdc::USDC>",
+                "0x2::display::DisplayCreated<0xdef::nft::NFT>",
+            ]
+        );
+        assert_eq!(isolated["result"]["hasNextPage"], true);
+    }
+
+    #[test]
+    fn test_isolate_objects() {
+        let tenant = tenancy().tenant(Some("usdc-key")).unwrap().unwrap();
+        let objects = response(json!([
+            object("0x1", "0x2::coin::Coin<0xabc::usdc::USDC>"),
+            object("0x2", "0x2::coin::Coin<0x2::sui::SUI>"),
+            object("0xabc", "package"),
+            {"data": {"objectId": "0x3", "version": "1"}},
+        ]));
+
+        let isolated: Value = serde_json::from_str(&tenant.isolate(objects)).unwrap();
+        let objects = isolated["result"].as_array().unwrap();
+        assert_eq!(objects.len(), 4);
+        assert_eq!(objects[0]["data"]["objectId"], "0x1");
+        assert_eq!(
+            objects[1],
+            json!({"error": {"code": "notExists", "object_id": "0x2"}})
+        );
+        assert_eq!(objects[2]["data"]["objectId"], "0xabc");
+        assert_eq!(objects[3]["error"]["object_id"], "0x3");
+
+        // Nothing to remove.
+        let visible = response(object("0x1", "0xabc::usdc::Treasury"));
+        assert_eq!(tenant.isolate(visible.clone()), visible);
+    }
+
+    fn transaction(digest: &str, fields: Value) -> Value {
+        let mut transaction = json!({"digest": digest});
+        transaction
+            .as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        transaction
+    }
+
+    #[test]
+    fn test_isolate_transactions() {
+        let tenant = tenancy().tenant(Some("usdc-key")).unwrap().unwrap();
+        let call = |package: &str| {
+            json!({"data": {"transaction": {
+                "kind": "ProgrammableTransaction",
+                "inputs": [{"type": "object", "objectType": "immOrOwnedObject", "objectId": "0x5"}],
+                "transactions": [{"MoveCall": {"package": package, "module": "m", "function": "f"}}],
+            }}})
+        };
+        let page = response(json!({
+            "data": [
+                // Calls the tenant's package.
+                transaction("A", json!({"transaction": call("0xabc")})),
+                // Calls another package, and changes objects of both.
+                transaction("B", json!({
+                    "transaction": call("0xdef"),
+                    "objectChanges": [
+                        {"type": "created", "objectType": "0xabc::usdc::Treasury", "objectId": "0x6"},
+                        {"type": "mutated", "objectType": "0xdef::nft::NFT", "objectId": "0x7"},
+                    ],
+                    "balanceChanges": [
+                        {"owner": {"AddressOwner": "0x1"}, "coinType": "0x2::sui::SUI", "amount": "-1"},
+                    ],
+                })),
+                // Only touches what the tenant can't see.
+                transaction("C", json!({
+                    "transaction": call("0xdef"),
+                    "events": [event("0xdef", "0xdef::nft::Minted")],
+                    "objectChanges": [{"type": "published", "packageId": "0xdef", "modules": []}],
+                })),
+                // Can't be told apart.
+                transaction("D", json!({})),
+            ],
+            "nextCursor": "D",
+            "hasNextPage": false,
+        }));
+
+        let isolated: Value = serde_json::from_str(&tenant.isolate(page)).unwrap();
+        let transactions = isolated["result"]["data"].as_array().unwrap();
+        let digests: Vec<_> = transactions.iter().map(|t| &t["digest"]).collect();
+        assert_eq!(digests, vec!["A", "B"]);
+        // Inputs aren't mistaken for object changes.
+        assert_eq!(
+            transactions[0],
+            transaction("A", json!({"transaction": call("0xabc")}))
+        );
+        assert_eq!(
+            transactions[1]["objectChanges"],
+            json!([{"type": "created", "objectType": "0xabc::usdc::Treasury", "objectId": "0x6"}])
+        );
+        assert_eq!(transactions[1]["balanceChanges"], json!([]));
+
+        // A transaction asked for on its own is cut down to its digest.
+        let single = response(transaction("C", json!({"transaction": call("0xdef")})));
+        let isolated: Value = serde_json::from_str(&tenant.isolate(single)).unwrap();
+        assert_eq!(isolated["result"], json!({"digest": "C"}));
+    }
+
+    #[test]
+    fn test_isolate_balances() {
+        let tenant = tenancy().tenant(Some("usdc-key")).unwrap().unwrap();
+        let balance = |coin_type: &str| {
+            json!({
+                "coinType": coin_type,
+                "coinObjectCount": 2,
+                "totalBalance": "100",
+                "lockedBalance": {},
+            })
+        };
+        let balances = response(json!([
+            balance("0x2::sui::SUI"),
+            balance("0xabc::usdc::USDC"),
+            balance("0xdef::lp::LP<0xabc::usdc::USDC>"),
+        ]));
+        let isolated: Value = serde_json::from_str(&tenant.isolate(balances)).unwrap();
+        let coin_types: Vec<_> = isolated["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["coinType"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            coin_types,
+            vec!["0xabc::usdc::USDC", "0xdef::lp::LP<0xabc::usdc::USDC>"]
+        );
+
+        // A balance asked for on its own is reported as zero.
+        let isolated: Value =
+            serde_json::from_str(&tenant.isolate(response(balance("0x2::sui::SUI")))).unwrap();
+        assert_eq!(isolated["result"]["totalBalance"], "0");
+        assert_eq!(isolated["result"]["coinObjectCount"], 0);
+    }
+
+    #[test]
+    fn test_isolate_notifications() {
+        let tenant = tenancy().tenant(Some("usdc-key")).unwrap().unwrap();
+        let notification = |result: Value| {
+            json!({
+                "jsonrpc": "2.0",
+                "method": "suix_subscribeEvent",
+                "params": {"subscription": 1, "result": result},
+            })
+            .to_string()
+        };
+
+        let visible = notification(event("0xabc", "0xabc::usdc::Minted"));
+        assert_eq!(tenant.isolate_message(visible.clone()), Some(visible));
+        let hidden = notification(event("0xdef", "0xdef::nft::Minted"));
+        assert_eq!(tenant.isolate_message(hidden), None);
+        let effects = notification(json!({"transactionDigest": "A", "status": {}}));
+        assert_eq!(tenant.isolate_message(effects), None);
+
+        // Responses are isolated as they are over HTTP.
+        let page = response(json!([event("0xdef", "0xdef::nft::Minted")]));
+        let isolated: Value = serde_json::from_str(&tenant.isolate_message(page).unwrap()).unwrap();
+        assert_eq!(isolated["result"], json!([]));
+
+        let mut item = event("0xdef", "0xdef::nft::Minted");
+        assert!(!tenant.isolate_item(&mut item));
+    }
+
+    #[test]
+    fn test_tenant_keys() {
+        let tenancy = tenancy();
+        assert!(tenancy.tenant(None).unwrap().is_none());
+        assert!(tenancy.tenant(Some("other")).unwrap().is_none());
+
+        let mut config = TenancyConfig {
+            tenants: vec![TenantConfig {
+                name: "nft".to_string(),
+                api_keys: ["nft-key".to_string()].into(),
+                packages: BTreeSet::new(),
+            }],
+            reject_unknown_keys: true,
+        };
+        tenancy.set_config(config.clone()).unwrap();
+        assert!(tenancy.tenant(Some("usdc-key")).is_err());
+        assert_eq!(
+            tenancy.tenant(Some("nft-key")).unwrap().unwrap().name,
+            "nft"
+        );
+
+        // Keys can't be shared between tenants.
+        let mut other = config.tenants[0].clone();
+        other.name = "other".to_string();
+        config.tenants.push(other);
+        assert!(tenancy.set_config(config).is_err());
+        assert!(tenancy.tenant(Some("nft-key")).unwrap().is_some());
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -194,6 +195,31 @@ impl TransactionBuilderServer for TransactionBuilderApi {
         Ok(TransactionBlockBytes::from_data(data)?)
     }
 
+    async fn publish_with_dependency_resolution(
+        &self,
+        sender: SuiAddress,
+        compiled_modules: Vec<Base64>,
+        pinned_dependencies: Option<BTreeMap<ObjectID, ObjectID>>,
+        gas: Option<ObjectID>,
+        gas_budget: BigInt<u64>,
+    ) -> RpcResult<TransactionBlockBytes> {
+        let compiled_modules = compiled_modules
+            .into_iter()
+            .map(|data| data.to_vec().map_err(|e| anyhow::anyhow!(e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let data = self
+            .0
+            .publish_with_dependency_resolution(
+                sender,
+                compiled_modules,
+                pinned_dependencies.unwrap_or_default(),
+                gas,
+                *gas_budget,
+            )
+            .await?;
+        Ok(TransactionBlockBytes::from_data(data)?)
+    }
+
     async fn split_coin(
         &self,
         signer: SuiAddress,
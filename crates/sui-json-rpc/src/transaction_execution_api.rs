@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -18,7 +19,8 @@ use sui_core::transaction_orchestrator::TransactiondOrchestrator;
 use sui_json_rpc_api::{JsonRpcMetrics, WriteApiOpenRpc, WriteApiServer};
 use sui_json_rpc_types::{
     DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlock,
-    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockEvents, SuiTransactionBlockLint, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::SuiAddress;
@@ -37,11 +39,16 @@ use tracing::instrument;
 
 use crate::authority_state::StateRead;
 use crate::error::{Error, SuiRpcInputError};
+use crate::transaction_limits::check_transaction_limits;
+use crate::transaction_lints::lint_transaction;
 use crate::{
     get_balance_changes_from_effect, get_object_changes, with_tracing, ObjectProviderCache,
     SuiRpcModule,
 };
 
+/// The most transactions that can be dry-run in one bundle.
+const MAX_DRY_RUN_BUNDLE_SIZE: usize = 16;
+
 pub struct TransactionExecutionApi {
     state: Arc<dyn StateRead>,
     transaction_orchestrator: Arc<TransactiondOrchestrator<NetworkAuthorityClient>>,
@@ -104,6 +111,14 @@ impl TransactionExecutionApi {
             sigs.push(GenericSignature::from_bytes(&sig.to_vec()?)?);
         }
         let txn = Transaction::from_generic_sig_data(tx_data, sigs);
+        check_transaction_limits(
+            txn.data().transaction_data(),
+            bcs::serialized_size(txn.data())?,
+            self.state
+                .load_epoch_store_one_call_per_task()
+                .protocol_config(),
+            /* check_gas */ true,
+        )?;
         let raw_transaction = if opts.show_raw_input {
             bcs::to_bytes(txn.data())?
         } else {
@@ -213,6 +228,7 @@ impl TransactionExecutionApi {
             checkpoint: None,
             errors: vec![],
             raw_effects,
+            labels: None,
         })
     }
 
@@ -220,7 +236,16 @@ impl TransactionExecutionApi {
         &self,
         tx_bytes: Base64,
     ) -> Result<(TransactionData, TransactionDigest, Vec<InputObjectKind>), SuiRpcInputError> {
-        let tx_data: TransactionData = self.convert_bytes(tx_bytes)?;
+        let tx_bytes = tx_bytes.to_vec()?;
+        let tx_data: TransactionData = bcs::from_bytes(&tx_bytes)?;
+        check_transaction_limits(
+            &tx_data,
+            tx_bytes.len(),
+            self.state
+                .load_epoch_store_one_call_per_task()
+                .protocol_config(),
+            /* check_gas */ false,
+        )?;
         let input_objs = tx_data.input_objects()?;
         let intent_msg = IntentMessage::new(
             Intent {
@@ -268,8 +293,82 @@ impl TransactionExecutionApi {
             object_changes,
             balance_changes,
             input: resp.input,
+            gas_fiat_value: None,
         })
     }
+
+    async fn dry_run_transaction_blocks(
+        &self,
+        tx_bytes: Vec<Base64>,
+    ) -> Result<Vec<DryRunTransactionBlockResponse>, Error> {
+        if tx_bytes.is_empty() {
+            Err(SuiRpcInputError::GenericInvalid(
+                "Expected at least one transaction to dry-run".to_string(),
+            ))?
+        }
+        if tx_bytes.len() > MAX_DRY_RUN_BUNDLE_SIZE {
+            Err(SuiRpcInputError::SizeLimitExceeded(
+                MAX_DRY_RUN_BUNDLE_SIZE.to_string(),
+            ))?
+        }
+
+        let mut transactions = Vec::with_capacity(tx_bytes.len());
+        let mut inputs = Vec::with_capacity(tx_bytes.len());
+        for tx_bytes in tx_bytes {
+            let (txn_data, txn_digest, input_objs) =
+                self.prepare_dry_run_transaction_block(tx_bytes)?;
+            inputs.push((txn_data.sender(), input_objs));
+            transactions.push((txn_data, txn_digest));
+        }
+        let results = self.state.dry_exec_transaction_bundle(transactions).await?;
+
+        // Shared by the whole bundle, as each transaction can modify objects written by the ones
+        // before it, which are only found in the cache.
+        let object_cache = ObjectProviderCache::new(self.state.clone());
+        let mut written_ids = HashSet::new();
+        let mut responses = Vec::with_capacity(results.len());
+        for ((sender, input_objs), (resp, written_objects, transaction_effects, mock_gas)) in
+            inputs.into_iter().zip(results)
+        {
+            // Owned inputs written earlier in the bundle were read at their latest version, not
+            // necessarily the one the transaction refers to them by.
+            let input_objs = input_objs
+                .into_iter()
+                .filter(|kind| match kind {
+                    InputObjectKind::ImmOrOwnedMoveObject(oref) => !written_ids.contains(&oref.0),
+                    _ => true,
+                })
+                .collect();
+            written_ids.extend(written_objects.keys().copied());
+            object_cache.insert_written_objects(written_objects).await;
+
+            let balance_changes = get_balance_changes_from_effect(
+                &object_cache,
+                &transaction_effects,
+                input_objs,
+                mock_gas,
+            )
+            .await?;
+            let object_changes = get_object_changes(
+                &object_cache,
+                sender,
+                transaction_effects.modified_at_versions(),
+                transaction_effects.all_changed_objects(),
+                transaction_effects.all_removed_objects(),
+            )
+            .await?;
+
+            responses.push(DryRunTransactionBlockResponse {
+                effects: resp.effects,
+                events: resp.events,
+                object_changes,
+                balance_changes,
+                input: resp.input,
+                gas_fiat_value: None,
+            });
+        }
+        Ok(responses)
+    }
 }
 
 #[async_trait]
@@ -329,6 +428,25 @@ impl WriteApiServer for TransactionExecutionApi {
     ) -> RpcResult<DryRunTransactionBlockResponse> {
         with_tracing!(async move { self.dry_run_transaction_block(tx_bytes).await })
     }
+
+    #[instrument(skip(self))]
+    async fn dry_run_transaction_blocks(
+        &self,
+        tx_bytes: Vec<Base64>,
+    ) -> RpcResult<Vec<DryRunTransactionBlockResponse>> {
+        with_tracing!(async move { self.dry_run_transaction_blocks(tx_bytes).await })
+    }
+
+    #[instrument(skip(self))]
+    async fn lint_transaction_block(
+        &self,
+        tx_bytes: Base64,
+    ) -> RpcResult<Vec<SuiTransactionBlockLint>> {
+        with_tracing!(async move {
+            let tx_data: TransactionData = self.convert_bytes(tx_bytes)?;
+            Ok(lint_transaction(&tx_data))
+        })
+    }
 }
 
 impl SuiRpcModule for TransactionExecutionApi {
@@ -0,0 +1,100 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checking transactions against the limits of the current protocol config before they are
+//! executed, dry run, or forwarded to another server, so that clients learn the limits they
+//! exceeded from a precise error, rather than from a failed execution.
+
+use sui_protocol_config::ProtocolConfig;
+use sui_types::error::{SuiError, UserInputError};
+use sui_types::transaction::{TransactionData, TransactionDataAPI, VersionedProtocolMessage};
+
+use crate::error::SuiRpcInputError;
+
+/// Check `tx_data`, whose signed transaction serializes to `tx_size` bytes, against the limits
+/// of `config`: the transaction's size, gas budget, number of inputs and commands, and so on.
+/// Gas payment is only checked if `check_gas`, as dry runs can leave it for the server to pick.
+pub fn check_transaction_limits(
+    tx_data: &TransactionData,
+    tx_size: usize,
+    config: &ProtocolConfig,
+    check_gas: bool,
+) -> Result<(), SuiRpcInputError> {
+    let max_tx_size_bytes = config.max_tx_size_bytes();
+    if tx_size as u64 > max_tx_size_bytes {
+        return Err(UserInputError::SizeLimitExceeded {
+            limit: format!("serialized transaction size exceeded maximum of {max_tx_size_bytes}"),
+            value: tx_size.to_string(),
+        }
+        .into());
+    }
+
+    let max_budget = config.max_tx_gas();
+    if tx_data.gas_budget() > max_budget {
+        return Err(UserInputError::GasBudgetTooHigh {
+            gas_budget: tx_data.gas_budget(),
+            max_budget,
+        }
+        .into());
+    }
+
+    tx_data
+        .check_version_supported(config)
+        .map_err(|e| match e {
+            SuiError::UserInputError { error } => SuiRpcInputError::UserInputError(error),
+            e => SuiRpcInputError::GenericInvalid(e.to_string()),
+        })?;
+    if check_gas {
+        tx_data.validity_check(config)?;
+    } else {
+        tx_data.validity_check_no_gas_check(config)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_protocol_config::ProtocolVersion;
+    use sui_types::base_types::{random_object_ref, SuiAddress};
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+    fn transaction(gas_budget: u64) -> TransactionData {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_sui(SuiAddress::random_for_testing_only(), Some(1));
+        TransactionData::new_programmable(
+            SuiAddress::random_for_testing_only(),
+            vec![random_object_ref()],
+            builder.finish(),
+            gas_budget,
+            1000,
+        )
+    }
+
+    #[test]
+    fn test_check_transaction_limits() {
+        let config = ProtocolConfig::get_for_version(ProtocolVersion::MAX, Default::default());
+
+        let tx_data = transaction(config.max_tx_gas());
+        check_transaction_limits(&tx_data, 1000, &config, true).unwrap();
+
+        let err = check_transaction_limits(
+            &tx_data,
+            config.max_tx_size_bytes() as usize + 1,
+            &config,
+            true,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            SuiRpcInputError::UserInputError(UserInputError::SizeLimitExceeded { .. })
+        ));
+
+        let tx_data = transaction(config.max_tx_gas() + 1);
+        let err = check_transaction_limits(&tx_data, 1000, &config, true).unwrap_err();
+        assert!(matches!(
+            err,
+            SuiRpcInputError::UserInputError(UserInputError::GasBudgetTooHigh { .. })
+        ));
+    }
+}
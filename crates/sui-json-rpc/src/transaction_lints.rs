@@ -0,0 +1,165 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Finding likely mistakes in programmable transactions from their commands alone, without
+//! loading the objects or packages they use, so that wallets can warn about them before a
+//! transaction is signed. Lints are heuristics: a transaction can succeed despite its lints, and
+//! fail without any.
+
+use std::collections::BTreeSet;
+
+use sui_json_rpc_types::{SuiTransactionBlockLint, SuiTransactionBlockLintKind};
+use sui_types::is_system_package;
+use sui_types::transaction::{
+    Argument, CallArg, Command, ProgrammableTransaction, TransactionData, TransactionDataAPI,
+    TransactionKind,
+};
+
+/// The lints of `tx_data`. Only programmable transactions are linted.
+pub fn lint_transaction(tx_data: &TransactionData) -> Vec<SuiTransactionBlockLint> {
+    let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() else {
+        return vec![];
+    };
+
+    let sender = tx_data.sender();
+    let used = used_results(pt);
+    let mut lints = vec![];
+    for (i, command) in pt.commands.iter().enumerate() {
+        let i = i as u16;
+        let mut lint = |kind, message| {
+            lints.push(SuiTransactionBlockLint {
+                kind,
+                command: i,
+                message,
+            })
+        };
+
+        // Only the results of commands that always return values that can't be dropped are
+        // checked, as the results of Move calls may be droppable.
+        let creates = match command {
+            Command::SplitCoins(..) => Some("coins split"),
+            Command::MakeMoveVec(..) => Some("vector made"),
+            Command::Publish(..) => Some("upgrade capability returned"),
+            Command::Upgrade(..) => Some("upgrade receipt returned"),
+            Command::MoveCall(_) | Command::TransferObjects(..) | Command::MergeCoins(..) => None,
+        };
+        if let Some(creates) = creates {
+            if !used.contains(&i) {
+                lint(
+                    SuiTransactionBlockLintKind::UnusedResult,
+                    format!("The {creates} by command {i} is never used"),
+                );
+            }
+        }
+
+        match command {
+            Command::TransferObjects(_, Argument::Input(input)) => {
+                let recipient = pt.inputs.get(*input as usize);
+                if matches!(recipient, Some(CallArg::Pure(bytes)) if bytes.as_slice() == sender.as_ref())
+                {
+                    lint(
+                        SuiTransactionBlockLintKind::TransferToSelf,
+                        format!("Command {i} transfers objects to the sender, {sender}"),
+                    );
+                }
+            }
+            Command::MoveCall(call) => {
+                if !is_system_package(call.package) && call.arguments.contains(&Argument::GasCoin) {
+                    lint(
+                        SuiTransactionBlockLintKind::GasCoinByValue,
+                        format!(
+                            "Command {i} passes the gas coin to {}::{}::{}, which fails the \
+                             transaction if the function takes the coin by value",
+                            call.package, call.module, call.function
+                        ),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    lints
+}
+
+/// The commands whose results are used by a later command.
+fn used_results(pt: &ProgrammableTransaction) -> BTreeSet<u16> {
+    let mut used = BTreeSet::new();
+    for command in &pt.commands {
+        let arguments: Vec<&Argument> = match command {
+            Command::MoveCall(call) => call.arguments.iter().collect(),
+            Command::TransferObjects(objects, address) => {
+                objects.iter().chain(std::iter::once(address)).collect()
+            }
+            Command::SplitCoins(coin, amounts) => {
+                std::iter::once(coin).chain(amounts.iter()).collect()
+            }
+            Command::MergeCoins(coin, coins) => std::iter::once(coin).chain(coins.iter()).collect(),
+            Command::MakeMoveVec(_, elements) => elements.iter().collect(),
+            Command::Publish(..) => vec![],
+            Command::Upgrade(_, _, _, ticket) => vec![ticket],
+        };
+        for argument in arguments {
+            if let Argument::Result(i) | Argument::NestedResult(i, _) = argument {
+                used.insert(*i);
+            }
+        }
+    }
+    used
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::identifier::Identifier;
+    use sui_types::base_types::{random_object_ref, ObjectID, SuiAddress};
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+    fn kinds(
+        sender: SuiAddress,
+        builder: ProgrammableTransactionBuilder,
+    ) -> Vec<(SuiTransactionBlockLintKind, u16)> {
+        let tx_data = TransactionData::new_programmable(
+            sender,
+            vec![random_object_ref()],
+            builder.finish(),
+            1_000_000,
+            1000,
+        );
+        lint_transaction(&tx_data)
+            .into_iter()
+            .map(|lint| (lint.kind, lint.command))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_transaction() {
+        let sender = SuiAddress::random_for_testing_only();
+
+        // Paying someone else is fine.
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .pay_sui(vec![SuiAddress::random_for_testing_only()], vec![1])
+            .unwrap();
+        assert_eq!(kinds(sender, builder), vec![]);
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let amount = builder.pure(1u64).unwrap();
+        builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]));
+        builder.transfer_sui(sender, Some(1));
+        builder.programmable_move_call(
+            ObjectID::random(),
+            Identifier::new("game").unwrap(),
+            Identifier::new("play").unwrap(),
+            vec![],
+            vec![Argument::GasCoin],
+        );
+        assert_eq!(
+            kinds(sender, builder),
+            vec![
+                (SuiTransactionBlockLintKind::UnusedResult, 0),
+                (SuiTransactionBlockLintKind::TransferToSelf, 2),
+                (SuiTransactionBlockLintKind::GasCoinByValue, 3),
+            ]
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serving JSON-RPC over a Unix domain socket, alongside TCP, so that services on the same host
+//! (e.g. gas stations, or an indexer next to its fullnode) can reach the server without the
+//! overhead of TCP, and without it being exposed on the network. Who can connect is controlled by
+//! the permissions of the socket file: clients need write permission on it.
+
+use std::fs::{self, Permissions};
+use std::future::Future;
+use std::io;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use hyper::server::accept::Accept;
+use tokio::net::{UnixListener, UnixStream};
+
+/// Permissions of the socket file if none are configured: read and write for its owner and
+/// group.
+pub const DEFAULT_UNIX_SOCKET_MODE: u32 = 0o660;
+
+#[derive(Clone, Debug)]
+pub struct UnixSocketConfig {
+    /// Path of the socket file.
+    pub path: PathBuf,
+    /// Permissions of the socket file, e.g. `0o660`.
+    pub mode: u32,
+}
+
+/// Bind a listener to a socket file at `path`, with permissions `mode`. A socket left at `path`
+/// by a previous run is replaced, but any other file there is an error.
+///
+/// The socket is bound at a temporary path and renamed into place once its permissions are set,
+/// so that there is no window in which clients can connect to it with the default permissions.
+pub fn bind(path: &Path, mode: u32) -> io::Result<UnixListener> {
+    remove_stale_socket(path)?;
+    let mut staging = path.as_os_str().to_owned();
+    staging.push(".tmp");
+    let staging = PathBuf::from(staging);
+    remove_stale_socket(&staging)?;
+
+    let listener = UnixListener::bind(&staging)?;
+    fs::set_permissions(&staging, Permissions::from_mode(mode))?;
+    fs::rename(&staging, path)?;
+    Ok(listener)
+}
+
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(path),
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a socket", path.display()),
+        )),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Serve `router` on the connections accepted by `listener`, until `shutdown` completes, after
+/// which the requests in flight are drained.
+pub async fn serve(
+    listener: UnixListener,
+    router: axum::Router,
+    shutdown: impl Future<Output = ()>,
+) -> hyper::Result<()> {
+    axum::Server::builder(UnixAccept(listener))
+        .serve(router.into_make_service())
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
+struct UnixAccept(UnixListener);
+
+impl Accept for UnixAccept {
+    type Conn = UnixStream;
+    type Error = io::Error;
+
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        let (stream, _) = ready!(self.0.poll_accept(cx))?;
+        Poll::Ready(Some(Ok(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_serve_on_unix_socket() {
+        let dir = std::env::temp_dir().join(format!("sui-json-rpc-uds-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rpc.sock");
+
+        // A socket left behind by a previous run is replaced.
+        drop(bind(&path, DEFAULT_UNIX_SOCKET_MODE).unwrap());
+        let listener = bind(&path, 0o600).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let router = axum::Router::new().route("/", axum::routing::get(|| async { "ok" }));
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, router, async {
+            shutdown_rx.await.ok();
+        }));
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("ok"));
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+
+        // Anything other than a socket is left alone.
+        fs::remove_file(&path).unwrap();
+        fs::write(&path, "not a socket").unwrap();
+        assert!(bind(&path, DEFAULT_UNIX_SOCKET_MODE).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::HeaderMap;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use versions::Versioning;
+
+use sui_json_rpc_api::{SERVER_API_VERSION_HEADER, SERVER_MIN_SUPPORTED_API_VERSION_HEADER};
+
+const UNKNOWN_LABEL: &str = "Unknown";
+const SPAM_LABEL: &str = "SPAM";
+
+/// Deprecation schedule of a single method, advertised to clients through the `Deprecation` and
+/// `Sunset` response headers (RFC 9745 and RFC 8594 respectively).
+#[derive(Clone, Debug, Default)]
+pub struct MethodDeprecation {
+    /// When the method was deprecated, as an HTTP date. If unset, the method is advertised as
+    /// deprecated without a date.
+    pub deprecated_at: Option<String>,
+    /// When the method is expected to stop being served, as an HTTP date.
+    pub sunset_at: Option<String>,
+    /// Link to documentation describing the deprecation and how to migrate off the method.
+    pub link: Option<String>,
+}
+
+/// The range of API versions this server supports, and the methods it plans to remove.
+#[derive(Clone, Debug)]
+pub(crate) struct ApiVersioning {
+    current: String,
+    min_supported: Option<String>,
+    deprecations: HashMap<String, MethodDeprecation>,
+    metrics: VersioningMetrics,
+}
+
+#[derive(Clone, Debug)]
+struct VersioningMetrics {
+    /// Requests by method and the API version clients targeted, to inform when deprecated
+    /// methods and old versions can be removed.
+    requests_by_api_version: IntCounterVec,
+    /// Requests rejected because they targeted a version older than the minimum supported.
+    unsupported_api_version_requests: IntCounterVec,
+}
+
+impl ApiVersioning {
+    pub(crate) fn new(
+        current: &str,
+        min_supported: Option<String>,
+        deprecations: HashMap<String, MethodDeprecation>,
+        registry: &Registry,
+    ) -> Self {
+        let metrics = VersioningMetrics {
+            requests_by_api_version: register_int_counter_vec_with_registry!(
+                "rpc_requests_by_api_version",
+                "Number of requests by route and the API version targeted by the client",
+                &["route", "api_version"],
+                registry,
+            )
+            .unwrap(),
+            unsupported_api_version_requests: register_int_counter_vec_with_registry!(
+                "rpc_unsupported_api_version_requests",
+                "Number of requests rejected for targeting an unsupported API version",
+                &["api_version"],
+                registry,
+            )
+            .unwrap(),
+        };
+
+        Self {
+            current: current.to_string(),
+            min_supported,
+            deprecations,
+            metrics,
+        }
+    }
+
+    /// Whether the API version requested by the client (if any) is still supported. Clients
+    /// targeting a version newer than the server's are served, as they are expected to cope with
+    /// an older server during upgrades.
+    pub(crate) fn is_supported(&self, requested: Option<&str>) -> bool {
+        let (Some(requested), Some(min)) = (requested, &self.min_supported) else {
+            return true;
+        };
+
+        match (Versioning::new(requested), Versioning::new(min)) {
+            (Some(version), Some(min)) if version < min => {
+                self.metrics
+                    .unsupported_api_version_requests
+                    .with_label_values(&[requested])
+                    .inc();
+                false
+            }
+            _ => true,
+        }
+    }
+
+    pub(crate) fn unsupported_message(&self, requested: &str) -> String {
+        format!(
+            "API version {requested} is no longer supported, minimum supported version is {}, \
+             current version is {}",
+            self.min_supported.as_deref().unwrap_or(UNKNOWN_LABEL),
+            self.current,
+        )
+    }
+
+    /// Count a call to `method` by the version the client targeted. Calls to methods the server
+    /// does not know are counted together, to bound the metric's cardinality.
+    pub(crate) fn record_request(&self, method: &str, known: bool, requested: Option<&str>) {
+        let route = if known { method } else { SPAM_LABEL };
+        self.metrics
+            .requests_by_api_version
+            .with_label_values(&[route, requested.unwrap_or(UNKNOWN_LABEL)])
+            .inc();
+    }
+
+    /// Add headers describing the supported version range, and the deprecation schedule of
+    /// `method`, if it has one, to a response.
+    pub(crate) fn add_headers(&self, method: Option<&str>, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.current) {
+            headers.insert(HeaderName::from_static(SERVER_API_VERSION_HEADER), value);
+        }
+
+        if let Some(value) = self
+            .min_supported
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(
+                HeaderName::from_static(SERVER_MIN_SUPPORTED_API_VERSION_HEADER),
+                value,
+            );
+        }
+
+        let Some(deprecation) = method.and_then(|m| self.deprecations.get(m)) else {
+            return;
+        };
+
+        let deprecated_at = deprecation.deprecated_at.as_deref().unwrap_or("true");
+        if let Ok(value) = HeaderValue::from_str(deprecated_at) {
+            headers.insert(HeaderName::from_static("deprecation"), value);
+        }
+
+        if let Some(value) = deprecation
+            .sunset_at
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(HeaderName::from_static("sunset"), value);
+        }
+
+        if let Some(value) = deprecation
+            .link
+            .as_deref()
+            .and_then(|l| HeaderValue::from_str(&format!("<{l}>; rel=\"deprecation\"")).ok())
+        {
+            headers.insert(hyper::header::LINK, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versioning(min_supported: Option<&str>) -> ApiVersioning {
+        let deprecations = HashMap::from([(
+            "sui_oldMethod".to_string(),
+            MethodDeprecation {
+                deprecated_at: None,
+                sunset_at: Some("Wed, 01 Jan 2025 00:00:00 GMT".to_string()),
+                link: Some("https://docs.sui.io".to_string()),
+            },
+        )]);
+
+        ApiVersioning::new(
+            "1.20.0",
+            min_supported.map(str::to_string),
+            deprecations,
+            &Registry::new(),
+        )
+    }
+
+    #[test]
+    fn test_version_check() {
+        let v = versioning(Some("1.10.0"));
+        assert!(v.is_supported(None));
+        assert!(v.is_supported(Some("1.10.0")));
+        assert!(v.is_supported(Some("1.21.0")));
+        assert!(!v.is_supported(Some("1.9.5")));
+
+        let v = versioning(None);
+        assert!(v.is_supported(Some("0.1.0")));
+    }
+
+    #[test]
+    fn test_deprecation_headers() {
+        let v = versioning(Some("1.10.0"));
+
+        let mut headers = HeaderMap::new();
+        v.add_headers(Some("sui_getObject"), &mut headers);
+        assert_eq!(headers[SERVER_API_VERSION_HEADER], "1.20.0");
+        assert_eq!(headers[SERVER_MIN_SUPPORTED_API_VERSION_HEADER], "1.10.0");
+        assert!(!headers.contains_key("deprecation"));
+
+        let mut headers = HeaderMap::new();
+        v.add_headers(Some("sui_oldMethod"), &mut headers);
+        assert_eq!(headers["deprecation"], "true");
+        assert_eq!(headers["sunset"], "Wed, 01 Jan 2025 00:00:00 GMT");
+        assert_eq!(
+            headers[hyper::header::LINK],
+            "<https://docs.sui.io>; rel=\"deprecation\""
+        );
+    }
+}
@@ -100,6 +100,26 @@ pub fn handle_fail_point_if(identifier: &'static str) -> bool {
     }
 }
 
+type FpArgCallback<T, R> = Arc<dyn Fn(&T) -> R + Send + Sync>;
+
+fn get_fp_arg_result<T: 'static, R: 'static>(
+    result: Box<dyn std::any::Any + Send + 'static>,
+) -> FpArgCallback<T, R> {
+    match result.downcast::<FpArgCallback<T, R>>() {
+        Ok(callback) => *callback,
+        Err(_) => panic!("failpoint-arg registered with a different argument or result type"),
+    }
+}
+
+pub fn handle_fail_point_arg<T: 'static, R: 'static>(
+    identifier: &'static str,
+    arg: &T,
+) -> Option<R> {
+    let callback = get_callback(identifier)?;
+    tracing::trace!("hit failpoint_arg {}", identifier);
+    Some(get_fp_arg_result::<T, R>(callback())(arg))
+}
+
 fn register_fail_point_impl(identifier: &'static str, callback: Arc<FpCallback>) {
     with_fp_map(move |map| {
         assert!(
@@ -171,6 +191,30 @@ pub fn register_fail_point_if(
     register_fail_point_impl(identifier, Arc::new(move || Box::new(callback())));
 }
 
+/// Register a fail point that is passed an argument by the code that hits it, e.g. to tell which
+/// node or peer it concerns, and returns a result to it. Example:
+///
+/// In the test:
+///
+/// ```ignore
+///     register_fail_point_arg("foo", move |peer: &PeerId| *peer == faulty);
+/// ```
+///
+/// In the code:
+///
+/// ```ignore
+///     if fail_point_arg!("foo", &peer) == Some(true) {
+///         return Err(...);
+///     }
+/// ```
+pub fn register_fail_point_arg<T: 'static, R: 'static>(
+    identifier: &'static str,
+    callback: impl Fn(&T) -> R + Sync + Send + 'static,
+) {
+    let callback: FpArgCallback<T, R> = Arc::new(callback);
+    register_fail_point_impl(identifier, Arc::new(move || Box::new(callback.clone())));
+}
+
 pub fn register_fail_points(
     identifiers: &[&'static str],
     callback: impl Fn() + Sync + Send + 'static,
@@ -219,6 +263,16 @@ macro_rules! fail_point_if {
     };
 }
 
+/// Pass an argument to a fail point, evaluating to the result of its callback, or `None` if the
+/// fail point is not registered.
+#[cfg(any(msim, fail_points))]
+#[macro_export]
+macro_rules! fail_point_arg {
+    ($tag: expr, $arg: expr) => {
+        $crate::handle_fail_point_arg($tag, $arg)
+    };
+}
+
 #[cfg(not(any(msim, fail_points)))]
 #[macro_export]
 macro_rules! fail_point {
@@ -237,6 +291,15 @@ macro_rules! fail_point_if {
     ($tag: expr, $callback: expr) => {};
 }
 
+#[cfg(not(any(msim, fail_points)))]
+#[macro_export]
+macro_rules! fail_point_arg {
+    ($tag: expr, $arg: expr) => {{
+        let _ = $arg;
+        None
+    }};
+}
+
 /// Use to write INFO level logs only when REPLAY_LOG
 /// environment variable is set. Useful for log lines that
 /// are only relevant to test infra which still may need to
@@ -257,6 +320,24 @@ macro_rules! replay_log {
 mod test {
     use super::*;
 
+    #[test]
+    fn test_fail_point_arg() {
+        assert_eq!(handle_fail_point_arg::<u64, bool>("test-arg", &1), None);
+
+        register_fail_point_arg("test-arg", |n: &u64| *n > 1);
+        assert_eq!(
+            handle_fail_point_arg::<u64, bool>("test-arg", &1),
+            Some(false)
+        );
+        assert_eq!(
+            handle_fail_point_arg::<u64, bool>("test-arg", &2),
+            Some(true)
+        );
+
+        clear_fail_point("test-arg");
+        assert_eq!(handle_fail_point_arg::<u64, bool>("test-arg", &2), None);
+    }
+
     // Uncomment to test error messages
     // #[with_checked_arithmetic]
     // struct TestStruct;
@@ -18,6 +18,8 @@ pub mod manage_package;
 pub mod new;
 #[cfg(feature = "unit_test")]
 pub mod unit_test;
+#[cfg(feature = "build")]
+pub mod validate_display;
 
 #[derive(Parser)]
 pub enum Command {
@@ -31,6 +33,8 @@ pub enum Command {
     New(new::New),
     #[cfg(feature = "unit_test")]
     Test(unit_test::Test),
+    #[cfg(feature = "build")]
+    ValidateDisplay(validate_display::ValidateDisplay),
 }
 #[derive(Parser)]
 pub struct Calib {
@@ -69,5 +73,7 @@ pub fn execute_move_command(
 
             Ok(())
         }
+        #[cfg(feature = "build")]
+        Command::ValidateDisplay(c) => c.execute(package_path, build_config),
     }
 }
@@ -5,10 +5,10 @@ use clap::Parser;
 use move_cli::base::new;
 use std::path::PathBuf;
 
-const SUI_PKG_NAME: &str = "Sui";
+pub const SUI_PKG_NAME: &str = "Sui";
 
 // Use testnet by default. Probably want to add options to make this configurable later
-const SUI_PKG_PATH: &str = "{ git = \"https://github.com/MystenLabs/sui.git\", subdir = \"crates/sui-framework/packages/sui-framework\", rev = \"framework/testnet\" }";
+pub const SUI_PKG_PATH: &str = "{ git = \"https://github.com/MystenLabs/sui.git\", subdir = \"crates/sui-framework/packages/sui-framework\", rev = \"framework/testnet\" }";
 
 #[derive(Parser)]
 #[group(id = "sui-move-new")]
@@ -0,0 +1,185 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use clap::Parser;
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::file_format::{
+    Bytecode, ConstantPoolIndex, SignatureToken, StructHandleIndex,
+};
+use move_binary_format::CompiledModule;
+use move_cli::base;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::runtime_value::MoveValue;
+use move_package::BuildConfig as MoveBuildConfig;
+use sui_move_build::BuildConfig;
+use sui_types::display::{validate_display_template, DISPLAY_MODULE_NAME};
+use sui_types::object::MoveObject;
+use sui_types::SUI_FRAMEWORK_ADDRESS;
+
+use crate::build::resolve_lock_file_path;
+
+/// Functions of `sui::display` that take templates for the Display of their type argument.
+const DISPLAY_TEMPLATE_FUNCTIONS: &[&str] = &["new_with_fields", "add", "add_multiple", "edit"];
+
+/// Check the Display templates set by the package's modules against the fields of the types they
+/// display, so that typos are caught before publishing, rather than showing up as blank fields
+/// in wallets.
+///
+/// Templates are found by looking for the byte string constants containing `{` that a function
+/// loads before calling one of the functions of `sui::display` that set templates, and are
+/// checked against that call's type.
+#[derive(Parser)]
+#[group(id = "sui-move-validate-display")]
+pub struct ValidateDisplay;
+
+impl ValidateDisplay {
+    pub fn execute(
+        &self,
+        path: Option<PathBuf>,
+        build_config: MoveBuildConfig,
+    ) -> anyhow::Result<()> {
+        let rerooted_path = base::reroot_path(path.clone())?;
+        let build_config = resolve_lock_file_path(build_config, path)?;
+        let pkg = BuildConfig {
+            config: build_config,
+            run_bytecode_verifier: true,
+            print_diags_to_stderr: true,
+        }
+        .build(rerooted_path)?;
+
+        let mut templates: BTreeMap<StructTag, Vec<String>> = BTreeMap::new();
+        for module in pkg.get_modules() {
+            for (type_, template) in display_templates(module) {
+                templates.entry(type_).or_default().push(template);
+            }
+        }
+
+        let mut checked = 0;
+        let mut errors = 0;
+        for (type_, templates) in templates {
+            let name = type_.to_canonical_string(/* with_prefix */ true);
+            let layout = MoveObject::get_layout_from_struct_tag(type_, &pkg)?;
+            for template in templates {
+                checked += 1;
+                for error in validate_display_template(&template, &layout) {
+                    errors += 1;
+                    eprintln!("{name}: \"{template}\": {error}");
+                }
+            }
+        }
+
+        if errors > 0 {
+            anyhow::bail!("Found {errors} problems in {checked} Display templates");
+        }
+        println!("Checked {checked} Display templates");
+        Ok(())
+    }
+}
+
+/// The templates that functions in `module` set for the Display of each type. Templates for types
+/// that depend on the calling function's type parameters are skipped.
+pub fn display_templates(module: &CompiledModule) -> Vec<(StructTag, String)> {
+    let mut templates = vec![];
+    for def in module.function_defs() {
+        let Some(code) = &def.code else {
+            continue;
+        };
+
+        // Constants loaded since the last call to a Display function, in order.
+        let mut pending = vec![];
+        for instruction in &code.code {
+            match instruction {
+                Bytecode::LdConst(idx) => {
+                    if let Some(template) = template_constant(module, *idx) {
+                        pending.push(template);
+                    }
+                }
+                Bytecode::CallGeneric(idx) => {
+                    let instantiation = module.function_instantiation_at(*idx);
+                    let handle = module.function_handle_at(instantiation.handle);
+                    let callee =
+                        module.module_id_for_handle(module.module_handle_at(handle.module));
+                    if callee.address() != &SUI_FRAMEWORK_ADDRESS
+                        || callee.name() != DISPLAY_MODULE_NAME
+                        || !DISPLAY_TEMPLATE_FUNCTIONS
+                            .contains(&module.identifier_at(handle.name).as_str())
+                    {
+                        continue;
+                    }
+
+                    let type_ = module
+                        .signature_at(instantiation.type_parameters)
+                        .0
+                        .first()
+                        .and_then(|token| type_tag(module, token));
+                    let pending = std::mem::take(&mut pending);
+                    if let Some(TypeTag::Struct(type_)) = type_ {
+                        templates.extend(pending.into_iter().map(|t| (*type_.clone(), t)));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    templates
+}
+
+/// The constant at `idx`, if it is a UTF-8 byte string that looks like a template.
+fn template_constant(module: &CompiledModule, idx: ConstantPoolIndex) -> Option<String> {
+    let constant = module.constant_at(idx);
+    if constant.type_ != SignatureToken::Vector(Box::new(SignatureToken::U8)) {
+        return None;
+    }
+    let MoveValue::Vector(bytes) = constant.deserialize_constant()? else {
+        return None;
+    };
+    let bytes = MoveValue::vec_to_vec_u8(bytes).ok()?;
+    String::from_utf8(bytes)
+        .ok()
+        .filter(|template| template.contains('{'))
+}
+
+/// The type that `token` stands for, if it doesn't depend on type parameters.
+fn type_tag(module: &CompiledModule, token: &SignatureToken) -> Option<TypeTag> {
+    Some(match token {
+        SignatureToken::Bool => TypeTag::Bool,
+        SignatureToken::U8 => TypeTag::U8,
+        SignatureToken::U16 => TypeTag::U16,
+        SignatureToken::U32 => TypeTag::U32,
+        SignatureToken::U64 => TypeTag::U64,
+        SignatureToken::U128 => TypeTag::U128,
+        SignatureToken::U256 => TypeTag::U256,
+        SignatureToken::Address => TypeTag::Address,
+        SignatureToken::Vector(inner) => TypeTag::Vector(Box::new(type_tag(module, inner)?)),
+        SignatureToken::Struct(idx) => struct_tag(module, *idx, vec![]),
+        SignatureToken::StructInstantiation(idx, type_args) => {
+            let type_params = type_args
+                .iter()
+                .map(|t| type_tag(module, t))
+                .collect::<Option<_>>()?;
+            struct_tag(module, *idx, type_params)
+        }
+        SignatureToken::Signer
+        | SignatureToken::Reference(_)
+        | SignatureToken::MutableReference(_)
+        | SignatureToken::TypeParameter(_) => return None,
+    })
+}
+
+fn struct_tag(
+    module: &CompiledModule,
+    idx: StructHandleIndex,
+    type_params: Vec<TypeTag>,
+) -> TypeTag {
+    let handle = module.struct_handle_at(idx);
+    let module_id = module.module_id_for_handle(module.module_handle_at(handle.module));
+    TypeTag::Struct(Box::new(StructTag {
+        address: *module_id.address(),
+        module: module_id.name().to_owned(),
+        name: module.identifier_at(handle.name).to_owned(),
+        type_params,
+    }))
+}
@@ -5,6 +5,7 @@ use super::{
     metrics::Metrics, server::Server, Discovery, DiscoveryEventLoop, DiscoveryServer, State,
 };
 use crate::discovery::TrustedPeerChangeEvent;
+use crate::peer_controls::PeerControls;
 use anemo::codegen::InboundRequestLayer;
 use anemo_tower::rate_limit;
 use std::{
@@ -23,6 +24,7 @@ pub struct Builder {
     config: Option<P2pConfig>,
     metrics: Option<Metrics>,
     trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
+    peer_controls: Option<PeerControls>,
 }
 
 impl Builder {
@@ -32,6 +34,7 @@ impl Builder {
             config: None,
             metrics: None,
             trusted_peer_change_rx,
+            peer_controls: None,
         }
     }
 
@@ -45,6 +48,11 @@ impl Builder {
         self
     }
 
+    pub fn peer_controls(mut self, peer_controls: PeerControls) -> Self {
+        self.peer_controls = Some(peer_controls);
+        self
+    }
+
     pub fn build(self) -> (UnstartedDiscovery, DiscoveryServer<impl Discovery>) {
         let discovery_config = self
             .config
@@ -71,6 +79,7 @@ impl Builder {
             config,
             metrics,
             trusted_peer_change_rx,
+            peer_controls,
         } = self;
         let config = config.unwrap();
         let metrics = metrics.unwrap_or_else(Metrics::disabled);
@@ -100,6 +109,7 @@ impl Builder {
                 state,
                 trusted_peer_change_rx,
                 metrics,
+                peer_controls: peer_controls.unwrap_or_default(),
             },
             server,
         )
@@ -114,6 +124,7 @@ pub struct UnstartedDiscovery {
     pub(super) state: Arc<RwLock<State>>,
     pub(super) trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
     pub(super) metrics: Metrics,
+    pub(super) peer_controls: PeerControls,
 }
 
 impl UnstartedDiscovery {
@@ -125,6 +136,7 @@ impl UnstartedDiscovery {
             state,
             trusted_peer_change_rx,
             metrics,
+            peer_controls,
         } = self;
 
         let discovery_config = config.discovery.clone().unwrap_or_default();
@@ -153,6 +165,7 @@ impl UnstartedDiscovery {
                 state,
                 trusted_peer_change_rx,
                 metrics,
+                peer_controls,
             },
             handle,
         )
@@ -41,6 +41,7 @@ pub use generated::{
 pub use server::GetKnownPeersResponse;
 
 use self::metrics::Metrics;
+use crate::peer_controls::PeerControls;
 
 /// The internal discovery state shared between the main event loop and the request handler
 struct State {
@@ -84,6 +85,7 @@ struct DiscoveryEventLoop {
     state: Arc<RwLock<State>>,
     trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
     metrics: Metrics,
+    peer_controls: PeerControls,
 }
 
 impl DiscoveryEventLoop {
@@ -212,6 +214,12 @@ impl DiscoveryEventLoop {
     fn handle_peer_event(&mut self, peer_event: Result<PeerEvent, RecvError>) {
         match peer_event {
             Ok(PeerEvent::NewPeer(peer_id)) => {
+                // Banned peers may still dial us, but we don't keep the connection.
+                if self.peer_controls.is_banned(&peer_id) {
+                    debug!("Disconnecting from banned peer {peer_id}");
+                    let _ = self.network.disconnect(peer_id);
+                    return;
+                }
                 if let Some(peer) = self.network.peer(peer_id) {
                     self.state
                         .write()
@@ -280,6 +288,7 @@ impl DiscoveryEventLoop {
                 !info.addresses.is_empty() // Peer has addresses we can dial
                 && !state.connected_peers.contains_key(peer_id) // We're not already connected
                 && !self.pending_dials.contains_key(peer_id) // There is no pending dial to this node
+                && !self.peer_controls.is_banned(peer_id) // The peer hasn't been banned
             })
             .collect::<Vec<_>>();
 
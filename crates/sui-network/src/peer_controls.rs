@@ -0,0 +1,166 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runtime controls over which p2p peers this node talks to. Banned peers are not dialed by
+//! discovery, are not synced from by state sync, and have their inbound requests rejected (see
+//! [`RejectBannedPeers`]). Preferred peers are tried first when state sync picks a peer to
+//! download checkpoints from.
+//!
+//! Controls are kept in memory only, and are lost when the node restarts.
+
+use anemo::rpc::Status;
+use anemo::types::response::{IntoResponse, StatusCode};
+use anemo::{PeerId, Request, Response};
+use anemo_tower::auth::AuthorizeRequest;
+use bytes::Bytes;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+/// Shared set of banned and preferred peers. Cloning it yields a handle to the same set.
+#[derive(Clone, Debug, Default)]
+pub struct PeerControls {
+    inner: Arc<RwLock<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    banned: HashSet<PeerId>,
+    preferred: HashSet<PeerId>,
+}
+
+impl PeerControls {
+    /// Ban `peer_id`. A banned peer is no longer preferred. Returns false if it was already
+    /// banned.
+    pub fn ban(&self, peer_id: PeerId) -> bool {
+        let mut inner = self.inner.write().unwrap();
+        inner.preferred.remove(&peer_id);
+        inner.banned.insert(peer_id)
+    }
+
+    /// Returns false if `peer_id` was not banned.
+    pub fn unban(&self, peer_id: &PeerId) -> bool {
+        self.inner.write().unwrap().banned.remove(peer_id)
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.inner.read().unwrap().banned.contains(peer_id)
+    }
+
+    pub fn banned(&self) -> Vec<PeerId> {
+        self.inner.read().unwrap().banned.iter().copied().collect()
+    }
+
+    /// Prefer `peer_id` for state sync. Banned peers can't be preferred, so this returns false if
+    /// `peer_id` is banned or already preferred.
+    pub fn prefer(&self, peer_id: PeerId) -> bool {
+        let mut inner = self.inner.write().unwrap();
+        !inner.banned.contains(&peer_id) && inner.preferred.insert(peer_id)
+    }
+
+    /// Returns false if `peer_id` was not preferred.
+    pub fn unprefer(&self, peer_id: &PeerId) -> bool {
+        self.inner.write().unwrap().preferred.remove(peer_id)
+    }
+
+    pub fn is_preferred(&self, peer_id: &PeerId) -> bool {
+        self.inner.read().unwrap().preferred.contains(peer_id)
+    }
+
+    pub fn preferred(&self) -> Vec<PeerId> {
+        self.inner
+            .read()
+            .unwrap()
+            .preferred
+            .iter()
+            .copied()
+            .collect()
+    }
+}
+
+/// Rejects inbound requests from banned peers.
+#[derive(Clone, Debug)]
+pub struct RejectBannedPeers {
+    controls: PeerControls,
+}
+
+impl RejectBannedPeers {
+    pub fn new(controls: PeerControls) -> Self {
+        Self { controls }
+    }
+}
+
+impl AuthorizeRequest for RejectBannedPeers {
+    fn authorize(&self, request: &mut Request<Bytes>) -> Result<(), Response<Bytes>> {
+        match request.peer_id() {
+            Some(peer_id) if self.controls.is_banned(peer_id) => Err(Status::new_with_message(
+                StatusCode::BadRequest,
+                "peer is banned",
+            )
+            .into_response()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anemo_tower::auth::RequireAuthorizationLayer;
+    use tower::{BoxError, Service, ServiceBuilder, ServiceExt};
+
+    #[test]
+    fn ban_and_prefer() {
+        let controls = PeerControls::default();
+        let peer = PeerId([1; 32]);
+
+        assert!(controls.prefer(peer));
+        assert!(!controls.prefer(peer));
+        assert!(controls.is_preferred(&peer));
+
+        // Banning a peer drops its preference, and it can't be preferred until unbanned.
+        assert!(controls.ban(peer));
+        assert!(!controls.ban(peer));
+        assert!(!controls.is_preferred(&peer));
+        assert!(!controls.prefer(peer));
+
+        assert!(controls.unban(&peer));
+        assert!(!controls.is_banned(&peer));
+        assert!(controls.prefer(peer));
+        assert!(controls.unprefer(&peer));
+        assert!(controls.preferred().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reject_banned_peers() {
+        let controls = PeerControls::default();
+        let banned = PeerId([1; 32]);
+        let other = PeerId([2; 32]);
+        controls.ban(banned);
+
+        let mut svc = ServiceBuilder::new()
+            .layer(RequireAuthorizationLayer::new(RejectBannedPeers::new(
+                controls.clone(),
+            )))
+            .service_fn(echo);
+
+        let request = |peer_id| {
+            let mut request = Request::new(Bytes::from("foobar"));
+            request.extensions_mut().insert(peer_id);
+            request
+        };
+
+        let response = svc.ready().await.unwrap().call(request(banned)).await;
+        assert_eq!(response.unwrap().status(), StatusCode::BadRequest);
+
+        let response = svc.ready().await.unwrap().call(request(other)).await;
+        assert_eq!(response.unwrap().status(), StatusCode::Success);
+
+        controls.unban(&banned);
+        let response = svc.ready().await.unwrap().call(request(banned)).await;
+        assert_eq!(response.unwrap().status(), StatusCode::Success);
+    }
+
+    async fn echo(req: Request<Bytes>) -> Result<Response<Bytes>, BoxError> {
+        Ok(Response::new(req.into_body()))
+    }
+}
@@ -22,6 +22,7 @@ use super::{
     server::{CheckpointContentsDownloadLimitLayer, Server},
     Handle, PeerHeights, StateSync, StateSyncEventLoop, StateSyncMessage, StateSyncServer,
 };
+use crate::peer_controls::PeerControls;
 use sui_types::storage::WriteStore;
 
 pub struct Builder<S> {
@@ -29,6 +30,7 @@ pub struct Builder<S> {
     config: Option<StateSyncConfig>,
     metrics: Option<Metrics>,
     archive_readers: Option<ArchiveReaderBalancer>,
+    peer_controls: Option<PeerControls>,
 }
 
 impl Builder<()> {
@@ -39,6 +41,7 @@ impl Builder<()> {
             config: None,
             metrics: None,
             archive_readers: None,
+            peer_controls: None,
         }
     }
 }
@@ -50,6 +53,7 @@ impl<S> Builder<S> {
             config: self.config,
             metrics: self.metrics,
             archive_readers: self.archive_readers,
+            peer_controls: self.peer_controls,
         }
     }
 
@@ -67,6 +71,11 @@ impl<S> Builder<S> {
         self.archive_readers = Some(archive_readers);
         self
     }
+
+    pub fn peer_controls(mut self, peer_controls: PeerControls) -> Self {
+        self.peer_controls = Some(peer_controls);
+        self
+    }
 }
 
 impl<S> Builder<S>
@@ -127,6 +136,7 @@ where
             config,
             metrics,
             archive_readers,
+            peer_controls,
         } = self;
         let store = store.unwrap();
         let config = config.unwrap_or_default();
@@ -137,18 +147,27 @@ where
         let (checkpoint_event_sender, _receiver) =
             broadcast::channel(config.synced_checkpoint_broadcast_channel_capacity());
         let weak_sender = sender.downgrade();
-        let handle = Handle {
-            sender,
-            checkpoint_event_sender: checkpoint_event_sender.clone(),
-        };
         let peer_heights = PeerHeights {
             peers: HashMap::new(),
             unprocessed_checkpoints: HashMap::new(),
             sequence_number_to_digest: HashMap::new(),
             wait_interval_when_no_peer_to_sync_content: Duration::from_secs(10),
+            peer_controls: peer_controls.unwrap_or_default(),
+            region: config.region.clone(),
+            peer_regions: config
+                .peer_regions
+                .iter()
+                .map(|hint| (hint.peer_id, hint.region.clone()))
+                .collect(),
+            latencies: HashMap::new(),
         }
         .pipe(RwLock::new)
         .pipe(Arc::new);
+        let handle = Handle {
+            sender,
+            checkpoint_event_sender: checkpoint_event_sender.clone(),
+            peer_heights: peer_heights.clone(),
+        };
 
         let server = Server {
             store: store.clone(),
@@ -54,7 +54,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::{
     collections::{HashMap, VecDeque},
     sync::{Arc, RwLock},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use sui_config::p2p::StateSyncConfig;
 use sui_types::{
@@ -94,6 +94,7 @@ use sui_archival::reader::ArchiveReaderBalancer;
 use sui_storage::verify_checkpoint;
 
 use self::{metrics::Metrics, server::CheckpointContentsDownloadLimitLayer};
+use crate::peer_controls::PeerControls;
 
 /// A handle to the StateSync subsystem.
 ///
@@ -103,6 +104,7 @@ use self::{metrics::Metrics, server::CheckpointContentsDownloadLimitLayer};
 pub struct Handle {
     sender: mpsc::Sender<StateSyncMessage>,
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
+    peer_heights: Arc<RwLock<PeerHeights>>,
 }
 
 impl Handle {
@@ -125,8 +127,14 @@ impl Handle {
     pub fn subscribe_to_synced_checkpoints(&self) -> broadcast::Receiver<VerifiedCheckpoint> {
         self.checkpoint_event_sender.subscribe()
     }
+
+    /// What StateSync knows about the checkpoints available from each of its peers.
+    pub fn peer_sync_info(&self) -> HashMap<PeerId, PeerStateSyncInfo> {
+        self.peer_heights.read().unwrap().peers.clone()
+    }
 }
 
+#[derive(Debug)]
 struct PeerHeights {
     /// Table used to track the highest checkpoint for each of our peers.
     peers: HashMap<PeerId, PeerStateSyncInfo>,
@@ -135,19 +143,30 @@ struct PeerHeights {
 
     // The amount of time to wait before retry if there are no peers to sync content from.
     wait_interval_when_no_peer_to_sync_content: Duration,
+
+    /// Banned peers are never synced from, and preferred peers are tried first.
+    peer_controls: PeerControls,
+
+    /// Our region, and hints for the regions of our peers, used to download checkpoint contents
+    /// from nearby peers first.
+    region: Option<String>,
+    peer_regions: HashMap<PeerId, String>,
+    /// Smoothed latency of each peer, probed by timing the periodic queries for their latest
+    /// checkpoint.
+    latencies: HashMap<PeerId, Duration>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-struct PeerStateSyncInfo {
+pub struct PeerStateSyncInfo {
     /// The digest of the Peer's genesis checkpoint.
-    genesis_checkpoint_digest: CheckpointDigest,
+    pub genesis_checkpoint_digest: CheckpointDigest,
     /// Indicates if this Peer is on the same chain as us.
-    on_same_chain_as_us: bool,
+    pub on_same_chain_as_us: bool,
     /// Highest checkpoint sequence number we know of for this Peer.
-    height: CheckpointSequenceNumber,
+    pub height: CheckpointSequenceNumber,
     /// lowest available checkpoint watermark for this Peer.
     /// This defaults to 0 for now.
-    lowest: CheckpointSequenceNumber,
+    pub lowest: CheckpointSequenceNumber,
 }
 
 impl PeerHeights {
@@ -158,16 +177,16 @@ impl PeerHeights {
     }
 
     pub fn highest_known_checkpoint_sequence_number(&self) -> Option<CheckpointSequenceNumber> {
-        self.peers
-            .values()
-            .filter_map(|info| info.on_same_chain_as_us.then_some(info.height))
+        self.peers_on_same_chain()
+            .map(|(_peer_id, info)| info.height)
             .max()
     }
 
+    /// Peers on the same chain as us, that haven't been banned.
     pub fn peers_on_same_chain(&self) -> impl Iterator<Item = (&PeerId, &PeerStateSyncInfo)> {
-        self.peers
-            .iter()
-            .filter(|(_peer_id, info)| info.on_same_chain_as_us)
+        self.peers.iter().filter(|(peer_id, info)| {
+            info.on_same_chain_as_us && !self.peer_controls.is_banned(peer_id)
+        })
     }
 
     // Returns a bool that indicates if the update was done successfully.
@@ -256,6 +275,27 @@ impl PeerHeights {
         self.unprocessed_checkpoints.get(digest)
     }
 
+    pub fn is_in_our_region(&self, peer_id: &PeerId) -> bool {
+        self.region.is_some() && self.peer_regions.get(peer_id) == self.region.as_ref()
+    }
+
+    pub fn record_latency(&mut self, peer_id: PeerId, latency: Duration) {
+        // Exponentially weighted, so a single slow response doesn't demote a nearby peer.
+        const WEIGHT_OF_NEW_SAMPLE: f64 = 0.2;
+
+        self.latencies
+            .entry(peer_id)
+            .and_modify(|smoothed| {
+                *smoothed = smoothed.mul_f64(1.0 - WEIGHT_OF_NEW_SAMPLE)
+                    + latency.mul_f64(WEIGHT_OF_NEW_SAMPLE)
+            })
+            .or_insert(latency);
+    }
+
+    pub fn latency(&self, peer_id: &PeerId) -> Option<Duration> {
+        self.latencies.get(peer_id).copied()
+    }
+
     #[cfg(test)]
     pub fn set_wait_interval_when_no_peer_to_sync_content(&mut self, duration: Duration) {
         self.wait_interval_when_no_peer_to_sync_content = duration;
@@ -269,7 +309,9 @@ impl PeerHeights {
 // PeerBalancer is an Iterator that selects peers based on RTT with some added randomness.
 #[derive(Clone)]
 struct PeerBalancer {
-    peers: VecDeque<(anemo::Peer, PeerStateSyncInfo)>,
+    /// Peers in the order they should be tried, with their rank: peers are only ever picked at
+    /// random among peers of the same rank.
+    peers: VecDeque<(u8, anemo::Peer, PeerStateSyncInfo)>,
     requested_checkpoint: Option<CheckpointSequenceNumber>,
     request_type: PeerCheckpointRequestType,
 }
@@ -286,18 +328,41 @@ impl PeerBalancer {
         peer_heights: Arc<RwLock<PeerHeights>>,
         request_type: PeerCheckpointRequestType,
     ) -> Self {
+        let peer_heights = peer_heights.read().unwrap();
         let mut peers: Vec<_> = peer_heights
-            .read()
-            .unwrap()
             .peers_on_same_chain()
             // Filter out any peers who we aren't connected with.
             .filter_map(|(peer_id, info)| network.peer(*peer_id).map(|peer| (peer, *info)))
+            .map(|(peer, info)| {
+                let peer_id = peer.peer_id();
+                let preferred = peer_heights.peer_controls.is_preferred(&peer_id);
+                match request_type {
+                    // Summaries are small, so they're fetched from whichever peer is fastest.
+                    PeerCheckpointRequestType::Summary => {
+                        (u8::from(!preferred), peer.connection_rtt(), peer, info)
+                    }
+                    // Contents make up most of the data synced, so they're fetched from peers in
+                    // our region first, by probed latency. Both are only hints: contents are
+                    // always verified against summaries certified by the committee.
+                    PeerCheckpointRequestType::Content => {
+                        let remote = !peer_heights.is_in_our_region(&peer_id);
+                        let latency = peer_heights
+                            .latency(&peer_id)
+                            .unwrap_or_else(|| peer.connection_rtt());
+                        let rank = 2 * u8::from(!preferred) + u8::from(remote);
+                        (rank, latency, peer, info)
+                    }
+                }
+            })
+            .collect();
+        // Lowest rank first, and the lowest latency within each rank.
+        peers.sort_by_key(|(rank, latency, _, _)| (*rank, *latency));
+        let peers = peers
+            .into_iter()
+            .map(|(rank, _, peer, info)| (rank, peer, info))
             .collect();
-        peers.sort_by(|(peer_a, _), (peer_b, _)| {
-            peer_a.connection_rtt().cmp(&peer_b.connection_rtt())
-        });
         Self {
-            peers: peers.into(),
+            peers,
             requested_checkpoint: None,
             request_type,
         }
@@ -315,9 +380,15 @@ impl Iterator for PeerBalancer {
     fn next(&mut self) -> Option<Self::Item> {
         while !self.peers.is_empty() {
             const SELECTION_WINDOW: usize = 2;
-            let idx =
-                rand::thread_rng().gen_range(0..std::cmp::min(SELECTION_WINDOW, self.peers.len()));
-            let (peer, info) = self.peers.remove(idx).unwrap();
+            let best_rank = self.peers[0].0;
+            let window = self
+                .peers
+                .iter()
+                .take(SELECTION_WINDOW)
+                .take_while(|(rank, _, _)| *rank == best_rank)
+                .count();
+            let idx = rand::thread_rng().gen_range(0..window);
+            let (_rank, peer, info) = self.peers.remove(idx).unwrap();
             let requested_checkpoint = self.requested_checkpoint.unwrap_or(0);
             match &self.request_type {
                 // Summary will never be pruned
@@ -898,13 +969,16 @@ async fn query_peers_for_their_latest_checkpoint(
             let mut client = StateSyncClient::new(peer);
 
             async move {
+                let start = Instant::now();
                 let response = query_peer_for_latest_info(&mut client, timeout).await;
                 match response {
-                    Some((highest_checkpoint, low_watermark)) => peer_heights
-                        .write()
-                        .unwrap()
-                        .update_peer_info(peer_id, highest_checkpoint.clone(), low_watermark)
-                        .then_some(highest_checkpoint),
+                    Some((highest_checkpoint, low_watermark)) => {
+                        let mut peer_heights = peer_heights.write().unwrap();
+                        peer_heights.record_latency(peer_id, start.elapsed());
+                        peer_heights
+                            .update_peer_info(peer_id, highest_checkpoint.clone(), low_watermark)
+                            .then_some(highest_checkpoint)
+                    }
                     None => None,
                 }
             }
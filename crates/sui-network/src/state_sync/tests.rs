@@ -17,6 +17,7 @@ use sui_archival::reader::ArchiveReaderBalancer;
 use sui_archival::writer::ArchiveWriter;
 use sui_config::node::ArchiveReaderConfig;
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_config::p2p::{PeerRegion, StateSyncConfig};
 use sui_storage::{FileCompression, StorageFormat};
 use sui_swarm_config::test_utils::{empty_contents, CommitteeFixture};
 use sui_types::{
@@ -920,3 +921,39 @@ async fn sync_with_checkpoints_watermark() {
         &last_checkpoint_seq
     );
 }
+
+#[test]
+fn peer_regions_and_latency() {
+    let nearby = PeerId([1; 32]);
+    let remote = PeerId([2; 32]);
+    let config = StateSyncConfig {
+        region: Some("us-east-1".to_owned()),
+        peer_regions: vec![
+            PeerRegion {
+                peer_id: nearby,
+                region: "us-east-1".to_owned(),
+            },
+            PeerRegion {
+                peer_id: remote,
+                region: "eu-west-1".to_owned(),
+            },
+        ],
+        ..Default::default()
+    };
+    let (UnstartedStateSync { peer_heights, .. }, _server) = Builder::new()
+        .config(config)
+        .store(SharedInMemoryStore::default())
+        .build_internal();
+
+    let mut peer_heights = peer_heights.write().unwrap();
+    assert!(peer_heights.is_in_our_region(&nearby));
+    assert!(!peer_heights.is_in_our_region(&remote));
+    assert!(!peer_heights.is_in_our_region(&PeerId([3; 32])));
+
+    // A single slow probe only moves the smoothed latency part of the way.
+    peer_heights.record_latency(nearby, Duration::from_millis(10));
+    peer_heights.record_latency(nearby, Duration::from_millis(110));
+    let latency = peer_heights.latency(&nearby).unwrap();
+    assert!(latency > Duration::from_millis(10) && latency < Duration::from_millis(50));
+    assert_eq!(peer_heights.latency(&remote), None);
+}
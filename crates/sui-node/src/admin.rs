@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::SuiNode;
+use anemo::PeerId;
 use axum::{
     extract::{Query, State},
     http::StatusCode,
@@ -47,6 +48,20 @@ use tracing::info;
 // Reset tracing to the TRACE_FILTER env var.
 //
 //   $ curl -X POST 'http://127.0.0.1:1337/reset-tracing'
+//
+// List connected p2p peers with their RTT and state-sync heights, and any banned peers:
+//
+//   $ curl 'http://127.0.0.1:1337/p2p/peers'
+//
+// Ban a p2p peer, disconnecting from it and rejecting its requests until it is unbanned:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/p2p/ban?peer_id=<hex>'
+//   $ curl -X POST 'http://127.0.0.1:1337/p2p/unban?peer_id=<hex>'
+//
+// Prefer a p2p peer when choosing where to sync checkpoints from:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/p2p/prefer?peer_id=<hex>'
+//   $ curl -X POST 'http://127.0.0.1:1337/p2p/unprefer?peer_id=<hex>'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -56,6 +71,11 @@ const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const P2P_PEERS: &str = "/p2p/peers";
+const P2P_BAN: &str = "/p2p/ban";
+const P2P_UNBAN: &str = "/p2p/unban";
+const P2P_PREFER: &str = "/p2p/prefer";
+const P2P_UNPREFER: &str = "/p2p/unprefer";
 
 struct AppState {
     node: Arc<SuiNode>,
@@ -74,6 +94,7 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(LOGGING_ROUTE, get(get_filter))
         .route(CAPABILITIES, get(capabilities))
         .route(NODE_CONFIG, get(node_config))
+        .route(P2P_PEERS, get(p2p_peers))
         .route(LOGGING_ROUTE, post(set_filter))
         .route(
             SET_BUFFER_STAKE_ROUTE,
@@ -86,6 +107,10 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
         .route(TRACING_ROUTE, post(enable_tracing))
         .route(TRACING_RESET_ROUTE, post(reset_tracing))
+        .route(P2P_BAN, post(p2p_ban))
+        .route(P2P_UNBAN, post(p2p_unban))
+        .route(P2P_PREFER, post(p2p_prefer))
+        .route(P2P_UNPREFER, post(p2p_unprefer))
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -288,3 +313,99 @@ async fn force_close_epoch(
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
     }
 }
+
+async fn p2p_peers(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let node = &state.node;
+    let sync_info = node.state_sync.peer_sync_info();
+
+    let mut output = String::new();
+    let mut connected = node.p2p_network.peers();
+    connected.sort();
+    for peer_id in connected {
+        let Some(peer) = node.p2p_network.peer(peer_id) else {
+            continue;
+        };
+        output.push_str(&format!(
+            "{peer_id} address={} rtt={:?}",
+            peer.address(),
+            peer.connection_rtt()
+        ));
+        if let Some(info) = sync_info.get(&peer_id) {
+            output.push_str(&format!(
+                " height={} lowest={} on_same_chain={}",
+                info.height, info.lowest, info.on_same_chain_as_us
+            ));
+        }
+        if node.peer_controls.is_preferred(&peer_id) {
+            output.push_str(" preferred");
+        }
+        output.push('\n');
+    }
+
+    let mut banned = node.peer_controls.banned();
+    banned.sort();
+    for peer_id in banned {
+        output.push_str(&format!("{peer_id} banned\n"));
+    }
+
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+struct Peer {
+    peer_id: PeerId,
+}
+
+async fn p2p_ban(State(state): State<Arc<AppState>>, peer: Query<Peer>) -> (StatusCode, String) {
+    let Query(Peer { peer_id }) = peer;
+    if !state.node.peer_controls.ban(peer_id) {
+        return (
+            StatusCode::OK,
+            format!("peer {peer_id} is already banned\n"),
+        );
+    }
+
+    // Inbound requests are rejected from now on, but existing connections have to be closed.
+    let _ = state.node.p2p_network.disconnect(peer_id);
+    info!(%peer_id, "Banned p2p peer");
+    (StatusCode::OK, format!("peer {peer_id} banned\n"))
+}
+
+async fn p2p_unban(State(state): State<Arc<AppState>>, peer: Query<Peer>) -> (StatusCode, String) {
+    let Query(Peer { peer_id }) = peer;
+    if state.node.peer_controls.unban(&peer_id) {
+        info!(%peer_id, "Unbanned p2p peer");
+        (StatusCode::OK, format!("peer {peer_id} unbanned\n"))
+    } else {
+        (StatusCode::OK, format!("peer {peer_id} is not banned\n"))
+    }
+}
+
+async fn p2p_prefer(State(state): State<Arc<AppState>>, peer: Query<Peer>) -> (StatusCode, String) {
+    let Query(Peer { peer_id }) = peer;
+    if state.node.peer_controls.is_banned(&peer_id) {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("peer {peer_id} is banned, unban it first\n"),
+        );
+    }
+    state.node.peer_controls.prefer(peer_id);
+    info!(%peer_id, "Preferring p2p peer for state sync");
+    (StatusCode::OK, format!("peer {peer_id} preferred\n"))
+}
+
+async fn p2p_unprefer(
+    State(state): State<Arc<AppState>>,
+    peer: Query<Peer>,
+) -> (StatusCode, String) {
+    let Query(Peer { peer_id }) = peer;
+    if state.node.peer_controls.unprefer(&peer_id) {
+        info!(%peer_id, "No longer preferring p2p peer for state sync");
+        (
+            StatusCode::OK,
+            format!("peer {peer_id} no longer preferred\n"),
+        )
+    } else {
+        (StatusCode::OK, format!("peer {peer_id} is not preferred\n"))
+    }
+}
@@ -0,0 +1,417 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in crash reports, written to a local directory for operators to attach to bug reports.
+//!
+//! A report is written when the node panics, with the panic's message and location, a backtrace,
+//! and metadata about the node: its version, role, chain and platform, and how long it had been
+//! running. Paths in the message and backtrace are cut down to the part within the source tree or
+//! crate they are in, so that reports don't give away the layout of the host, e.g. user names.
+//! Nothing is taken from the node's config, so keys and addresses are left out.
+//!
+//! Aborts and kills (e.g. by the OOM killer) can't be caught as they happen. Instead, a marker is
+//! kept in the directory while the node runs, and if it is still there when the node starts, the
+//! previous run didn't shut down cleanly, and a report of that is written, pointing to any panic
+//! reports from the run.
+//!
+//! If an upload URL is configured, the reports that haven't been uploaded yet are POSTed to it
+//! when the node starts, and `.uploaded` is added to their names once they are.
+
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::panic::PanicInfo;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use sui_config::node::CrashReportConfig;
+use tracing::{info, warn};
+
+/// Kept in the directory while the node runs, holding the metadata of the run.
+const MARKER: &str = "running";
+const REPORT_PREFIX: &str = "crash-";
+const REPORT_SUFFIX: &str = ".txt";
+const UPLOADED_SUFFIX: &str = ".uploaded";
+
+static REPORTER: OnceLock<Reporter> = OnceLock::new();
+
+/// Start reporting panics as configured, with `metadata` about the node in each report, and
+/// report the previous run if it didn't shut down cleanly. Panics are reported before they are
+/// passed on to the panic hook already set, so this is to be called once logging is set up.
+pub fn install(
+    config: &CrashReportConfig,
+    metadata: Vec<(&'static str, String)>,
+) -> io::Result<()> {
+    let reporter = Reporter::new(config, metadata);
+    if let Some(report) = reporter.start()? {
+        warn!(
+            "The previous run didn't shut down cleanly, see {}",
+            report.display()
+        );
+    }
+    REPORTER
+        .set(reporter)
+        .map_err(|_| io::Error::new(io::ErrorKind::AlreadyExists, "Already installed"))?;
+
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic| {
+        if let Some(reporter) = REPORTER.get() {
+            match reporter.report_panic(panic) {
+                Ok(report) => eprintln!("Wrote crash report {}", report.display()),
+                Err(e) => eprintln!("Failed to write crash report: {e}"),
+            }
+        }
+        previous_hook(panic);
+    }));
+    Ok(())
+}
+
+/// Add `value` to the metadata in the reports written from now on, e.g. once it is known.
+pub fn add_metadata(key: &'static str, value: String) {
+    if let Some(reporter) = REPORTER.get() {
+        let mut metadata = reporter.metadata.write().unwrap_or_else(|e| e.into_inner());
+        metadata.push((key, value));
+    }
+}
+
+/// Record that the node is shutting down cleanly, so that this run isn't reported on the next
+/// start.
+pub fn clean_shutdown() {
+    if let Some(reporter) = REPORTER.get() {
+        let _ = fs::remove_file(reporter.directory.join(MARKER));
+    }
+}
+
+/// Upload the reports that haven't been uploaded yet, if an upload URL is configured. Reports that
+/// fail to upload are tried again on the next start.
+pub async fn upload_reports(config: &CrashReportConfig) {
+    let Some(url) = &config.upload_url else {
+        return;
+    };
+    let client = reqwest::Client::new();
+    for path in reports(&config.directory) {
+        if path.to_string_lossy().ends_with(UPLOADED_SUFFIX) {
+            continue;
+        }
+        let body = match tokio::fs::read(&path).await {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to read crash report {}: {e}", path.display());
+                continue;
+            }
+        };
+        let response = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        if let Err(e) = response {
+            warn!("Failed to upload crash report {}: {e}", path.display());
+            continue;
+        }
+        let mut uploaded = path.clone().into_os_string();
+        uploaded.push(UPLOADED_SUFFIX);
+        if let Err(e) = tokio::fs::rename(&path, &uploaded).await {
+            warn!("Failed to mark {} as uploaded: {e}", path.display());
+        }
+        info!("Uploaded crash report {}", path.display());
+    }
+}
+
+struct Reporter {
+    directory: PathBuf,
+    max_reports: usize,
+    started: Instant,
+    pid: u32,
+    /// About the node, in every report, in the order added.
+    metadata: RwLock<Vec<(&'static str, String)>>,
+}
+
+impl Reporter {
+    fn new(config: &CrashReportConfig, mut metadata: Vec<(&'static str, String)>) -> Self {
+        let pid = std::process::id();
+        metadata.extend([
+            ("os", std::env::consts::OS.to_string()),
+            ("arch", std::env::consts::ARCH.to_string()),
+            ("pid", pid.to_string()),
+            ("started_at_ms", now_ms().to_string()),
+        ]);
+        Self {
+            directory: config.directory.clone(),
+            max_reports: config.max_reports,
+            started: Instant::now(),
+            pid,
+            metadata: RwLock::new(metadata),
+        }
+    }
+
+    /// Create the directory, and report the previous run if its marker is still there, before
+    /// leaving a marker for this one.
+    fn start(&self) -> io::Result<Option<PathBuf>> {
+        fs::create_dir_all(&self.directory)?;
+        let marker = self.directory.join(MARKER);
+        let report = match fs::read_to_string(&marker) {
+            Ok(previous) => Some(self.report_unclean_shutdown(&previous)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        fs::write(&marker, self.metadata())?;
+        Ok(report)
+    }
+
+    fn report_unclean_shutdown(&self, previous: &str) -> io::Result<PathBuf> {
+        let field = |key: &str| {
+            previous
+                .lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix(": "))
+                .and_then(|value| value.parse::<u128>().ok())
+        };
+        let panics: Vec<_> = match (field("pid"), field("started_at_ms")) {
+            (Some(pid), Some(started_at_ms)) => reports(&self.directory)
+                .into_iter()
+                .filter_map(|path| {
+                    let name = path.file_name()?.to_str()?.to_string();
+                    let (time_ms, report_pid) = parse_report_name(&name)?;
+                    (report_pid as u128 == pid && time_ms >= started_at_ms).then_some(name)
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        let mut body = String::from(
+            "The previous run of the node didn't shut down cleanly: it panicked, aborted, was \
+             killed (e.g. by the OOM killer), or the host went down.\n\n",
+        );
+        if panics.is_empty() {
+            body.push_str("No panics were reported in the run.\n");
+        } else {
+            body.push_str("Panics reported in the run:\n");
+            for name in panics {
+                let _ = writeln!(body, "  {name}");
+            }
+        }
+        let _ = write!(body, "\nprevious run:\n{previous}");
+        self.write("unclean-shutdown", &body)
+    }
+
+    fn report_panic(&self, panic: &PanicInfo<'_>) -> io::Result<PathBuf> {
+        let payload = panic.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("<non-string payload>");
+        let location = panic
+            .location()
+            .map(|l| format!("{}:{}:{}", redact_path(l.file()), l.line(), l.column()));
+        let body = panic_body(
+            std::thread::current().name().unwrap_or("<unnamed>"),
+            location.as_deref().unwrap_or("<unknown>"),
+            message,
+            &Backtrace::force_capture().to_string(),
+        );
+        self.write("panic", &body)
+    }
+
+    /// The metadata, one `key: value` per line.
+    fn metadata(&self) -> String {
+        // A panic while the metadata was being added leaves it poisoned, but still whole.
+        let metadata = self.metadata.read().unwrap_or_else(|e| e.into_inner());
+        metadata
+            .iter()
+            .map(|(key, value)| format!("{key}: {value}\n"))
+            .collect()
+    }
+
+    /// Write a report of `kind`, and remove the oldest reports beyond the most kept.
+    fn write(&self, kind: &str, body: &str) -> io::Result<PathBuf> {
+        // Reports written within the same millisecond are told apart by bumping their times.
+        let mut time_ms = now_ms();
+        let path = loop {
+            let name = format!(
+                "{REPORT_PREFIX}{time_ms:013}-{}-{kind}{REPORT_SUFFIX}",
+                self.pid
+            );
+            let path = self.directory.join(name);
+            if !path.exists() {
+                break path;
+            }
+            time_ms += 1;
+        };
+        let report = format!(
+            "Sui node crash report: {kind}\n\ntime_ms: {time_ms}\n{}uptime_seconds: {}\n\n{body}",
+            self.metadata(),
+            self.started.elapsed().as_secs(),
+        );
+        fs::write(&path, report)?;
+
+        let reports = reports(&self.directory);
+        for old in &reports[..reports.len().saturating_sub(self.max_reports)] {
+            let _ = fs::remove_file(old);
+        }
+        Ok(path)
+    }
+}
+
+fn panic_body(thread: &str, location: &str, message: &str, backtrace: &str) -> String {
+    format!(
+        "thread: {thread}\nlocation: {location}\nmessage: {}\n\nbacktrace:\n{}\n",
+        redact(message),
+        redact(backtrace),
+    )
+}
+
+/// The reports in `directory`, oldest first.
+fn reports(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return vec![];
+    };
+    let mut reports: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| parse_report_name(name).is_some())
+        })
+        .collect();
+    reports.sort();
+    reports
+}
+
+/// The time a report was written, and the process that wrote it, from its file name.
+fn parse_report_name(name: &str) -> Option<(u128, u32)> {
+    let mut parts = name.strip_prefix(REPORT_PREFIX)?.splitn(3, '-');
+    let time_ms = parts.next()?.parse().ok()?;
+    let pid = parts.next()?.parse().ok()?;
+    parts
+        .next()?
+        .contains(REPORT_SUFFIX)
+        .then_some((time_ms, pid))
+}
+
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis())
+}
+
+/// `text` with the absolute paths in it redacted (see [`redact_path`]).
+fn redact(text: &str) -> String {
+    let is_separator = |c: char| c.is_whitespace() || "'\"`()[]{}<>,;".contains(c);
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('/') {
+        // Only paths starting at a word boundary, or after `=` or `:`, e.g. in `path=/home/...`.
+        let boundary = rest[..start]
+            .chars()
+            .next_back()
+            .map_or(true, |c| is_separator(c) || c == '=' || c == ':');
+        let end = rest[start..]
+            .find(is_separator)
+            .map_or(rest.len(), |end| start + end);
+        redacted.push_str(&rest[..start]);
+        if boundary {
+            redacted.push_str(&redact_path(&rest[start..end]));
+        } else {
+            redacted.push_str(&rest[start..end]);
+        }
+        rest = &rest[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// An absolute `path` cut down to the part within the source tree or crate it is in, e.g.
+/// `crates/sui-node/src/main.rs` or `tokio-1.35.0/src/runtime/mod.rs`, or just its file name if
+/// it isn't in one. Relative paths are left as they are.
+fn redact_path(path: &str) -> String {
+    if !path.starts_with('/') {
+        return path.to_string();
+    }
+    // Dependencies are in a directory of the registry or of a git checkout.
+    for (marker, skip) in [("/registry/src/", 1), ("/git/checkouts/", 2)] {
+        if let Some(i) = path.find(marker) {
+            let mut parts = path[i + marker.len()..].splitn(skip + 1, '/');
+            if let Some(within) = parts.nth(skip) {
+                return within.to_string();
+            }
+        }
+    }
+    for marker in ["/external-crates/", "/crates/", "/library/"] {
+        if let Some(i) = path.find(marker) {
+            return path[i + 1..].to_string();
+        }
+    }
+    match path.trim_end_matches('/').rsplit_once('/') {
+        Some((_, name)) if !name.is_empty() => format!(".../{name}"),
+        _ => "...".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let backtrace = "   0: sui_node::main\n             at /home/alice/sui/crates/sui-node/src/main.rs:10:5\n   1: tokio::runtime::block_on\n             at /home/alice/.cargo/registry/src/index.crates.io-6f17d22bba15001f/tokio-1.35.0/src/runtime/mod.rs:1:1\n   2: fastcrypto::hash\n             at /home/alice/.cargo/git/checkouts/fastcrypto-1e2f3a/69d496c/fastcrypto/src/hash.rs:2:2\n   3: std::rt::lang_start\n             at /rustc/82e1608dfa6e0b5569232559e3d385fea5a93112/library/std/src/rt.rs:3:3";
+        assert_eq!(
+            redact(backtrace),
+            "   0: sui_node::main\n             at crates/sui-node/src/main.rs:10:5\n   1: tokio::runtime::block_on\n             at tokio-1.35.0/src/runtime/mod.rs:1:1\n   2: fastcrypto::hash\n             at fastcrypto/src/hash.rs:2:2\n   3: std::rt::lang_start\n             at library/std/src/rt.rs:3:3"
+        );
+        assert_eq!(
+            redact("failed to open \"/home/alice/.sui/db/store\" (path=/var/lib/sui/x.yaml): 1/2"),
+            "failed to open \".../store\" (path=.../x.yaml): 1/2"
+        );
+        assert_eq!(
+            redact_path("crates/sui-node/src/lib.rs"),
+            "crates/sui-node/src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn test_reports() {
+        let directory =
+            std::env::temp_dir().join(format!("sui-node-crash-reports-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let config = CrashReportConfig {
+            directory: directory.clone(),
+            max_reports: 3,
+            upload_url: None,
+        };
+        let metadata = || vec![("version", "1.0.0-abc".to_string())];
+
+        // A first run, that panics and doesn't shut down cleanly.
+        let reporter = Reporter::new(&config, metadata());
+        assert!(reporter.start().unwrap().is_none());
+        let body = panic_body("main", "crates/a/src/lib.rs:1:1", "oops", "");
+        let panic = reporter.write("panic", &body).unwrap();
+        let report = fs::read_to_string(&panic).unwrap();
+        assert!(report.starts_with("Sui node crash report: panic\n"));
+        assert!(report.contains("version: 1.0.0-abc\n"));
+        assert!(report.contains("message: oops\n"));
+
+        // The next run reports it, pointing to the panic.
+        let reporter = Reporter::new(&config, metadata());
+        let unclean = reporter.start().unwrap().unwrap();
+        let report = fs::read_to_string(unclean).unwrap();
+        let panic_name = panic.file_name().unwrap().to_str().unwrap();
+        assert!(report.contains(&format!("Panics reported in the run:\n  {panic_name}\n")));
+        assert!(report.contains("previous run:\nversion: 1.0.0-abc\n"));
+
+        // Only the newest reports are kept.
+        for _ in 0..3 {
+            reporter.write("panic", &body).unwrap();
+        }
+        let kept = reports(&directory);
+        assert_eq!(kept.len(), 3);
+        assert!(!kept.contains(&panic));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anemo::Network;
+use anemo_tower::auth::RequireAuthorizationLayer;
 use anemo_tower::callback::CallbackLayer;
 use anemo_tower::trace::DefaultMakeSpan;
 use anemo_tower::trace::DefaultOnFailure;
@@ -16,6 +17,7 @@ use narwhal_worker::LazyNarwhalClient;
 use prometheus::Registry;
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
 #[cfg(msim)]
@@ -80,6 +82,7 @@ use sui_core::epoch::reconfiguration::ReconfigurationInitiator;
 use sui_core::execution_cache::{ExecutionCache, ExecutionCacheReconfigAPI};
 use sui_core::module_cache_metrics::ResolverMetrics;
 use sui_core::overload_monitor::overload_monitor;
+use sui_core::scheduled_transactions::ScheduledTransactionService;
 use sui_core::signature_verifier::SignatureVerifierMetrics;
 use sui_core::state_accumulator::StateAccumulator;
 use sui_core::storage::RocksDbStore;
@@ -93,6 +96,8 @@ use sui_json_rpc::governance_api::GovernanceReadApi;
 use sui_json_rpc::indexer_api::IndexerApi;
 use sui_json_rpc::move_utils::MoveUtils;
 use sui_json_rpc::read_api::ReadApi;
+use sui_json_rpc::read_your_writes::StateCheckpointWatermark;
+use sui_json_rpc::scheduled_transaction_api::ScheduledTransactionApi;
 use sui_json_rpc::transaction_builder_api::TransactionBuilderApi;
 use sui_json_rpc::transaction_execution_api::TransactionExecutionApi;
 use sui_json_rpc::JsonRpcServerBuilder;
@@ -101,6 +106,7 @@ use sui_macros::{fail_point_async, replay_log};
 use sui_network::api::ValidatorServer;
 use sui_network::discovery;
 use sui_network::discovery::TrustedPeerChangeEvent;
+use sui_network::peer_controls::{PeerControls, RejectBannedPeers};
 use sui_network::state_sync;
 use sui_protocol_config::{Chain, ProtocolConfig, SupportedProtocolVersions};
 use sui_snapshot::uploader::StateSnapshotUploader;
@@ -127,8 +133,10 @@ use typed_store::DBMetrics;
 use crate::metrics::{GrpcMetrics, SuiNodeMetrics};
 
 pub mod admin;
+pub mod crash_report;
 mod handle;
 pub mod metrics;
+mod tls;
 
 pub struct ValidatorComponents {
     validator_server_handle: JoinHandle<Result<()>>,
@@ -213,8 +221,11 @@ pub struct SuiNode {
     registry_service: RegistryService,
     metrics: Arc<SuiNodeMetrics>,
 
+    p2p_network: Network,
     _discovery: discovery::Handle,
     state_sync: state_sync::Handle,
+    /// Runtime bans and state-sync preferences for p2p peers, managed through the admin server.
+    peer_controls: PeerControls,
     checkpoint_store: Arc<CheckpointStore>,
     accumulator: Arc<StateAccumulator>,
     connection_monitor_status: Arc<ConnectionMonitorStatus>,
@@ -540,12 +551,14 @@ impl SuiNode {
         let archive_readers =
             ArchiveReaderBalancer::new(config.archive_reader_config(), &prometheus_registry)?;
         let (trusted_peer_change_tx, trusted_peer_change_rx) = watch::channel(Default::default());
+        let peer_controls = PeerControls::default();
         let (p2p_network, discovery_handle, state_sync_handle) = Self::create_p2p_network(
             &config,
             state_sync_store.clone(),
             chain_identifier,
             trusted_peer_change_rx,
             archive_readers.clone(),
+            peer_controls.clone(),
             &prometheus_registry,
         )?;
         // We must explicitly send this instead of relying on the initial value to trigger
@@ -651,15 +664,33 @@ impl SuiNode {
             None
         };
 
+        let scheduled_transactions =
+            match (&config.scheduled_transactions, &transaction_orchestrator) {
+                (Some(scheduled_config), Some(transaction_orchestrator)) => {
+                    let service = Arc::new(ScheduledTransactionService::new(
+                        state.clone(),
+                        transaction_orchestrator.clone(),
+                        config.db_path().join("scheduled_transactions"),
+                        scheduled_config.clone(),
+                        &prometheus_registry,
+                    ));
+                    spawn_monitored_task!(service.clone().run());
+                    Some(service)
+                }
+                _ => None,
+            };
+
         let http_server = build_http_server(
             state.clone(),
             state_sync_store,
             &transaction_orchestrator.clone(),
+            &scheduled_transactions,
             &config,
             &prometheus_registry,
             custom_rpc_runtime,
             software_version,
-        )?;
+        )
+        .await?;
 
         let accumulator = Arc::new(StateAccumulator::new(store));
 
@@ -722,8 +753,10 @@ impl SuiNode {
             registry_service,
             metrics: sui_node_metrics,
 
+            p2p_network,
             _discovery: discovery_handle,
             state_sync: state_sync_handle,
+            peer_controls,
             checkpoint_store,
             accumulator,
             end_of_epoch_channel,
@@ -915,23 +948,29 @@ impl SuiNode {
         chain_identifier: ChainIdentifier,
         trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
         archive_readers: ArchiveReaderBalancer,
+        peer_controls: PeerControls,
         prometheus_registry: &Registry,
     ) -> Result<(Network, discovery::Handle, state_sync::Handle)> {
         let (state_sync, state_sync_server) = state_sync::Builder::new()
             .config(config.p2p_config.state_sync.clone().unwrap_or_default())
             .store(state_sync_store)
             .archive_readers(archive_readers)
+            .peer_controls(peer_controls.clone())
             .with_metrics(prometheus_registry)
             .build();
 
         let (discovery, discovery_server) = discovery::Builder::new(trusted_peer_change_rx)
             .config(config.p2p_config.clone())
+            .peer_controls(peer_controls.clone())
             .build();
 
         let p2p_network = {
             let routes = anemo::Router::new()
                 .add_rpc_service(discovery_server)
-                .add_rpc_service(state_sync_server);
+                .add_rpc_service(state_sync_server)
+                .route_layer(RequireAuthorizationLayer::new(RejectBannedPeers::new(
+                    peer_controls,
+                )));
 
             let inbound_network_metrics =
                 NetworkMetrics::new("sui", "inbound", prometheus_registry);
@@ -1773,10 +1812,11 @@ fn build_kv_store(
     )))
 }
 
-pub fn build_http_server(
+pub async fn build_http_server(
     state: Arc<AuthorityState>,
     store: RocksDbStore,
     transaction_orchestrator: &Option<Arc<TransactiondOrchestrator<NetworkAuthorityClient>>>,
+    scheduled_transactions: &Option<Arc<ScheduledTransactionService<NetworkAuthorityClient>>>,
     config: &NodeConfig,
     prometheus_registry: &Registry,
     _custom_runtime: Option<Handle>,
@@ -1793,6 +1833,7 @@ pub fn build_http_server(
 
     let json_rpc_router = {
         let mut server = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
+        server.set_checkpoint_watermark(Arc::new(StateCheckpointWatermark::new(state.clone())));
 
         let kv_store = build_kv_store(&state, config, prometheus_registry)?;
 
@@ -1823,6 +1864,10 @@ pub fn build_http_server(
             ))?;
         }
 
+        if let Some(scheduled_transactions) = scheduled_transactions {
+            server.register_module(ScheduledTransactionApi::new(scheduled_transactions.clone()))?;
+        }
+
         let name_service_config =
             if let (Some(package_address), Some(registry_id), Some(reverse_registry_id)) = (
                 config.name_service_package_address,
@@ -1860,12 +1905,67 @@ pub fn build_http_server(
         router = router.nest("/rest", rest_router);
     }
 
-    let server = axum::Server::bind(&config.json_rpc_address).serve(router.into_make_service());
+    #[cfg(unix)]
+    if let Some(socket) = &config.json_rpc_unix_socket {
+        let listener = sui_json_rpc::unix_socket::bind(&socket.path, socket.mode)
+            .map_err(|e| anyhow!("Failed to bind Unix socket {}: {e}", socket.path.display()))?;
+        info!(path =? socket.path, "Sui JSON-RPC server listening on Unix socket");
+        let server =
+            sui_json_rpc::unix_socket::serve(listener, router.clone(), std::future::pending());
+        tokio::spawn(async move { server.await.unwrap() });
+    }
+
+    let mut addresses = vec![config.json_rpc_address];
+    addresses.extend(&config.json_rpc_additional_addresses);
+    let listeners = sui_json_rpc::listen::bind_all(&addresses)?;
 
-    let addr = server.local_addr();
-    let handle = tokio::spawn(async move { server.await.unwrap() });
+    let handle = if let Some(tls_config) = &config.json_rpc_tls {
+        let (tls, reloader) = tls::load_reloading_tls_config(tls_config).await?;
+        let acme_server = tls_config
+            .acme_challenge
+            .as_ref()
+            .map(tls::start_acme_challenge_server)
+            .transpose()?;
+
+        let mut servers = vec![];
+        for listener in listeners {
+            let addr = listener.local_addr()?;
+            let server = axum_server::from_tcp_rustls(listener, tls.clone()).serve(
+                router
+                    .clone()
+                    .into_make_service_with_connect_info::<SocketAddr>(),
+            );
+            info!(local_addr =? addr, "Sui JSON-RPC server listening on {addr} over TLS");
+            servers.push(server);
+        }
 
-    info!(local_addr =? addr, "Sui JSON-RPC server listening on {addr}");
+        tokio::spawn(async move {
+            for result in futures::future::join_all(servers).await {
+                result.unwrap();
+            }
+            reloader.abort();
+            if let Some(acme_server) = acme_server {
+                acme_server.abort();
+            }
+        })
+    } else {
+        let mut servers = vec![];
+        for listener in listeners {
+            let server = axum::Server::from_tcp(listener)?.serve(
+                router
+                    .clone()
+                    .into_make_service_with_connect_info::<SocketAddr>(),
+            );
+            let addr = server.local_addr();
+            info!(local_addr =? addr, "Sui JSON-RPC server listening on {addr}");
+            servers.push(server);
+        }
+        tokio::spawn(async move {
+            for result in futures::future::join_all(servers).await {
+                result.unwrap();
+            }
+        })
+    };
 
     Ok(Some(handle))
 }
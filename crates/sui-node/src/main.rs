@@ -13,7 +13,7 @@ use mysten_common::sync::async_once_cell::AsyncOnceCell;
 use sui_config::node::RunWithRange;
 use sui_config::{Config, NodeConfig};
 use sui_core::runtime::SuiRuntimes;
-use sui_node::metrics;
+use sui_node::{crash_report, metrics};
 use sui_protocol_config::SupportedProtocolVersions;
 use sui_telemetry::send_telemetry_event;
 use sui_types::committee::EpochId;
@@ -87,7 +87,10 @@ fn main() {
 
     let runtimes = SuiRuntimes::new(&config);
     let metrics_rt = runtimes.metrics.enter();
-    let registry_service = mysten_metrics::start_prometheus_server(config.metrics_address);
+    let mut metrics_addresses = vec![config.metrics_address];
+    metrics_addresses.extend(&config.metrics_additional_addresses);
+    let metrics_listeners = sui_json_rpc::listen::bind_all(&metrics_addresses).unwrap();
+    let registry_service = mysten_metrics::start_prometheus_server_on_listeners(metrics_listeners);
     let prometheus_registry = registry_service.default_registry();
 
     // Initialize logging
@@ -96,6 +99,23 @@ fn main() {
         .with_prom_registry(&prometheus_registry)
         .init();
 
+    let is_validator = config.consensus_config().is_some();
+
+    if let Some(crash_reports) = &config.crash_reports {
+        let role = if is_validator {
+            "validator"
+        } else {
+            "fullnode"
+        };
+        let metadata = vec![("version", VERSION.to_string()), ("role", role.to_string())];
+        if let Err(e) = crash_report::install(crash_reports, metadata) {
+            error!(
+                "Failed to set up crash reports in {}: {e}",
+                crash_reports.directory.display()
+            );
+        }
+    }
+
     drop(metrics_rt);
 
     info!("Sui Node version: {VERSION}");
@@ -104,22 +124,21 @@ fn main() {
         config.supported_protocol_versions
     );
 
-    info!(
-        "Started Prometheus HTTP endpoint at {}",
-        config.metrics_address
-    );
+    info!("Started Prometheus HTTP endpoint at {metrics_addresses:?}");
 
     {
         let _enter = runtimes.metrics.enter();
         metrics::start_metrics_push_task(&config, registry_service.clone());
+        metrics::start_metrics_export_tasks(&config, registry_service.clone());
+        if let Some(crash_reports) = config.crash_reports.clone() {
+            tokio::spawn(async move { crash_report::upload_reports(&crash_reports).await });
+        }
     }
 
     if let Some(listen_address) = args.listen_address {
         config.network_address = listen_address;
     }
 
-    let is_validator = config.consensus_config().is_some();
-
     let admin_interface_port = config.admin_interface_port;
 
     // Run node in a separate runtime so that admin/monitoring functions continue to work
@@ -169,6 +188,7 @@ fn main() {
         };
 
         info!("Sui chain identifier: {chain_identifier}");
+        crash_report::add_metadata("chain", chain_identifier.clone());
         prometheus_registry
             .register(mysten_metrics::uptime_metric(
                 if is_validator {
@@ -202,6 +222,7 @@ fn main() {
 
     // Drop and wait all runtimes on main thread
     drop(runtimes);
+    crash_report::clean_shutdown();
 }
 
 #[cfg(not(unix))]
@@ -17,11 +17,13 @@ use tracing::error;
 pub struct MetricsPushClient {
     certificate: std::sync::Arc<sui_tls::SelfSignedCertificate>,
     client: reqwest::Client,
+    network_public_key: sui_types::crypto::NetworkPublicKey,
 }
 
 impl MetricsPushClient {
     pub fn new(network_key: sui_types::crypto::NetworkKeyPair) -> Self {
         use fastcrypto::traits::KeyPair;
+        let network_public_key = network_key.public().clone();
         let certificate = std::sync::Arc::new(sui_tls::SelfSignedCertificate::new(
             network_key.private(),
             sui_tls::SUI_VALIDATOR_SERVER_NAME,
@@ -35,9 +37,14 @@ impl MetricsPushClient {
         Self {
             certificate,
             client,
+            network_public_key,
         }
     }
 
+    pub fn network_public_key(&self) -> &sui_types::crypto::NetworkPublicKey {
+        &self.network_public_key
+    }
+
     pub fn certificate(&self) -> &sui_tls::SelfSignedCertificate {
         &self.certificate
     }
@@ -59,6 +66,7 @@ pub fn start_metrics_push_task(config: &sui_config::NodeConfig, registry: Regist
         Some(MetricsConfig {
             push_interval_seconds,
             push_url: Some(url),
+            ..
         }) => {
             let interval = push_interval_seconds
                 .map(Duration::from_secs)
@@ -71,7 +79,7 @@ pub fn start_metrics_push_task(config: &sui_config::NodeConfig, registry: Regist
 
     // make a copy so we can make a new client later when we hit errors posting metrics
     let config_copy = config.clone();
-    let mut client = MetricsPushClient::new(config_copy.network_key_pair().copy());
+    let mut client = MetricsPushClient::new(current_network_key(&config_copy));
 
     async fn push_metrics(
         client: &MetricsPushClient,
@@ -137,16 +145,141 @@ pub fn start_metrics_push_task(config: &sui_config::NodeConfig, registry: Regist
         loop {
             interval.tick().await;
 
+            // pick up a network key that was rotated on disk since the last push
+            let network_key = current_network_key(&config_copy);
+            if network_key.public() != client.network_public_key() {
+                tracing::info!("network key changed on disk, creating a new metrics push client");
+                client = MetricsPushClient::new(network_key);
+            }
+
             if let Err(error) = push_metrics(&client, &url, &registry).await {
                 tracing::warn!("unable to push metrics: {error}; new client will be created");
                 // aggressively recreate our client connection if we hit an error
                 // since our tick interval is only every min, this should not be racey
-                client = MetricsPushClient::new(config_copy.network_key_pair().copy());
+                client = MetricsPushClient::new(current_network_key(&config_copy));
             }
         }
     });
 }
 
+/// Starts a task for each of the endpoints in `metrics.exports` that periodically pushes metrics
+/// to it, over the Prometheus remote-write protocol or to a Pushgateway.
+pub fn start_metrics_export_tasks(config: &sui_config::NodeConfig, registry: RegistryService) {
+    use mysten_metrics::export::{self, Relabel};
+    use sui_config::node::MetricsExportFormat;
+
+    const DEFAULT_METRICS_EXPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+    let Some(metrics) = &config.metrics else {
+        return;
+    };
+    for endpoint in &metrics.exports {
+        let mut url =
+            reqwest::Url::parse(&endpoint.url).expect("unable to parse metrics export url");
+        let default_headers = match endpoint.format {
+            MetricsExportFormat::RemoteWrite => &export::REMOTE_WRITE_HEADERS[..],
+            MetricsExportFormat::Pushgateway => {
+                // Pushes replace the metrics previously pushed under the same job and labels.
+                let mut segments = url
+                    .path_segments_mut()
+                    .expect("metrics export url can't be a base");
+                segments
+                    .pop_if_empty()
+                    .extend(["metrics", "job", "sui-node"]);
+                for (name, value) in &endpoint.external_labels {
+                    segments.extend([name, value]);
+                }
+                &export::PUSHGATEWAY_HEADERS[..]
+            }
+        };
+        let mut headers = reqwest::header::HeaderMap::new();
+        let endpoint_headers = endpoint
+            .headers
+            .iter()
+            .map(|(n, v)| (n.as_str(), v.as_str()));
+        for (name, value) in default_headers.iter().copied().chain(endpoint_headers) {
+            headers.insert(
+                reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                    .expect("invalid metrics export header name"),
+                reqwest::header::HeaderValue::from_str(value)
+                    .expect("invalid metrics export header value"),
+            );
+        }
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap();
+
+        let relabel = Relabel {
+            keep_metrics: endpoint.keep_metrics.clone(),
+            drop_metrics: endpoint.drop_metrics.clone(),
+            drop_labels: endpoint.drop_labels.clone(),
+            external_labels: endpoint.external_labels.clone().into_iter().collect(),
+        };
+        let format = endpoint.format;
+        let interval = endpoint
+            .interval_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_METRICS_EXPORT_INTERVAL);
+        let registry = registry.clone();
+
+        tokio::spawn(async move {
+            tracing::info!(export_url =% url, interval =? interval, "Started Metrics Export Service");
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            loop {
+                interval.tick().await;
+
+                let families = relabel.apply(registry.gather_all());
+                let request = match format {
+                    MetricsExportFormat::RemoteWrite => {
+                        let now = SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap()
+                            .as_millis() as i64;
+                        client
+                            .post(url.clone())
+                            .body(export::remote_write_body(&families, now))
+                    }
+                    MetricsExportFormat::Pushgateway => match export::pushgateway_body(&families) {
+                        Ok(body) => client.put(url.clone()).body(body),
+                        Err(error) => {
+                            tracing::warn!("unable to encode metrics for export: {error}");
+                            continue;
+                        }
+                    },
+                };
+                match request.send().await.and_then(|r| r.error_for_status()) {
+                    Ok(_) => tracing::debug!("successfully exported metrics to {url}"),
+                    Err(error) => tracing::warn!("unable to export metrics to {url}: {error}"),
+                }
+            }
+        });
+    }
+}
+
+/// The network key metrics pushes are authenticated with. Keys stored in a file are read again
+/// on every call, so credentials rotated on disk are used without restarting the node. The key
+/// loaded at startup is used if the file cannot be read.
+fn current_network_key(config: &sui_config::NodeConfig) -> sui_types::crypto::NetworkKeyPair {
+    use fastcrypto::traits::KeyPair;
+    use sui_types::crypto::SuiKeyPair;
+
+    match config.network_key_pair.reload_from_file() {
+        None => config.network_key_pair().copy(),
+        Some(Ok(SuiKeyPair::Ed25519(key))) => key,
+        Some(Ok(other)) => {
+            tracing::warn!("network key file holds a {other:?} key, only Ed25519 is allowed");
+            config.network_key_pair().copy()
+        }
+        Some(Err(error)) => {
+            tracing::warn!("unable to read network key file: {error}");
+            config.network_key_pair().copy()
+        }
+    }
+}
+
 pub struct SuiNodeMetrics {
     pub jwk_requests: IntCounterVec,
     pub jwk_request_errors: IntCounterVec,
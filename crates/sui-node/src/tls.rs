@@ -0,0 +1,213 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! TLS for the JSON-RPC listener. Certificates are read from disk, and reloaded whenever they
+//! change there, so they can be rotated without restarting the node, e.g. by an ACME client
+//! renewing them. Such a client can be served its HTTP-01 challenges from its webroot.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use sui_config::node::{AcmeChallengeConfig, JsonRpcTlsConfig};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Load the certificate and key in `config`, and keep reloading them whenever they change on
+/// disk, until the returned task is aborted.
+pub(crate) async fn load_reloading_tls_config(
+    config: &JsonRpcTlsConfig,
+) -> Result<(RustlsConfig, JoinHandle<()>)> {
+    let tls = RustlsConfig::from_pem_file(&config.cert_path, &config.key_path)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to load TLS certificate {} and key {}",
+                config.cert_path.display(),
+                config.key_path.display()
+            )
+        })?;
+
+    let reloaded = tls.clone();
+    let config = config.clone();
+    let handle = tokio::spawn(async move {
+        let interval = Duration::from_secs(config.reload_interval_seconds);
+        let mut loaded = modified_times(&config);
+        loop {
+            tokio::time::sleep(interval).await;
+            let current = modified_times(&config);
+            if current.is_none() || current == loaded {
+                continue;
+            }
+
+            // A failed reload, e.g. because the files were being rewritten, keeps the previous
+            // certificate, and is retried on the next tick.
+            match reloaded
+                .reload_from_pem_file(&config.cert_path, &config.key_path)
+                .await
+            {
+                Ok(()) => {
+                    info!(cert_path =? config.cert_path, "Reloaded JSON-RPC TLS certificate");
+                    loaded = current;
+                }
+                Err(e) => warn!("Failed to reload JSON-RPC TLS certificate: {e}"),
+            }
+        }
+    });
+
+    Ok((tls, handle))
+}
+
+fn modified_times(config: &JsonRpcTlsConfig) -> Option<(SystemTime, SystemTime)> {
+    let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    Some((modified(&config.cert_path)?, modified(&config.key_path)?))
+}
+
+/// Serve the ACME HTTP-01 challenge responses in `config.webroot` over plain HTTP.
+pub(crate) fn start_acme_challenge_server(config: &AcmeChallengeConfig) -> Result<JoinHandle<()>> {
+    let router = Router::new()
+        .route(
+            "/.well-known/acme-challenge/:token",
+            get(challenge_response),
+        )
+        .with_state(config.webroot.join(".well-known/acme-challenge"));
+
+    let server = axum::Server::try_bind(&config.listen_address)
+        .context("Failed to bind ACME challenge server")?
+        .serve(router.into_make_service());
+    info!("ACME challenge server listening on {}", server.local_addr());
+    Ok(tokio::spawn(async move { server.await.unwrap() }))
+}
+
+async fn challenge_response(
+    State(challenges): State<PathBuf>,
+    Path(token): Path<String>,
+) -> Result<String, StatusCode> {
+    // Tokens are base64url, which also keeps requests from escaping the challenge directory.
+    if token.is_empty()
+        || !token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    tokio::fs::read_to_string(challenges.join(token))
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::sync::Arc;
+
+    use fastcrypto::encoding::{Base64, Encoding};
+    use fastcrypto::traits::KeyPair;
+    use sui_tls::SelfSignedCertificate;
+    use sui_types::crypto::{get_key_pair, NetworkKeyPair};
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sui-node-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a new self-signed certificate, and its key, to where `config` reads them from.
+    fn write_certificate(config: &JsonRpcTlsConfig) {
+        let (_, key): (_, NetworkKeyPair) = get_key_pair();
+        let cert = SelfSignedCertificate::new(key.private(), "localhost");
+        let pem = |label: &str, der: &[u8]| {
+            format!(
+                "-----BEGIN {label}-----\n{}\n-----END {label}-----\n",
+                Base64::encode(der)
+            )
+        };
+        std::fs::write(
+            &config.cert_path,
+            pem("CERTIFICATE", &cert.rustls_certificate().0),
+        )
+        .unwrap();
+        std::fs::write(
+            &config.key_path,
+            pem("PRIVATE KEY", &cert.rustls_private_key().0),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_rotated_certificate() {
+        let dir = temp_dir("tls");
+        let config = JsonRpcTlsConfig {
+            cert_path: dir.join("cert.pem"),
+            key_path: dir.join("key.pem"),
+            reload_interval_seconds: 1,
+            acme_challenge: None,
+        };
+        write_certificate(&config);
+        let (tls, handle) = load_reloading_tls_config(&config).await.unwrap();
+        let loaded = tls.get_inner();
+
+        // The certificate is only reloaded once it changes on disk.
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(Arc::ptr_eq(&loaded, &tls.get_inner()));
+
+        write_certificate(&config);
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while Arc::ptr_eq(&loaded, &tls.get_inner()) {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("Rotated certificate is reloaded");
+
+        handle.abort();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_acme_challenge_server() {
+        let webroot = temp_dir("acme");
+        let challenges = webroot.join(".well-known/acme-challenge");
+        std::fs::create_dir_all(&challenges).unwrap();
+        std::fs::write(challenges.join("token_1-A"), "token_1-A.thumbprint").unwrap();
+        std::fs::write(webroot.join("secret"), "secret").unwrap();
+
+        let config = AcmeChallengeConfig {
+            listen_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8082),
+            webroot: webroot.clone(),
+        };
+        let handle = start_acme_challenge_server(&config).unwrap();
+        let get = |token: &str| {
+            let url = format!(
+                "http://{}/.well-known/acme-challenge/{token}",
+                config.listen_address
+            );
+            async move {
+                let response = reqwest::get(url).await.unwrap();
+                (response.status(), response.text().await.unwrap())
+            }
+        };
+
+        assert_eq!(
+            get("token_1-A").await,
+            (reqwest::StatusCode::OK, "token_1-A.thumbprint".to_string())
+        );
+        assert_eq!(get("unknown").await.0, reqwest::StatusCode::NOT_FOUND);
+        // Tokens can't reach outside of the challenge directory.
+        assert_eq!(
+            get("..%2F..%2Fsecret").await.0,
+            reqwest::StatusCode::NOT_FOUND
+        );
+
+        handle.abort();
+        std::fs::remove_dir_all(webroot).unwrap();
+    }
+}
@@ -351,6 +351,7 @@ impl RpcExampleProvider {
                     type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
                     bcs: None,
                     display: None,
+                    labels: None,
                 })
             })
             .collect()
@@ -395,6 +396,7 @@ impl RpcExampleProvider {
             type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
             bcs: None,
             display: None,
+            labels: None,
         });
 
         Examples::new(
@@ -460,6 +462,7 @@ impl RpcExampleProvider {
             data: page,
             next_cursor: Some((seq + pagelen).into()),
             has_next_page: true,
+            truncated: false,
         };
 
         Examples::new(
@@ -496,6 +499,7 @@ impl RpcExampleProvider {
                 display: None,
                 content: None,
                 bcs: None,
+                labels: None,
             })
             .collect::<Vec<_>>();
 
@@ -574,6 +578,7 @@ impl RpcExampleProvider {
             data,
             next_cursor,
             has_next_page,
+            truncated: false,
         };
         Examples::new(
             "suix_queryTransactionBlocks",
@@ -769,6 +774,7 @@ impl RpcExampleProvider {
             checkpoint: None,
             errors: vec![],
             raw_effects: vec![],
+            labels: None,
         };
 
         (data2, signatures, recipient, obj_id, result)
@@ -795,6 +801,7 @@ impl RpcExampleProvider {
             data: vec![event],
             next_cursor: Some((tx_dig, 5).into()),
             has_next_page: false,
+            truncated: false,
         };
         Examples::new(
             "sui_getEvents",
@@ -845,6 +852,7 @@ impl RpcExampleProvider {
             coin_object_count: 15,
             total_balance: 3000000000,
             locked_balance: HashMap::new(),
+            fiat_value: None,
         };
         Examples::new(
             "suix_getAllBalances",
@@ -876,6 +884,7 @@ impl RpcExampleProvider {
             data: coins,
             next_cursor: Some(next),
             has_next_page: true,
+            truncated: false,
         };
 
         Examples::new(
@@ -900,6 +909,7 @@ impl RpcExampleProvider {
             coin_object_count: 15,
             total_balance: 15,
             locked_balance: HashMap::new(),
+            fiat_value: None,
         };
 
         Examples::new(
@@ -968,6 +978,7 @@ impl RpcExampleProvider {
             data: coins,
             next_cursor: Some(next_cursor),
             has_next_page: true,
+            truncated: false,
         };
 
         Examples::new(
@@ -1203,6 +1214,7 @@ impl RpcExampleProvider {
             data: dynamic_fields,
             next_cursor: Some(next_cursor),
             has_next_page: true,
+            truncated: false,
         };
 
         Examples::new("suix_getDynamicFields",
@@ -1258,6 +1270,7 @@ impl RpcExampleProvider {
             ))),
             bcs: None,
             display: None,
+            labels: None,
         });
         Examples::new(
             "suix_getDynamicFieldObject",
@@ -1304,6 +1317,7 @@ impl RpcExampleProvider {
                     type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
                     bcs: None,
                     display: None,
+                    labels: None,
                 })
             })
             .collect::<Vec<_>>();
@@ -1313,6 +1327,7 @@ impl RpcExampleProvider {
             data: items,
             next_cursor: Some(next_cursor.unwrap()),
             has_next_page: true,
+            truncated: false,
         };
 
         Examples::new(
@@ -1357,6 +1372,7 @@ impl RpcExampleProvider {
             data,
             next_cursor,
             has_next_page,
+            truncated: false,
         };
         Examples::new(
             "suix_queryEvents",
@@ -1419,6 +1435,7 @@ impl RpcExampleProvider {
                         status: StakeStatus::Active {
                             estimated_reward: (principal as f64 * 0.0026) as u64,
                         },
+                        fiat_value: None,
                     },
                     Stake {
                         staked_sui_id: ObjectID::new(self.rng.gen()),
@@ -1426,6 +1443,7 @@ impl RpcExampleProvider {
                         stake_active_epoch: 143,
                         principal,
                         status: StakeStatus::Pending,
+                        fiat_value: None,
                     },
                 ],
             },
@@ -1438,6 +1456,7 @@ impl RpcExampleProvider {
                     stake_active_epoch: 245,
                     principal,
                     status: StakeStatus::Unstaked,
+                    fiat_value: None,
                 }],
             },
         ];
@@ -1468,6 +1487,7 @@ impl RpcExampleProvider {
                     status: StakeStatus::Active {
                         estimated_reward: (principal as f64 * 0.0026) as u64,
                     },
+                    fiat_value: None,
                 },
                 Stake {
                     staked_sui_id: stake2,
@@ -1475,6 +1495,7 @@ impl RpcExampleProvider {
                     stake_active_epoch: 245,
                     principal,
                     status: StakeStatus::Unstaked,
+                    fiat_value: None,
                 },
             ],
         };
@@ -1507,6 +1528,7 @@ impl RpcExampleProvider {
             data: vec!["example.sui".to_string()],
             next_cursor,
             has_next_page: false,
+            truncated: false,
         };
         Examples::new(
             "suix_resolveNameServiceNames",
@@ -1556,6 +1578,7 @@ impl RpcExampleProvider {
                 type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
                 bcs: None,
                 display: None,
+                labels: None,
             }),
             SuiPastObjectResponse::VersionFound(SuiObjectData {
                 content: Some(
@@ -1576,6 +1599,7 @@ impl RpcExampleProvider {
                 type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
                 bcs: None,
                 display: None,
+                labels: None,
             }),
         ];
 
@@ -7,17 +7,20 @@ use clap::ValueEnum;
 use pretty_assertions::assert_str_eq;
 use std::fs::File;
 use std::io::Write;
+use std::path::PathBuf;
 //temporarily remove api ref content for indexer methods
 //use sui_json_rpc::api::ExtendedApiOpenRpc;
 use sui_json_rpc::coin_api::CoinReadApi;
 use sui_json_rpc::governance_api::GovernanceReadApi;
 use sui_json_rpc::read_api::ReadApi;
+use sui_json_rpc::scheduled_transaction_api::ScheduledTransactionApi;
 use sui_json_rpc::sui_rpc_doc;
 use sui_json_rpc::transaction_builder_api::TransactionBuilderApi;
 use sui_json_rpc::transaction_execution_api::TransactionExecutionApi;
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_api::IndexerApiOpenRpc;
 use sui_json_rpc_api::MoveUtilsOpenRpc;
+use sui_open_rpc::load_recorded_examples;
 
 mod examples;
 
@@ -36,9 +39,14 @@ enum Action {
 struct Options {
     #[clap(value_enum, default_value = "Record", ignore_case = true)]
     action: Action,
+    /// Directory of examples recorded by integration tests (see `ExamplePairing::record`), to
+    /// include in the generated specs alongside the hand-written examples.
+    #[clap(long)]
+    recorded_examples: Option<PathBuf>,
 }
 
 const FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/spec/openrpc.json",);
+const OPENAPI_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/spec/openapi.json",);
 
 // TODO: This currently always use workspace version, which is not ideal.
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -52,13 +60,22 @@ async fn main() {
     open_rpc.add_module(CoinReadApi::rpc_doc_module());
     open_rpc.add_module(IndexerApiOpenRpc::module_doc());
     open_rpc.add_module(TransactionExecutionApi::rpc_doc_module());
+    open_rpc.add_module(ScheduledTransactionApi::rpc_doc_module());
     open_rpc.add_module(TransactionBuilderApi::rpc_doc_module());
     open_rpc.add_module(GovernanceReadApi::rpc_doc_module());
     //temporarily remove api ref content for indexer methods
     //open_rpc.add_module(ExtendedApiOpenRpc::module_doc());
     open_rpc.add_module(MoveUtilsOpenRpc::module_doc());
 
-    open_rpc.add_examples(RpcExampleProvider::new().examples());
+    let mut examples = RpcExampleProvider::new().examples();
+    if let Some(dir) = &options.recorded_examples {
+        for (method, recorded) in load_recorded_examples(dir).unwrap() {
+            examples.entry(method).or_default().extend(recorded);
+        }
+    }
+    open_rpc.add_examples(examples);
+
+    let open_api = open_rpc.openapi();
 
     match options.action {
         Action::Print => {
@@ -69,11 +86,19 @@ async fn main() {
             let content = serde_json::to_string_pretty(&open_rpc).unwrap();
             let mut f = File::create(FILE_PATH).unwrap();
             writeln!(f, "{content}").unwrap();
+
+            let content = serde_json::to_string_pretty(&open_api).unwrap();
+            let mut f = File::create(OPENAPI_FILE_PATH).unwrap();
+            writeln!(f, "{content}").unwrap();
         }
         Action::Test => {
             let reference = std::fs::read_to_string(FILE_PATH).unwrap();
             let content = serde_json::to_string_pretty(&open_rpc).unwrap() + "\n";
             assert_str_eq!(&reference, &content);
+
+            let reference = std::fs::read_to_string(OPENAPI_FILE_PATH).unwrap();
+            let content = serde_json::to_string_pretty(&open_api).unwrap() + "\n";
+            assert_str_eq!(&reference, &content);
         }
     }
 }
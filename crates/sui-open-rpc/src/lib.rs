@@ -5,6 +5,10 @@ extern crate core;
 
 use std::collections::btree_map::Entry::Occupied;
 use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use schemars::gen::{SchemaGenerator, SchemaSettings};
 use schemars::schema::SchemaObject;
@@ -13,6 +17,13 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use versions::Versioning;
 
+mod openapi;
+
+/// Environment variable naming a directory to record examples into, using
+/// [`ExamplePairing::record`]. Integration tests use this to capture real requests and responses,
+/// which the spec generator can then include alongside its hand-written examples.
+pub const RECORD_EXAMPLES_DIR_ENV: &str = "SUI_RPC_RECORD_EXAMPLES_DIR";
+
 /// OPEN-RPC documentation following the OpenRPC specification <https://spec.open-rpc.org>
 /// The implementation is partial, only required fields and subset of optional fields
 /// in the specification are implemented catered to Sui's need.
@@ -77,6 +88,14 @@ impl Project {
         self.method_routing.extend(module.method_routing);
     }
 
+    /// Names of the methods that are marked as deprecated.
+    pub fn deprecated_methods(&self) -> impl Iterator<Item = &str> {
+        self.methods
+            .iter()
+            .filter(|m| m.deprecated)
+            .map(|m| m.name.as_str())
+    }
+
     pub fn add_examples(&mut self, mut example_provider: BTreeMap<String, Vec<ExamplePairing>>) {
         for method in &mut self.methods {
             if let Occupied(entry) = example_provider.entry(method.name.clone()) {
@@ -114,6 +133,24 @@ pub struct Module {
     method_routing: BTreeMap<String, MethodRouting>,
 }
 
+impl Module {
+    /// The JSON schema of `method`'s result, if the module has the method and it returns a
+    /// result. The schema refers to the module's [`schemas`](Self::schemas) by name.
+    pub fn result_schema(&self, method: &str) -> Option<Value> {
+        let method = self.methods.iter().find(|m| m.name == method)?;
+        serde_json::to_value(&method.result.as_ref()?.schema).ok()
+    }
+
+    /// The JSON schemas of the types used by the module's methods, referred to as
+    /// `#/components/schemas/<name>`.
+    pub fn schemas(&self) -> impl Iterator<Item = (&str, Value)> + '_ {
+        self.components
+            .schemas
+            .iter()
+            .map(|(name, schema)| (name.as_str(), serde_json::json!(schema)))
+    }
+}
+
 pub struct RpcModuleDocBuilder {
     schema_generator: SchemaGenerator,
     methods: BTreeMap<String, Method>,
@@ -236,6 +273,83 @@ impl ExamplePairing {
             },
         }
     }
+
+    /// Record this pairing as an example of `method` in the directory named by
+    /// [`RECORD_EXAMPLES_DIR_ENV`], if it is set. Each example is written to its own file, so
+    /// tests running concurrently can record examples for the same method.
+    pub fn record(&self, method: &str) -> io::Result<()> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let Some(dir) = std::env::var_os(RECORD_EXAMPLES_DIR_ENV) else {
+            return Ok(());
+        };
+
+        let dir = Path::new(&dir).join(method);
+        fs::create_dir_all(&dir)?;
+
+        let file = dir.join(format!(
+            "{}-{}.json",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+        fs::write(file, serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// Load examples previously recorded into `dir` by [`ExamplePairing::record`], keyed by method
+/// name. Examples for each method are ordered by the name of the file they were recorded in.
+pub fn load_recorded_examples(dir: &Path) -> io::Result<BTreeMap<String, Vec<ExamplePairing>>> {
+    let mut examples = BTreeMap::new();
+    for method_dir in fs::read_dir(dir)? {
+        let method_dir = method_dir?;
+        if !method_dir.file_type()?.is_dir() {
+            continue;
+        }
+
+        let method = method_dir.file_name().to_string_lossy().into_owned();
+        let mut files = fs::read_dir(method_dir.path())?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        files.sort();
+
+        let pairings: &mut Vec<ExamplePairing> = examples.entry(method).or_default();
+        for file in files {
+            let pairing = serde_json::from_slice(&fs::read(&file)?)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            pairings.push(pairing);
+        }
+    }
+
+    Ok(examples)
+}
+
+#[test]
+fn test_load_recorded_examples() {
+    let dir = std::env::temp_dir().join(format!("sui-open-rpc-examples-{}", std::process::id()));
+    let method_dir = dir.join("sui_getVersion");
+    fs::create_dir_all(&method_dir).unwrap();
+    // Recorded out of order, to be loaded in order of their file names.
+    for (file, version) in [("2.json", 2), ("1.json", 1)] {
+        let pairing = ExamplePairing::new(
+            "Recorded",
+            vec![("object_id", serde_json::json!("0x5"))],
+            serde_json::json!(version),
+        );
+        fs::write(method_dir.join(file), serde_json::to_vec(&pairing).unwrap()).unwrap();
+    }
+    // Files next to the methods' directories are not examples.
+    fs::write(dir.join("README"), "").unwrap();
+
+    let examples = load_recorded_examples(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+    let examples = examples.unwrap();
+
+    assert_eq!(examples.keys().collect::<Vec<_>>(), ["sui_getVersion"]);
+    let results: Vec<_> = examples["sui_getVersion"]
+        .iter()
+        .map(|pairing| pairing.result.value.clone())
+        .collect();
+    assert_eq!(results, [serde_json::json!(1), serde_json::json!(2)]);
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -0,0 +1,309 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Translation of the OpenRPC document into an OpenAPI 3.1 document, for consumption by API
+//! gateways and client generators that do not understand OpenRPC.
+//!
+//! JSON-RPC multiplexes all methods over a single HTTP endpoint, whereas OpenAPI identifies
+//! operations by path, so every method is described as a `POST` to the path `/#<method>`: the
+//! fragment is never sent to the server, so these all resolve to the root of the RPC server.
+//! Request and response bodies are described as JSON-RPC 2.0 envelopes around the method's
+//! parameters and result, and the method's examples are re-expressed as complete envelopes.
+
+use serde_json::{json, Map, Value};
+
+use crate::{ContentDescriptor, ExamplePairing, Method, Project};
+
+const OPENAPI_VERSION: &str = "3.1.0";
+
+/// Name of the schema, added to the OpenAPI document's components, describing JSON-RPC errors.
+const JSON_RPC_ERROR: &str = "JsonRpcError";
+
+impl Project {
+    /// Describe this project's methods as an OpenAPI 3.1 document. Subscriptions are omitted,
+    /// because they are only available over websockets.
+    pub fn openapi(&self) -> Value {
+        let paths: Map<String, Value> = self
+            .methods
+            .iter()
+            .filter(|method| !method.is_subscription())
+            .map(|method| {
+                (
+                    format!("/#{}", method.name),
+                    json!({ "post": operation(method) }),
+                )
+            })
+            .collect();
+
+        let mut schemas: Map<String, Value> = self
+            .components
+            .schemas
+            .iter()
+            .map(|(name, schema)| (name.clone(), json!(schema)))
+            .collect();
+        schemas.insert(JSON_RPC_ERROR.to_string(), json_rpc_error_schema());
+
+        json!({
+            "openapi": OPENAPI_VERSION,
+            "info": self.info,
+            "paths": paths,
+            "components": {
+                "schemas": schemas,
+            },
+        })
+    }
+}
+
+impl Method {
+    fn is_subscription(&self) -> bool {
+        self.tags.iter().any(|tag| tag.name == "PubSub")
+    }
+}
+
+fn operation(method: &Method) -> Value {
+    let mut op = Map::new();
+    op.insert("operationId".to_string(), json!(method.name));
+    if let Some(description) = &method.description {
+        op.insert("description".to_string(), json!(description));
+    }
+    if !method.tags.is_empty() {
+        let tags: Vec<_> = method.tags.iter().map(|tag| &tag.name).collect();
+        op.insert("tags".to_string(), json!(tags));
+    }
+    if method.deprecated {
+        op.insert("deprecated".to_string(), json!(true));
+    }
+
+    let params: Vec<_> = method.params.iter().map(param_schema).collect();
+    let mut request = json!({
+        "schema": {
+            "type": "object",
+            "required": ["jsonrpc", "id", "method"],
+            "properties": {
+                "jsonrpc": { "const": "2.0" },
+                "id": { "type": ["integer", "string"] },
+                "method": { "const": method.name },
+                "params": {
+                    "type": "array",
+                    "prefixItems": params,
+                },
+            },
+        },
+    });
+
+    let mut response_properties = Map::new();
+    response_properties.insert("jsonrpc".to_string(), json!({ "const": "2.0" }));
+    response_properties.insert(
+        "id".to_string(),
+        json!({ "type": ["integer", "string", "null"] }),
+    );
+    if let Some(result) = &method.result {
+        response_properties.insert("result".to_string(), param_schema(result));
+    }
+    response_properties.insert(
+        "error".to_string(),
+        json!({ "$ref": format!("#/components/schemas/{JSON_RPC_ERROR}") }),
+    );
+    let mut response = json!({
+        "schema": {
+            "type": "object",
+            "required": ["jsonrpc", "id"],
+            "properties": response_properties,
+        },
+    });
+
+    if !method.examples.is_empty() {
+        let (requests, responses) = examples(&method.name, &method.examples);
+        request["examples"] = requests;
+        response["examples"] = responses;
+    }
+
+    op.insert(
+        "requestBody".to_string(),
+        json!({
+            "required": true,
+            "content": { "application/json": request },
+        }),
+    );
+    op.insert(
+        "responses".to_string(),
+        json!({
+            "200": {
+                "description": "JSON-RPC response, containing either the result or an error.",
+                "content": { "application/json": response },
+            },
+        }),
+    );
+
+    Value::Object(op)
+}
+
+/// The schema of a parameter or result, annotated with its description.
+fn param_schema(descriptor: &ContentDescriptor) -> Value {
+    let mut schema = json!(descriptor.schema);
+    if let (Some(description), Value::Object(fields)) = (&descriptor.description, &mut schema) {
+        fields.insert("description".to_string(), json!(description));
+    }
+    if descriptor.deprecated {
+        if let Value::Object(fields) = &mut schema {
+            fields.insert("deprecated".to_string(), json!(true));
+        }
+    }
+    schema
+}
+
+/// Re-express `pairings` as OpenAPI example objects for the request and response bodies, keyed
+/// by their position in the method's list of examples.
+fn examples(method: &str, pairings: &[ExamplePairing]) -> (Value, Value) {
+    let mut requests = Map::new();
+    let mut responses = Map::new();
+
+    for (i, pairing) in pairings.iter().enumerate() {
+        let key = format!("example{}", i + 1);
+        let params: Vec<_> = pairing.params.iter().map(|p| &p.value).collect();
+
+        requests.insert(
+            key.clone(),
+            json!({
+                "summary": pairing.name,
+                "value": {
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": method,
+                    "params": params,
+                },
+            }),
+        );
+
+        responses.insert(
+            key,
+            json!({
+                "summary": pairing.name,
+                "value": {
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": pairing.result.value,
+                },
+            }),
+        );
+    }
+
+    (Value::Object(requests), Value::Object(responses))
+}
+
+fn json_rpc_error_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["code", "message"],
+        "properties": {
+            "code": { "type": "integer" },
+            "message": { "type": "string" },
+            "data": {},
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{ExamplePairing, Project, RpcModuleDocBuilder};
+
+    fn project() -> Project {
+        let mut builder = RpcModuleDocBuilder::default();
+        let id = builder.create_content_descriptor::<String>(
+            "object_id",
+            None,
+            Some("The ID of the object".to_string()),
+            true,
+        );
+        let result = builder.create_content_descriptor::<u64>("Version", None, None, true);
+        builder.add_method(
+            "sui",
+            "getVersion",
+            vec![id.clone()],
+            Some(result.clone()),
+            "Return the version of an object.",
+            Some("Read API".to_string()),
+            false,
+        );
+        builder.add_subscription(
+            "suix",
+            "subscribeVersion",
+            vec![id],
+            Some(result),
+            "Subscribe to the versions of an object.",
+            None,
+            false,
+        );
+
+        let mut project = Project::new("1.0.0", "Test", "", "", "", "", "", "");
+        project.add_module(builder.build());
+        project.add_examples(
+            [(
+                "sui_getVersion".to_string(),
+                vec![ExamplePairing::new(
+                    "Gets the version of an object",
+                    vec![("object_id", json!("0x5"))],
+                    json!(42),
+                )],
+            )]
+            .into(),
+        );
+        project
+    }
+
+    #[test]
+    fn test_openapi_operations() {
+        let spec = project().openapi();
+        assert_eq!(spec["openapi"], "3.1.0");
+
+        // Subscriptions are only served over websockets, so they have no operation.
+        let paths = spec["paths"].as_object().unwrap();
+        assert_eq!(paths.keys().collect::<Vec<_>>(), ["/#sui_getVersion"]);
+
+        let op = &paths["/#sui_getVersion"]["post"];
+        assert_eq!(op["operationId"], "sui_getVersion");
+        assert_eq!(op["tags"], json!(["Read API"]));
+
+        let request = &op["requestBody"]["content"]["application/json"];
+        let properties = &request["schema"]["properties"];
+        assert_eq!(properties["method"], json!({ "const": "sui_getVersion" }));
+        let params = properties["params"]["prefixItems"].as_array().unwrap();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0]["description"], "The ID of the object");
+
+        let response = &op["responses"]["200"]["content"]["application/json"];
+        assert_eq!(
+            response["schema"]["properties"]["error"]["$ref"],
+            "#/components/schemas/JsonRpcError"
+        );
+        assert!(spec["components"]["schemas"]["JsonRpcError"].is_object());
+    }
+
+    #[test]
+    fn test_openapi_examples_are_envelopes() {
+        let spec = project().openapi();
+        let op = &spec["paths"]["/#sui_getVersion"]["post"];
+
+        assert_eq!(
+            op["requestBody"]["content"]["application/json"]["examples"]["example1"],
+            json!({
+                "summary": "Gets the version of an object",
+                "value": {
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "sui_getVersion",
+                    "params": ["0x5"],
+                },
+            })
+        );
+        assert_eq!(
+            op["responses"]["200"]["content"]["application/json"]["examples"]["example1"],
+            json!({
+                "summary": "Gets the version of an object",
+                "value": { "jsonrpc": "2.0", "id": 1, "result": 42 },
+            })
+        );
+    }
+}
@@ -68,6 +68,8 @@ async fn main() -> Result<(), anyhow::Error> {
         show_content: true,
         show_bcs: true,
         show_storage_rebate: true,
+        show_labels: false,
+        fields: None,
     };
 
     let past_object = sui
@@ -126,6 +128,8 @@ async fn main() -> Result<(), anyhow::Error> {
                 show_object_changes: true,
                 show_balance_changes: true,
                 show_raw_effects: true,
+                show_labels: false,
+                fields: None,
             },
         )
         .await?;
@@ -20,13 +20,14 @@ use sui_json_rpc_api::{
     WriteApiClient,
 };
 use sui_json_rpc_types::{
-    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake, DevInspectResults,
-    DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter, EventPage, ObjectsPage,
-    ProtocolConfigResponse, SuiCoinMetadata, SuiCommittee, SuiEvent, SuiGetPastObjectRequest,
-    SuiMoveNormalizedModule, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery,
-    SuiPastObjectResponse, SuiTransactionBlockEffects, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
-    TransactionFilter,
+    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake, DelegatedStakePage,
+    DelegatedStakeResponse, DevInspectResults, DryRunTransactionBlockResponse, DynamicFieldPage,
+    EventFilter, EventPage, ObjectsPage, ProtocolConfigResponse, StakeRewardEstimate,
+    SuiCoinMetadata, SuiCommittee, SuiEvent, SuiGetPastObjectRequest, SuiMoveNormalizedModule,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiPastObjectResponse,
+    SuiTransactionBlockEffects, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockResponseQuery, TransactionBlocksPage, TransactionFilter,
+    ValidatorSetChanges,
 };
 use sui_json_rpc_types::{CheckpointPage, SuiLoadedChildObjectsResponse};
 use sui_types::balance::Supply;
@@ -84,7 +85,7 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .get_owned_objects(address, query, cursor, limit)
+            .get_owned_objects(address, query, cursor, limit, None)
             .await?)
     }
 
@@ -200,6 +201,8 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 show_labels: false,
+    ///                 fields: None,
     ///             },
     ///         )
     ///         .await?;
@@ -263,6 +266,8 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 show_labels: false,
+    ///                 fields: None,
     ///             },
     ///         )
     ///         .await?;
@@ -282,6 +287,8 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 show_labels: false,
+    ///                 fields: None,
     ///             },
     ///         )
     ///         .await?;
@@ -341,6 +348,8 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 show_labels: false,
+    ///                 fields: None,
     ///             },
     ///         ).await?;
     ///     Ok(())
@@ -393,6 +402,8 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 show_labels: false,
+    ///                 fields: None,
     ///             },
     ///         ).await?;
     ///     Ok(())
@@ -517,7 +528,7 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .query_transaction_blocks(query, cursor, limit, Some(descending_order))
+            .query_transaction_blocks(query, cursor, limit, Some(descending_order), None)
             .await?)
     }
 
@@ -1071,7 +1082,7 @@ impl EventApi {
         Ok(self
             .api
             .http
-            .query_events(query, cursor, limit, Some(descending_order))
+            .query_events(query, cursor, limit, Some(descending_order), None)
             .await?)
     }
 
@@ -1181,7 +1192,47 @@ impl GovernanceApi {
 
     /// Return a list of [DelegatedStake] objects for the given address, or an error upon failure.
     pub async fn get_stakes(&self, owner: SuiAddress) -> SuiRpcResult<Vec<DelegatedStake>> {
-        Ok(self.api.http.get_stakes(owner).await?)
+        Ok(self.api.http.get_stakes(owner, None).await?)
+    }
+
+    /// Return the [DelegatedStake] of each of the given StakedSui objects, in the same order, or
+    /// an error upon failure. IDs that don't resolve to a StakedSui object are reported as
+    /// `Unstaked` or `NotFound`, instead of failing the request.
+    pub async fn try_get_stakes_by_ids(
+        &self,
+        staked_sui_ids: Vec<ObjectID>,
+    ) -> SuiRpcResult<Vec<DelegatedStakeResponse>> {
+        Ok(self.api.http.try_get_stakes_by_ids(staked_sui_ids).await?)
+    }
+
+    /// Return what the given StakedSui object has earned so far, and is projected to have earned
+    /// if it's held until `target_epoch`, or an error upon failure.
+    pub async fn get_stake_reward_estimate(
+        &self,
+        staked_sui_id: ObjectID,
+        target_epoch: u64,
+    ) -> SuiRpcResult<StakeRewardEstimate> {
+        Ok(self
+            .api
+            .http
+            .get_stake_reward_estimate(staked_sui_id, target_epoch.into())
+            .await?)
+    }
+
+    /// Return a page of [DelegatedStake] objects for the given address, starting after the
+    /// StakedSui object `cursor`, or an error upon failure. The stakes on a page are grouped by
+    /// staking pool, so a pool can appear on more than one page.
+    pub async fn get_stakes_paginated(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> SuiRpcResult<DelegatedStakePage> {
+        Ok(self
+            .api
+            .http
+            .get_stakes_paginated(owner, cursor, limit)
+            .await?)
     }
 
     /// Return the [SuiCommittee] information for the given `epoch`, or an error upon failure.
@@ -1216,11 +1267,28 @@ impl GovernanceApi {
     /// the protocol version, the reference gas price, the total stake, active validators,
     /// and much more. See the [SuiSystemStateSummary] for all the available fields.
     pub async fn get_latest_sui_system_state(&self) -> SuiRpcResult<SuiSystemStateSummary> {
-        Ok(self.api.http.get_latest_sui_system_state().await?)
+        Ok(self.api.http.get_latest_sui_system_state(None).await?)
     }
 
     /// Return the reference gas price for the network, or an error upon failure.
     pub async fn get_reference_gas_price(&self) -> SuiRpcResult<u64> {
         Ok(*self.api.http.get_reference_gas_price().await?)
     }
+
+    /// Subscribe to a stream of the changes to the active validator set, sent at the start of
+    /// each epoch.
+    ///
+    /// This is only available through WebSockets.
+    pub async fn subscribe_validator_set_changes(
+        &self,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<ValidatorSetChanges>>> {
+        let Some(c) = &self.api.ws else {
+            return Err(Error::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            ));
+        };
+        let subscription: Subscription<ValidatorSetChanges> =
+            c.subscribe_validator_set_changes().await?;
+        Ok(subscription.map(|item| Ok(item?)))
+    }
 }
@@ -102,6 +102,7 @@ use crate::error::{Error, SuiRpcResult};
 pub mod apis;
 pub mod error;
 pub mod json_rpc_error;
+pub mod session;
 pub mod sui_client_config;
 pub mod wallet_context;
 
@@ -538,6 +539,7 @@ impl DataReader for ReadApi {
                 data,
                 next_cursor,
                 has_next_page,
+                ..
             } = self
                 .get_owned_objects(address, query.clone(), cursor, None)
                 .await?;
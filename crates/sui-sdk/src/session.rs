@@ -0,0 +1,238 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Session keys are ephemeral keys that an account authorizes to spend a capped amount of a coin
+//! on its behalf, so that an app such as a game can sign transactions for its player without
+//! prompting their wallet each time.
+//!
+//! Authorizations are held on-chain, by a shared `Session` object of the `utils::session` Move
+//! module (see `sui_programmability/examples/utils`), which the app publishes. The account funds
+//! the session and allowlists the key on it with a spending limit and an expiry epoch, and the
+//! key spends from the session's balance. The module enforces the limits, and [SessionKey] also
+//! checks them before building a transaction, so that the app learns it is over its limits
+//! without paying for a failed transaction.
+//!
+//! ```rust,no_run
+//! use sui_sdk::session::{SessionKey, SessionLimits};
+//! use sui_sdk::types::base_types::{ObjectID, ObjectRef, SuiAddress};
+//! use sui_sdk::types::gas_coin::GAS;
+//!
+//! fn open(package: ObjectID, owner: SuiAddress, funds: ObjectRef, gas: ObjectRef) -> anyhow::Result<()> {
+//!     let key = SessionKey::generate(
+//!         package,
+//!         GAS::type_tag(),
+//!         SessionLimits { spend_limit: 1_000_000_000, expires_at_epoch: 100 },
+//!     );
+//!     // Signed and executed by the owner, this shares a session funded with `funds`, and sends
+//!     // the key some SUI to pay for gas with.
+//!     let _data = key.authorization_transaction(owner, funds, gas, 10_000_000, 50_000_000, 1000)?;
+//!     Ok(())
+//! }
+//! ```
+
+use anyhow::{anyhow, ensure};
+use fastcrypto::ed25519::Ed25519KeyPair;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::TypeTag;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::committee::EpochId;
+use sui_types::crypto::{get_key_pair, SuiKeyPair};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{
+    Argument, ObjectArg, Transaction, TransactionData, TransactionDataAPI,
+};
+
+const SESSION_MODULE: &str = "session";
+
+/// What a session key is allowed to spend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionLimits {
+    /// The most the key can spend in total, in the coin's smallest unit.
+    pub spend_limit: u64,
+    /// The last epoch the key can spend in.
+    pub expires_at_epoch: EpochId,
+}
+
+/// An ephemeral key, and the limits it is (or will be) authorized to spend from a session with.
+pub struct SessionKey {
+    keypair: SuiKeyPair,
+    package: ObjectID,
+    coin_type: TypeTag,
+    limits: SessionLimits,
+    spent: u64,
+}
+
+impl SessionKey {
+    /// A fresh key for sessions of `coin_type` created by the `session` module published at
+    /// `package`.
+    pub fn generate(package: ObjectID, coin_type: TypeTag, limits: SessionLimits) -> Self {
+        let (_, keypair): (_, Ed25519KeyPair) = get_key_pair();
+        Self {
+            keypair: SuiKeyPair::Ed25519(keypair),
+            package,
+            coin_type,
+            limits,
+            spent: 0,
+        }
+    }
+
+    pub fn address(&self) -> SuiAddress {
+        (&self.keypair.public()).into()
+    }
+
+    pub fn limits(&self) -> &SessionLimits {
+        &self.limits
+    }
+
+    /// How much more the key can spend, going by the spending it has built transactions for.
+    pub fn remaining(&self) -> u64 {
+        self.limits.spend_limit - self.spent
+    }
+
+    /// Add commands to `builder` that create a session funded with `funds`, authorize this key
+    /// on it, and share it. The commands also send the key `gas_allowance` MIST from the gas coin
+    /// to pay for its own transactions with. Must be signed by the owner of `funds`.
+    pub fn open_session(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        funds: ObjectRef,
+        gas_allowance: u64,
+    ) -> anyhow::Result<()> {
+        let funds = builder.obj(ObjectArg::ImmOrOwnedObject(funds))?;
+        let session = self.call(builder, "new", vec![funds]);
+        self.authorize_arg(builder, session)?;
+        self.call(builder, "share", vec![session]);
+        if gas_allowance > 0 {
+            builder.transfer_sui(self.address(), Some(gas_allowance));
+        }
+        Ok(())
+    }
+
+    /// Add commands to `builder` that authorize this key on an existing shared `session`,
+    /// replacing any allowance it had, and send it `gas_allowance` MIST from the gas coin. Must be
+    /// signed by the session's owner.
+    pub fn authorize(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        session: ObjectArg,
+        gas_allowance: u64,
+    ) -> anyhow::Result<()> {
+        let session = builder.obj(session)?;
+        self.authorize_arg(builder, session)?;
+        if gas_allowance > 0 {
+            builder.transfer_sui(self.address(), Some(gas_allowance));
+        }
+        Ok(())
+    }
+
+    /// Add a command to `builder` that revokes this key's authorization on `session`. Must be
+    /// signed by the session's owner.
+    pub fn revoke(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        session: ObjectArg,
+    ) -> anyhow::Result<()> {
+        let session = builder.obj(session)?;
+        let key = builder.pure(self.address())?;
+        self.call(builder, "revoke", vec![session, key]);
+        Ok(())
+    }
+
+    /// Add a command to `builder` that spends `amount` from `session`, returning the coin spent.
+    /// Fails without adding any commands if the key has expired by `current_epoch`, or if
+    /// `amount` is more than it has left to spend. Must be signed by this key.
+    ///
+    /// The amount counts against the key's limit from when the command is built, as the key
+    /// can't tell whether the transaction will be executed.
+    pub fn spend(
+        &mut self,
+        builder: &mut ProgrammableTransactionBuilder,
+        session: ObjectArg,
+        amount: u64,
+        current_epoch: EpochId,
+    ) -> anyhow::Result<Argument> {
+        ensure!(
+            current_epoch <= self.limits.expires_at_epoch,
+            "Session key {} expired at the end of epoch {}",
+            self.address(),
+            self.limits.expires_at_epoch
+        );
+        ensure!(
+            amount <= self.remaining(),
+            "Session key {} can't spend {amount}, it has {} left to spend",
+            self.address(),
+            self.remaining()
+        );
+
+        let session = builder.obj(session)?;
+        let amount_arg = builder.pure(amount)?;
+        let coin = self.call(builder, "spend", vec![session, amount_arg]);
+        self.spent += amount;
+        Ok(coin)
+    }
+
+    /// The transaction opening a session for this key, as [Self::open_session], with gas paid
+    /// by `owner` from `gas`.
+    pub fn authorization_transaction(
+        &self,
+        owner: SuiAddress,
+        funds: ObjectRef,
+        gas: ObjectRef,
+        gas_allowance: u64,
+        gas_budget: u64,
+        gas_price: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        self.open_session(&mut builder, funds, gas_allowance)?;
+        Ok(TransactionData::new_programmable(
+            owner,
+            vec![gas],
+            builder.finish(),
+            gas_budget,
+            gas_price,
+        ))
+    }
+
+    /// Sign `data`, which must be sent by this key.
+    pub fn sign(&self, data: TransactionData) -> anyhow::Result<Transaction> {
+        if data.sender() != self.address() {
+            return Err(anyhow!(
+                "Session key {} can't sign a transaction sent by {}",
+                self.address(),
+                data.sender()
+            ));
+        }
+        Ok(Transaction::from_data_and_signer(data, vec![&self.keypair]))
+    }
+
+    fn authorize_arg(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        session: Argument,
+    ) -> anyhow::Result<()> {
+        let key = builder.pure(self.address())?;
+        let spend_limit = builder.pure(self.limits.spend_limit)?;
+        let expires_at_epoch = builder.pure(self.limits.expires_at_epoch)?;
+        self.call(
+            builder,
+            "authorize",
+            vec![session, key, spend_limit, expires_at_epoch],
+        );
+        Ok(())
+    }
+
+    fn call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        function: &str,
+        arguments: Vec<Argument>,
+    ) -> Argument {
+        builder.programmable_move_call(
+            self.package,
+            Identifier::new(SESSION_MODULE).unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![self.coin_type.clone()],
+            arguments,
+        )
+    }
+}
@@ -3,8 +3,13 @@
 use tempfile::TempDir;
 
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_sdk::session::{SessionKey, SessionLimits};
+use sui_types::base_types::{random_object_ref, ObjectID};
 use sui_types::crypto::Ed25519SuiSignature;
 use sui_types::crypto::{SignatureScheme, SuiSignatureInner};
+use sui_types::gas_coin::GAS;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{ObjectArg, TransactionData};
 #[test]
 fn mnemonic_test() {
     let temp_dir = TempDir::new().unwrap();
@@ -32,3 +37,38 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn session_key_limits_test() -> Result<(), anyhow::Error> {
+    let mut key = SessionKey::generate(
+        ObjectID::random(),
+        GAS::type_tag(),
+        SessionLimits {
+            spend_limit: 100,
+            expires_at_epoch: 5,
+        },
+    );
+    let session = ObjectArg::SharedObject {
+        id: ObjectID::random(),
+        initial_shared_version: 1.into(),
+        mutable: true,
+    };
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    key.spend(&mut builder, session, 60, 5)?;
+    assert_eq!(key.remaining(), 40);
+    // Over the limit, or after the key expired.
+    assert!(key.spend(&mut builder, session, 60, 5).is_err());
+    assert!(key.spend(&mut builder, session, 10, 6).is_err());
+    assert_eq!(key.remaining(), 40);
+
+    let data = TransactionData::new_programmable(
+        key.address(),
+        vec![random_object_ref()],
+        builder.finish(),
+        1_000_000,
+        1000,
+    );
+    key.sign(data)?;
+    Ok(())
+}
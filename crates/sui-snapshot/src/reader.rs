@@ -367,38 +367,8 @@ impl StateSnapshotReaderV1 {
                         let remote_object_store = remote_object_store.clone();
                         let sha3_digests_cloned = sha3_digests.clone();
                         async move {
-                            // Download object file with retries
-                            let max_timeout = Duration::from_secs(30);
-                            let mut timeout = Duration::from_secs(2);
-                            timeout += timeout / 2;
-                            timeout = std::cmp::min(max_timeout, timeout);
-                            let mut attempts = 0usize;
-                            let bytes = loop {
-                                match remote_object_store.get_bytes(&file_path).await {
-                                    Ok(bytes) => {
-                                        break bytes;
-                                    }
-                                    Err(err) => {
-                                        error!(
-                                            "Obj {} .get failed (attempt {}): {}",
-                                            file_metadata.file_path(&epoch_dir),
-                                            attempts,
-                                            err,
-                                        );
-                                        if timeout > max_timeout {
-                                            panic!(
-                                                "Failed to get obj file after {} attempts",
-                                                attempts
-                                            );
-                                        } else {
-                                            attempts += 1;
-                                            tokio::time::sleep(timeout).await;
-                                            timeout += timeout / 2;
-                                            continue;
-                                        }
-                                    }
-                                }
-                            };
+                            let bytes =
+                                download_object_file(&remote_object_store, &file_path).await;
 
                             let sha3_digest = sha3_digests_cloned.lock().await;
                             let bucket_map = sha3_digest
@@ -448,6 +418,51 @@ impl StateSnapshotReaderV1 {
         ret
     }
 
+    /// Download every object file in the snapshot, and pass each live object in it to `f`,
+    /// without restoring them into a database. Objects are passed in no particular order.
+    pub async fn for_each_live_object<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(LiveObject) + Sync,
+    {
+        let epoch_dir = self.epoch_dir();
+        let remote_object_store = self.remote_object_store.clone();
+        let input_files: Vec<_> = self
+            .object_files
+            .values()
+            .flat_map(|parts| parts.values().cloned())
+            .collect();
+        let obj_progress_bar = self.m.add(
+            ProgressBar::new(input_files.len() as u64).with_style(
+                ProgressStyle::with_template(
+                    "[{elapsed_precise}] {wide_bar} {pos} out of {len} .obj files scanned",
+                )
+                .unwrap(),
+            ),
+        );
+
+        futures::stream::iter(input_files)
+            .map(|file_metadata| {
+                let file_path = file_metadata.file_path(&epoch_dir);
+                let remote_object_store = remote_object_store.clone();
+                async move {
+                    let bytes = download_object_file(&remote_object_store, &file_path).await;
+                    Ok::<_, anyhow::Error>((bytes, file_metadata))
+                }
+            })
+            .boxed()
+            .buffer_unordered(self.concurrency)
+            .try_for_each(|(bytes, file_metadata)| {
+                let result = LiveObjectIter::new(&file_metadata, bytes).map(|obj_iter| {
+                    obj_iter.for_each(&f);
+                });
+                obj_progress_bar.inc(1);
+                futures::future::ready(result)
+            })
+            .await?;
+        obj_progress_bar.finish_with_message("Objects scan complete");
+        Ok(())
+    }
+
     pub fn ref_iter(&self, bucket_num: u32, part_num: u32) -> Result<ObjectRefIter> {
         let file_metadata = self
             .ref_files
@@ -548,6 +563,36 @@ impl Iterator for ObjectRefIter {
     }
 }
 
+/// Download an object file, retrying with backoff. Panics if the file still cannot be downloaded
+/// after several attempts.
+async fn download_object_file(
+    remote_object_store: &Arc<dyn ObjectStoreGetExt>,
+    file_path: &Path,
+) -> Bytes {
+    let max_timeout = Duration::from_secs(30);
+    let mut timeout = Duration::from_secs(2);
+    timeout += timeout / 2;
+    timeout = std::cmp::min(max_timeout, timeout);
+    let mut attempts = 0usize;
+    loop {
+        match remote_object_store.get_bytes(file_path).await {
+            Ok(bytes) => return bytes,
+            Err(err) => {
+                error!(
+                    "Obj {} .get failed (attempt {}): {}",
+                    file_path, attempts, err,
+                );
+                if timeout > max_timeout {
+                    panic!("Failed to get obj file after {} attempts", attempts);
+                }
+                attempts += 1;
+                tokio::time::sleep(timeout).await;
+                timeout += timeout / 2;
+            }
+        }
+    }
+}
+
 /// An iterator over all objects in a *.obj file.
 pub struct LiveObjectIter {
     reader: Box<dyn Read>,
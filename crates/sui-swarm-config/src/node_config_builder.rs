@@ -154,10 +154,14 @@ impl ValidatorConfigBuilder {
             db_path,
             network_address,
             metrics_address: validator.metrics_address,
+            metrics_additional_addresses: vec![],
             admin_interface_port: local_ip_utils::get_available_port(&localhost),
             json_rpc_address: local_ip_utils::new_tcp_address_for_testing(&localhost)
                 .to_socket_addr()
                 .unwrap(),
+            json_rpc_additional_addresses: vec![],
+            json_rpc_tls: None,
+            json_rpc_unix_socket: None,
             consensus_config: Some(consensus_config),
             enable_event_processing: false,
             enable_index_processing: default_enable_index_processing(),
@@ -195,6 +199,8 @@ impl ValidatorConfigBuilder {
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
             authority_overload_config: self.authority_overload_config.unwrap_or_default(),
             run_with_range: None,
+            scheduled_transactions: None,
+            crash_reports: None,
         }
     }
 
@@ -405,10 +411,14 @@ impl FullnodeConfigBuilder {
             metrics_address: self
                 .metrics_address
                 .unwrap_or(local_ip_utils::new_local_tcp_socket_for_testing()),
+            metrics_additional_addresses: vec![],
             admin_interface_port: self
                 .admin_interface_port
                 .unwrap_or(local_ip_utils::get_available_port(&localhost)),
             json_rpc_address: self.json_rpc_address.unwrap_or(json_rpc_address),
+            json_rpc_additional_addresses: vec![],
+            json_rpc_tls: None,
+            json_rpc_unix_socket: None,
             consensus_config: None,
             enable_event_processing: true, // This is unused.
             enable_index_processing: default_enable_index_processing(),
@@ -447,6 +457,8 @@ impl FullnodeConfigBuilder {
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
             authority_overload_config: Default::default(),
             run_with_range: self.run_with_range,
+            scheduled_transactions: None,
+            crash_reports: None,
         }
     }
 }
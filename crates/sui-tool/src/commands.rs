@@ -5,9 +5,12 @@ use crate::{
     check_completed_snapshot,
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
     download_db_snapshot, download_formal_snapshot, dump_checkpoints_from_archive,
+    fetch_checkpoints::fetch_checkpoints,
     get_latest_available_epoch, get_object, get_transaction_block, make_clients, pkg_dump,
-    restore_from_db_checkpoint, state_sync_from_archive, verify_archive,
-    verify_archive_by_checksum, ConciseObjectOutput, GroupedObjectOutput, VerboseObjectOutput,
+    restore_from_db_checkpoint,
+    state_diff::epoch_state_diff,
+    state_sync_from_archive, verify_archive, verify_archive_by_checksum, ConciseObjectOutput,
+    GroupedObjectOutput, VerboseObjectOutput,
 };
 use anyhow::Result;
 use std::env;
@@ -184,6 +187,54 @@ pub enum ToolCommand {
         max_content_length: usize,
     },
 
+    /// Download checkpoints in the range [from, to] from the archive store, and write them to a
+    /// local directory that data ingestion workers can read from, as one `<seq>.chk` file per
+    /// checkpoint. The archive holds no events or objects: these are fetched from a fullnode if
+    /// --fullnode-rpc-url is set, and left empty otherwise.
+    #[command(name = "fetch-checkpoints")]
+    FetchCheckpoints {
+        /// Genesis to start verifying checkpoint signatures from. Required with --verify.
+        #[arg(long = "genesis")]
+        genesis: Option<PathBuf>,
+        #[command(flatten)]
+        object_store_config: ObjectStoreConfig,
+        #[arg(long = "from")]
+        from: CheckpointSequenceNumber,
+        #[arg(long = "to")]
+        to: CheckpointSequenceNumber,
+        /// Verify checkpoint signatures, starting from the committee in genesis. This requires
+        /// reading every checkpoint summary since genesis.
+        #[arg(long = "verify", requires = "genesis")]
+        verify: bool,
+        #[arg(long = "out")]
+        out: PathBuf,
+        /// Fullnode to read events and input and output objects from. It must not have pruned
+        /// the objects of the checkpoints fetched.
+        #[arg(long = "fullnode-rpc-url")]
+        fullnode_rpc_url: Option<String>,
+        #[arg(long = "download-concurrency", default_value_t = 5)]
+        download_concurrency: usize,
+    },
+
+    /// Diff the objects and balances owned by a set of addresses between the ends of two epochs,
+    /// read from the formal snapshots of those epochs, and print it as CSV.
+    #[command(name = "epoch-state-diff")]
+    EpochStateDiff {
+        #[command(flatten)]
+        object_store_config: ObjectStoreConfig,
+        #[arg(long = "from-epoch")]
+        from_epoch: EpochId,
+        #[arg(long = "to-epoch")]
+        to_epoch: EpochId,
+        #[arg(long = "address", required = true, num_args = 1..)]
+        addresses: Vec<SuiAddress>,
+        /// File to write the CSV to, instead of stdout.
+        #[arg(long = "out")]
+        out: Option<PathBuf>,
+        #[arg(long = "download-concurrency", default_value_t = 5)]
+        download_concurrency: usize,
+    },
+
     /// Download all packages to the local filesystem from an indexer database. Each package gets
     /// its own sub-directory, named for its ID on-chain, containing two metadata files
     /// (linkage.json and origins.json) as well as a file for every module it contains. Each module
@@ -963,6 +1014,49 @@ impl ToolCommand {
                 dump_checkpoints_from_archive(object_store_config, start, end, max_content_length)
                     .await?;
             }
+            ToolCommand::FetchCheckpoints {
+                genesis,
+                object_store_config,
+                from,
+                to,
+                verify,
+                out,
+                fullnode_rpc_url,
+                download_concurrency,
+            } => {
+                let genesis = genesis.filter(|_| verify);
+                fetch_checkpoints(
+                    genesis.as_deref(),
+                    object_store_config,
+                    from..to + 1,
+                    &out,
+                    fullnode_rpc_url,
+                    download_concurrency,
+                )
+                .await?;
+            }
+            ToolCommand::EpochStateDiff {
+                object_store_config,
+                from_epoch,
+                to_epoch,
+                addresses,
+                out,
+                download_concurrency,
+            } => {
+                let out: Box<dyn std::io::Write> = match out {
+                    Some(path) => Box::new(std::fs::File::create(path)?),
+                    None => Box::new(std::io::stdout()),
+                };
+                epoch_state_diff(
+                    object_store_config,
+                    from_epoch,
+                    to_epoch,
+                    &addresses,
+                    download_concurrency,
+                    out,
+                )
+                .await?;
+            }
             ToolCommand::SignTransaction {
                 genesis,
                 sender_signed_data,
@@ -0,0 +1,430 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Downloads a range of checkpoints from the archive into a local store that the data ingestion
+//! framework can read from: one `<seq>.chk` file per checkpoint, holding its BCS-encoded
+//! `CheckpointData`.
+//!
+//! The archive only holds checkpoint summaries, transactions and effects. Events and the input and
+//! output objects of each transaction are fetched from a fullnode if one is given, and are left
+//! empty otherwise.
+
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use futures::{StreamExt, TryStreamExt};
+use prometheus::Registry;
+use sui_archival::reader::{ArchiveReader, ArchiveReaderMetrics};
+use sui_config::genesis::Genesis;
+use sui_config::node::ArchiveReaderConfig;
+use sui_config::object_storage_config::ObjectStoreConfig;
+use sui_sdk::rpc_types::{SuiGetPastObjectRequest, SuiObjectDataOptions};
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use sui_storage::blob::{Blob, BlobEncoding};
+use sui_types::base_types::ExecutionData;
+use sui_types::effects::{TransactionEffectsAPI, TransactionEvents};
+use sui_types::event::Event;
+use sui_types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
+use sui_types::messages_checkpoint::{
+    CheckpointSequenceNumber, FullCheckpointContents, VerifiedCheckpointContents,
+};
+use sui_types::object::Object;
+use sui_types::storage::{
+    ObjectKey, ReadStore, SharedInMemoryStore, SingleCheckpointSharedInMemoryStore,
+};
+use tracing::info;
+
+/// Number of transactions whose events and objects are fetched from the fullnode concurrently.
+const FULLNODE_CONCURRENCY: usize = 16;
+
+/// Download the checkpoints in `range` from the archive in `remote_store_config`, and write them
+/// to `out`. If `genesis` is set, every checkpoint summary from genesis to the end of `range` is
+/// verified against the committee of its epoch, starting from the committee in `genesis`, and
+/// checkpoint contents are checked against their summaries.
+pub async fn fetch_checkpoints(
+    genesis: Option<&Path>,
+    remote_store_config: ObjectStoreConfig,
+    range: Range<CheckpointSequenceNumber>,
+    out: &Path,
+    fullnode_rpc_url: Option<String>,
+    concurrency: usize,
+) -> Result<()> {
+    ensure!(!range.is_empty(), "Empty checkpoint range: {range:?}");
+    std::fs::create_dir_all(out)
+        .with_context(|| format!("Failed to create output directory {}", out.display()))?;
+
+    let metrics = ArchiveReaderMetrics::new(&Registry::default());
+    let config = ArchiveReaderConfig {
+        remote_store_config,
+        download_concurrency: NonZeroUsize::new(concurrency).context("Concurrency must be > 0")?,
+        use_for_pruning_watermark: false,
+    };
+    let archive_reader = ArchiveReader::new(config, &metrics)?;
+    archive_reader.sync_manifest_once().await?;
+
+    let verify = genesis.is_some();
+    let store = match genesis {
+        Some(genesis) => verified_store_before(&archive_reader, genesis, range.start).await?,
+        None => SharedInMemoryStore::default(),
+    };
+
+    info!("Downloading checkpoints {range:?} from archive");
+    archive_reader
+        .read(
+            store.clone(),
+            range.clone(),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            verify,
+        )
+        .await?;
+
+    let client = match fullnode_rpc_url {
+        Some(url) => Some(SuiClientBuilder::default().build(url).await?),
+        None => None,
+    };
+
+    for seq in range {
+        let checkpoint = store
+            .get_checkpoint_by_sequence_number(seq)?
+            .with_context(|| format!("Checkpoint {seq} is not in the archive"))?;
+        let contents = store
+            .get_full_checkpoint_contents_by_sequence_number(seq)?
+            .with_context(|| format!("Contents of checkpoint {seq} are not in the archive"))?;
+
+        let transactions = match &client {
+            Some(client) => fetch_transactions(client, &contents).await?,
+            None => contents
+                .iter()
+                .map(|data| checkpoint_transaction(data, None, vec![], vec![]))
+                .collect(),
+        };
+
+        let checkpoint_data = CheckpointData {
+            checkpoint_summary: checkpoint.into(),
+            checkpoint_contents: contents.checkpoint_contents(),
+            transactions,
+        };
+        let blob = Blob::encode(&checkpoint_data, BlobEncoding::Bcs)?;
+        std::fs::write(out.join(format!("{seq}.chk")), blob.to_bytes())
+            .with_context(|| format!("Failed to write checkpoint {seq}"))?;
+        info!("Wrote checkpoint {seq}");
+    }
+
+    Ok(())
+}
+
+/// A store holding the committees of all epochs up to the one containing checkpoint `start`, and
+/// the (verified) checkpoint before it, which are needed to verify checkpoints from `start` on.
+/// Summaries are verified one at a time, from genesis, without keeping them all in memory.
+async fn verified_store_before(
+    archive_reader: &ArchiveReader,
+    genesis: &Path,
+    start: CheckpointSequenceNumber,
+) -> Result<SharedInMemoryStore> {
+    let genesis = Genesis::load(genesis)?;
+    let genesis_committee = genesis.committee()?;
+    let genesis_contents = VerifiedCheckpointContents::new_unchecked(
+        FullCheckpointContents::from_contents_and_execution_data(
+            genesis.checkpoint_contents().clone(),
+            std::iter::once(ExecutionData::new(
+                genesis.transaction().clone(),
+                genesis.effects().clone(),
+            )),
+        ),
+    );
+
+    let store = SharedInMemoryStore::default();
+    if start <= 1 {
+        store.inner_mut().insert_genesis_state(
+            genesis.checkpoint(),
+            genesis_contents,
+            genesis_committee,
+        );
+        return Ok(store);
+    }
+
+    let mut summaries = SingleCheckpointSharedInMemoryStore::default();
+    summaries.insert_genesis_state(genesis.checkpoint(), genesis_contents, genesis_committee);
+
+    info!("Verifying checkpoint summaries from genesis up to {start}");
+    archive_reader
+        .read_summaries(
+            summaries.clone(),
+            1..start,
+            Arc::new(AtomicU64::new(0)),
+            true,
+        )
+        .await?;
+
+    let previous = summaries
+        .get_checkpoint_by_sequence_number(start - 1)?
+        .with_context(|| format!("Checkpoint {} is not in the archive", start - 1))?;
+    let epochs = previous.epoch() + u64::from(previous.end_of_epoch_data.is_some());
+
+    let mut inner = store.inner_mut();
+    for epoch in 0..=epochs {
+        let committee = summaries
+            .get_committee(epoch)?
+            .ok_or_else(|| anyhow!("Missing committee for epoch {epoch}"))?;
+        inner.insert_committee((*committee).clone());
+    }
+    inner.insert_checkpoint(&previous);
+    drop(inner);
+
+    Ok(store)
+}
+
+/// Complete the transactions in `contents` with their events and objects, read from a fullnode.
+/// Everything read is checked against the transactions' effects.
+async fn fetch_transactions(
+    client: &SuiClient,
+    contents: &FullCheckpointContents,
+) -> Result<Vec<CheckpointTransaction>> {
+    futures::stream::iter(contents.iter())
+        .map(|data| async move {
+            let events = match data.effects.events_digest() {
+                Some(digest) => {
+                    let events = fetch_events(client, data).await?;
+                    ensure!(
+                        events.digest() == *digest,
+                        "Events of transaction {} do not match its effects",
+                        data.transaction.digest()
+                    );
+                    Some(events)
+                }
+                None => None,
+            };
+
+            let fx = &data.effects;
+            // Objects that were unwrapped and deleted did not exist before the transaction.
+            let unwrapped_then_deleted: HashSet<_> = fx
+                .unwrapped_then_deleted()
+                .into_iter()
+                .map(|(id, _, _)| id)
+                .collect();
+            let input_keys: Vec<_> = fx
+                .input_shared_objects()
+                .into_iter()
+                .map(|kind| {
+                    let (id, version) = kind.id_and_version();
+                    ObjectKey(id, version)
+                })
+                .chain(
+                    fx.modified_at_versions()
+                        .into_iter()
+                        .map(|(id, version)| ObjectKey(id, version)),
+                )
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .filter(|key| !unwrapped_then_deleted.contains(&key.0))
+                .collect();
+            let input_objects = fetch_objects(client, &input_keys).await?;
+
+            let output_refs: Vec<_> = fx
+                .all_changed_objects()
+                .into_iter()
+                .map(|(object_ref, _, _)| object_ref)
+                .collect();
+            let output_keys: Vec<_> = output_refs.iter().copied().map(ObjectKey::from).collect();
+            let output_objects = fetch_objects(client, &output_keys).await?;
+            for (object, expected) in output_objects.iter().zip(&output_refs) {
+                ensure!(
+                    object.compute_object_reference() == *expected,
+                    "Object {:?} does not match the effects of transaction {}",
+                    expected,
+                    data.transaction.digest()
+                );
+            }
+
+            Ok(checkpoint_transaction(
+                data,
+                events,
+                input_objects,
+                output_objects,
+            ))
+        })
+        .buffered(FULLNODE_CONCURRENCY)
+        .try_collect()
+        .await
+}
+
+async fn fetch_events(client: &SuiClient, data: &ExecutionData) -> Result<TransactionEvents> {
+    let events = client
+        .event_api()
+        .get_events(*data.transaction.digest())
+        .await?
+        .into_iter()
+        .map(|event| Event {
+            package_id: event.package_id,
+            transaction_module: event.transaction_module,
+            sender: event.sender,
+            type_: event.type_,
+            contents: event.bcs,
+        })
+        .collect();
+    Ok(TransactionEvents { data: events })
+}
+
+async fn fetch_objects(client: &SuiClient, keys: &[ObjectKey]) -> Result<Vec<Object>> {
+    if keys.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let requests = keys
+        .iter()
+        .map(|ObjectKey(object_id, version)| SuiGetPastObjectRequest {
+            object_id: *object_id,
+            version: *version,
+        })
+        .collect();
+    client
+        .read_api()
+        .try_multi_get_parsed_past_object(requests, SuiObjectDataOptions::bcs_lossless())
+        .await?
+        .into_iter()
+        .zip(keys)
+        .map(|(response, ObjectKey(object_id, version))| {
+            let data = response
+                .into_object()
+                .map_err(|e| anyhow!("Failed to fetch object {object_id} at {version}: {e}"))?;
+            TryInto::<Object>::try_into(data)
+        })
+        .collect()
+}
+
+fn checkpoint_transaction(
+    data: &ExecutionData,
+    events: Option<TransactionEvents>,
+    input_objects: Vec<Object>,
+    output_objects: Vec<Object>,
+) -> CheckpointTransaction {
+    CheckpointTransaction {
+        transaction: data.transaction.clone(),
+        effects: data.effects.clone(),
+        events,
+        input_objects,
+        output_objects,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sui_archival::read_manifest;
+    use sui_archival::writer::ArchiveWriter;
+    use sui_config::object_storage_config::ObjectStoreType;
+    use sui_storage::{FileCompression, StorageFormat};
+    use sui_types::committee::Committee;
+    use sui_types::crypto::KeypairTraits;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointSummary, SignedCheckpointSummary, VerifiedCheckpoint,
+    };
+
+    use super::*;
+
+    /// How many checkpoints are archived, starting from genesis.
+    const CHECKPOINTS: u64 = 5;
+
+    /// A store holding a chain of empty checkpoints, from genesis, for the archive to be written
+    /// from.
+    fn checkpoint_store() -> SharedInMemoryStore {
+        let (committee, keys) = Committee::new_simple_test_committee();
+        let store = SharedInMemoryStore::default();
+        let mut previous_digest = None;
+        for seq in 0..CHECKPOINTS {
+            let contents = VerifiedCheckpointContents::new_unchecked(
+                FullCheckpointContents::new_with_causally_ordered_transactions(std::iter::empty()),
+            );
+            let summary = CheckpointSummary::new(
+                0,
+                seq,
+                0,
+                &contents.clone().into_inner().into_checkpoint_contents(),
+                previous_digest,
+                GasCostSummary::default(),
+                None,
+                0,
+            );
+            let signatures = keys
+                .iter()
+                .map(|k| SignedCheckpointSummary::sign(0, &summary, k, k.public().into()))
+                .collect();
+            let checkpoint = VerifiedCheckpoint::new_unchecked(
+                CertifiedCheckpointSummary::new(summary, signatures, &committee).unwrap(),
+            );
+            previous_digest = Some(*checkpoint.digest());
+
+            let mut inner = store.inner_mut();
+            if seq == 0 {
+                inner.insert_genesis_state(checkpoint, contents, committee.clone());
+            } else {
+                inner.insert_checkpoint(&checkpoint);
+                inner.insert_checkpoint_contents(&checkpoint, contents);
+            }
+        }
+        store
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checkpoints_from_archive() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store_config = |name: &str| ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(dir.path().join(name)),
+            ..Default::default()
+        };
+        let remote_store_config = store_config("remote");
+
+        // Every checkpoint goes in a file of its own, which is uploaded once the next checkpoint
+        // is written, so all but the last are archived.
+        let writer = ArchiveWriter::new(
+            store_config("local"),
+            remote_store_config.clone(),
+            FileCompression::Zstd,
+            StorageFormat::Blob,
+            Duration::from_secs(600),
+            1,
+            &Registry::default(),
+        )
+        .await?;
+        let kill = writer.start(checkpoint_store()).await?;
+        let remote_store = remote_store_config.make()?;
+        tokio::time::timeout(Duration::from_secs(30), async {
+            while read_manifest(remote_store.clone())
+                .await
+                .map_or(true, |m| m.next_checkpoint_seq_num() < CHECKPOINTS - 1)
+            {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("Checkpoints are archived");
+        kill.send(())?;
+
+        let out = dir.path().join("out");
+        fetch_checkpoints(None, remote_store_config, 1..3, &out, None, 2).await?;
+
+        // Exactly the checkpoints in the range are written, without the objects and events that
+        // only a fullnode could provide.
+        let mut files: Vec<_> = std::fs::read_dir(&out)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect::<Result<_>>()?;
+        files.sort();
+        assert_eq!(files, ["1.chk", "2.chk"]);
+        for seq in 1..3 {
+            let data: CheckpointData =
+                Blob::from_bytes(&std::fs::read(out.join(format!("{seq}.chk")))?)?;
+            assert_eq!(*data.checkpoint_summary.sequence_number(), seq);
+            assert!(data.transactions.is_empty());
+        }
+        Ok(())
+    }
+}
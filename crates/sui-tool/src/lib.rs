@@ -67,7 +67,9 @@ use typed_store::rocks::MetricConf;
 
 pub mod commands;
 pub mod db_tool;
+pub mod fetch_checkpoints;
 pub mod pkg_dump;
+pub mod state_diff;
 
 // This functions requires at least one of genesis or fullnode_rpc to be `Some`.
 async fn make_clients(
@@ -0,0 +1,226 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Diffs the state owned by a set of addresses between the ends of two epochs, read from the
+//! formal snapshots taken at those epochs' last checkpoints. The diff is written as CSV, with one
+//! row per change:
+//!
+//! ```text
+//! address,kind,object_id,type,before,after
+//! 0x..,object,0x..,0x2::coin::Coin<0x2::sui::SUI>,12,15
+//! 0x..,balance,,0x2::sui::SUI,1000000,900000
+//! ```
+//!
+//! `object` rows are objects directly owned by the address that were created, deleted, modified,
+//! or transferred, with their versions before and after (empty if the address did not own the
+//! object). `balance` rows are changes in the total balance of the address's coins of a type.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::Write;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use indicatif::MultiProgress;
+use serde::Serialize;
+use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+use sui_core::authority::authority_store_tables::LiveObject;
+use sui_snapshot::reader::StateSnapshotReaderV1;
+use sui_types::base_types::{EpochId, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::object::Owner;
+use tracing::info;
+
+/// State owned by a set of addresses at the end of an epoch.
+#[derive(Default)]
+struct OwnedState {
+    /// Version and type of each object owned by each address.
+    objects: BTreeMap<(SuiAddress, ObjectID), (SequenceNumber, String)>,
+    /// Total balance of each address, by coin type.
+    balances: BTreeMap<(SuiAddress, String), u128>,
+}
+
+#[derive(Serialize)]
+struct DiffRow {
+    address: SuiAddress,
+    kind: &'static str,
+    object_id: Option<ObjectID>,
+    #[serde(rename = "type")]
+    type_: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+/// Write the changes to the objects and balances owned by `addresses` between the end of
+/// `from_epoch` and the end of `to_epoch` to `out`, as CSV. Both snapshots are scanned in full.
+pub async fn epoch_state_diff(
+    snapshot_store_config: ObjectStoreConfig,
+    from_epoch: EpochId,
+    to_epoch: EpochId,
+    addresses: &[SuiAddress],
+    concurrency: usize,
+    out: impl Write,
+) -> Result<()> {
+    let addresses: HashSet<_> = addresses.iter().copied().collect();
+    let before =
+        read_owned_state(&snapshot_store_config, from_epoch, &addresses, concurrency).await?;
+    let after = read_owned_state(&snapshot_store_config, to_epoch, &addresses, concurrency).await?;
+
+    let mut writer = csv::Writer::from_writer(out);
+    for row in diff(&before, &after) {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+async fn read_owned_state(
+    snapshot_store_config: &ObjectStoreConfig,
+    epoch: EpochId,
+    addresses: &HashSet<SuiAddress>,
+    concurrency: usize,
+) -> Result<OwnedState> {
+    info!("Reading state at the end of epoch {epoch}");
+    let staging_dir = tempfile::tempdir()?;
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(staging_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let reader = StateSnapshotReaderV1::new(
+        epoch,
+        snapshot_store_config,
+        &local_store_config,
+        usize::MAX,
+        NonZeroUsize::new(concurrency).unwrap_or(NonZeroUsize::MIN),
+        MultiProgress::new(),
+    )
+    .await?;
+
+    let state = Mutex::new(OwnedState::default());
+    reader
+        .for_each_live_object(|object| {
+            let LiveObject::Normal(object) = object else {
+                return;
+            };
+            let Owner::AddressOwner(owner) = object.owner else {
+                return;
+            };
+            if !addresses.contains(&owner) {
+                return;
+            }
+
+            let type_ = object
+                .struct_tag()
+                .map(|tag| tag.to_canonical_string(true))
+                .unwrap_or_default();
+            let mut state = state.lock().unwrap();
+            if let Some(coin_type) = object.coin_type_maybe() {
+                *state
+                    .balances
+                    .entry((owner, coin_type.to_canonical_string(true)))
+                    .or_default() += object.get_coin_value_unsafe() as u128;
+            }
+            state
+                .objects
+                .insert((owner, object.id()), (object.version(), type_));
+        })
+        .await?;
+
+    Ok(state.into_inner().unwrap())
+}
+
+fn diff(before: &OwnedState, after: &OwnedState) -> Vec<DiffRow> {
+    let mut rows = vec![];
+
+    let object_keys: BTreeSet<_> = before.objects.keys().chain(after.objects.keys()).collect();
+    for key @ (address, object_id) in object_keys {
+        let old = before.objects.get(key);
+        let new = after.objects.get(key);
+        if old.map(|(version, _)| version) == new.map(|(version, _)| version) {
+            continue;
+        }
+        let (_, type_) = new.or(old).expect("Object is owned before or after");
+        rows.push(DiffRow {
+            address: *address,
+            kind: "object",
+            object_id: Some(*object_id),
+            type_: type_.clone(),
+            before: old.map(|(version, _)| version.value().to_string()),
+            after: new.map(|(version, _)| version.value().to_string()),
+        });
+    }
+
+    let balance_keys: BTreeSet<_> = before
+        .balances
+        .keys()
+        .chain(after.balances.keys())
+        .collect();
+    for key @ (address, coin_type) in balance_keys {
+        let old = before.balances.get(key).copied().unwrap_or_default();
+        let new = after.balances.get(key).copied().unwrap_or_default();
+        if old == new {
+            continue;
+        }
+        rows.push(DiffRow {
+            address: *address,
+            kind: "balance",
+            object_id: None,
+            type_: coin_type.clone(),
+            before: Some(old.to_string()),
+            after: Some(new.to_string()),
+        });
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let address = SuiAddress::random_for_testing_only();
+        let (kept, changed, deleted, created) = (
+            ObjectID::random(),
+            ObjectID::random(),
+            ObjectID::random(),
+            ObjectID::random(),
+        );
+        let sui = "0x2::sui::SUI".to_string();
+        let coin = "0x2::coin::Coin<0x2::sui::SUI>".to_string();
+        let v = SequenceNumber::from_u64;
+
+        let before = OwnedState {
+            objects: BTreeMap::from([
+                ((address, kept), (v(1), coin.clone())),
+                ((address, changed), (v(1), coin.clone())),
+                ((address, deleted), (v(1), coin.clone())),
+            ]),
+            balances: BTreeMap::from([((address, sui.clone()), 30)]),
+        };
+        let after = OwnedState {
+            objects: BTreeMap::from([
+                ((address, kept), (v(1), coin.clone())),
+                ((address, changed), (v(2), coin.clone())),
+                ((address, created), (v(2), coin.clone())),
+            ]),
+            balances: BTreeMap::from([((address, sui.clone()), 25)]),
+        };
+
+        let rows: BTreeMap<_, _> = diff(&before, &after)
+            .into_iter()
+            .map(|row| ((row.kind, row.object_id), (row.before, row.after)))
+            .collect();
+        let s = |s: &str| Some(s.to_string());
+        assert_eq!(
+            rows,
+            BTreeMap::from([
+                (("object", Some(changed)), (s("1"), s("2"))),
+                (("object", Some(deleted)), (s("1"), None)),
+                (("object", Some(created)), (None, s("2"))),
+                (("balance", None), (s("30"), s("25"))),
+            ])
+        );
+    }
+}
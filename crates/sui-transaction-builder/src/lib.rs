@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::result::Result;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -9,8 +9,9 @@ use std::sync::Arc;
 use anyhow::{anyhow, bail, ensure, Ok};
 use async_trait::async_trait;
 use futures::future::join_all;
+use move_binary_format::access::ModuleAccess;
 use move_binary_format::binary_views::BinaryIndexedView;
-use move_binary_format::file_format::SignatureToken;
+use move_binary_format::file_format::{CompiledModule, SignatureToken};
 use move_binary_format::file_format_common::VERSION_MAX;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{StructTag, TypeTag};
@@ -21,11 +22,13 @@ use sui_json_rpc_types::{
     SuiTypeTag,
 };
 use sui_protocol_config::ProtocolConfig;
-use sui_types::base_types::{ObjectID, ObjectInfo, ObjectRef, ObjectType, SuiAddress};
+use sui_types::base_types::{
+    ObjectID, ObjectInfo, ObjectRef, ObjectType, SequenceNumber, SuiAddress,
+};
 use sui_types::error::UserInputError;
 use sui_types::gas_coin::GasCoin;
 use sui_types::governance::{ADD_STAKE_MUL_COIN_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
-use sui_types::move_package::MovePackage;
+use sui_types::move_package::{MovePackage, UpgradeInfo};
 use sui_types::object::{Object, Owner};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
@@ -365,25 +368,7 @@ impl TransactionBuilder {
         type_args: &[TypeTag],
         json_args: Vec<SuiJsonValue>,
     ) -> Result<Vec<Argument>, anyhow::Error> {
-        let object = self
-            .0
-            .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
-            .await?
-            .into_object()?;
-        let Some(SuiRawData::Package(package)) = object.bcs else {
-            bail!(
-                "Bcs field in object [{}] is missing or not a package.",
-                package_id
-            );
-        };
-        let package: MovePackage = MovePackage::new(
-            package.id,
-            object.version,
-            package.module_map,
-            ProtocolConfig::get_for_min_version().max_move_package_size(),
-            package.type_origin_table,
-            package.linkage_table,
-        )?;
+        let package = self.get_package(package_id).await?;
 
         let json_args_and_tokens = resolve_move_function_args(
             &package,
@@ -458,6 +443,93 @@ impl TransactionBuilder {
         ))
     }
 
+    /// Publish `compiled_modules`, linked against the packages that they depend on, directly or
+    /// transitively, as found on chain. Modules refer to their dependencies by the ID that each
+    /// was first published at, and are linked against that version of an immediate dependency
+    /// unless `pinned_dependencies` maps its original ID to the ID of another version. Transitive
+    /// dependencies are linked against the latest version that any of the packages depending on
+    /// them was linked against, unless pinned.
+    pub async fn publish_with_dependency_resolution(
+        &self,
+        sender: SuiAddress,
+        compiled_modules: Vec<Vec<u8>>,
+        pinned_dependencies: BTreeMap<ObjectID, ObjectID>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let mut immediate_dependencies = BTreeSet::new();
+        for bytes in &compiled_modules {
+            let module = CompiledModule::deserialize_with_defaults(bytes)
+                .map_err(|e| anyhow!("Failed to deserialize compiled module: {e}"))?;
+            for dependency in module.immediate_dependencies() {
+                // The modules being published refer to each other at address 0x0.
+                let address = ObjectID::from(*dependency.address());
+                if address != ObjectID::ZERO {
+                    immediate_dependencies.insert(address);
+                }
+            }
+        }
+
+        let pinned = |original: ObjectID| *pinned_dependencies.get(&original).unwrap_or(&original);
+        let mut pending: Vec<_> = immediate_dependencies
+            .into_iter()
+            .map(|original| (original, pinned(original)))
+            .collect();
+        // The version each dependency is linked against, and the oldest version of each that the
+        // packages depending on it can be linked against.
+        let mut linkage: BTreeMap<ObjectID, UpgradeInfo> = BTreeMap::new();
+        let mut required: BTreeMap<ObjectID, SequenceNumber> = BTreeMap::new();
+        while let Some((original, storage)) = pending.pop() {
+            if linkage
+                .get(&original)
+                .is_some_and(|info| info.upgraded_id == storage)
+            {
+                continue;
+            }
+            let package = self.get_package(storage).await?;
+            ensure!(
+                package.original_package_id() == original,
+                "Package [{storage}] is not a version of package [{original}]"
+            );
+            if linkage
+                .get(&original)
+                .is_some_and(|info| info.upgraded_version >= package.version())
+            {
+                continue;
+            }
+            linkage.insert(
+                original,
+                UpgradeInfo {
+                    upgraded_id: storage,
+                    upgraded_version: package.version(),
+                },
+            );
+            for (dependency, info) in package.linkage_table() {
+                let version = required.entry(*dependency).or_insert(info.upgraded_version);
+                *version = (*version).max(info.upgraded_version);
+                let storage = match pinned_dependencies.get(dependency) {
+                    Some(pinned) => *pinned,
+                    None => info.upgraded_id,
+                };
+                pending.push((*dependency, storage));
+            }
+        }
+
+        for (original, version) in required {
+            let info = &linkage[&original];
+            ensure!(
+                info.upgraded_version >= version,
+                "Dependency [{original}] is pinned to [{}] at version {}, but its dependents require version {version} or later",
+                info.upgraded_id,
+                info.upgraded_version,
+            );
+        }
+
+        let dep_ids = linkage.into_values().map(|info| info.upgraded_id).collect();
+        self.publish(sender, compiled_modules, dep_ids, gas, gas_budget)
+            .await
+    }
+
     pub async fn upgrade(
         &self,
         sender: SuiAddress,
@@ -777,6 +849,28 @@ impl TransactionBuilder {
             .map(|(oref, _)| oref)
     }
 
+    async fn get_package(&self, package_id: ObjectID) -> anyhow::Result<MovePackage> {
+        let object = self
+            .0
+            .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .into_object()?;
+        let Some(SuiRawData::Package(package)) = object.bcs else {
+            bail!(
+                "Bcs field in object [{}] is missing or not a package.",
+                package_id
+            );
+        };
+        Ok(MovePackage::new(
+            package.id,
+            object.version,
+            package.module_map,
+            ProtocolConfig::get_for_min_version().max_move_package_size(),
+            package.type_origin_table,
+            package.linkage_table,
+        )?)
+    }
+
     async fn get_object_ref_and_type(
         &self,
         object_id: ObjectID,
@@ -4,7 +4,9 @@
 use crate::collection_types::VecMap;
 use crate::event::Event;
 use crate::id::{ID, UID};
-use crate::SUI_FRAMEWORK_ADDRESS;
+use crate::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::annotated_value::{MoveStructLayout, MoveTypeLayout};
 use move_core_types::ident_str;
 use move_core_types::identifier::IdentStr;
 use move_core_types::language_storage::StructTag;
@@ -14,6 +16,21 @@ pub const DISPLAY_MODULE_NAME: &IdentStr = ident_str!("display");
 pub const DISPLAY_CREATED_EVENT_NAME: &IdentStr = ident_str!("DisplayCreated");
 pub const DISPLAY_VERSION_UPDATED_EVENT_NAME: &IdentStr = ident_str!("VersionUpdated");
 
+/// The most fields a Display template can reference in a single path, e.g. `{a.b.c}` has three.
+pub const MAX_DISPLAY_NESTED_LEVEL: usize = 10;
+
+/// Structs that are rendered as values rather than as structs of fields, so that templates can't
+/// reference their fields.
+const DISPLAY_VALUE_STRUCTS: &[(AccountAddress, &str, &str)] = &[
+    (MOVE_STDLIB_ADDRESS, "string", "String"),
+    (MOVE_STDLIB_ADDRESS, "ascii", "String"),
+    (MOVE_STDLIB_ADDRESS, "option", "Option"),
+    (SUI_FRAMEWORK_ADDRESS, "url", "Url"),
+    (SUI_FRAMEWORK_ADDRESS, "object", "ID"),
+    (SUI_FRAMEWORK_ADDRESS, "object", "UID"),
+    (SUI_FRAMEWORK_ADDRESS, "balance", "Balance"),
+];
+
 // TODO: add tests to keep in sync
 /// Rust version of the Move sui::display::Display type
 #[derive(Debug, Deserialize, Clone, Eq, PartialEq)]
@@ -89,3 +106,185 @@ impl DisplayCreatedEvent {
         }
     }
 }
+
+/// Check that `template` renders for objects whose type has `layout`: that its braces are
+/// balanced, and that each `{path}` in it names a chain of fields that exist, going only through
+/// structs that are rendered as structs of fields, and ending at a value that can be displayed.
+/// Returns a description of each problem found, so that templates can be checked before a
+/// Display is published, rather than rendering blank in wallets.
+pub fn validate_display_template(template: &str, layout: &MoveStructLayout) -> Vec<String> {
+    let mut errors = vec![];
+    let mut var_name = String::new();
+    let mut in_braces = false;
+    let mut escaped = false;
+
+    // Mirrors how templates are parsed when they are rendered.
+    for ch in template.chars() {
+        match ch {
+            '\\' => {
+                escaped = true;
+                continue;
+            }
+            '{' if !escaped => {
+                if in_braces {
+                    errors.push(format!("`{{{var_name}` is not closed"));
+                }
+                in_braces = true;
+                var_name.clear();
+            }
+            '}' if !escaped => {
+                if in_braces {
+                    if let Err(e) = validate_display_path(&var_name, layout) {
+                        errors.push(e);
+                    }
+                } else {
+                    errors.push("`}` has no matching `{`".to_string());
+                }
+                in_braces = false;
+            }
+            _ if !escaped && in_braces => var_name.push(ch),
+            _ => {}
+        }
+        escaped = false;
+    }
+    if in_braces {
+        errors.push(format!("`{{{var_name}` is not closed"));
+    }
+    errors
+}
+
+fn validate_display_path(path: &str, layout: &MoveStructLayout) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("`{}` does not reference a field".to_string());
+    }
+    let parts: Vec<_> = path.split('.').collect();
+    if parts.len() > MAX_DISPLAY_NESTED_LEVEL {
+        return Err(format!(
+            "`{{{path}}}` references fields more than {MAX_DISPLAY_NESTED_LEVEL} levels deep"
+        ));
+    }
+
+    let mut current = layout;
+    for (i, part) in parts.iter().enumerate() {
+        let Some(field) = current.fields.iter().find(|f| f.name.as_str() == *part) else {
+            return Err(format!(
+                "`{{{path}}}`: `{}` has no field `{part}`",
+                current.type_.to_canonical_display(/* with_prefix */ true)
+            ));
+        };
+        let is_last = i + 1 == parts.len();
+        match &field.layout {
+            MoveTypeLayout::Vector(_) => {
+                return Err(format!(
+                    "`{{{path}}}`: `{part}` is a vector, which can't be displayed"
+                ));
+            }
+            MoveTypeLayout::Struct(s) if !is_last => {
+                if is_display_value(&s.type_) {
+                    return Err(format!(
+                        "`{{{path}}}`: `{part}` is a `{}`, which is displayed as a value, so its \
+                         fields can't be referenced",
+                        s.type_.to_canonical_display(/* with_prefix */ true)
+                    ));
+                }
+                current = s;
+            }
+            _ if !is_last => {
+                return Err(format!(
+                    "`{{{path}}}`: `{part}` is not a struct, so it has no fields"
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn is_display_value(type_: &StructTag) -> bool {
+    DISPLAY_VALUE_STRUCTS.iter().any(|(address, module, name)| {
+        type_.address == *address
+            && type_.module.as_str() == *module
+            && type_.name.as_str() == *name
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::annotated_value::MoveFieldLayout;
+    use move_core_types::identifier::Identifier;
+
+    fn struct_layout(
+        address: AccountAddress,
+        module: &str,
+        name: &str,
+        fields: Vec<(&str, MoveTypeLayout)>,
+    ) -> MoveStructLayout {
+        MoveStructLayout {
+            type_: StructTag {
+                address,
+                module: Identifier::new(module).unwrap(),
+                name: Identifier::new(name).unwrap(),
+                type_params: vec![],
+            },
+            fields: fields
+                .into_iter()
+                .map(|(name, layout)| MoveFieldLayout::new(Identifier::new(name).unwrap(), layout))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_validate_display_template() {
+        let string = || {
+            MoveTypeLayout::Struct(struct_layout(
+                MOVE_STDLIB_ADDRESS,
+                "string",
+                "String",
+                vec![(
+                    "bytes",
+                    MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+                )],
+            ))
+        };
+        let stats = struct_layout(
+            AccountAddress::ONE,
+            "hero",
+            "Stats",
+            vec![("level", MoveTypeLayout::U64)],
+        );
+        let hero = struct_layout(
+            AccountAddress::ONE,
+            "hero",
+            "Hero",
+            vec![
+                ("name", string()),
+                ("stats", MoveTypeLayout::Struct(stats)),
+                (
+                    "items",
+                    MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U64)),
+                ),
+            ],
+        );
+
+        assert!(validate_display_template("{name} (level {stats.level})", &hero).is_empty());
+        assert!(validate_display_template("\\{not a field\\}", &hero).is_empty());
+
+        for template in [
+            "{nmae}",
+            "{stats.lvl}",
+            "{name.bytes}",
+            "{stats.level.value}",
+            "{items}",
+            "{}",
+            "{name",
+            "name}",
+        ] {
+            assert_eq!(
+                validate_display_template(template, &hero).len(),
+                1,
+                "{template}"
+            );
+        }
+    }
+}
@@ -32,9 +32,10 @@ use shared_crypto::intent::Intent;
 use sui_execution::verifier::VerifierOverrides;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    Coin, DynamicFieldPage, SuiCoinMetadata, SuiData, SuiExecutionStatus, SuiObjectData,
-    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiParsedData, SuiRawData,
-    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    Coin, DynamicFieldPage, ObjectChange, SuiCoinMetadata, SuiData, SuiExecutionStatus,
+    SuiObjectData, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiParsedData,
+    SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
 };
 use sui_keys::keystore::AccountKeystore;
 use sui_move_build::{
@@ -62,6 +63,7 @@ use sui_types::{
     parse_sui_type_tag,
     signature::GenericSignature,
     transaction::{SenderSignedData, Transaction, TransactionData, TransactionDataAPI},
+    SUI_FRAMEWORK_ADDRESS,
 };
 
 use json_to_table::json_to_table;
@@ -76,6 +78,7 @@ use tabled::{
 use tracing::info;
 
 use crate::key_identity::{get_identity_address, KeyIdentity};
+use crate::new_coin::CoinTemplate;
 
 #[path = "unit_tests/profiler_tests.rs"]
 #[cfg(test)]
@@ -300,6 +303,49 @@ pub enum SuiClientCommands {
         derivation_path: Option<DerivationPath>,
     },
 
+    /// Create a coin for testing, by generating and publishing a Move package that mints the
+    /// coin's supply to the sender and freezes its metadata.
+    #[clap(name = "new-coin")]
+    NewCoin {
+        /// Symbol of the coin, which also names its type and module, e.g. FOO
+        #[clap(long)]
+        symbol: String,
+
+        /// Number of decimal places of the coin
+        #[clap(long, default_value = "9")]
+        decimals: u8,
+
+        /// Supply minted to the sender, in whole coins, e.g. 1e9
+        #[clap(long, default_value = "0")]
+        supply: String,
+
+        /// Name of the coin, defaults to its symbol
+        #[clap(long)]
+        name: Option<String>,
+
+        /// Description of the coin
+        #[clap(long)]
+        description: Option<String>,
+
+        /// URL of the coin's icon
+        #[clap(long, value_hint = ValueHint::Url)]
+        icon_url: Option<String>,
+
+        /// Path to the Sui framework package to build the coin's package against, e.g. from the
+        /// checkout a localnet was built from. Defaults to the framework's testnet branch.
+        #[clap(long)]
+        sui_framework: Option<PathBuf>,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for publishing the coin's package
+        #[clap(long)]
+        gas_budget: u64,
+    },
+
     /// Add new Sui environment.
     #[clap(name = "new-env")]
     NewEnv {
@@ -1115,6 +1161,8 @@ impl SuiClientCommands {
                             show_object_changes: true,
                             show_balance_changes: false,
                             show_raw_effects: false,
+                            show_labels: false,
+                            fields: None,
                         },
                     )
                     .await?;
@@ -1363,6 +1411,49 @@ impl SuiClientCommands {
                     recovery_phrase: phrase,
                 })
             }
+            SuiClientCommands::NewCoin {
+                symbol,
+                decimals,
+                supply,
+                name,
+                description,
+                icon_url,
+                sui_framework,
+                gas,
+                gas_budget,
+            } => {
+                let template =
+                    CoinTemplate::new(symbol, decimals, &supply, name, description, icon_url)?;
+                let package_dir = tempfile::tempdir()?;
+                template.write_package(package_dir.path(), sui_framework.as_deref())?;
+
+                let sender = context.try_get_object_owner(&gas).await?;
+                let sender = sender.unwrap_or(context.active_address()?);
+
+                let client = context.get_client().await?;
+                let (dependencies, compiled_modules, _, _) = compile_package(
+                    client.read_api(),
+                    MoveBuildConfig::default(),
+                    package_dir.path().to_path_buf(),
+                    /* with_unpublished_dependencies */ false,
+                    /* skip_dependency_verification */ false,
+                )
+                .await?;
+                let data = client
+                    .transaction_builder()
+                    .publish(
+                        sender,
+                        compiled_modules,
+                        dependencies.published.into_values().collect(),
+                        gas,
+                        gas_budget,
+                    )
+                    .await?;
+                let response = context
+                    .execute_transaction_may_fail(context.sign_transaction(&data))
+                    .await?;
+                SuiClientCommandResult::NewCoin(NewCoinOutput::try_from(response)?)
+            }
             SuiClientCommands::Gas { address } => {
                 let address = get_identity_address(address, context)?;
                 let coins = context
@@ -1889,6 +1980,22 @@ impl Display for SuiClientCommandResult {
 
                 write!(f, "{}", table)?
             }
+            SuiClientCommandResult::NewCoin(new_coin) => {
+                let mut builder = TableBuilder::default();
+                builder.push_record(vec!["coinType", new_coin.coin_type.as_str()]);
+                builder.push_record(vec!["packageId", &new_coin.package_id.to_string()]);
+                builder.push_record(vec!["metadataId", &new_coin.metadata_id.to_string()]);
+                builder.push_record(vec!["treasuryCapId", &new_coin.treasury_cap_id.to_string()]);
+                if let Some(coin_id) = new_coin.coin_id {
+                    builder.push_record(vec!["coinId", &coin_id.to_string()]);
+                }
+                builder.push_record(vec!["digest", &new_coin.digest.to_string()]);
+
+                let mut table = builder.build();
+                table.with(TableStyle::rounded());
+                table.with(TablePanel::header("Created new coin."));
+                write!(f, "{}", table)?
+            }
             SuiClientCommandResult::Object(object_read) => match object_read.object() {
                 Ok(obj) => {
                     let object = ObjectOutput::from(obj);
@@ -2196,6 +2303,71 @@ pub struct NewAddressOutput {
     pub recovery_phrase: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewCoinOutput {
+    pub coin_type: String,
+    pub package_id: ObjectID,
+    pub metadata_id: ObjectID,
+    pub treasury_cap_id: ObjectID,
+    /// The coin holding the minted supply, if any was minted.
+    pub coin_id: Option<ObjectID>,
+    pub digest: TransactionDigest,
+}
+
+impl TryFrom<SuiTransactionBlockResponse> for NewCoinOutput {
+    type Error = anyhow::Error;
+
+    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+        if let Some(effects) = &response.effects {
+            if let SuiExecutionStatus::Failure { error } = effects.status() {
+                bail!("Error publishing coin: {error}");
+            }
+        }
+
+        let mut package_id = None;
+        let mut metadata = None;
+        let mut treasury_cap_id = None;
+        let mut coin_id = None;
+        for change in response.object_changes.unwrap_or_default() {
+            match change {
+                ObjectChange::Published { package_id: id, .. } => package_id = Some(id),
+                ObjectChange::Created {
+                    object_id,
+                    object_type,
+                    ..
+                } if object_type.address == SUI_FRAMEWORK_ADDRESS
+                    && object_type.module.as_str() == "coin" =>
+                {
+                    match object_type.name.as_str() {
+                        "CoinMetadata" => metadata = Some((object_id, object_type)),
+                        "TreasuryCap" => treasury_cap_id = Some(object_id),
+                        "Coin" => coin_id = Some(object_id),
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let missing = |what| anyhow!("Transaction {} did not create a {what}", response.digest);
+        let (metadata_id, metadata_type) = metadata.ok_or_else(|| missing("coin metadata"))?;
+        let coin_type = metadata_type
+            .type_params
+            .first()
+            .ok_or_else(|| missing("coin type"))?
+            .to_canonical_string(/* with_prefix */ true);
+        Ok(Self {
+            coin_type,
+            package_id: package_id.ok_or_else(|| missing("package"))?,
+            metadata_id,
+            treasury_cap_id: treasury_cap_id.ok_or_else(|| missing("treasury cap"))?,
+            coin_id,
+            digest: response.digest,
+        })
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ObjectOutput {
@@ -2305,6 +2477,7 @@ pub enum SuiClientCommandResult {
     Gas(Vec<GasCoin>),
     MergeCoin(SuiTransactionBlockResponse),
     NewAddress(NewAddressOutput),
+    NewCoin(NewCoinOutput),
     NewEnv(SuiEnv),
     NoOutput,
     Object(SuiObjectResponse),
@@ -22,7 +22,7 @@ use rand::Rng;
 use rand::SeedableRng;
 use rusoto_core::Region;
 use rusoto_kms::{Kms, KmsClient, SignRequest};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use shared_crypto::intent::{Intent, IntentMessage, IntentScope, PersonalMessage};
 use std::fmt::{Debug, Display, Formatter};
@@ -147,6 +147,23 @@ pub enum KeyToolCommand {
         #[clap(long, num_args(1..))]
         weights: Vec<WeightUnit>,
     },
+    /// Inspect a MultiSig address before funding or sharing it. Reconstructs the address from
+    /// its public keys `flag || pk` in Base64, weights and threshold, checks that it matches
+    /// the given address, and lists the smallest sets of signers whose weights reach the
+    /// threshold. Optionally writes a JSON descriptor of the address, which cosigners can use
+    /// to reconstruct it with `multi-sig-address`.
+    MultiSigInspect {
+        address: SuiAddress,
+        #[clap(long)]
+        threshold: ThresholdUnit,
+        #[clap(long, num_args(1..))]
+        pks: Vec<PublicKey>,
+        #[clap(long, num_args(1..))]
+        weights: Vec<WeightUnit>,
+        /// File to write the JSON descriptor of the address to.
+        #[clap(long)]
+        descriptor_file: Option<PathBuf>,
+    },
     /// Provides a list of participating signatures (`flag || sig || pk` encoded in Base64),
     /// threshold, a list of all public keys and a list of their weights that define the
     /// MultiSig address. Returns a valid MultiSig signature and its sender address. The
@@ -349,6 +366,30 @@ pub struct MultiSigAddress {
     multisig: Vec<MultiSigOutput>,
 }
 
+/// Everything needed to reconstruct a MultiSig address, to share with its cosigners.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigDescriptor {
+    multisig_address: SuiAddress,
+    threshold: ThresholdUnit,
+    /// Base64 encoded `flag || pk` of each signer, in the order they are combined in.
+    pks: Vec<String>,
+    weights: Vec<WeightUnit>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigInspectOutput {
+    multisig_address: SuiAddress,
+    threshold: ThresholdUnit,
+    total_weight: u16,
+    signers: Vec<MultiSigOutput>,
+    /// The smallest sets of signers, by address, whose weights reach the threshold: any set of
+    /// signers that can sign for the address contains one of them.
+    signing_sets: Vec<Vec<SuiAddress>>,
+    descriptor: MultiSigDescriptor,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MultiSigCombinePartialSig {
@@ -448,6 +489,7 @@ pub enum CommandOutput {
     List(Vec<Key>),
     LoadKeypair(KeypairData),
     MultiSigAddress(MultiSigAddress),
+    MultiSigInspect(MultiSigInspectOutput),
     MultiSigCombinePartialSig(MultiSigCombinePartialSig),
     MultiSigCombinePartialSigLegacy(MultiSigCombinePartialSigLegacyOutput),
     PrivateKeyBase64(PrivateKeyBase64),
@@ -705,6 +747,59 @@ impl KeyToolCommand {
                 CommandOutput::MultiSigAddress(output)
             }
 
+            KeyToolCommand::MultiSigInspect {
+                address,
+                threshold,
+                pks,
+                weights,
+                descriptor_file,
+            } => {
+                let multisig_pk = MultiSigPublicKey::new(pks.clone(), weights.clone(), threshold)?;
+                let multisig_address: SuiAddress = (&multisig_pk).into();
+                if multisig_address != address {
+                    return Err(anyhow!(
+                        "Address {address} does not match the MultiSig address {multisig_address} \
+                         of the given public keys, weights and threshold. Check that the public \
+                         keys and weights are in the same order as when the address was created."
+                    ));
+                }
+
+                let signers: Vec<_> = pks
+                    .iter()
+                    .zip(&weights)
+                    .map(|(pk, w)| MultiSigOutput {
+                        address: pk.into(),
+                        public_base64_key: pk.encode_base64(),
+                        weight: *w,
+                    })
+                    .collect();
+                let signing_sets = minimal_signing_sets(&weights, threshold)
+                    .into_iter()
+                    .map(|set| set.into_iter().map(|i| signers[i].address).collect())
+                    .collect();
+                let descriptor = MultiSigDescriptor {
+                    multisig_address,
+                    threshold,
+                    pks: signers
+                        .iter()
+                        .map(|s| s.public_base64_key.clone())
+                        .collect(),
+                    weights: weights.clone(),
+                };
+                if let Some(path) = descriptor_file {
+                    fs::write(path, serde_json::to_string_pretty(&descriptor)?)?;
+                }
+
+                CommandOutput::MultiSigInspect(MultiSigInspectOutput {
+                    multisig_address,
+                    threshold,
+                    total_weight: weights.iter().map(|w| *w as u16).sum(),
+                    signers,
+                    signing_sets,
+                    descriptor,
+                })
+            }
+
             KeyToolCommand::MultiSigCombinePartialSig {
                 sigs,
                 pks,
@@ -1230,6 +1325,27 @@ impl Debug for CommandOutput {
     }
 }
 
+/// The smallest sets of signers, by index, whose `weights` sum to at least `threshold`, such that
+/// removing any signer from a set leaves it below the threshold. Sets are listed in order of
+/// their bitmaps, i.e. the set containing signer 0 alone comes first.
+pub fn minimal_signing_sets(weights: &[WeightUnit], threshold: ThresholdUnit) -> Vec<Vec<usize>> {
+    let mut sets = vec![];
+    for bitmap in 1u32..(1 << weights.len()) {
+        let set: Vec<_> = (0..weights.len())
+            .filter(|i| bitmap & (1 << i) != 0)
+            .collect();
+        let weight: u16 = set.iter().map(|i| weights[*i] as u16).sum();
+        if weight >= threshold
+            && set
+                .iter()
+                .all(|i| weight - (weights[*i] as u16) < threshold)
+        {
+            sets.push(set);
+        }
+    }
+    sets
+}
+
 /// Converts legacy formatted private key to 33 bytes bech32 encoded private key or vice versa.
 /// It can handle:
 /// 1) Hex encoded 32 byte private key (assumes scheme is Ed25519), this is the legacy wallet format
@@ -10,6 +10,7 @@ pub mod genesis_ceremony;
 pub mod genesis_inspector;
 pub mod key_identity;
 pub mod keytool;
+pub mod new_coin;
 pub mod shell;
 pub mod sui_commands;
 pub mod validator_commands;
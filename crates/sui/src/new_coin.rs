@@ -0,0 +1,215 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generating the Move packages published by `sui client new-coin`, which create a coin when
+//! published, mint its supply to the publisher, and freeze its metadata.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, ensure};
+use sui_move::new::{SUI_PKG_NAME, SUI_PKG_PATH};
+
+#[cfg(test)]
+#[path = "unit_tests/new_coin_tests.rs"]
+mod new_coin_tests;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinTemplate {
+    /// The coin's symbol, which also names its type and module.
+    pub symbol: String,
+    pub decimals: u8,
+    /// The supply minted to the publisher, in the coin's smallest unit.
+    pub supply: u64,
+    pub name: String,
+    pub description: String,
+    pub icon_url: Option<String>,
+}
+
+impl CoinTemplate {
+    /// A coin with `supply` given in whole coins, as a decimal number with an optional exponent,
+    /// e.g. `1e9` or `2.5`.
+    pub fn new(
+        symbol: String,
+        decimals: u8,
+        supply: &str,
+        name: Option<String>,
+        description: Option<String>,
+        icon_url: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let mut chars = symbol.chars();
+        ensure!(
+            chars.next().is_some_and(|c| c.is_ascii_uppercase())
+                && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'),
+            "Symbol {symbol} must start with an uppercase letter, followed by uppercase letters, \
+             digits, or underscores"
+        );
+        ensure!(
+            !["SUI", "STD"].contains(&symbol.as_str()),
+            "Symbol {symbol} clashes with the name of a framework package"
+        );
+        if let Some(url) = &icon_url {
+            ensure!(url.is_ascii(), "Icon URL {url} must be ASCII");
+        }
+
+        Ok(Self {
+            supply: parse_supply(supply, decimals)?,
+            name: name.unwrap_or_else(|| symbol.clone()),
+            description: description.unwrap_or_default(),
+            symbol,
+            decimals,
+            icon_url,
+        })
+    }
+
+    pub fn module_name(&self) -> String {
+        self.symbol.to_lowercase()
+    }
+
+    /// Write the package to `dir`, depending on the Sui framework at `sui_framework` if given,
+    /// and on the testnet branch of the framework's repository otherwise.
+    pub fn write_package(&self, dir: &Path, sui_framework: Option<&Path>) -> anyhow::Result<()> {
+        let sources = dir.join("sources");
+        fs::create_dir_all(&sources)?;
+        fs::write(dir.join("Move.toml"), self.manifest(sui_framework))?;
+        fs::write(
+            sources.join(format!("{}.move", self.module_name())),
+            self.module_source(),
+        )?;
+        Ok(())
+    }
+
+    pub fn manifest(&self, sui_framework: Option<&Path>) -> String {
+        let sui = match sui_framework {
+            Some(path) => format!("{{ local = {:?} }}", path.display().to_string()),
+            None => SUI_PKG_PATH.to_string(),
+        };
+        format!(
+            "[package]\n\
+             name = \"{}\"\n\
+             version = \"0.0.1\"\n\
+             \n\
+             [dependencies]\n\
+             {SUI_PKG_NAME} = {sui}\n\
+             \n\
+             [addresses]\n\
+             {} = \"0x0\"\n",
+            self.symbol,
+            self.module_name(),
+        )
+    }
+
+    pub fn module_source(&self) -> String {
+        let module = self.module_name();
+        let symbol = &self.symbol;
+        let (url_import, icon_url) = match &self.icon_url {
+            Some(url) => (
+                "    use sui::url;\n",
+                format!(
+                    "option::some(url::new_unsafe_from_bytes({}))",
+                    byte_string(url)
+                ),
+            ),
+            None => ("", "option::none()".to_string()),
+        };
+        let mint = if self.supply > 0 {
+            format!(
+                "        coin::mint_and_transfer(&mut treasury_cap, {}, sender, ctx);\n",
+                self.supply
+            )
+        } else {
+            String::new()
+        };
+
+        format!(
+            "module {module}::{module} {{\n\
+             \x20   use std::option;\n\
+             \x20   use sui::coin;\n\
+             \x20   use sui::transfer;\n\
+             \x20   use sui::tx_context::{{Self, TxContext}};\n\
+             {url_import}\
+             \n\
+             \x20   struct {symbol} has drop {{}}\n\
+             \n\
+             \x20   fun init(witness: {symbol}, ctx: &mut TxContext) {{\n\
+             \x20       let (treasury_cap, metadata) = coin::create_currency(\n\
+             \x20           witness,\n\
+             \x20           {decimals},\n\
+             \x20           {symbol_bytes},\n\
+             \x20           {name},\n\
+             \x20           {description},\n\
+             \x20           {icon_url},\n\
+             \x20           ctx,\n\
+             \x20       );\n\
+             \x20       let sender = tx_context::sender(ctx);\n\
+             {mint}\
+             \x20       transfer::public_freeze_object(metadata);\n\
+             \x20       transfer::public_transfer(treasury_cap, sender);\n\
+             \x20   }}\n\
+             }}\n",
+            decimals = self.decimals,
+            symbol_bytes = byte_string(symbol),
+            name = byte_string(&self.name),
+            description = byte_string(&self.description),
+        )
+    }
+}
+
+/// `supply` whole coins, as a decimal number with an optional exponent, in units of
+/// `10^-decimals` coins.
+pub fn parse_supply(supply: &str, decimals: u8) -> anyhow::Result<u64> {
+    let (mantissa, exponent) = match supply.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>()?),
+        None => (supply, 0),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    ensure!(
+        !(whole.is_empty() && fraction.is_empty())
+            && whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit()),
+        "Supply {supply} is not a decimal number"
+    );
+
+    let mut digits = format!("{whole}{fraction}");
+    if digits.chars().all(|c| c == '0') {
+        return Ok(0);
+    }
+    let mut scale = exponent + decimals as i64 - fraction.len() as i64;
+    while scale < 0 {
+        if digits.ends_with('0') {
+            digits.pop();
+            scale += 1;
+        } else {
+            bail!("Supply {supply} has more decimal places than the coin's {decimals} decimals");
+        }
+    }
+
+    let too_large = || anyhow::anyhow!("Supply {supply} is too large");
+    let digits: u128 = digits
+        .trim_start_matches('0')
+        .parse()
+        .map_err(|_| too_large())?;
+    u32::try_from(scale)
+        .ok()
+        .and_then(|scale| 10u128.checked_pow(scale))
+        .and_then(|scale| digits.checked_mul(scale))
+        .and_then(|units| u64::try_from(units).ok())
+        .ok_or_else(too_large)
+}
+
+/// `s` as a Move byte string literal.
+fn byte_string(s: &str) -> String {
+    let mut literal = String::from("b\"");
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || b" .,:;/-_!?()".contains(&byte) {
+            literal.push(byte as char);
+        } else {
+            let _ = write!(literal, "\\x{byte:02X}");
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -8,6 +8,7 @@ use crate::keytool::read_authority_keypair_from_file;
 use crate::keytool::read_keypair_from_file;
 use crate::keytool::CommandOutput;
 
+use super::minimal_signing_sets;
 use super::write_keypair_to_file;
 use super::KeyToolCommand;
 use anyhow::Ok;
@@ -36,6 +37,7 @@ use sui_types::crypto::Signature;
 use sui_types::crypto::SignatureScheme;
 use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::SuiSignatureInner;
+use sui_types::multisig::MultiSigPublicKey;
 use sui_types::transaction::TransactionData;
 use sui_types::transaction::TEST_ONLY_GAS_UNIT_FOR_TRANSFER;
 use tempfile::TempDir;
@@ -565,3 +567,58 @@ async fn test_sign_command() -> Result<(), anyhow::Error> {
     .await?;
     Ok(())
 }
+
+#[test]
+async fn test_multisig_inspect() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(3));
+    let pks = keystore.keys();
+    let weights = vec![1, 1, 2];
+    let address: SuiAddress = (&MultiSigPublicKey::new(pks.clone(), weights.clone(), 2)?).into();
+
+    let dir = TempDir::new()?;
+    let descriptor_file = dir.path().join("multisig.json");
+    let output = KeyToolCommand::MultiSigInspect {
+        address,
+        threshold: 2,
+        pks: pks.clone(),
+        weights: weights.clone(),
+        descriptor_file: Some(descriptor_file.clone()),
+    }
+    .execute(&mut keystore)
+    .await?;
+    let CommandOutput::MultiSigInspect(output) = output else {
+        panic!("Command failed")
+    };
+    let signers: Vec<SuiAddress> = pks.iter().map(|pk| pk.into()).collect();
+    assert_eq!(
+        output.signing_sets,
+        vec![vec![signers[0], signers[1]], vec![signers[2]]]
+    );
+    let descriptor: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(descriptor_file)?)?;
+    assert_eq!(descriptor["multisigAddress"], address.to_string());
+    assert_eq!(descriptor["weights"], serde_json::json!([1, 1, 2]));
+
+    // The same keys with different weights give a different address.
+    assert!(KeyToolCommand::MultiSigInspect {
+        address,
+        threshold: 2,
+        pks,
+        weights: vec![1, 2, 1],
+        descriptor_file: None,
+    }
+    .execute(&mut keystore)
+    .await
+    .is_err());
+    Ok(())
+}
+
+#[test]
+async fn test_minimal_signing_sets() {
+    assert_eq!(
+        minimal_signing_sets(&[1, 1, 1], 2),
+        vec![vec![0, 1], vec![0, 2], vec![1, 2]]
+    );
+    assert_eq!(minimal_signing_sets(&[3, 1, 1], 3), vec![vec![0]]);
+    assert!(minimal_signing_sets(&[1, 1], 3).is_empty());
+}
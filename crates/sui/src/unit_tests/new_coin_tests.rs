@@ -0,0 +1,52 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+#[test]
+fn test_parse_supply() {
+    assert_eq!(parse_supply("1e9", 6).unwrap(), 1_000_000_000_000_000);
+    assert_eq!(parse_supply("2.5", 2).unwrap(), 250);
+    assert_eq!(parse_supply("1.50", 1).unwrap(), 15);
+    assert_eq!(parse_supply("25e-1", 1).unwrap(), 25);
+    assert_eq!(parse_supply("0", 9).unwrap(), 0);
+    assert_eq!(parse_supply("0.0e100", 0).unwrap(), 0);
+
+    // More precise than the coin's decimals.
+    assert!(parse_supply("1.5", 0).is_err());
+    assert!(parse_supply("1e-7", 6).is_err());
+    // Larger than a coin can hold.
+    assert!(parse_supply("1e20", 0).is_err());
+    assert!(parse_supply("1e12", 9).is_err());
+    assert!(parse_supply("", 0).is_err());
+    assert!(parse_supply("1,000", 0).is_err());
+}
+
+#[test]
+fn test_coin_template() {
+    assert!(CoinTemplate::new("foo".into(), 6, "1", None, None, None).is_err());
+    assert!(CoinTemplate::new("SUI".into(), 6, "1", None, None, None).is_err());
+
+    let template = CoinTemplate::new(
+        "FOO".into(),
+        6,
+        "1e9",
+        Some("Foo \"Coin\"".into()),
+        None,
+        Some("https://example.com/foo.png".into()),
+    )
+    .unwrap();
+    assert_eq!(template.module_name(), "foo");
+
+    let source = template.module_source();
+    assert!(source.starts_with("module foo::foo {\n"));
+    assert!(source.contains("    struct FOO has drop {}\n"));
+    assert!(source.contains("            b\"Foo \\x22Coin\\x22\",\n"));
+    assert!(source.contains("option::some(url::new_unsafe_from_bytes(b\"https:/"));
+    assert!(source
+        .contains("coin::mint_and_transfer(&mut treasury_cap, 1000000000000000, sender, ctx);"));
+
+    let manifest = template.manifest(Some(Path::new("/sui-framework")));
+    assert!(manifest.contains("Sui = { local = \"/sui-framework\" }\n"));
+    assert!(manifest.contains("[addresses]\nfoo = \"0x0\"\n"));
+}
@@ -0,0 +1,171 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Programmable faults for the validators of a [`TestCluster`], to reproduce liveness bugs from
+//! tests: dropping consensus messages between specific validators, slowing down checkpoint
+//! construction, and crashing validators once consensus reaches a given round.
+//!
+//! Faults are injected through fail points, so message drops and checkpoint delays only take
+//! effect in simtests, or in builds with `--cfg fail_points`. Crashes work in any build.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anemo::PeerId;
+use sui_macros::{clear_fail_point, register_fail_point_arg};
+use sui_types::base_types::{AuthorityName, ConciseableName};
+use sui_types::crypto::KeypairTraits;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::info;
+
+use crate::TestCluster;
+
+const DROP_INBOUND_FAILPOINT: &str = "narwhal-drop-inbound";
+const CHECKPOINT_DELAY_FAILPOINT: &str = "checkpoint-builder-delay";
+
+/// How often to poll a validator's consensus round while waiting to crash it.
+const ROUND_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Injects faults into a [`TestCluster`], until it is dropped. Only one injector can exist at a
+/// time, because it owns the fail points it injects faults through.
+pub struct FaultInjector {
+    test_cluster: Arc<TestCluster>,
+    /// Pairs of (sender, receiver) consensus peers whose messages are dropped.
+    dropped_links: Arc<Mutex<HashSet<(PeerId, PeerId)>>>,
+    checkpoint_delays: Arc<Mutex<HashMap<AuthorityName, Duration>>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl FaultInjector {
+    pub(crate) fn new(test_cluster: Arc<TestCluster>) -> Self {
+        let dropped_links: Arc<Mutex<HashSet<(PeerId, PeerId)>>> = Default::default();
+        let links = dropped_links.clone();
+        register_fail_point_arg(DROP_INBOUND_FAILPOINT, move |link: &(PeerId, PeerId)| {
+            links.lock().unwrap().contains(link)
+        });
+
+        let checkpoint_delays: Arc<Mutex<HashMap<AuthorityName, Duration>>> = Default::default();
+        let delays = checkpoint_delays.clone();
+        register_fail_point_arg(CHECKPOINT_DELAY_FAILPOINT, move |name: &AuthorityName| {
+            delays
+                .lock()
+                .unwrap()
+                .get(name)
+                .copied()
+                .unwrap_or_default()
+        });
+
+        Self {
+            test_cluster,
+            dropped_links,
+            checkpoint_delays,
+            tasks: Default::default(),
+        }
+    }
+
+    /// Drop consensus messages sent by validator `from` to validator `to`.
+    pub fn drop_consensus_messages(&self, from: &AuthorityName, to: &AuthorityName) {
+        info!(
+            "Dropping consensus messages from {:?} to {:?}",
+            from.concise(),
+            to.concise()
+        );
+        let link = (self.peer_id(from), self.peer_id(to));
+        self.dropped_links.lock().unwrap().insert(link);
+    }
+
+    /// Drop consensus messages between `validator` and every other validator.
+    pub fn isolate(&self, validator: &AuthorityName) {
+        for other in self.test_cluster.get_validator_pubkeys() {
+            if other != *validator {
+                self.drop_consensus_messages(validator, &other);
+                self.drop_consensus_messages(&other, validator);
+            }
+        }
+    }
+
+    /// Stop dropping consensus messages between all validators.
+    pub fn heal(&self) {
+        info!("Healing all consensus links");
+        self.dropped_links.lock().unwrap().clear();
+    }
+
+    /// Wait for `delay` before building each checkpoint on `validator`.
+    pub fn delay_checkpoints(&self, validator: &AuthorityName, delay: Duration) {
+        info!(
+            "Delaying checkpoints on {:?} by {delay:?}",
+            validator.concise()
+        );
+        self.checkpoint_delays
+            .lock()
+            .unwrap()
+            .insert(*validator, delay);
+    }
+
+    pub fn clear_checkpoint_delays(&self) {
+        self.checkpoint_delays.lock().unwrap().clear();
+    }
+
+    /// Crash `validator` once its consensus has committed a leader at or beyond `round`, and
+    /// restart it after `restart_after`, if set.
+    pub fn crash_at_round(
+        &self,
+        validator: AuthorityName,
+        round: u64,
+        restart_after: Option<Duration>,
+    ) {
+        let test_cluster = self.test_cluster.clone();
+        let task = tokio::spawn(async move {
+            while last_committed_round(&test_cluster, &validator).map_or(true, |r| r < round) {
+                sleep(ROUND_POLL_INTERVAL).await;
+            }
+
+            info!(
+                "Crashing validator {:?} at round {round}",
+                validator.concise()
+            );
+            test_cluster.stop_node(&validator);
+
+            if let Some(delay) = restart_after {
+                sleep(delay).await;
+                info!("Restarting validator {:?}", validator.concise());
+                test_cluster.start_node(&validator).await;
+            }
+        });
+        self.tasks.lock().unwrap().push(task);
+    }
+
+    fn peer_id(&self, validator: &AuthorityName) -> PeerId {
+        let node = self
+            .test_cluster
+            .swarm
+            .node(validator)
+            .expect("Unknown validator");
+        PeerId(node.config.network_key_pair().public().0.to_bytes())
+    }
+}
+
+impl Drop for FaultInjector {
+    fn drop(&mut self) {
+        for task in self.tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+        clear_fail_point(DROP_INBOUND_FAILPOINT);
+        clear_fail_point(CHECKPOINT_DELAY_FAILPOINT);
+    }
+}
+
+/// The round of the last leader committed by `validator`'s consensus, or `None` if it is not
+/// running.
+fn last_committed_round(test_cluster: &TestCluster, validator: &AuthorityName) -> Option<u64> {
+    let handle = test_cluster.swarm.node(validator)?.get_node_handle()?;
+    handle.with(|node| {
+        node.state()
+            .epoch_store_for_testing()
+            .get_last_consensus_stats()
+            .ok()
+            .map(|stats| stats.index.last_committed_round)
+    })
+}
@@ -57,6 +57,10 @@ use tokio::time::{timeout, Instant};
 use tokio::{task::JoinHandle, time::sleep};
 use tracing::{error, info};
 
+pub use crate::fault_injection::FaultInjector;
+
+pub mod fault_injection;
+
 const NUM_VALIDATOR: usize = 4;
 
 pub struct FullNodeHandle {
@@ -212,6 +216,10 @@ impl TestCluster {
         RandomNodeRestarter::new(self.clone())
     }
 
+    pub fn fault_injector(self: &Arc<Self>) -> FaultInjector {
+        FaultInjector::new(self.clone())
+    }
+
     pub async fn get_reference_gas_price(&self) -> u64 {
         self.sui_client()
             .governance_api()
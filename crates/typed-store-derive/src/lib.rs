@@ -489,6 +489,25 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 }
             }
 
+            /// Opens a set of tables as a secondary instance of the DB at `path`, which can only read,
+            /// and sees the primary's writes when it catches up with `try_catch_up_with_primary`
+            /// `secondary_path` is where the secondary instance keeps its own files
+            /// Any number of processes may do this, alongside the one that opened the DB in read-write mode
+            #[allow(unused_parens)]
+            pub fn open_tables_secondary(
+                path: std::path::PathBuf,
+                secondary_path: std::path::PathBuf,
+                metric_conf: typed_store::rocks::MetricConf,
+                global_db_options_override: Option<rocksdb::Options>,
+            ) -> Self {
+                let inner = #intermediate_db_map_struct_name::open_tables_impl(path, Some(secondary_path), false, metric_conf, global_db_options_override, None);
+                Self {
+                    #(
+                        #field_names: #post_process_fn(inner.#field_names),
+                    )*
+                }
+            }
+
             /// Opens a set of tables in transactional read-write mode
             /// Only one process is allowed to do this at a time
             /// `global_db_options_override` apply to the whole DB
@@ -1,7 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use anemo::rpc::Status;
+use anemo::types::response::{IntoResponse, StatusCode};
+use anemo::{PeerId, Request, Response};
+use anemo_tower::auth::AuthorizeRequest;
 use anemo_tower::callback::{MakeCallbackHandler, ResponseHandler};
-use sui_macros::fail_point;
+use bytes::Bytes;
+use sui_macros::{fail_point, fail_point_arg};
+
+/// Fail point passed the `(sender, receiver)` peer ids of every inbound request, which is dropped
+/// if the fail point returns `true`. Lets tests partition specific peers from each other.
+pub const DROP_INBOUND_FAILPOINT: &str = "narwhal-drop-inbound";
 
 #[derive(Clone, Default)]
 pub struct FailpointsMakeCallbackHandler {}
@@ -29,3 +38,32 @@ impl ResponseHandler for FailpointsResponseHandler {
 
     fn on_error<E>(self, _error: &E) {}
 }
+
+/// Rejects inbound requests that tests asked to drop, through the [`DROP_INBOUND_FAILPOINT`].
+#[derive(Clone, Debug)]
+pub struct FailpointsAuthorizeRequest {
+    own_peer_id: PeerId,
+}
+
+impl FailpointsAuthorizeRequest {
+    pub fn new(own_peer_id: PeerId) -> Self {
+        Self { own_peer_id }
+    }
+}
+
+impl AuthorizeRequest for FailpointsAuthorizeRequest {
+    fn authorize(&self, request: &mut Request<Bytes>) -> Result<(), Response<Bytes>> {
+        let Some(sender) = request.peer_id() else {
+            return Ok(());
+        };
+
+        if fail_point_arg!(DROP_INBOUND_FAILPOINT, &(*sender, self.own_peer_id)) == Some(true) {
+            Err(
+                Status::new_with_message(StatusCode::InternalServerError, "dropped by failpoint")
+                    .into_response(),
+            )
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -50,7 +50,10 @@ use network::{
     client::NetworkClient,
     epoch_filter::{AllowedEpoch, EPOCH_HEADER_KEY},
 };
-use network::{failpoints::FailpointsMakeCallbackHandler, metrics::MetricsMakeCallbackHandler};
+use network::{
+    failpoints::{FailpointsAuthorizeRequest, FailpointsMakeCallbackHandler},
+    metrics::MetricsMakeCallbackHandler,
+};
 use parking_lot::Mutex;
 use prometheus::Registry;
 use std::{
@@ -305,6 +308,9 @@ impl Primary {
             .route_layer(RequireAuthorizationLayer::new(AllowedEpoch::new(
                 epoch_string.clone(),
             )))
+            .route_layer(RequireAuthorizationLayer::new(
+                FailpointsAuthorizeRequest::new(own_peer_id),
+            ))
             .merge(worker_to_primary_router);
 
         let service = ServiceBuilder::new()
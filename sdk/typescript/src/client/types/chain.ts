@@ -77,6 +77,12 @@ export type NetworkMetrics = {
 	totalAddresses: string;
 	totalObjects: string;
 	totalPackages: string;
+	rollingTps: number;
+	checkpointsPerSecond: number;
+	averageCheckpointSize: number;
+	epochStartTimestampMs: string;
+	epochDurationMs: string;
+	epochProgress: number;
 };
 
 export type AddressMetrics = {
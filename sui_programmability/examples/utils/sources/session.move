@@ -0,0 +1,121 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Sessions let an account hand an app, such as a game, an ephemeral key that can spend a capped
+/// amount of a coin on the account's behalf, without the key ever holding the account's funds.
+/// The account funds a shared `Session` and allowlists ephemeral keys on it, each with a spending
+/// limit and an epoch after which it expires. Keys spend from the session's balance, and the
+/// owner can revoke keys or withdraw the balance at any time.
+module utils::session {
+    use sui::balance::{Self, Balance};
+    use sui::coin::{Self, Coin};
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+    use sui::vec_map::{Self, VecMap};
+
+    // Errors
+    const ENotOwner: u64 = 0;
+    const ENotAuthorized: u64 = 1;
+    const EExpired: u64 = 2;
+    const EOverLimit: u64 = 3;
+
+    ///
+    /// A balance that the keys on its allowlist can spend from, up to their limits.
+    ///
+    /// @ownership: Shared
+    ///
+    struct Session<phantom T> has key {
+        id: UID,
+        owner: address,
+        balance: Balance<T>,
+        /// The keys allowed to spend from the session, by address.
+        keys: VecMap<address, Allowance>,
+    }
+
+    struct Allowance has store, copy, drop {
+        /// How much more the key can spend.
+        remaining: u64,
+        /// The last epoch the key can spend in.
+        expires_at_epoch: u64,
+    }
+
+    /// A session owned by the sender, funded with `coin`. Keys can be authorized on it before it
+    /// is shared with `share`, so that a session can be opened in a single transaction.
+    public fun new<T>(coin: Coin<T>, ctx: &mut TxContext): Session<T> {
+        Session {
+            id: object::new(ctx),
+            owner: tx_context::sender(ctx),
+            balance: coin::into_balance(coin),
+            keys: vec_map::empty(),
+        }
+    }
+
+    public fun share<T>(session: Session<T>) {
+        transfer::share_object(session)
+    }
+
+    public entry fun create<T>(coin: Coin<T>, ctx: &mut TxContext) {
+        share(new(coin, ctx))
+    }
+
+    public fun owner<T>(session: &Session<T>): address {
+        session.owner
+    }
+
+    public fun value<T>(session: &Session<T>): u64 {
+        balance::value(&session.balance)
+    }
+
+    /// How much more `key` can spend, zero if it isn't authorized.
+    public fun remaining<T>(session: &Session<T>, key: address): u64 {
+        if (vec_map::contains(&session.keys, &key)) {
+            vec_map::get(&session.keys, &key).remaining
+        } else {
+            0
+        }
+    }
+
+    public entry fun deposit<T>(session: &mut Session<T>, coin: Coin<T>) {
+        coin::put(&mut session.balance, coin)
+    }
+
+    /// Allow `key` to spend up to `spend_limit` until the end of `expires_at_epoch`, replacing
+    /// any allowance it already has.
+    public entry fun authorize<T>(
+        session: &mut Session<T>,
+        key: address,
+        spend_limit: u64,
+        expires_at_epoch: u64,
+        ctx: &mut TxContext,
+    ) {
+        assert!(tx_context::sender(ctx) == session.owner, ENotOwner);
+        if (vec_map::contains(&session.keys, &key)) {
+            vec_map::remove(&mut session.keys, &key);
+        };
+        vec_map::insert(&mut session.keys, key, Allowance { remaining: spend_limit, expires_at_epoch });
+    }
+
+    public entry fun revoke<T>(session: &mut Session<T>, key: address, ctx: &mut TxContext) {
+        assert!(tx_context::sender(ctx) == session.owner, ENotOwner);
+        assert!(vec_map::contains(&session.keys, &key), ENotAuthorized);
+        vec_map::remove(&mut session.keys, &key);
+    }
+
+    /// Spend `amount` from the session as one of its keys.
+    public fun spend<T>(session: &mut Session<T>, amount: u64, ctx: &mut TxContext): Coin<T> {
+        let key = tx_context::sender(ctx);
+        assert!(vec_map::contains(&session.keys, &key), ENotAuthorized);
+        let allowance = vec_map::get_mut(&mut session.keys, &key);
+        assert!(tx_context::epoch(ctx) <= allowance.expires_at_epoch, EExpired);
+        assert!(amount <= allowance.remaining, EOverLimit);
+        allowance.remaining = allowance.remaining - amount;
+        coin::take(&mut session.balance, amount, ctx)
+    }
+
+    /// Withdraw `amount` from the session as its owner.
+    public fun withdraw<T>(session: &mut Session<T>, amount: u64, ctx: &mut TxContext): Coin<T> {
+        assert!(tx_context::sender(ctx) == session.owner, ENotOwner);
+        coin::take(&mut session.balance, amount, ctx)
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test_only]
+module utils::session_tests {
+    use utils::session::{Self, Session};
+    use sui::test_scenario::{Self as ts, Scenario, ctx};
+    use sui::coin;
+    use sui::sui::SUI;
+    use sui::test_utils;
+
+    const OWNER: address = @0x1337;
+    const KEY: address = @0x1ce1ce1ce;
+
+    fun open_session(scenario: &mut Scenario, funds: u64, spend_limit: u64, expires_at_epoch: u64) {
+        ts::next_tx(scenario, OWNER);
+        let coin = coin::mint_for_testing<SUI>(funds, ctx(scenario));
+        let session = session::new(coin, ctx(scenario));
+        session::authorize(&mut session, KEY, spend_limit, expires_at_epoch, ctx(scenario));
+        session::share(session);
+    }
+
+    fun spend(scenario: &mut Scenario, sender: address, amount: u64) {
+        ts::next_tx(scenario, sender);
+        let session = ts::take_shared<Session<SUI>>(scenario);
+        let coin = session::spend(&mut session, amount, ctx(scenario));
+        assert!(coin::value(&coin) == amount, 0);
+        test_utils::destroy(coin);
+        ts::return_shared(session);
+    }
+
+    #[test]
+    fun test_spend_within_limit() {
+        let scenario_val = ts::begin(OWNER);
+        let scenario = &mut scenario_val;
+        open_session(scenario, 1000, 300, 0);
+
+        spend(scenario, KEY, 100);
+        spend(scenario, KEY, 200);
+
+        ts::next_tx(scenario, OWNER);
+        let session = ts::take_shared<Session<SUI>>(scenario);
+        assert!(session::remaining(&session, KEY) == 0, 0);
+        assert!(session::value(&session) == 700, 0);
+        let coin = session::withdraw(&mut session, 700, ctx(scenario));
+        test_utils::destroy(coin);
+        ts::return_shared(session);
+        ts::end(scenario_val);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = session::EOverLimit)]
+    fun test_spend_over_limit() {
+        let scenario_val = ts::begin(OWNER);
+        let scenario = &mut scenario_val;
+        open_session(scenario, 1000, 300, 0);
+
+        spend(scenario, KEY, 200);
+        spend(scenario, KEY, 200);
+        ts::end(scenario_val);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = session::EExpired)]
+    fun test_spend_after_expiry() {
+        let scenario_val = ts::begin(OWNER);
+        let scenario = &mut scenario_val;
+        open_session(scenario, 1000, 300, 0);
+
+        ts::next_epoch(scenario, OWNER);
+        spend(scenario, KEY, 100);
+        ts::end(scenario_val);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = session::ENotAuthorized)]
+    fun test_spend_after_revoke() {
+        let scenario_val = ts::begin(OWNER);
+        let scenario = &mut scenario_val;
+        open_session(scenario, 1000, 300, 0);
+
+        ts::next_tx(scenario, OWNER);
+        let session = ts::take_shared<Session<SUI>>(scenario);
+        session::revoke(&mut session, KEY, ctx(scenario));
+        ts::return_shared(session);
+
+        spend(scenario, KEY, 100);
+        ts::end(scenario_val);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = session::ENotOwner)]
+    fun test_authorize_as_key() {
+        let scenario_val = ts::begin(OWNER);
+        let scenario = &mut scenario_val;
+        open_session(scenario, 1000, 300, 0);
+
+        ts::next_tx(scenario, KEY);
+        let session = ts::take_shared<Session<SUI>>(scenario);
+        session::authorize(&mut session, KEY, 1000, 10, ctx(scenario));
+        ts::return_shared(session);
+        ts::end(scenario_val);
+    }
+}